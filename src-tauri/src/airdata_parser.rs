@@ -0,0 +1,260 @@
+//! Parser module for AirData UAV per-flight CSV exports.
+//!
+//! AirData exports use unit-suffixed headers (e.g. `height_above_takeoff(feet)`,
+//! `speed(mph)`), so the same `ColumnMap` used for Litchi handles conversion.
+//!
+//! Column mapping (AirData → `TelemetryPoint`):
+//! - `time(millisecond)` → `timestamp_ms`
+//! - `latitude` / `longitude` → `latitude` / `longitude`
+//! - `height_above_takeoff` → `height`
+//! - `altitude_above_seaLevel` → `altitude_abs`
+//! - `height_sonar` → `vps_height`
+//! - `speed` → `speed`
+//! - `xSpeed` / `ySpeed` / `zSpeed` → `velocity_x` / `velocity_y` / `velocity_z`
+//! - `pitch` / `roll` / `compass_heading` → `pitch` / `roll` / `yaw`
+//! - `gimbal_pitch` / `gimbal_roll` / `gimbal_heading` → `gimbal_*`
+//! - `battery_percent` / `voltage` / `current` / `battery_temperature` → `battery_*`
+//! - `voltageCell1` … `voltageCell6` → `cell_voltages`
+//! - `satellites` / `gpslevel` → `satellites` / `gps_signal`
+//! - `flycState` → `flight_mode`
+//! - `rc_aileron` / `rc_elevator` / `rc_throttle` / `rc_rudder` → `rc_*`
+//! - `isPhoto` / `isVideo` → `is_photo` / `is_video`
+
+use std::io::{BufRead, BufReader};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::database::Database;
+use crate::litchi_parser::ColumnMap;
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, LogSource, ParseResult, ParserError};
+
+/// AirData exports up to six cell voltage columns
+const AIRDATA_MAX_CELLS: usize = 6;
+
+/// AirData CSV Parser
+pub struct AirDataParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> AirDataParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Check if a file is an AirData CSV export
+    pub fn is_airdata_csv(source: &LogSource) -> bool {
+        let path = source.path();
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !ext.eq_ignore_ascii_case("csv") {
+            return false;
+        }
+
+        if let Ok(file) = source.open() {
+            let reader = BufReader::new(file);
+            if let Some(Ok(first_line)) = reader.lines().next() {
+                let lower = first_line.trim_start_matches('\u{feff}').to_lowercase();
+                // AirData has these characteristic columns
+                return lower.contains("time(millisecond)")
+                    && lower.contains("latitude")
+                    && lower.contains("height_above_takeoff")
+                    && (lower.contains("compass_heading") || lower.contains("flycstate"));
+            }
+        }
+        false
+    }
+
+    /// Parse an AirData CSV file
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing AirData CSV file: {:?}", file_path);
+
+        let file = source.open()?;
+        let reader = BufReader::new(file);
+        let mut lines = reader.lines();
+
+        let header_line = lines
+            .next()
+            .ok_or_else(|| ParserError::Parse("Empty CSV file".to_string()))?
+            .map_err(|e| ParserError::Parse(format!("Failed to read header: {}", e)))?;
+
+        let headers: Vec<String> = header_line
+            .trim_start_matches('\u{feff}')
+            .split(',')
+            .map(|s| s.trim().trim_matches('"').to_string())
+            .collect();
+        let col_map = ColumnMap::new(&headers);
+
+        let mut points = Vec::new();
+        let mut start_time: Option<DateTime<Utc>> = None;
+
+        for line_result in lines {
+            let line = match line_result {
+                Ok(l) => l,
+                Err(e) => {
+                    log::warn!("Skipping malformed line: {}", e);
+                    continue;
+                }
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(|s| s.trim().trim_matches('"')).collect();
+            if fields.len() < headers.len() / 2 {
+                continue;
+            }
+
+            if start_time.is_none() {
+                start_time = col_map.get_str(&fields, "datetime").and_then(|dt| {
+                    NaiveDateTime::parse_from_str(&dt, "%Y-%m-%d %H:%M:%S%.f")
+                        .or_else(|_| NaiveDateTime::parse_from_str(&dt, "%Y-%m-%d %H:%M:%S"))
+                        .ok()
+                        .map(|ndt| DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc))
+                });
+            }
+
+            points.push(Self::parse_row(&col_map, &fields));
+        }
+
+        // Rows without a GPS fix are kept so the timeline stays intact
+        if !points.iter().any(|p| p.latitude.is_some() && p.longitude.is_some()) {
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: None,
+            drone_serial: None,
+            aircraft_name: None,
+            battery_serial: None,
+            start_time,
+            end_time: start_time
+                .map(|s| s + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64)),
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(&points, stats.home_location),
+        };
+
+        log::info!(
+            "AirData parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            points.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "AirData".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors: Vec::new(), events: Vec::new() })
+    }
+
+    /// Parse a single CSV row into a TelemetryPoint
+    fn parse_row(col_map: &ColumnMap, row: &[&str]) -> TelemetryPoint {
+        let latitude = col_map.get_f64(row, "latitude");
+        let longitude = col_map.get_f64(row, "longitude");
+        let has_gps = matches!((latitude, longitude), (Some(lat), Some(lon)) if lat.abs() > 1e-6 || lon.abs() > 1e-6);
+
+        TelemetryPoint {
+            timestamp_ms: col_map
+                .get_f64(row, "time")
+                .map(|t| t as i64)
+                .unwrap_or(0),
+
+            // Position
+            latitude: latitude.filter(|_| has_gps),
+            longitude: longitude.filter(|_| has_gps),
+            altitude: col_map.get_f64(row, "altitude").or(col_map.get_f64(row, "height_above_takeoff")),
+            height: col_map.get_f64(row, "height_above_takeoff"),
+            vps_height: col_map.get_f64(row, "height_sonar"),
+            altitude_abs: col_map.get_f64(row, "altitude_above_seaLevel"),
+
+            // Velocity
+            speed: col_map.get_f64(row, "speed"),
+            velocity_x: col_map.get_f64(row, "xSpeed"),
+            velocity_y: col_map.get_f64(row, "ySpeed"),
+            velocity_z: col_map.get_f64(row, "zSpeed"),
+
+            // Orientation
+            pitch: col_map.get_f64(row, "pitch"),
+            roll: col_map.get_f64(row, "roll"),
+            yaw: col_map.get_f64(row, "compass_heading"),
+
+            // Gimbal
+            gimbal_pitch: col_map.get_f64(row, "gimbal_pitch"),
+            gimbal_roll: col_map.get_f64(row, "gimbal_roll"),
+            gimbal_yaw: col_map.get_f64(row, "gimbal_heading"),
+
+            // Power
+            battery_percent: col_map.get_i32(row, "battery_percent"),
+            battery_voltage: col_map.get_f64(row, "voltage"),
+            battery_current: col_map.get_f64(row, "current"),
+            battery_temp: col_map.get_f64(row, "battery_temperature"),
+
+            // Status
+            flight_mode: col_map.get_str(row, "flycState").filter(|s| !s.is_empty()),
+            gps_signal: col_map.get_i32(row, "gpslevel"),
+            satellites: col_map.get_i32(row, "satellites"),
+            rc_signal: None,
+            rc_uplink: None,
+            rc_downlink: None,
+
+            // RC stick inputs
+            rc_aileron: col_map.get_f64(row, "rc_aileron"),
+            rc_elevator: col_map.get_f64(row, "rc_elevator"),
+            rc_throttle: col_map.get_f64(row, "rc_throttle"),
+            rc_rudder: col_map.get_f64(row, "rc_rudder"),
+
+            // Camera state
+            is_photo: col_map.get_bool(row, "isPhoto"),
+            is_video: col_map.get_bool(row, "isVideo"),
+
+            cell_voltages: Self::parse_cell_voltages(col_map, row),
+            compass_error: None,
+            compass_interference: None,
+            imu_status: None,
+            wind_speed: None,
+            wind_direction: None,
+        }
+    }
+
+    /// Collect `voltageCell1..6`, stopping at the first cell the pack doesn't have.
+    /// AirData writes 0 for unused cells.
+    fn parse_cell_voltages(col_map: &ColumnMap, row: &[&str]) -> Option<Vec<f64>> {
+        let cells: Vec<f64> = (1..=AIRDATA_MAX_CELLS)
+            .map_while(|i| col_map.get_f64(row, &format!("voltageCell{}", i)).filter(|v| *v > 0.0))
+            .collect();
+        (!cells.is_empty()).then_some(cells)
+    }
+}
@@ -32,6 +32,9 @@ pub enum ApiError {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Invalid keychain bundle: {0}")]
+    InvalidKeychainBundle(String),
 }
 
 /// Configuration file structure
@@ -41,6 +44,73 @@ pub struct AppConfig {
     pub dji_api_key: Option<String>,
 }
 
+/// Format marker written into keychain bundle files
+const KEYCHAIN_BUNDLE_FORMAT: &str = "drone-logbook-keychains";
+
+/// Portable set of cached V13+ decryption keychains.
+///
+/// Exported on a machine with internet access and imported on an air-gapped
+/// one, so encrypted logs can be decrypted there without calling the DJI API.
+/// Entries are keyed by the SHA256 of the log file they belong to.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub entries: Vec<KeychainBundleEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainBundleEntry {
+    pub file_hash: String,
+    pub keychains: serde_json::Value,
+}
+
+impl KeychainBundle {
+    /// Build a bundle from `(file_hash, keychains JSON)` pairs
+    pub fn new(cached: Vec<(String, String)>) -> Self {
+        let entries = cached
+            .into_iter()
+            .filter_map(|(file_hash, json)| {
+                serde_json::from_str(&json)
+                    .ok()
+                    .map(|keychains| KeychainBundleEntry { file_hash, keychains })
+            })
+            .collect();
+        Self {
+            format: KEYCHAIN_BUNDLE_FORMAT.to_string(),
+            version: 1,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            entries,
+        }
+    }
+
+    /// Serialize the bundle for writing to disk
+    pub fn to_json(&self) -> Result<String, ApiError> {
+        serde_json::to_string_pretty(self).map_err(|e| ApiError::ApiResponse(e.to_string()))
+    }
+
+    /// Parse a bundle file, rejecting anything that is not a keychain bundle
+    pub fn from_json(content: &str) -> Result<Self, ApiError> {
+        let bundle: Self = serde_json::from_str(content)
+            .map_err(|e| ApiError::InvalidKeychainBundle(e.to_string()))?;
+        if bundle.format != KEYCHAIN_BUNDLE_FORMAT {
+            return Err(ApiError::InvalidKeychainBundle(format!("unexpected format '{}'", bundle.format)));
+        }
+        Ok(bundle)
+    }
+
+    /// `(file_hash, keychains JSON)` pairs ready to store in the cache
+    pub fn into_cache_entries(self) -> Vec<(String, String)> {
+        self.entries
+            .into_iter()
+            .map(|e| (e.file_hash, e.keychains.to_string()))
+            .collect()
+    }
+}
+
 /// DJI API client for key fetching
 pub struct DjiApi {
     app_data_dir: Option<PathBuf>,
@@ -297,4 +367,20 @@ mod tests {
         let parsed: AppConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.dji_api_key, Some("test_key".to_string()));
     }
+
+    #[test]
+    fn test_keychain_bundle_roundtrip() {
+        let bundle = KeychainBundle::new(vec![
+            ("abc123".to_string(), r#"[[{"featurePoint":1,"aesKey":"k","aesIv":"i"}]]"#.to_string()),
+            ("broken".to_string(), "not json".to_string()),
+        ]);
+        assert_eq!(bundle.entries.len(), 1);
+
+        let parsed = KeychainBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        let entries = parsed.into_cache_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "abc123");
+
+        assert!(KeychainBundle::from_json(r#"{"format":"other","version":1,"exportedAt":"","entries":[]}"#).is_err());
+    }
 }
@@ -0,0 +1,356 @@
+//! Parser module for Autel EVO flight log CSV exports (Autel Explorer / Autel Sky).
+//!
+//! Autel exports are not consistent between app versions: column names vary in
+//! case, spacing, and unit suffix (e.g. `Altitude(m)`, `altitude (ft)`,
+//! `Gimbal Pitch`). Headers are normalized to lowercase alphanumerics (keeping
+//! the unit suffix) and each field is looked up through a list of aliases.
+//!
+//! Only CSV exports are read. The app's own `.acl` flight records use an
+//! undocumented binary layout, encrypted in recent app versions, with no
+//! public decoder; importing one fails with a hint to export CSV instead.
+
+use std::io::{BufRead, BufReader};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::database::Database;
+use crate::litchi_parser::ColumnMap;
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, LogSource, ParseResult, ParserError};
+
+/// Normalized header aliases per field
+const TIME: &[&str] = &["time", "flytime", "flighttime", "timems", "times"];
+const DATETIME: &[&str] = &["datetime", "datetimeutc", "gpstime", "date"];
+const LATITUDE: &[&str] = &["latitude", "lat", "dronelatitude"];
+const LONGITUDE: &[&str] = &["longitude", "lon", "lng", "dronelongitude"];
+const ALTITUDE: &[&str] = &["altitude", "relativealtitude", "height"];
+const ALTITUDE_ABS: &[&str] = &["absolutealtitude", "altitudemsl", "gpsaltitude"];
+const SPEED: &[&str] = &["speed", "horizontalspeed", "groundspeed"];
+const VSPEED: &[&str] = &["verticalspeed", "vspeed", "velocityz"];
+const PITCH: &[&str] = &["pitch", "dronepitch"];
+const ROLL: &[&str] = &["roll", "droneroll"];
+const YAW: &[&str] = &["yaw", "heading", "droneyaw"];
+const GIMBAL_PITCH: &[&str] = &["gimbalpitch"];
+const GIMBAL_ROLL: &[&str] = &["gimbalroll"];
+const GIMBAL_YAW: &[&str] = &["gimbalyaw"];
+const BATTERY_PERCENT: &[&str] = &["battery", "batterypercent", "batterylevel", "remainingbattery"];
+const BATTERY_VOLTAGE: &[&str] = &["voltage", "batteryvoltage"];
+const BATTERY_CURRENT: &[&str] = &["current", "batterycurrent"];
+const BATTERY_TEMP: &[&str] = &["batterytemperature", "batterytemp", "temperature"];
+const SATELLITES: &[&str] = &["satellites", "gpscount", "satellitecount", "gpsnum"];
+const FLIGHT_MODE: &[&str] = &["flightmode", "mode"];
+const RC_UPLINK: &[&str] = &["uplinksignal", "rcsignal", "rcsignalstrength", "remotecontrolsignal"];
+const RC_DOWNLINK: &[&str] = &["downlinksignal", "imagetransmissionsignal", "videosignal", "videolinksignal"];
+const MODEL: &[&str] = &["dronemodel", "aircraftmodel", "model", "aircraft"];
+const SERIAL: &[&str] = &["serialnumber", "droneserial", "aircraftsn", "sn"];
+const BATTERY_SERIAL: &[&str] = &["batteryserial", "batterysn"];
+
+/// Autel EVO CSV Parser
+pub struct AutelParser<'a> {
+    db: &'a Database,
+}
+
+/// Column map plus a resolver for normalized alias names
+struct AutelColumns {
+    map: ColumnMap,
+    names: Vec<String>,
+    units: Vec<String>,
+}
+
+impl AutelColumns {
+    fn new(headers: &[String]) -> Self {
+        let normalized: Vec<String> = headers.iter().map(|h| normalize_header(h)).collect();
+        let names = normalized
+            .iter()
+            .map(|h| h.split('(').next().unwrap_or("").to_string())
+            .collect();
+        let units = normalized
+            .iter()
+            .map(|h| h.find('(').map(|p| h[p..].to_string()).unwrap_or_default())
+            .collect();
+        Self {
+            map: ColumnMap::new(&normalized),
+            names,
+            units,
+        }
+    }
+
+    /// Unit suffix (e.g. `(ms)`) of the column an alias resolves to
+    fn unit(&self, aliases: &[&str]) -> Option<&str> {
+        let field = self.resolve(aliases)?;
+        let idx = self.names.iter().position(|n| n == field)?;
+        Some(self.units[idx].as_str())
+    }
+
+    /// First alias that exists in the header row
+    fn resolve(&self, aliases: &[&str]) -> Option<&str> {
+        aliases
+            .iter()
+            .find(|a| self.names.iter().any(|n| n == *a))
+            .copied()
+    }
+
+    fn f64(&self, row: &[&str], aliases: &[&str]) -> Option<f64> {
+        self.map.get_f64(row, self.resolve(aliases)?)
+    }
+
+    fn i32(&self, row: &[&str], aliases: &[&str]) -> Option<i32> {
+        let field = self.resolve(aliases)?;
+        self.map
+            .get_i32(row, field)
+            .or_else(|| self.map.get_f64(row, field).map(|v| v.round() as i32))
+    }
+
+    fn str(&self, row: &[&str], aliases: &[&str]) -> Option<String> {
+        self.map
+            .get_str(row, self.resolve(aliases)?)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+/// Lowercase a header and strip everything but alphanumerics from the base
+/// name, keeping the `(unit)` suffix so `ColumnMap` can still convert units.
+fn normalize_header(header: &str) -> String {
+    let header = header.trim().trim_start_matches('\u{feff}').trim_matches('"');
+    let (base, unit) = match header.find('(') {
+        Some(pos) => (&header[..pos], &header[pos..]),
+        None => (header, ""),
+    };
+    let base: String = base
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    format!("{}{}", base, unit.replace(' ', "").to_lowercase())
+}
+
+impl<'a> AutelParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Check if a file is an Autel flight log CSV export.
+    /// Autel exports don't share a fixed header, so we look for position
+    /// columns plus an Autel model string in the header or first data row.
+    pub fn is_autel_csv(source: &LogSource) -> bool {
+        let path = source.path();
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !ext.eq_ignore_ascii_case("csv") {
+            return false;
+        }
+
+        if let Ok(file) = source.open() {
+            let mut lines = BufReader::new(file).lines();
+            let header = match lines.next() {
+                Some(Ok(h)) => h.to_lowercase(),
+                _ => return false,
+            };
+            if !header.contains("latitude") || !header.contains("longitude") {
+                return false;
+            }
+            let first_row = lines
+                .next()
+                .and_then(|l| l.ok())
+                .unwrap_or_default()
+                .to_lowercase();
+            return header.contains("autel") || first_row.contains("autel") || first_row.contains("evo ");
+        }
+        false
+    }
+
+    /// Parse an Autel CSV file
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing Autel CSV file: {:?}", file_path);
+
+        let file = source.open()?;
+        let mut lines = BufReader::new(file).lines();
+
+        let header_line = lines
+            .next()
+            .ok_or_else(|| ParserError::Parse("Empty CSV file".to_string()))?
+            .map_err(|e| ParserError::Parse(format!("Failed to read header: {}", e)))?;
+        let headers: Vec<String> = header_line.split(',').map(|s| s.to_string()).collect();
+        let cols = AutelColumns::new(&headers);
+
+        let mut points = Vec::new();
+        let mut start_time: Option<DateTime<Utc>> = None;
+        let mut drone_model: Option<String> = None;
+        let mut drone_serial: Option<String> = None;
+        let mut battery_serial: Option<String> = None;
+
+        for line_result in lines {
+            let line = match line_result {
+                Ok(l) => l,
+                Err(e) => {
+                    log::warn!("Skipping malformed line: {}", e);
+                    continue;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(|s| s.trim().trim_matches('"')).collect();
+            if fields.len() < headers.len() / 2 {
+                continue;
+            }
+
+            if start_time.is_none() {
+                start_time = cols.str(&fields, DATETIME).and_then(|dt| parse_autel_datetime(&dt));
+            }
+            if drone_model.is_none() {
+                drone_model = cols.str(&fields, MODEL);
+            }
+            if drone_serial.is_none() {
+                drone_serial = cols.str(&fields, SERIAL).map(|s| s.to_uppercase());
+            }
+            if battery_serial.is_none() {
+                battery_serial = cols.str(&fields, BATTERY_SERIAL).map(|s| s.to_uppercase());
+            }
+
+            points.push(self.parse_row(&cols, &fields, points.len()));
+        }
+
+        if !points.iter().any(|p| p.latitude.is_some() && p.longitude.is_some()) {
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        // Normalize to a timeline starting at 0
+        if let Some(first_ts) = points.first().map(|p| p.timestamp_ms) {
+            for p in points.iter_mut() {
+                p.timestamp_ms -= first_ts;
+            }
+        }
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        // Always record the manufacturer so mixed fleets group correctly
+        let drone_model = Some(match drone_model {
+            Some(m) if m.to_lowercase().contains("autel") => m,
+            Some(m) => format!("Autel {}", m),
+            None => "Autel EVO".to_string(),
+        });
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model,
+            drone_serial,
+            aircraft_name: None,
+            battery_serial,
+            start_time,
+            end_time: start_time
+                .map(|s| s + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64)),
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(&points, stats.home_location),
+        };
+
+        log::info!(
+            "Autel parse complete in {:.1}s: model={:?}, duration={:.1}s, distance={:.0}m, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            metadata.drone_model,
+            stats.duration_secs,
+            stats.total_distance_m,
+            points.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "Autel".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors: Vec::new(), events: Vec::new() })
+    }
+
+    /// Parse a single CSV row into a TelemetryPoint
+    fn parse_row(&self, cols: &AutelColumns, row: &[&str], index: usize) -> TelemetryPoint {
+        // Prefer the elapsed time column (seconds or milliseconds depending on
+        // its suffix), then the row's wall clock, and finally assume the 10 Hz
+        // rate used by Autel Explorer exports
+        let time_scale = match cols.unit(TIME) {
+            Some("(s)") | Some("(sec)") | Some("(seconds)") => 1000.0,
+            _ => 1.0,
+        };
+        let timestamp_ms = cols
+            .f64(row, TIME)
+            .map(|t| (t * time_scale) as i64)
+            .or_else(|| {
+                cols.str(row, DATETIME)
+                    .and_then(|dt| parse_autel_datetime(&dt))
+                    .map(|dt| dt.timestamp_millis())
+            })
+            .unwrap_or(index as i64 * 100);
+
+        let latitude = cols.f64(row, LATITUDE);
+        let longitude = cols.f64(row, LONGITUDE);
+        let has_gps = matches!((latitude, longitude), (Some(lat), Some(lon)) if lat.abs() > 1e-6 || lon.abs() > 1e-6);
+
+        let altitude = cols.f64(row, ALTITUDE);
+        let rc_uplink = cols.i32(row, RC_UPLINK);
+        let rc_downlink = cols.i32(row, RC_DOWNLINK);
+
+        TelemetryPoint {
+            timestamp_ms,
+            latitude: latitude.filter(|_| has_gps),
+            longitude: longitude.filter(|_| has_gps),
+            altitude,
+            height: altitude,
+            altitude_abs: cols.f64(row, ALTITUDE_ABS),
+            speed: cols.f64(row, SPEED),
+            velocity_z: cols.f64(row, VSPEED),
+            pitch: cols.f64(row, PITCH),
+            roll: cols.f64(row, ROLL),
+            yaw: cols.f64(row, YAW),
+            gimbal_pitch: cols.f64(row, GIMBAL_PITCH),
+            gimbal_roll: cols.f64(row, GIMBAL_ROLL),
+            gimbal_yaw: cols.f64(row, GIMBAL_YAW),
+            battery_percent: cols.i32(row, BATTERY_PERCENT),
+            battery_voltage: cols.f64(row, BATTERY_VOLTAGE),
+            battery_current: cols.f64(row, BATTERY_CURRENT),
+            battery_temp: cols.f64(row, BATTERY_TEMP),
+            flight_mode: cols.str(row, FLIGHT_MODE),
+            satellites: cols.i32(row, SATELLITES),
+            rc_uplink,
+            rc_downlink,
+            rc_signal: rc_downlink.or(rc_uplink),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parse the datetime formats seen in Autel exports
+fn parse_autel_datetime(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y/%m/%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .map(|ndt| DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc))
+}
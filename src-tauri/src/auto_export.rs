@@ -0,0 +1,159 @@
+//! Writing a copy of every newly imported flight to a folder.
+//!
+//! Configured with `auto_export` in config.json: whether it is `enabled`, the
+//! target `directory`, the `formats` to write and `after_ms`, the import time
+//! of the last flight handled. Only flights imported later are picked up, so
+//! turning the feature on doesn't copy the whole library. Files are named
+//! like batch exports plus the flight ID, and existing files are left alone.
+//! Telemetry is written in SI units.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::library::read_config;
+use crate::export::{ExportError, ExportFormat, ExportUnits};
+use crate::models::AutoExportSettings;
+
+/// Written unless configured otherwise
+const DEFAULT_FORMATS: [ExportFormat; 2] = [ExportFormat::Gpx, ExportFormat::Csv];
+
+/// Set while an export runs so imports finishing meanwhile don't start a
+/// second one; the running export picks their flights up
+static EXPORT_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Auto-export settings saved in config.json
+pub fn settings(data_dir: &Path) -> AutoExportSettings {
+    let config = read_config(data_dir);
+    let section = config.get("auto_export");
+    let formats = section
+        .and_then(|s| s.get("formats"))
+        .and_then(|v| serde_json::from_value::<Vec<ExportFormat>>(v.clone()).ok())
+        .unwrap_or_else(|| DEFAULT_FORMATS.to_vec());
+    AutoExportSettings {
+        enabled: section.and_then(|s| s.get("enabled")).and_then(|v| v.as_bool()).unwrap_or(false),
+        directory: section
+            .and_then(|s| s.get("directory"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(str::to_string),
+        formats,
+    }
+}
+
+/// Save the auto-export settings to config.json. Turning the export on or
+/// pointing it at another folder starts from the flights imported from now on.
+pub fn save_settings(data_dir: &Path, settings: &AutoExportSettings) -> std::io::Result<()> {
+    let directory = settings.directory.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    if settings.enabled && directory.is_none() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Choose a folder to export to"));
+    }
+    if settings.enabled && settings.formats.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Choose at least one format"));
+    }
+
+    let current = self::settings(data_dir);
+    let mut config = read_config(data_dir);
+    let after_ms = config["auto_export"].get("after_ms").and_then(|v| v.as_i64());
+    let restart = settings.enabled && (!current.enabled || current.directory.as_deref() != directory);
+    let after_ms = if restart || after_ms.is_none() {
+        chrono::Utc::now().timestamp_millis()
+    } else {
+        after_ms.unwrap_or_default()
+    };
+
+    let mut formats: Vec<ExportFormat> = Vec::new();
+    for format in &settings.formats {
+        if !formats.contains(format) {
+            formats.push(*format);
+        }
+    }
+    config["auto_export"] = serde_json::json!({
+        "enabled": settings.enabled,
+        "directory": directory,
+        "formats": formats,
+        "after_ms": after_ms,
+    });
+    std::fs::write(data_dir.join("config.json"), serde_json::to_string_pretty(&config).unwrap())
+}
+
+fn save_cursor(data_dir: &Path, after_ms: i64) -> std::io::Result<()> {
+    let mut config = read_config(data_dir);
+    if !config["auto_export"].is_object() {
+        return Ok(());
+    }
+    config["auto_export"]["after_ms"] = serde_json::json!(after_ms);
+    std::fs::write(data_dir.join("config.json"), serde_json::to_string_pretty(&config).unwrap())
+}
+
+/// Write the configured formats of every flight imported since the last run.
+/// A format a flight can't be written in (GPX without GPS, say) is logged
+/// and skipped. Returns the number of flights handled.
+pub fn export_new_flights(db: &Database) -> Result<usize, ExportError> {
+    let settings = settings(&db.data_dir);
+    let Some(directory) = settings.directory.filter(|_| settings.enabled) else {
+        return Ok(0);
+    };
+    let directory = Path::new(&directory);
+    let after_ms = read_config(&db.data_dir)["auto_export"]
+        .get("after_ms")
+        .and_then(|v| v.as_i64())
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+
+    let pending = db.get_flights_imported_after(after_ms)?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    std::fs::create_dir_all(directory)?;
+    log::info!("Auto-exporting {} new flights to {}", pending.len(), directory.display());
+
+    let flight_count = pending.len();
+    let mut written = 0usize;
+    let mut cursor = after_ms;
+    for (flight_id, imported_at_ms) in pending {
+        let flight = db.get_flight_by_id(flight_id)?;
+        for format in &settings.formats {
+            let extension = format.extension();
+            let name = crate::export::file_name(&flight, extension)
+                .replacen(&format!(".{}", extension), &format!("_{}.{}", flight_id, extension), 1);
+            let path = directory.join(name);
+            if path.exists() {
+                continue;
+            }
+            match crate::export::flight_export(db, flight_id, *format, &[], &ExportUnits::default()) {
+                Ok(content) => {
+                    std::fs::write(&path, content)?;
+                    written += 1;
+                }
+                Err(e) => log::warn!("Auto-export of flight {} as {} failed: {}", flight_id, extension, e),
+            }
+        }
+        cursor = cursor.max(imported_at_ms);
+    }
+
+    save_cursor(&db.data_dir, cursor)?;
+    log::info!("Auto-export wrote {} files", written);
+    Ok(flight_count)
+}
+
+/// Export newly imported flights without holding up the import
+pub fn export_in_background(db: Arc<Database>) {
+    if !settings(&db.data_dir).enabled || EXPORT_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::task::spawn_blocking(move || {
+        // Flights imported during a pass are picked up by the next one
+        loop {
+            match export_new_flights(&db) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("Auto-export failed: {}", e);
+                    break;
+                }
+            }
+        }
+        EXPORT_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
@@ -0,0 +1,311 @@
+//! Parallel batch import pipeline.
+//!
+//! Files are parsed concurrently (bounded by the number of CPU cores) and the
+//! parsed flights are written to DuckDB in transactions of up to
+//! `WRITE_BATCH_SIZE` flights. The async tasks only hand out the permits:
+//! each file is hashed and parsed on tokio's blocking thread pool, whatever
+//! its format. A progress
+//! callback fires after every file, and once more after the final write, so
+//! callers can surface progress to the UI.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::database::{Database, FlightBatchRow};
+use crate::library::read_config;
+use crate::models::ImportResult;
+use crate::parser::{LogParser, ParseResult, ParserError};
+
+/// Number of parsed flights committed per database transaction
+const WRITE_BATCH_SIZE: usize = 25;
+
+/// Imports of at least this many files take an automatic snapshot first
+/// (when `auto_backup_enabled` is set in config.json)
+const AUTO_BACKUP_MIN_FILES: usize = 20;
+
+/// Automatic snapshots kept unless `auto_backup_keep` says otherwise
+const DEFAULT_AUTO_BACKUP_KEEP: usize = 5;
+
+/// Progress snapshot reported after each file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub current_file: String,
+    pub processed: usize,
+    pub total: usize,
+    pub percent: f64,
+    pub failures: usize,
+}
+
+/// Apply the smart tag settings from config.json to a parsed tag list
+pub fn configured_tags(data_dir: &Path, tags: &[String]) -> Vec<String> {
+    let config = read_config(data_dir);
+
+    if !config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true) {
+        return Vec::new();
+    }
+    match config.get("enabled_tag_types").and_then(|v| v.as_array()) {
+        Some(types) => {
+            let enabled_types: Vec<String> = types
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+            LogParser::filter_smart_tags(tags.to_vec(), &enabled_types)
+        }
+        None => tags.to_vec(),
+    }
+}
+
+/// Take a rotating database snapshot before a large import, if enabled.
+/// Failures are logged and never block the import.
+pub fn backup_before_import(db: &Database, file_count: usize) {
+    if file_count < AUTO_BACKUP_MIN_FILES {
+        return;
+    }
+    let config = read_config(&db.data_dir);
+    if !config.get("auto_backup_enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return;
+    }
+    let keep = config
+        .get("auto_backup_keep")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_AUTO_BACKUP_KEEP, |n| n as usize);
+
+    match db.rotating_backup(keep) {
+        Ok(path) => log::info!("Snapshot before importing {} files: {:?}", file_count, path),
+        Err(e) => log::warn!("Automatic pre-import backup failed: {}", e),
+    }
+}
+
+fn failed(message: String, file_hash: Option<String>) -> ImportResult {
+    ImportResult {
+        success: false,
+        flight_id: None,
+        message,
+        point_count: 0,
+        file_hash,
+    }
+}
+
+/// Import many files at once. Results are returned in the same order as `paths`.
+pub async fn import_batch<F>(db: Arc<Database>, paths: Vec<PathBuf>, on_progress: F) -> Vec<ImportResult>
+where
+    F: Fn(&BatchProgress),
+{
+    let import_start = std::time::Instant::now();
+    let total = paths.len();
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    log::info!("Batch importing {} files with {} parallel workers", total, workers);
+    backup_before_import(&db, total);
+
+    let semaphore = Arc::new(Semaphore::new(workers));
+    let runtime = tokio::runtime::Handle::current();
+    let mut tasks = JoinSet::new();
+    for (index, path) in paths.iter().cloned().enumerate() {
+        let db = db.clone();
+        let semaphore = semaphore.clone();
+        let runtime = runtime.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("import semaphore closed");
+            // Hashing and most format parsers are synchronous; keep them off the async workers
+            let parse_path = path.clone();
+            let outcome = tokio::task::spawn_blocking(move || {
+                runtime.block_on(LogParser::new(&db).parse_log(&parse_path))
+            })
+            .await
+            .unwrap_or_else(|e| Err(ParserError::Parse(format!("Parser task failed: {}", e))));
+            (index, path, outcome)
+        });
+    }
+
+    let mut results: Vec<Option<ImportResult>> = vec![None; total];
+    let mut pending: Vec<(usize, ParseResult)> = Vec::new();
+    let mut seen_hashes: HashSet<String> = HashSet::new();
+    // (drone serial, battery serial, start time) of the flights waiting to be written
+    let mut seen_signatures: HashMap<(String, String, i64), String> = HashMap::new();
+    let mut processed = 0usize;
+    let mut failures = 0usize;
+    let mut last_file = String::new();
+
+    while let Some(joined) = tasks.join_next().await {
+        let (index, path, outcome) = match joined {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Batch import task failed: {}", e);
+                processed += 1;
+                failures += 1;
+                continue;
+            }
+        };
+        processed += 1;
+
+        match outcome {
+            Ok(parse_result) => {
+                let metadata = &parse_result.metadata;
+                let hash = metadata.file_hash.clone();
+                let in_batch = hash.as_ref().map_or(false, |h| !seen_hashes.insert(h.clone()));
+                // Same keys as is_duplicate_flight, which only sees flights already written
+                let signature = match (&metadata.drone_serial, &metadata.battery_serial, metadata.start_time) {
+                    (Some(d), Some(b), Some(t)) if !d.is_empty() && !b.is_empty() => {
+                        Some((d.clone(), b.clone(), t.timestamp_millis()))
+                    }
+                    _ => None,
+                };
+                let duplicate = match signature.as_ref().and_then(|key| seen_signatures.get(key)) {
+                    Some(name) => Some(name.clone()),
+                    None => db
+                        .is_duplicate_flight(
+                            metadata.drone_serial.as_deref(),
+                            metadata.battery_serial.as_deref(),
+                            metadata.start_time,
+                        )
+                        .unwrap_or(None),
+                };
+
+                if in_batch {
+                    failures += 1;
+                    results[index] = Some(failed("Same file appears twice in this import".to_string(), hash));
+                } else if let Some(matching_flight) = duplicate {
+                    failures += 1;
+                    results[index] = Some(failed(
+                        format!("Duplicate flight: matches '{}' (same drone, battery, and start time)", matching_flight),
+                        hash,
+                    ));
+                } else {
+                    if let Some(key) = signature {
+                        seen_signatures.insert(key, parse_result.metadata.display_name.clone());
+                    }
+                    pending.push((index, parse_result));
+                }
+            }
+            Err(ParserError::AlreadyImported(matching_flight)) => {
+                failures += 1;
+                results[index] = Some(failed(
+                    format!("This flight log has already been imported (matches: {})", matching_flight),
+                    None,
+                ));
+            }
+            Err(ParserError::InTrash(trashed_flight)) => {
+                failures += 1;
+                results[index] = Some(failed(
+                    format!("This flight log belongs to '{}', which is in the trash. Restore it from the trash instead.", trashed_flight),
+                    None,
+                ));
+            }
+            Err(ParserError::LinkedToFlight(flight_id, matching_flight)) => {
+                results[index] = Some(ImportResult {
+                    success: true,
+                    flight_id: Some(flight_id),
+                    message: format!("Linked as rich data to existing flight '{}'", matching_flight),
+                    point_count: 0,
+                    file_hash: None,
+                });
+            }
+            Err(e) => {
+                log::error!("Failed to parse log {:?}: {}", path, e);
+                failures += 1;
+                results[index] = Some(failed(format!("Failed to parse log: {}", e), None));
+            }
+        }
+
+        if pending.len() >= WRITE_BATCH_SIZE {
+            failures += flush(&db, &mut pending, &mut results);
+        }
+
+        last_file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        on_progress(&BatchProgress {
+            current_file: last_file.clone(),
+            processed,
+            total,
+            percent: if total > 0 { processed as f64 * 100.0 / total as f64 } else { 100.0 },
+            failures,
+        });
+    }
+
+    // The last batch can fail too; report its failures like the others
+    let final_failures = flush(&db, &mut pending, &mut results);
+    if final_failures > 0 {
+        failures += final_failures;
+        on_progress(&BatchProgress {
+            current_file: last_file,
+            processed,
+            total,
+            percent: 100.0,
+            failures,
+        });
+    }
+
+    let results: Vec<ImportResult> = results
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| failed("Import task failed".to_string(), None)))
+        .collect();
+
+    log::info!(
+        "Batch import finished in {:.1}s: {} of {} files imported",
+        import_start.elapsed().as_secs_f64(),
+        results.iter().filter(|r| r.success).count(),
+        total
+    );
+    if results.iter().any(|r| r.success) {
+        crate::auto_export::export_in_background(db.clone());
+        crate::geocoder::locate_in_background(db);
+    }
+
+    results
+}
+
+/// Commit the pending flights in one transaction. Returns the number of failures.
+fn flush(db: &Database, pending: &mut Vec<(usize, ParseResult)>, results: &mut [Option<ImportResult>]) -> usize {
+    if pending.is_empty() {
+        return 0;
+    }
+
+    let tags: Vec<Vec<String>> = pending
+        .iter()
+        .map(|(_, r)| configured_tags(&db.data_dir, &r.tags))
+        .collect();
+    let rows: Vec<_> = pending
+        .iter()
+        .zip(&tags)
+        .map(|((_, r), t)| FlightBatchRow {
+            metadata: &r.metadata,
+            points: &r.points,
+            tags: t,
+            home_points: &r.home_points,
+            motors: &r.motors,
+            events: &r.events,
+        })
+        .collect();
+
+    let outcome = db.insert_flights_batch(&rows);
+    let failures = match &outcome {
+        Ok(_) => 0,
+        Err(e) => {
+            log::error!("Failed to save a batch of {} flights: {}", pending.len(), e);
+            pending.len()
+        }
+    };
+
+    for (i, (index, parse_result)) in pending.drain(..).enumerate() {
+        results[index] = Some(match &outcome {
+            Ok(counts) => ImportResult {
+                success: true,
+                flight_id: Some(parse_result.metadata.id),
+                message: parse_result.import_message(counts[i]),
+                point_count: counts[i],
+                file_hash: parse_result.metadata.file_hash,
+            },
+            Err(e) => failed(format!("Failed to save flight: {}", e), parse_result.metadata.file_hash),
+        });
+    }
+
+    failures
+}
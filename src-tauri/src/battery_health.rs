@@ -0,0 +1,170 @@
+//! Battery health trends from the battery data logged on every flight.
+//!
+//! Per flight the capacity is estimated from the mean current, the flight
+//! time and the percentage used, and the voltage sag is the resting voltage
+//! minus the lowest voltage over the first 10% of charge. Both are compared
+//! between a battery's first and latest flights; together with the worst
+//! cell imbalance of the latest flights they give a 0-100 health score.
+//! Logs without current or cell voltages simply leave those parts out.
+//!
+//! `discharge_curves` returns the charge over time and the voltage over
+//! charge of each flight of a battery, so an ageing battery shows as curves
+//! that drop faster or sit lower than its early ones.
+
+use std::collections::BTreeMap;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{BatteryFlightSample, BatteryHealth, DischargeCurve, Flight, FlightFilter, TelemetryRecord};
+
+/// Below this much charge used the capacity estimate is mostly noise
+const MIN_USED_PERCENT: f64 = 20.0;
+/// Flights averaged at each end of the trend
+const TREND_FLIGHTS: usize = 5;
+/// Cell imbalance considered normal
+const NORMAL_IMBALANCE_V: f64 = 0.05;
+/// Telemetry points read per flight for a discharge curve
+const CURVE_POINTS: usize = 600;
+
+/// Full capacity in mAh from the mean current (A) over a flight that used
+/// `used_percent` of the charge
+pub fn capacity_mah(avg_current_a: f64, duration_secs: f64, used_percent: f64) -> Option<f64> {
+    if used_percent < MIN_USED_PERCENT || avg_current_a <= 0.0 || duration_secs <= 0.0 {
+        return None;
+    }
+    let drawn_mah = avg_current_a * duration_secs / 3.6;
+    Some(drawn_mah / (used_percent / 100.0))
+}
+
+/// Health trend of every battery, most recently flown first
+pub fn battery_health(db: &Database) -> Result<Vec<BatteryHealth>, DatabaseError> {
+    let mut by_serial: BTreeMap<String, Vec<BatteryFlightSample>> = BTreeMap::new();
+    for sample in db.get_battery_flight_samples()? {
+        by_serial.entry(sample.battery_serial.clone()).or_default().push(sample);
+    }
+
+    let mut batteries: Vec<BatteryHealth> = by_serial
+        .into_iter()
+        .map(|(serial, flights)| {
+            let capacities: Vec<f64> = flights.iter().filter_map(|f| f.capacity_mah).collect();
+            let sags: Vec<f64> = flights.iter().filter_map(|f| f.voltage_sag_v).collect();
+            let capacity_fade_percent =
+                early_late(&capacities).map(|(early, late)| ((early - late) / early * 100.0).max(0.0));
+            let voltage_sag_change_v = early_late(&sags).map(|(early, late)| late - early);
+            let recent_cell_imbalance_v = flights
+                .iter()
+                .rev()
+                .take(TREND_FLIGHTS)
+                .filter_map(|f| f.cell_imbalance_v)
+                .reduce(f64::max);
+
+            BatteryHealth {
+                health_score: health_score(capacity_fade_percent, voltage_sag_change_v, recent_cell_imbalance_v),
+                serial,
+                flight_count: flights.len() as i64,
+                first_flight: flights.first().and_then(|f| f.start_time.clone()),
+                last_flight: flights.last().and_then(|f| f.start_time.clone()),
+                capacity_fade_percent,
+                voltage_sag_change_v,
+                recent_cell_imbalance_v,
+                flights,
+            }
+        })
+        .collect();
+    batteries.sort_by(|a, b| b.last_flight.cmp(&a.last_flight));
+    Ok(batteries)
+}
+
+/// Discharge curves of every active flight of a battery, oldest first
+pub fn discharge_curves(db: &Database, serial: &str) -> Result<Vec<DischargeCurve>, DatabaseError> {
+    let filter = FlightFilter {
+        batteries: vec![serial.to_string()],
+        ..Default::default()
+    };
+    let mut curves = Vec::new();
+    // The filter lists the newest flight first
+    for flight_id in db.evaluate_flight_filter(&filter)?.into_iter().rev() {
+        let flight = db.get_flight_by_id(flight_id)?;
+        let records = db.get_flight_telemetry(flight_id, Some(CURVE_POINTS), flight.point_count.map(i64::from))?;
+        curves.push(discharge_curve(&flight, &records));
+    }
+    Ok(curves)
+}
+
+fn discharge_curve(flight: &Flight, records: &[TelemetryRecord]) -> DischargeCurve {
+    let start_ms = records.first().map_or(0, |r| r.timestamp_ms);
+    let percent_by_minute = records
+        .iter()
+        .filter_map(|r| {
+            let percent = r.battery_percent?;
+            Some([(r.timestamp_ms - start_ms) as f64 / 60_000.0, f64::from(percent)])
+        })
+        .collect();
+
+    // Mean voltage at each whole percent
+    let mut by_percent: BTreeMap<i32, (f64, usize)> = BTreeMap::new();
+    let readings = records
+        .iter()
+        .filter_map(|r| Some((r.battery_percent?, r.battery_voltage?)))
+        .filter(|(_, voltage)| *voltage > 0.0);
+    for (percent, voltage) in readings {
+        let entry = by_percent.entry(percent).or_insert((0.0, 0));
+        entry.0 += voltage;
+        entry.1 += 1;
+    }
+    let voltage_by_percent = by_percent
+        .into_iter()
+        .rev()
+        .map(|(percent, (sum, count))| [f64::from(percent), sum / count as f64])
+        .collect();
+
+    DischargeCurve {
+        flight_id: flight.id,
+        display_name: flight.display_name.clone(),
+        start_time: flight.start_time.clone(),
+        percent_by_minute,
+        voltage_by_percent,
+    }
+}
+
+/// Means of the first and the latest flights; None without enough flights
+/// for the two groups to differ
+fn early_late(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 4 {
+        return None;
+    }
+    let n = TREND_FLIGHTS.min(values.len() / 2);
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    let early = mean(&values[..n]);
+    (early > 0.0).then(|| (early, mean(&values[values.len() - n..])))
+}
+
+/// 100 minus 2 points per percent of capacity lost, 1 per 10 mV of extra
+/// sag and 2 per 10 mV of cell imbalance beyond normal
+fn health_score(fade_percent: Option<f64>, sag_change_v: Option<f64>, imbalance_v: Option<f64>) -> f64 {
+    let mut score = 100.0;
+    score -= 2.0 * fade_percent.unwrap_or(0.0);
+    score -= 100.0 * sag_change_v.unwrap_or(0.0).max(0.0);
+    score -= 200.0 * (imbalance_v.unwrap_or(0.0) - NORMAL_IMBALANCE_V).max(0.0);
+    score.clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_from_current_and_charge_used() {
+        // 10 A for 18 minutes is 3000 mAh; using 60% gives 5000 mAh full
+        let capacity = capacity_mah(10.0, 1080.0, 60.0).unwrap();
+        assert!((capacity - 5000.0).abs() < 1e-6);
+        assert_eq!(capacity_mah(10.0, 1080.0, 5.0), None);
+    }
+
+    #[test]
+    fn score_drops_with_fade_and_imbalance() {
+        assert_eq!(health_score(None, None, None), 100.0);
+        let (early, late) = early_late(&[5000.0, 5000.0, 4500.0, 4500.0]).unwrap();
+        let fade = (early - late) / early * 100.0;
+        assert!((health_score(Some(fade), Some(-0.1), Some(0.15)) - 60.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,56 @@
+//! Height and distance compliance: flights that went above a height limit or
+//! further from home than a distance limit, with the stretches that did.
+//!
+//! Limits are saved as `compliance_limits` in config.json. Height is measured
+//! above the takeoff point, which is what the aircraft logs; distance is the
+//! horizontal distance from the home point. Flights excluded from statistics
+//! aren't checked.
+
+use std::path::Path;
+
+use crate::database::{Database, DatabaseError};
+use crate::library::read_config;
+use crate::models::{ComplianceLimits, ComplianceReport};
+
+/// Height limit until configured: 120 m, the usual open-category ceiling
+const DEFAULT_MAX_HEIGHT_M: f64 = 120.0;
+
+/// Saved limits; 120 m of height and no distance limit until configured
+pub fn limits(data_dir: &Path) -> ComplianceLimits {
+    read_config(data_dir)
+        .get("compliance_limits")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or(ComplianceLimits {
+            max_height_m: Some(DEFAULT_MAX_HEIGHT_M),
+            max_distance_m: None,
+        })
+}
+
+/// Save the limits to config.json
+pub fn save_limits(data_dir: &Path, limits: &ComplianceLimits) -> std::io::Result<()> {
+    for limit in [limits.max_height_m, limits.max_distance_m].into_iter().flatten() {
+        if !limit.is_finite() || limit <= 0.0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Limits must be above zero"));
+        }
+    }
+
+    let config_path = data_dir.join("config.json");
+    let mut config = read_config(data_dir);
+    config["compliance_limits"] = serde_json::json!(limits);
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+}
+
+/// Flights counted in statistics checked against the saved limits
+pub fn compliance_report(db: &Database) -> Result<ComplianceReport, DatabaseError> {
+    let limits = limits(&db.data_dir);
+    let flights = db.get_limit_exceedances(limits.max_height_m, limits.max_distance_m)?;
+    let violations = |kind: &str| flights.iter().filter(|f| f.segments.iter().any(|s| s.kind == kind)).count() as i64;
+
+    Ok(ComplianceReport {
+        flight_count: db.get_library_stats(None)?.total_flights,
+        height_violations: violations("height"),
+        distance_violations: violations("distance"),
+        limits,
+        flights,
+    })
+}
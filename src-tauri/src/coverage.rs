@@ -0,0 +1,355 @@
+//! Coverage map: the tracks of many flights merged into one layer, to see
+//! everywhere the aircraft have been in Google Earth or a GIS tool.
+//!
+//! Tracks are simplified with Douglas-Peucker so a whole library stays a
+//! manageable file. Optionally the positions are also binned into a square
+//! heat grid, each cell colored by the time spent flying over it.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::export::{flight_start, xml_escape, ExportError};
+use crate::models::FlightFilter;
+
+/// Meters per degree of latitude
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// Longest gap between two positions counted as time over a heat grid cell;
+/// longer gaps are signal losses, not hovering
+const MAX_SAMPLE_GAP_MS: i64 = 5000;
+
+/// Heat grid colors from least to most time flown, as KML `aabbggrr`
+const HEAT_COLORS: [&str; 5] = ["80ffff00", "8000ff80", "8000ffff", "800080ff", "800000ff"];
+
+/// File formats of the coverage export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageFormat {
+    Kml,
+    Geojson,
+}
+
+impl CoverageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CoverageFormat::Kml => "kml",
+            CoverageFormat::Geojson => "geojson",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            CoverageFormat::Kml => "application/vnd.google-earth.kml+xml",
+            CoverageFormat::Geojson => "application/geo+json",
+        }
+    }
+}
+
+/// Simplification and heat grid settings of the coverage export
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CoverageOptions {
+    /// Largest distance in meters a simplified track may stray from the
+    /// logged one; 0 keeps every position
+    pub simplify_m: f64,
+    /// Edge of a heat grid cell in meters; no grid when unset
+    pub heat_grid_m: Option<f64>,
+}
+
+impl Default for CoverageOptions {
+    fn default() -> Self {
+        Self {
+            simplify_m: 5.0,
+            heat_grid_m: Some(100.0),
+        }
+    }
+}
+
+/// Simplified track of one flight
+struct CoverageTrack {
+    flight_id: i64,
+    name: String,
+    start: Option<String>,
+    /// (lat, lon)
+    points: Vec<(f64, f64)>,
+}
+
+/// One heat grid cell: its south-west corner, size in degrees and the
+/// seconds flown over it
+struct HeatCell {
+    lat: f64,
+    lon: f64,
+    lat_size: f64,
+    lon_size: f64,
+    seconds: f64,
+}
+
+/// Coverage layer of every flight matching `filter`
+pub fn coverage(
+    db: &Database,
+    filter: &FlightFilter,
+    format: CoverageFormat,
+    options: &CoverageOptions,
+) -> Result<String, ExportError> {
+    let start = std::time::Instant::now();
+    let cell_m = options.heat_grid_m.filter(|m| *m > 0.0);
+    let mut tracks = Vec::new();
+    // Seconds flown per (row, column) cell
+    let mut grid: HashMap<(i64, i64), f64> = HashMap::new();
+
+    for flight_id in db.evaluate_flight_filter(filter)? {
+        let track = db.get_flight_track(flight_id)?;
+        if track.len() < 2 {
+            continue;
+        }
+        if let Some(cell_m) = cell_m {
+            for pair in track.windows(2) {
+                let (ts, lat, lon) = pair[0];
+                let gap_ms = (pair[1].0 - ts).clamp(0, MAX_SAMPLE_GAP_MS);
+                *grid.entry(cell_key(lat, lon, cell_m)).or_default() += gap_ms as f64 / 1000.0;
+            }
+        }
+        let flight = db.get_flight_by_id(flight_id)?;
+        let points: Vec<(f64, f64)> = track.iter().map(|(_, lat, lon)| (*lat, *lon)).collect();
+        tracks.push(CoverageTrack {
+            flight_id,
+            name: flight.display_name.clone(),
+            start: flight_start(&flight).map(|s| s.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            points: simplify(&points, options.simplify_m),
+        });
+    }
+
+    // Sorted so the same library always gives the same file
+    let mut grid: Vec<((i64, i64), f64)> = grid.into_iter().collect();
+    grid.sort_by_key(|(key, _)| *key);
+    let cells: Vec<HeatCell> = match cell_m {
+        Some(cell_m) => grid
+            .into_iter()
+            .map(|((row, column), seconds)| {
+                let lat_size = cell_m / METERS_PER_DEGREE;
+                let lon_size = lon_cell_size(row, cell_m);
+                HeatCell {
+                    lat: row as f64 * lat_size,
+                    lon: column as f64 * lon_size,
+                    lat_size,
+                    lon_size,
+                    seconds,
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let output = match format {
+        CoverageFormat::Kml => write_kml(&tracks, &cells),
+        CoverageFormat::Geojson => write_geojson(&tracks, &cells),
+    };
+    log::info!(
+        "Coverage export of {} flights ({} points, {} heat cells) in {:.1}s",
+        tracks.len(),
+        tracks.iter().map(|t| t.points.len()).sum::<usize>(),
+        cells.len(),
+        start.elapsed().as_secs_f64()
+    );
+    Ok(output)
+}
+
+/// Grid cell of a position. Rows are `cell_m` tall everywhere; columns are
+/// `cell_m` wide at the row's latitude.
+fn cell_key(lat: f64, lon: f64, cell_m: f64) -> (i64, i64) {
+    let row = (lat / (cell_m / METERS_PER_DEGREE)).floor() as i64;
+    let column = (lon / lon_cell_size(row, cell_m)).floor() as i64;
+    (row, column)
+}
+
+/// Width in degrees of longitude of the cells in a grid row
+fn lon_cell_size(row: i64, cell_m: f64) -> f64 {
+    let lat_size = cell_m / METERS_PER_DEGREE;
+    let center = (row as f64 + 0.5) * lat_size;
+    // Near the poles a cell would span the whole globe
+    lat_size / center.to_radians().cos().max(0.01)
+}
+
+/// Douglas-Peucker simplification of a (lat, lon) path, keeping every point
+/// farther than `tolerance_m` from the simplified line
+fn simplify(points: &[(f64, f64)], tolerance_m: f64) -> Vec<(f64, f64)> {
+    if points.len() < 3 || tolerance_m <= 0.0 {
+        return points.to_vec();
+    }
+    // Local equirectangular projection in meters, fine at track scale
+    let lat0 = points[0].0.to_radians().cos();
+    let xy: Vec<(f64, f64)> = points
+        .iter()
+        .map(|(lat, lon)| (lon * METERS_PER_DEGREE * lat0, lat * METERS_PER_DEGREE))
+        .collect();
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0usize, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let mut farthest = (0.0, 0usize);
+        for i in first + 1..last {
+            let distance = segment_distance(xy[i], xy[first], xy[last]);
+            if distance > farthest.0 {
+                farthest = (distance, i);
+            }
+        }
+        if farthest.0 > tolerance_m {
+            keep[farthest.1] = true;
+            stack.push((first, farthest.1));
+            stack.push((farthest.1, last));
+        }
+    }
+    points.iter().zip(keep).filter(|(_, k)| *k).map(|(p, _)| *p).collect()
+}
+
+/// Distance from `p` to the segment from `a` to `b`
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    };
+    ((p.0 - a.0 - t * dx).powi(2) + (p.1 - a.1 - t * dy).powi(2)).sqrt()
+}
+
+/// Heat level of a cell, 0 to `HEAT_COLORS.len() - 1`, on a log scale up
+/// to the busiest cell
+fn heat_level(seconds: f64, max_seconds: f64) -> usize {
+    if max_seconds <= 1.0 {
+        return HEAT_COLORS.len() - 1;
+    }
+    let ratio = seconds.max(1.0).ln() / max_seconds.ln();
+    ((ratio * HEAT_COLORS.len() as f64) as usize).min(HEAT_COLORS.len() - 1)
+}
+
+fn format_minutes(seconds: f64) -> String {
+    if seconds < 60.0 {
+        format!("{:.0} s", seconds)
+    } else {
+        format!("{:.1} min", seconds / 60.0)
+    }
+}
+
+fn write_kml(tracks: &[CoverageTrack], cells: &[HeatCell]) -> String {
+    let mut kml = String::new();
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n");
+    kml.push_str("  <Document>\n");
+    kml.push_str("    <name>Flight coverage</name>\n");
+    kml.push_str("    <open>1</open>\n");
+    let _ = writeln!(kml, "    <description>{} flights</description>", tracks.len());
+    kml.push_str(concat!(
+        "    <Style id=\"track\">\n",
+        "      <LineStyle><color>ff00a5ff</color><width>2</width></LineStyle>\n",
+        "    </Style>\n",
+    ));
+    for (level, color) in HEAT_COLORS.iter().enumerate() {
+        let _ = writeln!(
+            kml,
+            "    <Style id=\"heat{}\"><LineStyle><width>0</width></LineStyle><PolyStyle><color>{}</color><outline>0</outline></PolyStyle></Style>",
+            level, color
+        );
+    }
+
+    kml.push_str("    <Folder>\n");
+    kml.push_str("      <name>Flights</name>\n");
+    for track in tracks {
+        kml.push_str("      <Placemark>\n");
+        let _ = writeln!(kml, "        <name>{}</name>", xml_escape(&track.name));
+        if let Some(start) = &track.start {
+            let _ = writeln!(kml, "        <TimeStamp><when>{}</when></TimeStamp>", start);
+        }
+        kml.push_str("        <styleUrl>#track</styleUrl>\n");
+        let _ = writeln!(kml, "        <ExtendedData><Data name=\"flightId\"><value>{}</value></Data></ExtendedData>", track.flight_id);
+        kml.push_str("        <LineString>\n");
+        kml.push_str("          <tessellate>1</tessellate>\n");
+        kml.push_str("          <altitudeMode>clampToGround</altitudeMode>\n");
+        kml.push_str("          <coordinates>");
+        for (lat, lon) in &track.points {
+            let _ = write!(kml, "{:.6},{:.6} ", lon, lat);
+        }
+        kml.push_str("</coordinates>\n");
+        kml.push_str("        </LineString>\n");
+        kml.push_str("      </Placemark>\n");
+    }
+    kml.push_str("    </Folder>\n");
+
+    if !cells.is_empty() {
+        let max_seconds = cells.iter().map(|c| c.seconds).fold(0.0, f64::max);
+        kml.push_str("    <Folder>\n");
+        kml.push_str("      <name>Heat grid</name>\n");
+        for cell in cells {
+            let (south, west) = (cell.lat, cell.lon);
+            let (north, east) = (cell.lat + cell.lat_size, cell.lon + cell.lon_size);
+            kml.push_str("      <Placemark>\n");
+            let _ = writeln!(kml, "        <description>{} flown</description>", format_minutes(cell.seconds));
+            let _ = writeln!(kml, "        <styleUrl>#heat{}</styleUrl>", heat_level(cell.seconds, max_seconds));
+            let _ = writeln!(
+                kml,
+                "        <Polygon><tessellate>1</tessellate><outerBoundaryIs><LinearRing><coordinates>{w:.6},{s:.6} {e:.6},{s:.6} {e:.6},{n:.6} {w:.6},{n:.6} {w:.6},{s:.6}</coordinates></LinearRing></outerBoundaryIs></Polygon>",
+                w = west,
+                s = south,
+                e = east,
+                n = north
+            );
+            kml.push_str("      </Placemark>\n");
+        }
+        kml.push_str("    </Folder>\n");
+    }
+
+    kml.push_str("  </Document>\n");
+    kml.push_str("</kml>\n");
+    kml
+}
+
+fn write_geojson(tracks: &[CoverageTrack], cells: &[HeatCell]) -> String {
+    use serde_json::json;
+
+    let round = |value: f64| (value * 1e6).round() / 1e6;
+    let mut features: Vec<serde_json::Value> = tracks
+        .iter()
+        .map(|track| {
+            json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": track.points.iter().map(|(lat, lon)| json!([round(*lon), round(*lat)])).collect::<Vec<_>>(),
+                },
+                "properties": {
+                    "featureType": "track",
+                    "flightId": track.flight_id,
+                    "name": track.name,
+                    "startTime": track.start,
+                },
+            })
+        })
+        .collect();
+
+    let max_seconds = cells.iter().map(|c| c.seconds).fold(0.0, f64::max);
+    features.extend(cells.iter().map(|cell| {
+        let (south, west) = (round(cell.lat), round(cell.lon));
+        let (north, east) = (round(cell.lat + cell.lat_size), round(cell.lon + cell.lon_size));
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[west, south], [east, south], [east, north], [west, north], [west, south]]],
+            },
+            "properties": {
+                "featureType": "heatCell",
+                "seconds": (cell.seconds * 10.0).round() / 10.0,
+                "level": heat_level(cell.seconds, max_seconds),
+            },
+        })
+    }));
+
+    serde_json::to_string(&json!({ "type": "FeatureCollection", "features": features })).unwrap_or_default()
+}
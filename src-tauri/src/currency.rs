@@ -0,0 +1,118 @@
+//! Flight currency: how much the pilot and each aircraft flew recently,
+//! checked against recency requirements such as an insurer's "3 flights in
+//! the last 90 days".
+//!
+//! Requirements are saved as `currency_requirements` in config.json. Flights
+//! don't record who flew them, so the pilot's figures cover every aircraft.
+//! Flights excluded from statistics don't count.
+
+use std::path::Path;
+
+use crate::database::{Database, DatabaseError};
+use crate::library::read_config;
+use crate::models::{CurrencyCheck, CurrencyEntry, CurrencyReport, CurrencyRequirement, CurrencyWindow};
+
+/// Windows always reported, in days
+pub const WINDOWS: [u32; 3] = [30, 90, 365];
+
+/// Longest window a requirement may use
+const MAX_WINDOW_DAYS: u32 = 3650;
+
+/// Saved requirements; 3 flights in 90 days until configured
+pub fn requirements(data_dir: &Path) -> Vec<CurrencyRequirement> {
+    read_config(data_dir)
+        .get("currency_requirements")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_else(|| {
+            vec![CurrencyRequirement {
+                window_days: 90,
+                min_flights: 3,
+                min_hours: 0.0,
+            }]
+        })
+}
+
+/// Save the requirements to config.json; an empty list turns the checks off
+pub fn save_requirements(data_dir: &Path, requirements: &[CurrencyRequirement]) -> std::io::Result<()> {
+    for requirement in requirements {
+        if requirement.window_days == 0 || requirement.window_days > MAX_WINDOW_DAYS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Windows must be 1 to {} days", MAX_WINDOW_DAYS),
+            ));
+        }
+        if !requirement.min_hours.is_finite() || requirement.min_hours < 0.0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Minimum hours can't be negative"));
+        }
+    }
+
+    let config_path = data_dir.join("config.json");
+    let mut config = read_config(data_dir);
+    config["currency_requirements"] = serde_json::json!(requirements);
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+}
+
+/// Recent flying of the pilot and each aircraft against the saved requirements
+pub fn currency(db: &Database) -> Result<CurrencyReport, DatabaseError> {
+    let requirements = requirements(&db.data_dir);
+    let mut windows: Vec<u32> = WINDOWS
+        .iter()
+        .copied()
+        .chain(requirements.iter().map(|r| r.window_days))
+        .collect();
+    windows.sort_unstable();
+    windows.dedup();
+
+    let mut drones = db.get_recent_activity(&windows)?;
+    let mut pilot = CurrencyEntry {
+        name: crate::report::pilot_details(&db.data_dir).name,
+        drone_model: None,
+        drone_serial: None,
+        last_flight: drones.iter().filter_map(|d| d.last_flight.clone()).max(),
+        windows: windows
+            .iter()
+            .map(|days| CurrencyWindow {
+                days: *days,
+                flight_count: 0,
+                duration_secs: 0.0,
+            })
+            .collect(),
+        checks: Vec::new(),
+        current: false,
+    };
+    // Every flight belongs to exactly one aircraft, so the pilot's totals are the sums
+    for drone in &drones {
+        for (total, window) in pilot.windows.iter_mut().zip(&drone.windows) {
+            total.flight_count += window.flight_count;
+            total.duration_secs += window.duration_secs;
+        }
+    }
+
+    check(&mut pilot, &requirements);
+    for drone in &mut drones {
+        check(drone, &requirements);
+    }
+    Ok(CurrencyReport { pilot, drones })
+}
+
+fn check(entry: &mut CurrencyEntry, requirements: &[CurrencyRequirement]) {
+    entry.checks = requirements
+        .iter()
+        .map(|requirement| {
+            let (flight_count, duration_secs) = entry
+                .windows
+                .iter()
+                .find(|w| w.days == requirement.window_days)
+                .map(|w| (w.flight_count, w.duration_secs))
+                .unwrap_or((0, 0.0));
+            CurrencyCheck {
+                requirement: requirement.clone(),
+                flight_count,
+                duration_secs,
+                met: flight_count >= i64::from(requirement.min_flights)
+                    && duration_secs / 3600.0 >= requirement.min_hours,
+            }
+        })
+        .collect();
+    entry.current = entry.checks.iter().all(|c| c.met);
+}
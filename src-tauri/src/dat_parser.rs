@@ -0,0 +1,477 @@
+//! Parser module for DJI flight controller DAT logs (FLY###.DAT).
+//!
+//! These files are written by the aircraft itself to its internal storage
+//! and are independent from the mobile app TXT logs. The layout is:
+//! - A fixed-size header containing the `BUILD` marker at offset 16
+//! - A stream of records, each framed as
+//!   `0x55 | len | 0x00 | type (u16 LE) | pad | tick (u32 LE) | payload | crc16`
+//!
+//! The flight controller clock ticks at 600 Hz, which gives us the timeline.
+//! Besides the OSD track, the ESC status record is decoded into per-motor
+//! RPM, current and temperature samples, and the raw magnetometer record into
+//! a compass interference level.
+//! Records are read incrementally from disk, so the raw bytes of FLY files of
+//! several hundred MB are never held in memory at once; the decoded points
+//! are, since statistics and matching need the whole track. Each record's CRC16 is checked
+//! so stray 0x55 bytes after a resync are not taken for records, and OSD
+//! points implying an impossible jump from the previous one are dropped.
+//! Records from newer aircraft (Mavic 2 and later) have scrambled payloads;
+//! those are detected during validation and rejected with a clear error
+//! rather than producing garbage tracks.
+
+use std::io::{BufReader, Read};
+
+use crate::database::Database;
+use crate::models::{FlightMetadata, MotorSample, TelemetryPoint};
+use crate::parser::{haversine_distance, LogParser, LogSource, ParseResult, ParserError};
+
+/// Offset of the `BUILD` marker inside the DAT header
+const BUILD_MARKER_OFFSET: usize = 16;
+
+/// Size of the DAT header before the record stream begins
+const HEADER_LEN: usize = 128;
+
+/// Start-of-record sync byte
+const RECORD_SYNC: u8 = 0x55;
+
+/// Bytes before the payload (sync, len, pad, type, pad, tick)
+const RECORD_HEADER_LEN: usize = 10;
+
+/// Trailing CRC16
+const RECORD_CRC_LEN: usize = 2;
+
+/// Seed of the record CRC16 (reflected CCITT polynomial)
+const RECORD_CRC_SEED: u16 = 0x3692;
+
+/// Flight controller tick rate (ticks per second)
+const TICKS_PER_SEC: f64 = 600.0;
+
+/// Record type for the OSD general block (position, attitude, velocity)
+const RECORD_OSD_GENERAL: u16 = 12;
+
+/// Minimum OSD payload size we know how to decode
+const OSD_GENERAL_MIN_LEN: usize = 38;
+
+/// Record type for the ESC status block (one entry per motor)
+const RECORD_ESC_DATA: u16 = 10090;
+
+/// Bytes per motor inside the ESC record:
+/// `status u8 | current i16 (cA) | speed i16 (rpm) | voltage u16 (mV) | temperature i16 (°C) | ...`
+const ESC_ENTRY_LEN: usize = 19;
+
+/// Upper bound on motors per aircraft (octocopters)
+const ESC_MAX_MOTORS: usize = 8;
+
+/// ESC records arrive at up to 50 Hz; keep one sample per motor per interval
+const ESC_SAMPLE_INTERVAL_MS: i64 = 100;
+
+/// Record type for the raw magnetometer reading (`x, y, z` as i16)
+const RECORD_MAG_RAW: u16 = 2256;
+
+/// Minimum magnetometer payload size
+const MAG_RAW_MIN_LEN: usize = 6;
+
+/// Fastest plausible ground speed between two OSD points (m/s); faster
+/// jumps are decoding garbage, not flight
+const MAX_PLAUSIBLE_SPEED_MS: f64 = 60.0;
+
+/// Jump allowed regardless of elapsed time, for GPS noise between close points (m)
+const JUMP_TOLERANCE_M: f64 = 20.0;
+
+/// Consecutive implausible points after which the previous point is taken
+/// to be the bad one and the track restarts from the new position
+const MAX_CONSECUTIVE_JUMPS: usize = 25;
+
+/// Bytes pulled from disk per read while walking the record stream
+const READ_CHUNK_LEN: usize = 64 * 1024;
+
+/// Decoded record from the DAT stream
+struct DatRecord {
+    record_type: u16,
+    tick: u32,
+    payload: Vec<u8>,
+}
+
+/// Incremental reader over the DAT record stream. Only a small window of the
+/// file is buffered; it resynchronises on the 0x55 sync byte after any
+/// malformed record.
+struct DatRecordReader<R: Read> {
+    reader: R,
+    buf: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read> DatRecordReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::with_capacity(READ_CHUNK_LEN),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Make sure at least `n` unread bytes are buffered. Returns false at end of file.
+    fn fill(&mut self, n: usize) -> bool {
+        let mut chunk = [0u8; READ_CHUNK_LEN];
+        while self.buf.len() - self.pos < n && !self.eof {
+            if self.pos > 0 {
+                self.buf.drain(..self.pos);
+                self.pos = 0;
+            }
+            match self.reader.read(&mut chunk) {
+                Ok(0) => self.eof = true,
+                Ok(read) => self.buf.extend_from_slice(&chunk[..read]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    log::warn!("DAT read error, stopping at current position: {}", e);
+                    self.eof = true;
+                }
+            }
+        }
+        self.buf.len() - self.pos >= n
+    }
+}
+
+impl<R: Read> Iterator for DatRecordReader<R> {
+    type Item = DatRecord;
+
+    fn next(&mut self) -> Option<DatRecord> {
+        loop {
+            if !self.fill(RECORD_HEADER_LEN + RECORD_CRC_LEN) {
+                return None;
+            }
+            if self.buf[self.pos] != RECORD_SYNC {
+                self.pos += 1;
+                continue;
+            }
+
+            let len = self.buf[self.pos + 1] as usize;
+            if len < RECORD_HEADER_LEN + RECORD_CRC_LEN || !self.fill(len) {
+                self.pos += 1;
+                continue;
+            }
+
+            let r = &self.buf[self.pos..self.pos + len];
+            let crc = u16::from_le_bytes([r[len - 2], r[len - 1]]);
+            if record_crc(&r[..len - RECORD_CRC_LEN]) != crc {
+                // Not a record boundary (or a damaged record): resync past this byte
+                self.pos += 1;
+                continue;
+            }
+            let record = DatRecord {
+                record_type: u16::from_le_bytes([r[3], r[4]]),
+                tick: u32::from_le_bytes([r[6], r[7], r[8], r[9]]),
+                payload: r[RECORD_HEADER_LEN..len - RECORD_CRC_LEN].to_vec(),
+            };
+            self.pos += len;
+            return Some(record);
+        }
+    }
+}
+
+/// CRC16 of a record's bytes before the checksum
+fn record_crc(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(RECORD_CRC_SEED, |crc, &b| {
+        (0..8).fold(crc ^ b as u16, |crc, _| if crc & 1 != 0 { (crc >> 1) ^ 0x8408 } else { crc >> 1 })
+    })
+}
+
+/// DJI flight controller DAT parser
+pub struct DatParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> DatParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Check if a file is a DJI flight controller DAT log
+    pub fn is_dat_file(source: &LogSource) -> bool {
+        let path = source.path();
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !ext.eq_ignore_ascii_case("dat") {
+            return false;
+        }
+
+        let mut header = [0u8; HEADER_LEN];
+        match source.open().and_then(|mut f| f.read_exact(&mut header)) {
+            Ok(()) => &header[BUILD_MARKER_OFFSET..BUILD_MARKER_OFFSET + 5] == b"BUILD",
+            Err(_) => false,
+        }
+    }
+
+    /// Parse a DAT file
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing DJI DAT file: {:?}", file_path);
+
+        let mut reader = BufReader::new(source.open()?);
+        let mut header = [0u8; HEADER_LEN];
+        if reader.read_exact(&mut header).is_err() {
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        let mut points = Vec::new();
+        let mut motors = Vec::new();
+        let mut first_tick: Option<u32> = None;
+        let mut last_esc_ms: Option<i64> = None;
+        let mut last_mag: Option<f64> = None;
+        let mut record_count = 0usize;
+        let mut rejected = 0usize;
+        let mut jumps = 0usize;
+        let mut consecutive_jumps = 0usize;
+        let mut last_fix: Option<usize> = None;
+
+        for record in DatRecordReader::new(reader) {
+            record_count += 1;
+            if record.record_type == RECORD_MAG_RAW {
+                last_mag = Self::decode_mag_raw(&record.payload).or(last_mag);
+                continue;
+            }
+            if record.record_type != RECORD_OSD_GENERAL && record.record_type != RECORD_ESC_DATA {
+                continue;
+            }
+            // Signed: records arriving slightly out of order sit just before the base
+            let base = *first_tick.get_or_insert(record.tick);
+            let timestamp_ms = ((record.tick as i64 - base as i64) as f64 / TICKS_PER_SEC * 1000.0) as i64;
+
+            if record.record_type == RECORD_ESC_DATA {
+                if last_esc_ms.map_or(true, |last| timestamp_ms - last >= ESC_SAMPLE_INTERVAL_MS) {
+                    let samples = Self::decode_esc_data(&record.payload, timestamp_ms);
+                    if !samples.is_empty() {
+                        last_esc_ms = Some(timestamp_ms);
+                        motors.extend(samples);
+                    }
+                }
+                continue;
+            }
+            match Self::decode_osd_general(&record.payload, timestamp_ms) {
+                Some(mut point) => {
+                    if let Some(previous) = last_fix.map(|i| &points[i]) {
+                        if !Self::is_plausible_move(previous, &point) {
+                            consecutive_jumps += 1;
+                            if consecutive_jumps < MAX_CONSECUTIVE_JUMPS {
+                                jumps += 1;
+                                continue;
+                            }
+                            log::debug!("DAT track restarts at {} ms after {} implausible points", timestamp_ms, consecutive_jumps);
+                        }
+                    }
+                    consecutive_jumps = 0;
+                    if point.latitude.is_some() {
+                        last_fix = Some(points.len());
+                    }
+                    // Raw field strength for now, turned into a deviation below
+                    point.compass_interference = last_mag;
+                    points.push(point);
+                }
+                None => rejected += 1,
+            }
+        }
+        log::info!("DAT record stream: {} records", record_count);
+
+        if points.is_empty() {
+            if rejected > 0 {
+                log::warn!(
+                    "All {} DAT OSD records failed validation — payloads are likely scrambled (newer aircraft)",
+                    rejected
+                );
+                return Err(ParserError::Parse(
+                    "DAT payloads are scrambled; only unscrambled flight controller logs are supported".to_string(),
+                ));
+            }
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        // Records can arrive slightly out of order; start the timeline at the earliest
+        points.sort_by_key(|p| p.timestamp_ms);
+        points.dedup_by_key(|p| p.timestamp_ms);
+        motors.sort_by_key(|m: &MotorSample| (m.timestamp_ms, m.motor_index));
+        motors.dedup_by_key(|m| (m.timestamp_ms, m.motor_index));
+        let earliest = points[0].timestamp_ms.min(motors.first().map_or(i64::MAX, |m| m.timestamp_ms));
+        points.iter_mut().for_each(|p| p.timestamp_ms -= earliest);
+        motors.iter_mut().for_each(|m| m.timestamp_ms -= earliest);
+        Self::apply_compass_interference(&mut points);
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        // DAT files carry no wall clock; fall back to the file's modified time
+        let end_time = source.modified().map(chrono::DateTime::<chrono::Utc>::from);
+        let start_time = end_time
+            .map(|end| end - chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64));
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: None,
+            drone_serial: None,
+            aircraft_name: None,
+            battery_serial: None,
+            start_time,
+            end_time,
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(&points, stats.home_location),
+        };
+
+        log::info!(
+            "DAT parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, points={} ({} rejected, {} implausible jumps), motor samples={}",
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            points.len(),
+            rejected,
+            jumps,
+            motors.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "DAT".to_string());
+
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors, events: Vec::new() })
+    }
+
+    /// Decode an OSD general record into a telemetry point.
+    /// Returns `None` if the decoded values are physically implausible.
+    fn decode_osd_general(p: &[u8], timestamp_ms: i64) -> Option<TelemetryPoint> {
+        if p.len() < OSD_GENERAL_MIN_LEN {
+            return None;
+        }
+
+        let f64_at = |o: usize| f64::from_le_bytes(p[o..o + 8].try_into().unwrap());
+        let i16_at = |o: usize| i16::from_le_bytes([p[o], p[o + 1]]) as f64;
+
+        let longitude = f64_at(0).to_degrees();
+        let latitude = f64_at(8).to_degrees();
+        let height = i16_at(16) / 10.0;
+        let vx = i16_at(18) / 10.0;
+        let vy = i16_at(20) / 10.0;
+        let vz = i16_at(22) / 10.0;
+
+        if !latitude.is_finite() || !longitude.is_finite() || latitude.abs() > 90.0 || longitude.abs() > 180.0 {
+            return None;
+        }
+
+        let has_gps_lock = !(latitude.abs() < 1e-6 && longitude.abs() < 1e-6);
+
+        Some(TelemetryPoint {
+            timestamp_ms,
+            latitude: has_gps_lock.then_some(latitude),
+            longitude: has_gps_lock.then_some(longitude),
+            height: Some(height),
+            speed: has_gps_lock.then(|| (vx * vx + vy * vy).sqrt()),
+            velocity_x: Some(vx),
+            velocity_y: Some(vy),
+            velocity_z: Some(vz),
+            pitch: Some(i16_at(24) / 10.0),
+            roll: Some(i16_at(26) / 10.0),
+            yaw: Some(i16_at(28) / 10.0),
+            flight_mode: Some(format!("{}", p[30] & 0x7F)),
+            satellites: Some(p[36] as i32),
+            ..Default::default()
+        })
+    }
+
+    /// Whether `point` can follow `previous` in flight: its position may move
+    /// no faster than `MAX_PLAUSIBLE_SPEED_MS` (plus GPS noise) and its own
+    /// velocity must be in range too. Points without a fix always pass.
+    fn is_plausible_move(previous: &TelemetryPoint, point: &TelemetryPoint) -> bool {
+        let speed_ok = point.speed.map_or(true, |s| s <= MAX_PLAUSIBLE_SPEED_MS)
+            && point.velocity_z.map_or(true, |v| v.abs() <= MAX_PLAUSIBLE_SPEED_MS);
+        let (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) =
+            (previous.latitude, previous.longitude, point.latitude, point.longitude)
+        else {
+            return speed_ok;
+        };
+        let elapsed_secs = (point.timestamp_ms - previous.timestamp_ms).abs() as f64 / 1000.0;
+        let distance = haversine_distance(lat1, lon1, lat2, lon2);
+        speed_ok && distance <= JUMP_TOLERANCE_M + MAX_PLAUSIBLE_SPEED_MS * elapsed_secs
+    }
+
+    /// Magnitude of a raw magnetometer reading, `None` for an empty or
+    /// saturated sample
+    fn decode_mag_raw(p: &[u8]) -> Option<f64> {
+        if p.len() < MAG_RAW_MIN_LEN {
+            return None;
+        }
+        let i16_at = |o: usize| i16::from_le_bytes([p[o], p[o + 1]]) as f64;
+        let (x, y, z) = (i16_at(0), i16_at(2), i16_at(4));
+        let saturated = [x, y, z].iter().any(|v| v.abs() >= i16::MAX as f64);
+        let magnitude = (x * x + y * y + z * z).sqrt();
+        (magnitude > 0.0 && !saturated).then_some(magnitude)
+    }
+
+    /// Replace the raw field strengths stored in `compass_interference` with
+    /// their deviation from the flight's median strength in percent. The earth
+    /// field is constant over a flight, so any deviation is interference from
+    /// metal, power lines or the aircraft's own currents.
+    fn apply_compass_interference(points: &mut [TelemetryPoint]) {
+        let mut strengths: Vec<f64> = points.iter().filter_map(|p| p.compass_interference).collect();
+        if strengths.is_empty() {
+            return;
+        }
+        strengths.sort_by(|a, b| a.total_cmp(b));
+        let baseline = strengths[strengths.len() / 2];
+        for point in points.iter_mut() {
+            point.compass_interference = point
+                .compass_interference
+                .map(|m| ((m - baseline).abs() / baseline * 100.0 * 10.0).round() / 10.0);
+        }
+    }
+
+    /// Decode an ESC status record into one sample per motor. Entries that
+    /// are all zero (unpopulated motor slots) or implausible are skipped.
+    fn decode_esc_data(p: &[u8], timestamp_ms: i64) -> Vec<MotorSample> {
+        p.chunks_exact(ESC_ENTRY_LEN)
+            .take(ESC_MAX_MOTORS)
+            .enumerate()
+            .filter_map(|(index, e)| {
+                if e.iter().all(|&b| b == 0) {
+                    return None;
+                }
+                let i16_at = |o: usize| i16::from_le_bytes([e[o], e[o + 1]]) as f64;
+                let current = i16_at(1) / 100.0;
+                let rpm = i16_at(3);
+                let temperature = i16_at(7);
+
+                let plausible = (-5.0..=100.0).contains(&current)
+                    && (0.0..=30_000.0).contains(&rpm)
+                    && (-40.0..=150.0).contains(&temperature);
+                plausible.then_some(MotorSample {
+                    timestamp_ms,
+                    motor_index: index as i32,
+                    rpm: Some(rpm),
+                    current: Some(current),
+                    temperature: Some(temperature),
+                })
+            })
+            .collect()
+    }
+}
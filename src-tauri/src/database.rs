@@ -6,15 +6,17 @@
 //! - Optimized bulk inserts using Appender
 //! - Downsampled query retrieval for large datasets
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
-use duckdb::{params, Connection, OptionalExt, Result as DuckResult};
+use duckdb::{params, params_from_iter, Connection, OptionalExt, Result as DuckResult, ToSql};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use crate::models::{BatteryHealthPoint, BatteryUsage, DroneUsage, Flight, FlightDateCount, FlightMetadata, FlightTag, OverviewStats, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight};
+use crate::models::{AuditEntry, Battery, BatteryFlightSample, BatteryHealthPoint, BatteryUsage, ChecklistItem, ChecklistTemplate, ComplianceFlight, ComplianceSegment, CurrencyEntry, CurrencyWindow, DroneComparisonStats, DroneProfile, DroneStats, DroneUsage, Equipment, ExportTemplate, FirmwareHistoryEntry, Flight, FlightAnnotation, FlightAttachment, FlightChecklist, FlightDateCount, FlightDocument, FlightEnergy, FlightEvent, FlightFilter, FlightLocation, FlightMedia, FlightMetadata, FlightSafetyEvents, FlightSearchHit, FlightTag, FlightTotals, HomePoint, Incident, LibraryCheckReport, LibraryIssue, LibraryStats, MaintenanceRecord, MaintenanceStatus, MediaFile, Mission, MissionMetadata, MonthlyStats, MotorSample, MotorSeries, OverviewStats, PersonalRecord, PersonalRecords, RthEventCounts, SafetyEvent, Site, SiteFlight, SiteHomePoint, SmartList, StitchCandidate, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight, TrendBucket, TrendPoint, WarningSummary, Waypoint};
+use crate::telemetry_pack::{self, PackError, Series, SeriesKind};
 
 #[derive(Error, Debug)]
 pub enum DatabaseError {
@@ -26,11 +28,301 @@ pub enum DatabaseError {
 
     #[error("Flight not found: {0}")]
     FlightNotFound(i64),
+
+    #[error("Mission not found: {0}")]
+    MissionNotFound(i64),
+
+    #[error("Cannot merge flights: {0}")]
+    InvalidMerge(String),
+
+    #[error("Invalid maintenance record: {0}")]
+    InvalidMaintenance(String),
+
+    #[error("Invalid incident: {0}")]
+    InvalidIncident(String),
+
+    #[error("Invalid equipment: {0}")]
+    InvalidEquipment(String),
+
+    #[error("Invalid checklist: {0}")]
+    InvalidChecklist(String),
+
+    #[error("Invalid site: {0}")]
+    InvalidSite(String),
+
+    #[error("Packed telemetry error: {0}")]
+    Pack(#[from] PackError),
+
+    #[error("Flights must be at least one year old to be archived")]
+    InvalidArchiveAge,
+
+    #[error("Invalid smart list: {0}")]
+    InvalidSmartList(String),
+
+    #[error("Invalid export template: {0}")]
+    InvalidExportTemplate(String),
+
+    #[error("Document not found: {0}")]
+    DocumentNotFound(i64),
+}
+
+/// Kinds of maintenance work that can be logged
+pub const MAINTENANCE_KINDS: &[&str] = &["prop_swap", "firmware_update", "repair", "calibration", "service", "other"];
+
+/// Kinds of safety incident that can be reported
+pub const INCIDENT_KINDS: &[&str] = &["crash", "flyaway", "near_miss", "equipment_failure", "other"];
+
+/// Incident severities, least to most severe
+pub const INCIDENT_SEVERITIES: &[&str] = &["minor", "moderate", "serious", "critical"];
+
+/// Categories of payloads and accessories in the equipment inventory
+pub const EQUIPMENT_CATEGORIES: &[&str] = &["filter", "lens", "payload", "propellers", "controller", "other"];
+
+/// Telemetry rows appended per chunk during bulk insert
+const TELEMETRY_CHUNK_ROWS: usize = 50_000;
+
+/// Rows bound into one multi-row INSERT statement
+const INSERT_BATCH_ROWS: usize = 500;
+
+/// Max distance between takeoff points (degrees, ~100 m) for two logs of
+/// different formats to be considered the same flight
+const CROSS_FORMAT_HOME_TOLERANCE_DEG: f64 = 0.001;
+
+/// A flight counts as flying a mission when it passes this close to a waypoint
+const MISSION_WAYPOINT_RADIUS_M: f64 = 30.0;
+
+/// Fraction of a mission's waypoints a flight must reach to be linked to it
+const MISSION_MIN_COVERAGE: f64 = 0.8;
+
+/// Margin around the mission area within which a takeoff point is considered (~2 km)
+const MISSION_AREA_MARGIN_DEG: f64 = 0.02;
+
+/// Media captured this long before takeoff or after landing still belongs to the flight
+const MEDIA_TIME_SLACK_SECS: i64 = 120;
+
+/// Geotagged media further than this from a flight's home point is not matched to it
+const MEDIA_MAX_HOME_DISTANCE_M: f64 = 15_000.0;
+
+/// A motor or cell sample is carried forward onto chart points at most this long
+const ALIGNED_SAMPLE_MAX_AGE_MS: i64 = 1_000;
+
+/// Telemetry gap over which time-weighted sums stop counting (lost link, paused log)
+const TELEMETRY_MAX_GAP_MS: i64 = 5_000;
+
+/// Shortest flight distance for a per-km energy figure
+const ENERGY_MIN_DISTANCE_M: f64 = 100.0;
+
+/// Default max gap between two log files that may be stitched into one flight
+pub const DEFAULT_STITCH_MAX_GAP_SECS: f64 = 120.0;
+
+/// Search terms beyond this are ignored (each term adds a scoring column)
+const MAX_SEARCH_TERMS: usize = 8;
+
+/// Default number of ranked search results returned
+pub const DEFAULT_SEARCH_LIMIT: usize = 500;
+
+/// Default number of audit log entries returned
+pub const DEFAULT_AUDIT_LIMIT: usize = 500;
+
+/// Identifies a portable logbook archive in its manifest
+const ARCHIVE_FORMAT: &str = "dji-logbook-archive";
+
+/// Bumped when the archive layout changes incompatibly
+const ARCHIVE_FORMAT_VERSION: i64 = 1;
+
+/// Flight-scoped tables merged from an archive; their `flight_id` is remapped
+const ARCHIVE_FLIGHT_TABLES: &[&str] = &[
+    "telemetry",
+    "telemetry_packed",
+    "flight_tags",
+    "flight_attachments",
+    "home_points",
+    "flight_events",
+    "flight_media",
+    "motor_telemetry",
+    "battery_cells",
+    "flight_annotations",
+    "incidents",
+    "flight_equipment",
+    "flight_checklists",
+    "flight_documents",
+];
+
+/// Library-wide tables merged from an archive; local rows win on conflict
+const ARCHIVE_LIBRARY_TABLES: &[&str] = &[
+    "keychains",
+    "log_keychains",
+    "drones",
+    "batteries",
+    "maintenance_records",
+    "equipment",
+    "checklist_templates",
+    "smart_lists",
+    "export_templates",
+];
+
+/// Audit log actions that `reconcile_library` replays from the other copy
+const REPLAYED_AUDIT_ACTIONS: &[&str] = &[
+    "delete",
+    "restore",
+    "purge",
+    "rename",
+    "notes",
+    "favorite",
+    "unfavorite",
+    "exclude_stats",
+    "include_stats",
+    "tag_add",
+    "tag_remove",
+];
+
+/// Overlap tolerated between the end of one segment and the start of the next
+const STITCH_MAX_OVERLAP_SECS: f64 = 5.0;
+
+/// Battery level may read this many percent higher after a reconnect
+const STITCH_BATTERY_TOLERANCE: i32 = 3;
+
+/// Max battery drop across the gap for the segments to count as continuous
+const STITCH_MAX_BATTERY_DROP: i32 = 10;
+
+/// One step of the schema upgrade path. Pending steps run in version order
+/// when the database is opened, each in its own transaction, and are recorded
+/// in `schema_migrations`. Databases created before versioning existed replay
+/// every step, so steps must be idempotent (check before altering).
+struct Migration {
+    version: i64,
+    name: &'static str,
+    apply: fn(&Connection) -> Result<(), DatabaseError>,
+}
+
+/// Ordered schema migrations. Append new steps with the next version number;
+/// never renumber or edit a released step.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "flights: display name, aircraft, battery, mission and partial columns",
+        apply: Database::migrate_flights_table,
+    },
+    Migration {
+        version: 2,
+        name: "telemetry: height, RC, camera, sensor health and wind columns",
+        apply: Database::migrate_telemetry_table,
+    },
+    Migration {
+        version: 3,
+        name: "flight_tags: tag_type column",
+        apply: Database::migrate_flight_tags_table,
+    },
+    Migration {
+        version: 4,
+        name: "flight_events: detail and severity columns",
+        apply: Database::migrate_flight_events_table,
+    },
+    Migration {
+        version: 5,
+        name: "flights: deleted_at column for the trash",
+        apply: Database::add_flights_deleted_at,
+    },
+    Migration {
+        version: 6,
+        name: "batteries: register the batteries of existing flights",
+        apply: Database::register_batteries_from_flights,
+    },
+    Migration {
+        version: 7,
+        name: "flights: reverse-geocoded location columns",
+        apply: Database::add_flights_location,
+    },
+    Migration {
+        version: 8,
+        name: "flights: favorite flag",
+        apply: Database::add_flights_favorite,
+    },
+    Migration {
+        version: 9,
+        name: "flights: firmware version",
+        apply: Database::add_flights_firmware_version,
+    },
+    Migration {
+        version: 10,
+        name: "flights: excluded from statistics flag",
+        apply: Database::add_flights_excluded_from_stats,
+    },
+    Migration {
+        version: 11,
+        name: "drones: registration",
+        apply: Database::add_drones_registration,
+    },
+    Migration {
+        version: 12,
+        name: "flights: max distance from home",
+        apply: Database::add_flights_max_distance_from_home,
+    },
+    Migration {
+        version: 13,
+        name: "flight_stats_cache: average wind speed",
+        apply: Database::add_stats_cache_wind_speed,
+    },
+    Migration {
+        version: 14,
+        name: "flights: day and night classification",
+        apply: Database::add_flights_daylight,
+    },
+];
+
+/// Darkest light and night seconds of a flight, when its home point and
+/// start are known
+fn daylight_of(
+    lat: Option<f64>,
+    lon: Option<f64>,
+    start_time: Option<chrono::DateTime<chrono::Utc>>,
+    duration_secs: Option<f64>,
+) -> Option<(crate::sun::Daylight, f64)> {
+    match (lat, lon, start_time) {
+        (Some(lat), Some(lon), Some(start)) if !(lat.abs() < 0.000001 && lon.abs() < 0.000001) => {
+            Some(crate::sun::classify_flight(lat, lon, start, duration_secs.unwrap_or(0.0)))
+        }
+        _ => None,
+    }
+}
+
+/// A file path quoted for use inside a SQL string literal
+fn sql_path(path: &std::path::Path) -> String {
+    path.to_string_lossy().replace('\'', "''")
+}
+
+/// For each target timestamp, the index of the latest sample at or before
+/// it, unless that sample is older than `ALIGNED_SAMPLE_MAX_AGE_MS`. Both
+/// slices must be sorted ascending.
+fn align_samples(sample_times: &[i64], targets: &[i64]) -> Vec<Option<usize>> {
+    let mut next = 0;
+    targets
+        .iter()
+        .map(|&t| {
+            while next < sample_times.len() && sample_times[next] <= t {
+                next += 1;
+            }
+            next.checked_sub(1)
+                .filter(|&i| t - sample_times[i] <= ALIGNED_SAMPLE_MAX_AGE_MS)
+        })
+        .collect()
+}
+
+/// One parsed flight written by `insert_flights_batch`
+pub struct FlightBatchRow<'a> {
+    pub metadata: &'a FlightMetadata,
+    pub points: &'a [TelemetryPoint],
+    pub tags: &'a [String],
+    pub home_points: &'a [HomePoint],
+    pub motors: &'a [MotorSample],
+    pub events: &'a [FlightEvent],
 }
 
 /// Thread-safe database manager
 pub struct Database {
     conn: Mutex<Connection>,
+    /// Database file of the open library (see `library`)
+    db_path: Mutex<PathBuf>,
     pub data_dir: PathBuf,
 }
 
@@ -40,15 +332,18 @@ impl Database {
     /// Creates the following directory structure:
     /// ```text
     /// {app_data_dir}/
-    /// ├── flights.db       # DuckDB database file
+    /// ├── flights.db       # DuckDB database file of the default library
+    /// ├── libraries/       # Further libraries created by name
     /// └── keychains/       # Cached decryption keys
     /// ```
+    ///
+    /// The library that was open when the app last closed is reopened.
     pub fn new(app_data_dir: PathBuf) -> Result<Self, DatabaseError> {
         // Ensure directory structure exists
         fs::create_dir_all(&app_data_dir)?;
         fs::create_dir_all(app_data_dir.join("keychains"))?;
 
-        let db_path = app_data_dir.join("flights.db");
+        let db_path = crate::library::active_library_path(&app_data_dir);
 
         log::info!("Initializing DuckDB at: {:?}", db_path);
 
@@ -65,16 +360,69 @@ impl Database {
 
         let db = Self {
             conn: Mutex::new(conn),
+            db_path: Mutex::new(db_path),
             data_dir: app_data_dir,
         };
+        db.prepare_library()?;
+
+        Ok(db)
+    }
 
+    /// Bring a freshly opened library up to date
+    fn prepare_library(&self) -> Result<(), DatabaseError> {
         // Initialize schema
-        db.init_schema()?;
+        self.init_schema()?;
 
         // Run one-time startup deduplication for existing data
-        db.run_startup_deduplication();
+        self.run_startup_deduplication();
 
-        Ok(db)
+        // Derive capture events for flights imported before they were recorded
+        self.run_startup_event_backfill();
+
+        // Fill in the distance from home of flights imported before it was stored
+        self.run_startup_distance_backfill();
+
+        // Total distances of older versions, so they match the distance series
+        self.run_startup_total_distance_backfill();
+
+        // Classify day and night for flights imported before it was stored
+        if let Err(e) = self.backfill_daylight(false) {
+            log::error!("Day and night backfill failed: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Database file of the open library
+    pub fn db_path(&self) -> PathBuf {
+        self.db_path.lock().unwrap().clone()
+    }
+
+    /// Close the open library and open the database file at `db_path`
+    /// instead, creating an empty library if it doesn't exist yet.
+    ///
+    /// The connection is swapped in place, so everything holding this
+    /// `Database` follows the switch. Settings and keychains in the app data
+    /// directory are shared by all libraries.
+    pub fn open_library(&self, db_path: &std::path::Path) -> Result<(), DatabaseError> {
+        let start = std::time::Instant::now();
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        {
+            let mut conn = self.conn.lock().unwrap();
+            if let Err(e) = conn.execute_batch("CHECKPOINT;") {
+                log::warn!("WAL checkpoint before library switch failed (non-fatal): {}", e);
+            }
+            let opened = Self::open_with_recovery(&db_path.to_path_buf())?;
+            Self::configure_connection(&opened)?;
+            *conn = opened;
+            *self.db_path.lock().unwrap() = db_path.to_path_buf();
+        }
+        self.prepare_library()?;
+
+        log::info!("Opened library {:?} in {:.1}ms", db_path, start.elapsed().as_secs_f64() * 1000.0);
+        Ok(())
     }
 
     fn open_with_recovery(db_path: &PathBuf) -> Result<Connection, DatabaseError> {
@@ -133,6 +481,9 @@ impl Database {
             SET memory_limit = '2GB';
             SET threads = 4;
             SET enable_progress_bar = false;
+            -- DuckDB always journals to the WAL; the default 16MB threshold
+            -- checkpoints several times during one large import
+            SET checkpoint_threshold = '256MB';
             "#,
         )?;
         Ok(())
@@ -167,13 +518,29 @@ impl Database {
                 home_lon        DOUBLE,
                 point_count     INTEGER,                 -- Number of telemetry points
                 imported_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                notes           VARCHAR
+                notes           VARCHAR,
+                mission_name    VARCHAR,                 -- Planned mission (GS Pro / SDK apps)
+                partial         BOOLEAN DEFAULT FALSE,   -- Recovered from a truncated log
+                deleted_at      TIMESTAMP WITH TIME ZONE, -- Set while the flight is in the trash
+                location_city   VARCHAR,                 -- Reverse-geocoded from the home point
+                location_region VARCHAR,
+                location_country VARCHAR,
+                favorite        BOOLEAN DEFAULT FALSE,   -- Starred by the user
+                firmware_version VARCHAR,                -- Aircraft firmware from the log
+                excluded_from_stats BOOLEAN DEFAULT FALSE, -- Test flight left out of statistics
+                max_distance_from_home DOUBLE,           -- Farthest 3D distance from home (m)
+                daylight        VARCHAR,                 -- Darkest light flown in: 'day', 'civil_twilight' or 'night'
+                night_secs      DOUBLE                   -- Flight time after civil twilight (s)
             );
 
             -- Index for sorting by flight date
             CREATE INDEX IF NOT EXISTS idx_flights_start_time 
                 ON flights(start_time DESC);
 
+            -- Index for looking up the flights of a battery
+            CREATE INDEX IF NOT EXISTS idx_flights_battery
+                ON flights(battery_serial);
+
             -- ============================================================
             -- TELEMETRY TABLE: Time-series data for each flight
             -- Optimized for range queries on timestamp
@@ -231,6 +598,15 @@ impl Database {
                 -- Camera state
                 is_photo        BOOLEAN,
                 is_video        BOOLEAN,
+
+                -- Sensor health
+                compass_error   BOOLEAN,
+                compass_interference DOUBLE,             -- Field strength deviation from baseline (%)
+                imu_status      VARCHAR,                 -- NULL while healthy
+
+                -- Wind (only for logs that record it)
+                wind_speed      DOUBLE,                  -- m/s
+                wind_direction  DOUBLE,                  -- Degrees the wind blows from
                 
                 -- Composite primary key for efficient range queries
                 PRIMARY KEY (flight_id, timestamp_ms)
@@ -249,6 +625,243 @@ impl Database {
                 fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
             );
 
+            -- ============================================================
+            -- LOG_KEYCHAINS TABLE: Decryption keychains fetched per log file
+            -- Lets V13+ logs be re-parsed without calling the DJI API again
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS log_keychains (
+                file_hash       VARCHAR PRIMARY KEY,     -- SHA256 of the log file
+                keychains       VARCHAR NOT NULL,        -- JSON-encoded keychain list
+                fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- BATTERIES TABLE: Every flight battery seen in the logs
+            -- Flights point at it through flights.battery_serial
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS batteries (
+                serial          VARCHAR PRIMARY KEY,     -- Battery serial as parsed from the log
+                drone_model     VARCHAR,                 -- Aircraft it was first flown with
+                first_seen      TIMESTAMP WITH TIME ZONE, -- Start of its earliest flight
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- DRONES TABLE: User profile per aircraft, keyed by serial
+            -- Nickname tells apart several drones of the same model
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS drones (
+                serial          VARCHAR PRIMARY KEY,     -- Aircraft serial as stored on flights
+                nickname        VARCHAR,
+                purchase_date   DATE,
+                notes           VARCHAR,
+                registration    VARCHAR,                 -- Registration or operator marking shown on the logbook
+                updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- HOME_POINTS TABLE: Home point history per flight
+            -- Takeoff home point plus every mid-flight update
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS home_points (
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,         -- Milliseconds since flight start
+                latitude        DOUBLE NOT NULL,
+                longitude       DOUBLE NOT NULL,
+                altitude        DOUBLE,
+                PRIMARY KEY (flight_id, timestamp_ms)
+            );
+
+            -- ============================================================
+            -- FLIGHT_EVENTS TABLE: Camera capture and settings events along the track
+            -- 'photo' = shutter release, 'video' = one recording segment,
+            -- 'camera_settings' / 'gimbal_mode' = parsed change with new value in detail,
+            -- 'tip' / 'warning' = app message in detail, with severity
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_events (
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,         -- Milliseconds since flight start
+                event_type      VARCHAR NOT NULL,
+                latitude        DOUBLE,
+                longitude       DOUBLE,
+                altitude        DOUBLE,
+                duration_ms     BIGINT,                  -- Recording length (video only)
+                detail          VARCHAR,                 -- New value of a settings change / message
+                severity        VARCHAR,                 -- 'info', 'warning', 'critical' (messages only)
+                PRIMARY KEY (flight_id, timestamp_ms, event_type)
+            );
+
+            -- ============================================================
+            -- TELEMETRY_PACKED TABLE: Telemetry of flights in packed storage
+            -- One compressed blob per telemetry column (see telemetry_pack.rs);
+            -- a packed flight has no rows in the telemetry table
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS telemetry_packed (
+                flight_id           BIGINT NOT NULL,
+                column_name         VARCHAR NOT NULL,
+                column_type         VARCHAR NOT NULL,    -- Type of the telemetry column
+                point_count         INTEGER NOT NULL,
+                first_timestamp_ms  BIGINT,              -- Same for every column of a flight
+                data                BLOB NOT NULL,
+                PRIMARY KEY (flight_id, column_name)
+            );
+
+            -- ============================================================
+            -- ARCHIVED_TELEMETRY TABLE: Flights whose telemetry was moved to a
+            -- Parquet sidecar file in the library's telemetry archive directory;
+            -- an archived flight has no telemetry rows or packed series
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS archived_telemetry (
+                flight_id           BIGINT PRIMARY KEY,
+                file_name           VARCHAR NOT NULL,    -- Relative to the archive directory
+                point_count         BIGINT NOT NULL,
+                first_timestamp_ms  BIGINT,
+                archived_at         TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- FLIGHT_ANNOTATIONS TABLE: User notes pinned to a moment of a flight
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_annotations (
+                id              BIGINT PRIMARY KEY,
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,         -- Same clock as telemetry.timestamp_ms
+                text            VARCHAR NOT NULL,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_annotations_flight
+                ON flight_annotations(flight_id);
+
+            -- ============================================================
+            -- INCIDENTS TABLE: Safety events (crash, flyaway, ...) during a flight
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS incidents (
+                id              BIGINT PRIMARY KEY,
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,         -- Same clock as telemetry.timestamp_ms
+                kind            VARCHAR NOT NULL,        -- See INCIDENT_KINDS
+                severity        VARCHAR NOT NULL,        -- See INCIDENT_SEVERITIES
+                description     VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_incidents_flight
+                ON incidents(flight_id);
+
+            -- ============================================================
+            -- FLIGHT_MEDIA TABLE: Photos/videos on disk matched to flights
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_media (
+                file_path       VARCHAR PRIMARY KEY,
+                flight_id       BIGINT NOT NULL,
+                file_name       VARCHAR NOT NULL,
+                media_type      VARCHAR NOT NULL,        -- 'photo' or 'video'
+                captured_at     TIMESTAMP WITH TIME ZONE,
+                latitude        DOUBLE,
+                longitude       DOUBLE,
+                camera_serial   VARCHAR,
+                linked_at       TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_media_flight ON flight_media(flight_id);
+
+            -- ============================================================
+            -- FLIGHT_DOCUMENTS TABLE: Files attached to flights by the user
+            -- The content lives in the documents directory, named by hash
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_documents (
+                id              BIGINT PRIMARY KEY,
+                flight_id       BIGINT NOT NULL,
+                file_name       VARCHAR NOT NULL,        -- Name the file was attached under
+                sha256          VARCHAR NOT NULL,        -- Hex digest, also the stored file's name
+                size_bytes      BIGINT NOT NULL,
+                added_at        TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_documents_flight ON flight_documents(flight_id);
+
+            -- ============================================================
+            -- BATTERY_CELLS TABLE: Per-cell voltages alongside telemetry
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS battery_cells (
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,
+                cell_index      INTEGER NOT NULL,        -- 0 = cell 1
+                voltage         DOUBLE NOT NULL,
+                PRIMARY KEY (flight_id, timestamp_ms, cell_index)
+            );
+
+            -- ============================================================
+            -- MOTOR_TELEMETRY TABLE: Per-motor ESC readings (DAT logs)
+            -- Kept apart from telemetry: one row per motor per sample
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS motor_telemetry (
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,
+                motor_index     INTEGER NOT NULL,
+                rpm             DOUBLE,
+                current         DOUBLE,                  -- ESC current in amps
+                temperature     DOUBLE,                  -- ESC temperature in °C
+                PRIMARY KEY (flight_id, timestamp_ms, motor_index)
+            );
+
+            -- ============================================================
+            -- FLIGHT_ATTACHMENTS TABLE: Extra source files linked to a flight
+            -- e.g. the aircraft DAT log of a flight imported from the app TXT
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_attachments (
+                file_hash       VARCHAR PRIMARY KEY,     -- SHA256 of the attached file
+                flight_id       BIGINT NOT NULL,
+                file_name       VARCHAR NOT NULL,
+                source_format   VARCHAR NOT NULL,        -- e.g. 'dat'
+                point_count     INTEGER,
+                attached_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_attachments_flight
+                ON flight_attachments(flight_id);
+
+            -- ============================================================
+            -- IMPORT_BLACKLIST TABLE: Files of deleted flights that sync and
+            -- the folder watcher must not import again
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS import_blacklist (
+                file_hash       VARCHAR PRIMARY KEY,     -- SHA256 of the source file
+                added_at        TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Watched-folder files already handled, so unchanged files are
+            -- not hashed and parsed again on every launch
+            CREATE TABLE IF NOT EXISTS watched_files (
+                path            VARCHAR PRIMARY KEY,
+                size            BIGINT NOT NULL,
+                modified_ms     BIGINT                   -- NULL when the platform has no mtime
+            );
+
+            -- ============================================================
+            -- MISSIONS TABLE: Planned waypoint missions (DJI Fly .kmz)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS missions (
+                id              BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                file_name       VARCHAR NOT NULL,
+                file_hash       VARCHAR UNIQUE,          -- SHA256 to prevent duplicates
+                drone_model     VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE,
+                imported_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                waypoint_count  INTEGER,
+                waypoints       VARCHAR NOT NULL         -- JSON-encoded waypoint list
+            );
+
+            -- Flights that executed a mission
+            CREATE TABLE IF NOT EXISTS flight_missions (
+                flight_id       BIGINT NOT NULL,
+                mission_id      BIGINT NOT NULL,
+                coverage        DOUBLE,                  -- Fraction of waypoints reached
+                PRIMARY KEY (flight_id, mission_id)
+            );
+
             -- ============================================================
             -- FLIGHT_TAGS TABLE: Tags associated with each flight
             -- Separate table for backward compatibility with old backups
@@ -265,6 +878,183 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_flight_tags_tag 
                 ON flight_tags(tag);
 
+            -- ============================================================
+            -- MAINTENANCE_RECORDS TABLE: Work done on a drone or battery
+            -- Flights after performed_at count towards the next service
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS maintenance_records (
+                id              BIGINT PRIMARY KEY,
+                target_type     VARCHAR NOT NULL,        -- 'drone' or 'battery'
+                serial          VARCHAR NOT NULL,        -- flights.drone_serial / flights.battery_serial
+                kind            VARCHAR NOT NULL,        -- See MAINTENANCE_KINDS
+                performed_at    TIMESTAMP WITH TIME ZONE NOT NULL,
+                description     VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_maintenance_target
+                ON maintenance_records(target_type, serial);
+
+            -- ============================================================
+            -- EQUIPMENT TABLE: Payloads and accessories (filters, props, ...)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS equipment (
+                id              BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                category        VARCHAR NOT NULL,        -- See EQUIPMENT_CATEGORIES
+                serial          VARCHAR,
+                notes           VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- FLIGHT_EQUIPMENT TABLE: Equipment used on each flight
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_equipment (
+                flight_id       BIGINT NOT NULL,
+                equipment_id    BIGINT NOT NULL,
+                PRIMARY KEY (flight_id, equipment_id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_equipment_equipment
+                ON flight_equipment(equipment_id);
+
+            -- ============================================================
+            -- SITES TABLE: Places flown from, found by clustering home points.
+            -- Rebuilt on reclustering; ids and custom names carry over to
+            -- the new site nearest to the old one.
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS sites (
+                id              BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                custom_name     BOOLEAN DEFAULT FALSE,   -- Renamed by the user
+                center_lat      DOUBLE NOT NULL,
+                center_lon      DOUBLE NOT NULL,
+                radius_m        DOUBLE NOT NULL          -- Farthest home point from the center
+            );
+
+            -- ============================================================
+            -- FLIGHT_SITES TABLE: Site of each flight with a home point
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_sites (
+                flight_id       BIGINT PRIMARY KEY,
+                site_id         BIGINT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_sites_site ON flight_sites(site_id);
+
+            -- ============================================================
+            -- FLIGHT_STATS_CACHE TABLE: Per-flight figures the overview
+            -- would otherwise scan telemetry for. A row is dropped when
+            -- the flight's telemetry changes and filled in again on the
+            -- next overview.
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_stats_cache (
+                flight_id           BIGINT PRIMARY KEY,
+                max_distance_2d     DOUBLE,              -- Farthest horizontal distance from home (m)
+                battery_delta_percent DOUBLE,            -- Battery percent used, NULL without readings
+                avg_wind_speed      DOUBLE,              -- m/s, NULL without wind readings
+                computed_at         TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- AUDIT_LOG TABLE: Imports, merges, deletions and edits.
+            -- Kept when flights are purged, so no cleanup by flight_id.
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id              BIGINT PRIMARY KEY,
+                logged_at       TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                action          VARCHAR NOT NULL,
+                flight_id       BIGINT,                  -- NULL for library-wide actions
+                source_file     VARCHAR,
+                file_hash       VARCHAR,
+                app_version     VARCHAR,
+                details         VARCHAR
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_audit_log_flight ON audit_log(flight_id);
+
+            -- ============================================================
+            -- SMART_LISTS TABLE: Saved flight filters
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS smart_lists (
+                id              BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                filter          VARCHAR NOT NULL,        -- JSON-encoded FlightFilter
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- EXPORT_TEMPLATES TABLE: Saved export format, columns and units
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS export_templates (
+                id              BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                format          VARCHAR NOT NULL,        -- 'gpx', 'kml', 'csv' or 'json'
+                columns         VARCHAR NOT NULL,        -- JSON-encoded telemetry column names, empty for all
+                units           VARCHAR NOT NULL,        -- JSON-encoded ExportUnits
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- CHECKLIST_TEMPLATES TABLE: Reusable pre-flight checklists
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS checklist_templates (
+                id              BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                items           VARCHAR NOT NULL,        -- JSON-encoded list of item texts
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- FLIGHT_CHECKLISTS TABLE: A checklist as completed for a flight
+            -- Items are copied from the template so later edits keep history intact
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_checklists (
+                id              BIGINT PRIMARY KEY,
+                flight_id       BIGINT NOT NULL,
+                template_id     BIGINT,                  -- NULL once the template is deleted
+                name            VARCHAR NOT NULL,
+                items           VARCHAR NOT NULL,        -- JSON-encoded [{text, checked}]
+                completed_by    VARCHAR,
+                notes           VARCHAR,
+                completed_at    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_flight_checklists_flight
+                ON flight_checklists(flight_id);
+
+            -- ============================================================
+            -- GEOCODE_CACHE TABLE: Reverse geocoding results per ~1 km cell
+            -- Rows with no names mark places the provider could not name
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS geocode_cache (
+                provider        VARCHAR NOT NULL,        -- 'offline' or 'nominatim'
+                lat_key         DOUBLE NOT NULL,         -- Latitude rounded to the cache grid
+                lon_key         DOUBLE NOT NULL,
+                city            VARCHAR,
+                region          VARCHAR,
+                country         VARCHAR,
+                fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (provider, lat_key, lon_key)
+            );
+
+            -- ============================================================
+            -- ELEVATION_CACHE TABLE: Ground elevation per ~50 m cell from
+            -- online terrain providers
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS elevation_cache (
+                provider        VARCHAR NOT NULL,        -- 'open-meteo'
+                lat_key         DOUBLE NOT NULL,         -- Latitude rounded to the cache grid
+                lon_key         DOUBLE NOT NULL,
+                elevation_m     DOUBLE NOT NULL,         -- Above sea level
+                fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (provider, lat_key, lon_key)
+            );
+
             -- ============================================================
             -- SETTINGS TABLE: Key-value store for app settings/flags
             -- ============================================================
@@ -276,10 +1066,8 @@ impl Database {
             "#,
         )?;
 
-        // Run selective migrations only for missing columns
-        Self::migrate_flights_table(&conn)?;
-        Self::migrate_telemetry_table(&conn)?;
-        Self::migrate_flight_tags_table(&conn)?;
+        // Bring tables of older databases up to the current schema
+        Self::run_migrations(&conn)?;
 
         Self::ensure_telemetry_column_order(&conn)?;
 
@@ -287,6 +1075,64 @@ impl Database {
         Ok(())
     }
 
+    /// Apply every migration newer than the recorded schema version.
+    /// A failing step is rolled back and aborts startup, leaving the
+    /// database at the last good version.
+    fn run_migrations(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version         BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                applied_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+        )?;
+
+        let current: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )?;
+        let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+
+        if current > latest {
+            log::warn!(
+                "Database schema version {} is newer than this app supports ({}); skipping migrations",
+                current,
+                latest
+            );
+            return Ok(());
+        }
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            log::info!("Applying schema migration {}: {}", migration.version, migration.name);
+            conn.execute_batch("BEGIN TRANSACTION")?;
+            let result = (migration.apply)(conn).and_then(|_| {
+                conn.execute(
+                    "INSERT INTO schema_migrations (version, name) VALUES (?, ?)",
+                    params![migration.version, migration.name],
+                )?;
+                Ok(())
+            });
+            match result {
+                Ok(()) => conn.execute_batch("COMMIT")?,
+                Err(e) => {
+                    if let Err(rollback_err) = conn.execute_batch("ROLLBACK") {
+                        log::error!("Failed to roll back migration {}: {}", migration.version, rollback_err);
+                    }
+                    log::error!("Schema migration {} failed: {}", migration.version, e);
+                    return Err(e);
+                }
+            }
+        }
+
+        if current < latest {
+            log::info!("Database schema upgraded from version {} to {}", current, latest);
+        }
+        Ok(())
+    }
+
     /// Get existing column names for a table (single query)
     fn get_table_columns(conn: &Connection, table_name: &str) -> Result<HashSet<String>, DatabaseError> {
         let mut stmt = conn.prepare(&format!("PRAGMA table_info('{}')", table_name))?;
@@ -304,6 +1150,8 @@ impl Database {
             ("display_name", "ALTER TABLE flights ADD COLUMN display_name VARCHAR"),
             ("aircraft_name", "ALTER TABLE flights ADD COLUMN aircraft_name VARCHAR"),
             ("battery_serial", "ALTER TABLE flights ADD COLUMN battery_serial VARCHAR"),
+            ("mission_name", "ALTER TABLE flights ADD COLUMN mission_name VARCHAR"),
+            ("partial", "ALTER TABLE flights ADD COLUMN partial BOOLEAN DEFAULT FALSE"),
         ];
 
         for (col_name, sql) in migrations {
@@ -315,21 +1163,137 @@ impl Database {
         Ok(())
     }
 
-    /// Migrate telemetry table - only add missing columns
-    fn migrate_telemetry_table(conn: &Connection) -> Result<(), DatabaseError> {
-        let columns = Self::get_table_columns(conn, "telemetry")?;
-        
-        let migrations: &[(&str, &str)] = &[
-            ("height", "ALTER TABLE telemetry ADD COLUMN height DOUBLE"),
-            ("vps_height", "ALTER TABLE telemetry ADD COLUMN vps_height DOUBLE"),
-            ("rc_uplink", "ALTER TABLE telemetry ADD COLUMN rc_uplink INTEGER"),
-            ("rc_downlink", "ALTER TABLE telemetry ADD COLUMN rc_downlink INTEGER"),
+    /// Add the soft-delete timestamp to the flights table
+    fn add_flights_deleted_at(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "flights")?.contains("deleted_at") {
+            log::info!("Migrating flights table: adding deleted_at column");
+            conn.execute_batch("ALTER TABLE flights ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE")?;
+        }
+        Ok(())
+    }
+
+    /// Add the reverse-geocoded location columns to the flights table
+    fn add_flights_location(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "flights")?;
+        for col_name in ["location_city", "location_region", "location_country"] {
+            if !columns.contains(col_name) {
+                log::info!("Migrating flights table: adding {} column", col_name);
+                conn.execute_batch(&format!("ALTER TABLE flights ADD COLUMN {} VARCHAR", col_name))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the favorite flag to the flights table
+    fn add_flights_favorite(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "flights")?.contains("favorite") {
+            log::info!("Migrating flights table: adding favorite column");
+            conn.execute_batch("ALTER TABLE flights ADD COLUMN favorite BOOLEAN DEFAULT FALSE")?;
+        }
+        Ok(())
+    }
+
+    /// Add the aircraft firmware version to the flights table
+    fn add_flights_firmware_version(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "flights")?.contains("firmware_version") {
+            log::info!("Migrating flights table: adding firmware_version column");
+            conn.execute_batch("ALTER TABLE flights ADD COLUMN firmware_version VARCHAR")?;
+        }
+        Ok(())
+    }
+
+    /// Add the flag that leaves a flight out of statistics
+    fn add_flights_excluded_from_stats(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "flights")?.contains("excluded_from_stats") {
+            log::info!("Migrating flights table: adding excluded_from_stats column");
+            conn.execute_batch("ALTER TABLE flights ADD COLUMN excluded_from_stats BOOLEAN DEFAULT FALSE")?;
+        }
+        Ok(())
+    }
+
+    fn add_drones_registration(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "drones")?.contains("registration") {
+            log::info!("Migrating drones table: adding registration column");
+            conn.execute_batch("ALTER TABLE drones ADD COLUMN registration VARCHAR")?;
+        }
+        Ok(())
+    }
+
+    /// Add the farthest distance from home to the flights table. Existing
+    /// flights get it from the startup backfill.
+    fn add_flights_max_distance_from_home(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "flights")?.contains("max_distance_from_home") {
+            log::info!("Migrating flights table: adding max_distance_from_home column");
+            conn.execute_batch("ALTER TABLE flights ADD COLUMN max_distance_from_home DOUBLE")?;
+        }
+        Ok(())
+    }
+
+    fn add_flights_daylight(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "flights")?;
+        for (column, sql) in [
+            ("daylight", "ALTER TABLE flights ADD COLUMN daylight VARCHAR"),
+            ("night_secs", "ALTER TABLE flights ADD COLUMN night_secs DOUBLE"),
+        ] {
+            if !columns.contains(column) {
+                log::info!("Migrating flights table: adding {} column", column);
+                conn.execute_batch(sql)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cached rows from before the column existed are dropped so the next
+    /// refresh computes them with the wind speed
+    fn add_stats_cache_wind_speed(conn: &Connection) -> Result<(), DatabaseError> {
+        if !Self::get_table_columns(conn, "flight_stats_cache")?.contains("avg_wind_speed") {
+            log::info!("Migrating flight_stats_cache table: adding avg_wind_speed column");
+            conn.execute_batch(
+                "ALTER TABLE flight_stats_cache ADD COLUMN avg_wind_speed DOUBLE; DELETE FROM flight_stats_cache;",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Add a batteries row for every battery serial found on a flight.
+    /// Batteries that are already registered keep their row.
+    fn register_batteries_from_flights(conn: &Connection) -> Result<(), DatabaseError> {
+        let added = conn.execute(
+            r#"
+            INSERT OR IGNORE INTO batteries (serial, drone_model, first_seen)
+            SELECT battery_serial, arg_min(drone_model, start_time), MIN(start_time)
+            FROM flights
+            WHERE battery_serial IS NOT NULL AND battery_serial != ''
+            GROUP BY battery_serial
+            "#,
+            [],
+        )?;
+        if added > 0 {
+            log::info!("Registered {} batteries from existing flights", added);
+        }
+        Ok(())
+    }
+
+    /// Migrate telemetry table - only add missing columns
+    fn migrate_telemetry_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "telemetry")?;
+        
+        let migrations: &[(&str, &str)] = &[
+            ("height", "ALTER TABLE telemetry ADD COLUMN height DOUBLE"),
+            ("vps_height", "ALTER TABLE telemetry ADD COLUMN vps_height DOUBLE"),
+            ("rc_uplink", "ALTER TABLE telemetry ADD COLUMN rc_uplink INTEGER"),
+            ("rc_downlink", "ALTER TABLE telemetry ADD COLUMN rc_downlink INTEGER"),
             ("rc_aileron", "ALTER TABLE telemetry ADD COLUMN rc_aileron DOUBLE"),
             ("rc_elevator", "ALTER TABLE telemetry ADD COLUMN rc_elevator DOUBLE"),
             ("rc_throttle", "ALTER TABLE telemetry ADD COLUMN rc_throttle DOUBLE"),
             ("rc_rudder", "ALTER TABLE telemetry ADD COLUMN rc_rudder DOUBLE"),
             ("is_photo", "ALTER TABLE telemetry ADD COLUMN is_photo BOOLEAN"),
             ("is_video", "ALTER TABLE telemetry ADD COLUMN is_video BOOLEAN"),
+            ("compass_error", "ALTER TABLE telemetry ADD COLUMN compass_error BOOLEAN"),
+            ("compass_interference", "ALTER TABLE telemetry ADD COLUMN compass_interference DOUBLE"),
+            ("imu_status", "ALTER TABLE telemetry ADD COLUMN imu_status VARCHAR"),
+            ("wind_speed", "ALTER TABLE telemetry ADD COLUMN wind_speed DOUBLE"),
+            ("wind_direction", "ALTER TABLE telemetry ADD COLUMN wind_direction DOUBLE"),
         ];
 
         for (col_name, sql) in migrations {
@@ -341,6 +1305,24 @@ impl Database {
         Ok(())
     }
 
+    /// Migrate flight_events table - only add missing columns
+    fn migrate_flight_events_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "flight_events")?;
+
+        let migrations: &[(&str, &str)] = &[
+            ("detail", "ALTER TABLE flight_events ADD COLUMN detail VARCHAR"),
+            ("severity", "ALTER TABLE flight_events ADD COLUMN severity VARCHAR"),
+        ];
+
+        for (col_name, sql) in migrations {
+            if !columns.contains(*col_name) {
+                log::info!("Migrating flight_events table: adding {} column", col_name);
+                conn.execute_batch(sql)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Migrate flight_tags table - only add missing columns
     fn migrate_flight_tags_table(conn: &Connection) -> Result<(), DatabaseError> {
         let columns = Self::get_table_columns(conn, "flight_tags")?;
@@ -400,6 +1382,11 @@ impl Database {
             "rc_rudder",
             "is_photo",
             "is_video",
+            "compass_error",
+            "compass_interference",
+            "imu_status",
+            "wind_speed",
+            "wind_direction",
         ];
 
         let mut stmt = conn.prepare("PRAGMA table_info('telemetry')")?;
@@ -446,27 +1433,45 @@ impl Database {
 
     /// Generate a new unique flight ID using timestamp + random
     pub fn generate_flight_id(&self) -> i64 {
+        Self::next_id()
+    }
+
+    fn next_id() -> i64 {
+        use std::sync::atomic::{AtomicI64, Ordering};
         use std::time::{SystemTime, UNIX_EPOCH};
+        static LAST_ID: AtomicI64 = AtomicI64::new(0);
+
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis() as i64;
         // Use lower bits for uniqueness
-        timestamp % 1_000_000_000_000
+        let candidate = timestamp % 1_000_000_000_000;
+        // Parallel imports can ask for several IDs within the same millisecond
+        let previous = LAST_ID
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(candidate.max(last + 1)))
+            .unwrap();
+        candidate.max(previous + 1)
     }
 
     /// Insert flight metadata and return the flight ID
     pub fn insert_flight(&self, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        Self::insert_flight_with_conn(&conn, flight)
+    }
 
+    fn insert_flight_with_conn(conn: &Connection, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
+        let daylight = daylight_of(flight.home_lat, flight.home_lon, flight.start_time, flight.duration_secs);
         conn.execute(
             r#"
             INSERT INTO flights (
                 id, file_name, display_name, file_hash, drone_model, drone_serial,
                 aircraft_name, battery_serial,
                 start_time, end_time, duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                max_altitude, max_speed, home_lat, home_lon, point_count,
+                mission_name, partial, firmware_version, max_distance_from_home,
+                daylight, night_secs
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             params![
                 flight.id,
@@ -486,9 +1491,29 @@ impl Database {
                 flight.home_lat,
                 flight.home_lon,
                 flight.point_count,
+                flight.mission_name,
+                flight.partial,
+                flight.firmware_version,
+                flight.max_distance_from_home,
+                daylight.map(|(light, _)| light.as_str()),
+                daylight.map(|(_, night_secs)| night_secs),
             ],
         )?;
 
+        if let Some(serial) = flight.battery_serial.as_deref().filter(|s| !s.is_empty()) {
+            conn.execute(
+                r#"
+                INSERT INTO batteries (serial, drone_model, first_seen) VALUES (?, ?, ?)
+                ON CONFLICT (serial) DO UPDATE SET
+                    drone_model = COALESCE(drone_model, EXCLUDED.drone_model),
+                    first_seen = LEAST(first_seen, EXCLUDED.first_seen)
+                "#,
+                params![serial, flight.drone_model, flight.start_time.map(|t| t.to_rfc3339())],
+            )?;
+        }
+
+        Self::record_audit(conn, "import", Some(flight.id), None)?;
+
         log::info!("Inserted flight with ID: {}", flight.id);
         Ok(flight.id)
     }
@@ -496,19 +1521,74 @@ impl Database {
     /// Bulk insert telemetry data using DuckDB's Appender for maximum performance
     ///
     /// This is significantly faster than individual INSERT statements for large datasets.
+    /// Points are appended in chunks of `TELEMETRY_CHUNK_ROWS`, each flushed on its
+    /// own so the appender buffer stays small. All chunks, the capture events and
+    /// the packing step share one transaction, so a failure partway through a
+    /// long flight leaves none of its telemetry behind. Chunking only bounds the
+    /// appender: the parsers still hand over the whole track as one slice.
     pub fn bulk_insert_telemetry(
         &self,
         flight_id: i64,
         points: &[TelemetryPoint],
     ) -> Result<usize, DatabaseError> {
+        let mut seen_timestamps: HashSet<i64> = HashSet::with_capacity(points.len());
         let conn = self.conn.lock().unwrap();
+        let (inserted, skipped) = Self::in_transaction(&conn, || {
+            let (mut inserted, mut skipped) = (0usize, 0usize);
+            for chunk in points.chunks(TELEMETRY_CHUNK_ROWS) {
+                let (chunk_inserted, chunk_skipped) =
+                    Self::append_telemetry_chunk(&conn, flight_id, chunk, &mut seen_timestamps)?;
+                inserted += chunk_inserted;
+                skipped += chunk_skipped;
+            }
+            Self::record_capture_events_with_conn(&conn, Some(flight_id))?;
+            if self.packed_storage_enabled() {
+                Self::pack_flight_with_conn(&conn, flight_id)?;
+            }
+            Ok((inserted, skipped))
+        })?;
+
+        log::info!(
+            "Bulk inserted {} telemetry points for flight {} ({} skipped)",
+            inserted,
+            flight_id,
+            skipped
+        );
+        Ok(inserted)
+    }
+
+    fn bulk_insert_telemetry_with_conn(
+        conn: &Connection,
+        flight_id: i64,
+        points: &[TelemetryPoint],
+    ) -> Result<usize, DatabaseError> {
+        let mut seen_timestamps: HashSet<i64> = HashSet::with_capacity(points.len());
+        let (inserted, skipped) = Self::append_telemetry_chunk(conn, flight_id, points, &mut seen_timestamps)?;
+        Self::record_capture_events_with_conn(conn, Some(flight_id))?;
+
+        log::info!(
+            "Bulk inserted {} telemetry points for flight {} ({} skipped)",
+            inserted,
+            flight_id,
+            skipped
+        );
+        Ok(inserted)
+    }
 
+    /// Append one chunk of telemetry rows and flush it.
+    /// Returns `(inserted, skipped)`; duplicate timestamps are skipped.
+    fn append_telemetry_chunk(
+        conn: &Connection,
+        flight_id: i64,
+        points: &[TelemetryPoint],
+        seen_timestamps: &mut HashSet<i64>,
+    ) -> Result<(usize, usize), DatabaseError> {
         // Use DuckDB Appender for high-performance bulk inserts
         let mut appender = conn.appender("telemetry")?;
 
         let mut inserted = 0usize;
         let mut skipped = 0usize;
-        let mut seen_timestamps: HashSet<i64> = HashSet::with_capacity(points.len());
+        let mut cell_rows: Vec<(i64, i32, f64)> = Vec::new();
 
         for point in points {
             if !seen_timestamps.insert(point.timestamp_ms) {
@@ -550,8 +1630,18 @@ impl Database {
                 point.rc_rudder,
                 point.is_photo,
                 point.is_video,
+                point.compass_error,
+                point.compass_interference,
+                point.imu_status.as_deref(),
+                point.wind_speed,
+                point.wind_direction,
             ]) {
-                Ok(()) => inserted += 1,
+                Ok(()) => {
+                    inserted += 1;
+                    if let Some(cells) = &point.cell_voltages {
+                        cell_rows.extend(cells.iter().enumerate().map(|(i, &v)| (point.timestamp_ms, i as i32, v)));
+                    }
+                }
                 Err(err) => {
                     let message = err.to_string().to_lowercase();
                     if message.contains("primary key")
@@ -568,21 +1658,111 @@ impl Database {
 
         appender.flush()?;
 
-        log::info!(
-            "Bulk inserted {} telemetry points for flight {} ({} skipped)",
-            inserted,
-            flight_id,
-            skipped
-        );
-        Ok(inserted)
+        if !cell_rows.is_empty() {
+            let mut cell_appender = conn.appender("battery_cells")?;
+            for (timestamp_ms, cell_index, voltage) in &cell_rows {
+                cell_appender.append_row(params![flight_id, timestamp_ms, cell_index, voltage])?;
+            }
+            cell_appender.flush()?;
+        }
+
+        Ok((inserted, skipped))
+    }
+
+    /// Insert several parsed flights in a single transaction. Used by batch
+    /// import to avoid one commit per flight.
+    /// Returns the number of telemetry points inserted for each flight.
+    pub fn insert_flights_batch(&self, flights: &[FlightBatchRow<'_>]) -> Result<Vec<usize>, DatabaseError> {
+        let pack = self.packed_storage_enabled();
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("BEGIN TRANSACTION")?;
+
+        let result: Result<Vec<usize>, DatabaseError> = (|| {
+            let mut counts = Vec::with_capacity(flights.len());
+            for row in flights {
+                let flight_id = Self::insert_flight_with_conn(&conn, row.metadata)?;
+                counts.push(Self::bulk_insert_telemetry_with_conn(&conn, flight_id, row.points)?);
+                if pack {
+                    Self::pack_flight_with_conn(&conn, flight_id)?;
+                }
+                if !row.tags.is_empty() {
+                    Self::insert_flight_tags_with_conn(&conn, flight_id, row.tags)?;
+                }
+                Self::insert_home_points_with_conn(&conn, flight_id, row.home_points)?;
+                Self::insert_motor_samples_with_conn(&conn, flight_id, row.motors)?;
+                Self::insert_flight_events_with_conn(&conn, flight_id, row.events)?;
+            }
+            Ok(counts)
+        })();
+
+        match result {
+            Ok(counts) => {
+                conn.execute_batch("COMMIT")?;
+                log::info!("Committed batch of {} flights", flights.len());
+                Ok(counts)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = conn.execute_batch("ROLLBACK") {
+                    log::error!("Failed to roll back flight batch: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Run `f` in a transaction, rolling back if it fails. Must not be called
+    /// while a transaction is already open on `conn`.
+    fn in_transaction<T>(
+        conn: &Connection,
+        f: impl FnOnce() -> Result<T, DatabaseError>,
+    ) -> Result<T, DatabaseError> {
+        conn.execute_batch("BEGIN TRANSACTION")?;
+        match f() {
+            Ok(value) => {
+                conn.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = conn.execute_batch("ROLLBACK") {
+                    log::error!("Failed to roll back transaction: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Insert `rows` with multi-row `VALUES (...), (...)` statements of up to
+    /// `INSERT_BATCH_ROWS` rows. `head` is the statement up to `VALUES`; every
+    /// row must bind the same number of parameters.
+    fn insert_rows_batched(conn: &Connection, head: &str, rows: &[Vec<&dyn ToSql>]) -> Result<(), DatabaseError> {
+        let Some(width) = rows.first().map(|row| row.len()) else {
+            return Ok(());
+        };
+        let placeholder = format!("({})", vec!["?"; width].join(", "));
+        for chunk in rows.chunks(INSERT_BATCH_ROWS) {
+            let sql = format!("{} VALUES {}", head, vec![placeholder.as_str(); chunk.len()].join(", "));
+            let mut stmt = conn.prepare_cached(&sql)?;
+            stmt.execute(params_from_iter(chunk.iter().flatten()))?;
+        }
+        Ok(())
     }
 
-    /// Get all flights metadata (for the flight list sidebar)
+    /// Get all flights metadata (for the flight list sidebar). Flights in
+    /// the trash are left out.
     pub fn get_all_flights(&self) -> Result<Vec<Flight>, DatabaseError> {
+        self.list_flights("deleted_at IS NULL", "start_time DESC")
+    }
+
+    /// Flights in the trash, most recently deleted first
+    pub fn get_deleted_flights(&self) -> Result<Vec<Flight>, DatabaseError> {
+        self.list_flights("deleted_at IS NOT NULL", "deleted_at DESC")
+    }
+
+    fn list_flights(&self, filter: &str, order: &str) -> Result<Vec<Flight>, DatabaseError> {
         let start = std::time::Instant::now();
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT 
                 id, file_name, COALESCE(display_name, file_name) AS display_name,
@@ -590,11 +1770,20 @@ impl Database {
                 drone_model, drone_serial, aircraft_name, battery_serial,
                 CAST(start_time AS VARCHAR) AS start_time,
                 duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count, notes
+                max_altitude, max_speed, home_lat, home_lon, point_count, notes,
+                mission_name, COALESCE(partial, FALSE) AS partial,
+                CAST(deleted_at AS VARCHAR) AS deleted_at,
+                (SELECT d.nickname FROM drones d WHERE d.serial = flights.drone_serial) AS drone_nickname,
+                location_city, location_region, location_country,
+                COALESCE(favorite, FALSE) AS favorite, firmware_version,
+                COALESCE(excluded_from_stats, FALSE) AS excluded_from_stats,
+                max_distance_from_home, daylight, night_secs
             FROM flights
-            ORDER BY start_time DESC
+            WHERE {}
+            ORDER BY {}
             "#,
-        )?;
+            filter, order
+        ))?;
 
         let mut flights: Vec<Flight> = stmt
             .query_map([], |row| {
@@ -617,6 +1806,19 @@ impl Database {
                     point_count: row.get(15)?,
                     tags: Vec::new(),
                     notes: row.get(16)?,
+                    mission_name: row.get(17)?,
+                    partial: row.get(18)?,
+                    deleted_at: row.get(19)?,
+                    drone_nickname: row.get(20)?,
+                    location_city: row.get(21)?,
+                    location_region: row.get(22)?,
+                    location_country: row.get(23)?,
+                    favorite: row.get(24)?,
+                    firmware_version: row.get(25)?,
+                    excluded_from_stats: row.get(26)?,
+                    max_distance_from_home: row.get(27)?,
+                    daylight: row.get(28)?,
+                    night_secs: row.get(29)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -632,7 +1834,7 @@ impl Database {
             }
         }
 
-        log::debug!("get_all_flights: {} rows in {:.1}ms", flights.len(), start.elapsed().as_secs_f64() * 1000.0);
+        log::debug!("list_flights: {} rows in {:.1}ms", flights.len(), start.elapsed().as_secs_f64() * 1000.0);
         Ok(flights)
     }
 
@@ -663,7 +1865,14 @@ impl Database {
                 file_hash, drone_model, drone_serial, aircraft_name, battery_serial,
                 CAST(start_time AS VARCHAR) AS start_time,
                 duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count, notes
+                max_altitude, max_speed, home_lat, home_lon, point_count, notes,
+                mission_name, COALESCE(partial, FALSE) AS partial,
+                CAST(deleted_at AS VARCHAR) AS deleted_at,
+                (SELECT d.nickname FROM drones d WHERE d.serial = flights.drone_serial) AS drone_nickname,
+                location_city, location_region, location_country,
+                COALESCE(favorite, FALSE) AS favorite, firmware_version,
+                COALESCE(excluded_from_stats, FALSE) AS excluded_from_stats,
+                max_distance_from_home, daylight, night_secs
             FROM flights
             WHERE id = ?
             "#,
@@ -688,6 +1897,19 @@ impl Database {
                     point_count: row.get(15)?,
                     tags: Vec::new(),
                     notes: row.get(16)?,
+                    mission_name: row.get(17)?,
+                    partial: row.get(18)?,
+                    deleted_at: row.get(19)?,
+                    drone_nickname: row.get(20)?,
+                    location_city: row.get(21)?,
+                    location_region: row.get(22)?,
+                    location_country: row.get(23)?,
+                    favorite: row.get(24)?,
+                    firmware_version: row.get(25)?,
+                    excluded_from_stats: row.get(26)?,
+                    max_distance_from_home: row.get(27)?,
+                    daylight: row.get(28)?,
+                    night_secs: row.get(29)?,
                 })
             },
         )
@@ -717,9 +1939,10 @@ impl Database {
     /// Get flight telemetry with automatic downsampling for large datasets.
     ///
     /// Strategy:
-    /// - If points < 5000: return raw data
-    /// - If points >= 5000: group by 1-second intervals, averaging values
-    /// - This keeps the frontend responsive while preserving data trends
+    /// - If points <= `max_points` (5000 by default): return raw data
+    /// - Otherwise: pick about `max_points` raw points by LTTB (see `lttb`),
+    ///   always keeping the height, speed and battery peaks
+    /// - This keeps the frontend responsive without flattening spikes
     ///
     /// `known_point_count` avoids an extra COUNT query when the flight metadata
     /// already provides the point count.
@@ -731,13 +1954,14 @@ impl Database {
     ) -> Result<Vec<TelemetryRecord>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
         let max_points = max_points.unwrap_or(5000);
+        let source = self.telemetry_source(&conn, flight_id)?;
 
         // Use known count or fall back to a COUNT query
         let point_count = match known_point_count {
             Some(c) if c > 0 => c,
             _ => {
                 let c: i64 = conn.query_row(
-                    "SELECT COUNT(*) FROM telemetry WHERE flight_id = ?",
+                    &format!("SELECT COUNT(*) FROM {} WHERE flight_id = ?", source),
                     params![flight_id],
                     |row| row.get(0),
                 )?;
@@ -755,16 +1979,15 @@ impl Database {
                 point_count,
                 flight_id
             );
-            self.query_raw_telemetry(&conn, flight_id)?
+            self.query_raw_telemetry(&conn, source, flight_id)?
         } else {
-            // Downsample using 1-second interval averaging
             log::debug!(
                 "Downsampling {} points to ~{} for flight {}",
                 point_count,
                 max_points,
                 flight_id
             );
-            self.query_downsampled_telemetry(&conn, flight_id, max_points)?
+            crate::lttb::downsample(&self.query_raw_telemetry(&conn, source, flight_id)?, max_points)
         };
 
         Ok(records)
@@ -774,9 +1997,10 @@ impl Database {
     fn query_raw_telemetry(
         &self,
         conn: &Connection,
+        source: &str,
         flight_id: i64,
     ) -> Result<Vec<TelemetryRecord>, DatabaseError> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT 
                 timestamp_ms,
@@ -805,14 +2029,19 @@ impl Database {
                 rc_throttle,
                 rc_rudder,
                 is_photo,
-                is_video
-            FROM telemetry
+                is_video,
+                compass_error,
+                compass_interference,
+                imu_status,
+                wind_speed,
+                wind_direction
+            FROM {source}
             WHERE flight_id = ?
             ORDER BY timestamp_ms ASC
             "#,
-        )?;
+        ))?;
 
-        let records = stmt
+        let mut records = stmt
             .query_map(params![flight_id], |row| {
                 Ok(TelemetryRecord {
                     timestamp_ms: row.get(0)?,
@@ -842,126 +2071,701 @@ impl Database {
                     rc_rudder: row.get(24)?,
                     is_photo: row.get(25)?,
                     is_video: row.get(26)?,
+                    compass_error: row.get(27)?,
+                    compass_interference: row.get(28)?,
+                    imu_status: row.get(29)?,
+                    wind_speed: row.get(30)?,
+                    wind_direction: row.get(31)?,
+                    cumulative_distance: 0.0,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
+        // Same computation as the flight's total distance at import
+        let distances =
+            crate::derived::cumulative_distance(records.iter().map(|r| (r.timestamp_ms, r.latitude, r.longitude)));
+        for (record, distance) in records.iter_mut().zip(distances) {
+            record.cumulative_distance = distance;
+        }
+
         Ok(records)
     }
 
-    /// Query telemetry with downsampling using DuckDB's analytical capabilities
+    // ================================================================
+    // PACKED TELEMETRY
+    // ================================================================
+
+    /// Whether flights are kept in packed storage (`telemetry_storage` in
+    /// config.json is "packed"). The default is one row per telemetry point.
+    pub fn packed_storage_enabled(&self) -> bool {
+        crate::library::read_config(&self.data_dir)
+            .get("telemetry_storage")
+            .and_then(|v| v.as_str())
+            .map_or(false, |m| m == "packed")
+    }
+
+    /// Convert every flight to packed storage, or back to rows. Returns the
+    /// number of flights converted.
     ///
-    /// Groups data into time buckets and averages values for smooth visualization
-    fn query_downsampled_telemetry(
-        &self,
-        conn: &Connection,
-        flight_id: i64,
-        target_points: usize,
-    ) -> Result<Vec<TelemetryRecord>, DatabaseError> {
-        // Calculate the bucket size in milliseconds based on flight duration and target points
-        let (min_ts, max_ts): (i64, i64) = conn.query_row(
-            "SELECT MIN(timestamp_ms), MAX(timestamp_ms) FROM telemetry WHERE flight_id = ?",
-            params![flight_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )?;
+    /// Packed flights are read transparently for charts, the map and exports.
+    /// Library-wide figures computed from raw telemetry (distance from home
+    /// records, battery health trend, stitch candidates) only cover flights
+    /// stored as rows.
+    pub fn convert_telemetry_storage(&self, packed: bool) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        let ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id FROM flights ORDER BY id")?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
 
-        let duration_ms = max_ts - min_ts;
-        let bucket_size_ms = (duration_ms / target_points as i64).max(1000); // At least 1 second
+        let mut converted = 0usize;
+        for flight_id in ids {
+            // One flight per lock so the app stays responsive during the conversion
+            let conn = self.conn.lock().unwrap();
+            let changed = Self::in_transaction(&conn, || {
+                if packed {
+                    Self::pack_flight_with_conn(&conn, flight_id)
+                } else {
+                    Self::unpack_flight_with_conn(&conn, flight_id)
+                }
+            })?;
+            if changed {
+                converted += 1;
+            }
+        }
 
-        let mut stmt = conn.prepare(
-            r#"
-            WITH bucketed AS (
-                SELECT 
-                    (timestamp_ms / ?) * ? AS bucket_ts,
-                    AVG(latitude) AS latitude,
-                    AVG(longitude) AS longitude,
-                    AVG(altitude) AS altitude,
-                    AVG(height) AS height,
-                    AVG(vps_height) AS vps_height,
-                    AVG(speed) AS speed,
-                    AVG(velocity_x) AS velocity_x,
-                    AVG(velocity_y) AS velocity_y,
-                    AVG(velocity_z) AS velocity_z,
-                    AVG(battery_percent)::INTEGER AS battery_percent,
-                    AVG(battery_voltage) AS battery_voltage,
-                    AVG(battery_temp) AS battery_temp,
-                    AVG(pitch) AS pitch,
-                    AVG(roll) AS roll,
-                    AVG(yaw) AS yaw,
-                    ROUND(AVG(satellites))::INTEGER AS satellites,
-                    FIRST(flight_mode ORDER BY timestamp_ms) AS flight_mode,
-                    AVG(rc_signal)::INTEGER AS rc_signal,
-                    AVG(rc_uplink)::INTEGER AS rc_uplink,
-                    AVG(rc_downlink)::INTEGER AS rc_downlink,
-                    AVG(rc_aileron) AS rc_aileron,
-                    AVG(rc_elevator) AS rc_elevator,
-                    AVG(rc_throttle) AS rc_throttle,
-                    AVG(rc_rudder) AS rc_rudder,
-                    BOOL_OR(is_photo) AS is_photo,
-                    BOOL_OR(is_video) AS is_video
-                FROM telemetry
-                WHERE flight_id = ?
-                GROUP BY bucket_ts
-                ORDER BY bucket_ts ASC
-            )
-            SELECT * FROM bucketed
-            "#,
-        )?;
+        // Reclaim the space of the deleted rows
+        if let Err(e) = self.conn.lock().unwrap().execute_batch("CHECKPOINT;") {
+            log::warn!("Checkpoint after telemetry conversion failed: {}", e);
+        }
+        log::info!(
+            "Converted {} flights to {} telemetry storage in {:.1}s",
+            converted,
+            if packed { "packed" } else { "row" },
+            start.elapsed().as_secs_f64()
+        );
+        Ok(converted)
+    }
 
-        let records = stmt
-            .query_map(params![bucket_size_ms, bucket_size_ms, flight_id], |row| {
-                Ok(TelemetryRecord {
-                    timestamp_ms: row.get(0)?,
-                    latitude: row.get(1)?,
-                    longitude: row.get(2)?,
-                    altitude: row.get(3)?,
-                    height: row.get(4)?,
-                    vps_height: row.get(5)?,
-                    speed: row.get(6)?,
-                    velocity_x: row.get(7)?,
-                    velocity_y: row.get(8)?,
-                    velocity_z: row.get(9)?,
-                    battery_percent: row.get(10)?,
-                    battery_voltage: row.get(11)?,
-                    battery_temp: row.get(12)?,
-                    pitch: row.get(13)?,
-                    roll: row.get(14)?,
-                    yaw: row.get(15)?,
-                    satellites: row.get(16)?,
-                    flight_mode: row.get(17)?,
-                    rc_signal: row.get(18)?,
-                    rc_uplink: row.get(19)?,
-                    rc_downlink: row.get(20)?,
-                    rc_aileron: row.get(21)?,
-                    rc_elevator: row.get(22)?,
-                    rc_throttle: row.get(23)?,
-                    rc_rudder: row.get(24)?,
-                    is_photo: row.get(25)?,
-                    is_video: row.get(26)?,
-                })
-            })?
+    /// Telemetry columns other than `flight_id`, as `(name, type)` in table order
+    fn telemetry_column_types(conn: &Connection) -> Result<Vec<(String, String)>, DatabaseError> {
+        let mut stmt = conn.prepare("PRAGMA table_info('telemetry')")?;
+        let columns = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
             .collect::<Result<Vec<_>, _>>()?;
+        Ok(columns.into_iter().filter(|(name, _)| name != "flight_id").collect())
+    }
 
-        Ok(records)
+    /// Read the `columns` of a flight's rows in `source` into one series per
+    /// column, in timestamp order
+    fn read_telemetry_series(
+        conn: &Connection,
+        source: &str,
+        flight_id: i64,
+        columns: &[(String, String)],
+    ) -> Result<Vec<Series>, DatabaseError> {
+        let mut series = columns
+            .iter()
+            .map(|(_, sql_type)| SeriesKind::for_sql_type(sql_type).map(Series::new))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM {} WHERE flight_id = ? ORDER BY timestamp_ms",
+            names.join(", "),
+            source
+        ))?;
+        let mut rows = stmt.query(params![flight_id])?;
+        while let Some(row) = rows.next()? {
+            for (i, values) in series.iter_mut().enumerate() {
+                match values {
+                    Series::Int(v) => v.push(row.get(i)?),
+                    Series::Float(v) => v.push(row.get(i)?),
+                    Series::Bool(v) => v.push(row.get(i)?),
+                    Series::Text(v) => v.push(row.get(i)?),
+                }
+            }
+        }
+        Ok(series)
     }
 
-    /// Delete a flight and all associated telemetry data
-    pub fn delete_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
-        let start = std::time::Instant::now();
+    /// Every stored telemetry column of a flight at full resolution, as
+    /// `(column name, values)` in table order. Unlike `get_flight_telemetry`
+    /// this includes the columns the charts don't use, e.g. gimbal angles.
+    pub fn get_flight_telemetry_series(&self, flight_id: i64) -> Result<Vec<(String, Series)>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        let source = self.telemetry_source(&conn, flight_id)?;
+        let columns = Self::telemetry_column_types(&conn)?;
+        let series = Self::read_telemetry_series(&conn, source, flight_id, &columns)?;
+        Ok(columns.into_iter().map(|(name, _)| name).zip(series).collect())
+    }
 
-        conn.execute(
-            "DELETE FROM telemetry WHERE flight_id = ?",
+    fn is_packed_with_conn(conn: &Connection, flight_id: i64) -> Result<bool, DatabaseError> {
+        let packed: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM telemetry_packed WHERE flight_id = ?",
             params![flight_id],
+            |row| row.get(0),
         )?;
-        // Clean up tags (ignore errors if table doesn't exist in old DBs)
-        let _ = conn.execute(
-            "DELETE FROM flight_tags WHERE flight_id = ?",
-            params![flight_id],
-        );
-        conn.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
+        Ok(packed)
+    }
+
+    /// Replace the telemetry rows of a flight by one compressed blob per
+    /// column. Returns false if the flight has no telemetry rows.
+    fn pack_flight_with_conn(conn: &Connection, flight_id: i64) -> Result<bool, DatabaseError> {
+        let columns = Self::telemetry_column_types(conn)?;
+        let series = Self::read_telemetry_series(conn, "telemetry", flight_id, &columns)?;
+
+        let point_count = series.first().map(Series::len).unwrap_or(0);
+        if point_count == 0 {
+            return Ok(false);
+        }
+        let first_timestamp_ms = columns
+            .iter()
+            .zip(&series)
+            .find_map(|((name, _), values)| match values {
+                Series::Int(v) if name == "timestamp_ms" => v.first().copied().flatten(),
+                _ => None,
+            });
+
+        conn.execute("DELETE FROM telemetry_packed WHERE flight_id = ?", params![flight_id])?;
+        for ((name, sql_type), values) in columns.iter().zip(&series) {
+            conn.execute(
+                r#"
+                INSERT INTO telemetry_packed
+                    (flight_id, column_name, column_type, point_count, first_timestamp_ms, data)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    flight_id,
+                    name,
+                    sql_type,
+                    point_count as i64,
+                    first_timestamp_ms,
+                    telemetry_pack::encode(values)?
+                ],
+            )?;
+        }
+        conn.execute("DELETE FROM telemetry WHERE flight_id = ?", params![flight_id])?;
+        log::debug!("Packed {} telemetry points of flight {}", point_count, flight_id);
+        Ok(true)
+    }
+
+    /// Move a packed flight's telemetry back into the telemetry table.
+    /// Returns false if the flight was not packed.
+    fn unpack_flight_with_conn(conn: &Connection, flight_id: i64) -> Result<bool, DatabaseError> {
+        if Self::decode_packed_into(conn, flight_id, "telemetry")? == 0 {
+            return Ok(false);
+        }
+        conn.execute("DELETE FROM telemetry_packed WHERE flight_id = ?", params![flight_id])?;
+        Ok(true)
+    }
+
+    /// Decode the packed telemetry of a flight and append it to `table`, which
+    /// must have the telemetry table's columns. Returns the number of points.
+    fn decode_packed_into(conn: &Connection, flight_id: i64, table: &str) -> Result<usize, DatabaseError> {
+        let mut stmt = conn.prepare(
+            "SELECT column_name, column_type, point_count, data FROM telemetry_packed WHERE flight_id = ?",
+        )?;
+        let mut packed: HashMap<String, Series> = HashMap::new();
+        let mut point_count = 0usize;
+        let mut rows = stmt.query(params![flight_id])?;
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(0)?;
+            let sql_type: String = row.get(1)?;
+            let count: i64 = row.get(2)?;
+            let data: Vec<u8> = row.get(3)?;
+            point_count = count as usize;
+            packed.insert(name, telemetry_pack::decode(SeriesKind::for_sql_type(&sql_type)?, &data, point_count)?);
+        }
+        drop(rows);
+        if packed.is_empty() {
+            return Ok(0);
+        }
+
+        // Columns added to the telemetry table after packing come back as NULL
+        let mut stmt = conn.prepare("PRAGMA table_info('telemetry')")?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut appender = conn.appender(table)?;
+        let flight_id_value = duckdb::types::Value::BigInt(flight_id);
+        for i in 0..point_count {
+            let values: Vec<duckdb::types::Value> = columns
+                .iter()
+                .map(|name| match packed.get(name) {
+                    _ if name == "flight_id" => flight_id_value.clone(),
+                    Some(series) => Self::series_value(series, i),
+                    None => duckdb::types::Value::Null,
+                })
+                .collect();
+            let refs: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+            appender.append_row(refs.as_slice())?;
+        }
+        appender.flush()?;
+        Ok(point_count)
+    }
+
+    fn series_value(series: &Series, i: usize) -> duckdb::types::Value {
+        use duckdb::types::Value;
+        match series {
+            Series::Int(v) => v[i].map_or(Value::Null, Value::BigInt),
+            Series::Float(v) => v[i].map_or(Value::Null, Value::Double),
+            Series::Bool(v) => v[i].map_or(Value::Null, Value::Boolean),
+            Series::Text(v) => v[i].clone().map_or(Value::Null, Value::Text),
+        }
+    }
+
+    /// Table to read a flight's telemetry from: `telemetry`, or for a packed
+    /// or archived flight a temp table its telemetry is loaded into
+    fn telemetry_source(&self, conn: &Connection, flight_id: i64) -> Result<&'static str, DatabaseError> {
+        if let Some(path) = self.archived_telemetry_path(conn, flight_id)? {
+            if !path.exists() {
+                return Err(DatabaseError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Archived telemetry of flight {} is missing: {:?}", flight_id, path),
+                )));
+            }
+            conn.execute_batch(&format!(
+                r#"
+                CREATE OR REPLACE TEMP TABLE loaded_telemetry AS SELECT * FROM telemetry LIMIT 0;
+                INSERT INTO loaded_telemetry BY NAME SELECT * FROM read_parquet('{}');
+                "#,
+                sql_path(&path)
+            ))?;
+            return Ok("loaded_telemetry");
+        }
+        if !Self::is_packed_with_conn(conn, flight_id)? {
+            return Ok("telemetry");
+        }
+        conn.execute_batch("CREATE OR REPLACE TEMP TABLE loaded_telemetry AS SELECT * FROM telemetry LIMIT 0;")?;
+        Self::decode_packed_into(conn, flight_id, "loaded_telemetry")?;
+        Ok("loaded_telemetry")
+    }
+
+    /// Run `visit` for each flight matching `filter` (a condition on `flights`)
+    /// whose telemetry is packed or archived rather than in `telemetry`, with
+    /// the table its samples were decoded into. Packed and archived flights
+    /// have no telemetry rows, so they are decoded one at a time, one flight
+    /// per lock so the app stays responsive. A flight whose archive cannot be
+    /// read is logged as skipping `what` and left out.
+    fn for_each_stored_elsewhere<F>(&self, filter: &str, what: &str, mut visit: F) -> Result<(), DatabaseError>
+    where
+        F: FnMut(&Connection, &str, i64) -> Result<(), DatabaseError>,
+    {
+        let flight_ids = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT id FROM flights
+                WHERE ({})
+                  AND (id IN (SELECT flight_id FROM telemetry_packed) OR id IN (SELECT flight_id FROM archived_telemetry))
+                "#,
+                filter
+            ))?;
+            let ids = stmt.query_map([], |row| row.get::<_, i64>(0))?.collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        for flight_id in flight_ids {
+            let conn = self.conn.lock().unwrap();
+            let source = match self.telemetry_source(&conn, flight_id) {
+                Ok(source) => source,
+                Err(e) => {
+                    log::warn!("Skipping the {} of flight {}: {}", what, flight_id, e);
+                    continue;
+                }
+            };
+            visit(&conn, source, flight_id)?;
+        }
+        Ok(())
+    }
+
+    /// Write the telemetry of `flight_ids` to a Parquet file, one row per
+    /// sample with the flight ID and the absolute `time_utc` of the sample
+    /// next to the stored columns. Packed and archived flights are included.
+    /// Returns the number of rows written.
+    pub fn export_telemetry_parquet(&self, flight_ids: &[i64], path: &std::path::Path) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        self.conn
+            .lock()
+            .unwrap()
+            .execute_batch("CREATE OR REPLACE TEMP TABLE parquet_export AS SELECT * FROM telemetry LIMIT 0;")?;
+        for flight_id in flight_ids {
+            // One flight per lock so the app stays responsive during a library export
+            let conn = self.conn.lock().unwrap();
+            let source = self.telemetry_source(&conn, *flight_id)?;
+            conn.execute(
+                &format!("INSERT INTO parquet_export SELECT * FROM {} WHERE flight_id = ?", source),
+                params![flight_id],
+            )?;
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let rows: i64 = conn.query_row("SELECT COUNT(*) FROM parquet_export", [], |row| row.get(0))?;
+        conn.execute_batch(&format!(
+            r#"
+            COPY (
+                SELECT t.flight_id, f.start_time + to_milliseconds(t.timestamp_ms) AS time_utc, t.* EXCLUDE (flight_id)
+                FROM parquet_export t
+                LEFT JOIN flights f ON f.id = t.flight_id
+                ORDER BY t.flight_id, t.timestamp_ms
+            ) TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);
+            DROP TABLE parquet_export;
+            "#,
+            sql_path(path)
+        ))?;
+        log::info!(
+            "Exported {} telemetry rows of {} flights to Parquet in {:.1}s",
+            rows,
+            flight_ids.len(),
+            start.elapsed().as_secs_f64()
+        );
+        Ok(rows as usize)
+    }
+
+    // ================================================================
+    // TELEMETRY ARCHIVE
+    // ================================================================
+
+    /// Directory next to the library database that holds the telemetry of
+    /// archived flights, one Parquet file per flight
+    pub fn telemetry_archive_dir(&self) -> PathBuf {
+        self.db_path().with_extension("telemetry-archive")
+    }
+
+    /// Number of flights whose telemetry is archived
+    pub fn archived_flight_count(&self) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let count = conn.query_row("SELECT COUNT(*) FROM archived_telemetry", [], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Move the telemetry of flights that started more than `older_than_years`
+    /// years ago out of the database into compressed sidecar files. Flight
+    /// metadata, tags and notes stay queryable; the telemetry is read back
+    /// from the sidecar whenever the flight is opened. Returns the number of
+    /// flights archived.
+    ///
+    /// Like packed flights, archived flights are left out of library-wide
+    /// figures computed from raw telemetry. Backups include their telemetry.
+    pub fn archive_old_flights(&self, older_than_years: u32) -> Result<usize, DatabaseError> {
+        if older_than_years == 0 {
+            return Err(DatabaseError::InvalidArchiveAge);
+        }
+        let start = std::time::Instant::now();
+        let ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id FROM flights
+                WHERE start_time < now() - to_years(CAST(? AS INTEGER))
+                  AND id NOT IN (SELECT flight_id FROM archived_telemetry)
+                ORDER BY id
+                "#,
+            )?;
+            let ids = stmt
+                .query_map(params![older_than_years], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let dir = self.telemetry_archive_dir();
+        fs::create_dir_all(&dir)?;
+        let mut archived = 0usize;
+        for flight_id in ids {
+            // One flight per lock so the app stays responsive while archiving
+            let conn = self.conn.lock().unwrap();
+            if self.archive_flight_with_conn(&conn, flight_id, &dir)? {
+                archived += 1;
+            }
+        }
+
+        // Reclaim the space of the deleted rows
+        if let Err(e) = self.conn.lock().unwrap().execute_batch("CHECKPOINT;") {
+            log::warn!("Checkpoint after archiving telemetry failed: {}", e);
+        }
+        log::info!(
+            "Archived the telemetry of {} flights older than {} years in {:.1}s",
+            archived,
+            older_than_years,
+            start.elapsed().as_secs_f64()
+        );
+        Ok(archived)
+    }
+
+    /// Write a flight's telemetry to `dir` and remove it from the database.
+    /// Returns false if the flight has no telemetry.
+    fn archive_flight_with_conn(
+        &self,
+        conn: &Connection,
+        flight_id: i64,
+        dir: &std::path::Path,
+    ) -> Result<bool, DatabaseError> {
+        let source = self.telemetry_source(conn, flight_id)?;
+        let (point_count, first_timestamp_ms): (i64, Option<i64>) = conn.query_row(
+            &format!("SELECT COUNT(*), MIN(timestamp_ms) FROM {} WHERE flight_id = ?", source),
+            params![flight_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if point_count == 0 {
+            return Ok(false);
+        }
+
+        // Written under a temporary name so an interrupted copy never looks like an archive
+        let file_name = format!("{}.parquet", flight_id);
+        let path = dir.join(&file_name);
+        let partial_path = path.with_extension("partial");
+        conn.execute_batch(&format!(
+            "COPY (SELECT * FROM {} WHERE flight_id = {} ORDER BY timestamp_ms) TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            source,
+            flight_id,
+            sql_path(&partial_path)
+        ))?;
+        fs::rename(&partial_path, &path)?;
+
+        let moved = Self::in_transaction(conn, || {
+            conn.execute(
+                r#"
+                INSERT INTO archived_telemetry (flight_id, file_name, point_count, first_timestamp_ms)
+                VALUES (?, ?, ?, ?)
+                "#,
+                params![flight_id, file_name, point_count, first_timestamp_ms],
+            )?;
+            conn.execute("DELETE FROM telemetry WHERE flight_id = ?", params![flight_id])?;
+            conn.execute("DELETE FROM telemetry_packed WHERE flight_id = ?", params![flight_id])?;
+            Ok(())
+        });
+        if let Err(e) = moved {
+            let _ = fs::remove_file(&path);
+            return Err(e);
+        }
+        log::debug!("Archived {} telemetry points of flight {}", point_count, flight_id);
+        Ok(true)
+    }
+
+    /// Move the telemetry of every archived flight back into the database.
+    /// Returns the number of flights restored.
+    pub fn restore_archived_flights(&self) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        let ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT flight_id FROM archived_telemetry ORDER BY flight_id")?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+
+        let pack = self.packed_storage_enabled();
+        let mut restored = 0usize;
+        for flight_id in ids {
+            let conn = self.conn.lock().unwrap();
+            let sidecar = Self::in_transaction(&conn, || {
+                let sidecar = self.unarchive_flight_with_conn(&conn, flight_id)?;
+                if pack && sidecar.is_some() {
+                    Self::pack_flight_with_conn(&conn, flight_id)?;
+                }
+                Ok(sidecar)
+            })?;
+            if let Some(path) = sidecar {
+                if let Err(e) = fs::remove_file(&path) {
+                    log::warn!("Failed to remove archived telemetry {:?}: {}", path, e);
+                }
+                restored += 1;
+            }
+        }
+
+        // Drop the directory once it is empty
+        let _ = fs::remove_dir(self.telemetry_archive_dir());
+        log::info!(
+            "Restored the archived telemetry of {} flights in {:.1}s",
+            restored,
+            start.elapsed().as_secs_f64()
+        );
+        Ok(restored)
+    }
+
+    /// Load an archived flight's telemetry back into the telemetry table.
+    /// Returns the sidecar file, to be deleted once the transaction commits,
+    /// or None if the flight was not archived.
+    fn unarchive_flight_with_conn(&self, conn: &Connection, flight_id: i64) -> Result<Option<PathBuf>, DatabaseError> {
+        let Some(path) = self.archived_telemetry_path(conn, flight_id)? else {
+            return Ok(None);
+        };
+        conn.execute_batch(&format!(
+            "INSERT INTO telemetry BY NAME SELECT * FROM read_parquet('{}');",
+            sql_path(&path)
+        ))?;
+        conn.execute("DELETE FROM archived_telemetry WHERE flight_id = ?", params![flight_id])?;
+        Ok(Some(path))
+    }
+
+    /// Sidecar file of an archived flight, None if the flight is not archived
+    fn archived_telemetry_path(&self, conn: &Connection, flight_id: i64) -> Result<Option<PathBuf>, DatabaseError> {
+        let file_name: Option<String> = conn
+            .query_row(
+                "SELECT file_name FROM archived_telemetry WHERE flight_id = ?",
+                params![flight_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(file_name.map(|name| self.telemetry_archive_dir().join(name)))
+    }
+
+    /// Move a flight to the trash. It disappears from lists and stats but
+    /// keeps all its data until restored or purged. Its source file goes on
+    /// the import blacklist so sync does not import it again.
+    pub fn delete_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE flights SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL",
+            params![flight_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        // Keep sync and the folder watcher from bringing the file back
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO import_blacklist (file_hash)
+            SELECT file_hash FROM flights WHERE id = ? AND file_hash IS NOT NULL AND file_hash != ''
+            "#,
+            params![flight_id],
+        )?;
+        Self::record_audit(&conn, "delete", Some(flight_id), None)?;
+        log::info!("Moved flight {} to the trash", flight_id);
+        Ok(())
+    }
+
+    /// Take a flight back out of the trash
+    pub fn restore_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE flights SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+            params![flight_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        conn.execute(
+            "DELETE FROM import_blacklist WHERE file_hash = (SELECT file_hash FROM flights WHERE id = ?)",
+            params![flight_id],
+        )?;
+        Self::record_audit(&conn, "restore", Some(flight_id), None)?;
+        log::info!("Restored flight {} from the trash", flight_id);
+        Ok(())
+    }
+
+    /// Permanently delete every flight in the trash. Returns how many were purged.
+    pub fn empty_trash(&self) -> Result<usize, DatabaseError> {
+        let ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id FROM flights WHERE deleted_at IS NOT NULL")?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        for &id in &ids {
+            self.purge_flight(id)?;
+        }
+        log::info!("Emptied trash: {} flights purged", ids.len());
+        Ok(ids.len())
+    }
+
+    /// Permanently delete a flight and all associated telemetry data
+    pub fn purge_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
+        let start = std::time::Instant::now();
+        let conn = self.conn.lock().unwrap();
+
+        // Logged first, while the flight's file name can still be looked up
+        Self::record_audit(&conn, "purge", Some(flight_id), None)?;
+        conn.execute(
+            "DELETE FROM telemetry WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        // Clean up tags (ignore errors if table doesn't exist in old DBs)
+        let _ = conn.execute(
+            "DELETE FROM flight_tags WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_attachments WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_missions WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM home_points WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_events WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_media WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM motor_telemetry WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM battery_cells WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_annotations WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM incidents WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_equipment WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_sites WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_stats_cache WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_checklists WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM telemetry_packed WHERE flight_id = ?",
+            params![flight_id],
+        );
+        if let Ok(Some(path)) = self.archived_telemetry_path(&conn, flight_id) {
+            let _ = fs::remove_file(path);
+            let _ = conn.execute(
+                "DELETE FROM archived_telemetry WHERE flight_id = ?",
+                params![flight_id],
+            );
+        }
+        let _ = conn.execute(
+            "DELETE FROM flight_documents WHERE flight_id = ?",
+            params![flight_id],
+        );
+        self.remove_orphan_documents(&conn);
+        conn.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
 
-        log::info!("Deleted flight {} in {:.1}ms", flight_id, start.elapsed().as_secs_f64() * 1000.0);
+        log::info!("Purged flight {} in {:.1}ms", flight_id, start.elapsed().as_secs_f64() * 1000.0);
         Ok(())
     }
 
@@ -972,15 +2776,111 @@ impl Database {
 
         conn.execute("DELETE FROM telemetry", params![])?;
         let _ = conn.execute("DELETE FROM flight_tags", params![]);
+        let _ = conn.execute("DELETE FROM flight_attachments", params![]);
+        let _ = conn.execute("DELETE FROM flight_missions", params![]);
+        let _ = conn.execute("DELETE FROM home_points", params![]);
+        let _ = conn.execute("DELETE FROM flight_events", params![]);
+        let _ = conn.execute("DELETE FROM flight_media", params![]);
+        let _ = conn.execute("DELETE FROM motor_telemetry", params![]);
+        let _ = conn.execute("DELETE FROM battery_cells", params![]);
+        let _ = conn.execute("DELETE FROM flight_annotations", params![]);
+        let _ = conn.execute("DELETE FROM incidents", params![]);
+        let _ = conn.execute("DELETE FROM flight_equipment", params![]);
+        let _ = conn.execute("DELETE FROM flight_sites", params![]);
+        let _ = conn.execute("DELETE FROM sites", params![]);
+        let _ = conn.execute("DELETE FROM flight_stats_cache", params![]);
+        let _ = conn.execute("DELETE FROM flight_checklists", params![]);
+        let _ = conn.execute("DELETE FROM telemetry_packed", params![]);
+        let _ = conn.execute("DELETE FROM archived_telemetry", params![]);
+        let _ = fs::remove_dir_all(self.telemetry_archive_dir());
+        let _ = fs::remove_dir_all(self.documents_dir());
+        let flight_count: i64 = conn.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get(0))?;
+        let _ = conn.execute("DELETE FROM flight_documents", params![]);
         conn.execute("DELETE FROM flights", params![])?;
+        Self::record_audit(&conn, "delete_all", None, Some(&format!("{} flights deleted", flight_count)))?;
 
         log::info!("Deleted all flights and telemetry in {:.1}ms", start.elapsed().as_secs_f64() * 1000.0);
         Ok(())
     }
 
-    /// Get overview stats across all flights
+    /// Compute the cached per-flight figures of flights that have none yet,
+    /// or of every flight with `force`. Returns the number of flights computed.
+    pub fn refresh_flight_stats_cache(&self, force: bool) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        let pending = if force {
+            "deleted_at IS NULL"
+        } else {
+            "deleted_at IS NULL AND id NOT IN (SELECT flight_id FROM flight_stats_cache)"
+        };
+        let cache_sql = |source: &str, flights: &str| {
+            format!(
+                r#"
+                INSERT OR REPLACE INTO flight_stats_cache
+                    (flight_id, max_distance_2d, battery_delta_percent, avg_wind_speed, computed_at)
+                SELECT
+                    f.id,
+                    COALESCE(MAX(
+                        CASE WHEN f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
+                             AND t.latitude IS NOT NULL AND t.longitude IS NOT NULL
+                             AND NOT (ABS(t.latitude) < 0.000001 AND ABS(t.longitude) < 0.000001)
+                        THEN
+                            6371000 * 2 * ASIN(SQRT(
+                                POWER(SIN(RADIANS(t.latitude - f.home_lat) / 2), 2) +
+                                COS(RADIANS(f.home_lat)) * COS(RADIANS(t.latitude)) *
+                                POWER(SIN(RADIANS(t.longitude - f.home_lon) / 2), 2)
+                            ))
+                        ELSE 0 END
+                    ), 0)::DOUBLE,
+                    (MAX(t.battery_percent) - MIN(t.battery_percent))::DOUBLE,
+                    AVG(t.wind_speed)::DOUBLE,
+                    CURRENT_TIMESTAMP
+                FROM flights f
+                LEFT JOIN {source} t ON t.flight_id = f.id
+                WHERE f.id IN ({flights})
+                GROUP BY f.id
+                "#
+            )
+        };
+
+        let mut computed = {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                &cache_sql(
+                    "telemetry",
+                    &format!(
+                        r#"
+                        SELECT id FROM flights
+                        WHERE {}
+                          AND id NOT IN (SELECT flight_id FROM telemetry_packed)
+                          AND id NOT IN (SELECT flight_id FROM archived_telemetry)
+                        "#,
+                        pending
+                    ),
+                ),
+                [],
+            )?
+        };
+        self.for_each_stored_elsewhere(pending, "cached statistics", |conn, source, flight_id| {
+            computed += conn.execute(&cache_sql(source, &flight_id.to_string()), [])?;
+            Ok(())
+        })?;
+
+        if computed > 0 {
+            log::info!(
+                "Computed cached statistics of {} flights in {:.1}ms",
+                computed,
+                start.elapsed().as_secs_f64() * 1000.0
+            );
+        }
+        Ok(computed)
+    }
+
+    /// Overview figures of the flights counted in statistics. Per-flight
+    /// figures that need telemetry come from `flight_stats_cache`, which is
+    /// brought up to date first.
     pub fn get_overview_stats(&self) -> Result<OverviewStats, DatabaseError> {
         let start = std::time::Instant::now();
+        self.refresh_flight_stats_cache(false)?;
         let conn = self.conn.lock().unwrap();
 
         // Basic aggregate stats
@@ -994,6 +2894,7 @@ impl Database {
                     COALESCE(SUM(point_count), 0)::BIGINT,
                     COALESCE(MAX(max_altitude), 0)::DOUBLE
                 FROM flights
+                WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)
                 "#,
                 [],
                 |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
@@ -1004,7 +2905,7 @@ impl Database {
             r#"
             SELECT battery_serial, COUNT(*)::BIGINT AS flight_count, COALESCE(SUM(duration_secs), 0)::DOUBLE AS total_duration
             FROM flights
-            WHERE battery_serial IS NOT NULL AND battery_serial <> ''
+            WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE) AND battery_serial IS NOT NULL AND battery_serial <> ''
             GROUP BY battery_serial
             ORDER BY flight_count DESC
             "#,
@@ -1027,18 +2928,21 @@ impl Database {
                 COALESCE(MAX(drone_model), 'Unknown') AS drone_model, 
                 drone_serial,
                 MAX(aircraft_name) AS aircraft_name,
-                COUNT(*)::BIGINT AS flight_count
+                COUNT(*)::BIGINT AS flight_count,
+                MAX(d.nickname) AS nickname
             FROM flights
-            WHERE drone_serial IS NOT NULL AND drone_serial != ''
+            LEFT JOIN drones d ON d.serial = flights.drone_serial
+            WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE) AND drone_serial IS NOT NULL AND drone_serial != ''
             GROUP BY drone_serial
             UNION ALL
             SELECT 
                 COALESCE(drone_model, 'Unknown') AS drone_model, 
                 NULL AS drone_serial,
                 MAX(aircraft_name) AS aircraft_name,
-                COUNT(*)::BIGINT AS flight_count
+                COUNT(*)::BIGINT AS flight_count,
+                NULL AS nickname
             FROM flights
-            WHERE drone_serial IS NULL OR drone_serial = ''
+            WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE) AND (drone_serial IS NULL OR drone_serial = '')
             GROUP BY drone_model
             ORDER BY flight_count DESC
             "#,
@@ -1051,6 +2955,7 @@ impl Database {
                     drone_serial: row.get(1)?,
                     aircraft_name: row.get(2)?,
                     flight_count: row.get(3)?,
+                    nickname: row.get(4)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -1062,7 +2967,7 @@ impl Database {
                 CAST(DATE_TRUNC('day', start_time) AS DATE)::VARCHAR AS flight_date,
                 COUNT(*)::BIGINT AS count
             FROM flights
-            WHERE start_time IS NOT NULL 
+            WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE) AND start_time IS NOT NULL
               AND start_time >= CURRENT_DATE - INTERVAL '365 days'
             GROUP BY DATE_TRUNC('day', start_time)
             ORDER BY flight_date ASC
@@ -1087,7 +2992,7 @@ impl Database {
                 COALESCE(duration_secs, 0)::DOUBLE AS duration_secs,
                 CAST(start_time AS VARCHAR) AS start_time
             FROM flights
-            WHERE duration_secs IS NOT NULL
+            WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE) AND duration_secs IS NOT NULL
             ORDER BY duration_secs DESC
             LIMIT 3
             "#,
@@ -1110,24 +3015,13 @@ impl Database {
             SELECT
                 f.id,
                 COALESCE(f.display_name, f.file_name) AS display_name,
-                COALESCE(MAX(
-                    CASE WHEN f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
-                         AND t.latitude IS NOT NULL AND t.longitude IS NOT NULL
-                         AND NOT (ABS(t.latitude) < 0.000001 AND ABS(t.longitude) < 0.000001)
-                    THEN
-                        6371000 * 2 * ASIN(SQRT(
-                            POWER(SIN(RADIANS(t.latitude - f.home_lat) / 2), 2) +
-                            COS(RADIANS(f.home_lat)) * COS(RADIANS(t.latitude)) *
-                            POWER(SIN(RADIANS(t.longitude - f.home_lon) / 2), 2)
-                        ))
-                    ELSE 0 END
-                ), 0)::DOUBLE AS max_distance_from_home_m,
+                COALESCE(c.max_distance_2d, 0)::DOUBLE AS max_distance_from_home_m,
                 CAST(f.start_time AS VARCHAR) AS start_time
             FROM flights f
-            LEFT JOIN telemetry t ON f.id = t.flight_id
-            WHERE NOT (ABS(f.home_lat) < 0.000001 AND ABS(f.home_lon) < 0.000001)
-               OR f.home_lat IS NULL
-            GROUP BY f.id, f.display_name, f.file_name, f.start_time
+            LEFT JOIN flight_stats_cache c ON c.flight_id = f.id
+            WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+              AND (NOT (ABS(f.home_lat) < 0.000001 AND ABS(f.home_lon) < 0.000001)
+                   OR f.home_lat IS NULL)
             ORDER BY max_distance_from_home_m DESC
             "#,
         )?;
@@ -1151,12 +3045,11 @@ impl Database {
                 f.battery_serial,
                 CAST(f.start_time AS VARCHAR) AS start_time,
                 COALESCE(f.duration_secs, 0)::DOUBLE AS duration_secs,
-                (MAX(t.battery_percent) - MIN(t.battery_percent))::DOUBLE AS delta_percent
+                c.battery_delta_percent AS delta_percent
             FROM flights f
-            JOIN telemetry t ON f.id = t.flight_id
-            WHERE f.battery_serial IS NOT NULL AND f.battery_serial <> ''
-              AND t.battery_percent IS NOT NULL
-            GROUP BY f.id, f.battery_serial, f.start_time, f.duration_secs
+            JOIN flight_stats_cache c ON c.flight_id = f.id
+            WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE) AND f.battery_serial IS NOT NULL AND f.battery_serial <> ''
+              AND c.battery_delta_percent IS NOT NULL
             ORDER BY f.start_time ASC
             "#,
         )?;
@@ -1207,563 +3100,6371 @@ impl Database {
         })
     }
 
-    /// Update the display name for a flight
-    pub fn update_flight_name(&self, flight_id: i64, display_name: &str) -> Result<(), DatabaseError> {
+    /// Totals, per-drone and per-month figures, aggregated in SQL. With
+    /// `flight_ids` only those flights count, e.g. the sidebar's current
+    /// filter; flights excluded from statistics never do.
+    pub fn get_library_stats(&self, flight_ids: Option<&[i64]>) -> Result<LibraryStats, DatabaseError> {
+        let start = std::time::Instant::now();
         let conn = self.conn.lock().unwrap();
 
-        conn.execute(
-            "UPDATE flights SET display_name = ? WHERE id = ?",
-            params![display_name, flight_id],
-        )?;
+        let mut condition = "f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)".to_string();
+        if let Some(ids) = flight_ids {
+            if ids.is_empty() {
+                condition.push_str(" AND FALSE");
+            } else {
+                let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+                condition.push_str(&format!(" AND f.id IN ({})", ids));
+            }
+        }
 
-        log::debug!("Updated flight {} display name to '{}'", flight_id, display_name);
-        Ok(())
-    }
+        let (
+            total_flights,
+            total_duration_secs,
+            total_distance_m,
+            total_points,
+            max_altitude_m,
+            first_flight,
+            last_flight,
+            days_flown,
+            night_duration_secs,
+            night_flight_count,
+            twilight_flight_count,
+        ) =
+            conn.query_row(
+                &format!(
+                    r#"
+                    SELECT
+                        COUNT(*)::BIGINT,
+                        COALESCE(SUM(duration_secs), 0)::DOUBLE,
+                        COALESCE(SUM(total_distance), 0)::DOUBLE,
+                        COALESCE(SUM(point_count), 0)::BIGINT,
+                        COALESCE(MAX(max_altitude), 0)::DOUBLE,
+                        CAST(MIN(start_time) AS VARCHAR),
+                        CAST(MAX(start_time) AS VARCHAR),
+                        COUNT(DISTINCT CAST(start_time AS DATE))::BIGINT,
+                        COALESCE(SUM(night_secs), 0)::DOUBLE,
+                        COUNT(*) FILTER (WHERE daylight = 'night')::BIGINT,
+                        COUNT(*) FILTER (WHERE daylight = 'civil_twilight')::BIGINT
+                    FROM flights f
+                    WHERE {condition}
+                    "#
+                ),
+                [],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                        row.get(6)?,
+                        row.get(7)?,
+                        row.get(8)?,
+                        row.get(9)?,
+                        row.get(10)?,
+                    ))
+                },
+            )?;
 
-    /// Update the notes for a flight
-    pub fn update_flight_notes(&self, flight_id: i64, notes: Option<&str>) -> Result<(), DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+        // One row per aircraft: by serial when known, otherwise by model
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                COALESCE(MAX(f.drone_model), 'Unknown'),
+                NULLIF(upper(trim(MAX(f.drone_serial))), ''),
+                MAX(f.aircraft_name),
+                MAX(d.nickname),
+                COUNT(*)::BIGINT,
+                COALESCE(SUM(f.duration_secs), 0)::DOUBLE AS total_duration,
+                COALESCE(SUM(f.total_distance), 0)::DOUBLE,
+                CAST(MIN(f.start_time) AS VARCHAR),
+                CAST(MAX(f.start_time) AS VARCHAR)
+            FROM flights f
+            LEFT JOIN drones d ON d.serial = f.drone_serial
+            WHERE {condition}
+            GROUP BY COALESCE(NULLIF(upper(trim(f.drone_serial)), ''), 'model:' || COALESCE(f.drone_model, 'Unknown'))
+            ORDER BY total_duration DESC
+            "#
+        ))?;
+        let drones = stmt
+            .query_map([], |row| {
+                Ok(DroneStats {
+                    drone_model: row.get(0)?,
+                    drone_serial: row.get(1)?,
+                    aircraft_name: row.get(2)?,
+                    nickname: row.get(3)?,
+                    flight_count: row.get(4)?,
+                    total_duration_secs: row.get(5)?,
+                    total_distance_m: row.get(6)?,
+                    first_flight: row.get(7)?,
+                    last_flight: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        conn.execute(
-            "UPDATE flights SET notes = ? WHERE id = ?",
-            params![notes, flight_id],
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                battery_serial,
+                COUNT(*)::BIGINT,
+                COALESCE(SUM(duration_secs), 0)::DOUBLE AS total_duration
+            FROM flights f
+            WHERE {condition} AND battery_serial IS NOT NULL AND battery_serial <> ''
+            GROUP BY battery_serial
+            ORDER BY total_duration DESC
+            "#
+        ))?;
+        let batteries = stmt
+            .query_map([], |row| {
+                Ok(BatteryUsage {
+                    battery_serial: row.get(0)?,
+                    flight_count: row.get(1)?,
+                    total_duration_secs: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        log::debug!("Updated flight {} notes", flight_id);
-        Ok(())
-    }
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                strftime(start_time, '%Y-%m') AS month,
+                COUNT(*)::BIGINT,
+                COALESCE(SUM(duration_secs), 0)::DOUBLE,
+                COALESCE(SUM(total_distance), 0)::DOUBLE
+            FROM flights f
+            WHERE {condition} AND start_time IS NOT NULL
+            GROUP BY month
+            ORDER BY month ASC
+            "#
+        ))?;
+        let months = stmt
+            .query_map([], |row| {
+                Ok(MonthlyStats {
+                    month: row.get(0)?,
+                    flight_count: row.get(1)?,
+                    total_duration_secs: row.get(2)?,
+                    total_distance_m: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
-    // ================================================================
-    // TAG MANAGEMENT
-    // ================================================================
+        log::debug!(
+            "get_library_stats: {} flights, {} drones, {} months in {:.1}ms",
+            total_flights,
+            drones.len(),
+            months.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(LibraryStats {
+            total_flights,
+            total_duration_secs,
+            total_distance_m,
+            total_points,
+            max_altitude_m,
+            first_flight,
+            last_flight,
+            days_flown,
+            night_duration_secs,
+            night_flight_count,
+            twilight_flight_count,
+            drones,
+            batteries,
+            months,
+        })
+    }
 
-    /// Insert multiple tags for a flight (used during import, always type 'auto')
-    pub fn insert_flight_tags(&self, flight_id: i64, tags: &[String]) -> Result<(), DatabaseError> {
-        if tags.is_empty() {
-            return Ok(());
-        }
+    /// Flight count, time and distance per day, week, month or year, oldest
+    /// first. Periods without flights between the first and the last flight
+    /// are included with zeros, so charts keep an even time axis.
+    pub fn get_trends(&self, bucket: TrendBucket, flight_ids: Option<&[i64]>) -> Result<Vec<TrendPoint>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
-        for tag in tags {
-            let trimmed = tag.trim();
-            if trimmed.is_empty() {
-                continue;
+
+        let mut condition = "f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)".to_string();
+        if let Some(ids) = flight_ids {
+            if ids.is_empty() {
+                condition.push_str(" AND FALSE");
+            } else {
+                let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+                condition.push_str(&format!(" AND f.id IN ({})", ids));
             }
-            // Use INSERT OR IGNORE to avoid duplicate key errors
-            conn.execute(
-                "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type) VALUES (?, ?, 'auto')",
-                params![flight_id, trimmed],
-            )?;
         }
-        log::debug!("Inserted {} tags for flight {}", tags.len(), flight_id);
-        Ok(())
-    }
+        let unit = match bucket {
+            TrendBucket::Day => "day",
+            TrendBucket::Week => "week",
+            TrendBucket::Month => "month",
+            TrendBucket::Year => "year",
+        };
 
-    /// Get all tags for a specific flight
-    pub fn get_flight_tags(&self, flight_id: i64) -> Result<Vec<FlightTag>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT tag, tag_type FROM flight_tags WHERE flight_id = ? ORDER BY tag",
-        )?;
-        let tags = stmt
-            .query_map(params![flight_id], |row| {
-                Ok(FlightTag {
-                    tag: row.get::<_, String>(0)?,
-                    tag_type: row.get::<_, String>(1)?,
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH buckets AS (
+                SELECT
+                    CAST(date_trunc('{unit}', CAST(start_time AS DATE)) AS DATE) AS period,
+                    COUNT(*)::BIGINT AS flight_count,
+                    COALESCE(SUM(duration_secs), 0)::DOUBLE AS total_duration,
+                    COALESCE(SUM(total_distance), 0)::DOUBLE AS total_distance
+                FROM flights f
+                WHERE {condition} AND start_time IS NOT NULL
+                GROUP BY period
+            ),
+            periods AS (
+                SELECT CAST(unnest(range(
+                    CAST(MIN(period) AS TIMESTAMP),
+                    CAST(MAX(period) AS TIMESTAMP) + INTERVAL 1 {unit},
+                    INTERVAL 1 {unit}
+                )) AS DATE) AS period
+                FROM buckets
+            )
+            SELECT
+                strftime(p.period, '%Y-%m-%d'),
+                COALESCE(b.flight_count, 0)::BIGINT,
+                COALESCE(b.total_duration, 0)::DOUBLE,
+                COALESCE(b.total_distance, 0)::DOUBLE
+            FROM periods p
+            LEFT JOIN buckets b ON b.period = p.period
+            ORDER BY p.period ASC
+            "#
+        ))?;
+        let points = stmt
+            .query_map([], |row| {
+                Ok(TrendPoint {
+                    period: row.get(0)?,
+                    flight_count: row.get(1)?,
+                    total_duration_secs: row.get(2)?,
+                    total_distance_m: row.get(3)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(tags)
+
+        Ok(points)
     }
 
-    /// Add a single tag to a flight (manual user-added tag)
-    pub fn add_flight_tag(&self, flight_id: i64, tag: &str) -> Result<(), DatabaseError> {
-        let trimmed = tag.trim();
-        if trimmed.is_empty() {
-            return Ok(());
-        }
+    /// Totals, averages, wind and reported incidents of the flights of one
+    /// aircraft, matched by serial, or of every aircraft of a model. Energy
+    /// and safety event figures are left for the caller.
+    pub fn get_drone_comparison_stats(&self, drone: &str) -> Result<DroneComparisonStats, DatabaseError> {
+        self.refresh_flight_stats_cache(false)?;
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type) VALUES (?, ?, 'manual')",
-            params![flight_id, trimmed],
+        let condition = r#"
+            f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+            AND (upper(trim(f.drone_serial)) = upper(trim(?)) OR f.drone_model = ?)
+        "#;
+
+        let mut stats = conn.query_row(
+            &format!(
+                r#"
+                SELECT
+                    arg_max(f.drone_model, f.start_time),
+                    CASE WHEN COUNT(DISTINCT NULLIF(upper(trim(f.drone_serial)), '')) = 1
+                         THEN MAX(NULLIF(upper(trim(f.drone_serial)), '')) END,
+                    CASE WHEN COUNT(DISTINCT NULLIF(upper(trim(f.drone_serial)), '')) = 1
+                         THEN MAX(d.nickname) END,
+                    COUNT(*)::BIGINT,
+                    COALESCE(SUM(f.duration_secs), 0)::DOUBLE,
+                    COALESCE(SUM(f.total_distance), 0)::DOUBLE,
+                    COALESCE(AVG(f.duration_secs), 0)::DOUBLE,
+                    COALESCE(AVG(f.total_distance), 0)::DOUBLE,
+                    COALESCE(MAX(f.max_altitude), 0)::DOUBLE,
+                    CAST(MIN(f.start_time) AS VARCHAR),
+                    CAST(MAX(f.start_time) AS VARCHAR),
+                    (SUM(c.avg_wind_speed * COALESCE(f.duration_secs, 0))
+                        / NULLIF(SUM(COALESCE(f.duration_secs, 0)) FILTER (WHERE c.avg_wind_speed IS NOT NULL), 0))::DOUBLE,
+                    COUNT(c.avg_wind_speed)::BIGINT
+                FROM flights f
+                LEFT JOIN drones d ON d.serial = f.drone_serial
+                LEFT JOIN flight_stats_cache c ON c.flight_id = f.id
+                WHERE {condition}
+                "#
+            ),
+            params![drone, drone],
+            |row| {
+                Ok(DroneComparisonStats {
+                    drone: drone.to_string(),
+                    drone_model: row.get(0)?,
+                    drone_serial: row.get(1)?,
+                    nickname: row.get(2)?,
+                    flight_count: row.get(3)?,
+                    total_duration_secs: row.get(4)?,
+                    total_distance_m: row.get(5)?,
+                    avg_duration_secs: row.get(6)?,
+                    avg_distance_m: row.get(7)?,
+                    max_altitude_m: row.get(8)?,
+                    first_flight: row.get(9)?,
+                    last_flight: row.get(10)?,
+                    wh_per_km: None,
+                    wh_per_min: None,
+                    avg_wind_speed: row.get(11)?,
+                    wind_flight_count: row.get(12)?,
+                    incident_count: 0,
+                    safety_events: RthEventCounts::default(),
+                })
+            },
         )?;
-        log::debug!("Added manual tag '{}' to flight {}", trimmed, flight_id);
-        Ok(())
+
+        stats.incident_count = conn.query_row(
+            &format!("SELECT COUNT(*)::BIGINT FROM incidents i JOIN flights f ON f.id = i.flight_id WHERE {condition}"),
+            params![drone, drone],
+            |row| row.get(0),
+        )?;
+        Ok(stats)
     }
 
-    /// Remove a single tag from a flight
-    pub fn remove_flight_tag(&self, flight_id: i64, tag: &str) -> Result<(), DatabaseError> {
+    /// Flights and flight time of each aircraft within the last `windows`
+    /// days, most recently flown first. Checks are left for the caller.
+    pub fn get_recent_activity(&self, windows: &[u32]) -> Result<Vec<CurrencyEntry>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM flight_tags WHERE flight_id = ? AND tag = ?",
-            params![flight_id, tag.trim()],
-        )?;
-        log::debug!("Removed tag '{}' from flight {}", tag, flight_id);
-        Ok(())
+        // Flight count and seconds flown per window
+        let columns: String = windows
+            .iter()
+            .map(|days| {
+                let recent = format!("f.start_time >= now() - INTERVAL {} DAY", days);
+                format!(
+                    ", COUNT(*) FILTER (WHERE {recent})::BIGINT, COALESCE(SUM(f.duration_secs) FILTER (WHERE {recent}), 0)::DOUBLE"
+                )
+            })
+            .collect();
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                COALESCE(MAX(f.drone_model), 'Unknown'),
+                NULLIF(upper(trim(MAX(f.drone_serial))), ''),
+                COALESCE(MAX(d.nickname), MAX(f.aircraft_name)),
+                CAST(MAX(f.start_time) AS VARCHAR) AS last_flight{columns}
+            FROM flights f
+            LEFT JOIN drones d ON d.serial = f.drone_serial
+            WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE) AND f.start_time IS NOT NULL
+            GROUP BY COALESCE(NULLIF(upper(trim(f.drone_serial)), ''), 'model:' || COALESCE(f.drone_model, 'Unknown'))
+            ORDER BY last_flight DESC
+            "#
+        ))?;
+        let entries = stmt
+            .query_map([], |row| {
+                let mut recent = Vec::with_capacity(windows.len());
+                for (i, days) in windows.iter().enumerate() {
+                    recent.push(CurrencyWindow {
+                        days: *days,
+                        flight_count: row.get(4 + 2 * i)?,
+                        duration_secs: row.get(5 + 2 * i)?,
+                    });
+                }
+                Ok(CurrencyEntry {
+                    name: row.get(2)?,
+                    drone_model: row.get(0)?,
+                    drone_serial: row.get(1)?,
+                    last_flight: row.get(3)?,
+                    windows: recent,
+                    checks: Vec::new(),
+                    current: false,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
     }
 
-    /// Get all unique tags across all flights
-    pub fn get_all_unique_tags(&self) -> Result<Vec<String>, DatabaseError> {
+    /// Start, duration and distance of every flight counted in statistics,
+    /// oldest first
+    pub fn get_flight_totals(&self) -> Result<Vec<FlightTotals>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT DISTINCT tag FROM flight_tags ORDER BY tag",
+            r#"
+            SELECT
+                id,
+                CAST(start_time AS VARCHAR),
+                COALESCE(duration_secs, 0)::DOUBLE,
+                COALESCE(total_distance, 0)::DOUBLE
+            FROM flights
+            WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE) AND start_time IS NOT NULL
+            ORDER BY start_time ASC, id ASC
+            "#,
         )?;
-        let tags = stmt
-            .query_map([], |row| row.get::<_, String>(0))?
+        let flights = stmt
+            .query_map([], |row| {
+                Ok(FlightTotals {
+                    flight_id: row.get(0)?,
+                    start_time: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    distance_m: row.get(3)?,
+                })
+            })?
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(tags)
+        Ok(flights)
     }
 
-    /// Replace all auto tags for a flight with new ones (keeps manual tags)
-    pub fn replace_auto_tags(&self, flight_id: i64, new_tags: &[String]) -> Result<(), DatabaseError> {
+    /// The flights holding the duration, distance, altitude, speed and
+    /// distance-from-home records, among `flight_ids` when given
+    pub fn get_personal_records(&self, flight_ids: Option<&[i64]>) -> Result<PersonalRecords, DatabaseError> {
+        let start = std::time::Instant::now();
         let conn = self.conn.lock().unwrap();
-        // Delete existing auto tags
-        conn.execute(
-            "DELETE FROM flight_tags WHERE flight_id = ? AND tag_type = 'auto'",
-            params![flight_id],
-        )?;
-        // Insert new auto tags
-        for tag in new_tags {
-            let trimmed = tag.trim();
-            if trimmed.is_empty() {
-                continue;
+
+        let mut condition = "deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)".to_string();
+        if let Some(ids) = flight_ids {
+            if ids.is_empty() {
+                condition.push_str(" AND FALSE");
+            } else {
+                let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+                condition.push_str(&format!(" AND id IN ({})", ids));
             }
-            conn.execute(
-                "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type) VALUES (?, ?, 'auto')",
-                params![flight_id, trimmed],
-            )?;
         }
-        Ok(())
+
+        let record = |column: &str| -> Result<Option<PersonalRecord>, DatabaseError> {
+            let sql = format!(
+                r#"
+                SELECT id, COALESCE(display_name, file_name), CAST(start_time AS VARCHAR), {column}::DOUBLE
+                FROM flights
+                WHERE {condition} AND {column} > 0
+                ORDER BY {column} DESC, id ASC
+                LIMIT 1
+                "#
+            );
+            Ok(conn
+                .query_row(&sql, [], |row| {
+                    Ok(PersonalRecord {
+                        flight_id: row.get(0)?,
+                        display_name: row.get(1)?,
+                        start_time: row.get(2)?,
+                        value: row.get(3)?,
+                    })
+                })
+                .optional()?)
+        };
+        let longest_duration = record("duration_secs")?;
+        let longest_distance = record("total_distance")?;
+        let highest_altitude = record("max_altitude")?;
+        let top_speed = record("max_speed")?;
+
+        let farthest_from_home = record("max_distance_from_home")?;
+
+        log::debug!("get_personal_records in {:.1}ms", start.elapsed().as_secs_f64() * 1000.0);
+        Ok(PersonalRecords {
+            longest_duration,
+            longest_distance,
+            highest_altitude,
+            top_speed,
+            farthest_from_home,
+        })
     }
 
-    /// Remove all auto-generated tags from all flights (keeps manual tags)
-    /// Returns the number of auto tags removed
-    pub fn remove_all_auto_tags(&self) -> Result<usize, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-        let removed = conn.execute(
-            "DELETE FROM flight_tags WHERE tag_type = 'auto'",
-            [],
+    /// Stretches of the flights counted in statistics where the height above
+    /// takeoff or the horizontal distance from home went over a limit, per
+    /// flight with any. A `None` limit isn't checked. Home and takeoff height
+    /// are found as in `backfill_max_distance_from_home`. A flight whose
+    /// archived telemetry is missing is left out with a warning rather than
+    /// failing the whole report.
+    pub fn get_limit_exceedances(
+        &self,
+        max_height_m: Option<f64>,
+        max_distance_m: Option<f64>,
+    ) -> Result<Vec<ComplianceFlight>, DatabaseError> {
+        let start = std::time::Instant::now();
+        let mut checks = Vec::new();
+        if let Some(limit) = max_height_m.filter(|l| l.is_finite()) {
+            checks.push(format!("SELECT *, 'height' AS kind, height_m AS value, {limit}::DOUBLE AS lim FROM samples WHERE height_m IS NOT NULL"));
+        }
+        if let Some(limit) = max_distance_m.filter(|l| l.is_finite()) {
+            checks.push(format!("SELECT *, 'distance' AS kind, distance_m AS value, {limit}::DOUBLE AS lim FROM samples"));
+        }
+        if checks.is_empty() {
+            return Ok(Vec::new());
+        }
+        let checks = checks.join(" UNION ALL ");
+
+        let segment_sql = |source: &str, flights: &str| {
+            format!(
+                r#"
+                WITH positions AS (
+                    SELECT flight_id, timestamp_ms, latitude, longitude, COALESCE(height, altitude) AS height
+                    FROM {source}
+                    WHERE flight_id IN ({flights})
+                      AND latitude IS NOT NULL AND longitude IS NOT NULL
+                      AND (ABS(latitude) > 0.0001 OR ABS(longitude) > 0.0001)
+                ),
+                origin AS (
+                    SELECT
+                        p.flight_id,
+                        COALESCE(ANY_VALUE(f.home_lat), arg_min(p.latitude, p.timestamp_ms)) AS home_lat,
+                        COALESCE(ANY_VALUE(f.home_lon), arg_min(p.longitude, p.timestamp_ms)) AS home_lon,
+                        COALESCE(arg_min(p.height, p.timestamp_ms), 0) AS base_height
+                    FROM positions p
+                    JOIN flights f ON f.id = p.flight_id
+                    GROUP BY p.flight_id
+                ),
+                samples AS (
+                    SELECT
+                        p.flight_id,
+                        p.timestamp_ms,
+                        p.latitude,
+                        p.longitude,
+                        p.height - o.base_height AS height_m,
+                        6371000 * 2 * ASIN(SQRT(
+                            POWER(SIN(RADIANS(p.latitude - o.home_lat) / 2), 2) +
+                            COS(RADIANS(o.home_lat)) * COS(RADIANS(p.latitude)) *
+                            POWER(SIN(RADIANS(p.longitude - o.home_lon) / 2), 2)
+                        )) AS distance_m
+                    FROM positions p
+                    JOIN origin o ON o.flight_id = p.flight_id
+                ),
+                checked AS ({checks}),
+                runs AS (
+                    SELECT *,
+                        -- Consecutive samples over the limit share a run number
+                        SUM(CASE WHEN value > lim THEN 0 ELSE 1 END)
+                            OVER (PARTITION BY flight_id, kind ORDER BY timestamp_ms) AS run
+                    FROM checked
+                )
+                SELECT
+                    flight_id,
+                    kind,
+                    MIN(timestamp_ms),
+                    MAX(timestamp_ms),
+                    MAX(value)::DOUBLE,
+                    arg_min(latitude, timestamp_ms),
+                    arg_min(longitude, timestamp_ms)
+                FROM runs
+                WHERE value > lim
+                GROUP BY flight_id, kind, run
+                "#
+            )
+        };
+        let segment_from_row = |row: &duckdb::Row<'_>| -> duckdb::Result<(i64, ComplianceSegment)> {
+            Ok((
+                row.get(0)?,
+                ComplianceSegment {
+                    kind: row.get(1)?,
+                    start_ms: row.get(2)?,
+                    end_ms: row.get(3)?,
+                    peak_m: row.get(4)?,
+                    lat: row.get(5)?,
+                    lon: row.get(6)?,
+                },
+            ))
+        };
+
+        let mut segments = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&segment_sql(
+                "telemetry",
+                "SELECT id FROM flights WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            ))?;
+            let segments = stmt.query_map([], segment_from_row)?.collect::<Result<Vec<_>, _>>()?;
+            segments
+        };
+        self.for_each_stored_elsewhere(
+            "deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            "compliance check",
+            |conn, source, flight_id| {
+                let mut stmt = conn.prepare(&segment_sql(source, &flight_id.to_string()))?;
+                segments.extend(stmt.query_map([], segment_from_row)?.collect::<Result<Vec<_>, _>>()?);
+                Ok(())
+            },
         )?;
-        Ok(removed)
-    }
 
-    /// Get all flight IDs (for bulk operations like tag regeneration)
-    pub fn get_all_flight_ids(&self) -> Result<Vec<i64>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT id FROM flights ORDER BY id")?;
-        let ids = stmt
-            .query_map([], |row| row.get::<_, i64>(0))?
-            .collect::<Result<Vec<_>, _>>()?;
-        Ok(ids)
+        let mut by_flight: HashMap<i64, Vec<ComplianceSegment>> = HashMap::new();
+        for (flight_id, segment) in segments {
+            by_flight.entry(flight_id).or_default().push(segment);
+        }
+        let mut flights = Vec::with_capacity(by_flight.len());
+        if !by_flight.is_empty() {
+            let conn = self.conn.lock().unwrap();
+            let ids = by_flight.keys().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT id, COALESCE(display_name, file_name), CAST(start_time AS VARCHAR), drone_model, drone_serial
+                FROM flights
+                WHERE id IN ({})
+                ORDER BY start_time DESC NULLS LAST, id DESC
+                "#,
+                ids
+            ))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok(ComplianceFlight {
+                        flight_id: row.get(0)?,
+                        display_name: row.get(1)?,
+                        start_time: row.get(2)?,
+                        drone_model: row.get(3)?,
+                        drone_serial: row.get(4)?,
+                        segments: Vec::new(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            for mut flight in rows {
+                if let Some(mut segments) = by_flight.remove(&flight.flight_id) {
+                    segments.sort_by_key(|s| s.start_ms);
+                    flight.segments = segments;
+                }
+                flights.push(flight);
+            }
+        }
+
+        log::debug!(
+            "get_limit_exceedances: {} flights over a limit in {:.1}ms",
+            flights.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(flights)
     }
 
-    /// Check if a file has already been imported (by hash)
-    /// Returns the display_name of the matching flight if found, None otherwise
-    pub fn is_file_imported(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+    /// Store the farthest 3D distance from home of flights that have none
+    /// yet, or of every flight with `force`, computed as in
+    /// `parser::max_distance_from_home`. Returns the number of flights updated.
+    pub fn backfill_max_distance_from_home(&self, force: bool) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        let pending = if force { "TRUE" } else { "max_distance_from_home IS NULL" };
+        let distance_sql = |source: &str, flights: &str| {
+            format!(
+                r#"
+                UPDATE flights SET max_distance_from_home = d.distance_m
+                FROM (
+                    WITH positions AS (
+                        SELECT flight_id, timestamp_ms, latitude, longitude, COALESCE(height, altitude) AS height
+                        FROM {source}
+                        WHERE flight_id IN ({flights})
+                          AND latitude IS NOT NULL AND longitude IS NOT NULL
+                          AND (ABS(latitude) > 0.0001 OR ABS(longitude) > 0.0001)
+                    ),
+                    origin AS (
+                        SELECT
+                            p.flight_id,
+                            COALESCE(ANY_VALUE(f.home_lat), arg_min(p.latitude, p.timestamp_ms)) AS home_lat,
+                            COALESCE(ANY_VALUE(f.home_lon), arg_min(p.longitude, p.timestamp_ms)) AS home_lon,
+                            COALESCE(arg_min(p.height, p.timestamp_ms), 0) AS base_height
+                        FROM positions p
+                        JOIN flights f ON f.id = p.flight_id
+                        GROUP BY p.flight_id
+                    )
+                    SELECT
+                        p.flight_id,
+                        MAX(SQRT(
+                            POWER(6371000 * 2 * ASIN(SQRT(
+                                POWER(SIN(RADIANS(p.latitude - o.home_lat) / 2), 2) +
+                                COS(RADIANS(o.home_lat)) * COS(RADIANS(p.latitude)) *
+                                POWER(SIN(RADIANS(p.longitude - o.home_lon) / 2), 2)
+                            )), 2) +
+                            POWER(COALESCE(p.height - o.base_height, 0), 2)
+                        ))::DOUBLE AS distance_m
+                    FROM positions p
+                    JOIN origin o ON o.flight_id = p.flight_id
+                    GROUP BY p.flight_id
+                ) d
+                WHERE flights.id = d.flight_id
+                "#
+            )
+        };
 
-        let result: Option<String> = conn.query_row(
-            "SELECT COALESCE(display_name, file_name) FROM flights WHERE file_hash = ? LIMIT 1",
-            params![file_hash],
-            |row| row.get(0),
-        ).optional()?;
+        let mut updated = {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                &distance_sql("telemetry", &format!("SELECT id FROM flights WHERE {}", pending)),
+                [],
+            )?
+        };
+        self.for_each_stored_elsewhere(pending, "distance from home", |conn, source, flight_id| {
+            updated += conn.execute(&distance_sql(source, &flight_id.to_string()), [])?;
+            Ok(())
+        })?;
 
-        Ok(result)
+        log::info!(
+            "Stored the distance from home of {} flights in {:.1}s",
+            updated,
+            start.elapsed().as_secs_f64()
+        );
+        Ok(updated)
     }
 
-    /// Get all file hashes from existing flights
-    /// Used by sync to filter out already-imported files (web feature only)
-    #[allow(dead_code)]
-    pub fn get_all_file_hashes(&self) -> Result<Vec<String>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT file_hash FROM flights WHERE file_hash IS NOT NULL AND file_hash != ''"
-        )?;
-        let hashes = stmt.query_map([], |row| row.get(0))?
-            .collect::<Result<Vec<String>, _>>()?;
-        Ok(hashes)
+    /// Recompute the total distance of every flight from its stored
+    /// telemetry, the same way as imports and the cumulative distance series
+    /// (`derived::path_distance`). Flights without GPS positions keep theirs.
+    /// Returns the number of flights updated.
+    pub fn recompute_total_distance(&self) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        let mut updated = {
+            let conn = self.conn.lock().unwrap();
+            Self::store_total_distance(&conn, "telemetry", "SELECT id FROM flights")?
+        };
+        self.for_each_stored_elsewhere("TRUE", "total distance", |conn, source, flight_id| {
+            updated += Self::store_total_distance(conn, source, &flight_id.to_string())?;
+            Ok(())
+        })?;
+
+        log::info!(
+            "Recomputed the total distance of {} flights in {:.1}s",
+            updated,
+            start.elapsed().as_secs_f64()
+        );
+        Ok(updated)
     }
 
-    /// Check if a duplicate flight exists based on exact signature match (drone_serial + battery_serial + start_time).
-    /// Returns the display_name of the matching flight if found, None otherwise.
-    /// If any of the signature fields are None, returns None (can't reliably deduplicate).
-    pub fn is_duplicate_flight(
-        &self,
-        drone_serial: Option<&str>,
-        battery_serial: Option<&str>,
-        start_time: Option<chrono::DateTime<chrono::Utc>>,
-    ) -> Result<Option<String>, DatabaseError> {
-        // If any key field is missing, we can't reliably check for duplicates
-        let (drone, battery, time) = match (drone_serial, battery_serial, start_time) {
-            (Some(d), Some(b), Some(t)) if !d.is_empty() && !b.is_empty() => (d, b, t),
-            _ => return Ok(None),
-        };
+    /// Store the total distance of `flights` (ids or a subquery) from the
+    /// positions in `source`, computed by `derived::path_distance`. Flights
+    /// without a GPS fix keep theirs. Returns the number of flights updated.
+    fn store_total_distance(conn: &Connection, source: &str, flights: &str) -> Result<usize, DatabaseError> {
+        let mut distances: Vec<(i64, f64)> = Vec::new();
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT flight_id, timestamp_ms, latitude, longitude FROM {source} WHERE flight_id IN ({flights}) ORDER BY flight_id, timestamp_ms"
+            ))?;
+            let mut rows = stmt.query([])?;
+            let mut current: Option<i64> = None;
+            let mut points: Vec<(i64, Option<f64>, Option<f64>)> = Vec::new();
+            let mut flush = |flight_id: Option<i64>, points: &mut Vec<(i64, Option<f64>, Option<f64>)>| {
+                if let Some(flight_id) = flight_id {
+                    if points.iter().any(|&(_, lat, lon)| crate::gps_filter::valid_position(lat, lon).is_some()) {
+                        distances.push((flight_id, crate::derived::path_distance(points.drain(..))));
+                    }
+                }
+                points.clear();
+            };
+            while let Some(row) = rows.next()? {
+                let flight_id: i64 = row.get(0)?;
+                if current != Some(flight_id) {
+                    flush(current, &mut points);
+                    current = Some(flight_id);
+                }
+                points.push((row.get(1)?, row.get(2)?, row.get(3)?));
+            }
+            flush(current, &mut points);
+        }
+
+        for (flight_id, distance) in &distances {
+            conn.execute("UPDATE flights SET total_distance = ? WHERE id = ?", params![distance, flight_id])?;
+        }
+        Ok(distances.len())
+    }
+
+    /// One-time recompute of total distances stored by older versions, which
+    /// counted jumps to and from 0,0 before a GPS fix and to GPS glitches
+    fn run_startup_total_distance_backfill(&self) {
+        const SETTING_KEY: &str = "total_distance_recomputed";
 
+        if let Ok(Some(value)) = self.get_setting(SETTING_KEY) {
+            if value == "true" {
+                return;
+            }
+        }
+        if let Err(e) = self.recompute_total_distance() {
+            log::error!("Total distance recompute failed: {}", e);
+            return;
+        }
+        if let Err(e) = self.set_setting(SETTING_KEY, "true") {
+            log::error!("Failed to save total distance recompute flag: {}", e);
+        }
+    }
+
+    /// Classify the light of flights with a home point and start time that
+    /// have no classification yet, or of every such flight with `force`.
+    /// Returns the number of flights classified.
+    pub fn backfill_daylight(&self, force: bool) -> Result<usize, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        Self::classify_daylight_with_conn(&conn, if force { "TRUE" } else { "daylight IS NULL" })
+    }
 
-        // Check for existing flight with exact same signature (drone, battery, start_time)
-        let result: Option<String> = conn.query_row(
+    fn classify_daylight_with_conn(conn: &Connection, condition: &str) -> Result<usize, DatabaseError> {
+        let start = std::time::Instant::now();
+        let mut stmt = conn.prepare(&format!(
             r#"
-            SELECT COALESCE(display_name, file_name) FROM flights 
-            WHERE drone_serial = ?
-              AND battery_serial = ?
-              AND start_time IS NOT NULL
-              AND start_time = ?::TIMESTAMPTZ
-            LIMIT 1
+            SELECT id, home_lat, home_lon, epoch_ms(start_time), duration_secs
+            FROM flights
+            WHERE ({}) AND home_lat IS NOT NULL AND home_lon IS NOT NULL AND start_time IS NOT NULL
+              AND NOT (ABS(home_lat) < 0.000001 AND ABS(home_lon) < 0.000001)
             "#,
-            params![drone, battery, time.to_rfc3339()],
-            |row| row.get(0),
-        ).optional()?;
+            condition
+        ))?;
+        let flights = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<f64>>(1)?,
+                    row.get::<_, Option<f64>>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                    row.get::<_, Option<f64>>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(result)
+        let mut classified = 0;
+        for (flight_id, lat, lon, start_ms, duration_secs) in flights {
+            let start_time = start_ms.and_then(chrono::DateTime::from_timestamp_millis);
+            if let Some((light, night_secs)) = daylight_of(lat, lon, start_time, duration_secs) {
+                classified += conn.execute(
+                    "UPDATE flights SET daylight = ?, night_secs = ? WHERE id = ?",
+                    params![light.as_str(), night_secs, flight_id],
+                )?;
+            }
+        }
+        if classified > 0 {
+            log::info!(
+                "Classified day and night of {} flights in {:.1}ms",
+                classified,
+                start.elapsed().as_secs_f64() * 1000.0
+            );
+        }
+        Ok(classified)
     }
 
-    /// Remove duplicate flights from the database based on exact signature match (drone_serial + battery_serial + start_time).
-    /// Keeps the flight with the most telemetry points for each duplicate group.
-    /// Returns the number of duplicates removed.
-    pub fn deduplicate_flights(&self) -> Result<usize, DatabaseError> {
+    /// Compute the distance from home once for flights imported before it was stored
+    fn run_startup_distance_backfill(&self) {
+        const SETTING_KEY: &str = "max_distance_from_home_backfilled";
+
+        if let Ok(Some(value)) = self.get_setting(SETTING_KEY) {
+            if value == "true" {
+                return;
+            }
+        }
+        if let Err(e) = self.backfill_max_distance_from_home(false) {
+            log::error!("Distance from home backfill failed: {}", e);
+            return;
+        }
+        if let Err(e) = self.set_setting(SETTING_KEY, "true") {
+            log::error!("Failed to save distance from home backfill flag: {}", e);
+        }
+    }
+
+    /// Update the display name for a flight
+    pub fn update_flight_name(&self, flight_id: i64, display_name: &str) -> Result<(), DatabaseError> {
         let conn = self.conn.lock().unwrap();
-        let start = std::time::Instant::now();
-        log::info!("Starting flight deduplication...");
 
-        let mut total_removed = 0;
+        conn.execute(
+            "UPDATE flights SET display_name = ? WHERE id = ?",
+            params![display_name, flight_id],
+        )?;
+        Self::record_audit(&conn, "rename", Some(flight_id), Some(display_name))?;
 
-        // Method 1: Remove exact file_hash duplicates (keep the one with most telemetry points)
-        let hash_duplicates = conn.execute(
+        log::debug!("Updated flight {} display name to '{}'", flight_id, display_name);
+        Ok(())
+    }
+
+    /// Update the notes for a flight
+    pub fn update_flight_notes(&self, flight_id: i64, notes: Option<&str>) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE flights SET notes = ? WHERE id = ?",
+            params![notes, flight_id],
+        )?;
+        Self::record_audit(&conn, "notes", Some(flight_id), notes)?;
+
+        log::debug!("Updated flight {} notes", flight_id);
+        Ok(())
+    }
+
+    /// Star or unstar a flight
+    pub fn set_flight_favorite(&self, flight_id: i64, favorite: bool) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE flights SET favorite = ? WHERE id = ?",
+            params![favorite, flight_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        Self::record_audit(&conn, if favorite { "favorite" } else { "unfavorite" }, Some(flight_id), None)?;
+        log::debug!("Set flight {} favorite to {}", flight_id, favorite);
+        Ok(())
+    }
+
+    /// Leave a flight out of statistics, e.g. a bench test or a short hover
+    /// check, or count it again
+    pub fn set_flight_excluded_from_stats(&self, flight_id: i64, excluded: bool) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE flights SET excluded_from_stats = ? WHERE id = ?",
+            params![excluded, flight_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        Self::record_audit(&conn, if excluded { "exclude_stats" } else { "include_stats" }, Some(flight_id), None)?;
+        log::debug!("Set flight {} excluded from stats to {}", flight_id, excluded);
+        Ok(())
+    }
+
+    // ================================================================
+    // LOCATIONS
+    // ================================================================
+
+    /// Flights with a home point that still need a location, as
+    /// `(flight_id, home_lat, home_lon)`. With `force`, every such flight.
+    pub fn get_flights_to_locate(&self, force: bool) -> Result<Vec<(i64, f64, f64)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
             r#"
-            WITH hash_duplicates AS (
-                SELECT file_hash, COUNT(*) as cnt
-                FROM flights
-                WHERE file_hash IS NOT NULL AND file_hash != ''
-                GROUP BY file_hash
-                HAVING COUNT(*) > 1
-            ),
-            ranked_flights AS (
-                SELECT f.id, f.file_hash, f.point_count,
-                       ROW_NUMBER() OVER (PARTITION BY f.file_hash ORDER BY f.point_count DESC, f.id ASC) as rn
-                FROM flights f
-                WHERE f.file_hash IN (SELECT file_hash FROM hash_duplicates)
-            )
-            DELETE FROM flights WHERE id IN (
-                SELECT id FROM ranked_flights WHERE rn > 1
-            )
+            SELECT id, home_lat, home_lon
+            FROM flights
+            WHERE deleted_at IS NULL
+              AND home_lat IS NOT NULL AND home_lon IS NOT NULL
+              AND NOT (abs(home_lat) < 0.001 AND abs(home_lon) < 0.001)
+              {}
+            ORDER BY start_time DESC
             "#,
-            [],
-        )?;
-        total_removed += hash_duplicates;
-        log::info!("Removed {} file_hash duplicates", hash_duplicates);
+            if force { "" } else { "AND location_country IS NULL AND location_city IS NULL" }
+        ))?;
+        let flights = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(flights)
+    }
 
-        // Method 2: Remove signature-based duplicates (same drone + battery + exact same start_time)
-        // This catches re-imports of the same flight from different sources
-        let signature_duplicates = conn.execute(
+    /// Flights (not in the trash) imported after `after_ms` (Unix ms), as
+    /// `(flight_id, imported_at_ms)` in import order
+    pub fn get_flights_imported_after(&self, after_ms: i64) -> Result<Vec<(i64, i64)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
             r#"
-            WITH flight_pairs AS (
-                SELECT 
-                    f1.id as id1,
-                    f2.id as id2,
-                    f1.point_count as points1,
-                    f2.point_count as points2
-                FROM flights f1
-                JOIN flights f2 ON f1.drone_serial = f2.drone_serial
-                    AND f1.battery_serial = f2.battery_serial
-                    AND f1.id < f2.id
-                    AND f1.start_time = f2.start_time
-                WHERE f1.drone_serial IS NOT NULL AND f1.drone_serial != ''
-                  AND f1.battery_serial IS NOT NULL AND f1.battery_serial != ''
-                  AND f1.start_time IS NOT NULL
-                  AND f2.start_time IS NOT NULL
-            ),
-            ids_to_delete AS (
-                SELECT CASE 
-                    WHEN points1 >= points2 THEN id2
-                    ELSE id1
-                END as id
-                FROM flight_pairs
-            )
-            DELETE FROM flights WHERE id IN (SELECT DISTINCT id FROM ids_to_delete)
+            SELECT id, epoch_ms(imported_at)
+            FROM flights
+            WHERE deleted_at IS NULL AND imported_at IS NOT NULL AND epoch_ms(imported_at) > ?
+            ORDER BY imported_at, id
             "#,
-            [],
         )?;
-        total_removed += signature_duplicates;
-        log::info!("Removed {} signature-based duplicates", signature_duplicates);
+        let flights = stmt
+            .query_map(params![after_ms], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(flights)
+    }
 
-        // Clean up orphaned telemetry data
-        let orphaned_telemetry = conn.execute(
-            "DELETE FROM telemetry WHERE flight_id NOT IN (SELECT id FROM flights)",
-            [],
-        )?;
-        log::info!("Cleaned up {} orphaned telemetry records", orphaned_telemetry);
+    /// Cached geocoding result for a grid cell. `Some` with no names means
+    /// the provider was asked and had nothing for that place.
+    pub fn get_cached_location(
+        &self,
+        provider: &str,
+        lat_key: f64,
+        lon_key: f64,
+    ) -> Result<Option<FlightLocation>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let location = conn
+            .query_row(
+                "SELECT city, region, country FROM geocode_cache WHERE provider = ? AND lat_key = ? AND lon_key = ?",
+                params![provider, lat_key, lon_key],
+                |row| {
+                    Ok(FlightLocation {
+                        city: row.get(0)?,
+                        region: row.get(1)?,
+                        country: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(location)
+    }
 
-        // Clean up orphaned tags
-        let orphaned_tags = conn.execute(
-            "DELETE FROM flight_tags WHERE flight_id NOT IN (SELECT id FROM flights)",
-            [],
+    /// Remember the geocoding result for a grid cell
+    pub fn cache_location(
+        &self,
+        provider: &str,
+        lat_key: f64,
+        lon_key: f64,
+        location: &FlightLocation,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO geocode_cache (provider, lat_key, lon_key, city, region, country)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            params![provider, lat_key, lon_key, location.city, location.region, location.country],
         )?;
-        log::info!("Cleaned up {} orphaned tags", orphaned_tags);
-
-        log::info!(
-            "Deduplication complete in {:.1}s: {} total duplicate flights removed",
-            start.elapsed().as_secs_f64(),
-            total_removed
-        );
+        Ok(())
+    }
 
-        Ok(total_removed)
+    /// Cached ground elevation of a grid cell
+    pub fn get_cached_elevation(&self, provider: &str, lat_key: f64, lon_key: f64) -> Result<Option<f64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let elevation = conn
+            .query_row(
+                "SELECT elevation_m FROM elevation_cache WHERE provider = ? AND lat_key = ? AND lon_key = ?",
+                params![provider, lat_key, lon_key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(elevation)
     }
 
-    /// Get a setting value by key
-    pub fn get_setting(&self, key: &str) -> Result<Option<String>, DatabaseError> {
+    /// Remember the ground elevation of a grid cell
+    pub fn cache_elevation(&self, provider: &str, lat_key: f64, lon_key: f64, elevation_m: f64) -> Result<(), DatabaseError> {
         let conn = self.conn.lock().unwrap();
-        
-        let result: Result<String, _> = conn.query_row(
-            "SELECT value FROM settings WHERE key = ?",
-            params![key],
-            |row| row.get(0),
-        );
-        
-        match result {
-            Ok(value) => Ok(Some(value)),
-            Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(DatabaseError::from(e)),
-        }
+        conn.execute(
+            "INSERT OR REPLACE INTO elevation_cache (provider, lat_key, lon_key, elevation_m) VALUES (?, ?, ?, ?)",
+            params![provider, lat_key, lon_key, elevation_m],
+        )?;
+        Ok(())
     }
 
-    /// Set a setting value (insert or update)
-    pub fn set_setting(&self, key: &str, value: &str) -> Result<(), DatabaseError> {
+    /// Store the reverse-geocoded location of a flight
+    pub fn set_flight_location(&self, flight_id: i64, location: &FlightLocation) -> Result<(), DatabaseError> {
         let conn = self.conn.lock().unwrap();
-        
-        // DuckDB doesn't support CURRENT_TIMESTAMP in ON CONFLICT, so use INSERT OR REPLACE
         conn.execute(
-            "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
-            params![key, value],
+            "UPDATE flights SET location_city = ?, location_region = ?, location_country = ? WHERE id = ?",
+            params![location.city, location.region, location.country, flight_id],
         )?;
-        
         Ok(())
     }
 
-    /// Run one-time startup deduplication for existing data.
-    /// This only runs once - on first startup after the dedup feature is added.
-    /// After running, it sets a flag so it won't run again.
-    fn run_startup_deduplication(&self) {
-        const SETTING_KEY: &str = "duplicate_checked";
-        
-        // Check if we've already run deduplication
-        match self.get_setting(SETTING_KEY) {
-            Ok(Some(value)) if value == "true" => {
-                log::debug!("Startup deduplication already completed, skipping");
-                return;
-            }
-            Ok(_) => {
-                // Not set or not "true", run deduplication
-            }
-            Err(e) => {
-                log::warn!("Failed to check dedup setting: {}, proceeding with deduplication", e);
-            }
-        }
+    // ================================================================
+    // BATTERIES
+    // ================================================================
 
-        log::info!("Running one-time startup deduplication for existing flights...");
-        
-        match self.deduplicate_flights() {
-            Ok(count) => {
-                if count > 0 {
-                    log::info!("Startup deduplication removed {} duplicate flights", count);
-                } else {
-                    log::info!("Startup deduplication complete, no duplicates found");
+    /// Every registered battery with its usage across active flights,
+    /// most recently flown first
+    pub fn get_batteries(&self) -> Result<Vec<Battery>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                b.serial,
+                b.drone_model,
+                CAST(b.first_seen AS VARCHAR) AS first_seen,
+                CAST(MAX(f.start_time) AS VARCHAR) AS last_used,
+                COUNT(f.id)::BIGINT AS flight_count,
+                COALESCE(SUM(f.duration_secs), 0) AS total_duration_secs,
+                COALESCE(SUM(f.total_distance), 0) AS total_distance_m
+            FROM batteries b
+            LEFT JOIN flights f ON f.battery_serial = b.serial AND f.deleted_at IS NULL
+            GROUP BY b.serial, b.drone_model, b.first_seen
+            ORDER BY MAX(f.start_time) DESC NULLS LAST, b.serial
+            "#,
+        )?;
+        let batteries = stmt
+            .query_map([], |row| {
+                Ok(Battery {
+                    serial: row.get(0)?,
+                    drone_model: row.get(1)?,
+                    first_seen: row.get(2)?,
+                    last_used: row.get(3)?,
+                    flight_count: row.get(4)?,
+                    total_duration_secs: row.get(5)?,
+                    total_distance_m: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(batteries)
+    }
+
+    /// Battery figures of every active flight with a battery serial, oldest
+    /// first, for health trends. Flights excluded from statistics count.
+    pub fn get_battery_flight_samples(&self) -> Result<Vec<BatteryFlightSample>, DatabaseError> {
+        let start = std::time::Instant::now();
+        // Rows of one source table grouped per flight; the loaded voltage is
+        // the lowest over the first 10% of charge used
+        let sample_sql = |source: &str| {
+            format!(
+                r#"
+                WITH charge AS (
+                    SELECT flight_id, MAX(battery_percent) AS start_percent
+                    FROM {source}
+                    GROUP BY flight_id
+                )
+                SELECT
+                    f.id,
+                    f.battery_serial,
+                    CAST(f.start_time AS VARCHAR),
+                    COALESCE(f.duration_secs, 0)::DOUBLE,
+                    MAX(t.battery_percent)::DOUBLE,
+                    MIN(t.battery_percent)::DOUBLE,
+                    AVG(ABS(t.battery_current))::DOUBLE,
+                    MAX(t.battery_voltage)::DOUBLE,
+                    (MIN(t.battery_voltage) FILTER (WHERE t.battery_percent >= c.start_percent - 10))::DOUBLE
+                FROM flights f
+                JOIN {source} t ON t.flight_id = f.id
+                JOIN charge c ON c.flight_id = f.id
+                WHERE f.deleted_at IS NULL AND f.battery_serial IS NOT NULL AND f.battery_serial <> ''
+                GROUP BY f.id, f.battery_serial, f.start_time, f.duration_secs
+                "#
+            )
+        };
+        let mut samples = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&sample_sql("telemetry"))?;
+            let samples = stmt
+                .query_map([], Self::battery_sample_from_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            samples
+        };
+        self.for_each_stored_elsewhere(
+            "deleted_at IS NULL AND battery_serial IS NOT NULL AND battery_serial <> ''",
+            "battery health samples",
+            |conn, source, _| {
+                let mut stmt = conn.prepare(&sample_sql(source))?;
+                samples.extend(stmt.query_map([], Self::battery_sample_from_row)?.collect::<Result<Vec<_>, _>>()?);
+                Ok(())
+            },
+        )?;
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT flight_id, MAX(spread)::DOUBLE
+            FROM (
+                SELECT flight_id, MAX(voltage) - MIN(voltage) AS spread
+                FROM battery_cells
+                GROUP BY flight_id, timestamp_ms
+                HAVING COUNT(*) > 1
+            )
+            GROUP BY flight_id
+            "#,
+        )?;
+        let imbalance: HashMap<i64, f64> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        for sample in &mut samples {
+            sample.cell_imbalance_v = imbalance.get(&sample.flight_id).copied();
+        }
+        samples.sort_by(|a, b| a.start_time.cmp(&b.start_time).then(a.flight_id.cmp(&b.flight_id)));
+
+        log::debug!(
+            "get_battery_flight_samples: {} flights in {:.1}ms",
+            samples.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(samples)
+    }
+
+    /// A row of the per-flight battery query in `get_battery_flight_samples`
+    fn battery_sample_from_row(row: &duckdb::Row<'_>) -> DuckResult<BatteryFlightSample> {
+        let duration_secs: f64 = row.get(3)?;
+        let start_percent: Option<f64> = row.get(4)?;
+        let end_percent: Option<f64> = row.get(5)?;
+        let avg_current_a: Option<f64> = row.get(6)?;
+        let rest_voltage: Option<f64> = row.get(7)?;
+        let load_voltage: Option<f64> = row.get(8)?;
+        Ok(BatteryFlightSample {
+            flight_id: row.get(0)?,
+            battery_serial: row.get(1)?,
+            start_time: row.get(2)?,
+            duration_secs,
+            start_percent,
+            end_percent,
+            avg_current_a,
+            capacity_mah: match (avg_current_a, start_percent, end_percent) {
+                (Some(current), Some(from), Some(to)) => {
+                    crate::battery_health::capacity_mah(current, duration_secs, from - to)
                 }
+                _ => None,
+            },
+            voltage_sag_v: match (rest_voltage, load_voltage) {
+                (Some(rest), Some(load)) if rest > 0.0 && load > 0.0 => Some((rest - load).max(0.0)),
+                _ => None,
+            },
+            cell_imbalance_v: None,
+        })
+    }
+
+    /// Battery energy of every flight counted in statistics, oldest first.
+    /// Power is summed over the time to the next sample; gaps longer than
+    /// `TELEMETRY_MAX_GAP_MS` are left out rather than bridged.
+    pub fn get_flight_energy(&self) -> Result<Vec<FlightEnergy>, DatabaseError> {
+        let start = std::time::Instant::now();
+        let energy_sql = |source: &str| {
+            format!(
+                r#"
+                WITH samples AS (
+                    SELECT
+                        flight_id,
+                        ABS(battery_voltage * battery_current) AS power_w,
+                        LEAD(timestamp_ms) OVER (PARTITION BY flight_id ORDER BY timestamp_ms) - timestamp_ms AS dt_ms
+                    FROM {source}
+                    WHERE battery_voltage > 0 AND battery_current IS NOT NULL
+                )
+                SELECT
+                    f.id,
+                    COALESCE(f.display_name, f.file_name),
+                    CAST(f.start_time AS VARCHAR),
+                    f.drone_model,
+                    f.drone_serial,
+                    (SUM(s.power_w * s.dt_ms) FILTER (WHERE s.dt_ms <= {TELEMETRY_MAX_GAP_MS}) / 3600000.0)::DOUBLE AS energy_wh,
+                    COALESCE(f.duration_secs, 0)::DOUBLE,
+                    COALESCE(f.total_distance, 0)::DOUBLE
+                FROM flights f
+                JOIN samples s ON s.flight_id = f.id
+                WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+                GROUP BY f.id, f.display_name, f.file_name, f.start_time, f.drone_model, f.drone_serial,
+                         f.duration_secs, f.total_distance
+                HAVING energy_wh > 0
+                "#
+            )
+        };
+        let mut flights = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&energy_sql("telemetry"))?;
+            let flights = stmt
+                .query_map([], Self::flight_energy_from_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            flights
+        };
+        self.for_each_stored_elsewhere(
+            "deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            "energy use",
+            |conn, source, _| {
+                let mut stmt = conn.prepare(&energy_sql(source))?;
+                flights.extend(stmt.query_map([], Self::flight_energy_from_row)?.collect::<Result<Vec<_>, _>>()?);
+                Ok(())
+            },
+        )?;
+        flights.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+
+        log::debug!(
+            "get_flight_energy: {} flights in {:.1}ms",
+            flights.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(flights)
+    }
+
+    /// Seconds spent in each flight mode as `(flight_id, mode, seconds)`, for
+    /// one flight or for every flight counted in statistics. A sample's mode
+    /// lasts until the next sample, as in `get_flight_energy`.
+    pub fn get_flight_mode_times(&self, flight_id: Option<i64>) -> Result<Vec<(i64, String, f64)>, DatabaseError> {
+        let mode_sql = |source: &str, flights: &str| {
+            format!(
+                r#"
+                SELECT flight_id, flight_mode, (SUM(dt_ms) FILTER (WHERE dt_ms <= {TELEMETRY_MAX_GAP_MS}) / 1000.0)::DOUBLE AS seconds
+                FROM (
+                    SELECT
+                        flight_id,
+                        flight_mode,
+                        LEAD(timestamp_ms) OVER (PARTITION BY flight_id ORDER BY timestamp_ms) - timestamp_ms AS dt_ms
+                    FROM {source}
+                    WHERE flight_id IN ({flights})
+                )
+                WHERE flight_mode IS NOT NULL AND flight_mode <> ''
+                GROUP BY flight_id, flight_mode
+                HAVING seconds > 0
+                "#
+            )
+        };
+        if let Some(flight_id) = flight_id {
+            let conn = self.conn.lock().unwrap();
+            let source = self.telemetry_source(&conn, flight_id)?;
+            let mut stmt = conn.prepare(&mode_sql(source, &flight_id.to_string()))?;
+            let times = stmt.query_map([], Self::mode_time_from_row)?.collect::<Result<Vec<_>, _>>()?;
+            return Ok(times);
+        }
+
+        let mut times = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&mode_sql(
+                "telemetry",
+                "SELECT id FROM flights WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            ))?;
+            let times = stmt.query_map([], Self::mode_time_from_row)?.collect::<Result<Vec<_>, _>>()?;
+            times
+        };
+        self.for_each_stored_elsewhere(
+            "deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            "flight mode times",
+            |conn, source, flight_id| {
+                let mut stmt = conn.prepare(&mode_sql(source, &flight_id.to_string()))?;
+                times.extend(stmt.query_map([], Self::mode_time_from_row)?.collect::<Result<Vec<_>, _>>()?);
+                Ok(())
+            },
+        )?;
+        Ok(times)
+    }
+
+    fn mode_time_from_row(row: &duckdb::Row<'_>) -> duckdb::Result<(i64, String, f64)> {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    }
+
+    fn flight_energy_from_row(row: &duckdb::Row<'_>) -> duckdb::Result<FlightEnergy> {
+        let energy_wh: f64 = row.get(5)?;
+        let duration_secs: f64 = row.get(6)?;
+        let distance_m: f64 = row.get(7)?;
+        Ok(FlightEnergy {
+            flight_id: row.get(0)?,
+            display_name: row.get(1)?,
+            start_time: row.get(2)?,
+            drone_model: row.get(3)?,
+            drone_serial: row.get(4)?,
+            energy_wh,
+            duration_secs,
+            distance_m,
+            wh_per_km: (distance_m >= ENERGY_MIN_DISTANCE_M).then(|| energy_wh / (distance_m / 1000.0)),
+            wh_per_min: (duration_secs > 0.0).then(|| energy_wh / (duration_secs / 60.0)),
+        })
+    }
+
+
+    // ================================================================
+    // MAINTENANCE
+    // ================================================================
+
+    /// Log maintenance work on a drone or battery. `performed_at` defaults to now.
+    pub fn add_maintenance_record(
+        &self,
+        target_type: &str,
+        serial: &str,
+        kind: &str,
+        performed_at: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<MaintenanceRecord, DatabaseError> {
+        if target_type != "drone" && target_type != "battery" {
+            return Err(DatabaseError::InvalidMaintenance(format!("unknown target '{}'", target_type)));
+        }
+        if !MAINTENANCE_KINDS.contains(&kind) {
+            return Err(DatabaseError::InvalidMaintenance(format!("unknown kind '{}'", kind)));
+        }
+        let serial = serial.trim();
+        if serial.is_empty() {
+            return Err(DatabaseError::InvalidMaintenance("serial is required".to_string()));
+        }
+        let description = description.map(str::trim).filter(|d| !d.is_empty());
+
+        let id = self.generate_flight_id();
+        let conn = self.conn.lock().unwrap();
+        let record = conn.query_row(
+            r#"
+            INSERT INTO maintenance_records (id, target_type, serial, kind, performed_at, description)
+            VALUES (?, ?, ?, ?, COALESCE(CAST(? AS TIMESTAMP WITH TIME ZONE), CURRENT_TIMESTAMP), ?)
+            RETURNING id, target_type, serial, kind, CAST(performed_at AS VARCHAR), description
+            "#,
+            params![id, target_type, serial, kind, performed_at, description],
+            Self::maintenance_record_from_row,
+        )?;
+        log::info!("Logged {} maintenance for {} {}", kind, target_type, serial);
+        Ok(record)
+    }
+
+    /// Maintenance history, newest first, optionally for one drone or battery
+    pub fn get_maintenance_records(
+        &self,
+        target_type: Option<&str>,
+        serial: Option<&str>,
+    ) -> Result<Vec<MaintenanceRecord>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, target_type, serial, kind, CAST(performed_at AS VARCHAR), description
+            FROM maintenance_records
+            WHERE (CAST(? AS VARCHAR) IS NULL OR target_type = ?)
+              AND (CAST(? AS VARCHAR) IS NULL OR serial = ?)
+            ORDER BY performed_at DESC
+            "#,
+        )?;
+        let records = stmt
+            .query_map(
+                params![target_type, target_type, serial, serial],
+                Self::maintenance_record_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    /// Remove a maintenance record
+    pub fn delete_maintenance_record(&self, record_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM maintenance_records WHERE id = ?", params![record_id])?;
+        Ok(())
+    }
+
+    fn maintenance_record_from_row(row: &duckdb::Row<'_>) -> DuckResult<MaintenanceRecord> {
+        Ok(MaintenanceRecord {
+            id: row.get(0)?,
+            target_type: row.get(1)?,
+            serial: row.get(2)?,
+            kind: row.get(3)?,
+            performed_at: row.get(4)?,
+            description: row.get(5)?,
+        })
+    }
+
+    /// Flights and flight hours since the last maintenance of every drone
+    /// and battery in the logbook
+    pub fn get_maintenance_status(&self) -> Result<Vec<MaintenanceStatus>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            WITH targets AS (
+                SELECT 'drone' AS target_type, drone_serial AS serial
+                FROM flights
+                WHERE deleted_at IS NULL AND drone_serial IS NOT NULL AND drone_serial != ''
+                UNION
+                SELECT 'battery', battery_serial
+                FROM flights
+                WHERE deleted_at IS NULL AND battery_serial IS NOT NULL AND battery_serial != ''
+                UNION
+                SELECT target_type, serial FROM maintenance_records
+            ),
+            latest AS (
+                SELECT target_type, serial, MAX(performed_at) AS last_at, arg_max(kind, performed_at) AS last_kind
+                FROM maintenance_records
+                GROUP BY target_type, serial
+            )
+            SELECT
+                t.target_type,
+                t.serial,
+                CAST(l.last_at AS VARCHAR) AS last_maintenance,
+                l.last_kind,
+                COUNT(f.id)::BIGINT AS flights_since,
+                COALESCE(SUM(f.duration_secs), 0) / 3600.0 AS flight_hours_since
+            FROM targets t
+            LEFT JOIN latest l ON l.target_type = t.target_type AND l.serial = t.serial
+            LEFT JOIN flights f
+                ON f.deleted_at IS NULL
+               AND (CASE WHEN t.target_type = 'drone' THEN f.drone_serial ELSE f.battery_serial END) = t.serial
+               AND (l.last_at IS NULL OR f.start_time > l.last_at)
+            GROUP BY t.target_type, t.serial, l.last_at, l.last_kind
+            ORDER BY t.target_type, flight_hours_since DESC
+            "#,
+        )?;
+        let status = stmt
+            .query_map([], |row| {
+                Ok(MaintenanceStatus {
+                    target_type: row.get(0)?,
+                    serial: row.get(1)?,
+                    last_maintenance: row.get(2)?,
+                    last_kind: row.get(3)?,
+                    flights_since: row.get(4)?,
+                    flight_hours_since: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(status)
+    }
+
+    // ================================================================
+    // EQUIPMENT
+    // ================================================================
+
+    /// Add an item to the equipment inventory, or update it when `id` is given
+    pub fn save_equipment(
+        &self,
+        id: Option<i64>,
+        name: &str,
+        category: &str,
+        serial: Option<&str>,
+        notes: Option<&str>,
+    ) -> Result<Equipment, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidEquipment("name is required".to_string()));
+        }
+        if !EQUIPMENT_CATEGORIES.contains(&category) {
+            return Err(DatabaseError::InvalidEquipment(format!("unknown category '{}'", category)));
+        }
+        let serial = serial.map(str::trim).filter(|s| !s.is_empty());
+        let notes = notes.map(str::trim).filter(|n| !n.is_empty());
+
+        let id = id.unwrap_or_else(|| self.generate_flight_id());
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                r#"
+                INSERT INTO equipment (id, name, category, serial, notes)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    name = excluded.name,
+                    category = excluded.category,
+                    serial = excluded.serial,
+                    notes = excluded.notes
+                "#,
+                params![id, name, category, serial, notes],
+            )?;
+        }
+
+        self.get_equipment()?
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| DatabaseError::InvalidEquipment(format!("equipment {} not found", id)))
+    }
+
+    /// Remove an item from the inventory along with its flight links
+    pub fn delete_equipment(&self, equipment_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM flight_equipment WHERE equipment_id = ?", params![equipment_id])?;
+        conn.execute("DELETE FROM equipment WHERE id = ?", params![equipment_id])?;
+        Ok(())
+    }
+
+    /// The equipment inventory with usage on flights that are not in the trash
+    pub fn get_equipment(&self) -> Result<Vec<Equipment>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                e.id,
+                e.name,
+                e.category,
+                e.serial,
+                e.notes,
+                COUNT(f.id)::BIGINT AS flight_count,
+                COALESCE(SUM(f.duration_secs), 0) AS total_duration_secs,
+                CAST(MAX(f.start_time) AS VARCHAR) AS last_used
+            FROM equipment e
+            LEFT JOIN flight_equipment fe ON fe.equipment_id = e.id
+            LEFT JOIN flights f ON f.id = fe.flight_id AND f.deleted_at IS NULL
+            GROUP BY e.id, e.name, e.category, e.serial, e.notes
+            ORDER BY e.category, e.name
+            "#,
+        )?;
+        let equipment = stmt
+            .query_map([], |row| {
+                Ok(Equipment {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    category: row.get(2)?,
+                    serial: row.get(3)?,
+                    notes: row.get(4)?,
+                    flight_count: row.get(5)?,
+                    total_duration_secs: row.get(6)?,
+                    last_used: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(equipment)
+    }
+
+    /// IDs of the equipment used on a flight
+    pub fn get_flight_equipment(&self, flight_id: i64) -> Result<Vec<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT equipment_id FROM flight_equipment WHERE flight_id = ? ORDER BY equipment_id",
+        )?;
+        let ids = stmt
+            .query_map(params![flight_id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Replace the equipment used on a flight
+    pub fn set_flight_equipment(&self, flight_id: i64, equipment_ids: &[i64]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), DatabaseError> {
+            conn.execute("DELETE FROM flight_equipment WHERE flight_id = ?", params![flight_id])?;
+            for equipment_id in equipment_ids {
+                conn.execute(
+                    r#"
+                    INSERT OR IGNORE INTO flight_equipment (flight_id, equipment_id)
+                    SELECT ?, id FROM equipment WHERE id = ?
+                    "#,
+                    params![flight_id, equipment_id],
+                )?;
             }
+            Ok(())
+        })();
+        match result {
+            Ok(()) => conn.execute_batch("COMMIT")?,
             Err(e) => {
-                log::error!("Startup deduplication failed: {}", e);
-                // Don't set the flag if dedup failed - try again next startup
-                return;
+                let _ = conn.execute_batch("ROLLBACK");
+                return Err(e);
             }
         }
+        Ok(())
+    }
 
-        // Mark deduplication as complete
-        if let Err(e) = self.set_setting(SETTING_KEY, "true") {
-            log::error!("Failed to save dedup completion flag: {}", e);
+    /// IDs of the flights (not in the trash) that used a piece of equipment,
+    /// newest first
+    pub fn get_equipment_flight_ids(&self, equipment_id: i64) -> Result<Vec<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT f.id
+            FROM flight_equipment fe
+            JOIN flights f ON f.id = fe.flight_id
+            WHERE fe.equipment_id = ? AND f.deleted_at IS NULL
+            ORDER BY f.start_time DESC
+            "#,
+        )?;
+        let ids = stmt
+            .query_map(params![equipment_id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    // ================================================================
+    // SITES
+    // ================================================================
+
+    /// Home points of every flight not in the trash that has a usable one
+    pub fn get_site_home_points(&self) -> Result<Vec<SiteHomePoint>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, home_lat, home_lon, COALESCE(location_city, location_region, location_country)
+            FROM flights
+            WHERE deleted_at IS NULL
+              AND home_lat IS NOT NULL AND home_lon IS NOT NULL
+              AND NOT (abs(home_lat) < 0.000001 AND abs(home_lon) < 0.000001)
+            ORDER BY id
+            "#,
+        )?;
+        let points = stmt
+            .query_map([], |row| {
+                Ok(SiteHomePoint {
+                    flight_id: row.get(0)?,
+                    lat: row.get(1)?,
+                    lon: row.get(2)?,
+                    place: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    /// Flights with a usable home point that have no site yet
+    pub fn count_flights_without_site(&self) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let count = conn.query_row(
+            r#"
+            SELECT COUNT(*)
+            FROM flights f
+            WHERE f.deleted_at IS NULL
+              AND f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
+              AND NOT (abs(f.home_lat) < 0.000001 AND abs(f.home_lon) < 0.000001)
+              AND NOT EXISTS (SELECT 1 FROM flight_sites fs WHERE fs.flight_id = f.id)
+            "#,
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Replace every site and flight assignment; `assignments` are
+    /// `(flight_id, site_id)`. Only the stored fields of `sites` are used.
+    pub fn replace_sites(&self, sites: &[Site], assignments: &[(i64, i64)]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), DatabaseError> {
+            conn.execute("DELETE FROM flight_sites", [])?;
+            conn.execute("DELETE FROM sites", [])?;
+            {
+                let mut appender = conn.appender("sites")?;
+                for site in sites {
+                    appender.append_row(params![
+                        site.id,
+                        site.name,
+                        site.custom_name,
+                        site.center_lat,
+                        site.center_lon,
+                        site.radius_m
+                    ])?;
+                }
+            }
+            let mut appender = conn.appender("flight_sites")?;
+            for (flight_id, site_id) in assignments {
+                appender.append_row(params![flight_id, site_id])?;
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(()) => conn.execute_batch("COMMIT")?,
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                return Err(e);
+            }
         }
+        log::info!("Stored {} sites for {} flights", sites.len(), assignments.len());
+        Ok(())
     }
 
-    /// Export the entire database to a compressed backup file.
-    ///
-    /// Uses DuckDB's Parquet COPY for each table, then packs them into a single
-    /// gzip-compressed tar archive.  The resulting `.db.backup` file is portable
-    /// and can be restored with `import_backup`.
-    pub fn export_backup(&self, dest_path: &std::path::Path) -> Result<(), DatabaseError> {
-        let start = std::time::Instant::now();
-        log::info!("Starting database backup to {:?}", dest_path);
+    /// Every site with its visits and flight time, most recently flown first.
+    /// Only flights counted in statistics add to the figures.
+    pub fn get_sites(&self) -> Result<Vec<Site>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                s.id,
+                s.name,
+                COALESCE(s.custom_name, FALSE),
+                s.center_lat,
+                s.center_lon,
+                s.radius_m,
+                COUNT(f.id)::BIGINT,
+                COUNT(DISTINCT CAST(f.start_time AS DATE))::BIGINT,
+                COALESCE(SUM(f.duration_secs), 0)::DOUBLE,
+                CAST(MIN(f.start_time) AS VARCHAR),
+                CAST(MAX(f.start_time) AS VARCHAR) AS last_flight
+            FROM sites s
+            LEFT JOIN flight_sites fs ON fs.site_id = s.id
+            LEFT JOIN flights f ON f.id = fs.flight_id
+                AND f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+            GROUP BY s.id, s.name, s.custom_name, s.center_lat, s.center_lon, s.radius_m
+            ORDER BY last_flight DESC NULLS LAST, s.name
+            "#,
+        )?;
+        let sites = stmt
+            .query_map([], |row| {
+                Ok(Site {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    custom_name: row.get(2)?,
+                    center_lat: row.get(3)?,
+                    center_lon: row.get(4)?,
+                    radius_m: row.get(5)?,
+                    flight_count: row.get(6)?,
+                    visit_count: row.get(7)?,
+                    duration_secs: row.get(8)?,
+                    first_flight: row.get(9)?,
+                    last_flight: row.get(10)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(sites)
+    }
 
-        // Create a temp directory for the Parquet exports
-        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-backup-{}", uuid::Uuid::new_v4()));
-        fs::create_dir_all(&temp_dir)?;
+    /// Flights counted in statistics that belong to a site, with their
+    /// cached wind speed, oldest first
+    pub fn get_site_flights(&self) -> Result<Vec<SiteFlight>, DatabaseError> {
+        self.refresh_flight_stats_cache(false)?;
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                fs.site_id,
+                CAST(f.start_time AS VARCHAR),
+                COALESCE(f.duration_secs, 0)::DOUBLE,
+                COALESCE(f.total_distance, 0)::DOUBLE,
+                c.avg_wind_speed,
+                f.drone_model,
+                NULLIF(upper(trim(f.drone_serial)), ''),
+                f.aircraft_name,
+                d.nickname
+            FROM flight_sites fs
+            JOIN flights f ON f.id = fs.flight_id
+            LEFT JOIN flight_stats_cache c ON c.flight_id = f.id
+            LEFT JOIN drones d ON d.serial = f.drone_serial
+            WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+            ORDER BY f.start_time ASC NULLS FIRST
+            "#,
+        )?;
+        let flights = stmt
+            .query_map([], |row| {
+                Ok(SiteFlight {
+                    site_id: row.get(0)?,
+                    start_time: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    distance_m: row.get(3)?,
+                    avg_wind_speed: row.get(4)?,
+                    drone_model: row.get(5)?,
+                    drone_serial: row.get(6)?,
+                    aircraft_name: row.get(7)?,
+                    nickname: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(flights)
+    }
+
+    /// Give a site a name of the user's choosing, kept when reclustering
+    pub fn rename_site(&self, site_id: i64, name: &str) -> Result<(), DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidSite("name is required".to_string()));
+        }
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE sites SET name = ?, custom_name = TRUE WHERE id = ?",
+            params![name, site_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::InvalidSite(format!("site {} not found", site_id)));
+        }
+        Ok(())
+    }
+
+    // ================================================================
+    // CHECKLISTS
+    // ================================================================
+
+    /// Every checklist template, by name
+    pub fn get_checklist_templates(&self) -> Result<Vec<ChecklistTemplate>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, items, CAST(updated_at AS VARCHAR) FROM checklist_templates ORDER BY name",
+        )?;
+        let templates = stmt
+            .query_map([], |row| {
+                let items: String = row.get(2)?;
+                Ok(ChecklistTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    items: serde_json::from_str(&items).unwrap_or_default(),
+                    updated_at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(templates)
+    }
+
+    /// Create a checklist template, or replace it when `id` is given
+    pub fn save_checklist_template(
+        &self,
+        id: Option<i64>,
+        name: &str,
+        items: &[String],
+    ) -> Result<ChecklistTemplate, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidChecklist("name is required".to_string()));
+        }
+        let items: Vec<&str> = items.iter().map(|i| i.trim()).filter(|i| !i.is_empty()).collect();
+        if items.is_empty() {
+            return Err(DatabaseError::InvalidChecklist("a checklist needs at least one item".to_string()));
+        }
+        let items_json = serde_json::to_string(&items).unwrap_or_else(|_| "[]".to_string());
+
+        let id = id.unwrap_or_else(|| self.generate_flight_id());
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                r#"
+                INSERT INTO checklist_templates (id, name, items)
+                VALUES (?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    name = excluded.name,
+                    items = excluded.items,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+                params![id, name, items_json],
+            )?;
+        }
+
+        self.get_checklist_templates()?
+            .into_iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| DatabaseError::InvalidChecklist(format!("template {} not found", id)))
+    }
+
+    /// Remove a checklist template. Completed checklists keep their copy of the items.
+    pub fn delete_checklist_template(&self, template_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE flight_checklists SET template_id = NULL WHERE template_id = ?",
+            params![template_id],
+        )?;
+        conn.execute("DELETE FROM checklist_templates WHERE id = ?", params![template_id])?;
+        Ok(())
+    }
+
+    /// Record a checklist as completed for a flight
+    pub fn record_flight_checklist(
+        &self,
+        flight_id: i64,
+        template_id: Option<i64>,
+        name: &str,
+        items: &[ChecklistItem],
+        completed_by: Option<&str>,
+        notes: Option<&str>,
+    ) -> Result<FlightChecklist, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidChecklist("name is required".to_string()));
+        }
+        if items.is_empty() {
+            return Err(DatabaseError::InvalidChecklist("a checklist needs at least one item".to_string()));
+        }
+        let items_json = serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string());
+        let completed_by = completed_by.map(str::trim).filter(|c| !c.is_empty());
+        let notes = notes.map(str::trim).filter(|n| !n.is_empty());
+
+        let id = self.generate_flight_id();
+        {
+            let conn = self.conn.lock().unwrap();
+            let exists: bool = conn.query_row(
+                "SELECT COUNT(*) > 0 FROM flights WHERE id = ?",
+                params![flight_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(DatabaseError::FlightNotFound(flight_id));
+            }
+            conn.execute(
+                r#"
+                INSERT INTO flight_checklists (id, flight_id, template_id, name, items, completed_by, notes)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![id, flight_id, template_id, name, items_json, completed_by, notes],
+            )?;
+        }
+        log::info!("Recorded checklist '{}' for flight {}", name, flight_id);
+
+        self.get_flight_checklists(flight_id)?
+            .into_iter()
+            .find(|c| c.id == id)
+            .ok_or(DatabaseError::FlightNotFound(flight_id))
+    }
+
+    /// Checklists completed for a flight, oldest first
+    pub fn get_flight_checklists(&self, flight_id: i64) -> Result<Vec<FlightChecklist>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, flight_id, template_id, name, items, completed_by, notes, CAST(completed_at AS VARCHAR)
+            FROM flight_checklists
+            WHERE flight_id = ?
+            ORDER BY completed_at
+            "#,
+        )?;
+        let checklists = stmt
+            .query_map(params![flight_id], |row| {
+                let items: String = row.get(4)?;
+                let items: Vec<ChecklistItem> = serde_json::from_str(&items).unwrap_or_default();
+                Ok(FlightChecklist {
+                    id: row.get(0)?,
+                    flight_id: row.get(1)?,
+                    template_id: row.get(2)?,
+                    name: row.get(3)?,
+                    all_checked: items.iter().all(|i| i.checked),
+                    items,
+                    completed_by: row.get(5)?,
+                    notes: row.get(6)?,
+                    completed_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(checklists)
+    }
+
+    /// Remove a completed checklist from a flight
+    pub fn delete_flight_checklist(&self, checklist_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM flight_checklists WHERE id = ?", params![checklist_id])?;
+        Ok(())
+    }
+
+    // ================================================================
+    // DRONE PROFILES
+    // ================================================================
+
+    /// Every aircraft seen in the logbook plus any saved profile, most flown first
+    pub fn get_drone_profiles(&self) -> Result<Vec<DroneProfile>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            WITH seen AS (
+                SELECT
+                    drone_serial AS serial,
+                    MAX(drone_model) AS drone_model,
+                    MAX(aircraft_name) AS aircraft_name,
+                    COUNT(*)::BIGINT AS flight_count,
+                    COALESCE(SUM(duration_secs), 0) AS total_duration_secs,
+                    CAST(MAX(start_time) AS VARCHAR) AS last_flight,
+                    arg_max(firmware_version, start_time) FILTER (WHERE firmware_version IS NOT NULL) AS firmware_version
+                FROM flights
+                WHERE deleted_at IS NULL AND drone_serial IS NOT NULL AND drone_serial != ''
+                GROUP BY drone_serial
+            )
+            SELECT
+                COALESCE(s.serial, d.serial) AS serial,
+                s.drone_model, s.aircraft_name,
+                d.nickname, CAST(d.purchase_date AS VARCHAR) AS purchase_date, d.notes,
+                COALESCE(s.flight_count, 0) AS flight_count,
+                COALESCE(s.total_duration_secs, 0) AS total_duration_secs,
+                s.last_flight, s.firmware_version, d.registration
+            FROM seen s
+            FULL OUTER JOIN drones d ON d.serial = s.serial
+            ORDER BY flight_count DESC, serial
+            "#,
+        )?;
+        let drones = stmt
+            .query_map([], |row| {
+                Ok(DroneProfile {
+                    serial: row.get(0)?,
+                    drone_model: row.get(1)?,
+                    aircraft_name: row.get(2)?,
+                    nickname: row.get(3)?,
+                    purchase_date: row.get(4)?,
+                    notes: row.get(5)?,
+                    flight_count: row.get(6)?,
+                    total_duration_secs: row.get(7)?,
+                    last_flight: row.get(8)?,
+                    firmware_version: row.get(9)?,
+                    registration: row.get(10)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(drones)
+    }
+
+    /// Firmware versions an aircraft has flown with, oldest first
+    pub fn get_firmware_history(&self, drone_serial: &str) -> Result<Vec<FirmwareHistoryEntry>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                firmware_version,
+                CAST(MIN(start_time) AS VARCHAR) AS first_flight,
+                CAST(MAX(start_time) AS VARCHAR) AS last_flight,
+                COUNT(*)::BIGINT AS flight_count
+            FROM flights
+            WHERE deleted_at IS NULL AND drone_serial = ? AND firmware_version IS NOT NULL
+            GROUP BY firmware_version
+            ORDER BY MIN(start_time) NULLS LAST, firmware_version
+            "#,
+        )?;
+        let history = stmt
+            .query_map(params![drone_serial.trim().to_uppercase()], |row| {
+                Ok(FirmwareHistoryEntry {
+                    firmware_version: row.get(0)?,
+                    first_flight: row.get(1)?,
+                    last_flight: row.get(2)?,
+                    flight_count: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(history)
+    }
+
+    /// Save the profile of an aircraft. Blank fields are cleared, and a
+    /// profile with nothing left in it is removed.
+    pub fn set_drone_profile(
+        &self,
+        serial: &str,
+        nickname: Option<&str>,
+        purchase_date: Option<&str>,
+        notes: Option<&str>,
+        registration: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let clean = |v: Option<&str>| v.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        let (nickname, purchase_date, notes, registration) =
+            (clean(nickname), clean(purchase_date), clean(notes), clean(registration));
+        let conn = self.conn.lock().unwrap();
+
+        if nickname.is_none() && purchase_date.is_none() && notes.is_none() && registration.is_none() {
+            conn.execute("DELETE FROM drones WHERE serial = ?", params![serial])?;
+        } else {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO drones (serial, nickname, purchase_date, notes, registration, updated_at)
+                VALUES (?, ?, CAST(? AS DATE), ?, ?, CURRENT_TIMESTAMP)
+                "#,
+                params![serial, nickname, purchase_date, notes, registration],
+            )?;
+        }
+
+        log::debug!("Updated profile of drone {}", serial);
+        Ok(())
+    }
+
+    // ================================================================
+    // TAG MANAGEMENT
+    // ================================================================
+
+    /// Insert multiple tags for a flight (used during import, always type 'auto')
+    pub fn insert_flight_tags(&self, flight_id: i64, tags: &[String]) -> Result<(), DatabaseError> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        Self::insert_flight_tags_with_conn(&conn, flight_id, tags)
+    }
+
+    fn insert_flight_tags_with_conn(conn: &Connection, flight_id: i64, tags: &[String]) -> Result<(), DatabaseError> {
+        let trimmed: HashSet<&str> = tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+        let rows: Vec<Vec<&dyn ToSql>> = trimmed
+            .iter()
+            .map(|tag| -> Vec<&dyn ToSql> { vec![&flight_id, tag, &"auto"] })
+            .collect();
+        // Use INSERT OR IGNORE to avoid duplicate key errors
+        Self::insert_rows_batched(conn, "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type)", &rows)?;
+        log::debug!("Inserted {} tags for flight {}", tags.len(), flight_id);
+        Ok(())
+    }
+
+    /// Get all tags for a specific flight
+    pub fn get_flight_tags(&self, flight_id: i64) -> Result<Vec<FlightTag>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT tag, tag_type FROM flight_tags WHERE flight_id = ? ORDER BY tag",
+        )?;
+        let tags = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightTag {
+                    tag: row.get::<_, String>(0)?,
+                    tag_type: row.get::<_, String>(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    /// Add a single tag to a flight (manual user-added tag)
+    pub fn add_flight_tag(&self, flight_id: i64, tag: &str) -> Result<(), DatabaseError> {
+        let trimmed = tag.trim();
+        if trimmed.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type) VALUES (?, ?, 'manual')",
+            params![flight_id, trimmed],
+        )?;
+        Self::record_audit(&conn, "tag_add", Some(flight_id), Some(trimmed))?;
+        log::debug!("Added manual tag '{}' to flight {}", trimmed, flight_id);
+        Ok(())
+    }
+
+    /// Remove a single tag from a flight
+    pub fn remove_flight_tag(&self, flight_id: i64, tag: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM flight_tags WHERE flight_id = ? AND tag = ?",
+            params![flight_id, tag.trim()],
+        )?;
+        Self::record_audit(&conn, "tag_remove", Some(flight_id), Some(tag.trim()))?;
+        log::debug!("Removed tag '{}' from flight {}", tag, flight_id);
+        Ok(())
+    }
+
+    // ================================================================
+    // SEARCH
+    // ================================================================
+
+    /// Ranked search over flight names, file names, drone details, notes,
+    /// annotations and tags (which carry the geocoded city/country names).
+    ///
+    /// Every whitespace-separated term must match somewhere. Each term scores by
+    /// the field it hits: name 4, file name 3, tags 2, drone 2, notes 1, with a
+    /// bonus when it matches a whole tag. Matching is case-insensitive substring
+    /// matching, so partial words like "mav" find "Mavic 3".
+    pub fn search_flights(&self, query: &str, limit: usize) -> Result<Vec<FlightSearchHit>, DatabaseError> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|t| t.to_lowercase())
+            .take(MAX_SEARCH_TERMS)
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let term_scores: Vec<String> = (0..terms.len())
+            .map(|i| {
+                format!(
+                    "(CASE WHEN contains(name, ?) THEN 4 ELSE 0 END \
+                     + CASE WHEN contains(file_name, ?) THEN 3 ELSE 0 END \
+                     + CASE WHEN contains(tags, ?) THEN 2 ELSE 0 END \
+                     + CASE WHEN list_contains(tag_list, ?) THEN 1 ELSE 0 END \
+                     + CASE WHEN contains(drone, ?) THEN 2 ELSE 0 END \
+                     + CASE WHEN contains(notes, ?) THEN 1 ELSE 0 END) AS s{}",
+                    i
+                )
+            })
+            .collect();
+        let matched: Vec<String> = (0..terms.len()).map(|i| format!("s{} > 0", i)).collect();
+        let total: Vec<String> = (0..terms.len()).map(|i| format!("s{}", i)).collect();
+
+        let sql = format!(
+            r#"
+            WITH docs AS (
+                SELECT
+                    f.id,
+                    f.start_time,
+                    lower(COALESCE(f.display_name, '')) AS name,
+                    lower(f.file_name) AS file_name,
+                    lower(concat_ws(' ', f.drone_model, f.aircraft_name, f.drone_serial, f.battery_serial,
+                        (SELECT d.nickname FROM drones d WHERE d.serial = f.drone_serial))) AS drone,
+                    lower(concat_ws(' ', f.notes,
+                        (SELECT string_agg(a.text, ' ') FROM flight_annotations a WHERE a.flight_id = f.id),
+                        (SELECT string_agg(concat_ws(' ', replace(i.kind, '_', ' '), i.description), ' ')
+                         FROM incidents i WHERE i.flight_id = f.id))) AS notes,
+                    lower(concat_ws(' ', (SELECT string_agg(t.tag, ' ') FROM flight_tags t WHERE t.flight_id = f.id),
+                        f.location_city, f.location_region, f.location_country,
+                        (SELECT string_agg(e.name, ' ') FROM flight_equipment fe
+                         JOIN equipment e ON e.id = fe.equipment_id WHERE fe.flight_id = f.id))) AS tags,
+                    COALESCE((SELECT list(lower(t.tag)) FROM flight_tags t WHERE t.flight_id = f.id), []) AS tag_list
+                FROM flights f
+                WHERE f.deleted_at IS NULL
+            ),
+            scored AS (
+                SELECT id, start_time, {}
+                FROM docs
+            )
+            SELECT id, CAST({} AS DOUBLE) AS score
+            FROM scored
+            WHERE {}
+            ORDER BY score DESC, start_time DESC NULLS LAST
+            LIMIT ?
+            "#,
+            term_scores.join(",\n                       "),
+            total.join(" + "),
+            matched.join(" AND ")
+        );
+
+        let mut values: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
+        for term in &terms {
+            for _ in 0..6 {
+                values.push(Box::new(term.clone()));
+            }
+        }
+        values.push(Box::new(limit as i64));
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&sql)?;
+        let hits = stmt
+            .query_map(duckdb::params_from_iter(values.iter()), |row| {
+                Ok(FlightSearchHit {
+                    flight_id: row.get(0)?,
+                    score: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(hits)
+    }
+
+    // ================================================================
+    // AUDIT LOG
+    // ================================================================
+
+    /// Append an entry to the audit log. The flight's file name and hash are
+    /// copied into the entry so it still says where the flight came from after
+    /// the flight is purged or merged away.
+    fn record_audit(
+        conn: &Connection,
+        action: &str,
+        flight_id: Option<i64>,
+        details: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        conn.execute(
+            r#"
+            INSERT INTO audit_log (id, action, flight_id, source_file, file_hash, app_version, details)
+            SELECT ?, ?, CAST(? AS BIGINT),
+                   (SELECT file_name FROM flights WHERE id = CAST(? AS BIGINT)),
+                   (SELECT file_hash FROM flights WHERE id = CAST(? AS BIGINT)),
+                   ?, CAST(? AS VARCHAR)
+            "#,
+            params![Self::next_id(), action, flight_id, flight_id, flight_id, env!("CARGO_PKG_VERSION"), details],
+        )?;
+        Ok(())
+    }
+
+    /// Audit log entries, newest first. With a flight ID, only the entries of
+    /// that flight and of earlier imports of the same log file.
+    pub fn get_audit_log(&self, flight_id: Option<i64>, limit: usize) -> Result<Vec<AuditEntry>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, CAST(logged_at AS VARCHAR), action, flight_id, source_file, file_hash, app_version, details
+            FROM audit_log
+            WHERE CAST(? AS BIGINT) IS NULL
+               OR flight_id = CAST(? AS BIGINT)
+               OR file_hash = (SELECT file_hash FROM flights WHERE id = CAST(? AS BIGINT))
+            ORDER BY logged_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )?;
+        let entries = stmt
+            .query_map(params![flight_id, flight_id, flight_id, limit as i64], |row| {
+                Ok(AuditEntry {
+                    id: row.get(0)?,
+                    logged_at: row.get(1)?,
+                    action: row.get(2)?,
+                    flight_id: row.get(3)?,
+                    source_file: row.get(4)?,
+                    file_hash: row.get(5)?,
+                    app_version: row.get(6)?,
+                    details: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    // ================================================================
+    // SMART LISTS
+    // ================================================================
+
+    pub fn get_smart_lists(&self) -> Result<Vec<SmartList>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, filter, CAST(updated_at AS VARCHAR) FROM smart_lists ORDER BY name",
+        )?;
+        let lists = stmt
+            .query_map([], |row| {
+                let filter: String = row.get(2)?;
+                Ok(SmartList {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    filter: serde_json::from_str(&filter).unwrap_or_default(),
+                    updated_at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(lists)
+    }
+
+    /// Create a smart list, or replace it when `id` is given
+    pub fn save_smart_list(
+        &self,
+        id: Option<i64>,
+        name: &str,
+        filter: &FlightFilter,
+    ) -> Result<SmartList, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidSmartList("name is required".to_string()));
+        }
+        for date in [&filter.date_from, &filter.date_to].into_iter().flatten() {
+            if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                return Err(DatabaseError::InvalidSmartList(format!("invalid date: {}", date)));
+            }
+        }
+        let filter_json = serde_json::to_string(filter).unwrap_or_else(|_| "{}".to_string());
+
+        let id = id.unwrap_or_else(|| self.generate_flight_id());
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                r#"
+                INSERT INTO smart_lists (id, name, filter)
+                VALUES (?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    name = excluded.name,
+                    filter = excluded.filter,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+                params![id, name, filter_json],
+            )?;
+        }
+
+        self.get_smart_lists()?
+            .into_iter()
+            .find(|l| l.id == id)
+            .ok_or_else(|| DatabaseError::InvalidSmartList(format!("smart list {} not found", id)))
+    }
+
+    pub fn delete_smart_list(&self, list_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM smart_lists WHERE id = ?", params![list_id])?;
+        Ok(())
+    }
+
+    /// IDs of the flights in a smart list, newest first
+    pub fn evaluate_smart_list(&self, list_id: i64) -> Result<Vec<i64>, DatabaseError> {
+        let filter = self
+            .get_smart_lists()?
+            .into_iter()
+            .find(|l| l.id == list_id)
+            .map(|l| l.filter)
+            .ok_or_else(|| DatabaseError::InvalidSmartList(format!("smart list {} not found", list_id)))?;
+        self.evaluate_flight_filter(&filter)
+    }
+
+    /// IDs of the flights (not in the trash) matching a filter, newest first.
+    /// Follows the flight list: drone and battery serials are compared
+    /// trimmed and upper-cased, and missing values count as zero.
+    pub fn evaluate_flight_filter(&self, filter: &FlightFilter) -> Result<Vec<i64>, DatabaseError> {
+        let mut criteria: Vec<String> = Vec::new();
+        let mut values: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
+        let placeholders = |n: usize| vec!["?"; n].join(", ");
+
+        if filter.date_from.is_some() || filter.date_to.is_some() {
+            criteria.push(
+                "f.start_time IS NOT NULL \
+                 AND (CAST(? AS DATE) IS NULL OR CAST(f.start_time AS DATE) >= CAST(? AS DATE)) \
+                 AND (CAST(? AS DATE) IS NULL OR CAST(f.start_time AS DATE) <= CAST(? AS DATE))"
+                    .to_string(),
+            );
+            for date in [&filter.date_from, &filter.date_from, &filter.date_to, &filter.date_to] {
+                values.push(Box::new(date.clone()));
+            }
+        }
+        if !filter.drones.is_empty() {
+            criteria.push(format!(
+                "COALESCE(NULLIF(upper(trim(f.drone_serial)), ''), 'model:' || COALESCE(f.drone_model, 'Unknown')) IN ({})",
+                placeholders(filter.drones.len())
+            ));
+            for drone in &filter.drones {
+                values.push(Box::new(drone.clone()));
+            }
+        }
+        if !filter.batteries.is_empty() {
+            criteria.push(format!(
+                "upper(trim(f.battery_serial)) IN ({})",
+                placeholders(filter.batteries.len())
+            ));
+            for battery in &filter.batteries {
+                values.push(Box::new(battery.trim().to_uppercase()));
+            }
+        }
+        if !filter.sites.is_empty() {
+            criteria.push(format!(
+                "f.id IN (SELECT fs.flight_id FROM flight_sites fs WHERE fs.site_id IN ({}))",
+                placeholders(filter.sites.len())
+            ));
+            for site in &filter.sites {
+                values.push(Box::new(*site));
+            }
+        }
+        let ranges = [
+            ("COALESCE(f.duration_secs, 0) / 60.0", filter.min_duration_mins, filter.max_duration_mins),
+            ("COALESCE(f.max_altitude, 0)", filter.min_altitude, filter.max_altitude),
+            ("COALESCE(f.total_distance, 0)", filter.min_distance, filter.max_distance),
+        ];
+        for (expr, min, max) in ranges {
+            if min.is_none() && max.is_none() {
+                continue;
+            }
+            criteria.push(format!(
+                "(CAST(? AS DOUBLE) IS NULL OR {expr} >= CAST(? AS DOUBLE)) \
+                 AND (CAST(? AS DOUBLE) IS NULL OR {expr} <= CAST(? AS DOUBLE))"
+            ));
+            for bound in [min, min, max, max] {
+                values.push(Box::new(bound));
+            }
+        }
+        if !filter.tags.is_empty() {
+            criteria.push(format!(
+                "(SELECT COUNT(DISTINCT t.tag) FROM flight_tags t WHERE t.flight_id = f.id AND t.tag IN ({})) = {}",
+                placeholders(filter.tags.len()),
+                filter.tags.iter().collect::<HashSet<_>>().len()
+            ));
+            for tag in &filter.tags {
+                values.push(Box::new(tag.clone()));
+            }
+        }
+
+        // Inverted: a flight must fail every criterion, as in the flight list
+        let mut always = vec!["f.deleted_at IS NULL".to_string()];
+        if filter.favorites_only {
+            always.push("COALESCE(f.favorite, FALSE)".to_string());
+        }
+        let clause = criteria
+            .iter()
+            .map(|c| {
+                if filter.inverted {
+                    format!("NOT COALESCE(({}), FALSE)", c)
+                } else {
+                    format!("COALESCE(({}), FALSE)", c)
+                }
+            })
+            .chain(always)
+            .collect::<Vec<_>>()
+            .join("\n              AND ");
+        let sql = format!(
+            "SELECT f.id FROM flights f WHERE {} ORDER BY f.start_time DESC NULLS LAST, f.id DESC",
+            clause
+        );
+
+        let mut ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&sql)?;
+            let ids = stmt
+                .query_map(duckdb::params_from_iter(values.iter()), |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+
+        if let Some(search) = filter.search.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let hits: HashSet<i64> = self
+                .search_flights(search, i32::MAX as usize)?
+                .into_iter()
+                .map(|hit| hit.flight_id)
+                .collect();
+            ids.retain(|id| hits.contains(id));
+        }
+        Ok(ids)
+    }
+
+    // ================================================================
+    // EXPORT TEMPLATES
+    // ================================================================
+
+    /// Names of the telemetry columns exports can select, in table order
+    pub fn get_telemetry_columns(&self) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let columns = Self::telemetry_column_types(&conn)?;
+        Ok(columns.into_iter().map(|(name, _)| name).collect())
+    }
+
+    pub fn get_export_templates(&self) -> Result<Vec<ExportTemplate>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, format, columns, units, CAST(updated_at AS VARCHAR) \
+             FROM export_templates ORDER BY name",
+        )?;
+        let templates = stmt
+            .query_map([], |row| {
+                let format: String = row.get(2)?;
+                let columns: String = row.get(3)?;
+                let units: String = row.get(4)?;
+                Ok(ExportTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    format: serde_json::from_value(serde_json::Value::String(format))
+                        .unwrap_or(crate::export::ExportFormat::Csv),
+                    columns: serde_json::from_str(&columns).unwrap_or_default(),
+                    units: serde_json::from_str(&units).unwrap_or_default(),
+                    updated_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(templates)
+    }
+
+    /// Create an export template, or replace it when `id` is given. Columns
+    /// must be telemetry columns; duplicates are dropped.
+    pub fn save_export_template(
+        &self,
+        id: Option<i64>,
+        name: &str,
+        format: crate::export::ExportFormat,
+        columns: &[String],
+        units: &crate::export::ExportUnits,
+    ) -> Result<ExportTemplate, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidExportTemplate("name is required".to_string()));
+        }
+        let known = self.get_telemetry_columns()?;
+        let mut selected: Vec<&str> = Vec::new();
+        for column in columns.iter().map(|c| c.trim()) {
+            if !known.iter().any(|k| k == column) {
+                return Err(DatabaseError::InvalidExportTemplate(format!("unknown column: {}", column)));
+            }
+            if !selected.contains(&column) {
+                selected.push(column);
+            }
+        }
+        let columns_json = serde_json::to_string(&selected).unwrap_or_else(|_| "[]".to_string());
+        let units_json = serde_json::to_string(units).unwrap_or_else(|_| "{}".to_string());
+
+        let id = id.unwrap_or_else(|| self.generate_flight_id());
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                r#"
+                INSERT INTO export_templates (id, name, format, columns, units)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    name = excluded.name,
+                    format = excluded.format,
+                    columns = excluded.columns,
+                    units = excluded.units,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+                params![id, name, format.extension(), columns_json, units_json],
+            )?;
+        }
+
+        self.get_export_templates()?
+            .into_iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| DatabaseError::InvalidExportTemplate(format!("template {} not found", id)))
+    }
+
+    pub fn delete_export_template(&self, template_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM export_templates WHERE id = ?", params![template_id])?;
+        Ok(())
+    }
+
+    // ================================================================
+    // ANNOTATIONS
+    // ================================================================
+
+    /// Pin a note to a flight, `offset_secs` after its first telemetry point
+    pub fn add_flight_annotation(
+        &self,
+        flight_id: i64,
+        offset_secs: f64,
+        text: &str,
+    ) -> Result<FlightAnnotation, DatabaseError> {
+        let id = self.generate_flight_id();
+        let conn = self.conn.lock().unwrap();
+        let base_ms: i64 = conn.query_row(
+            r#"
+            SELECT COALESCE(
+                (SELECT MIN(timestamp_ms) FROM telemetry WHERE flight_id = ?),
+                (SELECT MIN(first_timestamp_ms) FROM telemetry_packed WHERE flight_id = ?),
+                (SELECT first_timestamp_ms FROM archived_telemetry WHERE flight_id = ?),
+                0
+            )
+            "#,
+            params![flight_id, flight_id, flight_id],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "INSERT INTO flight_annotations (id, flight_id, timestamp_ms, text) VALUES (?, ?, ?, ?)",
+            params![id, flight_id, base_ms + (offset_secs * 1000.0).round() as i64, text.trim()],
+        )?;
+        log::debug!("Added annotation {} to flight {} at {:.1}s", id, flight_id, offset_secs);
+        drop(conn);
+
+        self.get_flight_annotations(flight_id)?
+            .into_iter()
+            .find(|a| a.id == id)
+            .ok_or(DatabaseError::FlightNotFound(flight_id))
+    }
+
+    /// Change the text of an annotation
+    pub fn update_flight_annotation(&self, annotation_id: i64, text: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE flight_annotations SET text = ? WHERE id = ?",
+            params![text.trim(), annotation_id],
+        )?;
+        Ok(())
+    }
+
+    /// Remove an annotation
+    pub fn delete_flight_annotation(&self, annotation_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM flight_annotations WHERE id = ?", params![annotation_id])?;
+        Ok(())
+    }
+
+    /// Get the annotations of a flight in time order
+    pub fn get_flight_annotations(&self, flight_id: i64) -> Result<Vec<FlightAnnotation>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                a.id,
+                (a.timestamp_ms - COALESCE(
+                    (SELECT MIN(timestamp_ms) FROM telemetry WHERE flight_id = a.flight_id),
+                    (SELECT MIN(first_timestamp_ms) FROM telemetry_packed WHERE flight_id = a.flight_id),
+                    (SELECT first_timestamp_ms FROM archived_telemetry WHERE flight_id = a.flight_id),
+                    0
+                )) / 1000.0 AS offset_secs,
+                a.text,
+                CAST(a.created_at AS VARCHAR)
+            FROM flight_annotations a
+            WHERE a.flight_id = ?
+            ORDER BY a.timestamp_ms
+            "#,
+        )?;
+        let annotations = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightAnnotation {
+                    id: row.get(0)?,
+                    offset_secs: row.get(1)?,
+                    text: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(annotations)
+    }
+
+    /// Check the kind and severity of an incident against the allowed values
+    fn validate_incident(kind: &str, severity: &str) -> Result<(), DatabaseError> {
+        if !INCIDENT_KINDS.contains(&kind) {
+            return Err(DatabaseError::InvalidIncident(format!("unknown kind '{}'", kind)));
+        }
+        if !INCIDENT_SEVERITIES.contains(&severity) {
+            return Err(DatabaseError::InvalidIncident(format!("unknown severity '{}'", severity)));
+        }
+        Ok(())
+    }
+
+    /// Report an incident at `offset_secs` into a flight
+    pub fn add_incident(
+        &self,
+        flight_id: i64,
+        offset_secs: f64,
+        kind: &str,
+        severity: &str,
+        description: Option<&str>,
+    ) -> Result<Incident, DatabaseError> {
+        Self::validate_incident(kind, severity)?;
+        let description = description.map(str::trim).filter(|d| !d.is_empty());
+
+        let id = self.generate_flight_id();
+        let conn = self.conn.lock().unwrap();
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM flights WHERE id = ?",
+            params![flight_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        let base_ms: i64 = conn.query_row(
+            r#"
+            SELECT COALESCE(
+                (SELECT MIN(timestamp_ms) FROM telemetry WHERE flight_id = ?),
+                (SELECT MIN(first_timestamp_ms) FROM telemetry_packed WHERE flight_id = ?),
+                (SELECT first_timestamp_ms FROM archived_telemetry WHERE flight_id = ?),
+                0
+            )
+            "#,
+            params![flight_id, flight_id, flight_id],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "INSERT INTO incidents (id, flight_id, timestamp_ms, kind, severity, description) VALUES (?, ?, ?, ?, ?, ?)",
+            params![id, flight_id, base_ms + (offset_secs * 1000.0).round() as i64, kind, severity, description],
+        )?;
+        log::info!("Reported {} {} incident on flight {} at {:.1}s", severity, kind, flight_id, offset_secs);
+        drop(conn);
+
+        self.get_incidents(Some(flight_id), None, None)?
+            .into_iter()
+            .find(|i| i.id == id)
+            .ok_or(DatabaseError::FlightNotFound(flight_id))
+    }
+
+    /// Change the kind, severity and description of an incident
+    pub fn update_incident(
+        &self,
+        incident_id: i64,
+        kind: &str,
+        severity: &str,
+        description: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        Self::validate_incident(kind, severity)?;
+        let description = description.map(str::trim).filter(|d| !d.is_empty());
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE incidents SET kind = ?, severity = ?, description = ? WHERE id = ?",
+            params![kind, severity, description, incident_id],
+        )?;
+        Ok(())
+    }
+
+    /// Remove an incident
+    pub fn delete_incident(&self, incident_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM incidents WHERE id = ?", params![incident_id])?;
+        Ok(())
+    }
+
+    /// Incidents on flights that are not in the trash, newest flight first.
+    /// Each filter is skipped when `None`.
+    pub fn get_incidents(
+        &self,
+        flight_id: Option<i64>,
+        kind: Option<&str>,
+        severity: Option<&str>,
+    ) -> Result<Vec<Incident>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                i.id,
+                i.flight_id,
+                COALESCE(f.display_name, f.file_name),
+                CAST(f.start_time AS VARCHAR),
+                (i.timestamp_ms - COALESCE(
+                    (SELECT MIN(timestamp_ms) FROM telemetry WHERE flight_id = i.flight_id),
+                    (SELECT MIN(first_timestamp_ms) FROM telemetry_packed WHERE flight_id = i.flight_id),
+                    (SELECT first_timestamp_ms FROM archived_telemetry WHERE flight_id = i.flight_id),
+                    0
+                )) / 1000.0 AS offset_secs,
+                i.kind,
+                i.severity,
+                i.description,
+                CAST(i.created_at AS VARCHAR)
+            FROM incidents i
+            JOIN flights f ON f.id = i.flight_id
+            WHERE f.deleted_at IS NULL
+              AND (CAST(? AS BIGINT) IS NULL OR i.flight_id = ?)
+              AND (CAST(? AS VARCHAR) IS NULL OR i.kind = ?)
+              AND (CAST(? AS VARCHAR) IS NULL OR i.severity = ?)
+            ORDER BY f.start_time DESC NULLS LAST, i.timestamp_ms
+            "#,
+        )?;
+        let incidents = stmt
+            .query_map(
+                params![flight_id, flight_id, kind, kind, severity, severity],
+                |row| {
+                    Ok(Incident {
+                        id: row.get(0)?,
+                        flight_id: row.get(1)?,
+                        flight_name: row.get(2)?,
+                        flight_start_time: row.get(3)?,
+                        offset_secs: row.get(4)?,
+                        kind: row.get(5)?,
+                        severity: row.get(6)?,
+                        description: row.get(7)?,
+                        created_at: row.get(8)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(incidents)
+    }
+
+    /// Get all unique tags across all flights
+    pub fn get_all_unique_tags(&self) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT tag FROM flight_tags ORDER BY tag",
+        )?;
+        let tags = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    /// Replace all auto tags for a flight with new ones (keeps manual tags)
+    pub fn replace_auto_tags(&self, flight_id: i64, new_tags: &[String]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        // Delete existing auto tags
+        conn.execute(
+            "DELETE FROM flight_tags WHERE flight_id = ? AND tag_type = 'auto'",
+            params![flight_id],
+        )?;
+        // Insert new auto tags
+        for tag in new_tags {
+            let trimmed = tag.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            conn.execute(
+                "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type) VALUES (?, ?, 'auto')",
+                params![flight_id, trimmed],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove all auto-generated tags from all flights (keeps manual tags)
+    /// Returns the number of auto tags removed
+    pub fn remove_all_auto_tags(&self) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM flight_tags WHERE tag_type = 'auto'",
+            [],
+        )?;
+        Ok(removed)
+    }
+
+    /// Get all flight IDs (for bulk operations like tag regeneration)
+    pub fn get_all_flight_ids(&self) -> Result<Vec<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id FROM flights WHERE deleted_at IS NULL ORDER BY id")?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Check if a file has already been imported (by hash)
+    /// Returns the display_name of the matching flight if found, None otherwise.
+    /// Flights in the trash don't count; see `trashed_flight_for_file`.
+    pub fn is_file_imported(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
+        self.flight_for_file(file_hash, false)
+    }
+
+    /// Display name of the flight in the trash that a file (by hash) was
+    /// imported as, so the import can point at a restore instead
+    pub fn trashed_flight_for_file(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
+        self.flight_for_file(file_hash, true)
+    }
+
+    fn flight_for_file(&self, file_hash: &str, deleted: bool) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let result: Option<String> = conn.query_row(
+            "SELECT COALESCE(display_name, file_name) FROM flights WHERE file_hash = ? AND (deleted_at IS NOT NULL) = ? LIMIT 1",
+            params![file_hash, deleted],
+            |row| row.get(0),
+        ).optional()?;
+        if result.is_some() {
+            return Ok(result);
+        }
+
+        // Files linked to an existing flight as an attachment count as imported too
+        let attached: Option<String> = conn.query_row(
+            r#"
+            SELECT COALESCE(f.display_name, f.file_name)
+            FROM flight_attachments a
+            JOIN flights f ON f.id = a.flight_id
+            WHERE a.file_hash = ? AND (f.deleted_at IS NOT NULL) = ?
+            LIMIT 1
+            "#,
+            params![file_hash, deleted],
+            |row| row.get(0),
+        ).optional()?;
+
+        Ok(attached)
+    }
+
+    /// Check if a file belongs to a deleted flight and must be skipped by sync
+    pub fn is_file_blacklisted(&self, file_hash: &str) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM import_blacklist WHERE file_hash = ?",
+            params![file_hash],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// All blacklisted file hashes
+    pub fn get_import_blacklist(&self) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT file_hash FROM import_blacklist ORDER BY added_at")?;
+        let hashes = stmt.query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(hashes)
+    }
+
+    /// Keep sync and the folder watcher from importing these files again
+    pub fn add_to_import_blacklist(&self, file_hashes: &[String]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        for hash in file_hashes.iter().filter(|h| !h.is_empty()) {
+            conn.execute(
+                "INSERT OR IGNORE INTO import_blacklist (file_hash) VALUES (?)",
+                params![hash],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Allow a file to be imported again, e.g. after a manual import
+    pub fn remove_from_import_blacklist(&self, file_hash: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM import_blacklist WHERE file_hash = ?", params![file_hash])?;
+        Ok(())
+    }
+
+    /// Empty the blacklist. Returns how many files were removed from it.
+    pub fn clear_import_blacklist(&self) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM import_blacklist", params![])?;
+        log::info!("Cleared import blacklist: {} files", removed);
+        Ok(removed)
+    }
+
+    /// Watched-folder files already handled, with the size and modified
+    /// time (ms since the epoch) they had at the time
+    pub fn get_watched_files(&self) -> Result<Vec<(String, u64, Option<i64>)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT path, size, modified_ms FROM watched_files")?;
+        let files = stmt.query_map([], |row| {
+            let size: i64 = row.get(1)?;
+            Ok((row.get(0)?, size as u64, row.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(files)
+    }
+
+    /// Remember that a watched file was handled in this state
+    pub fn mark_watched_file(&self, path: &str, size: u64, modified_ms: Option<i64>) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO watched_files (path, size, modified_ms) VALUES (?, ?, ?)",
+            params![path, size as i64, modified_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Get all file hashes from existing flights
+    /// Used by sync to filter out already-imported files (web feature only)
+    #[allow(dead_code)]
+    pub fn get_all_file_hashes(&self) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT file_hash FROM flights WHERE file_hash IS NOT NULL AND file_hash != ''"
+        )?;
+        let hashes = stmt.query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(hashes)
+    }
+
+    /// Check if a duplicate flight exists based on exact signature match (drone_serial + battery_serial + start_time).
+    /// Returns the display_name of the matching flight if found, None otherwise.
+    /// If any of the signature fields are None, returns None (can't reliably deduplicate).
+    pub fn is_duplicate_flight(
+        &self,
+        drone_serial: Option<&str>,
+        battery_serial: Option<&str>,
+        start_time: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Option<String>, DatabaseError> {
+        // If any key field is missing, we can't reliably check for duplicates
+        let (drone, battery, time) = match (drone_serial, battery_serial, start_time) {
+            (Some(d), Some(b), Some(t)) if !d.is_empty() && !b.is_empty() => (d, b, t),
+            _ => return Ok(None),
+        };
+
+        let conn = self.conn.lock().unwrap();
+
+        // Check for existing flight with exact same signature (drone, battery, start_time)
+        let result: Option<String> = conn.query_row(
+            r#"
+            SELECT COALESCE(display_name, file_name) FROM flights 
+            WHERE drone_serial = ?
+              AND battery_serial = ?
+              AND start_time IS NOT NULL
+              AND start_time = ?::TIMESTAMPTZ
+            LIMIT 1
+            "#,
+            params![drone, battery, time.to_rfc3339()],
+            |row| row.get(0),
+        ).optional()?;
+
+        Ok(result)
+    }
+
+    /// Remove duplicate flights from the database based on exact signature match (drone_serial + battery_serial + start_time).
+    /// Keeps the flight with the most telemetry points for each duplicate group;
+    /// the others are moved to the trash so a wrong match can be restored.
+    /// Returns the number of duplicates removed.
+    pub fn deduplicate_flights(&self) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let start = std::time::Instant::now();
+        log::info!("Starting flight deduplication...");
+
+        let mut total_removed = 0;
+
+        // Method 1: Remove exact file_hash duplicates (keep the one with most telemetry points)
+        let hash_duplicates = conn.execute(
+            r#"
+            WITH hash_duplicates AS (
+                SELECT file_hash, COUNT(*) as cnt
+                FROM flights
+                WHERE deleted_at IS NULL AND file_hash IS NOT NULL AND file_hash != ''
+                GROUP BY file_hash
+                HAVING COUNT(*) > 1
+            ),
+            ranked_flights AS (
+                SELECT f.id, f.file_hash, f.point_count,
+                       ROW_NUMBER() OVER (PARTITION BY f.file_hash ORDER BY f.point_count DESC, f.id ASC) as rn
+                FROM flights f
+                WHERE f.deleted_at IS NULL AND f.file_hash IN (SELECT file_hash FROM hash_duplicates)
+            )
+            UPDATE flights SET deleted_at = CURRENT_TIMESTAMP WHERE id IN (
+                SELECT id FROM ranked_flights WHERE rn > 1
+            )
+            "#,
+            [],
+        )?;
+        total_removed += hash_duplicates;
+        log::info!("Moved {} file_hash duplicates to the trash", hash_duplicates);
+
+        // Method 2: Remove signature-based duplicates (same drone + battery + exact same start_time)
+        // This catches re-imports of the same flight from different sources
+        let signature_duplicates = conn.execute(
+            r#"
+            WITH flight_pairs AS (
+                SELECT 
+                    f1.id as id1,
+                    f2.id as id2,
+                    f1.point_count as points1,
+                    f2.point_count as points2
+                FROM flights f1
+                JOIN flights f2 ON f1.drone_serial = f2.drone_serial
+                    AND f1.battery_serial = f2.battery_serial
+                    AND f1.id < f2.id
+                    AND f1.start_time = f2.start_time
+                WHERE f1.deleted_at IS NULL AND f2.deleted_at IS NULL
+                  AND f1.drone_serial IS NOT NULL AND f1.drone_serial != ''
+                  AND f1.battery_serial IS NOT NULL AND f1.battery_serial != ''
+                  AND f1.start_time IS NOT NULL
+                  AND f2.start_time IS NOT NULL
+            ),
+            ids_to_delete AS (
+                SELECT CASE 
+                    WHEN points1 >= points2 THEN id2
+                    ELSE id1
+                END as id
+                FROM flight_pairs
+            )
+            UPDATE flights SET deleted_at = CURRENT_TIMESTAMP WHERE id IN (SELECT DISTINCT id FROM ids_to_delete)
+            "#,
+            [],
+        )?;
+        total_removed += signature_duplicates;
+        log::info!("Moved {} signature-based duplicates to the trash", signature_duplicates);
+
+        // Clean up orphaned telemetry data
+        let orphaned_telemetry = conn.execute(
+            "DELETE FROM telemetry WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        )?;
+        log::info!("Cleaned up {} orphaned telemetry records", orphaned_telemetry);
+
+        // Clean up orphaned tags
+        let orphaned_tags = conn.execute(
+            "DELETE FROM flight_tags WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        )?;
+        log::info!("Cleaned up {} orphaned tags", orphaned_tags);
+
+        let _ = conn.execute(
+            "DELETE FROM flight_attachments WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_missions WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM home_points WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_events WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_media WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM motor_telemetry WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM battery_cells WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_annotations WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM incidents WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_equipment WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_sites WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_stats_cache WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_checklists WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM telemetry_packed WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM archived_telemetry WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_documents WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+        self.remove_orphan_documents(&conn);
+
+        if total_removed > 0 {
+            Self::record_audit(
+                &conn,
+                "deduplicate",
+                None,
+                Some(&format!("{} duplicate flights moved to the trash", total_removed)),
+            )?;
+        }
+
+        log::info!(
+            "Deduplication complete in {:.1}s: {} total duplicate flights removed",
+            start.elapsed().as_secs_f64(),
+            total_removed
+        );
+
+        Ok(total_removed)
+    }
+
+    // ================================================================
+    // FLIGHT STITCHING
+    // ================================================================
+
+    /// Find consecutive flights of the same aircraft that were probably split
+    /// across two log files (app restart, RC reconnect). A pair qualifies when
+    /// the second log starts within `max_gap_secs` of the first one ending, the
+    /// battery serial matches (when both are known) and the battery level is
+    /// continuous across the gap.
+    pub fn find_stitch_candidates(&self, max_gap_secs: f64) -> Result<Vec<StitchCandidate>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            r#"
+            WITH ordered AS (
+                SELECT
+                    id,
+                    display_name,
+                    battery_serial,
+                    start_time,
+                    end_time,
+                    LEAD(id) OVER w AS next_id,
+                    LEAD(display_name) OVER w AS next_name,
+                    LEAD(battery_serial) OVER w AS next_battery_serial,
+                    LEAD(start_time) OVER w AS next_start
+                FROM flights
+                WHERE deleted_at IS NULL
+                  AND drone_serial IS NOT NULL AND drone_serial != ''
+                  AND start_time IS NOT NULL
+                  AND end_time IS NOT NULL
+                WINDOW w AS (PARTITION BY drone_serial ORDER BY start_time)
+            ),
+            pairs AS (
+                SELECT
+                    id, display_name, next_id, next_name,
+                    date_diff('millisecond', end_time, next_start) / 1000.0 AS gap_secs
+                FROM ordered
+                WHERE next_id IS NOT NULL
+                  AND (battery_serial IS NULL OR battery_serial = ''
+                       OR next_battery_serial IS NULL OR next_battery_serial = ''
+                       OR battery_serial = next_battery_serial)
+            )
+            SELECT
+                p.id,
+                p.display_name,
+                p.next_id,
+                p.next_name,
+                p.gap_secs
+            FROM pairs p
+            WHERE p.gap_secs >= ? AND p.gap_secs <= ?
+            ORDER BY p.id
+            "#,
+        )?;
+
+        let mut candidates = stmt
+            .query_map(params![-STITCH_MAX_OVERLAP_SECS, max_gap_secs], |row| {
+                Ok(StitchCandidate {
+                    first_id: row.get(0)?,
+                    first_name: row.get(1)?,
+                    second_id: row.get(2)?,
+                    second_name: row.get(3)?,
+                    gap_secs: row.get(4)?,
+                    end_battery_percent: None,
+                    start_battery_percent: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+        // Packed and archived flights keep their telemetry outside the row
+        // table, so the battery levels are read from wherever each flight is stored
+        for candidate in &mut candidates {
+            candidate.end_battery_percent = self.edge_battery_percent(&conn, candidate.first_id, false)?;
+            candidate.start_battery_percent = self.edge_battery_percent(&conn, candidate.second_id, true)?;
+        }
+        let candidates = candidates
+            .into_iter()
+            .filter(|c| match (c.end_battery_percent, c.start_battery_percent) {
+                // A swapped or recharged pack jumps up; a long idle period drops far
+                (Some(end), Some(start)) => {
+                    start <= end + STITCH_BATTERY_TOLERANCE && end - start <= STITCH_MAX_BATTERY_DROP
+                }
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+
+        log::debug!("Found {} stitch candidates (max gap {}s)", candidates.len(), max_gap_secs);
+        Ok(candidates)
+    }
+
+    /// First (`first`) or last logged battery percentage of a flight
+    fn edge_battery_percent(&self, conn: &Connection, flight_id: i64, first: bool) -> Result<Option<i32>, DatabaseError> {
+        let source = self.telemetry_source(conn, flight_id)?;
+        let percent = conn
+            .query_row(
+                &format!(
+                    "SELECT battery_percent FROM {} WHERE flight_id = ? AND battery_percent IS NOT NULL ORDER BY timestamp_ms {} LIMIT 1",
+                    source,
+                    if first { "ASC" } else { "DESC" }
+                ),
+                params![flight_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(percent)
+    }
+
+    /// Merge `second_id` into `first_id`: telemetry is appended with its
+    /// timestamps shifted onto the first flight's clock, stats and tags are
+    /// combined and the second flight is removed. Runs in one transaction.
+    pub fn merge_flights(&self, first_id: i64, second_id: i64) -> Result<(), DatabaseError> {
+        if first_id == second_id {
+            return Err(DatabaseError::InvalidMerge("a flight cannot be merged with itself".to_string()));
+        }
+        let start = std::time::Instant::now();
+        let conn = self.conn.lock().unwrap();
+
+        let offset_ms: Option<i64> = conn
+            .query_row(
+                r#"
+                SELECT date_diff('millisecond', a.start_time, b.start_time)
+                FROM flights a, flights b
+                WHERE a.id = ? AND b.id = ?
+                "#,
+                params![first_id, second_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or(DatabaseError::FlightNotFound(second_id))?;
+        let offset_ms = offset_ms
+            .ok_or_else(|| DatabaseError::InvalidMerge("both flights need a start time".to_string()))?;
+        if offset_ms < 0 {
+            return Err(DatabaseError::InvalidMerge("the second flight starts before the first one".to_string()));
+        }
+        let repack = self.packed_storage_enabled();
+
+        conn.execute_batch("BEGIN TRANSACTION")?;
+
+        let mut sidecars = Vec::new();
+        let result: Result<(), DatabaseError> = (|| {
+            // Segments are merged row by row; packed and archived ones are loaded first
+            Self::unpack_flight_with_conn(&conn, first_id)?;
+            Self::unpack_flight_with_conn(&conn, second_id)?;
+            sidecars.extend(self.unarchive_flight_with_conn(&conn, first_id)?);
+            sidecars.extend(self.unarchive_flight_with_conn(&conn, second_id)?);
+
+            // Drop points of the first flight that would collide with the
+            // shifted second segment (overlapping logs of the same moments)
+            conn.execute(
+                r#"
+                DELETE FROM telemetry
+                WHERE flight_id = ?
+                  AND timestamp_ms >= (SELECT MIN(timestamp_ms) + ? FROM telemetry WHERE flight_id = ?)
+                "#,
+                params![first_id, offset_ms, second_id],
+            )?;
+            conn.execute(
+                r#"
+                INSERT INTO telemetry
+                SELECT * REPLACE (CAST(? AS BIGINT) AS flight_id, timestamp_ms + ? AS timestamp_ms)
+                FROM telemetry
+                WHERE flight_id = ?
+                "#,
+                params![first_id, offset_ms, second_id],
+            )?;
+            conn.execute("DELETE FROM telemetry WHERE flight_id = ?", params![second_id])?;
+            conn.execute(
+                r#"
+                DELETE FROM battery_cells
+                WHERE flight_id = ?
+                  AND timestamp_ms >= (SELECT MIN(timestamp_ms) + ? FROM battery_cells WHERE flight_id = ?)
+                "#,
+                params![first_id, offset_ms, second_id],
+            )?;
+            conn.execute(
+                r#"
+                INSERT INTO battery_cells
+                SELECT * REPLACE (CAST(? AS BIGINT) AS flight_id, timestamp_ms + ? AS timestamp_ms)
+                FROM battery_cells
+                WHERE flight_id = ?
+                "#,
+                params![first_id, offset_ms, second_id],
+            )?;
+            conn.execute("DELETE FROM battery_cells WHERE flight_id = ?", params![second_id])?;
+
+            conn.execute(
+                r#"
+                UPDATE flights SET
+                    end_time = GREATEST(flights.end_time, b.end_time),
+                    duration_secs = date_diff('millisecond', flights.start_time, GREATEST(flights.end_time, b.end_time)) / 1000.0,
+                    total_distance = COALESCE(flights.total_distance, 0) + COALESCE(b.total_distance, 0),
+                    max_altitude = GREATEST(COALESCE(flights.max_altitude, b.max_altitude), COALESCE(b.max_altitude, flights.max_altitude)),
+                    max_speed = GREATEST(COALESCE(flights.max_speed, b.max_speed), COALESCE(b.max_speed, flights.max_speed)),
+                    max_distance_from_home = GREATEST(
+                        COALESCE(flights.max_distance_from_home, b.max_distance_from_home),
+                        COALESCE(b.max_distance_from_home, flights.max_distance_from_home)
+                    ),
+                    point_count = (SELECT COUNT(*) FROM telemetry WHERE flight_id = ?),
+                    partial = COALESCE(flights.partial, FALSE) OR COALESCE(b.partial, FALSE),
+                    favorite = COALESCE(flights.favorite, FALSE) OR COALESCE(b.favorite, FALSE),
+                    excluded_from_stats = COALESCE(flights.excluded_from_stats, FALSE) AND COALESCE(b.excluded_from_stats, FALSE)
+                FROM flights b
+                WHERE flights.id = ? AND b.id = ?
+                "#,
+                params![first_id, first_id, second_id],
+            )?;
+
+            let _ = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type)
+                SELECT ?, tag, tag_type FROM flight_tags WHERE flight_id = ?
+                "#,
+                params![first_id, second_id],
+            );
+            let _ = conn.execute("DELETE FROM flight_tags WHERE flight_id = ?", params![second_id]);
+            let _ = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_equipment (flight_id, equipment_id)
+                SELECT ?, equipment_id FROM flight_equipment WHERE flight_id = ?
+                "#,
+                params![first_id, second_id],
+            );
+            let _ = conn.execute("DELETE FROM flight_equipment WHERE flight_id = ?", params![second_id]);
+            // The merged flight keeps the first flight's home point and site
+            let _ = conn.execute("DELETE FROM flight_sites WHERE flight_id = ?", params![second_id]);
+            // The merged telemetry needs its figures computed again
+            let _ = conn.execute(
+                "DELETE FROM flight_stats_cache WHERE flight_id IN (?, ?)",
+                params![first_id, second_id],
+            );
+            Self::classify_daylight_with_conn(&conn, &format!("id = {}", first_id))?;
+            Self::store_total_distance(&conn, "telemetry", &first_id.to_string())?;
+            let _ = conn.execute(
+                "UPDATE flight_checklists SET flight_id = ? WHERE flight_id = ?",
+                params![first_id, second_id],
+            );
+            let _ = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_missions (flight_id, mission_id, coverage)
+                SELECT ?, mission_id, coverage FROM flight_missions WHERE flight_id = ?
+                "#,
+                params![first_id, second_id],
+            );
+            let _ = conn.execute("DELETE FROM flight_missions WHERE flight_id = ?", params![second_id]);
+            let _ = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO home_points
+                SELECT * REPLACE (CAST(? AS BIGINT) AS flight_id, timestamp_ms + ? AS timestamp_ms)
+                FROM home_points
+                WHERE flight_id = ?
+                "#,
+                params![first_id, offset_ms, second_id],
+            );
+            let _ = conn.execute("DELETE FROM home_points WHERE flight_id = ?", params![second_id]);
+            let _ = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_events
+                SELECT * REPLACE (CAST(? AS BIGINT) AS flight_id, timestamp_ms + ? AS timestamp_ms)
+                FROM flight_events
+                WHERE flight_id = ? AND event_type NOT IN ('photo', 'video')
+                "#,
+                params![first_id, offset_ms, second_id],
+            );
+            conn.execute("DELETE FROM flight_events WHERE flight_id = ?", params![second_id])?;
+            // Recording segments may continue across the split, so rebuild
+            conn.execute(
+                "DELETE FROM flight_events WHERE flight_id = ? AND event_type IN ('photo', 'video')",
+                params![first_id],
+            )?;
+            Self::record_capture_events_with_conn(&conn, Some(first_id))?;
+            let _ = conn.execute(
+                "UPDATE flight_media SET flight_id = ? WHERE flight_id = ?",
+                params![first_id, second_id],
+            );
+            let _ = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO motor_telemetry
+                SELECT * REPLACE (CAST(? AS BIGINT) AS flight_id, timestamp_ms + ? AS timestamp_ms)
+                FROM motor_telemetry
+                WHERE flight_id = ?
+                "#,
+                params![first_id, offset_ms, second_id],
+            );
+            let _ = conn.execute("DELETE FROM motor_telemetry WHERE flight_id = ?", params![second_id]);
+            let _ = conn.execute(
+                "UPDATE flight_annotations SET flight_id = ?, timestamp_ms = timestamp_ms + ? WHERE flight_id = ?",
+                params![first_id, offset_ms, second_id],
+            );
+            let _ = conn.execute(
+                "UPDATE incidents SET flight_id = ?, timestamp_ms = timestamp_ms + ? WHERE flight_id = ?",
+                params![first_id, offset_ms, second_id],
+            );
+            let _ = conn.execute(
+                "UPDATE flight_documents SET flight_id = ? WHERE flight_id = ?",
+                params![first_id, second_id],
+            );
+            // The second segment's source file stays known, so imports and
+            // sync don't bring it back as a flight of its own
+            conn.execute(
+                "UPDATE flight_attachments SET flight_id = ? WHERE flight_id = ?",
+                params![first_id, second_id],
+            )?;
+            conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_attachments (file_hash, flight_id, file_name, source_format, point_count)
+                SELECT
+                    file_hash, ?, file_name,
+                    COALESCE(NULLIF(lower(regexp_extract(file_name, '\.([A-Za-z0-9]+)$', 1)), ''), 'log'),
+                    point_count
+                FROM flights
+                WHERE id = ? AND file_hash IS NOT NULL
+                "#,
+                params![first_id, second_id],
+            )?;
+            Self::record_audit(&conn, "merge", Some(second_id), Some(&format!("merged into flight {}", first_id)))?;
+            conn.execute("DELETE FROM flights WHERE id = ?", params![second_id])?;
+            Self::record_audit(&conn, "merge", Some(first_id), Some(&format!("flight {} merged in", second_id)))?;
+            if repack {
+                Self::pack_flight_with_conn(&conn, first_id)?;
+            }
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                conn.execute_batch("COMMIT")?;
+                for path in sidecars {
+                    let _ = fs::remove_file(path);
+                }
+                log::info!(
+                    "Merged flight {} into {} in {:.1}ms",
+                    second_id,
+                    first_id,
+                    start.elapsed().as_secs_f64() * 1000.0
+                );
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(rollback_err) = conn.execute_batch("ROLLBACK") {
+                    log::error!("Failed to roll back flight merge: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    // ================================================================
+    // CROSS-FORMAT ATTACHMENTS
+    // ================================================================
+
+    /// Find existing app-log flights that may be the same physical flight as a
+    /// newly parsed aircraft DAT log. Takeoff location must be close; the
+    /// serial is only compared when both sides know it (DAT logs carry none).
+    /// DAT logs have no recorded clock either: their time is the file's
+    /// modified time, which for a file copied off the aircraft later is the
+    /// copy time. So `approx_time` only puts the nearest flights first and
+    /// never rules one out.
+    /// Returns `(flight_id, display_name)` pairs; the caller confirms the match
+    /// by how much of the ground track the two logs share.
+    pub fn find_cross_format_candidates(
+        &self,
+        drone_serial: Option<&str>,
+        approx_time: Option<chrono::DateTime<chrono::Utc>>,
+        home_lat: f64,
+        home_lon: f64,
+    ) -> Result<Vec<(i64, String)>, DatabaseError> {
+        let mut sql = String::from(
+            r#"
+            SELECT id, COALESCE(display_name, file_name)
+            FROM flights
+            WHERE deleted_at IS NULL AND home_lat IS NOT NULL AND home_lon IS NOT NULL
+              AND abs(home_lat - ?) <= ? AND abs(home_lon - ?) <= ?
+              AND upper(file_name) NOT LIKE '%.DAT'
+            "#,
+        );
+        let mut values: Vec<Box<dyn duckdb::ToSql>> = vec![
+            Box::new(home_lat),
+            Box::new(CROSS_FORMAT_HOME_TOLERANCE_DEG),
+            Box::new(home_lon),
+            Box::new(CROSS_FORMAT_HOME_TOLERANCE_DEG),
+        ];
+        if let Some(serial) = drone_serial.filter(|s| !s.is_empty()) {
+            sql.push_str(" AND (drone_serial IS NULL OR drone_serial = '' OR drone_serial = ?)");
+            values.push(Box::new(serial.to_string()));
+        }
+        match approx_time {
+            Some(time) => {
+                sql.push_str(
+                    " ORDER BY abs(date_diff('second', start_time, ?::TIMESTAMPTZ)) NULLS LAST, start_time DESC LIMIT 20",
+                );
+                values.push(Box::new(time.to_rfc3339()));
+            }
+            None => sql.push_str(" ORDER BY start_time DESC LIMIT 20"),
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&sql)?;
+        let candidates = stmt
+            .query_map(duckdb::params_from_iter(values.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(candidates)
+    }
+
+    /// Load the `(timestamp_ms, lat, lon)` track of a flight, skipping points
+    /// without a GPS fix
+    pub fn get_flight_track(&self, flight_id: i64) -> Result<Vec<(i64, f64, f64)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let source = self.telemetry_source(&conn, flight_id)?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT timestamp_ms, latitude, longitude
+            FROM {source}
+            WHERE flight_id = ?
+              AND latitude IS NOT NULL AND longitude IS NOT NULL
+              AND NOT (latitude = 0 AND longitude = 0)
+            ORDER BY timestamp_ms
+            "#,
+        ))?;
+        let track = stmt
+            .query_map(params![flight_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(track)
+    }
+
+    /// Link an aircraft DAT log to the flight it recorded and merge in what
+    /// the flight's own log lacks: per-motor samples and the compass
+    /// interference level. `offset_ms` moves DAT timestamps onto the flight's
+    /// timeline. Motor samples are only added if the flight has none yet.
+    pub fn attach_dat_log(
+        &self,
+        flight_id: i64,
+        file_hash: &str,
+        file_name: &str,
+        points: &[TelemetryPoint],
+        motors: &[MotorSample],
+        offset_ms: i64,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let repack = self.packed_storage_enabled();
+        let mut sidecar = None;
+
+        Self::in_transaction(&conn, || {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO flight_attachments (file_hash, flight_id, file_name, source_format, point_count)
+                VALUES (?, ?, ?, 'dat', ?)
+                "#,
+                params![file_hash, flight_id, file_name, points.len() as i64],
+            )?;
+
+            let has_motors: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM motor_telemetry WHERE flight_id = ?",
+                params![flight_id],
+                |row| row.get(0),
+            )?;
+            if has_motors == 0 {
+                let shifted: Vec<MotorSample> = motors
+                    .iter()
+                    .map(|m| MotorSample { timestamp_ms: m.timestamp_ms + offset_ms, ..m.clone() })
+                    .collect();
+                Self::insert_motor_samples_with_conn(&conn, flight_id, &shifted)?;
+            }
+
+            let compass: Vec<(i64, f64)> = points
+                .iter()
+                .filter_map(|p| p.compass_interference.map(|c| (p.timestamp_ms + offset_ms, c)))
+                .collect();
+            if compass.is_empty() {
+                return Ok(());
+            }
+
+            // Packed and archived rows are loaded to be updated in place
+            Self::unpack_flight_with_conn(&conn, flight_id)?;
+            sidecar = self.unarchive_flight_with_conn(&conn, flight_id)?;
+
+            let timestamps: Vec<i64> = {
+                let mut stmt = conn.prepare(
+                    "SELECT timestamp_ms FROM telemetry WHERE flight_id = ? AND compass_interference IS NULL ORDER BY timestamp_ms",
+                )?;
+                let timestamps = stmt
+                    .query_map(params![flight_id], |row| row.get(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                timestamps
+            };
+            let sample_times: Vec<i64> = compass.iter().map(|c| c.0).collect();
+            conn.execute_batch(
+                "CREATE OR REPLACE TEMP TABLE dat_compass (timestamp_ms BIGINT, compass_interference DOUBLE)",
+            )?;
+            {
+                let mut appender = conn.appender("dat_compass")?;
+                for (t, i) in timestamps.iter().zip(align_samples(&sample_times, &timestamps)) {
+                    if let Some(i) = i {
+                        appender.append_row(params![t, compass[i].1])?;
+                    }
+                }
+                appender.flush()?;
+            }
+            let updated = conn.execute(
+                r#"
+                UPDATE telemetry SET compass_interference = d.compass_interference
+                FROM dat_compass d
+                WHERE telemetry.flight_id = ? AND telemetry.timestamp_ms = d.timestamp_ms
+                "#,
+                params![flight_id],
+            )?;
+            conn.execute_batch("DROP TABLE dat_compass")?;
+            log::debug!("Filled compass interference of {} points of flight {} from DAT", updated, flight_id);
+
+            if repack {
+                Self::pack_flight_with_conn(&conn, flight_id)?;
+            }
+            Ok(())
+        })?;
+
+        if let Some(path) = sidecar {
+            let _ = fs::remove_file(path);
+        }
+        log::info!("Attached {} (dat) to flight {}", file_name, flight_id);
+        Ok(())
+    }
+
+    /// Get the files attached to a flight
+    pub fn get_flight_attachments(&self, flight_id: i64) -> Result<Vec<FlightAttachment>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT file_hash, file_name, source_format, COALESCE(point_count, 0), CAST(attached_at AS VARCHAR)
+            FROM flight_attachments
+            WHERE flight_id = ?
+            ORDER BY attached_at
+            "#,
+        )?;
+        let attachments = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightAttachment {
+                    file_hash: row.get(0)?,
+                    file_name: row.get(1)?,
+                    source_format: row.get(2)?,
+                    point_count: row.get(3)?,
+                    attached_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(attachments)
+    }
+
+    // ================================================================
+    // HOME POINTS
+    // ================================================================
+
+    /// Store the home point history of a flight
+    pub fn insert_home_points(&self, flight_id: i64, home_points: &[HomePoint]) -> Result<(), DatabaseError> {
+        if home_points.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        Self::in_transaction(&conn, || Self::insert_home_points_with_conn(&conn, flight_id, home_points))
+    }
+
+    fn insert_home_points_with_conn(
+        conn: &Connection,
+        flight_id: i64,
+        home_points: &[HomePoint],
+    ) -> Result<(), DatabaseError> {
+        // One statement may not replace the same key twice; keep the last sample
+        let mut latest: HashMap<i64, &HomePoint> = HashMap::with_capacity(home_points.len());
+        for home in home_points {
+            latest.insert(home.timestamp_ms, home);
+        }
+        let rows: Vec<Vec<&dyn ToSql>> = latest
+            .into_values()
+            .map(|home| -> Vec<&dyn ToSql> {
+                vec![&flight_id, &home.timestamp_ms, &home.lat, &home.lon, &home.altitude]
+            })
+            .collect();
+        Self::insert_rows_batched(
+            conn,
+            "INSERT OR REPLACE INTO home_points (flight_id, timestamp_ms, latitude, longitude, altitude)",
+            &rows,
+        )
+    }
+
+    /// Get the home point history of a flight, oldest first
+    pub fn get_home_points(&self, flight_id: i64) -> Result<Vec<HomePoint>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT timestamp_ms, latitude, longitude, altitude
+            FROM home_points
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms
+            "#,
+        )?;
+        let home_points = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(HomePoint {
+                    timestamp_ms: row.get(0)?,
+                    lat: row.get(1)?,
+                    lon: row.get(2)?,
+                    altitude: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(home_points)
+    }
+
+    // ================================================================
+    // MOTOR TELEMETRY
+    // ================================================================
+
+    /// Store per-motor ESC samples of a flight
+    pub fn insert_motor_samples(&self, flight_id: i64, motors: &[MotorSample]) -> Result<(), DatabaseError> {
+        if motors.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        Self::insert_motor_samples_with_conn(&conn, flight_id, motors)
+    }
+
+    fn insert_motor_samples_with_conn(
+        conn: &Connection,
+        flight_id: i64,
+        motors: &[MotorSample],
+    ) -> Result<(), DatabaseError> {
+        if motors.is_empty() {
+            return Ok(());
+        }
+        let mut appender = conn.appender("motor_telemetry")?;
+        for sample in motors {
+            appender.append_row(params![
+                flight_id,
+                sample.timestamp_ms,
+                sample.motor_index,
+                sample.rpm,
+                sample.current,
+                sample.temperature,
+            ])?;
+        }
+        appender.flush()?;
+        log::info!("Inserted {} motor samples for flight {}", motors.len(), flight_id);
+        Ok(())
+    }
+
+    /// Get per-motor series aligned to the telemetry rows returned by
+    /// `get_flight_telemetry` (one value per row timestamp)
+    pub fn get_motor_series(&self, flight_id: i64, timestamps: &[i64]) -> Result<Vec<MotorSeries>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT motor_index, timestamp_ms, rpm, current, temperature
+            FROM motor_telemetry
+            WHERE flight_id = ?
+            ORDER BY motor_index, timestamp_ms
+            "#,
+        )?;
+        let samples = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(MotorSample {
+                    motor_index: row.get(0)?,
+                    timestamp_ms: row.get(1)?,
+                    rpm: row.get(2)?,
+                    current: row.get(3)?,
+                    temperature: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut series = Vec::new();
+        let mut start = 0;
+        while start < samples.len() {
+            let motor_index = samples[start].motor_index;
+            let end = samples[start..]
+                .iter()
+                .position(|s| s.motor_index != motor_index)
+                .map_or(samples.len(), |offset| start + offset);
+            let motor = &samples[start..end];
+            start = end;
+
+            let sample_times: Vec<i64> = motor.iter().map(|s| s.timestamp_ms).collect();
+            let aligned: Vec<Option<&MotorSample>> = align_samples(&sample_times, timestamps)
+                .into_iter()
+                .map(|i| i.map(|i| &motor[i]))
+                .collect();
+            series.push(MotorSeries {
+                motor_index,
+                rpm: aligned.iter().map(|s| s.and_then(|s| s.rpm)).collect(),
+                current: aligned.iter().map(|s| s.and_then(|s| s.current)).collect(),
+                temperature: aligned.iter().map(|s| s.and_then(|s| s.temperature)).collect(),
+            });
+        }
+        Ok(series)
+    }
+
+    // ================================================================
+    // BATTERY CELLS
+    // ================================================================
+
+    /// Get cell voltage series (one per cell) aligned to the telemetry rows
+    /// returned by `get_flight_telemetry`. Empty when the log had no cell data.
+    pub fn get_cell_voltage_series(&self, flight_id: i64, timestamps: &[i64]) -> Result<Vec<Vec<Option<f64>>>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT cell_index, timestamp_ms, voltage
+            FROM battery_cells
+            WHERE flight_id = ?
+            ORDER BY cell_index, timestamp_ms
+            "#,
+        )?;
+        let rows: Vec<(i32, i64, f64)> = stmt
+            .query_map(params![flight_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut series = Vec::new();
+        let mut start = 0;
+        while start < rows.len() {
+            let cell_index = rows[start].0;
+            let end = rows[start..]
+                .iter()
+                .position(|r| r.0 != cell_index)
+                .map_or(rows.len(), |offset| start + offset);
+            let cell = &rows[start..end];
+            start = end;
+
+            let sample_times: Vec<i64> = cell.iter().map(|r| r.1).collect();
+            series.push(
+                align_samples(&sample_times, timestamps)
+                    .into_iter()
+                    .map(|i| i.map(|i| cell[i].2))
+                    .collect(),
+            );
+        }
+        Ok(series)
+    }
+
+    // ================================================================
+    // CAPTURE EVENTS
+    // ================================================================
+
+    /// Derive photo and video events from the camera state in telemetry.
+    /// A photo is a rising edge of `is_photo`; a video event is one
+    /// continuous run of `is_video` with its duration. With `None`, every
+    /// flight that has no events yet is processed (backfill).
+    fn record_capture_events_with_conn(conn: &Connection, flight_id: Option<i64>) -> Result<usize, DatabaseError> {
+        let filter = match flight_id {
+            Some(_) => "flight_id = ?",
+            None => "flight_id NOT IN (SELECT DISTINCT flight_id FROM flight_events)",
+        };
+        let sql = format!(
+            r#"
+            INSERT OR IGNORE INTO flight_events
+                (flight_id, timestamp_ms, event_type, latitude, longitude, altitude, duration_ms)
+            SELECT * FROM (
+                WITH states AS (
+                    SELECT
+                        flight_id,
+                        timestamp_ms,
+                        latitude,
+                        longitude,
+                        COALESCE(height, altitude) AS alt,
+                        COALESCE(is_photo, FALSE) AS photo,
+                        COALESCE(is_video, FALSE) AS video,
+                        COALESCE(LAG(is_photo) OVER w, FALSE) AS prev_photo,
+                        COALESCE(LAG(is_video) OVER w, FALSE) AS prev_video
+                    FROM telemetry
+                    WHERE {}
+                    WINDOW w AS (PARTITION BY flight_id ORDER BY timestamp_ms)
+                ),
+                segments AS (
+                    SELECT *,
+                        SUM(CASE WHEN video AND NOT prev_video THEN 1 ELSE 0 END)
+                            OVER (PARTITION BY flight_id ORDER BY timestamp_ms) AS segment
+                    FROM states
+                )
+                SELECT flight_id, timestamp_ms, 'photo', latitude, longitude, alt, NULL::BIGINT
+                FROM states
+                WHERE photo AND NOT prev_photo
+                UNION ALL
+                SELECT
+                    flight_id,
+                    MIN(timestamp_ms),
+                    'video',
+                    arg_min(latitude, timestamp_ms),
+                    arg_min(longitude, timestamp_ms),
+                    arg_min(alt, timestamp_ms),
+                    MAX(timestamp_ms) - MIN(timestamp_ms)
+                FROM segments
+                WHERE video
+                GROUP BY flight_id, segment
+            )
+            "#,
+            filter
+        );
+
+        let recorded = match flight_id {
+            Some(id) => conn.execute(&sql, params![id])?,
+            None => conn.execute(&sql, [])?,
+        };
+        Ok(recorded)
+    }
+
+    /// Store events reported by the parser (settings changes, app messages)
+    pub fn insert_flight_events(&self, flight_id: i64, events: &[FlightEvent]) -> Result<(), DatabaseError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        Self::in_transaction(&conn, || Self::insert_flight_events_with_conn(&conn, flight_id, events))
+    }
+
+    fn insert_flight_events_with_conn(
+        conn: &Connection,
+        flight_id: i64,
+        events: &[FlightEvent],
+    ) -> Result<(), DatabaseError> {
+        // One statement may not replace the same key twice; keep the last event
+        let mut latest: HashMap<(i64, &str), &FlightEvent> = HashMap::with_capacity(events.len());
+        for event in events {
+            latest.insert((event.timestamp_ms, event.event_type.as_str()), event);
+        }
+        let rows: Vec<Vec<&dyn ToSql>> = latest
+            .into_values()
+            .map(|event| -> Vec<&dyn ToSql> {
+                vec![
+                    &flight_id,
+                    &event.timestamp_ms,
+                    &event.event_type,
+                    &event.lat,
+                    &event.lon,
+                    &event.altitude,
+                    &event.duration_ms,
+                    &event.detail,
+                    &event.severity,
+                ]
+            })
+            .collect();
+        Self::insert_rows_batched(
+            conn,
+            "INSERT OR REPLACE INTO flight_events \
+             (flight_id, timestamp_ms, event_type, latitude, longitude, altitude, duration_ms, detail, severity)",
+            &rows,
+        )
+    }
+
+    /// Get all events of a flight in time order
+    pub fn get_flight_events(&self, flight_id: i64) -> Result<Vec<FlightEvent>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT timestamp_ms, event_type, latitude, longitude, altitude, duration_ms, detail, severity
+            FROM flight_events
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms
+            "#,
+        )?;
+        let events = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightEvent {
+                    timestamp_ms: row.get(0)?,
+                    event_type: row.get(1)?,
+                    lat: row.get(2)?,
+                    lon: row.get(3)?,
+                    altitude: row.get(4)?,
+                    duration_ms: row.get(5)?,
+                    detail: row.get(6)?,
+                    severity: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    /// Count every tip and warning message across the flights counted in
+    /// statistics, most frequent first
+    pub fn get_warning_summary(&self) -> Result<Vec<WarningSummary>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                e.detail,
+                e.severity,
+                COUNT(*) AS occurrences,
+                COUNT(DISTINCT e.flight_id) AS flight_count,
+                CAST(MAX(f.start_time) AS VARCHAR) AS last_seen
+            FROM flight_events e
+            JOIN flights f ON f.id = e.flight_id
+            WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+              AND e.event_type IN ('tip', 'warning') AND e.detail IS NOT NULL
+            GROUP BY e.detail, e.severity
+            ORDER BY
+                CASE e.severity WHEN 'critical' THEN 0 WHEN 'warning' THEN 1 ELSE 2 END,
+                occurrences DESC
+            "#,
+        )?;
+        let summary = stmt
+            .query_map([], |row| {
+                Ok(WarningSummary {
+                    message: row.get(0)?,
+                    severity: row.get::<_, Option<String>>(1)?.unwrap_or_else(|| "info".to_string()),
+                    occurrences: row.get(2)?,
+                    flight_count: row.get(3)?,
+                    last_seen: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(summary)
+    }
+
+    /// Flight mode changes, RC signal losses and app messages of every
+    /// flight counted in statistics, oldest flight first. A signal loss is
+    /// the RC signal dropping to zero after having been above it.
+    pub fn get_safety_events(&self) -> Result<Vec<FlightSafetyEvents>, DatabaseError> {
+        let start = std::time::Instant::now();
+        let telemetry_sql = |source: &str, flights: &str| {
+            format!(
+                r#"
+                WITH modes AS (
+                    SELECT flight_id, timestamp_ms, flight_mode, LAG(flight_mode) OVER w AS prev_mode
+                    FROM {source}
+                    WHERE flight_id IN ({flights}) AND flight_mode IS NOT NULL AND flight_mode <> ''
+                    WINDOW w AS (PARTITION BY flight_id ORDER BY timestamp_ms)
+                ),
+                signal AS (
+                    SELECT flight_id, timestamp_ms, rc_signal, LAG(rc_signal) OVER w AS prev_signal
+                    FROM {source}
+                    WHERE flight_id IN ({flights}) AND rc_signal IS NOT NULL
+                    WINDOW w AS (PARTITION BY flight_id ORDER BY timestamp_ms)
+                )
+                SELECT flight_id, timestamp_ms, 'mode', flight_mode
+                FROM modes
+                WHERE prev_mode IS NULL OR flight_mode <> prev_mode
+                UNION ALL
+                SELECT flight_id, timestamp_ms, 'signal_loss', NULL
+                FROM signal
+                WHERE rc_signal = 0 AND prev_signal > 0
+                "#
+            )
+        };
+        let event_from_row = |row: &duckdb::Row<'_>| -> duckdb::Result<(i64, SafetyEvent)> {
+            Ok((
+                row.get(0)?,
+                SafetyEvent {
+                    timestamp_ms: row.get(1)?,
+                    kind: row.get(2)?,
+                    detail: row.get(3)?,
+                },
+            ))
+        };
+
+        let (mut flights, mut events) = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, COALESCE(display_name, file_name), CAST(start_time AS VARCHAR), drone_model, drone_serial
+                FROM flights
+                WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)
+                ORDER BY start_time ASC NULLS FIRST, id ASC
+                "#,
+            )?;
+            let flights = stmt
+                .query_map([], |row| {
+                    Ok(FlightSafetyEvents {
+                        flight_id: row.get(0)?,
+                        display_name: row.get(1)?,
+                        start_time: row.get(2)?,
+                        drone_model: row.get(3)?,
+                        drone_serial: row.get(4)?,
+                        events: Vec::new(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT e.flight_id, e.timestamp_ms, 'message', e.detail
+                FROM flight_events e
+                JOIN flights f ON f.id = e.flight_id
+                WHERE f.deleted_at IS NULL AND NOT COALESCE(f.excluded_from_stats, FALSE)
+                  AND e.event_type IN ('tip', 'warning') AND e.detail IS NOT NULL
+                "#,
+            )?;
+            let mut events = stmt.query_map([], event_from_row)?.collect::<Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(&telemetry_sql(
+                "telemetry",
+                "SELECT id FROM flights WHERE deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            ))?;
+            events.extend(stmt.query_map([], event_from_row)?.collect::<Result<Vec<_>, _>>()?);
+            (flights, events)
+        };
+        self.for_each_stored_elsewhere(
+            "deleted_at IS NULL AND NOT COALESCE(excluded_from_stats, FALSE)",
+            "safety events",
+            |conn, source, flight_id| {
+                let mut stmt = conn.prepare(&telemetry_sql(source, &flight_id.to_string()))?;
+                events.extend(stmt.query_map([], event_from_row)?.collect::<Result<Vec<_>, _>>()?);
+                Ok(())
+            },
+        )?;
+
+        let index: HashMap<i64, usize> = flights.iter().enumerate().map(|(i, f)| (f.flight_id, i)).collect();
+        let event_count = events.len();
+        for (flight_id, event) in events {
+            if let Some(&i) = index.get(&flight_id) {
+                flights[i].events.push(event);
+            }
+        }
+        for flight in &mut flights {
+            flight.events.sort_by_key(|e| e.timestamp_ms);
+        }
+
+        log::debug!(
+            "get_safety_events: {} events of {} flights in {:.1}ms",
+            event_count,
+            flights.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(flights)
+    }
+
+    /// Derive capture events once for flights imported before events existed
+    fn run_startup_event_backfill(&self) {
+        const SETTING_KEY: &str = "capture_events_backfilled";
+
+        if let Ok(Some(value)) = self.get_setting(SETTING_KEY) {
+            if value == "true" {
+                return;
+            }
+        }
+
+        let start = std::time::Instant::now();
+        let result = {
+            let conn = self.conn.lock().unwrap();
+            Self::record_capture_events_with_conn(&conn, None)
+        };
+        match result {
+            Ok(count) => log::info!(
+                "Backfilled {} capture events in {:.1}s",
+                count,
+                start.elapsed().as_secs_f64()
+            ),
+            Err(e) => {
+                log::error!("Capture event backfill failed: {}", e);
+                return;
+            }
+        }
+
+        if let Err(e) = self.set_setting(SETTING_KEY, "true") {
+            log::error!("Failed to save capture event backfill flag: {}", e);
+        }
+    }
+
+    // ================================================================
+    // MEDIA
+    // ================================================================
+
+    /// Associate scanned media files with the flights they were captured
+    /// during. A file matches flights whose time window (plus slack) holds
+    /// its capture time; ties are broken by camera serial, then by the
+    /// distance between the media position and the home point. Returns the
+    /// number of files associated.
+    pub fn link_media_files(&self, files: &[MediaFile]) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        // (id, start ms, end ms, drone serial, home lat, home lon)
+        type FlightWindow = (i64, i64, i64, Option<String>, Option<f64>, Option<f64>);
+        let flights: Vec<FlightWindow> = {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, epoch_ms(start_time), epoch_ms(COALESCE(end_time, start_time)),
+                       drone_serial, home_lat, home_lon
+                FROM flights
+                WHERE deleted_at IS NULL AND start_time IS NOT NULL
+                "#,
+            )?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let slack_ms = MEDIA_TIME_SLACK_SECS * 1000;
+        let mut linked = 0;
+        for file in files {
+            let Some(captured_ms) = file.captured_at.map(|t| t.timestamp_millis()) else {
+                continue;
+            };
+
+            let mut candidates: Vec<&FlightWindow> = flights
+                .iter()
+                .filter(|f| captured_ms >= f.1 - slack_ms && captured_ms <= f.2 + slack_ms)
+                .collect();
+
+            if let Some(serial) = file.camera_serial.as_deref() {
+                let same_drone: Vec<&FlightWindow> =
+                    candidates.iter().copied().filter(|f| f.3.as_deref() == Some(serial)).collect();
+                if !same_drone.is_empty() {
+                    candidates = same_drone;
+                }
+            }
+
+            let home_distance = |f: &FlightWindow| match (file.lat, file.lon, f.4, f.5) {
+                (Some(lat), Some(lon), Some(home_lat), Some(home_lon)) => {
+                    crate::parser::haversine_distance(lat, lon, home_lat, home_lon)
+                }
+                _ => 0.0,
+            };
+            candidates.retain(|f| home_distance(f) <= MEDIA_MAX_HOME_DISTANCE_M);
+
+            // Prefer captures inside the flight window over ones in the slack
+            let best = candidates.into_iter().min_by(|a, b| {
+                let outside = |f: &FlightWindow| (f.1 - captured_ms).max(captured_ms - f.2).max(0);
+                outside(a)
+                    .cmp(&outside(b))
+                    .then(home_distance(a).total_cmp(&home_distance(b)))
+            });
+            let Some(flight) = best else {
+                continue;
+            };
+
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO flight_media
+                    (file_path, flight_id, file_name, media_type, captured_at, latitude, longitude, camera_serial)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    file.file_path,
+                    flight.0,
+                    file.file_name,
+                    file.media_type,
+                    file.captured_at.map(|t| t.to_rfc3339()),
+                    file.lat,
+                    file.lon,
+                    file.camera_serial,
+                ],
+            )?;
+            linked += 1;
+        }
+
+        log::info!("Associated {} of {} media files with flights", linked, files.len());
+        Ok(linked)
+    }
+
+    /// Get the media files associated with a flight in capture order
+    pub fn get_flight_media(&self, flight_id: i64) -> Result<Vec<FlightMedia>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT m.file_path, m.file_name, m.media_type, CAST(m.captured_at AS VARCHAR),
+                   m.latitude, m.longitude,
+                   date_diff('millisecond', f.start_time, m.captured_at) / 1000.0
+            FROM flight_media m
+            JOIN flights f ON f.id = m.flight_id
+            WHERE m.flight_id = ?
+            ORDER BY m.captured_at, m.file_name
+            "#,
+        )?;
+        let media = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightMedia {
+                    file_path: row.get(0)?,
+                    file_name: row.get(1)?,
+                    media_type: row.get(2)?,
+                    captured_at: row.get(3)?,
+                    lat: row.get(4)?,
+                    lon: row.get(5)?,
+                    offset_secs: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(media)
+    }
+
+    // ================================================================
+    // DOCUMENTS
+    // ================================================================
+
+    /// Directory next to the library database that holds the files attached
+    /// to flights, each stored once under its SHA-256 digest
+    pub fn documents_dir(&self) -> PathBuf {
+        self.db_path().with_extension("documents")
+    }
+
+    /// Attach a file to a flight. Identical content attached twice is stored
+    /// once on disk.
+    pub fn add_flight_document(
+        &self,
+        flight_id: i64,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<FlightDocument, DatabaseError> {
+        let file_name = std::path::Path::new(file_name.trim())
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "document".to_string());
+        let sha256 = format!("{:x}", Sha256::digest(data));
+
+        let id = Self::next_id();
+        let conn = self.conn.lock().unwrap();
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM flights WHERE id = ?",
+            params![flight_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+
+        // Written under a temporary name so an interrupted copy never looks complete
+        let dir = self.documents_dir();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(&sha256);
+        if !path.exists() {
+            let partial_path = path.with_extension("partial");
+            fs::write(&partial_path, data)?;
+            fs::rename(&partial_path, &path)?;
+        }
+
+        conn.execute(
+            "INSERT INTO flight_documents (id, flight_id, file_name, sha256, size_bytes) VALUES (?, ?, ?, ?, ?)",
+            params![id, flight_id, file_name, sha256, data.len() as i64],
+        )?;
+        Self::record_audit(&conn, "document_add", Some(flight_id), Some(&file_name))?;
+        log::info!("Attached {} ({} bytes) to flight {}", file_name, data.len(), flight_id);
+        drop(conn);
+
+        self.get_flight_documents(flight_id)?
+            .into_iter()
+            .find(|d| d.id == id)
+            .ok_or(DatabaseError::DocumentNotFound(id))
+    }
+
+    /// Documents attached to a flight, oldest first
+    pub fn get_flight_documents(&self, flight_id: i64) -> Result<Vec<FlightDocument>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, flight_id, file_name, sha256, size_bytes, CAST(added_at AS VARCHAR)
+            FROM flight_documents
+            WHERE flight_id = ?
+            ORDER BY added_at, id
+            "#,
+        )?;
+        let documents = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightDocument {
+                    id: row.get(0)?,
+                    flight_id: row.get(1)?,
+                    file_name: row.get(2)?,
+                    sha256: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                    added_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(documents)
+    }
+
+    /// A document and the file holding its content
+    pub fn flight_document_file(&self, document_id: i64) -> Result<(FlightDocument, PathBuf), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let document = conn
+            .query_row(
+                r#"
+                SELECT id, flight_id, file_name, sha256, size_bytes, CAST(added_at AS VARCHAR)
+                FROM flight_documents
+                WHERE id = ?
+                "#,
+                params![document_id],
+                |row| {
+                    Ok(FlightDocument {
+                        id: row.get(0)?,
+                        flight_id: row.get(1)?,
+                        file_name: row.get(2)?,
+                        sha256: row.get(3)?,
+                        size_bytes: row.get(4)?,
+                        added_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?
+            .ok_or(DatabaseError::DocumentNotFound(document_id))?;
+        let path = self.documents_dir().join(&document.sha256);
+        if !path.is_file() {
+            return Err(DatabaseError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("the file of document {} is missing", document.file_name),
+            )));
+        }
+        Ok((document, path))
+    }
+
+    /// Remove a document from its flight, and its file once no other
+    /// document shares the content
+    pub fn delete_flight_document(&self, document_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let document: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT flight_id, file_name FROM flight_documents WHERE id = ?",
+                params![document_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((flight_id, file_name)) = document else {
+            return Err(DatabaseError::DocumentNotFound(document_id));
+        };
+        conn.execute("DELETE FROM flight_documents WHERE id = ?", params![document_id])?;
+        Self::record_audit(&conn, "document_remove", Some(flight_id), Some(&file_name))?;
+        self.remove_orphan_documents(&conn);
+        log::info!("Removed document {} from flight {}", file_name, flight_id);
+        Ok(())
+    }
+
+    /// Delete stored files no document refers to any more
+    fn remove_orphan_documents(&self, conn: &Connection) {
+        let dir = self.documents_dir();
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return;
+        };
+        let referenced = (|| -> Result<HashSet<String>, duckdb::Error> {
+            let mut stmt = conn.prepare("SELECT DISTINCT sha256 FROM flight_documents")?;
+            let hashes = stmt.query_map([], |row| row.get(0))?.collect::<Result<HashSet<_>, _>>()?;
+            Ok(hashes)
+        })();
+        let referenced = match referenced {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                log::warn!("Failed to list attached documents: {}", e);
+                return;
+            }
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !referenced.contains(&name) {
+                if let Err(e) = fs::remove_file(entry.path()) {
+                    log::warn!("Failed to remove unused document {:?}: {}", entry.path(), e);
+                }
+            }
+        }
+        let _ = fs::remove_dir(&dir);
+    }
+
+    /// Copy document files from another library's documents directory (or
+    /// one unpacked from a backup) that this library doesn't have yet
+    fn copy_missing_documents(&self, src_dir: &std::path::Path) -> Result<(), DatabaseError> {
+        if !src_dir.is_dir() {
+            return Ok(());
+        }
+        let dir = self.documents_dir();
+        fs::create_dir_all(&dir)?;
+        for entry in fs::read_dir(src_dir)?.filter_map(|e| e.ok()) {
+            let target = dir.join(entry.file_name());
+            if entry.path().is_file() && !target.exists() {
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+        Ok(())
+    }
+
+    // ================================================================
+    // MISSIONS
+    // ================================================================
+
+    /// Check if a mission file has already been imported (by hash).
+    /// Returns the name of the matching mission if found.
+    pub fn is_mission_imported(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn
+            .query_row(
+                "SELECT name FROM missions WHERE file_hash = ? LIMIT 1",
+                params![file_hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Insert a parsed waypoint mission and return its ID
+    pub fn insert_mission(&self, mission: &MissionMetadata) -> Result<i64, DatabaseError> {
+        let id = self.generate_flight_id();
+        let waypoints = serde_json::to_string(&mission.waypoints)
+            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO missions (id, name, file_name, file_hash, drone_model, created_at, waypoint_count, waypoints)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                id,
+                mission.name,
+                mission.file_name,
+                mission.file_hash,
+                mission.drone_model,
+                mission.created_at.map(|t| t.to_rfc3339()),
+                mission.waypoints.len() as i64,
+                waypoints,
+            ],
+        )?;
+        log::info!("Inserted mission {} ('{}') with {} waypoints", id, mission.name, mission.waypoints.len());
+        Ok(id)
+    }
+
+    /// Get all missions with the flights linked to them
+    pub fn get_missions(&self) -> Result<Vec<Mission>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut links: HashMap<i64, Vec<i64>> = HashMap::new();
+        let mut stmt = conn.prepare("SELECT mission_id, flight_id FROM flight_missions ORDER BY flight_id")?;
+        for row in stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))? {
+            let (mission_id, flight_id) = row?;
+            links.entry(mission_id).or_default().push(flight_id);
+        }
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, name, file_name, drone_model,
+                   CAST(created_at AS VARCHAR), CAST(imported_at AS VARCHAR), waypoints
+            FROM missions
+            ORDER BY COALESCE(created_at, imported_at) DESC
+            "#,
+        )?;
+        let missions = stmt
+            .query_map([], |row| {
+                let id: i64 = row.get(0)?;
+                let waypoints: String = row.get(6)?;
+                Ok(Mission {
+                    id,
+                    name: row.get(1)?,
+                    file_name: row.get(2)?,
+                    drone_model: row.get(3)?,
+                    created_at: row.get(4)?,
+                    imported_at: row.get(5)?,
+                    waypoints: serde_json::from_str(&waypoints).unwrap_or_default(),
+                    flight_ids: links.remove(&id).unwrap_or_default(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(missions)
+    }
+
+    /// Delete a mission and its flight links
+    pub fn delete_mission(&self, mission_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM flight_missions WHERE mission_id = ?", params![mission_id])?;
+        conn.execute("DELETE FROM missions WHERE id = ?", params![mission_id])?;
+        Ok(())
+    }
+
+    /// Link a mission to every flight whose track passes within
+    /// `MISSION_WAYPOINT_RADIUS_M` of at least `MISSION_MIN_COVERAGE` of its
+    /// waypoints. Returns the number of linked flights.
+    pub fn link_mission_flights(&self, mission_id: i64) -> Result<usize, DatabaseError> {
+        let waypoints: Vec<Waypoint> = {
+            let conn = self.conn.lock().unwrap();
+            let json: String = conn
+                .query_row("SELECT waypoints FROM missions WHERE id = ?", params![mission_id], |row| row.get(0))
+                .optional()?
+                .ok_or(DatabaseError::MissionNotFound(mission_id))?;
+            serde_json::from_str(&json).unwrap_or_default()
+        };
+        if waypoints.is_empty() {
+            return Ok(0);
+        }
+
+        // Only flights taking off near the mission area can have flown it
+        let min_lat = waypoints.iter().map(|w| w.lat).fold(f64::INFINITY, f64::min) - MISSION_AREA_MARGIN_DEG;
+        let max_lat = waypoints.iter().map(|w| w.lat).fold(f64::NEG_INFINITY, f64::max) + MISSION_AREA_MARGIN_DEG;
+        let min_lon = waypoints.iter().map(|w| w.lon).fold(f64::INFINITY, f64::min) - MISSION_AREA_MARGIN_DEG;
+        let max_lon = waypoints.iter().map(|w| w.lon).fold(f64::NEG_INFINITY, f64::max) + MISSION_AREA_MARGIN_DEG;
+        let candidates: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id FROM flights
+                WHERE deleted_at IS NULL
+                  AND home_lat BETWEEN ? AND ?
+                  AND home_lon BETWEEN ? AND ?
+                "#,
+            )?;
+            let ids = stmt
+                .query_map(params![min_lat, max_lat, min_lon, max_lon], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+
+        let mut linked = 0;
+        for flight_id in candidates {
+            let track = self.get_flight_track(flight_id)?;
+            if track.is_empty() {
+                continue;
+            }
+            let reached = waypoints
+                .iter()
+                .filter(|w| {
+                    track.iter().any(|&(_, lat, lon)| {
+                        crate::parser::haversine_distance(w.lat, w.lon, lat, lon) <= MISSION_WAYPOINT_RADIUS_M
+                    })
+                })
+                .count();
+            let coverage = reached as f64 / waypoints.len() as f64;
+            if coverage < MISSION_MIN_COVERAGE {
+                continue;
+            }
+
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                "INSERT OR REPLACE INTO flight_missions (flight_id, mission_id, coverage) VALUES (?, ?, ?)",
+                params![flight_id, mission_id, coverage],
+            )?;
+            linked += 1;
+        }
+
+        log::info!("Linked mission {} to {} flights", mission_id, linked);
+        Ok(linked)
+    }
+
+    /// Re-run flight linking for every mission (e.g. after importing new flights)
+    pub fn relink_all_missions(&self) -> Result<usize, DatabaseError> {
+        let mission_ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id FROM missions")?;
+            let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        let mut linked = 0;
+        for mission_id in mission_ids {
+            linked += self.link_mission_flights(mission_id)?;
+        }
+        Ok(linked)
+    }
+
+    // ================================================================
+    // KEYCHAIN CACHE
+    // ================================================================
+
+    /// Get the cached decryption keychains (JSON) for a log file, if any
+    pub fn get_cached_keychains(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let keychains = conn
+            .query_row(
+                "SELECT keychains FROM log_keychains WHERE file_hash = ?",
+                params![file_hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(keychains)
+    }
+
+    /// Get every cached keychain as `(file_hash, keychains JSON)` pairs
+    pub fn get_all_cached_keychains(&self) -> Result<Vec<(String, String)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT file_hash, keychains FROM log_keychains ORDER BY fetched_at")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Store the decryption keychains (JSON) fetched for a log file
+    pub fn cache_keychains(&self, file_hash: &str, keychains_json: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO log_keychains (file_hash, keychains) VALUES (?, ?)",
+            params![file_hash, keychains_json],
+        )?;
+        log::debug!("Cached keychains for log {}", file_hash);
+        Ok(())
+    }
+
+    /// Get a setting value by key
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        
+        let result: Result<String, _> = conn.query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            params![key],
+            |row| row.get(0),
+        );
+        
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DatabaseError::from(e)),
+        }
+    }
+
+    /// Set a setting value (insert or update)
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        
+        // DuckDB doesn't support CURRENT_TIMESTAMP in ON CONFLICT, so use INSERT OR REPLACE
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+            params![key, value],
+        )?;
+        
+        Ok(())
+    }
+
+    /// Run one-time startup deduplication for existing data.
+    /// This only runs once - on first startup after the dedup feature is added.
+    /// After running, it sets a flag so it won't run again.
+    fn run_startup_deduplication(&self) {
+        const SETTING_KEY: &str = "duplicate_checked";
+        
+        // Check if we've already run deduplication
+        match self.get_setting(SETTING_KEY) {
+            Ok(Some(value)) if value == "true" => {
+                log::debug!("Startup deduplication already completed, skipping");
+                return;
+            }
+            Ok(_) => {
+                // Not set or not "true", run deduplication
+            }
+            Err(e) => {
+                log::warn!("Failed to check dedup setting: {}, proceeding with deduplication", e);
+            }
+        }
+
+        log::info!("Running one-time startup deduplication for existing flights...");
+        
+        match self.deduplicate_flights() {
+            Ok(count) => {
+                if count > 0 {
+                    log::info!("Startup deduplication removed {} duplicate flights", count);
+                } else {
+                    log::info!("Startup deduplication complete, no duplicates found");
+                }
+            }
+            Err(e) => {
+                log::error!("Startup deduplication failed: {}", e);
+                // Don't set the flag if dedup failed - try again next startup
+                return;
+            }
+        }
+
+        // Mark deduplication as complete
+        if let Err(e) = self.set_setting(SETTING_KEY, "true") {
+            log::error!("Failed to save dedup completion flag: {}", e);
+        }
+    }
+
+    /// Write a consistent snapshot of the database file to `dest_path`.
+    ///
+    /// DuckDB has no SQLite-style online backup API; instead the WAL is
+    /// checkpointed into the main file and the file is copied while the
+    /// connection lock is held, so no write can land in between. Unlike
+    /// `export_backup` the snapshot is a plain `.db` file that replaces the
+    /// whole library when restored with `restore_database`.
+    pub fn backup_database(&self, dest_path: &std::path::Path) -> Result<(), DatabaseError> {
+        let start = std::time::Instant::now();
+        let db_path = self.db_path();
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Copy next to the destination first so a failed copy never leaves a truncated backup
+        let partial_path = dest_path.with_extension("partial");
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("CHECKPOINT;")?;
+        let copied = fs::copy(&db_path, &partial_path);
+        drop(conn);
+
+        if let Err(e) = copied.and_then(|_| fs::rename(&partial_path, dest_path)) {
+            let _ = fs::remove_file(&partial_path);
+            return Err(e.into());
+        }
+
+        log::info!("Database snapshot written to {:?} in {:.1}ms", dest_path, start.elapsed().as_secs_f64() * 1000.0);
+        Ok(())
+    }
+
+    /// Replace the whole database with a snapshot made by `backup_database`.
+    ///
+    /// The snapshot is validated before anything is touched, and the current
+    /// database is snapshotted to `backups/` first so the restore can be undone.
+    /// Older snapshots are upgraded by the usual schema migrations.
+    pub fn restore_database(&self, src_path: &std::path::Path) -> Result<(), DatabaseError> {
+        let start = std::time::Instant::now();
+        let db_path = self.db_path();
+        let staged_path = db_path.with_extension("db.restore");
+        fs::copy(src_path, &staged_path)?;
+
+        let valid = Connection::open(&staged_path)
+            .and_then(|c| c.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get::<_, i64>(0)));
+        let _ = fs::remove_file(staged_path.with_extension("restore.wal"));
+        if let Err(e) = valid {
+            let _ = fs::remove_file(&staged_path);
+            return Err(DatabaseError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Not a DJI Logbook database snapshot: {}", e),
+            )));
+        }
+
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let pre_restore = self.backups_dir().join(format!("pre-restore-{}.db", timestamp));
+        self.backup_database(&pre_restore)?;
+
+        {
+            let mut conn = self.conn.lock().unwrap();
+            // Close the file-backed connection so the database file can be replaced
+            *conn = Connection::open_in_memory()?;
+            let _ = fs::remove_file(db_path.with_extension("db.wal"));
+            let swapped = fs::rename(&staged_path, &db_path).map_err(DatabaseError::from).and_then(|_| {
+                let restored = Self::open_with_recovery(&db_path)?;
+                Self::configure_connection(&restored)?;
+                Ok(restored)
+            });
+            match swapped {
+                Ok(restored) => *conn = restored,
+                Err(e) => {
+                    // Never leave the session writing into the in-memory placeholder
+                    log::error!("Restore from {:?} failed, reopening the previous database: {}", src_path, e);
+                    if !staged_path.exists() {
+                        // The snapshot already replaced the file: put the pre-restore copy back
+                        fs::copy(&pre_restore, &db_path)?;
+                    }
+                    let _ = fs::remove_file(&staged_path);
+                    let previous = Self::open_with_recovery(&db_path)?;
+                    Self::configure_connection(&previous)?;
+                    *conn = previous;
+                    return Err(e);
+                }
+            }
+        }
+        self.init_schema()?;
+
+        log::info!("Database restored from {:?} in {:.1}s", src_path, start.elapsed().as_secs_f64());
+        Ok(())
+    }
+
+    /// Directory holding automatic snapshots and pre-restore safety copies
+    pub fn backups_dir(&self) -> PathBuf {
+        self.data_dir.join("backups")
+    }
+
+    /// Snapshot the database into `backups/` and delete all but the newest
+    /// `keep` automatic snapshots. Returns the path of the new snapshot.
+    pub fn rotating_backup(&self, keep: usize) -> Result<PathBuf, DatabaseError> {
+        let dir = self.backups_dir();
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let path = dir.join(format!("auto-{}.db", timestamp));
+        self.backup_database(&path)?;
+
+        let mut snapshots: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map_or(false, |n| n.starts_with("auto-") && n.ends_with(".db"))
+            })
+            .collect();
+        // Timestamped names sort chronologically
+        snapshots.sort();
+        let excess = snapshots.len().saturating_sub(keep.max(1));
+        for old in &snapshots[..excess] {
+            if let Err(e) = fs::remove_file(old) {
+                log::warn!("Failed to remove old snapshot {:?}: {}", old, e);
+            }
+        }
+        Ok(path)
+    }
+
+    /// Export the entire database to a compressed backup file.
+    ///
+    /// Uses DuckDB's Parquet COPY for each table, then packs them into a single
+    /// gzip-compressed tar archive.  The resulting `.db.backup` file is portable
+    /// and can be restored with `import_backup`.
+    pub fn export_backup(&self, dest_path: &std::path::Path) -> Result<(), DatabaseError> {
+        let start = std::time::Instant::now();
+        log::info!("Starting database backup to {:?}", dest_path);
+
+        // Create a temp directory for the Parquet exports
+        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-backup-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)?;
+
+        let conn = self.conn.lock().unwrap();
+
+        // Export each table to Parquet (fast, compressed, columnar)
+        let flights_path = temp_dir.join("flights.parquet");
+        let telemetry_path = temp_dir.join("telemetry.parquet");
+        let keychains_path = temp_dir.join("keychains.parquet");
+        let tags_path = temp_dir.join("flight_tags.parquet");
+        let log_keychains_path = temp_dir.join("log_keychains.parquet");
+        let attachments_path = temp_dir.join("flight_attachments.parquet");
+        let missions_path = temp_dir.join("missions.parquet");
+        let flight_missions_path = temp_dir.join("flight_missions.parquet");
+        let home_points_path = temp_dir.join("home_points.parquet");
+        let events_path = temp_dir.join("flight_events.parquet");
+        let media_path = temp_dir.join("flight_media.parquet");
+        let motors_path = temp_dir.join("motor_telemetry.parquet");
+        let cells_path = temp_dir.join("battery_cells.parquet");
+        let annotations_path = temp_dir.join("flight_annotations.parquet");
+        let drones_path = temp_dir.join("drones.parquet");
+        let batteries_path = temp_dir.join("batteries.parquet");
+        let maintenance_path = temp_dir.join("maintenance_records.parquet");
+        let equipment_path = temp_dir.join("equipment.parquet");
+        let checklist_templates_path = temp_dir.join("checklist_templates.parquet");
+        let smart_lists_path = temp_dir.join("smart_lists.parquet");
+        let export_templates_path = temp_dir.join("export_templates.parquet");
+        let audit_log_path = temp_dir.join("audit_log.parquet");
+        let incidents_path = temp_dir.join("incidents.parquet");
+        let flight_equipment_path = temp_dir.join("flight_equipment.parquet");
+        let flight_checklists_path = temp_dir.join("flight_checklists.parquet");
+        let telemetry_packed_path = temp_dir.join("telemetry_packed.parquet");
+        let flight_documents_path = temp_dir.join("flight_documents.parquet");
+
+        conn.execute_batch(&format!(
+            "COPY flights    TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flights_path.to_string_lossy()
+        ))?;
+        // Archived flights go into the backup as regular telemetry rows
+        let archived_files: Vec<String> = {
+            let mut stmt = conn.prepare("SELECT file_name FROM archived_telemetry")?;
+            let names = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            let archive_dir = self.telemetry_archive_dir();
+            names
+                .into_iter()
+                .map(|name| archive_dir.join(name))
+                .filter(|path| {
+                    let exists = path.exists();
+                    if !exists {
+                        log::warn!("Archived telemetry {:?} is missing and left out of the backup", path);
+                    }
+                    exists
+                })
+                .map(|path| format!("'{}'", sql_path(&path)))
+                .collect()
+        };
+        let telemetry_query = if archived_files.is_empty() {
+            "telemetry".to_string()
+        } else {
+            format!(
+                "(SELECT * FROM telemetry UNION ALL BY NAME SELECT * FROM read_parquet([{}], union_by_name = true))",
+                archived_files.join(", ")
+            )
+        };
+        conn.execute_batch(&format!(
+            "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            telemetry_query,
+            telemetry_path.to_string_lossy()
+        ))?;
+        conn.execute_batch(&format!(
+            "COPY keychains  TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            keychains_path.to_string_lossy()
+        ))?;
+        // Export tags table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_tags TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            tags_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY log_keychains TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            log_keychains_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_attachments TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            attachments_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY missions TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            missions_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_missions TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flight_missions_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY home_points TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            home_points_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_events TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            events_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_media TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            media_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY motor_telemetry TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            motors_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY battery_cells TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            cells_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_annotations TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            annotations_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY drones TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            drones_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY batteries TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            batteries_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY maintenance_records TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            maintenance_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY equipment TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            equipment_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY checklist_templates TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            checklist_templates_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY smart_lists TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            smart_lists_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY export_templates TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            export_templates_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY audit_log TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            audit_log_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY incidents TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            incidents_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_equipment TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flight_equipment_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_checklists TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flight_checklists_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY telemetry_packed TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            telemetry_packed_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_documents TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flight_documents_path.to_string_lossy()
+        ));
+
+        drop(conn); // release the lock while we tar
+
+        // Pack the Parquet files into a gzip-compressed tar archive
+        let dest_file = fs::File::create(dest_path)?;
+        let gz = flate2::write::GzEncoder::new(dest_file, flate2::Compression::fast());
+        let mut tar = tar::Builder::new(gz);
+
+        for name in &["flights.parquet", "telemetry.parquet", "keychains.parquet", "flight_tags.parquet", "log_keychains.parquet", "flight_attachments.parquet", "missions.parquet", "flight_missions.parquet", "home_points.parquet", "flight_events.parquet", "flight_media.parquet", "motor_telemetry.parquet", "battery_cells.parquet", "flight_annotations.parquet", "drones.parquet", "batteries.parquet", "maintenance_records.parquet", "equipment.parquet", "checklist_templates.parquet", "smart_lists.parquet", "export_templates.parquet", "audit_log.parquet", "incidents.parquet", "flight_equipment.parquet", "flight_checklists.parquet", "telemetry_packed.parquet", "flight_documents.parquet"] {
+            let file_path = temp_dir.join(name);
+            if file_path.exists() {
+                tar.append_path_with_name(&file_path, name)
+                    .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+            }
+        }
+        let documents_dir = self.documents_dir();
+        if documents_dir.is_dir() {
+            tar.append_dir_all("documents", &documents_dir)
+                .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        }
+
+        tar.into_inner()
+            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+            .finish()
+            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+
+        // Clean up temp dir
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        log::info!(
+            "Database backup completed in {:.1}s → {:?}",
+            start.elapsed().as_secs_f64(),
+            dest_path
+        );
+        Ok(())
+    }
+
+    /// Import a backup file, restoring all flight data.
+    ///
+    /// Existing records are kept.  If a flight with the same ID already exists
+    /// it is overwritten (its telemetry is replaced as well).
+    pub fn import_backup(&self, src_path: &std::path::Path) -> Result<String, DatabaseError> {
+        let start = std::time::Instant::now();
+        log::info!("Starting database restore from {:?}", src_path);
+
+        // Extract the tar.gz archive to a temp directory
+        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-restore-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)?;
+
+        let file = fs::File::open(src_path)?;
+        let gz = flate2::read::GzDecoder::new(file);
+        let mut archive = tar::Archive::new(gz);
+        archive.unpack(&temp_dir)
+            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to extract backup archive: {}", e))))?;
+
+        let flights_path = temp_dir.join("flights.parquet");
+        let telemetry_path = temp_dir.join("telemetry.parquet");
+        let keychains_path = temp_dir.join("keychains.parquet");
+
+        if !flights_path.exists() {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(DatabaseError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Invalid backup file: missing flights.parquet",
+            )));
+        }
+
+        let conn = self.conn.lock().unwrap();
+
+        // --- Restore flights ---
+        // The flights table has multiple UNIQUE/PRIMARY KEY constraints (id + file_hash),
+        // so INSERT OR REPLACE is not supported.  Delete matching rows first, then insert.
+        conn.execute_batch(&format!(
+            r#"
+            DELETE FROM flights
+            WHERE id IN (SELECT id FROM read_parquet('{}'))
+               OR file_hash IN (SELECT file_hash FROM read_parquet('{}') WHERE file_hash IS NOT NULL);
+            INSERT INTO flights BY NAME
+            SELECT * FROM read_parquet('{}');
+            "#,
+            flights_path.to_string_lossy(),
+            flights_path.to_string_lossy(),
+            flights_path.to_string_lossy()
+        ))?;
+
+        let flights_restored: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM read_parquet('{}')", flights_path.to_string_lossy()),
+            [],
+            |row| row.get(0),
+        )?;
+
+        // --- Restore telemetry ---
+        if telemetry_path.exists() {
+            // Get the set of flight IDs being restored so we can remove their
+            // existing telemetry first (to handle overwrites cleanly).
+            conn.execute_batch(&format!(
+                r#"
+                DELETE FROM telemetry
+                WHERE flight_id IN (
+                    SELECT DISTINCT flight_id FROM read_parquet('{}')
+                );
+                INSERT INTO telemetry BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                telemetry_path.to_string_lossy(),
+                telemetry_path.to_string_lossy()
+            ))?;
+            let _ = conn.execute_batch(&format!(
+                "DELETE FROM flight_stats_cache WHERE flight_id IN (SELECT DISTINCT flight_id FROM read_parquet('{}'));",
+                telemetry_path.to_string_lossy()
+            ));
+            // Restored telemetry replaces the archived copy of the same flights
+            conn.execute_batch(&format!(
+                r#"
+                DELETE FROM archived_telemetry
+                WHERE flight_id IN (
+                    SELECT DISTINCT flight_id FROM read_parquet('{}')
+                );
+                "#,
+                telemetry_path.to_string_lossy()
+            ))?;
+        }
+
+        // --- Restore keychains ---
+        if keychains_path.exists() {
+            conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO keychains
+                SELECT * FROM read_parquet('{}');
+                "#,
+                keychains_path.to_string_lossy()
+            ))?;
+        }
+
+        // --- Restore flight tags (backward compatible — may not exist in old backups) ---
+        let tags_path = temp_dir.join("flight_tags.parquet");
+        if tags_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                DELETE FROM flight_tags
+                WHERE flight_id IN (
+                    SELECT DISTINCT flight_id FROM read_parquet('{}')
+                );
+                INSERT INTO flight_tags
+                SELECT * FROM read_parquet('{}');
+                "#,
+                tags_path.to_string_lossy(),
+                tags_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore cached log keychains (may not exist in old backups) ---
+        let log_keychains_path = temp_dir.join("log_keychains.parquet");
+        if log_keychains_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO log_keychains
+                SELECT * FROM read_parquet('{}');
+                "#,
+                log_keychains_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore linked attachments (may not exist in old backups) ---
+        let attachments_path = temp_dir.join("flight_attachments.parquet");
+        if attachments_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_attachments
+                SELECT * FROM read_parquet('{}');
+                "#,
+                attachments_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore missions and their flight links (may not exist in old backups) ---
+        let missions_path = temp_dir.join("missions.parquet");
+        if missions_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO missions
+                SELECT * FROM read_parquet('{}');
+                "#,
+                missions_path.to_string_lossy()
+            ));
+        }
+        let flight_missions_path = temp_dir.join("flight_missions.parquet");
+        if flight_missions_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_missions
+                SELECT * FROM read_parquet('{}');
+                "#,
+                flight_missions_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore home point history (may not exist in old backups) ---
+        let home_points_path = temp_dir.join("home_points.parquet");
+        if home_points_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO home_points
+                SELECT * FROM read_parquet('{}');
+                "#,
+                home_points_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore capture events; derive them from telemetry for old backups ---
+        let events_path = temp_dir.join("flight_events.parquet");
+        if events_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_events BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                events_path.to_string_lossy()
+            ));
+        } else if let Err(e) = Self::record_capture_events_with_conn(&conn, None) {
+            log::warn!("Failed to derive capture events for restored flights: {}", e);
+        }
+
+        // --- Restore media associations (optional, may not exist in old backups) ---
+        let media_path = temp_dir.join("flight_media.parquet");
+        if media_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_media
+                SELECT * FROM read_parquet('{}');
+                "#,
+                media_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore motor telemetry (optional, may not exist in old backups) ---
+        let motors_path = temp_dir.join("motor_telemetry.parquet");
+        if motors_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO motor_telemetry
+                SELECT * FROM read_parquet('{}');
+                "#,
+                motors_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore cell voltages (optional, may not exist in old backups) ---
+        let cells_path = temp_dir.join("battery_cells.parquet");
+        if cells_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO battery_cells
+                SELECT * FROM read_parquet('{}');
+                "#,
+                cells_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore annotations (optional, may not exist in old backups) ---
+        let annotations_path = temp_dir.join("flight_annotations.parquet");
+        if annotations_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_annotations BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                annotations_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore drone profiles (optional, may not exist in old backups) ---
+        let drones_path = temp_dir.join("drones.parquet");
+        if drones_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO drones BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                drones_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore batteries (optional; older backups get them from the flights) ---
+        let batteries_path = temp_dir.join("batteries.parquet");
+        if batteries_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO batteries BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                batteries_path.to_string_lossy()
+            ));
+        }
+        if let Err(e) = Self::register_batteries_from_flights(&conn) {
+            log::warn!("Failed to register batteries of restored flights: {}", e);
+        }
+
+        // --- Restore maintenance records (optional, may not exist in old backups) ---
+        let maintenance_path = temp_dir.join("maintenance_records.parquet");
+        if maintenance_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO maintenance_records BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                maintenance_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore equipment inventory (optional, may not exist in old backups) ---
+        let equipment_path = temp_dir.join("equipment.parquet");
+        if equipment_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO equipment BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                equipment_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore checklist templates (optional, may not exist in old backups) ---
+        let checklist_templates_path = temp_dir.join("checklist_templates.parquet");
+        if checklist_templates_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO checklist_templates BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                checklist_templates_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore smart lists (optional, may not exist in old backups) ---
+        let smart_lists_path = temp_dir.join("smart_lists.parquet");
+        if smart_lists_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO smart_lists BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                smart_lists_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore export templates (optional, may not exist in old backups) ---
+        let export_templates_path = temp_dir.join("export_templates.parquet");
+        if export_templates_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO export_templates BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                export_templates_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore the audit log (optional, may not exist in old backups) ---
+        let audit_log_path = temp_dir.join("audit_log.parquet");
+        if audit_log_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR IGNORE INTO audit_log BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                audit_log_path.to_string_lossy()
+            ));
+        }
+        let _ = Self::record_audit(
+            &conn,
+            "restore_backup",
+            None,
+            Some(&format!("{} flights restored", flights_restored)),
+        );
+
+        // --- Restore incident reports (optional, may not exist in old backups) ---
+        let incidents_path = temp_dir.join("incidents.parquet");
+        if incidents_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO incidents BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                incidents_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore flight equipment links (optional, may not exist in old backups) ---
+        let flight_equipment_path = temp_dir.join("flight_equipment.parquet");
+        if flight_equipment_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_equipment BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                flight_equipment_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore flight checklist records (optional, may not exist in old backups) ---
+        let flight_checklists_path = temp_dir.join("flight_checklists.parquet");
+        if flight_checklists_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_checklists BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                flight_checklists_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore packed telemetry (optional, may not exist in old backups) ---
+        let telemetry_packed_path = temp_dir.join("telemetry_packed.parquet");
+        if telemetry_packed_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO telemetry_packed BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                telemetry_packed_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore flight documents (optional, may not exist in old backups) ---
+        let flight_documents_path = temp_dir.join("flight_documents.parquet");
+        if flight_documents_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_documents BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                flight_documents_path.to_string_lossy()
+            ));
+        }
+
+        drop(conn);
+        self.copy_missing_documents(&temp_dir.join("documents"))?;
+
+        // Clean up temp dir
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let elapsed = start.elapsed().as_secs_f64();
+        let msg = format!(
+            "Restored {} flights in {:.1}s",
+            flights_restored, elapsed
+        );
+        log::info!("{}", msg);
+        Ok(msg)
+    }
+
+    // ================================================================
+    // PORTABLE ARCHIVE
+    // ================================================================
+
+    /// Bundle the whole library into one file for moving it to another
+    /// machine: the table backup from `export_backup`, config.json (including
+    /// the API key), the keychains folder and a manifest. Returns the number
+    /// of flights in the archive.
+    pub fn export_archive(&self, dest_path: &std::path::Path) -> Result<i64, DatabaseError> {
+        let start = std::time::Instant::now();
+        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-archive-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)?;
+        let result = self.write_archive(&temp_dir, dest_path);
+        let _ = fs::remove_dir_all(&temp_dir);
+        let flight_count = result?;
+
+        log::info!(
+            "Archive with {} flights written in {:.1}s → {:?}",
+            flight_count,
+            start.elapsed().as_secs_f64(),
+            dest_path
+        );
+        Ok(flight_count)
+    }
+
+    fn write_archive(&self, temp_dir: &std::path::Path, dest_path: &std::path::Path) -> Result<i64, DatabaseError> {
+        self.export_backup(&temp_dir.join("library.backup"))?;
+
+        let flight_count: i64 = {
+            let conn = self.conn.lock().unwrap();
+            conn.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get(0))?
+        };
+        let manifest = serde_json::json!({
+            "format": ARCHIVE_FORMAT,
+            "version": ARCHIVE_FORMAT_VERSION,
+            "app_version": env!("CARGO_PKG_VERSION"),
+            "created_at": chrono::Utc::now().to_rfc3339(),
+            "flight_count": flight_count,
+        });
+        fs::write(temp_dir.join("manifest.json"), serde_json::to_string_pretty(&manifest).unwrap())?;
+
+        let dest_file = fs::File::create(dest_path)?;
+        let gz = flate2::write::GzEncoder::new(dest_file, flate2::Compression::fast());
+        let mut tar = tar::Builder::new(gz);
+        tar.append_path_with_name(temp_dir.join("manifest.json"), "manifest.json")?;
+        tar.append_path_with_name(temp_dir.join("library.backup"), "library.backup")?;
+        let config_path = self.data_dir.join("config.json");
+        if config_path.exists() {
+            tar.append_path_with_name(&config_path, "config.json")?;
+        }
+        let keychains_dir = self.data_dir.join("keychains");
+        if keychains_dir.is_dir() {
+            tar.append_dir_all("keychains", &keychains_dir)?;
+        }
+        tar.into_inner()?.finish()?;
+
+        Ok(flight_count)
+    }
+
+    /// Merge an archive made by `export_archive` into this library.
+    ///
+    /// Flights whose file hash is already present are skipped, so importing
+    /// the same archive twice is harmless. Incoming flights keep their ID
+    /// unless it is taken locally, in which case the flight and all of its
+    /// rows get a fresh one. Settings and keychain files only fill in what is
+    /// missing here; local values always win.
+    pub fn import_archive(&self, src_path: &std::path::Path) -> Result<String, DatabaseError> {
+        let start = std::time::Instant::now();
+        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-archive-in-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)?;
+        let result = self.merge_archive(src_path, &temp_dir);
+        let _ = fs::remove_dir_all(&temp_dir);
+        let (imported, skipped) = result?;
+
+        let msg = format!(
+            "Imported {} flights ({} already in this library) in {:.1}s",
+            imported,
+            skipped,
+            start.elapsed().as_secs_f64()
+        );
+        log::info!("{}", msg);
+        Ok(msg)
+    }
+
+    fn merge_archive(&self, src_path: &std::path::Path, temp_dir: &std::path::Path) -> Result<(i64, i64), DatabaseError> {
+        let invalid = |msg: &str| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string()));
+
+        let gz = flate2::read::GzDecoder::new(fs::File::open(src_path)?);
+        tar::Archive::new(gz)
+            .unpack(temp_dir)
+            .map_err(|_| invalid("Not a logbook archive (failed to extract)"))?;
+
+        let manifest: serde_json::Value = fs::read_to_string(temp_dir.join("manifest.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .ok_or_else(|| invalid("Not a logbook archive (missing manifest)"))?;
+        if manifest.get("format").and_then(|v| v.as_str()) != Some(ARCHIVE_FORMAT) {
+            return Err(invalid("Not a logbook archive"));
+        }
+        if manifest.get("version").and_then(|v| v.as_i64()).unwrap_or(0) > ARCHIVE_FORMAT_VERSION {
+            return Err(invalid("This archive was made by a newer version of the app"));
+        }
+
+        let library_dir = temp_dir.join("library");
+        fs::create_dir_all(&library_dir)?;
+        let gz = flate2::read::GzDecoder::new(fs::File::open(temp_dir.join("library.backup"))?);
+        tar::Archive::new(gz).unpack(&library_dir)?;
+        if !library_dir.join("flights.parquet").exists() {
+            return Err(invalid("Invalid archive: missing flights.parquet"));
+        }
+
+        let counts = {
+            let conn = self.conn.lock().unwrap();
+            conn.execute_batch("BEGIN TRANSACTION")?;
+            match Self::merge_library_tables(&conn, &library_dir) {
+                Ok(counts) => {
+                    conn.execute_batch("COMMIT")?;
+                    counts
+                }
+                Err(e) => {
+                    let _ = conn.execute_batch("ROLLBACK");
+                    return Err(e);
+                }
+            }
+        };
 
-        let conn = self.conn.lock().unwrap();
+        self.copy_missing_documents(&library_dir.join("documents"))?;
+        self.merge_archive_config(&temp_dir.join("config.json"));
+        let archive_keychains = temp_dir.join("keychains");
+        if archive_keychains.is_dir() {
+            for entry in fs::read_dir(&archive_keychains)?.filter_map(|e| e.ok()) {
+                let target = self.data_dir.join("keychains").join(entry.file_name());
+                if entry.path().is_file() && !target.exists() {
+                    fs::copy(entry.path(), &target)?;
+                }
+            }
+        }
 
-        // Export each table to Parquet (fast, compressed, columnar)
-        let flights_path = temp_dir.join("flights.parquet");
-        let telemetry_path = temp_dir.join("telemetry.parquet");
-        let keychains_path = temp_dir.join("keychains.parquet");
-        let tags_path = temp_dir.join("flight_tags.parquet");
+        Ok(counts)
+    }
+
+    /// Insert the archive's flights that are new to this library, remapping
+    /// clashing flight and mission IDs. Returns (imported, skipped).
+    fn merge_library_tables(conn: &Connection, dir: &std::path::Path) -> Result<(i64, i64), DatabaseError> {
+        let parquet = |table: &str| {
+            let path = dir.join(format!("{}.parquet", table));
+            path.exists().then(|| path.to_string_lossy().to_string())
+        };
 
         conn.execute_batch(&format!(
-            "COPY flights    TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
-            flights_path.to_string_lossy()
-        ))?;
-        conn.execute_batch(&format!(
-            "COPY telemetry  TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
-            telemetry_path.to_string_lossy()
-        ))?;
-        conn.execute_batch(&format!(
-            "COPY keychains  TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
-            keychains_path.to_string_lossy()
+            r#"
+            CREATE OR REPLACE TEMP TABLE archive_flights AS
+            SELECT * FROM read_parquet('{}');
+
+            CREATE OR REPLACE TEMP TABLE archive_flight_ids AS
+            WITH incoming AS (
+                SELECT id AS old_id
+                FROM archive_flights
+                WHERE (file_hash IS NOT NULL
+                       AND file_hash NOT IN (SELECT file_hash FROM flights WHERE file_hash IS NOT NULL))
+                   OR (file_hash IS NULL AND id NOT IN (SELECT id FROM flights))
+            ),
+            base AS (
+                SELECT GREATEST(
+                    (SELECT COALESCE(MAX(id), 0) FROM flights),
+                    (SELECT COALESCE(MAX(id), 0) FROM archive_flights)
+                ) AS max_id
+            )
+            SELECT
+                old_id,
+                CASE WHEN old_id IN (SELECT id FROM flights)
+                     THEN (SELECT max_id FROM base) + ROW_NUMBER() OVER (ORDER BY old_id)
+                     ELSE old_id
+                END AS new_id
+            FROM incoming;
+
+            INSERT INTO flights BY NAME
+            SELECT a.* REPLACE (m.new_id AS id)
+            FROM archive_flights a
+            JOIN archive_flight_ids m ON a.id = m.old_id;
+            "#,
+            parquet("flights").unwrap_or_default()
         ))?;
-        // Export tags table (ignore error if empty or doesn't exist)
-        let _ = conn.execute_batch(&format!(
-            "COPY flight_tags TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
-            tags_path.to_string_lossy()
-        ));
 
-        drop(conn); // release the lock while we tar
+        for table in ARCHIVE_FLIGHT_TABLES {
+            let Some(path) = parquet(table) else { continue };
+            let sql = format!(
+                r#"
+                INSERT OR IGNORE INTO {table} BY NAME
+                SELECT x.* REPLACE (m.new_id AS flight_id)
+                FROM read_parquet('{path}') x
+                JOIN archive_flight_ids m ON x.flight_id = m.old_id
+                "#,
+            );
+            // Telemetry is essential; the other tables may be missing from old archives
+            match conn.execute_batch(&sql) {
+                Err(e) if *table == "telemetry" => return Err(e.into()),
+                Err(e) => log::warn!("Skipped merging {} from archive: {}", table, e),
+                Ok(()) => {}
+            }
+        }
 
-        // Pack the Parquet files into a gzip-compressed tar archive
-        let dest_file = fs::File::create(dest_path)?;
-        let gz = flate2::write::GzEncoder::new(dest_file, flate2::Compression::fast());
-        let mut tar = tar::Builder::new(gz);
+        if let (Some(missions), Some(links)) = (parquet("missions"), parquet("flight_missions")) {
+            // Missions are matched by file hash; unknown ones are added
+            if let Err(e) = conn.execute_batch(&format!(
+                r#"
+                CREATE OR REPLACE TEMP TABLE archive_missions AS
+                SELECT * FROM read_parquet('{missions}');
 
-        for name in &["flights.parquet", "telemetry.parquet", "keychains.parquet", "flight_tags.parquet"] {
-            let file_path = temp_dir.join(name);
-            if file_path.exists() {
-                tar.append_path_with_name(&file_path, name)
-                    .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+                CREATE OR REPLACE TEMP TABLE archive_mission_ids AS
+                SELECT
+                    a.id AS old_id,
+                    COALESCE(l.id, CASE WHEN a.id IN (SELECT id FROM missions)
+                        THEN GREATEST(
+                            (SELECT COALESCE(MAX(id), 0) FROM missions),
+                            (SELECT COALESCE(MAX(id), 0) FROM archive_missions)
+                        ) + ROW_NUMBER() OVER (ORDER BY a.id)
+                        ELSE a.id
+                    END) AS new_id,
+                    l.id IS NULL AS is_new
+                FROM archive_missions a
+                LEFT JOIN missions l ON l.file_hash = a.file_hash;
+
+                INSERT INTO missions BY NAME
+                SELECT a.* REPLACE (m.new_id AS id)
+                FROM archive_missions a
+                JOIN archive_mission_ids m ON a.id = m.old_id
+                WHERE m.is_new;
+
+                INSERT OR IGNORE INTO flight_missions BY NAME
+                SELECT x.* REPLACE (f.new_id AS flight_id, m.new_id AS mission_id)
+                FROM read_parquet('{links}') x
+                JOIN archive_flight_ids f ON x.flight_id = f.old_id
+                JOIN archive_mission_ids m ON x.mission_id = m.old_id;
+
+                DROP TABLE archive_missions;
+                DROP TABLE archive_mission_ids;
+                "#,
+            )) {
+                log::warn!("Skipped merging missions from archive: {}", e);
             }
         }
 
-        tar.into_inner()
-            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
-            .finish()
-            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        // Profiles already set up locally win over the archive's
+        for table in ARCHIVE_LIBRARY_TABLES {
+            if let Some(path) = parquet(table) {
+                if let Err(e) = conn.execute_batch(&format!(
+                    "INSERT OR IGNORE INTO {} BY NAME SELECT * FROM read_parquet('{}')",
+                    table, path
+                )) {
+                    log::warn!("Skipped merging {} from archive: {}", table, e);
+                }
+            }
+        }
+        Self::register_batteries_from_flights(conn)?;
 
-        // Clean up temp dir
-        let _ = fs::remove_dir_all(&temp_dir);
+        // The history of the imported flights comes along, then the import itself
+        if let Some(path) = parquet("audit_log") {
+            if let Err(e) = conn.execute_batch(&format!(
+                r#"
+                INSERT OR IGNORE INTO audit_log BY NAME
+                SELECT x.* REPLACE (m.new_id AS flight_id)
+                FROM read_parquet('{}') x
+                JOIN archive_flight_ids m ON x.flight_id = m.old_id
+                "#,
+                path
+            )) {
+                log::warn!("Skipped merging audit_log from archive: {}", e);
+            }
+        }
+        let new_ids: Vec<i64> = {
+            let mut stmt = conn.prepare("SELECT new_id FROM archive_flight_ids ORDER BY new_id")?;
+            let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        for flight_id in new_ids {
+            Self::record_audit(conn, "import", Some(flight_id), Some("from a portable archive"))?;
+        }
 
-        log::info!(
-            "Database backup completed in {:.1}s → {:?}",
-            start.elapsed().as_secs_f64(),
-            dest_path
-        );
-        Ok(())
+        let (imported, total): (i64, i64) = conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM archive_flight_ids), (SELECT COUNT(*) FROM archive_flights)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        conn.execute_batch("DROP TABLE archive_flights; DROP TABLE archive_flight_ids;")?;
+
+        Ok((imported, total - imported))
     }
 
-    /// Import a backup file, restoring all flight data.
+    /// Copy settings from an imported archive that are not set locally
+    fn merge_archive_config(&self, archive_config: &std::path::Path) {
+        let Some(incoming) = fs::read_to_string(archive_config)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        else {
+            return;
+        };
+        let config_path = self.data_dir.join("config.json");
+        let mut local: serde_json::Value = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+
+        let (Some(incoming), Some(local_map)) = (incoming.as_object(), local.as_object_mut()) else {
+            return;
+        };
+        for (key, value) in incoming {
+            local_map.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        if let Err(e) = fs::write(&config_path, serde_json::to_string_pretty(&local).unwrap()) {
+            log::warn!("Failed to merge archive settings into config.json: {}", e);
+        }
+    }
+
+    // ================================================================
+    // RECONCILE
+    // ================================================================
+
+    /// Merge a diverged copy of this library, such as the conflict copy a
+    /// folder sync leaves behind, into this one.
     ///
-    /// Existing records are kept.  If a flight with the same ID already exists
-    /// it is overwritten (its telemetry is replaced as well).
-    pub fn import_backup(&self, src_path: &std::path::Path) -> Result<String, DatabaseError> {
-        let start = std::time::Instant::now();
-        log::info!("Starting database restore from {:?}", src_path);
+    /// Flights the copy has and this library lacks are imported by file hash,
+    /// as with a portable archive, so no flight is duplicated. The copy's audit
+    /// log then serves as a change journal: deletions, restores, renames,
+    /// notes, favorites, statistics exclusions and tag edits made there to flights both sides share
+    /// are replayed here in order. Flights purged in the copy only go to the
+    /// trash here, and flights purged here since are not brought back. The
+    /// copy itself is opened read-only and left untouched.
+    pub fn reconcile_library(&self, other_path: &std::path::Path) -> Result<String, DatabaseError> {
+        let invalid = |msg: String| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, msg));
+        if !other_path.is_file() {
+            return Err(invalid(format!("library not found: {}", other_path.display())));
+        }
+        if other_path == self.db_path() {
+            return Err(invalid("a library can't be reconciled with itself".to_string()));
+        }
 
-        // Extract the tar.gz archive to a temp directory
-        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-restore-{}", uuid::Uuid::new_v4()));
+        let start = std::time::Instant::now();
+        let temp_dir = std::env::temp_dir().join(format!("dji-logbook-reconcile-{}", uuid::Uuid::new_v4()));
         fs::create_dir_all(&temp_dir)?;
+        let result = self.reconcile_from(other_path, &temp_dir);
+        let _ = fs::remove_dir_all(&temp_dir);
+        let (imported, skipped, replayed) = result?;
 
-        let file = fs::File::open(src_path)?;
-        let gz = flate2::read::GzDecoder::new(file);
-        let mut archive = tar::Archive::new(gz);
-        archive.unpack(&temp_dir)
-            .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to extract backup archive: {}", e))))?;
+        let msg = format!(
+            "Imported {} flights ({} already in this library) and applied {} changes in {:.1}s",
+            imported,
+            skipped,
+            replayed,
+            start.elapsed().as_secs_f64()
+        );
+        log::info!("Reconciled with {:?}: {}", other_path, msg);
+        Ok(msg)
+    }
 
-        let flights_path = temp_dir.join("flights.parquet");
-        let telemetry_path = temp_dir.join("telemetry.parquet");
-        let keychains_path = temp_dir.join("keychains.parquet");
+    fn reconcile_from(
+        &self,
+        other_path: &std::path::Path,
+        temp_dir: &std::path::Path,
+    ) -> Result<(i64, i64, usize), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(&format!("ATTACH '{}' AS reconcile_src (READ_ONLY)", sql_path(other_path)))?;
+
+        let result = (|| {
+            Self::dump_attached_library(&conn, &other_path.with_extension("telemetry-archive"), temp_dir)?;
+            Self::in_transaction(&conn, || {
+                let (imported, skipped) = Self::merge_library_tables(&conn, temp_dir)?;
+                let replayed = Self::replay_audit_log(&conn, temp_dir)?;
+                Self::record_audit(
+                    &conn,
+                    "reconcile",
+                    None,
+                    Some(&format!(
+                        "{}: {} flights imported, {} changes applied",
+                        other_path.display(),
+                        imported,
+                        replayed
+                    )),
+                )?;
+                Ok((imported, skipped, replayed))
+            })
+        })();
 
-        if !flights_path.exists() {
-            let _ = fs::remove_dir_all(&temp_dir);
+        if let Err(e) = conn.execute_batch("DETACH reconcile_src") {
+            log::warn!("Failed to detach reconciled library: {}", e);
+        }
+        drop(conn);
+        if result.is_ok() {
+            self.copy_missing_documents(&other_path.with_extension("documents"))?;
+        }
+        result
+    }
+
+    /// Write the tables of the library attached as `reconcile_src` to Parquet
+    /// files in `dir`, laid out like a backup so `merge_library_tables` can
+    /// read them. Archived telemetry is read from the copy's sidecar files.
+    fn dump_attached_library(
+        conn: &Connection,
+        archive_dir: &std::path::Path,
+        dir: &std::path::Path,
+    ) -> Result<(), DatabaseError> {
+        let tables: HashSet<String> = {
+            let mut stmt =
+                conn.prepare("SELECT table_name FROM duckdb_tables() WHERE database_name = 'reconcile_src'")?;
+            let names = stmt.query_map([], |row| row.get(0))?.collect::<Result<HashSet<_>, _>>()?;
+            names
+        };
+        if !tables.contains("flights") {
             return Err(DatabaseError::Io(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                "Invalid backup file: missing flights.parquet",
+                "Not a logbook library (no flights table)",
             )));
         }
 
-        let conn = self.conn.lock().unwrap();
+        let archived_files: Vec<String> = if tables.contains("archived_telemetry") {
+            let mut stmt = conn.prepare("SELECT file_name FROM reconcile_src.archived_telemetry")?;
+            let names = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            names
+                .into_iter()
+                .map(|name| archive_dir.join(name))
+                .filter(|path| path.exists())
+                .map(|path| format!("'{}'", sql_path(&path)))
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-        // --- Restore flights ---
-        // The flights table has multiple UNIQUE/PRIMARY KEY constraints (id + file_hash),
-        // so INSERT OR REPLACE is not supported.  Delete matching rows first, then insert.
+        let wanted = ["flights", "missions", "flight_missions", "audit_log"]
+            .iter()
+            .chain(ARCHIVE_FLIGHT_TABLES)
+            .chain(ARCHIVE_LIBRARY_TABLES);
+        for table in wanted.filter(|t| tables.contains(**t)) {
+            let source = if *table == "flights" {
+                // Flights purged here since the copies diverged stay purged
+                let seen_there = if tables.contains("audit_log") {
+                    "AND id NOT IN (SELECT id FROM reconcile_src.audit_log)"
+                } else {
+                    ""
+                };
+                format!(
+                    r#"
+                    SELECT * FROM reconcile_src.flights
+                    WHERE file_hash IS NULL OR file_hash NOT IN (
+                        SELECT file_hash FROM audit_log
+                        WHERE action = 'purge' AND file_hash IS NOT NULL {}
+                    )
+                    "#,
+                    seen_there
+                )
+            } else if *table == "telemetry" && !archived_files.is_empty() {
+                format!(
+                    "SELECT * FROM reconcile_src.telemetry UNION ALL BY NAME SELECT * FROM read_parquet([{}], union_by_name = true)",
+                    archived_files.join(", ")
+                )
+            } else {
+                format!("SELECT * FROM reconcile_src.{}", table)
+            };
+            conn.execute_batch(&format!(
+                "COPY ({}) TO '{}' (FORMAT PARQUET);",
+                source,
+                sql_path(&dir.join(format!("{}.parquet", table)))
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Apply the audit log entries of the other copy that this library has
+    /// not seen to the flights both share (matched by file hash), then copy
+    /// every missing entry over. Returns the number of changes applied.
+    fn replay_audit_log(conn: &Connection, dir: &std::path::Path) -> Result<usize, DatabaseError> {
+        let path = dir.join("audit_log.parquet");
+        if !path.exists() {
+            return Ok(0);
+        }
         conn.execute_batch(&format!(
             r#"
-            DELETE FROM flights
-            WHERE id IN (SELECT id FROM read_parquet('{}'))
-               OR file_hash IN (SELECT file_hash FROM read_parquet('{}') WHERE file_hash IS NOT NULL);
-            INSERT INTO flights
-            SELECT * FROM read_parquet('{}');
+            CREATE OR REPLACE TEMP TABLE reconcile_journal AS
+            SELECT x.* REPLACE (COALESCE(f.id, x.flight_id) AS flight_id), f.id AS local_flight_id
+            FROM read_parquet('{}') x
+            LEFT JOIN flights f ON f.file_hash = x.file_hash AND x.file_hash IS NOT NULL
+            WHERE x.id NOT IN (SELECT id FROM audit_log);
             "#,
-            flights_path.to_string_lossy(),
-            flights_path.to_string_lossy(),
-            flights_path.to_string_lossy()
+            sql_path(&path)
         ))?;
 
-        let flights_restored: i64 = conn.query_row(
-            &format!("SELECT COUNT(*) FROM read_parquet('{}')", flights_path.to_string_lossy()),
-            [],
-            |row| row.get(0),
-        )?;
-
-        // --- Restore telemetry ---
-        if telemetry_path.exists() {
-            // Get the set of flight IDs being restored so we can remove their
-            // existing telemetry first (to handle overwrites cleanly).
-            conn.execute_batch(&format!(
+        let changes: Vec<(String, i64, Option<String>, Option<String>)> = {
+            let actions = REPLAYED_AUDIT_ACTIONS
+                .iter()
+                .map(|a| format!("'{}'", a))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut stmt = conn.prepare(&format!(
                 r#"
-                DELETE FROM telemetry
-                WHERE flight_id IN (
-                    SELECT DISTINCT flight_id FROM read_parquet('{}')
-                );
-                INSERT INTO telemetry
-                SELECT * FROM read_parquet('{}');
+                SELECT action, local_flight_id, details, CAST(logged_at AS VARCHAR)
+                FROM reconcile_journal
+                WHERE local_flight_id IS NOT NULL AND action IN ({})
+                ORDER BY logged_at, id
                 "#,
-                telemetry_path.to_string_lossy(),
-                telemetry_path.to_string_lossy()
+                actions
             ))?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        for (action, flight_id, details, logged_at) in &changes {
+            match action.as_str() {
+                "delete" | "purge" => {
+                    conn.execute(
+                        "UPDATE flights SET deleted_at = COALESCE(deleted_at, CAST(? AS TIMESTAMPTZ), CURRENT_TIMESTAMP) WHERE id = ?",
+                        params![logged_at, flight_id],
+                    )?;
+                }
+                "restore" => {
+                    conn.execute("UPDATE flights SET deleted_at = NULL WHERE id = ?", params![flight_id])?;
+                }
+                "rename" => {
+                    if let Some(name) = details {
+                        conn.execute("UPDATE flights SET display_name = ? WHERE id = ?", params![name, flight_id])?;
+                    }
+                }
+                "notes" => {
+                    conn.execute("UPDATE flights SET notes = ? WHERE id = ?", params![details, flight_id])?;
+                }
+                "favorite" | "unfavorite" => {
+                    conn.execute(
+                        "UPDATE flights SET favorite = ? WHERE id = ?",
+                        params![action == "favorite", flight_id],
+                    )?;
+                }
+                "exclude_stats" | "include_stats" => {
+                    conn.execute(
+                        "UPDATE flights SET excluded_from_stats = ? WHERE id = ?",
+                        params![action == "exclude_stats", flight_id],
+                    )?;
+                }
+                "tag_add" => {
+                    if let Some(tag) = details {
+                        conn.execute(
+                            "INSERT OR IGNORE INTO flight_tags (flight_id, tag, tag_type) VALUES (?, ?, 'manual')",
+                            params![flight_id, tag],
+                        )?;
+                    }
+                }
+                "tag_remove" => {
+                    if let Some(tag) = details {
+                        conn.execute("DELETE FROM flight_tags WHERE flight_id = ? AND tag = ?", params![flight_id, tag])?;
+                    }
+                }
+                _ => {}
+            }
         }
 
-        // --- Restore keychains ---
-        if keychains_path.exists() {
-            conn.execute_batch(&format!(
+        conn.execute_batch(
+            r#"
+            INSERT OR IGNORE INTO audit_log BY NAME
+            SELECT * EXCLUDE (local_flight_id) FROM reconcile_journal;
+            DROP TABLE reconcile_journal;
+            "#,
+        )?;
+        Ok(changes.len())
+    }
+
+    // ================================================================
+    // INTEGRITY CHECK
+    // ================================================================
+
+    /// Check the library for damage and inconsistencies: tables that can't
+    /// be read, rows left behind by flights that no longer exist, flights
+    /// without any telemetry, references to missing missions, equipment or
+    /// checklist templates, and sidecar or document files that are missing
+    /// or no longer used.
+    ///
+    /// Without `repair` this is a dry run that only reports. Repairs never
+    /// drop a flight: flights without telemetry go to the trash, and what is
+    /// deleted otherwise is data nothing can reach any more.
+    pub fn check_library(&self, repair: bool) -> Result<LibraryCheckReport, DatabaseError> {
+        let start = std::time::Instant::now();
+        let conn = self.conn.lock().unwrap();
+        let mut issues = Vec::new();
+
+        // DuckDB has no integrity_check pragma; reading every block of every
+        // table is what surfaces corruption
+        let tables: Vec<String> = {
+            let mut stmt = conn.prepare(
                 r#"
-                INSERT OR REPLACE INTO keychains
-                SELECT * FROM read_parquet('{}');
+                SELECT table_name FROM duckdb_tables()
+                WHERE database_name = current_database() AND NOT temporary
+                ORDER BY table_name
                 "#,
-                keychains_path.to_string_lossy()
-            ))?;
+            )?;
+            let names = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+            names
+        };
+        for table in &tables {
+            let scan = conn.query_row(
+                &format!("SELECT bit_xor(hash(checked_row)) FROM \"{}\" checked_row", table),
+                [],
+                |row| row.get::<_, Option<u64>>(0),
+            );
+            if let Err(e) = scan {
+                issues.push(LibraryIssue {
+                    kind: "unreadable_table".to_string(),
+                    table: Some(table.clone()),
+                    description: format!("The table can't be read: {}", e),
+                    count: 1,
+                    repairable: false,
+                });
+            }
         }
 
-        // --- Restore flight tags (backward compatible — may not exist in old backups) ---
-        let tags_path = temp_dir.join("flight_tags.parquet");
-        if tags_path.exists() {
-            let _ = conn.execute_batch(&format!(
-                r#"
-                DELETE FROM flight_tags
-                WHERE flight_id IN (
-                    SELECT DISTINCT flight_id FROM read_parquet('{}')
-                );
-                INSERT INTO flight_tags
-                SELECT * FROM read_parquet('{}');
-                "#,
-                tags_path.to_string_lossy(),
-                tags_path.to_string_lossy()
+        // (kind, table, description, rows matching, statement that repairs them)
+        let mut checks: Vec<(&str, &str, &str, String, String)> = Vec::new();
+        for table in ARCHIVE_FLIGHT_TABLES.iter().chain(&["flight_missions", "archived_telemetry"]) {
+            checks.push((
+                "orphaned_rows",
+                *table,
+                "Rows of flights that no longer exist",
+                format!("FROM {} WHERE flight_id NOT IN (SELECT id FROM flights)", table),
+                format!("DELETE FROM {} WHERE flight_id NOT IN (SELECT id FROM flights)", table),
             ));
         }
+        checks.push((
+            "dangling_reference",
+            "flight_missions",
+            "Links to missions that no longer exist",
+            "FROM flight_missions WHERE mission_id NOT IN (SELECT id FROM missions)".to_string(),
+            "DELETE FROM flight_missions WHERE mission_id NOT IN (SELECT id FROM missions)".to_string(),
+        ));
+        checks.push((
+            "dangling_reference",
+            "flight_equipment",
+            "Links to equipment that no longer exists",
+            "FROM flight_equipment WHERE equipment_id NOT IN (SELECT id FROM equipment)".to_string(),
+            "DELETE FROM flight_equipment WHERE equipment_id NOT IN (SELECT id FROM equipment)".to_string(),
+        ));
+        checks.push((
+            "dangling_reference",
+            "flight_checklists",
+            "Completed checklists pointing at a deleted template",
+            "FROM flight_checklists WHERE template_id NOT IN (SELECT id FROM checklist_templates)".to_string(),
+            "UPDATE flight_checklists SET template_id = NULL WHERE template_id NOT IN (SELECT id FROM checklist_templates)"
+                .to_string(),
+        ));
 
-        drop(conn);
+        let mut repairs = Vec::new();
+        for (kind, table, description, rows, repair_sql) in checks {
+            let count: i64 = match conn.query_row(&format!("SELECT COUNT(*) {}", rows), [], |row| row.get(0)) {
+                Ok(count) => count,
+                Err(e) => {
+                    log::warn!("Skipped checking {} for {}: {}", table, kind, e);
+                    continue;
+                }
+            };
+            if count > 0 {
+                issues.push(LibraryIssue {
+                    kind: kind.to_string(),
+                    table: Some(table.to_string()),
+                    description: description.to_string(),
+                    count,
+                    repairable: true,
+                });
+                repairs.push(repair_sql);
+            }
+        }
 
-        // Clean up temp dir
-        let _ = fs::remove_dir_all(&temp_dir);
+        // Archived flights whose sidecar is gone have lost their telemetry;
+        // once the row is dropped they count as flights without telemetry
+        let archive_dir = self.telemetry_archive_dir();
+        let archived: Vec<(i64, String)> = {
+            let mut stmt = conn.prepare("SELECT flight_id, file_name FROM archived_telemetry")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let missing_sidecars: Vec<i64> = archived
+            .iter()
+            .filter(|(_, name)| !archive_dir.join(name).is_file())
+            .map(|(id, _)| *id)
+            .collect();
+        if !missing_sidecars.is_empty() {
+            issues.push(LibraryIssue {
+                kind: "missing_file".to_string(),
+                table: Some("archived_telemetry".to_string()),
+                description: "Archived flights whose telemetry file is missing".to_string(),
+                count: missing_sidecars.len() as i64,
+                repairable: true,
+            });
+            let ids = missing_sidecars.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+            repairs.push(format!("DELETE FROM archived_telemetry WHERE flight_id IN ({})", ids));
+        }
 
-        let elapsed = start.elapsed().as_secs_f64();
-        let msg = format!(
-            "Restored {} flights in {:.1}s",
-            flights_restored, elapsed
+        let documents_dir = self.documents_dir();
+        let documents: Vec<(i64, String)> = {
+            let mut stmt = conn.prepare("SELECT id, sha256 FROM flight_documents")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let missing_documents: Vec<i64> = documents
+            .iter()
+            .filter(|(_, sha256)| !documents_dir.join(sha256).is_file())
+            .map(|(id, _)| *id)
+            .collect();
+        if !missing_documents.is_empty() {
+            issues.push(LibraryIssue {
+                kind: "missing_file".to_string(),
+                table: Some("flight_documents".to_string()),
+                description: "Attached documents whose file is missing".to_string(),
+                count: missing_documents.len() as i64,
+                repairable: true,
+            });
+            let ids = missing_documents.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+            repairs.push(format!("DELETE FROM flight_documents WHERE id IN ({})", ids));
+        }
+
+        // Files on disk that no row points at
+        let unused_in = |dir: &std::path::Path, used: HashSet<&str>| -> Vec<PathBuf> {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return Vec::new();
+            };
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|path| path.is_file())
+                .filter(|path| !path.file_name().is_some_and(|n| used.contains(n.to_string_lossy().as_ref())))
+                .collect()
+        };
+        let mut unused_files = unused_in(&archive_dir, archived.iter().map(|(_, name)| name.as_str()).collect());
+        unused_files.extend(unused_in(&documents_dir, documents.iter().map(|(_, sha256)| sha256.as_str()).collect()));
+        if !unused_files.is_empty() {
+            issues.push(LibraryIssue {
+                kind: "unused_file".to_string(),
+                table: None,
+                description: "Telemetry or document files no flight refers to".to_string(),
+                count: unused_files.len() as i64,
+                repairable: true,
+            });
+        }
+
+        let empty_flights: Vec<i64> = {
+            let missing = missing_sidecars.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT id FROM flights
+                WHERE deleted_at IS NULL
+                  AND id NOT IN (SELECT flight_id FROM telemetry)
+                  AND id NOT IN (SELECT flight_id FROM telemetry_packed)
+                  AND (id NOT IN (SELECT flight_id FROM archived_telemetry) OR id IN ({}))
+                ORDER BY id
+                "#,
+                if missing.is_empty() { "NULL".to_string() } else { missing }
+            ))?;
+            let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        if !empty_flights.is_empty() {
+            issues.push(LibraryIssue {
+                kind: "empty_flights".to_string(),
+                table: Some("flights".to_string()),
+                description: "Flights without any telemetry (moved to the trash on repair)".to_string(),
+                count: empty_flights.len() as i64,
+                repairable: true,
+            });
+        }
+
+        if repair && issues.iter().any(|i| i.repairable) {
+            Self::in_transaction(&conn, || {
+                for sql in &repairs {
+                    conn.execute_batch(sql)?;
+                }
+                for &flight_id in &empty_flights {
+                    let trashed = conn.execute(
+                        "UPDATE flights SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL",
+                        params![flight_id],
+                    )?;
+                    if trashed > 0 {
+                        Self::record_audit(&conn, "delete", Some(flight_id), Some("no telemetry (library check)"))?;
+                    }
+                }
+                let repaired: i64 = issues.iter().filter(|i| i.repairable).map(|i| i.count).sum();
+                Self::record_audit(&conn, "check_library", None, Some(&format!("{} problems repaired", repaired)))?;
+                Ok(())
+            })?;
+            for path in &unused_files {
+                if let Err(e) = fs::remove_file(path) {
+                    log::warn!("Failed to remove unused file {:?}: {}", path, e);
+                }
+            }
+        }
+
+        log::info!(
+            "Library check{} found {} issues across {} tables in {:.1}s",
+            if repair { " and repair" } else { "" },
+            issues.len(),
+            tables.len(),
+            start.elapsed().as_secs_f64()
         );
-        log::info!("{}", msg);
-        Ok(msg)
+        Ok(LibraryCheckReport {
+            dry_run: !repair,
+            tables_checked: tables.len(),
+            issues,
+        })
     }
 }
 
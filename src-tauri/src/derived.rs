@@ -0,0 +1,195 @@
+//! Series derived from the logged telemetry for the flight charts.
+//!
+//! Path distance is the sum of the great-circle steps between consecutive
+//! GPS fixes, glitches left out. Imports store its total as the flight's
+//! distance, so the cumulative series always ends on the number the summary
+//! shows.
+//!
+//! Distances from home follow the home point as the aircraft logged it:
+//! each point is measured from the latest home point recorded at or before
+//! it, so a home point moved mid-flight (to a moving pilot, say) counts
+//! from then on.
+//!
+//! Logged heights step in small increments and jitter from sample to
+//! sample, so differences between neighbouring points swing wildly. Rates
+//! are taken over a window of `RATE_WINDOW_SECS` around each point instead,
+//! which is short enough to keep the start and end of a climb sharp.
+
+use crate::models::{HomePoint, TelemetryData};
+
+/// Time around a point, each way, that a rate is measured over
+const RATE_WINDOW_SECS: f64 = 1.0;
+
+/// Climb rate in m/s, positive when climbing, from heights at `time`
+/// seconds. Points without a height get none.
+pub fn vertical_speed(time: &[f64], height: &[Option<f64>]) -> Vec<Option<f64>> {
+    // (index, time, height) of the points with a height
+    let known: Vec<(usize, f64, f64)> = time
+        .iter()
+        .zip(height)
+        .enumerate()
+        .filter_map(|(i, (&t, h))| h.filter(|h| h.is_finite()).map(|h| (i, t, h)))
+        .collect();
+
+    let mut rates = vec![None; time.len()];
+    let (mut first, mut last) = (0, 0);
+    for (k, &(i, t, _)) in known.iter().enumerate() {
+        while known[first].1 < t - RATE_WINDOW_SECS {
+            first += 1;
+        }
+        last = last.max(k);
+        while last + 1 < known.len() && known[last + 1].1 <= t + RATE_WINDOW_SECS {
+            last += 1;
+        }
+        // Sparse points: fall back to the neighbours
+        let from = if first < k { first } else { k.saturating_sub(1) };
+        let to = if last > k { last } else { (k + 1).min(known.len() - 1) };
+        let (_, t0, h0) = known[from];
+        let (_, t1, h1) = known[to];
+        if t1 > t0 {
+            rates[i] = Some((h1 - h0) / (t1 - t0));
+        }
+    }
+    rates
+}
+
+/// Path distance in meters flown up to each `(timestamp ms, latitude,
+/// longitude)` point. Points without a fix, or with a glitched one (see
+/// `gps_filter`), keep the distance so far.
+pub fn cumulative_distance(points: impl IntoIterator<Item = (i64, Option<f64>, Option<f64>)>) -> Vec<f64> {
+    let mut total = 0.0;
+    let mut previous: Option<(f64, f64)> = None;
+    crate::gps_filter::filter_positions(points)
+        .into_iter()
+        .map(|fix| {
+            if let Some((lat, lon)) = fix {
+                if let Some((prev_lat, prev_lon)) = previous {
+                    total += crate::parser::haversine_distance(prev_lat, prev_lon, lat, lon);
+                }
+                previous = Some((lat, lon));
+            }
+            total
+        })
+        .collect()
+}
+
+/// Total path distance in meters, the last value of `cumulative_distance`
+pub fn path_distance(points: impl IntoIterator<Item = (i64, Option<f64>, Option<f64>)>) -> f64 {
+    cumulative_distance(points).last().copied().unwrap_or(0.0)
+}
+
+/// Horizontal and 3D distance from home in meters at each point, the 3D one
+/// adding the height above takeoff. `timestamps` are the points' log times,
+/// as in `home_points`. Before the first home point the flight's `home`
+/// counts, or without one the first GPS fix. Points without a position get
+/// neither distance, and points without a height no 3D distance.
+pub fn distance_from_home(
+    telemetry: &TelemetryData,
+    timestamps: &[i64],
+    home_points: &[HomePoint],
+    home: Option<(f64, f64)>,
+) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+    let fixes = crate::gps_filter::filter_positions((0..telemetry.time.len()).map(|i| {
+        (
+            timestamps.get(i).copied().unwrap_or_default(),
+            telemetry.latitude.get(i).copied().flatten(),
+            telemetry.longitude.get(i).copied().flatten(),
+        )
+    }));
+    let initial_home = home.or_else(|| fixes.iter().find_map(|fix| *fix));
+
+    let mut horizontal = vec![None; telemetry.time.len()];
+    let mut three_d = vec![None; telemetry.time.len()];
+    let mut next_home = 0;
+    let mut current_home = initial_home;
+    for i in 0..telemetry.time.len() {
+        let timestamp = timestamps.get(i).copied().unwrap_or(i64::MIN);
+        while let Some(point) = home_points.get(next_home).filter(|p| p.timestamp_ms <= timestamp) {
+            current_home = Some((point.lat, point.lon));
+            next_home += 1;
+        }
+        let (Some((home_lat, home_lon)), Some((lat, lon))) = (current_home, fixes[i]) else {
+            continue;
+        };
+        let distance = crate::parser::haversine_distance(home_lat, home_lon, lat, lon);
+        horizontal[i] = Some(distance);
+        three_d[i] = telemetry.height.get(i).copied().flatten().map(|h| distance.hypot(h));
+    }
+    (horizontal, three_d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cumulative_distance_skips_missing_fixes() {
+        let positions = [
+            (0, Some(0.0), Some(0.0)),
+            (10_000, Some(45.0), Some(7.0)),
+            (20_000, None, None),
+            (30_000, Some(45.001), Some(7.0)),
+            (40_000, Some(0.0), Some(0.0)),
+            (50_000, Some(45.002), Some(7.0)),
+        ];
+        let distances = cumulative_distance(positions);
+        assert_eq!(&distances[..3], &[0.0, 0.0, 0.0]);
+        assert!((distances[3] - 111.2).abs() < 0.5);
+        assert_eq!(distances[4], distances[3]);
+        assert!((distances[5] - 222.4).abs() < 1.0);
+        assert_eq!(path_distance(positions), distances[5]);
+    }
+
+    #[test]
+    fn distance_follows_a_moved_home_point() {
+        let telemetry = TelemetryData {
+            time: vec![0.0, 10.0, 20.0, 30.0],
+            latitude: vec![Some(0.0), Some(45.0), Some(45.001), Some(45.001)],
+            longitude: vec![Some(0.0), Some(7.0), Some(7.0), Some(7.0)],
+            height: vec![None, Some(0.0), Some(40.0), Some(40.0)],
+            ..Default::default()
+        };
+        let home_points = [HomePoint {
+            timestamp_ms: 30_000,
+            lat: 45.001,
+            lon: 7.0,
+            altitude: None,
+        }];
+
+        // No flight home: the first fix, skipping the 0,0 before it
+        let (horizontal, three_d) = distance_from_home(&telemetry, &[0, 10_000, 20_000, 30_000], &home_points, None);
+        assert_eq!((horizontal[0], three_d[0]), (None, None));
+        assert_eq!(horizontal[1], Some(0.0));
+        let north = horizontal[2].unwrap();
+        assert!((north - 111.2).abs() < 0.5);
+        assert!((three_d[2].unwrap() - north.hypot(40.0)).abs() < 1e-9);
+        // Home moved under the aircraft
+        assert_eq!((horizontal[3], three_d[3]), (Some(0.0), Some(40.0)));
+    }
+
+    #[test]
+    fn vertical_speed_follows_a_noisy_climb() {
+        // 10 Hz: hover, climb at 3 m/s with ±0.2 m of jitter, hover
+        let time: Vec<f64> = (0..300).map(|i| i as f64 / 10.0).collect();
+        let height: Vec<Option<f64>> = time
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| {
+                let jitter = if i % 2 == 0 { 0.2 } else { -0.2 };
+                let h = if t < 10.0 { 0.0 } else if t < 20.0 { (t - 10.0) * 3.0 } else { 30.0 };
+                Some(h + jitter)
+            })
+            .collect();
+
+        let rates = vertical_speed(&time, &height);
+        assert!(rates[10..90].iter().all(|r| r.unwrap().abs() < 0.25));
+        assert!(rates[115..185].iter().all(|r| (r.unwrap() - 3.0).abs() < 0.25));
+        assert!(rates[215..290].iter().all(|r| r.unwrap().abs() < 0.25));
+
+        let mut gaps = height.clone();
+        gaps[150] = None;
+        let rates = vertical_speed(&time, &gaps);
+        assert_eq!(rates[150], None);
+        assert!((rates[151].unwrap() - 3.0).abs() < 0.25);
+    }
+}
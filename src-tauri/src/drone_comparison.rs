@@ -0,0 +1,63 @@
+//! Two aircraft, or two models, side by side: flight time, flight length,
+//! energy efficiency, wind flown in and safety events.
+//!
+//! A side is a serial or a model name. A serial takes the flights of that
+//! aircraft; a model takes the flights of every aircraft of the model, so
+//! one model can be compared against another before an upgrade. Flights
+//! excluded from statistics don't count.
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{DroneComparison, DroneComparisonStats};
+
+/// Aggregates of `first` and `second`, each a serial or a model
+pub fn compare_drones(db: &Database, first: &str, second: &str) -> Result<DroneComparison, DatabaseError> {
+    let energy = db.get_flight_energy()?;
+    let safety_events = db.get_safety_events()?;
+    let side = |drone: &str| -> Result<DroneComparisonStats, DatabaseError> {
+        let drone = drone.trim();
+        let mut stats = db.get_drone_comparison_stats(drone)?;
+        let flights = energy
+            .iter()
+            .filter(|f| matches(drone, f.drone_serial.as_deref(), f.drone_model.as_deref()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !flights.is_empty() {
+            let drone_energy = crate::energy::drone_energy(flights);
+            stats.wh_per_km = drone_energy.wh_per_km;
+            stats.wh_per_min = drone_energy.wh_per_min;
+        }
+        stats.safety_events = crate::rth_events::summarize(
+            safety_events
+                .iter()
+                .filter(|f| matches(drone, f.drone_serial.as_deref(), f.drone_model.as_deref()))
+                .cloned()
+                .collect(),
+        )
+        .totals;
+        Ok(stats)
+    };
+
+    Ok(DroneComparison {
+        first: side(first)?,
+        second: side(second)?,
+    })
+}
+
+/// Whether a flight belongs to `drone`, the same match as the database uses
+fn matches(drone: &str, serial: Option<&str>, model: Option<&str>) -> bool {
+    serial.is_some_and(|s| s.trim().eq_ignore_ascii_case(drone)) || model == Some(drone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sides_match_a_serial_or_a_model() {
+        assert!(matches("1581F5FHD23", Some(" 1581f5fhd23 "), Some("Mini 4 Pro")));
+        assert!(matches("Mini 4 Pro", Some("1581F5FHD23"), Some("Mini 4 Pro")));
+        assert!(matches("Mini 4 Pro", None, Some("Mini 4 Pro")));
+        assert!(!matches("Mini 4 Pro", Some("1581F5FHD23"), Some("Mini 3 Pro")));
+        assert!(!matches("1581F5FHD23", None, Some("Mini 4 Pro")));
+    }
+}
@@ -5,15 +5,13 @@
 //! `time_s`, `lat`, `lng`, `alt_m`, `distance_to_home_m`.
 
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
 
 use chrono::{DateTime, NaiveDateTime, Utc, TimeZone};
 
 use crate::database::Database;
 use crate::models::{FlightMetadata, FlightStats, TelemetryPoint};
-use crate::parser::{ParseResult, ParserError, LogParser};
+use crate::parser::{ParseResult, ParserError, LogParser, LogSource};
 
 /// Parse a timestamp string flexibly, handling multiple formats:
 /// - RFC3339: "2026-02-01T14:35:52+00:00" or "2026-02-01T14:35:52Z"
@@ -141,7 +139,8 @@ impl<'a> DroneLogbookParser<'a> {
     }
 
     /// Check if a file is a valid Drone Logbook CSV export format
-    pub fn is_dronelogbook_csv(path: &Path) -> bool {
+    pub fn is_dronelogbook_csv(source: &LogSource) -> bool {
+        let path = source.path();
         // Must be a CSV file
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
         if !ext.eq_ignore_ascii_case("csv") {
@@ -149,7 +148,7 @@ impl<'a> DroneLogbookParser<'a> {
         }
 
         // Check header line for Drone Logbook-specific columns
-        if let Ok(file) = File::open(path) {
+        if let Ok(file) = source.open() {
             let reader = BufReader::new(file);
             for line in reader.lines() {
                 if let Ok(line_content) = line {
@@ -214,11 +213,12 @@ impl<'a> DroneLogbookParser<'a> {
     }
 
     /// Parse a Drone Logbook CSV file
-    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
         let parse_start = std::time::Instant::now();
         log::info!("Parsing Drone Logbook CSV file: {:?}", file_path);
 
-        let file = File::open(file_path)?;
+        let file = source.open()?;
         let reader = BufReader::new(file);
         let mut lines = reader.lines();
 
@@ -325,16 +325,12 @@ impl<'a> DroneLogbookParser<'a> {
         let mut last_valid_time_s: Option<f64> = None;
         
         // Track min/max for stats
-        let mut max_speed: f64 = 0.0;
         let mut max_altitude: f64 = 0.0;
-        let mut total_distance: f64 = 0.0;
-        let mut prev_lat: Option<f64> = None;
-        let mut prev_lon: Option<f64> = None;
         let mut home_lat: Option<f64> = meta_home_lat;
         let mut home_lon: Option<f64> = meta_home_lon;
 
         // Helper to parse a row into a TelemetryPoint
-        let parse_row = |fields: &[&str], col_map: &ColumnMap, first_valid_time_s: &mut Option<f64>, last_valid_time_s: &mut Option<f64>, prev_lat: &mut Option<f64>, prev_lon: &mut Option<f64>, home_lat: &mut Option<f64>, home_lon: &mut Option<f64>, max_speed: &mut f64, max_altitude: &mut f64, total_distance: &mut f64| -> Option<TelemetryPoint> {
+        let parse_row = |fields: &[&str], col_map: &ColumnMap, first_valid_time_s: &mut Option<f64>, last_valid_time_s: &mut Option<f64>, home_lat: &mut Option<f64>, home_lon: &mut Option<f64>, max_altitude: &mut f64| -> Option<TelemetryPoint> {
             // Get time in seconds and convert to milliseconds
             let time_s = col_map.get_f64(fields, "time_s");
             let timestamp_ms = time_s.map(|t| (t * 1000.0) as i64).unwrap_or(0);
@@ -357,22 +353,7 @@ impl<'a> DroneLogbookParser<'a> {
                 *home_lon = lon;
             }
 
-            // Calculate distance traveled
-            if let (Some(curr_lat), Some(curr_lon), Some(p_lat), Some(p_lon)) = (lat, lon, *prev_lat, *prev_lon) {
-                let dist = haversine_distance(p_lat, p_lon, curr_lat, curr_lon);
-                *total_distance += dist;
-            }
-            if lat.is_some() && lon.is_some() {
-                *prev_lat = lat;
-                *prev_lon = lon;
-            }
-
-            // Track max speed and altitude
-            if let Some(speed) = col_map.get_f64(fields, "speed_ms") {
-                if speed > *max_speed {
-                    *max_speed = speed;
-                }
-            }
+            // Track max altitude
             if let Some(alt) = col_map.get_f64(fields, "alt_m") {
                 if alt > *max_altitude {
                     *max_altitude = alt;
@@ -429,6 +410,12 @@ impl<'a> DroneLogbookParser<'a> {
                 // Camera state
                 is_photo: col_map.get_bool(fields, "is_photo"),
                 is_video: col_map.get_bool(fields, "is_video"),
+                cell_voltages: None,
+                compass_error: None,
+                compass_interference: None,
+                imu_status: None,
+                wind_speed: col_map.get_f64(fields, "wind_speed_ms"),
+                wind_direction: col_map.get_f64(fields, "wind_dir_deg"),
             };
 
             if point.latitude.is_some() && point.longitude.is_some() {
@@ -442,7 +429,7 @@ impl<'a> DroneLogbookParser<'a> {
         if let Some(ref first_line) = first_row_line {
             let fields: Vec<&str> = first_line.split(',').collect();
             if fields.len() >= headers.len() / 2 {
-                if let Some(point) = parse_row(&fields, &col_map, &mut first_valid_time_s, &mut last_valid_time_s, &mut prev_lat, &mut prev_lon, &mut home_lat, &mut home_lon, &mut max_speed, &mut max_altitude, &mut total_distance) {
+                if let Some(point) = parse_row(&fields, &col_map, &mut first_valid_time_s, &mut last_valid_time_s, &mut home_lat, &mut home_lon, &mut max_altitude) {
                     points.push(point);
                 }
             }
@@ -468,7 +455,7 @@ impl<'a> DroneLogbookParser<'a> {
                 continue;
             }
 
-            if let Some(point) = parse_row(&fields, &col_map, &mut first_valid_time_s, &mut last_valid_time_s, &mut prev_lat, &mut prev_lon, &mut home_lat, &mut home_lon, &mut max_speed, &mut max_altitude, &mut total_distance) {
+            if let Some(point) = parse_row(&fields, &col_map, &mut first_valid_time_s, &mut last_valid_time_s, &mut home_lat, &mut home_lon, &mut max_altitude) {
                 points.push(point);
             }
         }
@@ -477,6 +464,15 @@ impl<'a> DroneLogbookParser<'a> {
             return Err(ParserError::NoTelemetryData);
         }
 
+        // Calculate distance traveled, as for every other format
+        let total_distance = crate::derived::path_distance(points.iter().map(|p| (p.timestamp_ms, p.latitude, p.longitude)));
+
+        // Max speed without single-sample glitches
+        let max_speed = crate::gps_filter::filter_speeds(points.iter().map(|p| (p.timestamp_ms, p.speed)))
+            .into_iter()
+            .flatten()
+            .fold(0.0_f64, f64::max);
+
         // Calculate duration from time values
         let duration_secs = match (first_valid_time_s, last_valid_time_s) {
             (Some(first), Some(last)) => Some(last - first),
@@ -528,6 +524,13 @@ impl<'a> DroneLogbookParser<'a> {
             home_lat,
             home_lon,
             point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(
+                &points,
+                home_lat.zip(home_lon).map(|(lat, lon)| [lon, lat]),
+            ),
         };
 
         log::info!(
@@ -558,25 +561,10 @@ impl<'a> DroneLogbookParser<'a> {
         tags.insert(0, "Re-imported".to_string()); // Add tag at the beginning
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult { metadata, points, tags })
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors: Vec::new(), events: Vec::new() })
     }
 }
 
-/// Calculate haversine distance between two GPS coordinates in meters
-fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let r = 6371000.0; // Earth radius in meters
-    let phi1 = lat1.to_radians();
-    let phi2 = lat2.to_radians();
-    let delta_phi = (lat2 - lat1).to_radians();
-    let delta_lambda = (lon2 - lon1).to_radians();
-
-    let a = (delta_phi / 2.0).sin().powi(2)
-        + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
-    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-
-    r * c
-}
-
 /// Try to extract a datetime from a filename like "DJIFlightRecord_2026-01-27_10-56-42"
 fn extract_datetime_from_filename(filename: &str) -> Option<DateTime<Utc>> {
     // Try various patterns
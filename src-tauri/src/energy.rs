@@ -0,0 +1,84 @@
+//! Energy used per flight and the real-world efficiency of each aircraft.
+//!
+//! The database integrates battery voltage × current over each flight's
+//! telemetry; here the flights are grouped per aircraft and the totals turned
+//! into Wh per km and Wh per minute. Per-km figures only count flights that
+//! covered some ground, so hovering sessions don't skew a drone's average.
+
+use std::collections::BTreeMap;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{DroneEnergy, FlightEnergy};
+
+/// Energy figures of every aircraft, most energy used first
+pub fn energy_stats(db: &Database) -> Result<Vec<DroneEnergy>, DatabaseError> {
+    // Same key as the library statistics: the serial when known, otherwise the model
+    let mut by_drone: BTreeMap<String, Vec<FlightEnergy>> = BTreeMap::new();
+    for flight in db.get_flight_energy()? {
+        let key = match flight.drone_serial.as_deref().map(|s| s.trim().to_uppercase()) {
+            Some(serial) if !serial.is_empty() => serial,
+            _ => format!("model:{}", flight.drone_model.as_deref().unwrap_or("Unknown")),
+        };
+        by_drone.entry(key).or_default().push(flight);
+    }
+
+    let mut drones: Vec<DroneEnergy> = by_drone.into_values().map(drone_energy).collect();
+    drones.sort_by(|a, b| b.energy_wh.total_cmp(&a.energy_wh));
+    Ok(drones)
+}
+
+/// Totals and efficiency of one aircraft's flights, oldest first
+pub(crate) fn drone_energy(flights: Vec<FlightEnergy>) -> DroneEnergy {
+    let energy_wh: f64 = flights.iter().map(|f| f.energy_wh).sum();
+    let duration_secs: f64 = flights.iter().map(|f| f.duration_secs).sum();
+    let distance_m: f64 = flights.iter().map(|f| f.distance_m).sum();
+    let (moving_wh, moving_m) = flights
+        .iter()
+        .filter(|f| f.wh_per_km.is_some())
+        .fold((0.0, 0.0), |(wh, m), f| (wh + f.energy_wh, m + f.distance_m));
+
+    DroneEnergy {
+        drone_model: flights
+            .iter()
+            .rev()
+            .find_map(|f| f.drone_model.clone())
+            .unwrap_or_else(|| "Unknown".to_string()),
+        drone_serial: flights.iter().rev().find_map(|f| f.drone_serial.clone()),
+        flight_count: flights.len() as i64,
+        energy_wh,
+        duration_secs,
+        distance_m,
+        wh_per_km: (moving_m > 0.0).then(|| moving_wh / (moving_m / 1000.0)),
+        wh_per_min: (duration_secs > 0.0).then(|| energy_wh / (duration_secs / 60.0)),
+        flights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(energy_wh: f64, duration_secs: f64, distance_m: f64) -> FlightEnergy {
+        FlightEnergy {
+            flight_id: 1,
+            display_name: "Flight".to_string(),
+            start_time: None,
+            drone_model: Some("Mini 4 Pro".to_string()),
+            drone_serial: Some("SN1".to_string()),
+            energy_wh,
+            duration_secs,
+            distance_m,
+            wh_per_km: (distance_m >= 100.0).then(|| energy_wh / (distance_m / 1000.0)),
+            wh_per_min: Some(energy_wh / (duration_secs / 60.0)),
+        }
+    }
+
+    #[test]
+    fn per_km_ignores_hovering_flights() {
+        let drone = drone_energy(vec![flight(20.0, 1200.0, 4000.0), flight(10.0, 600.0, 20.0)]);
+        assert_eq!(drone.flight_count, 2);
+        assert!((drone.energy_wh - 30.0).abs() < 1e-9);
+        assert!((drone.wh_per_km.unwrap() - 5.0).abs() < 1e-9);
+        assert!((drone.wh_per_min.unwrap() - 1.0).abs() < 1e-9);
+    }
+}
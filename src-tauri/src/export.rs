@@ -0,0 +1,1530 @@
+//! Exporting flights to file formats read by other tools.
+//!
+//! - GPX 1.1: one track per flight, with speed and course in Garmin's
+//!   TrackPointExtension (`gpxtpx`) so fitness and GIS tools pick them up
+//! - KML/KMZ: the path as a LineString extruded to the ground, a `gx:Track`
+//!   for Google Earth's time slider, takeoff/landing/photo placemarks and
+//!   optionally a `gx:Tour` that flies along the path
+//! - CSV: every stored telemetry column, converted to the chosen units
+//! - GeoJSON: the path as a LineString plus a Point per event, with the
+//!   altitude, speed and battery level at that moment as properties
+//! - JSON: the flight record, its events and full-resolution telemetry
+//! - Overlay CSV: altitude, speed, distance and battery at fixed intervals
+//!   on the video's time axis, for gauge overlays in DashWare-style tools
+//! - SRT: DJI-style per-second subtitles for a video recorded in the flight,
+//!   for when the aircraft's own SRT file is lost
+//! - MAVLink tlog: position, attitude and battery as GLOBAL_POSITION_INT,
+//!   ATTITUDE and SYS_STATUS messages, for MAVLink log analysis tools
+//!
+//! `flights_xlsx` puts a summary of many flights, and optionally their
+//! telemetry, into one Excel workbook.
+//!
+//! `export_batch` writes many flights in one go, one file per flight plus a
+//! `summary.csv` listing what was exported.
+
+use std::fmt::Write;
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::mavlink::{Message, TlogWriter};
+use crate::models::{BatchExportResult, Flight, FlightEvent, FlightFilter, TelemetryRecord};
+use crate::parser::haversine_distance;
+use crate::telemetry_pack::Series;
+use crate::xlsx::{Cell, Sheet, Workbook};
+
+/// Name written as the creator of exported files
+const CREATOR: &str = concat!("Drone Logbook ", env!("CARGO_PKG_VERSION"));
+
+/// Points closer than this (meters) don't change the course, so hovering
+/// doesn't make it spin on GPS noise
+const MIN_COURSE_DISTANCE_M: f64 = 0.5;
+
+/// Upper bound on the camera moves of a KML tour; longer flights are sampled
+const MAX_TOUR_STEPS: usize = 300;
+
+/// Row spacing of the overlay export unless asked otherwise (10 Hz)
+const DEFAULT_OVERLAY_INTERVAL_SECS: f64 = 0.1;
+
+/// Finer overlay rows than this only repeat interpolated values
+const MIN_OVERLAY_INTERVAL_SECS: f64 = 0.02;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("ZIP error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Flight {0} has no GPS positions")]
+    NoPositions(i64),
+
+    #[error("Flight {0} has no telemetry")]
+    NoTelemetry(i64),
+
+    #[error("Flight {0} has no such video recording")]
+    NoRecording(i64),
+}
+
+/// Per-flight file formats of the batch export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Gpx,
+    Kml,
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gpx => "gpx",
+            Self::Kml => "kml",
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Progress snapshot reported after each flight of a batch export
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExportProgress {
+    pub current_flight: String,
+    pub processed: usize,
+    pub total: usize,
+    pub percent: f64,
+    pub failures: usize,
+}
+
+/// Unit of heights and altitudes in exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[default]
+    #[serde(rename = "m")]
+    Meters,
+    #[serde(rename = "ft")]
+    Feet,
+}
+
+/// Unit of speeds in exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeedUnit {
+    #[default]
+    #[serde(rename = "ms")]
+    MetersPerSecond,
+    #[serde(rename = "kmh")]
+    KilometersPerHour,
+    #[serde(rename = "mph")]
+    MilesPerHour,
+    #[serde(rename = "kn")]
+    Knots,
+}
+
+/// Unit of temperatures in exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    #[default]
+    #[serde(rename = "c")]
+    Celsius,
+    #[serde(rename = "f")]
+    Fahrenheit,
+}
+
+/// Units exported values are converted to; the database stores SI units
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportUnits {
+    #[serde(default)]
+    pub length: LengthUnit,
+    #[serde(default)]
+    pub speed: SpeedUnit,
+    #[serde(default)]
+    pub temperature: TemperatureUnit,
+}
+
+/// Time alignment of the overlay export
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayOptions {
+    /// Seconds into the flight at which the video starts. Defaults to the
+    /// start of the first recording in the log, or the start of the log.
+    #[serde(default)]
+    pub offset_secs: Option<f64>,
+    /// Seconds between rows
+    #[serde(default = "default_overlay_interval")]
+    pub interval_secs: f64,
+}
+
+impl Default for OverlayOptions {
+    fn default() -> Self {
+        Self {
+            offset_secs: None,
+            interval_secs: DEFAULT_OVERLAY_INTERVAL_SECS,
+        }
+    }
+}
+
+fn default_overlay_interval() -> f64 {
+    DEFAULT_OVERLAY_INTERVAL_SECS
+}
+
+impl LengthUnit {
+    /// Value in this unit of a length in meters
+    pub fn convert(&self, meters: f64) -> f64 {
+        match self {
+            Self::Meters => meters,
+            Self::Feet => meters / 0.3048,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Meters => "m",
+            Self::Feet => "ft",
+        }
+    }
+}
+
+impl SpeedUnit {
+    /// Value in this unit of a speed in m/s
+    pub fn convert(&self, speed: f64) -> f64 {
+        match self {
+            Self::MetersPerSecond => speed,
+            Self::KilometersPerHour => speed * 3.6,
+            Self::MilesPerHour => speed * 3600.0 / 1609.344,
+            Self::Knots => speed * 3600.0 / 1852.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::MetersPerSecond => "m/s",
+            Self::KilometersPerHour => "km/h",
+            Self::MilesPerHour => "mph",
+            Self::Knots => "kn",
+        }
+    }
+}
+
+impl TemperatureUnit {
+    /// Value in this unit of a temperature in °C
+    pub fn convert(&self, celsius: f64) -> f64 {
+        match self {
+            Self::Celsius => celsius,
+            Self::Fahrenheit => celsius * 1.8 + 32.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Celsius => "°C",
+            Self::Fahrenheit => "°F",
+        }
+    }
+}
+
+impl ExportUnits {
+    /// Suffix for the CSV header and linear conversion `(scale, offset)` of
+    /// the stored value, for the telemetry columns that have a unit
+    fn for_column(&self, column: &str) -> Option<(&'static str, f64, f64)> {
+        match column {
+            "altitude" | "height" | "vps_height" | "altitude_abs" => {
+                Some((self.length.label(), self.length.convert(1.0), 0.0))
+            }
+            "speed" | "velocity_x" | "velocity_y" | "velocity_z" | "wind_speed" => {
+                let suffix = match self.speed {
+                    SpeedUnit::MetersPerSecond => "ms",
+                    SpeedUnit::KilometersPerHour => "kmh",
+                    SpeedUnit::MilesPerHour => "mph",
+                    SpeedUnit::Knots => "kn",
+                };
+                Some((suffix, self.speed.convert(1.0), 0.0))
+            }
+            "battery_temp" => Some(match self.temperature {
+                TemperatureUnit::Celsius => ("c", 1.0, 0.0),
+                TemperatureUnit::Fahrenheit => ("f", 1.8, 32.0),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// File name for an exported flight: its display name reduced to characters
+/// that are safe on every filesystem
+pub fn file_name(flight: &Flight, extension: &str) -> String {
+    let mut base = String::new();
+    for c in flight.display_name.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            base.push(c);
+        } else if !base.ends_with('_') {
+            base.push('_');
+        }
+    }
+    let base: String = base.trim_matches('_').chars().take(80).collect();
+    let base = if base.is_empty() { format!("flight_{}", flight.id) } else { base };
+    format!("{}.{}", base, extension)
+}
+
+/// GPX document of a flight at full telemetry resolution
+pub fn flight_gpx(db: &Database, flight_id: i64) -> Result<String, ExportError> {
+    let (flight, records) = load_track(db, flight_id)?;
+    Ok(write_gpx(&flight, &records))
+}
+
+/// KML document of a flight at full telemetry resolution, with a flythrough
+/// tour when `tour` is set
+pub fn flight_kml(db: &Database, flight_id: i64, tour: bool) -> Result<String, ExportError> {
+    let (flight, records) = load_track(db, flight_id)?;
+    let events = db.get_flight_events(flight_id)?;
+    Ok(write_kml(&flight, &records, &events, tour))
+}
+
+/// The KML document zipped as `doc.kml`, the layout Google Earth expects
+pub fn flight_kmz(db: &Database, flight_id: i64, tour: bool) -> Result<Vec<u8>, ExportError> {
+    let kml = flight_kml(db, flight_id, tour)?;
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file("doc.kml", options)?;
+    std::io::Write::write_all(&mut zip, kml.as_bytes())?;
+    Ok(zip.finish()?.into_inner())
+}
+
+/// CSV of the telemetry of a flight, one row per point. The first columns
+/// are the seconds since takeoff and, when the start time is known, the UTC
+/// time of the point. Then follow the telemetry columns named in `selected`,
+/// in that order, or every stored column if `selected` is empty.
+pub fn flight_csv(
+    db: &Database,
+    flight_id: i64,
+    selected: &[String],
+    units: &ExportUnits,
+) -> Result<String, ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let columns = db.get_flight_telemetry_series(flight_id)?;
+    Ok(write_csv(&flight, &columns, selected, units))
+}
+
+fn write_csv(flight: &Flight, all_columns: &[(String, Series)], selected: &[String], units: &ExportUnits) -> String {
+    let start = flight_start(flight);
+    let timestamps: &[Option<i64>] = all_columns
+        .iter()
+        .find_map(|(name, series)| match series {
+            Series::Int(v) if name == "timestamp_ms" => Some(v.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[]);
+    let first_ts = timestamps.first().copied().flatten().unwrap_or(0);
+    let columns: Vec<&(String, Series)> = if selected.is_empty() {
+        all_columns.iter().collect()
+    } else {
+        selected
+            .iter()
+            .filter_map(|name| all_columns.iter().find(|(column, _)| column == name))
+            .collect()
+    };
+
+    let mut header = vec!["time_s".to_string()];
+    if start.is_some() {
+        header.push("utc_time".to_string());
+    }
+    for (name, _) in &columns {
+        match units.for_column(name) {
+            Some((suffix, ..)) => header.push(format!("{}_{}", name, suffix)),
+            None => header.push(name.clone()),
+        }
+    }
+
+    let mut csv = header.join(",");
+    csv.push('\n');
+    let row_count = all_columns.first().map(|(_, series)| series.len()).unwrap_or(0);
+    for i in 0..row_count {
+        let ts = timestamps.get(i).copied().flatten().unwrap_or(first_ts);
+        let _ = write!(csv, "{}", (ts - first_ts) as f64 / 1000.0);
+        if let Some(start) = start {
+            let _ = write!(csv, ",{}", gpx_time(point_time(start, first_ts, ts)));
+        }
+        for (name, series) in &columns {
+            csv.push(',');
+            match series {
+                Series::Int(v) => {
+                    if let Some(value) = v[i] {
+                        let _ = write!(csv, "{}", value);
+                    }
+                }
+                Series::Float(v) => {
+                    if let Some(value) = v[i].filter(|f| f.is_finite()) {
+                        match units.for_column(name) {
+                            // Rounded so unit conversions don't print float noise
+                            Some((_, scale, offset)) => {
+                                let _ = write!(csv, "{}", ((value * scale + offset) * 1e6).round() / 1e6);
+                            }
+                            None => {
+                                let _ = write!(csv, "{}", value);
+                            }
+                        }
+                    }
+                }
+                Series::Bool(v) => {
+                    if let Some(value) = v[i] {
+                        csv.push(if value { '1' } else { '0' });
+                    }
+                }
+                Series::Text(v) => {
+                    if let Some(value) = &v[i] {
+                        csv.push_str(&csv_escape(value));
+                    }
+                }
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Telemetry on the time axis of a video, interpolated to one row every
+/// `interval_secs`: position, height, speed, distance flown, distance from
+/// home, battery and heading. Without an offset the rows cover the first
+/// recording in the log.
+pub fn flight_overlay_csv(
+    db: &Database,
+    flight_id: i64,
+    options: &OverlayOptions,
+    units: &ExportUnits,
+) -> Result<String, ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(flight_id, Some(usize::MAX), flight.point_count.map(i64::from))?;
+    if records.is_empty() {
+        return Err(ExportError::NoTelemetry(flight_id));
+    }
+    let events = db.get_flight_events(flight_id)?;
+    Ok(write_overlay_csv(&flight, &records, &events, options, units))
+}
+
+/// SubRip subtitles for the `recording`-th video (0-based) recorded during
+/// the flight, in the style of the SRT files DJI aircraft write: one caption
+/// per second with the UTC time, position, distance from home, height and
+/// horizontal/vertical speed, in metric units. The captions start with the
+/// recording, so the file lines up with the video it belongs to, and can be
+/// imported again like an original SRT.
+pub fn flight_srt(db: &Database, flight_id: i64, recording: usize) -> Result<String, ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(flight_id, Some(usize::MAX), flight.point_count.map(i64::from))?;
+    if records.is_empty() {
+        return Err(ExportError::NoTelemetry(flight_id));
+    }
+    let events = db.get_flight_events(flight_id)?;
+    let (start, end) = recording_window(&records, &events, recording).ok_or(ExportError::NoRecording(flight_id))?;
+    Ok(write_srt(&flight, &records, start, end))
+}
+
+/// Values of one telemetry record for the overlay and SRT exports, distances
+/// in meters
+#[derive(Debug, Clone, Copy)]
+struct OverlaySample {
+    time: f64,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    height: Option<f64>,
+    speed: Option<f64>,
+    distance: f64,
+    home_distance: Option<f64>,
+    battery_percent: Option<f64>,
+    battery_voltage: Option<f64>,
+    heading: Option<f64>,
+    satellites: Option<i32>,
+}
+
+/// Start and end (seconds from the first record) of the `index`-th video
+/// recording. A recording without a length runs to the end of the log.
+fn recording_window(records: &[TelemetryRecord], events: &[FlightEvent], index: usize) -> Option<(f64, f64)> {
+    let first_ts = records.first()?.timestamp_ms;
+    let flight_end = (records[records.len() - 1].timestamp_ms - first_ts) as f64 / 1000.0;
+    let event = events.iter().filter(|e| e.event_type == "video").nth(index)?;
+    let start = (event.timestamp_ms - first_ts) as f64 / 1000.0;
+    let end = event.duration_ms.map(|d| start + d as f64 / 1000.0);
+    Some((start, end.unwrap_or(flight_end).min(flight_end)))
+}
+
+/// Per-record values for the overlay and SRT exports, with the distance
+/// flown so far and the distance from the home point (or the first fix)
+fn overlay_samples(flight: &Flight, records: &[TelemetryRecord]) -> Vec<OverlaySample> {
+    let first_ts = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+    let home = match (flight.home_lat, flight.home_lon) {
+        (Some(lat), Some(lon)) => Some((lat, lon)),
+        _ => records.iter().find_map(position),
+    };
+    let mut distance = 0.0;
+    let mut previous: Option<(f64, f64)> = None;
+    records
+        .iter()
+        .map(|record| {
+            let point = position(record);
+            if let (Some((lat, lon)), Some((prev_lat, prev_lon))) = (point, previous) {
+                distance += haversine_distance(prev_lat, prev_lon, lat, lon);
+            }
+            previous = point.or(previous);
+            OverlaySample {
+                time: (record.timestamp_ms - first_ts) as f64 / 1000.0,
+                latitude: point.map(|p| p.0),
+                longitude: point.map(|p| p.1),
+                height: record.height.or(record.altitude),
+                speed: record.speed,
+                distance,
+                home_distance: point
+                    .zip(home)
+                    .map(|((lat, lon), (home_lat, home_lon))| haversine_distance(home_lat, home_lon, lat, lon)),
+                battery_percent: record.battery_percent.map(f64::from),
+                battery_voltage: record.battery_voltage,
+                heading: record.yaw.map(|yaw| yaw.rem_euclid(360.0)),
+                satellites: record.satellites,
+            }
+        })
+        .collect()
+}
+
+/// Values at `time`, interpolated between the samples around it. Times must
+/// be asked for in increasing order; `index` keeps the search position.
+fn sample_at(samples: &[OverlaySample], index: &mut usize, time: f64) -> OverlaySample {
+    while *index + 1 < samples.len() && samples[*index + 1].time <= time {
+        *index += 1;
+    }
+    let a = samples[*index];
+    let b = samples.get(*index + 1).copied().unwrap_or(a);
+    let fraction = if b.time > a.time { ((time - a.time) / (b.time - a.time)).clamp(0.0, 1.0) } else { 0.0 };
+    let nearest = |x: Option<f64>, y: Option<f64>| if fraction < 0.5 { x } else { y };
+    let lerp = |x: Option<f64>, y: Option<f64>| match (x, y) {
+        (Some(x), Some(y)) => Some(x + (y - x) * fraction),
+        (x, y) => nearest(x, y),
+    };
+    OverlaySample {
+        time,
+        latitude: lerp(a.latitude, b.latitude),
+        longitude: lerp(a.longitude, b.longitude),
+        height: lerp(a.height, b.height),
+        speed: lerp(a.speed, b.speed),
+        distance: a.distance + (b.distance - a.distance) * fraction,
+        home_distance: lerp(a.home_distance, b.home_distance),
+        battery_percent: lerp(a.battery_percent, b.battery_percent),
+        battery_voltage: lerp(a.battery_voltage, b.battery_voltage),
+        // Headings interpolate the short way round, e.g. 350° to 10° passes 0°
+        heading: match (a.heading, b.heading) {
+            (Some(x), Some(y)) => Some((x + ((y - x + 540.0).rem_euclid(360.0) - 180.0) * fraction).rem_euclid(360.0)),
+            (x, y) => nearest(x, y),
+        },
+        satellites: if fraction < 0.5 { a.satellites } else { b.satellites },
+    }
+}
+
+fn write_overlay_csv(
+    flight: &Flight,
+    records: &[TelemetryRecord],
+    events: &[FlightEvent],
+    options: &OverlayOptions,
+    units: &ExportUnits,
+) -> String {
+    let first_ts = records[0].timestamp_ms;
+    let flight_end = (records[records.len() - 1].timestamp_ms - first_ts) as f64 / 1000.0;
+    let (video_start, video_end) = match options.offset_secs {
+        Some(offset) => (offset, flight_end),
+        None => recording_window(records, events, 0).unwrap_or((0.0, flight_end)),
+    };
+    let interval = if options.interval_secs.is_finite() && options.interval_secs > 0.0 {
+        options.interval_secs.max(MIN_OVERLAY_INTERVAL_SECS)
+    } else {
+        DEFAULT_OVERLAY_INTERVAL_SECS
+    };
+    let samples = overlay_samples(flight, records);
+
+    let start = flight_start(flight);
+    let length = units.length.label();
+    let speed = units.for_column("speed").map(|(suffix, ..)| suffix).unwrap_or("ms");
+    let mut header = vec!["video_time_s".to_string(), "flight_time_s".to_string()];
+    if start.is_some() {
+        header.push("utc_time".to_string());
+    }
+    header.extend([
+        "latitude".to_string(),
+        "longitude".to_string(),
+        format!("height_{}", length),
+        format!("speed_{}", speed),
+        format!("distance_{}", length),
+        format!("home_distance_{}", length),
+        "battery_percent".to_string(),
+        "battery_voltage_v".to_string(),
+        "heading_deg".to_string(),
+    ]);
+    let mut csv = header.join(",");
+    csv.push('\n');
+
+    let rows = ((video_end - video_start) / interval).floor().max(0.0) as usize + 1;
+    let mut index = 0;
+    for row in 0..rows {
+        let video_time = row as f64 * interval;
+        let time = video_start + video_time;
+        // Video before the log started or after it ended has no telemetry
+        if time < 0.0 || time > flight_end {
+            continue;
+        }
+        let sample = sample_at(&samples, &mut index, time);
+
+        let _ = write!(csv, "{},{}", round_to(video_time, 3), round_to(time, 3));
+        if let Some(start) = start {
+            let ts = first_ts + (time * 1000.0).round() as i64;
+            let _ = write!(csv, ",{}", gpx_time(point_time(start, first_ts, ts)));
+        }
+        let values = [
+            (sample.latitude, 7),
+            (sample.longitude, 7),
+            (sample.height.map(|h| units.length.convert(h)), 2),
+            (sample.speed.map(|s| units.speed.convert(s)), 2),
+            (Some(units.length.convert(sample.distance)), 1),
+            (sample.home_distance.map(|d| units.length.convert(d)), 1),
+            (sample.battery_percent, 0),
+            (sample.battery_voltage, 2),
+            (sample.heading, 1),
+        ];
+        for (value, decimals) in values {
+            csv.push(',');
+            if let Some(value) = value.filter(|v| v.is_finite()) {
+                let _ = write!(csv, "{}", round_to(value, decimals));
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// One caption per second of the video from `video_start` to `video_end`
+/// (seconds from the first record)
+fn write_srt(flight: &Flight, records: &[TelemetryRecord], video_start: f64, video_end: f64) -> String {
+    let first_ts = records[0].timestamp_ms;
+    let start = flight_start(flight);
+    let samples = overlay_samples(flight, records);
+    let duration = (video_end - video_start).max(0.0);
+    let captions = (duration.ceil() as usize).max(1);
+
+    // One sample per second plus the one after the last caption, for the
+    // vertical speed over each second
+    let mut index = 0;
+    let per_second: Vec<OverlaySample> = (0..=captions)
+        .map(|second| sample_at(&samples, &mut index, (video_start + second as f64).max(0.0)))
+        .collect();
+
+    let mut srt = String::new();
+    for (second, window) in per_second.windows(2).enumerate() {
+        let (sample, next) = (window[0], window[1]);
+        let cue_start = second as f64;
+        let cue_end = (cue_start + 1.0).min(duration.max(cue_start + 0.001));
+        let _ = writeln!(srt, "{}", second + 1);
+        let _ = writeln!(srt, "{} --> {}", srt_time(cue_start), srt_time(cue_end));
+        if let Some(start) = start {
+            let ts = first_ts + (sample.time * 1000.0).round() as i64;
+            let _ = writeln!(srt, "{}", point_time(start, first_ts, ts).format("%Y-%m-%d %H:%M:%S"));
+        }
+
+        let mut parts = Vec::new();
+        match (sample.latitude, sample.longitude, sample.satellites) {
+            (Some(lat), Some(lon), Some(satellites)) => {
+                parts.push(format!("GPS ({:.6}, {:.6}, {})", lon, lat, satellites))
+            }
+            (Some(lat), Some(lon), None) => parts.push(format!("GPS ({:.6}, {:.6})", lon, lat)),
+            _ => {}
+        }
+        if let Some(distance) = sample.home_distance {
+            parts.push(format!("D {:.1}m", distance));
+        }
+        if let Some(height) = sample.height {
+            parts.push(format!("H {:.1}m", height));
+        }
+        if let Some(speed) = sample.speed {
+            parts.push(format!("HS {:.1}m/s", speed));
+        }
+        if let (Some(height), Some(next_height)) = (sample.height, next.height) {
+            let dt = next.time - sample.time;
+            if dt > 0.0 {
+                parts.push(format!("VS {:.1}m/s", (next_height - height) / dt));
+            }
+        }
+        let _ = writeln!(srt, "{}", parts.join(", "));
+        srt.push('\n');
+    }
+    srt
+}
+
+/// SRT cue time `HH:MM:SS,mmm`
+fn srt_time(secs: f64) -> String {
+    let ms = (secs * 1000.0).round() as i64;
+    format!("{:02}:{:02}:{:02},{:03}", ms / 3_600_000, (ms / 60_000) % 60, (ms / 1000) % 60, ms % 1000)
+}
+
+/// GeoJSON FeatureCollection of a flight: the track, takeoff, landing and
+/// every located event. Coordinates are `[lon, lat, height above takeoff]`.
+pub fn flight_geojson(db: &Database, flight_id: i64) -> Result<String, ExportError> {
+    let (flight, records) = load_track(db, flight_id)?;
+    let events = db.get_flight_events(flight_id)?;
+    let geojson = build_geojson(&flight, &records, &events);
+    Ok(serde_json::to_string_pretty(&geojson).unwrap_or_default())
+}
+
+fn build_geojson(flight: &Flight, records: &[TelemetryRecord], events: &[FlightEvent]) -> serde_json::Value {
+    use serde_json::json;
+
+    let start = flight_start(flight);
+    let first_ts = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+    let utc = |ts: i64| start.map(|s| gpx_time(point_time(s, first_ts, ts)));
+    let located: Vec<&TelemetryRecord> = records.iter().filter(|r| position(r).is_some()).collect();
+    let coordinate = |lat: f64, lon: f64, height: Option<f64>| {
+        json!([round_to(lon, 7), round_to(lat, 7), round_to(height.unwrap_or(0.0), 2)])
+    };
+
+    let mut features = vec![json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": located
+                .iter()
+                .map(|r| coordinate(r.latitude.unwrap_or_default(), r.longitude.unwrap_or_default(), r.height))
+                .collect::<Vec<_>>(),
+        },
+        "properties": {
+            "featureType": "track",
+            "flightId": flight.id,
+            "name": flight.display_name,
+            "droneModel": flight.drone_model,
+            "droneSerial": flight.drone_serial,
+            "batterySerial": flight.battery_serial,
+            "startTime": start.map(gpx_time),
+            "durationSecs": flight.duration_secs,
+            "totalDistanceM": flight.total_distance,
+            "maxAltitudeM": flight.max_altitude,
+            "maxSpeedMs": flight.max_speed,
+            "notes": flight.notes,
+        },
+    })];
+
+    // Telemetry at or just before a moment, for the event properties
+    let state_at = |ts: i64| {
+        let end = records.partition_point(|r| r.timestamp_ms <= ts);
+        records[..end.max(1).min(records.len())].last()
+    };
+    let mut point = |feature_type: &str, ts: i64, lat: f64, lon: f64, height: Option<f64>, extra: serde_json::Value| {
+        let state = state_at(ts);
+        let mut properties = json!({
+            "featureType": feature_type,
+            "flightId": flight.id,
+            "offsetSecs": (ts - first_ts) as f64 / 1000.0,
+            "time": utc(ts),
+            "heightM": height.or(state.and_then(|r| r.height)),
+            "altitudeM": state.and_then(|r| r.altitude),
+            "speedMs": state.and_then(|r| r.speed),
+            "batteryPercent": state.and_then(|r| r.battery_percent),
+            "batteryVoltage": state.and_then(|r| r.battery_voltage),
+            "satellites": state.and_then(|r| r.satellites),
+        });
+        if let (Some(properties), Some(extra)) = (properties.as_object_mut(), extra.as_object()) {
+            properties.extend(extra.clone());
+        }
+        features.push(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": coordinate(lat, lon, height) },
+            "properties": properties,
+        }));
+    };
+
+    if let (Some(first), Some(last)) = (located.first(), located.last()) {
+        let (lat, lon) = position(first).unwrap_or_default();
+        point("takeoff", first.timestamp_ms, lat, lon, Some(0.0), json!({}));
+        let (lat, lon) = position(last).unwrap_or_default();
+        point("landing", last.timestamp_ms, lat, lon, Some(0.0), json!({}));
+    }
+    for event in events {
+        let (Some(lat), Some(lon)) = (event.lat, event.lon) else { continue };
+        point(
+            "event",
+            event.timestamp_ms,
+            lat,
+            lon,
+            event.altitude,
+            json!({
+                "eventType": event.event_type,
+                "durationMs": event.duration_ms,
+                "detail": event.detail,
+                "severity": event.severity,
+            }),
+        );
+    }
+
+    json!({
+        "type": "FeatureCollection",
+        "name": flight.display_name,
+        "features": features,
+    })
+}
+
+/// JSON document of a flight: its record, events and full-resolution telemetry
+pub fn flight_json(db: &Database, flight_id: i64) -> Result<String, ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let telemetry = db.get_flight_telemetry(flight_id, Some(usize::MAX), flight.point_count.map(i64::from))?;
+    let events = db.get_flight_events(flight_id)?;
+    let document = serde_json::json!({
+        "_exportInfo": {
+            "format": "Drone Logbook JSON Export",
+            "appVersion": env!("CARGO_PKG_VERSION"),
+            "exportedAt": gpx_time(Utc::now()),
+        },
+        "flight": flight,
+        "events": events,
+        "telemetry": telemetry,
+    });
+    Ok(serde_json::to_string_pretty(&document).unwrap_or_default())
+}
+
+/// JSON document of the library's aggregate statistics: totals, per-drone,
+/// per-battery and per-month figures and the record flights. Flights
+/// excluded from statistics are left out, as on the overview.
+pub fn library_stats_json(db: &Database) -> Result<String, ExportError> {
+    let library = db.get_library_stats(None)?;
+    let overview = db.get_overview_stats()?;
+    let records = db.get_personal_records(None)?;
+    let document = serde_json::json!({
+        "_exportInfo": {
+            "format": "Drone Logbook Statistics Export",
+            "appVersion": env!("CARGO_PKG_VERSION"),
+            "exportedAt": gpx_time(Utc::now()),
+        },
+        "totals": {
+            "flightCount": library.total_flights,
+            "totalDurationSecs": library.total_duration_secs,
+            "totalDistanceM": library.total_distance_m,
+            "telemetryPoints": library.total_points,
+            "maxAltitudeM": library.max_altitude_m,
+            "maxDistanceFromHomeM": overview.max_distance_from_home_m,
+            "firstFlight": library.first_flight,
+            "lastFlight": library.last_flight,
+        },
+        "drones": library.drones,
+        "batteries": overview.batteries_used,
+        "months": library.months,
+        "records": records,
+    });
+    Ok(serde_json::to_string_pretty(&document).unwrap_or_default())
+}
+
+/// MAVLink telemetry log of a flight, for tools that only read tlogs
+pub fn flight_tlog(db: &Database, flight_id: i64) -> Result<Vec<u8>, ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(flight_id, Some(usize::MAX), flight.point_count.map(i64::from))?;
+    if records.is_empty() {
+        return Err(ExportError::NoTelemetry(flight_id));
+    }
+    Ok(write_tlog(&flight, &records))
+}
+
+fn write_tlog(flight: &Flight, records: &[TelemetryRecord]) -> Vec<u8> {
+    // Without a start time the log starts at the epoch; the tools only need
+    // the relative timing
+    let start_us = flight_start(flight).map_or(0, |s| s.timestamp_micros().max(0) as u64);
+    let first_ts = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+    let scaled = |value: Option<f64>, factor: f64| value.map_or(0.0, |v| (v * factor).round());
+
+    let mut tlog = TlogWriter::new();
+    let mut next_status_ms = i64::MIN;
+    for record in records {
+        let time_boot_ms = (record.timestamp_ms - first_ts).max(0);
+        let time_us = start_us + time_boot_ms as u64 * 1000;
+        let time_boot_ms = time_boot_ms as u32;
+
+        // Heartbeat and battery once a second, as an autopilot sends them
+        if record.timestamp_ms >= next_status_ms {
+            tlog.push(time_us, &Message::Heartbeat { armed: true });
+            tlog.push(
+                time_us,
+                &Message::SysStatus {
+                    voltage_mv: record.battery_voltage.map(|v| (v * 1000.0).round().clamp(0.0, 65_534.0) as u16),
+                    battery_remaining: record.battery_percent.map(|p| p.clamp(0, 100) as i8),
+                },
+            );
+            next_status_ms = record.timestamp_ms + 1000;
+        }
+        if let (Some(roll), Some(pitch), Some(yaw)) = (record.roll, record.pitch, record.yaw) {
+            tlog.push(
+                time_us,
+                &Message::Attitude {
+                    time_boot_ms,
+                    roll: roll.to_radians() as f32,
+                    pitch: pitch.to_radians() as f32,
+                    yaw: yaw.to_radians() as f32,
+                },
+            );
+        }
+        if let Some((lat, lon)) = position(record) {
+            tlog.push(
+                time_us,
+                &Message::GlobalPositionInt {
+                    time_boot_ms,
+                    lat: (lat * 1e7).round() as i32,
+                    lon: (lon * 1e7).round() as i32,
+                    alt: scaled(record.altitude.or(record.height), 1000.0) as i32,
+                    relative_alt: scaled(record.height.or(record.altitude), 1000.0) as i32,
+                    vx: scaled(record.velocity_x, 100.0) as i16,
+                    vy: scaled(record.velocity_y, 100.0) as i16,
+                    vz: scaled(record.velocity_z, 100.0) as i16,
+                    heading: record.yaw.map(|yaw| (yaw.rem_euclid(360.0) * 100.0).round() as u16 % 36000),
+                },
+            );
+        }
+    }
+    tlog.finish()
+}
+
+/// Excel workbook of the flights matching `filter`: a "Flights" sheet with
+/// one row per flight and, with `telemetry`, a sheet per flight with every
+/// stored telemetry column. Values are converted to `units`.
+pub fn flights_xlsx(
+    db: &Database,
+    filter: &FlightFilter,
+    telemetry: bool,
+    units: &ExportUnits,
+) -> Result<Vec<u8>, ExportError> {
+    let start = std::time::Instant::now();
+    let flight_ids = db.evaluate_flight_filter(filter)?;
+    let length = units.length.label();
+    let speed = units.speed.label();
+
+    let mut workbook = Workbook::new();
+    let summary = workbook.add_sheet("Flights");
+    summary.push_header(&[
+        "Name".to_string(),
+        "Aircraft".to_string(),
+        "Aircraft SN".to_string(),
+        "Battery SN".to_string(),
+        "Takeoff (UTC)".to_string(),
+        "Landing (UTC)".to_string(),
+        "Duration".to_string(),
+        format!("Distance ({})", length),
+        format!("Max Altitude ({})", length),
+        format!("Max Distance from Home ({})", length),
+        format!("Max Speed ({})", speed),
+        "Takeoff Lat".to_string(),
+        "Takeoff Lon".to_string(),
+        "Location".to_string(),
+        "Tags".to_string(),
+        "Notes".to_string(),
+    ]);
+
+    let mut telemetry_sheets = 0usize;
+    for flight_id in &flight_ids {
+        let flight = db.get_flight_by_id(*flight_id)?;
+        let records = db.get_flight_telemetry(*flight_id, None, flight.point_count.map(i64::from))?;
+        let takeoff = flight_start(&flight);
+        let landing = takeoff
+            .zip(flight.duration_secs)
+            .map(|(t, d)| t + chrono::Duration::milliseconds((d * 1000.0) as i64));
+        let location = [&flight.location_city, &flight.location_region, &flight.location_country]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let tags: Vec<&str> = flight.tags.iter().map(|t| t.tag.as_str()).collect();
+        let aircraft = flight
+            .drone_nickname
+            .as_deref()
+            .or(flight.aircraft_name.as_deref())
+            .or(flight.drone_model.as_deref());
+        let row = vec![
+            Cell::Text(flight.display_name.clone()),
+            Cell::from(aircraft),
+            Cell::from(flight.drone_serial.as_deref()),
+            Cell::from(flight.battery_serial.as_deref()),
+            takeoff.map_or(Cell::Empty, Cell::DateTime),
+            landing.map_or(Cell::Empty, Cell::DateTime),
+            flight.duration_secs.map_or(Cell::Empty, Cell::Duration),
+            Cell::from(flight.total_distance.map(|d| round_to(units.length.convert(d), 1))),
+            Cell::from(flight.max_altitude.map(|a| round_to(units.length.convert(a), 1))),
+            Cell::from(max_distance_from_home(&flight, &records).map(|d| round_to(units.length.convert(d), 1))),
+            Cell::from(flight.max_speed.map(|s| round_to(units.speed.convert(s), 2))),
+            Cell::from(flight.home_lat),
+            Cell::from(flight.home_lon),
+            Cell::from(Some(location.as_str())),
+            Cell::from(Some(tags.join(", ").as_str())),
+            Cell::from(flight.notes.as_deref()),
+        ];
+        if let Some(summary) = workbook.sheet_mut(0) {
+            summary.push_row(row);
+        }
+        if telemetry {
+            let columns = db.get_flight_telemetry_series(*flight_id)?;
+            write_telemetry_sheet(workbook.add_sheet(&flight.display_name), &flight, &columns, units);
+            telemetry_sheets += 1;
+        }
+    }
+
+    let data = workbook.finish()?;
+    log::info!(
+        "Excel export of {} flights ({} telemetry sheets) in {:.1}s",
+        flight_ids.len(),
+        telemetry_sheets,
+        start.elapsed().as_secs_f64()
+    );
+    Ok(data)
+}
+
+/// Every stored telemetry column of a flight as rows of a sheet, like the
+/// CSV export
+fn write_telemetry_sheet(sheet: &mut Sheet, flight: &Flight, columns: &[(String, Series)], units: &ExportUnits) {
+    let start = flight_start(flight);
+    let timestamps: &[Option<i64>] = columns
+        .iter()
+        .find_map(|(name, series)| match series {
+            Series::Int(v) if name == "timestamp_ms" => Some(v.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[]);
+    let first_ts = timestamps.first().copied().flatten().unwrap_or(0);
+
+    let mut header = vec!["time_s".to_string()];
+    if start.is_some() {
+        header.push("utc_time".to_string());
+    }
+    for (name, _) in columns {
+        match units.for_column(name) {
+            Some((suffix, ..)) => header.push(format!("{}_{}", name, suffix)),
+            None => header.push(name.clone()),
+        }
+    }
+    sheet.push_header(&header);
+
+    let row_count = columns.first().map(|(_, series)| series.len()).unwrap_or(0);
+    for i in 0..row_count {
+        let ts = timestamps.get(i).copied().flatten().unwrap_or(first_ts);
+        let mut row = vec![Cell::Number((ts - first_ts) as f64 / 1000.0)];
+        if let Some(start) = start {
+            row.push(Cell::DateTime(point_time(start, first_ts, ts)));
+        }
+        for (name, series) in columns {
+            row.push(match series {
+                Series::Int(v) => Cell::from(v[i].map(|value| value as f64)),
+                Series::Float(v) => match units.for_column(name) {
+                    Some((_, scale, offset)) => Cell::from(v[i].map(|value| round_to(value * scale + offset, 6))),
+                    None => Cell::from(v[i]),
+                },
+                Series::Bool(v) => Cell::from(v[i].map(|value| if value { 1.0 } else { 0.0 })),
+                Series::Text(v) => Cell::from(v[i].as_deref()),
+            });
+        }
+        sheet.push_row(row);
+    }
+}
+
+/// One flight as a file in `format`; `columns` and `units` apply to CSV
+pub fn flight_export(
+    db: &Database,
+    flight_id: i64,
+    format: ExportFormat,
+    columns: &[String],
+    units: &ExportUnits,
+) -> Result<String, ExportError> {
+    match format {
+        ExportFormat::Gpx => flight_gpx(db, flight_id),
+        ExportFormat::Kml => flight_kml(db, flight_id, false),
+        ExportFormat::Csv => flight_csv(db, flight_id, columns, units),
+        ExportFormat::Json => flight_json(db, flight_id),
+    }
+}
+
+/// Export every flight matching `filter` in `format` to `dest_dir`, one file
+/// per flight plus `summary.csv`. CSV files hold the telemetry `columns`, or
+/// all of them when empty. A flight that can't be exported (e.g. GPX
+/// of a flight without GPS) is listed as failed in the summary and doesn't
+/// stop the others. `on_progress` fires after each flight.
+pub fn export_batch(
+    db: &Database,
+    filter: &FlightFilter,
+    format: ExportFormat,
+    columns: &[String],
+    units: &ExportUnits,
+    dest_dir: &Path,
+    on_progress: impl FnMut(&BatchExportProgress),
+) -> Result<BatchExportResult, ExportError> {
+    std::fs::create_dir_all(dest_dir)?;
+    let mut result = export_batch_with(db, filter, format, columns, units, on_progress, |name, data| {
+        std::fs::write(dest_dir.join(name), data)
+    })?;
+    result.output_path = Some(dest_dir.to_string_lossy().to_string());
+    Ok(result)
+}
+
+/// Like `export_batch`, but the files are returned as one ZIP archive
+pub fn export_batch_zip(
+    db: &Database,
+    filter: &FlightFilter,
+    format: ExportFormat,
+    columns: &[String],
+    units: &ExportUnits,
+) -> Result<(BatchExportResult, Vec<u8>), ExportError> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let result = export_batch_with(db, filter, format, columns, units, |_| {}, |name, data| {
+        zip.start_file(name, options).map_err(std::io::Error::other)?;
+        std::io::Write::write_all(&mut zip, data)
+    })?;
+    Ok((result, zip.finish()?.into_inner()))
+}
+
+fn export_batch_with(
+    db: &Database,
+    filter: &FlightFilter,
+    format: ExportFormat,
+    columns: &[String],
+    units: &ExportUnits,
+    mut on_progress: impl FnMut(&BatchExportProgress),
+    mut write_file: impl FnMut(&str, &[u8]) -> std::io::Result<()>,
+) -> Result<BatchExportResult, ExportError> {
+    let start = std::time::Instant::now();
+    let flight_ids = db.evaluate_flight_filter(filter)?;
+    let total = flight_ids.len();
+    log::info!("Batch exporting {} flights as {}", total, format.extension());
+
+    let mut used_names = HashSet::new();
+    let mut summary = String::from(
+        "flight_id,file,status,display_name,start_time,drone_model,drone_serial,battery_serial,\
+         duration_secs,total_distance_m,max_altitude_m,max_speed_ms,tags,notes\n",
+    );
+    let mut exported = 0usize;
+    let mut failures = 0usize;
+    for (index, flight_id) in flight_ids.iter().copied().enumerate() {
+        let flight = db.get_flight_by_id(flight_id)?;
+        let content = flight_export(db, flight_id, format, columns, units);
+
+        // Flights often share a display name, e.g. the log file's date
+        let mut name = file_name(&flight, format.extension());
+        if !used_names.insert(name.to_lowercase()) {
+            name = name.replacen(
+                &format!(".{}", format.extension()),
+                &format!("_{}.{}", flight_id, format.extension()),
+                1,
+            );
+            used_names.insert(name.to_lowercase());
+        }
+
+        let status = match content {
+            Ok(content) => {
+                write_file(&name, content.as_bytes())?;
+                exported += 1;
+                "exported".to_string()
+            }
+            Err(e) => {
+                log::warn!("Batch export of flight {} failed: {}", flight_id, e);
+                failures += 1;
+                name.clear();
+                format!("failed: {}", e)
+            }
+        };
+
+        let number = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or_default();
+        let text = |v: Option<&str>| csv_escape(v.unwrap_or_default());
+        let tags: Vec<&str> = flight.tags.iter().map(|t| t.tag.as_str()).collect();
+        let _ = writeln!(
+            summary,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            flight.id,
+            csv_escape(&name),
+            csv_escape(&status),
+            csv_escape(&flight.display_name),
+            text(flight.start_time.as_deref()),
+            text(flight.drone_model.as_deref()),
+            text(flight.drone_serial.as_deref()),
+            text(flight.battery_serial.as_deref()),
+            number(flight.duration_secs),
+            number(flight.total_distance),
+            number(flight.max_altitude),
+            number(flight.max_speed),
+            csv_escape(&tags.join("; ")),
+            text(flight.notes.as_deref()),
+        );
+
+        on_progress(&BatchExportProgress {
+            current_flight: flight.display_name.clone(),
+            processed: index + 1,
+            total,
+            percent: (index + 1) as f64 / total as f64 * 100.0,
+            failures,
+        });
+    }
+    write_file("summary.csv", summary.as_bytes())?;
+
+    log::info!(
+        "Batch export finished in {:.1}s: {} of {} flights exported",
+        start.elapsed().as_secs_f64(),
+        exported,
+        total
+    );
+    Ok(BatchExportResult {
+        total,
+        exported,
+        failed: failures,
+        output_path: None,
+    })
+}
+
+/// A flight and its full-resolution telemetry, if it has any GPS positions
+fn load_track(db: &Database, flight_id: i64) -> Result<(Flight, Vec<TelemetryRecord>), ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(flight_id, Some(usize::MAX), flight.point_count.map(i64::from))?;
+    if !records.iter().any(|r| position(r).is_some()) {
+        return Err(ExportError::NoPositions(flight_id));
+    }
+    Ok((flight, records))
+}
+
+fn write_gpx(flight: &Flight, records: &[TelemetryRecord]) -> String {
+    let start = flight_start(flight);
+    let first_ts = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+    let name = xml_escape(&flight.display_name);
+
+    let mut gpx = String::new();
+    gpx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        gpx,
+        "<gpx version=\"1.1\" creator=\"{}\" xmlns=\"http://www.topografix.com/GPX/1/1\" \
+         xmlns:gpxtpx=\"http://www.garmin.com/xmlschemas/TrackPointExtension/v2\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://www.topografix.com/GPX/1/1 http://www.topografix.com/GPX/1/1/gpx.xsd \
+         http://www.garmin.com/xmlschemas/TrackPointExtension/v2 http://www.garmin.com/xmlschemas/TrackPointExtensionv2.xsd\">",
+        CREATOR
+    );
+    gpx.push_str("  <metadata>\n");
+    let _ = writeln!(gpx, "    <name>{}</name>", name);
+    if let Some(start) = start {
+        let _ = writeln!(gpx, "    <time>{}</time>", gpx_time(start));
+    }
+    gpx.push_str("  </metadata>\n");
+    gpx.push_str("  <trk>\n");
+    let _ = writeln!(gpx, "    <name>{}</name>", name);
+    if let Some(notes) = flight.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        let _ = writeln!(gpx, "    <desc>{}</desc>", xml_escape(notes));
+    }
+    let _ = writeln!(gpx, "    <type>{}</type>", xml_escape(flight.drone_model.as_deref().unwrap_or("drone")));
+    gpx.push_str("    <trkseg>\n");
+
+    let mut previous: Option<(f64, f64)> = None;
+    let mut course: Option<f64> = None;
+    for record in records {
+        let Some((lat, lon)) = position(record) else { continue };
+        if let Some((prev_lat, prev_lon)) = previous {
+            if haversine_distance(prev_lat, prev_lon, lat, lon) >= MIN_COURSE_DISTANCE_M {
+                course = Some(bearing_deg(prev_lat, prev_lon, lat, lon));
+                previous = Some((lat, lon));
+            }
+        } else {
+            previous = Some((lat, lon));
+        }
+
+        let _ = writeln!(gpx, "      <trkpt lat=\"{:.7}\" lon=\"{:.7}\">", lat, lon);
+        if let Some(ele) = record.altitude.or(record.height) {
+            let _ = writeln!(gpx, "        <ele>{:.2}</ele>", ele);
+        }
+        if let Some(start) = start {
+            let _ = writeln!(gpx, "        <time>{}</time>", gpx_time(point_time(start, first_ts, record.timestamp_ms)));
+        }
+        if let Some(sat) = record.satellites {
+            let _ = writeln!(gpx, "        <sat>{}</sat>", sat.max(0));
+        }
+        if record.speed.is_some() || course.is_some() {
+            gpx.push_str("        <extensions>\n");
+            gpx.push_str("          <gpxtpx:TrackPointExtension>\n");
+            if let Some(speed) = record.speed {
+                let _ = writeln!(gpx, "            <gpxtpx:speed>{:.2}</gpxtpx:speed>", speed.abs());
+            }
+            if let Some(course) = course {
+                let _ = writeln!(gpx, "            <gpxtpx:course>{:.1}</gpxtpx:course>", course);
+            }
+            gpx.push_str("          </gpxtpx:TrackPointExtension>\n");
+            gpx.push_str("        </extensions>\n");
+        }
+        gpx.push_str("      </trkpt>\n");
+    }
+
+    gpx.push_str("    </trkseg>\n");
+    gpx.push_str("  </trk>\n");
+    gpx.push_str("</gpx>\n");
+    gpx
+}
+
+fn write_kml(flight: &Flight, records: &[TelemetryRecord], events: &[FlightEvent], tour: bool) -> String {
+    let start = flight_start(flight);
+    let first_ts = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+    let name = xml_escape(&flight.display_name);
+    // (timestamp, lat, lon, height above takeoff)
+    let points: Vec<(i64, f64, f64, f64)> = records
+        .iter()
+        .filter_map(|r| position(r).map(|(lat, lon)| (r.timestamp_ms, lat, lon, r.height.unwrap_or(0.0).max(0.0))))
+        .collect();
+
+    let mut kml = String::new();
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str(
+        "<kml xmlns=\"http://www.opengis.net/kml/2.2\" xmlns:gx=\"http://www.google.com/kml/ext/2.2\">\n",
+    );
+    kml.push_str("  <Document>\n");
+    let _ = writeln!(kml, "    <name>{}</name>", name);
+    kml.push_str("    <open>1</open>\n");
+    let _ = writeln!(kml, "    <description>{}</description>", xml_escape(&kml_description(flight)));
+    kml.push_str(concat!(
+        "    <Style id=\"track\">\n",
+        "      <LineStyle><color>ff00a5ff</color><width>3</width></LineStyle>\n",
+        "      <PolyStyle><color>4000a5ff</color></PolyStyle>\n",
+        "    </Style>\n",
+        "    <Style id=\"animated\">\n",
+        "      <IconStyle><Icon><href>http://maps.google.com/mapfiles/kml/shapes/airports.png</href></Icon></IconStyle>\n",
+        "      <LineStyle><color>ffffffff</color><width>2</width></LineStyle>\n",
+        "    </Style>\n",
+        "    <Style id=\"takeoff\">\n",
+        "      <IconStyle><Icon><href>http://maps.google.com/mapfiles/kml/paddle/grn-circle.png</href></Icon></IconStyle>\n",
+        "    </Style>\n",
+        "    <Style id=\"landing\">\n",
+        "      <IconStyle><Icon><href>http://maps.google.com/mapfiles/kml/paddle/red-circle.png</href></Icon></IconStyle>\n",
+        "    </Style>\n",
+        "    <Style id=\"photo\">\n",
+        "      <IconStyle><scale>0.8</scale><Icon><href>http://maps.google.com/mapfiles/kml/shapes/camera.png</href></Icon></IconStyle>\n",
+        "    </Style>\n",
+    ));
+
+    // Static 3D path, extruded down to the ground
+    kml.push_str("    <Placemark>\n");
+    kml.push_str("      <name>Flight path</name>\n");
+    kml.push_str("      <styleUrl>#track</styleUrl>\n");
+    kml.push_str("      <LineString>\n");
+    kml.push_str("        <extrude>1</extrude>\n");
+    kml.push_str("        <tessellate>1</tessellate>\n");
+    kml.push_str("        <altitudeMode>relativeToGround</altitudeMode>\n");
+    kml.push_str("        <coordinates>\n");
+    for (_, lat, lon, height) in &points {
+        let _ = writeln!(kml, "          {:.7},{:.7},{:.1}", lon, lat, height);
+    }
+    kml.push_str("        </coordinates>\n");
+    kml.push_str("      </LineString>\n");
+    kml.push_str("    </Placemark>\n");
+
+    // Timestamped track for the time slider; needs the absolute start time
+    if let Some(start) = start {
+        kml.push_str("    <Placemark>\n");
+        kml.push_str("      <name>Flight replay</name>\n");
+        kml.push_str("      <styleUrl>#animated</styleUrl>\n");
+        kml.push_str("      <gx:Track>\n");
+        kml.push_str("        <altitudeMode>relativeToGround</altitudeMode>\n");
+        for (ts, ..) in &points {
+            let _ = writeln!(kml, "        <when>{}</when>", gpx_time(point_time(start, first_ts, *ts)));
+        }
+        for (_, lat, lon, height) in &points {
+            let _ = writeln!(kml, "        <gx:coord>{:.7} {:.7} {:.1}</gx:coord>", lon, lat, height);
+        }
+        kml.push_str("      </gx:Track>\n");
+        kml.push_str("    </Placemark>\n");
+    }
+
+    kml.push_str("    <Folder>\n");
+    kml.push_str("      <name>Events</name>\n");
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        write_kml_point(&mut kml, "Takeoff", "takeoff", first.1, first.2, 0.0, start.map(|s| point_time(s, first_ts, first.0)));
+        write_kml_point(&mut kml, "Landing", "landing", last.1, last.2, 0.0, start.map(|s| point_time(s, first_ts, last.0)));
+    }
+    let photos = events.iter().filter(|e| e.event_type == "photo");
+    for (index, event) in photos.enumerate() {
+        let (Some(lat), Some(lon)) = (event.lat, event.lon) else { continue };
+        // Events carry altitude above takeoff like the telemetry height
+        let height = event.altitude.unwrap_or(0.0).max(0.0);
+        let label = format!("Photo {}", index + 1);
+        write_kml_point(&mut kml, &label, "photo", lat, lon, height, start.map(|s| point_time(s, first_ts, event.timestamp_ms)));
+    }
+    kml.push_str("    </Folder>\n");
+
+    if tour && points.len() > 1 {
+        write_kml_tour(&mut kml, &points);
+    }
+
+    kml.push_str("  </Document>\n");
+    kml.push_str("</kml>\n");
+    kml
+}
+
+fn write_kml_point(
+    kml: &mut String,
+    name: &str,
+    style: &str,
+    lat: f64,
+    lon: f64,
+    height: f64,
+    time: Option<DateTime<Utc>>,
+) {
+    kml.push_str("      <Placemark>\n");
+    let _ = writeln!(kml, "        <name>{}</name>", xml_escape(name));
+    if let Some(time) = time {
+        let _ = writeln!(kml, "        <TimeStamp><when>{}</when></TimeStamp>", gpx_time(time));
+    }
+    let _ = writeln!(kml, "        <styleUrl>#{}</styleUrl>", style);
+    kml.push_str("        <Point>\n");
+    kml.push_str("          <altitudeMode>relativeToGround</altitudeMode>\n");
+    let _ = writeln!(kml, "          <coordinates>{:.7},{:.7},{:.1}</coordinates>", lon, lat, height);
+    kml.push_str("        </Point>\n");
+    kml.push_str("      </Placemark>\n");
+}
+
+/// Camera following the path from behind and above, in real time
+fn write_kml_tour(kml: &mut String, points: &[(i64, f64, f64, f64)]) {
+    let step = points.len().div_ceil(MAX_TOUR_STEPS).max(1);
+    let mut samples: Vec<&(i64, f64, f64, f64)> = points.iter().step_by(step).collect();
+    if let Some(last) = points.last() {
+        if samples.last().is_some_and(|s| s.0 != last.0) {
+            samples.push(last);
+        }
+    }
+
+    kml.push_str("    <gx:Tour>\n");
+    kml.push_str("      <name>Flythrough</name>\n");
+    kml.push_str("      <gx:Playlist>\n");
+    let mut heading = 0.0;
+    let mut previous: Option<&(i64, f64, f64, f64)> = None;
+    for (index, sample) in samples.iter().enumerate() {
+        let (ts, lat, lon, height) = **sample;
+        if let Some(next) = samples.get(index + 1) {
+            if haversine_distance(lat, lon, next.1, next.2) >= MIN_COURSE_DISTANCE_M {
+                heading = bearing_deg(lat, lon, next.1, next.2);
+            }
+        }
+        let duration = previous.map(|p| ((ts - p.0) as f64 / 1000.0).max(0.1)).unwrap_or(2.0);
+        kml.push_str("        <gx:FlyTo>\n");
+        let _ = writeln!(kml, "          <gx:duration>{:.1}</gx:duration>", duration);
+        let _ = writeln!(kml, "          <gx:flyToMode>{}</gx:flyToMode>", if previous.is_some() { "smooth" } else { "bounce" });
+        kml.push_str("          <LookAt>\n");
+        let _ = writeln!(kml, "            <longitude>{:.7}</longitude>", lon);
+        let _ = writeln!(kml, "            <latitude>{:.7}</latitude>", lat);
+        let _ = writeln!(kml, "            <altitude>{:.1}</altitude>", height);
+        let _ = writeln!(kml, "            <heading>{:.1}</heading>", heading);
+        kml.push_str("            <tilt>65</tilt>\n");
+        kml.push_str("            <range>150</range>\n");
+        kml.push_str("            <altitudeMode>relativeToGround</altitudeMode>\n");
+        kml.push_str("          </LookAt>\n");
+        kml.push_str("        </gx:FlyTo>\n");
+        previous = Some(sample);
+    }
+    kml.push_str("      </gx:Playlist>\n");
+    kml.push_str("    </gx:Tour>\n");
+}
+
+/// Short summary shown in the Google Earth places panel
+fn kml_description(flight: &Flight) -> String {
+    let mut lines = Vec::new();
+    if let Some(model) = flight.drone_model.as_deref() {
+        lines.push(format!("Aircraft: {}", model));
+    }
+    if let Some(start) = flight.start_time.as_deref() {
+        lines.push(format!("Start: {}", start));
+    }
+    if let Some(duration) = flight.duration_secs {
+        lines.push(format!("Duration: {}m {:02}s", (duration / 60.0).floor(), (duration % 60.0).round() as i64));
+    }
+    if let Some(distance) = flight.total_distance {
+        lines.push(format!("Distance: {:.0} m", distance));
+    }
+    if let Some(altitude) = flight.max_altitude {
+        lines.push(format!("Max altitude: {:.0} m", altitude));
+    }
+    if let Some(notes) = flight.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        lines.push(notes.to_string());
+    }
+    lines.join("\n")
+}
+
+/// Latitude/longitude of a record, skipping the 0,0 fixes logged before GPS lock
+pub(crate) fn position(record: &TelemetryRecord) -> Option<(f64, f64)> {
+    match (record.latitude, record.longitude) {
+        (Some(lat), Some(lon)) if !(lat == 0.0 && lon == 0.0) => Some((lat, lon)),
+        _ => None,
+    }
+}
+
+/// Farthest the aircraft got from the home point (the first position when
+/// the log has none)
+pub(crate) fn max_distance_from_home(flight: &Flight, records: &[TelemetryRecord]) -> Option<f64> {
+    let mut positions = records.iter().filter_map(position);
+    let home = match (flight.home_lat, flight.home_lon) {
+        (Some(lat), Some(lon)) => (lat, lon),
+        _ => positions.next()?,
+    };
+    positions.map(|(lat, lon)| haversine_distance(home.0, home.1, lat, lon)).reduce(f64::max)
+}
+
+/// Flight start time in UTC. The database returns it as text, with or
+/// without a UTC offset depending on the column type.
+pub(crate) fn flight_start(flight: &Flight) -> Option<DateTime<Utc>> {
+    let text = flight.start_time.as_deref()?.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|ndt| Utc.from_utc_datetime(&ndt))
+}
+
+/// Absolute time of a telemetry timestamp, which counts from the log start
+fn point_time(start: DateTime<Utc>, first_ts: i64, timestamp_ms: i64) -> DateTime<Utc> {
+    start + chrono::Duration::milliseconds(timestamp_ms - first_ts)
+}
+
+fn gpx_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+pub(crate) fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Initial bearing from the first point to the second, 0-360° clockwise from north
+fn bearing_deg(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dlambda = (lon2 - lon1).to_radians();
+    let y = dlambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * dlambda.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
@@ -0,0 +1,139 @@
+//! Time spent in each flight mode, per flight and across the library.
+//!
+//! Every log format names its modes differently (DJI's "GPSAtti" and
+//! "GPSSport", Litchi's "P-GPS", Airdata's "Go Home"), so besides the raw
+//! mode each one is put into a category, which is what the totals compare.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{FlightModeCategory, FlightModeStats, FlightModeTime};
+
+/// Mode categories, in display order
+pub const CATEGORIES: [&str; 9] = [
+    "GPS",
+    "Sport",
+    "Cine",
+    "ATTI",
+    "RTH",
+    "Takeoff/Landing",
+    "Intelligent",
+    "Manual",
+    "Other",
+];
+
+/// Category of a logged mode name
+pub fn category(mode: &str) -> &'static str {
+    let key: String = mode
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| key.contains(w));
+
+    if has(&["sport"]) {
+        "Sport"
+    } else if has(&["cine", "tripod"]) {
+        "Cine"
+    } else if has(&["gohome", "rth", "returnhome", "returntohome"]) {
+        "RTH"
+    } else if has(&["takeoff", "landing"]) {
+        "Takeoff/Landing"
+    } else if has(&[
+        "activetrack", "tapfly", "hotpoint", "followme", "waypoint", "navigo", "clickgo", "pano", "draw",
+        "spotlight", "poi", "mission", "quickshot", "mastershot", "terrainfollow",
+    ]) {
+        "Intelligent"
+    } else if has(&["gps", "normal", "position"]) {
+        "GPS"
+    } else if has(&["atti"]) {
+        "ATTI"
+    } else if has(&["manual", "fpv", "rate", "acro"]) {
+        "Manual"
+    } else {
+        "Other"
+    }
+}
+
+/// Time per mode of one flight, longest first
+pub fn flight_modes(db: &Database, flight_id: i64) -> Result<Vec<FlightModeTime>, DatabaseError> {
+    Ok(summarize(db.get_flight_mode_times(Some(flight_id))?).modes)
+}
+
+/// Time per mode and per category across the flights counted in statistics
+pub fn flight_mode_stats(db: &Database) -> Result<FlightModeStats, DatabaseError> {
+    Ok(summarize(db.get_flight_mode_times(None)?))
+}
+
+fn summarize(times: Vec<(i64, String, f64)>) -> FlightModeStats {
+    let mut modes: BTreeMap<String, (f64, HashSet<i64>)> = BTreeMap::new();
+    let mut categories: BTreeMap<&'static str, (f64, HashSet<i64>)> = BTreeMap::new();
+    for (flight_id, mode, seconds) in times {
+        let entry = categories.entry(category(&mode)).or_default();
+        entry.0 += seconds;
+        entry.1.insert(flight_id);
+        let entry = modes.entry(mode).or_default();
+        entry.0 += seconds;
+        entry.1.insert(flight_id);
+    }
+
+    let mut modes: Vec<FlightModeTime> = modes
+        .into_iter()
+        .map(|(mode, (duration_secs, flights))| FlightModeTime {
+            category: category(&mode).to_string(),
+            mode,
+            duration_secs,
+            flight_count: flights.len() as i64,
+        })
+        .collect();
+    modes.sort_by(|a, b| b.duration_secs.total_cmp(&a.duration_secs));
+
+    let mut categories: Vec<FlightModeCategory> = categories
+        .into_iter()
+        .map(|(category, (duration_secs, flights))| FlightModeCategory {
+            category: category.to_string(),
+            duration_secs,
+            flight_count: flights.len() as i64,
+        })
+        .collect();
+    categories.sort_by(|a, b| b.duration_secs.total_cmp(&a.duration_secs));
+
+    FlightModeStats {
+        total_secs: modes.iter().map(|m| m.duration_secs).sum(),
+        categories,
+        modes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_from_different_apps_share_categories() {
+        assert_eq!(category("GPSAtti"), "GPS");
+        assert_eq!(category("P-GPS"), "GPS");
+        assert_eq!(category("GPSSport"), "Sport");
+        assert_eq!(category("Tripod"), "Cine");
+        assert_eq!(category("Go Home"), "RTH");
+        assert_eq!(category("AutoLanding"), "Takeoff/Landing");
+        assert_eq!(category("GPSHotPoint"), "Intelligent");
+        assert_eq!(category("Atti"), "ATTI");
+        assert_eq!(category("Manual (Rate)"), "Manual");
+        assert_eq!(category("6"), "Other");
+        assert!(CATEGORIES.contains(&category("ActiveTrack")));
+    }
+
+    #[test]
+    fn totals_count_each_flight_once_per_mode() {
+        let stats = summarize(vec![
+            (1, "GPSAtti".to_string(), 300.0),
+            (1, "GPSSport".to_string(), 60.0),
+            (2, "P-GPS".to_string(), 120.0),
+        ]);
+        assert_eq!(stats.total_secs, 480.0);
+        assert_eq!(stats.categories[0].category, "GPS");
+        assert_eq!(stats.categories[0].flight_count, 2);
+        assert_eq!(stats.modes[0].mode, "GPSAtti");
+    }
+}
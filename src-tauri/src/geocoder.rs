@@ -0,0 +1,209 @@
+//! Reverse geocoding of flight home points into a city/region/country.
+//!
+//! Two providers are available, picked with `geocode_provider` in config.json:
+//! - `offline` (default): the GeoNames dataset bundled with `reverse_geocoder`
+//! - `nominatim`: the OpenStreetMap Nominatim API, more precise but online
+//!
+//! Results are cached per provider on a ~1 km grid in the `geocode_cache`
+//! table, so flights from the same site never query twice.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::library::read_config;
+use crate::models::FlightLocation;
+use crate::parser::LogParser;
+
+/// Nominatim reverse geocoding endpoint
+const NOMINATIM_URL: &str = "https://nominatim.openstreetmap.org/reverse";
+
+/// Nominatim's usage policy allows one request per second
+const NOMINATIM_REQUEST_INTERVAL: Duration = Duration::from_millis(1100);
+
+/// Decimal places coordinates are rounded to for the cache (~1.1 km)
+const CACHE_GRID_DECIMALS: i32 = 2;
+
+/// Set while a backfill is running so imports don't start a second one
+static BACKFILL_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// The offline dataset takes a moment to load, so it is built once
+static OFFLINE_GEOCODER: OnceLock<reverse_geocoder::ReverseGeocoder> = OnceLock::new();
+
+#[derive(Error, Debug)]
+pub enum GeocodeError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("A location backfill is already running")]
+    AlreadyRunning,
+}
+
+/// Where locations are looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeocodeProvider {
+    Offline,
+    Nominatim,
+}
+
+impl GeocodeProvider {
+    /// Read the provider from config.json, defaulting to offline
+    pub fn from_config(data_dir: &Path) -> Self {
+        let config = read_config(data_dir);
+        config
+            .get("geocode_provider")
+            .and_then(|v| v.as_str())
+            .and_then(Self::parse)
+            .unwrap_or(Self::Offline)
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "offline" => Some(Self::Offline),
+            "nominatim" => Some(Self::Nominatim),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Offline => "offline",
+            Self::Nominatim => "nominatim",
+        }
+    }
+
+    /// Look up the location of a coordinate
+    pub async fn reverse(&self, lat: f64, lon: f64) -> Result<FlightLocation, GeocodeError> {
+        match self {
+            Self::Offline => Ok(offline_reverse(lat, lon)),
+            Self::Nominatim => nominatim_reverse(lat, lon).await,
+        }
+    }
+}
+
+fn offline_reverse(lat: f64, lon: f64) -> FlightLocation {
+    let geocoder = OFFLINE_GEOCODER.get_or_init(reverse_geocoder::ReverseGeocoder::new);
+    let record = geocoder.search((lat, lon)).record;
+    let non_empty = |s: &str| (!s.trim().is_empty()).then(|| s.trim().to_string());
+
+    FlightLocation {
+        city: non_empty(&record.name),
+        region: non_empty(&record.admin1),
+        country: LogParser::country_from_cc(&record.cc)
+            .map(|c| c.to_string())
+            .or_else(|| non_empty(&record.cc)),
+    }
+}
+
+async fn nominatim_reverse(lat: f64, lon: f64) -> Result<FlightLocation, GeocodeError> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("drone-logbook/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    let body: serde_json::Value = client
+        .get(NOMINATIM_URL)
+        .query(&[
+            ("format", "jsonv2".to_string()),
+            ("lat", lat.to_string()),
+            ("lon", lon.to_string()),
+            ("zoom", "10".to_string()),
+            ("accept-language", "en".to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    // Places over water come back as {"error": "Unable to geocode"}
+    let address = body.get("address");
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| address.and_then(|a| a.get(*k)).and_then(|v| v.as_str()))
+            .map(|s| s.to_string())
+    };
+
+    Ok(FlightLocation {
+        city: field(&["city", "town", "village", "municipality", "hamlet"]),
+        region: field(&["state", "region", "county"]),
+        country: field(&["country"]),
+    })
+}
+
+/// Round a coordinate to the cache grid
+fn grid(value: f64) -> f64 {
+    let factor = 10f64.powi(CACHE_GRID_DECIMALS);
+    (value * factor).round() / factor
+}
+
+/// Fill in the location of flights that have a home point but no location
+/// yet (every flight with a home point when `force` is set). Returns the
+/// number of flights that got a location.
+pub async fn locate_flights(db: &Database, force: bool) -> Result<usize, GeocodeError> {
+    if BACKFILL_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(GeocodeError::AlreadyRunning);
+    }
+    let result = locate_flights_inner(db, force).await;
+    BACKFILL_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn locate_flights_inner(db: &Database, force: bool) -> Result<usize, GeocodeError> {
+    let provider = GeocodeProvider::from_config(&db.data_dir);
+    let pending = db.get_flights_to_locate(force)?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    log::info!("Locating {} flights with the {} geocoder", pending.len(), provider.as_str());
+
+    let mut located = 0usize;
+    let mut queried = 0usize;
+    for (flight_id, lat, lon) in pending {
+        let (lat_key, lon_key) = (grid(lat), grid(lon));
+        let location = match db.get_cached_location(provider.as_str(), lat_key, lon_key)? {
+            Some(cached) => cached,
+            None => {
+                if provider == GeocodeProvider::Nominatim && queried > 0 {
+                    tokio::time::sleep(NOMINATIM_REQUEST_INTERVAL).await;
+                }
+                queried += 1;
+                match provider.reverse(lat_key, lon_key).await {
+                    Ok(location) => {
+                        db.cache_location(provider.as_str(), lat_key, lon_key, &location)?;
+                        location
+                    }
+                    Err(e) => {
+                        // Leave the flight for the next run rather than caching a failure
+                        log::warn!("Reverse geocoding of flight {} failed: {}", flight_id, e);
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if location.city.is_some() || location.region.is_some() || location.country.is_some() {
+            db.set_flight_location(flight_id, &location)?;
+            located += 1;
+        }
+    }
+
+    log::info!("Located {} flights ({} geocoder lookups)", located, queried);
+    Ok(located)
+}
+
+/// Locate newly imported flights without holding up the import
+pub fn locate_in_background(db: Arc<Database>) {
+    tokio::spawn(async move {
+        match locate_flights(&db, false).await {
+            Ok(_) | Err(GeocodeError::AlreadyRunning) => {}
+            Err(e) => log::warn!("Background location lookup failed: {}", e),
+        }
+    });
+}
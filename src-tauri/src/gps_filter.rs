@@ -0,0 +1,136 @@
+//! Plausibility checks against GPS and speed glitches.
+//!
+//! Receivers now and then report a single fix hundreds of meters off the
+//! path, or a single absurd speed. Stored telemetry keeps them as logged;
+//! flight statistics, distances and the map track skip them.
+//!
+//! - A fix is a glitch when getting to it from the previous fix and on to
+//!   the next one both take more than `MAX_SPEED_MS`, while going from the
+//!   previous fix straight to the next doesn't. The first and last fixes
+//!   have one neighbour, so they are glitches when the step to it is
+//!   implausible and the step after it isn't.
+//! - A speed is a glitch above `MAX_SPEED_MS`, or when it jumps away from
+//!   both neighbouring samples, up or down, faster than `MAX_ACCEL_MS2`.
+//!
+//! Each check compares a point with its neighbours as logged, not as
+//! filtered, so the result doesn't depend on the order points are checked in.
+
+/// Fastest plausible ground speed, well above what multirotors reach
+pub const MAX_SPEED_MS: f64 = 80.0;
+
+/// Fastest plausible change of speed, about 2 g
+const MAX_ACCEL_MS2: f64 = 20.0;
+
+/// Shortest time step assumed between samples, for logs that repeat timestamps
+const MIN_STEP_SECS: f64 = 0.1;
+
+/// Latitude and longitude of a usable GPS fix. Logs hold 0,0 until the
+/// first fix.
+pub fn valid_position(latitude: Option<f64>, longitude: Option<f64>) -> Option<(f64, f64)> {
+    let (lat, lon) = (latitude?, longitude?);
+    (lat.is_finite() && lon.is_finite() && (lat.abs() > 0.0001 || lon.abs() > 0.0001)).then_some((lat, lon))
+}
+
+/// Usable fixes of `(timestamp ms, latitude, longitude)` points, None for
+/// points without a fix or with a glitched one
+pub fn filter_positions(points: impl IntoIterator<Item = (i64, Option<f64>, Option<f64>)>) -> Vec<Option<(f64, f64)>> {
+    let mut filtered = Vec::new();
+    // (index, timestamp, latitude, longitude) of the fixes
+    let mut fixes: Vec<(usize, i64, f64, f64)> = Vec::new();
+    for (i, (timestamp_ms, lat, lon)) in points.into_iter().enumerate() {
+        filtered.push(None);
+        if let Some((lat, lon)) = valid_position(lat, lon) {
+            fixes.push((i, timestamp_ms, lat, lon));
+        }
+    }
+
+    let implausible = |a: usize, b: usize| {
+        let (_, t1, lat1, lon1) = fixes[a];
+        let (_, t2, lat2, lon2) = fixes[b];
+        crate::parser::haversine_distance(lat1, lon1, lat2, lon2) / step_secs(t1, t2) > MAX_SPEED_MS
+    };
+    let n = fixes.len();
+    for (k, &(i, _, lat, lon)) in fixes.iter().enumerate() {
+        let glitch = if k > 0 && k + 1 < n {
+            implausible(k - 1, k) && implausible(k, k + 1) && !implausible(k - 1, k + 1)
+        } else if n >= 3 && k == 0 {
+            implausible(0, 1) && !implausible(1, 2)
+        } else if n >= 3 {
+            implausible(k - 1, k) && !implausible(k - 2, k - 1)
+        } else {
+            false
+        };
+        if !glitch {
+            filtered[i] = Some((lat, lon));
+        }
+    }
+    filtered
+}
+
+/// Speeds of `(timestamp ms, speed m/s)` points, None for missing and
+/// glitched ones
+pub fn filter_speeds(points: impl IntoIterator<Item = (i64, Option<f64>)>) -> Vec<Option<f64>> {
+    let mut filtered = Vec::new();
+    // (index, timestamp, speed) of the samples
+    let mut samples: Vec<(usize, i64, f64)> = Vec::new();
+    for (i, (timestamp_ms, speed)) in points.into_iter().enumerate() {
+        filtered.push(None);
+        if let Some(speed) = speed.filter(|s| s.is_finite()) {
+            samples.push((i, timestamp_ms, speed));
+        }
+    }
+
+    let rate = |a: usize, b: usize| {
+        let (_, t1, v1) = samples[a];
+        let (_, t2, v2) = samples[b];
+        (v2 - v1) / step_secs(t1, t2)
+    };
+    for k in 0..samples.len() {
+        let (i, _, speed) = samples[k];
+        let spike = k > 0 && k + 1 < samples.len() && {
+            let (rise, fall) = (rate(k - 1, k), -rate(k, k + 1));
+            (rise > MAX_ACCEL_MS2 && fall > MAX_ACCEL_MS2) || (rise < -MAX_ACCEL_MS2 && fall < -MAX_ACCEL_MS2)
+        };
+        if speed.abs() <= MAX_SPEED_MS && !spike {
+            filtered[i] = Some(speed);
+        }
+    }
+    filtered
+}
+
+fn step_secs(from_ms: i64, to_ms: i64) -> f64 {
+    ((to_ms - from_ms).abs() as f64 / 1000.0).max(MIN_STEP_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_fix_glitches_are_dropped() {
+        // 10 Hz at 1 m per step northwards
+        let mut points: Vec<(i64, Option<f64>, Option<f64>)> =
+            (0..20).map(|i| (i * 100, Some(45.0 + i as f64 * 0.000009), Some(7.0))).collect();
+        points[0] = (0, Some(45.02), Some(7.0));
+        points[7].2 = Some(7.01);
+        points[12] = (1200, Some(0.0), Some(0.0));
+        points[19].1 = Some(46.0);
+
+        let fixes = filter_positions(points.clone());
+        let dropped: Vec<usize> = (0..20).filter(|&i| fixes[i].is_none()).collect();
+        assert_eq!(dropped, vec![0, 7, 12, 19]);
+        assert_eq!(fixes[1], points[1].1.zip(points[1].2));
+
+        // Two consecutive off fixes are both kept: it could be the path
+        points[8].2 = Some(7.01);
+        assert!(filter_positions(points)[7].is_some());
+    }
+
+    #[test]
+    fn speed_spikes_are_dropped() {
+        let speeds = [8.0, 8.2, 8.4, 35.0, 8.8, 9.0, 9.1, 0.0, 9.2, 150.0, 9.4, 9.5, 9.7];
+        let filtered = filter_speeds(speeds.iter().enumerate().map(|(i, &v)| (i as i64 * 100, Some(v))));
+        let dropped: Vec<usize> = (0..speeds.len()).filter(|&i| filtered[i].is_none()).collect();
+        assert_eq!(dropped, vec![3, 7, 9]);
+    }
+}
@@ -1,9 +1,47 @@
+pub mod airdata_parser;
 pub mod api;
+pub mod autel_parser;
+pub mod auto_export;
+pub mod batch_import;
+pub mod battery_health;
+pub mod compliance;
+pub mod coverage;
+pub mod currency;
+pub mod dat_parser;
 pub mod database;
+pub mod derived;
+pub mod drone_comparison;
 pub mod dronelogbook_parser;
+pub mod energy;
+pub mod export;
+pub mod flight_modes;
+pub mod geocoder;
+pub mod gps_filter;
+pub mod library;
 pub mod litchi_parser;
+pub mod logbook;
+pub mod lttb;
+pub mod mavlink;
+pub mod media_scanner;
+pub mod milestones;
+pub mod mission_parser;
 pub mod models;
+pub mod parrot_parser;
 pub mod parser;
+pub mod pdf;
+pub mod phases;
+pub mod report;
+pub mod rth_events;
+pub mod sites;
+pub mod srt_parser;
+pub mod sun;
+pub mod telemetry_pack;
+pub mod terrain;
+pub mod timeline;
+pub mod track_smoothing;
+pub mod wind;
+pub mod xlsx;
+pub mod year_review;
 
 #[cfg(feature = "web")]
 pub mod server;
@@ -13,3 +51,8 @@ pub use models::*;
 pub use parser::LogParser;
 pub use litchi_parser::LitchiParser;
 pub use dronelogbook_parser::DroneLogbookParser;
+pub use dat_parser::DatParser;
+pub use airdata_parser::AirDataParser;
+pub use autel_parser::AutelParser;
+pub use parrot_parser::ParrotParser;
+pub use srt_parser::SrtParser;
@@ -0,0 +1,236 @@
+//! Logbook libraries: separate database files, e.g. a personal and a work
+//! logbook, that can be switched at runtime.
+//!
+//! The default library is `flights.db` in the app data directory. Libraries
+//! created by name live in `libraries/`; on the desktop any `.db` file can be
+//! opened as a library. config.json remembers the open library in
+//! `active_library` and the recently opened ones in `recent_libraries`.
+//!
+//! When the library folder is synced between machines, diverged copies show
+//! up as conflict files next to the database; `Database::reconcile_library`
+//! merges them back in.
+
+use std::path::{Component, Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::Library;
+
+/// How many libraries the recent list keeps
+const MAX_RECENT_LIBRARIES: usize = 10;
+
+/// Name shown for the library in the app data directory
+const DEFAULT_LIBRARY_NAME: &str = "Default";
+
+#[derive(Error, Debug)]
+pub enum LibraryError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Invalid library: {0}")]
+    Invalid(String),
+}
+
+/// Database file of the default library
+pub fn default_library_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("flights.db")
+}
+
+/// Database file to open at startup: the library open when the app last
+/// closed, or the default one if that file is gone
+pub fn active_library_path(data_dir: &Path) -> PathBuf {
+    let active = read_config(data_dir)
+        .get("active_library")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from);
+    match active {
+        Some(path) if path.exists() => path,
+        Some(path) => {
+            log::warn!("Library {:?} not found, opening the default library", path);
+            default_library_path(data_dir)
+        }
+        None => default_library_path(data_dir),
+    }
+}
+
+/// Whether `input` is a plain library name rather than a path
+pub fn is_library_name(input: &str) -> bool {
+    let mut components = Path::new(input).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !input.contains([':', '/', '\\'])
+}
+
+/// Turn a library name or path into its database file. A plain name becomes
+/// `libraries/<name>.db`; other paths must be absolute and get a `.db`
+/// extension if they have none.
+pub fn resolve_library_path(data_dir: &Path, name_or_path: &str) -> Result<PathBuf, LibraryError> {
+    let input = name_or_path.trim();
+    if input.is_empty() {
+        return Err(LibraryError::Invalid("a library name or path is required".to_string()));
+    }
+    let path = if is_library_name(input) {
+        data_dir.join("libraries").join(input)
+    } else {
+        let path = PathBuf::from(input);
+        if !path.is_absolute() {
+            return Err(LibraryError::Invalid(format!("library path must be absolute: {}", input)));
+        }
+        path
+    };
+    if path.extension().is_some_and(|ext| ext == "db") {
+        return Ok(path);
+    }
+    let mut with_extension = path.into_os_string();
+    with_extension.push(".db");
+    Ok(PathBuf::from(with_extension))
+}
+
+/// Whether a database file is the default library or in the recent list
+pub fn is_known_library(data_dir: &Path, path: &Path) -> bool {
+    path == default_library_path(data_dir) || recent_entries(data_dir).iter().any(|(p, _, _)| p == path)
+}
+
+/// The default library, followed by the recently opened ones, most recent first
+pub fn list_libraries(db: &Database) -> Vec<Library> {
+    let active = db.db_path();
+    let default_path = default_library_path(&db.data_dir);
+    let mut libraries = vec![library_entry(&db.data_dir, &default_path, None, None, &active)];
+    for (path, name, last_opened) in recent_entries(&db.data_dir) {
+        if path == default_path {
+            libraries[0].last_opened = last_opened;
+            continue;
+        }
+        libraries.push(library_entry(&db.data_dir, &path, name, last_opened, &active));
+    }
+    libraries
+}
+
+/// Open another library, creating it if the file doesn't exist, and move it
+/// to the top of the recent list. `name` labels the library in the list and
+/// defaults to the previous label or the file name.
+pub fn switch_library(db: &Database, name_or_path: &str, name: Option<&str>) -> Result<Library, LibraryError> {
+    let path = resolve_library_path(&db.data_dir, name_or_path)?;
+    if path != db.db_path() {
+        log::info!("Switching library to {:?}", path);
+        db.open_library(&path)?;
+    }
+
+    let previous_name = recent_entries(&db.data_dir)
+        .into_iter()
+        .find(|(p, _, _)| *p == path)
+        .and_then(|(_, n, _)| n);
+    let name = name.map(str::trim).filter(|n| !n.is_empty()).map(str::to_string).or(previous_name);
+    let last_opened = chrono::Utc::now().to_rfc3339();
+
+    let mut recent: Vec<serde_json::Value> = recent_entries(&db.data_dir)
+        .into_iter()
+        .filter(|(p, _, _)| *p != path)
+        .map(|(p, n, opened)| serde_json::json!({ "path": p, "name": n, "lastOpened": opened }))
+        .collect();
+    recent.insert(0, serde_json::json!({ "path": path, "name": name, "lastOpened": last_opened }));
+    recent.truncate(MAX_RECENT_LIBRARIES);
+
+    let mut config = read_config(&db.data_dir);
+    config["active_library"] = serde_json::json!(path);
+    config["recent_libraries"] = serde_json::json!(recent);
+    write_config(&db.data_dir, &config)?;
+
+    Ok(library_entry(&db.data_dir, &path, name, Some(last_opened), &path))
+}
+
+/// Remove a library from the recent list. The database file is kept.
+pub fn forget_library(db: &Database, path: &str) -> Result<(), LibraryError> {
+    let path = PathBuf::from(path);
+    if path == db.db_path() {
+        return Err(LibraryError::Invalid("the open library can't be removed from the list".to_string()));
+    }
+    let recent: Vec<serde_json::Value> = recent_entries(&db.data_dir)
+        .into_iter()
+        .filter(|(p, _, _)| *p != path)
+        .map(|(p, n, opened)| serde_json::json!({ "path": p, "name": n, "lastOpened": opened }))
+        .collect();
+    let mut config = read_config(&db.data_dir);
+    config["recent_libraries"] = serde_json::json!(recent);
+    write_config(&db.data_dir, &config)?;
+    Ok(())
+}
+
+/// Conflict copies of the open library left by folder sync tools, e.g.
+/// Syncthing's `flights.sync-conflict-20240501-101500-ABCDEFG.db` or
+/// Dropbox's `flights (conflicted copy 2024-05-01).db`
+pub fn conflict_copies(db: &Database) -> Vec<PathBuf> {
+    let active = db.db_path();
+    let (Some(dir), Some(stem)) = (active.parent(), active.file_stem().map(|s| s.to_string_lossy().to_string())) else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut copies: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "db") && *path != active)
+        .filter(|path| {
+            let name = path.file_stem().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default();
+            name.starts_with(&stem.to_lowercase()) && name.contains("conflict")
+        })
+        .collect();
+    copies.sort();
+    copies
+}
+
+fn library_entry(
+    data_dir: &Path,
+    path: &Path,
+    name: Option<String>,
+    last_opened: Option<String>,
+    active: &Path,
+) -> Library {
+    let is_default = path == default_library_path(data_dir);
+    let name = name.unwrap_or_else(|| {
+        if is_default {
+            DEFAULT_LIBRARY_NAME.to_string()
+        } else {
+            path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+        }
+    });
+    Library {
+        name,
+        path: path.to_string_lossy().to_string(),
+        is_default,
+        is_active: path == active,
+        exists: path.exists(),
+        last_opened,
+    }
+}
+
+/// (path, name, last opened) of each library in the recent list
+fn recent_entries(data_dir: &Path) -> Vec<(PathBuf, Option<String>, Option<String>)> {
+    let config = read_config(data_dir);
+    let Some(entries) = config.get("recent_libraries").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    let text = |entry: &serde_json::Value, key: &str| entry.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    entries
+        .iter()
+        .filter_map(|entry| Some((PathBuf::from(text(entry, "path")?), text(entry, "name"), text(entry, "lastOpened"))))
+        .collect()
+}
+
+/// The settings in config.json, or an empty object when the file is missing
+/// or unreadable
+pub(crate) fn read_config(data_dir: &Path) -> serde_json::Value {
+    std::fs::read_to_string(data_dir.join("config.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}))
+}
+
+fn write_config(data_dir: &Path, config: &serde_json::Value) -> Result<(), LibraryError> {
+    std::fs::write(data_dir.join("config.json"), serde_json::to_string_pretty(config).unwrap())?;
+    Ok(())
+}
@@ -4,7 +4,6 @@
 //! Column names contain unit suffixes like `altitude(feet)` or `altitude(m)`.
 
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
@@ -12,7 +11,7 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 
 use crate::database::Database;
 use crate::models::{FlightMetadata, FlightStats, TelemetryPoint};
-use crate::parser::{ParseResult, ParserError, LogParser};
+use crate::parser::{ParseResult, ParserError, LogParser, LogSource};
 
 /// Unit type detected from column headers
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,6 +19,7 @@ enum Unit {
     Feet,
     Meters,
     Mph,
+    KmPerHour,
     MetersPerSec,
     Fahrenheit,
     Celsius,
@@ -36,6 +36,7 @@ impl Unit {
                     "feet" | "ft" => Unit::Feet,
                     "m" | "meters" => Unit::Meters,
                     "mph" => Unit::Mph,
+                    "km/h" | "kmh" => Unit::KmPerHour,
                     "m/s" => Unit::MetersPerSec,
                     "f" => Unit::Fahrenheit,
                     "c" => Unit::Celsius,
@@ -51,6 +52,7 @@ impl Unit {
         match self {
             Unit::Feet => value * 0.3048,
             Unit::Mph => value * 0.44704,
+            Unit::KmPerHour => value / 3.6,
             Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
             // Already metric or unitless
             _ => value,
@@ -58,8 +60,8 @@ impl Unit {
     }
 }
 
-/// Column mapping for Litchi CSV
-struct ColumnMap {
+/// Column mapping for unit-suffixed CSV headers (Litchi, AirData)
+pub(crate) struct ColumnMap {
     /// Column index -> field name (without unit suffix)
     indices: HashMap<String, usize>,
     /// Field name -> unit type
@@ -67,7 +69,7 @@ struct ColumnMap {
 }
 
 impl ColumnMap {
-    fn new(headers: &[String]) -> Self {
+    pub(crate) fn new(headers: &[String]) -> Self {
         let mut indices = HashMap::new();
         let mut units = HashMap::new();
 
@@ -88,7 +90,7 @@ impl ColumnMap {
     }
 
     /// Get a value by field name, converting to metric if needed
-    fn get_f64(&self, row: &[&str], field: &str) -> Option<f64> {
+    pub(crate) fn get_f64(&self, row: &[&str], field: &str) -> Option<f64> {
         let idx = *self.indices.get(field)?;
         let val_str = row.get(idx)?;
         let val: f64 = val_str.parse().ok()?;
@@ -97,20 +99,20 @@ impl ColumnMap {
     }
 
     /// Get a raw string value by field name
-    fn get_str(&self, row: &[&str], field: &str) -> Option<String> {
+    pub(crate) fn get_str(&self, row: &[&str], field: &str) -> Option<String> {
         let idx = *self.indices.get(field)?;
         row.get(idx).map(|s| s.to_string())
     }
 
     /// Get an integer value by field name
-    fn get_i32(&self, row: &[&str], field: &str) -> Option<i32> {
+    pub(crate) fn get_i32(&self, row: &[&str], field: &str) -> Option<i32> {
         let idx = *self.indices.get(field)?;
         let val_str = row.get(idx)?;
         val_str.parse().ok()
     }
 
     /// Get a boolean value (0/1) by field name
-    fn get_bool(&self, row: &[&str], field: &str) -> Option<bool> {
+    pub(crate) fn get_bool(&self, row: &[&str], field: &str) -> Option<bool> {
         self.get_i32(row, field).map(|v| v != 0)
     }
 }
@@ -126,7 +128,8 @@ impl<'a> LitchiParser<'a> {
     }
 
     /// Check if a file is a valid Litchi CSV format
-    pub fn is_litchi_csv(path: &Path) -> bool {
+    pub fn is_litchi_csv(source: &LogSource) -> bool {
+        let path = source.path();
         // Must be a CSV file
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
         if !ext.eq_ignore_ascii_case("csv") {
@@ -134,10 +137,10 @@ impl<'a> LitchiParser<'a> {
         }
 
         // Check header line for Litchi-specific columns
-        if let Ok(file) = File::open(path) {
+        if let Ok(file) = source.open() {
             let reader = BufReader::new(file);
             if let Some(Ok(first_line)) = reader.lines().next() {
-                let lower = first_line.to_lowercase();
+                let lower = first_line.trim_start_matches('\u{feff}').to_lowercase();
                 // Litchi CSVs have these characteristic columns
                 return lower.contains("latitude") 
                     && lower.contains("longitude")
@@ -149,11 +152,12 @@ impl<'a> LitchiParser<'a> {
     }
 
     /// Parse a Litchi CSV file
-    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
         let parse_start = std::time::Instant::now();
         log::info!("Parsing Litchi CSV file: {:?}", file_path);
 
-        let file = File::open(file_path)?;
+        let file = source.open()?;
         let reader = BufReader::new(file);
         let mut lines = reader.lines();
 
@@ -163,7 +167,13 @@ impl<'a> LitchiParser<'a> {
             .ok_or_else(|| ParserError::Parse("Empty CSV file".to_string()))?
             .map_err(|e| ParserError::Parse(format!("Failed to read header: {}", e)))?;
 
-        let headers: Vec<String> = header_line.split(',').map(|s| s.trim().to_string()).collect();
+        // Some Litchi exports (and files re-saved from spreadsheets) carry a UTF-8 BOM
+        // and quoted headers, which would otherwise break the column lookup
+        let headers: Vec<String> = header_line
+            .trim_start_matches('\u{feff}')
+            .split(',')
+            .map(|s| s.trim().trim_matches('"').to_string())
+            .collect();
         let col_map = ColumnMap::new(&headers);
 
         // Parse data rows
@@ -184,7 +194,7 @@ impl<'a> LitchiParser<'a> {
                 continue;
             }
 
-            let fields: Vec<&str> = line.split(',').collect();
+            let fields: Vec<&str> = line.split(',').map(|s| s.trim().trim_matches('"')).collect();
             if fields.len() < headers.len() / 2 {
                 // Skip rows with too few fields
                 continue;
@@ -234,7 +244,7 @@ impl<'a> LitchiParser<'a> {
         tags.insert(0, "Litchi".to_string()); // Add Litchi tag at the beginning
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult { metadata, points, tags })
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors: Vec::new(), events: Vec::new() })
     }
 
     /// Parse a single CSV row into a TelemetryPoint
@@ -303,6 +313,12 @@ impl<'a> LitchiParser<'a> {
             // Camera state
             is_photo: col_map.get_bool(row, "istakingphoto"),
             is_video: col_map.get_bool(row, "isTakingVideo"),
+            cell_voltages: None,
+            compass_error: None,
+            compass_interference: None,
+            imu_status: None,
+            wind_speed: None,
+            wind_direction: None,
         }
     }
 
@@ -365,7 +381,7 @@ impl<'a> LitchiParser<'a> {
 
         // Find max altitude and speed
         let max_altitude = points.iter().filter_map(|p| p.altitude).fold(0.0f64, f64::max);
-        let max_speed = points.iter().filter_map(|p| p.speed).fold(0.0f64, f64::max);
+        let max_speed = self.filtered_speeds(points).into_iter().fold(0.0f64, f64::max);
 
         // Extract home location
         let home_lat = col_map.get_f64(first_row, "home_latitude");
@@ -398,6 +414,13 @@ impl<'a> LitchiParser<'a> {
             home_lat,
             home_lon,
             point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(
+                points,
+                home_lat.zip(home_lon).map(|(lat, lon)| [lon, lat]),
+            ),
         })
     }
 
@@ -417,10 +440,11 @@ impl<'a> LitchiParser<'a> {
 
         let total_distance_m = self.calculate_total_distance(points);
         let max_altitude_m = points.iter().filter_map(|p| p.altitude).fold(0.0f64, f64::max);
-        let max_speed_ms = points.iter().filter_map(|p| p.speed).fold(0.0f64, f64::max);
+        let speeds = self.filtered_speeds(points);
+        let max_speed_ms = speeds.iter().copied().fold(0.0f64, f64::max);
 
         // Average speed (excluding zero speeds)
-        let speeds: Vec<f64> = points.iter().filter_map(|p| p.speed).filter(|&s| s > 0.1).collect();
+        let speeds: Vec<f64> = speeds.into_iter().filter(|&s| s > 0.1).collect();
         let avg_speed_ms = if !speeds.is_empty() {
             speeds.iter().sum::<f64>() / speeds.len() as f64
         } else {
@@ -437,14 +461,10 @@ impl<'a> LitchiParser<'a> {
 
         // Max distance from home
         let max_distance_from_home_m = if let Some([home_lon, home_lat]) = home_location {
-            points
-                .iter()
-                .filter_map(|p| {
-                    match (p.latitude, p.longitude) {
-                        (Some(lat), Some(lon)) => Some(self.haversine_distance(home_lat, home_lon, lat, lon)),
-                        _ => None,
-                    }
-                })
+            crate::gps_filter::filter_positions(points.iter().map(|p| (p.timestamp_ms, p.latitude, p.longitude)))
+                .into_iter()
+                .flatten()
+                .map(|(lat, lon)| self.haversine_distance(home_lat, home_lon, lat, lon))
                 .fold(0.0f64, f64::max)
         } else {
             0.0
@@ -480,18 +500,15 @@ impl<'a> LitchiParser<'a> {
 
     /// Calculate total distance traveled using haversine formula
     fn calculate_total_distance(&self, points: &[TelemetryPoint]) -> f64 {
-        let mut total = 0.0;
-        for i in 1..points.len() {
-            if let (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) = (
-                points[i - 1].latitude,
-                points[i - 1].longitude,
-                points[i].latitude,
-                points[i].longitude,
-            ) {
-                total += self.haversine_distance(lat1, lon1, lat2, lon2);
-            }
-        }
-        total
+        crate::derived::path_distance(points.iter().map(|p| (p.timestamp_ms, p.latitude, p.longitude)))
+    }
+
+    /// Logged speeds without single-sample glitches
+    fn filtered_speeds(&self, points: &[TelemetryPoint]) -> Vec<f64> {
+        crate::gps_filter::filter_speeds(points.iter().map(|p| (p.timestamp_ms, p.speed)))
+            .into_iter()
+            .flatten()
+            .collect()
     }
 
     /// Haversine distance between two coordinates in meters
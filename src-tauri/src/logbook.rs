@@ -0,0 +1,357 @@
+//! Operator logbook: one line per flight with the fields aviation authorities
+//! ask a remote pilot to keep (FAA Part 107, EASA open/specific category):
+//! date, takeoff and landing time, location, aircraft and its registration,
+//! pilot, flight time, day or night, and remarks.
+//!
+//! Times are in UTC. A flight counts as a night flight when the sun is more
+//! than 6° below the horizon at the home point, checked at takeoff and at
+//! landing; a flight that crosses civil twilight is logged as both.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::export::{csv_escape, flight_start, ExportError};
+use crate::models::{Flight, FlightFilter, PilotDetails};
+use crate::pdf::{text_width, wrap_text, Color, Font, PdfDocument, PAGE_HEIGHT, PAGE_WIDTH};
+use crate::sun;
+
+/// File formats of the logbook export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogbookFormat {
+    Csv,
+    Pdf,
+}
+
+impl LogbookFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LogbookFormat::Csv => "csv",
+            LogbookFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            LogbookFormat::Csv => "text/csv; charset=utf-8",
+            LogbookFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+/// Logbook of the flights matching `filter` in `format`
+pub fn logbook(db: &Database, filter: &FlightFilter, format: LogbookFormat) -> Result<Vec<u8>, ExportError> {
+    match format {
+        LogbookFormat::Csv => logbook_csv(db, filter).map(String::into_bytes),
+        LogbookFormat::Pdf => logbook_pdf(db, filter),
+    }
+}
+
+/// Light conditions of a flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Light {
+    Day,
+    Night,
+    /// Took off in daylight and landed after dark, or the other way round
+    Mixed,
+}
+
+impl Light {
+    fn label(&self) -> &'static str {
+        match self {
+            Light::Day => "Day",
+            Light::Night => "Night",
+            Light::Mixed => "Day/Night",
+        }
+    }
+}
+
+/// One line of the logbook
+struct Entry {
+    takeoff: Option<DateTime<Utc>>,
+    landing: Option<DateTime<Utc>>,
+    location: String,
+    aircraft: String,
+    serial: String,
+    registration: String,
+    duration_secs: f64,
+    light: Option<Light>,
+    remarks: String,
+}
+
+impl Entry {
+    fn date(&self) -> String {
+        self.takeoff.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default()
+    }
+
+    fn time(time: Option<DateTime<Utc>>) -> String {
+        time.map(|t| t.format("%H:%M").to_string()).unwrap_or_default()
+    }
+}
+
+/// Logbook lines of the flights matching `filter`, oldest first
+fn entries(db: &Database, filter: &FlightFilter) -> Result<Vec<Entry>, ExportError> {
+    let registrations: HashMap<String, String> = db
+        .get_drone_profiles()?
+        .into_iter()
+        .filter_map(|p| Some((p.serial, p.registration?)))
+        .collect();
+
+    let mut entries = Vec::new();
+    for flight_id in db.evaluate_flight_filter(filter)? {
+        let flight = db.get_flight_by_id(flight_id)?;
+        entries.push(entry(&flight, &registrations));
+    }
+    entries.sort_by_key(|e| e.takeoff);
+    Ok(entries)
+}
+
+fn entry(flight: &Flight, registrations: &HashMap<String, String>) -> Entry {
+    let takeoff = flight_start(flight);
+    let duration_secs = flight.duration_secs.unwrap_or(0.0).max(0.0);
+    let landing = takeoff.map(|t| t + chrono::Duration::milliseconds((duration_secs * 1000.0) as i64));
+
+    let mut location = [&flight.location_city, &flight.location_region, &flight.location_country]
+        .iter()
+        .filter_map(|part| part.as_deref())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if let (true, Some(lat), Some(lon)) = (location.is_empty(), flight.home_lat, flight.home_lon) {
+        location = format!("{:.5}, {:.5}", lat, lon);
+    }
+
+    let light = match (flight.home_lat, flight.home_lon, takeoff, landing) {
+        (Some(lat), Some(lon), Some(takeoff), Some(landing)) => {
+            match (sun::is_night(lat, lon, takeoff), sun::is_night(lat, lon, landing)) {
+                (false, false) => Some(Light::Day),
+                (true, true) => Some(Light::Night),
+                _ => Some(Light::Mixed),
+            }
+        }
+        _ => None,
+    };
+
+    let model = flight.aircraft_name.as_deref().or(flight.drone_model.as_deref()).unwrap_or_default();
+    let aircraft = match flight.drone_nickname.as_deref().filter(|n| !n.trim().is_empty()) {
+        Some(nickname) if !model.is_empty() => format!("{} ({})", model, nickname),
+        Some(nickname) => nickname.to_string(),
+        None => model.to_string(),
+    };
+    let serial = flight.drone_serial.clone().unwrap_or_default();
+
+    Entry {
+        takeoff,
+        landing,
+        location,
+        aircraft,
+        registration: registrations.get(&serial).cloned().unwrap_or_default(),
+        serial,
+        duration_secs,
+        light,
+        remarks: flight
+            .notes
+            .as_deref()
+            .map(|n| n.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default(),
+    }
+}
+
+/// Flight time as hours and minutes, the way logbooks total it
+fn format_hours(secs: f64) -> String {
+    let minutes = (secs / 60.0).round() as i64;
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn pilot_label(pilot: &PilotDetails) -> String {
+    match (pilot.name.as_deref(), pilot.license.as_deref()) {
+        (Some(name), Some(license)) => format!("{} ({})", name, license),
+        (Some(name), None) => name.to_string(),
+        (None, Some(license)) => license.to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Logbook of the flights matching `filter` as CSV
+pub fn logbook_csv(db: &Database, filter: &FlightFilter) -> Result<String, ExportError> {
+    let entries = entries(db, filter)?;
+    let pilot = crate::report::pilot_details(&db.data_dir);
+
+    let mut csv = String::from(
+        "Date,Takeoff (UTC),Landing (UTC),Location,Aircraft,Aircraft SN,Registration,Pilot,Pilot License,Flight Time (h:mm),Flight Time (min),Day/Night,Remarks\n",
+    );
+    for entry in &entries {
+        let fields = [
+            entry.date(),
+            Entry::time(entry.takeoff),
+            Entry::time(entry.landing),
+            entry.location.clone(),
+            entry.aircraft.clone(),
+            entry.serial.clone(),
+            entry.registration.clone(),
+            pilot.name.clone().unwrap_or_default(),
+            pilot.license.clone().unwrap_or_default(),
+            format_hours(entry.duration_secs),
+            format!("{:.1}", entry.duration_secs / 60.0),
+            entry.light.map(|l| l.label().to_string()).unwrap_or_default(),
+            entry.remarks.clone(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_escape(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+const MARGIN: f64 = 40.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - 2.0 * MARGIN;
+/// Lowest row bottom before the table moves to the next page
+const CONTENT_BOTTOM: f64 = PAGE_HEIGHT - 50.0;
+
+const FONT_SIZE: f64 = 7.0;
+const LINE_HEIGHT: f64 = 9.0;
+const CELL_PADDING: f64 = 3.0;
+/// Lines a cell wraps to before the rest is cut off
+const MAX_CELL_LINES: usize = 6;
+
+const HEADER_COLOR: Color = Color::hex(0x111827);
+const TEXT_COLOR: Color = Color::hex(0x111827);
+const MUTED_COLOR: Color = Color::hex(0x6b7280);
+const RULE_COLOR: Color = Color::hex(0xd1d5db);
+const STRIPE_COLOR: Color = Color::hex(0xf3f4f6);
+
+/// Table columns and their widths; they add up to the content width
+const COLUMNS: [(&str, f64); 9] = [
+    ("Date", 48.0),
+    ("Takeoff", 32.0),
+    ("Landing", 32.0),
+    ("Location", 88.0),
+    ("Aircraft", 82.0),
+    ("Registration", 56.0),
+    ("Time", 30.0),
+    ("Day/Night", 42.0),
+    ("Remarks", 105.0),
+];
+
+/// Logbook of the flights matching `filter` as a printable PDF table, with
+/// the pilot on every page and the totals at the end
+pub fn logbook_pdf(db: &Database, filter: &FlightFilter) -> Result<Vec<u8>, ExportError> {
+    let entries = entries(db, filter)?;
+    let pilot = pilot_label(&crate::report::pilot_details(&db.data_dir));
+
+    let mut doc = PdfDocument::new();
+    let mut y = page_header(&mut doc, &pilot);
+    for (index, entry) in entries.iter().enumerate() {
+        let cells = [
+            vec![entry.date()],
+            vec![Entry::time(entry.takeoff)],
+            vec![Entry::time(entry.landing)],
+            cell_lines(&entry.location, COLUMNS[3].1),
+            cell_lines(&entry.aircraft, COLUMNS[4].1),
+            cell_lines(&entry.registration, COLUMNS[5].1),
+            vec![format_hours(entry.duration_secs)],
+            vec![entry.light.map(|l| l.label().to_string()).unwrap_or_else(|| "-".to_string())],
+            cell_lines(&entry.remarks, COLUMNS[8].1),
+        ];
+        let lines = cells.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        let height = lines as f64 * LINE_HEIGHT + 2.0 * CELL_PADDING;
+        if y + height > CONTENT_BOTTOM {
+            doc.add_page();
+            y = page_header(&mut doc, &pilot);
+        }
+
+        let page = doc.current_page();
+        if index % 2 == 1 {
+            page.fill_rect(MARGIN, y, CONTENT_WIDTH, height, STRIPE_COLOR);
+        }
+        let mut x = MARGIN;
+        for (cell, (_, width)) in cells.iter().zip(COLUMNS) {
+            for (line_index, line) in cell.iter().enumerate() {
+                let baseline = y + CELL_PADDING + FONT_SIZE + line_index as f64 * LINE_HEIGHT;
+                page.text(x + CELL_PADDING, baseline, FONT_SIZE, Font::Regular, TEXT_COLOR, line);
+            }
+            x += width;
+        }
+        y += height;
+        page.line((MARGIN, y), (MARGIN + CONTENT_WIDTH, y), 0.3, RULE_COLOR);
+    }
+
+    let total_secs: f64 = entries.iter().map(|e| e.duration_secs).sum();
+    let night: Vec<&Entry> = entries.iter().filter(|e| matches!(e.light, Some(Light::Night | Light::Mixed))).collect();
+    let night_secs: f64 = night.iter().map(|e| e.duration_secs).sum();
+    let totals = [
+        ("Flights", entries.len().to_string()),
+        ("Total flight time", format_hours(total_secs)),
+        ("Flights at night", night.len().to_string()),
+        ("Flight time at night", format_hours(night_secs)),
+    ];
+    if y + 50.0 > CONTENT_BOTTOM {
+        doc.add_page();
+        y = page_header(&mut doc, &pilot);
+    }
+    let page = doc.current_page();
+    let column_width = CONTENT_WIDTH / totals.len() as f64;
+    for (i, (label, value)) in totals.iter().enumerate() {
+        let x = MARGIN + i as f64 * column_width;
+        page.text(x, y + 20.0, 8.0, Font::Regular, MUTED_COLOR, label);
+        page.text(x, y + 34.0, 11.0, Font::Bold, TEXT_COLOR, value);
+    }
+    page.text(
+        MARGIN,
+        y + 52.0,
+        7.0,
+        Font::Regular,
+        MUTED_COLOR,
+        "Times in UTC. Night flights include flights that took off or landed after civil twilight (sun 6\u{b0} below the horizon); times are h:mm.",
+    );
+
+    let total = doc.page_count();
+    for index in 0..total {
+        if let Some(page) = doc.page_mut(index) {
+            let y = PAGE_HEIGHT - 24.0;
+            page.line((MARGIN, y - 12.0), (MARGIN + CONTENT_WIDTH, y - 12.0), 0.5, RULE_COLOR);
+            page.text(MARGIN, y, 8.0, Font::Regular, MUTED_COLOR, "Operator logbook");
+            page.text_right(MARGIN + CONTENT_WIDTH, y, 8.0, Font::Regular, MUTED_COLOR, &format!("Page {} of {}", index + 1, total));
+        }
+    }
+    Ok(doc.finish("Operator Logbook"))
+}
+
+/// Title band and column headings of a page; returns the top of the first row
+fn page_header(doc: &mut PdfDocument, pilot: &str) -> f64 {
+    let generated = format!("Generated {}", Utc::now().format("%Y-%m-%d %H:%M UTC"));
+    let page = doc.current_page();
+    page.fill_rect(0.0, 0.0, PAGE_WIDTH, 64.0, HEADER_COLOR);
+    page.text(MARGIN, 30.0, 16.0, Font::Bold, Color::WHITE, "Operator Logbook");
+    if !pilot.is_empty() {
+        page.text(MARGIN, 48.0, 10.0, Font::Regular, Color::hex(0xd1d5db), &format!("Pilot: {}", pilot));
+    }
+    page.text_right(MARGIN + CONTENT_WIDTH, 30.0, 8.0, Font::Regular, Color::hex(0x9ca3af), &generated);
+
+    let y = 80.0;
+    let mut x = MARGIN;
+    for (title, width) in COLUMNS {
+        page.text(x + CELL_PADDING, y + 10.0, FONT_SIZE, Font::Bold, TEXT_COLOR, title);
+        x += width;
+    }
+    page.line((MARGIN, y + 15.0), (MARGIN + CONTENT_WIDTH, y + 15.0), 0.8, TEXT_COLOR);
+    y + 15.0
+}
+
+/// Text wrapped to a table column; words too long for it are cut short
+fn cell_lines(text: &str, width: f64) -> Vec<String> {
+    let max_width = width - 2.0 * CELL_PADDING;
+    wrap_text(text, FONT_SIZE, Font::Regular, max_width)
+        .into_iter()
+        .map(|mut line| {
+            while text_width(&line, FONT_SIZE, Font::Regular) > max_width {
+                line.pop();
+            }
+            line
+        })
+        .filter(|line| !line.is_empty())
+        .take(MAX_CELL_LINES)
+        .collect()
+}
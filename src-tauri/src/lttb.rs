@@ -0,0 +1,196 @@
+//! Largest-Triangle-Three-Buckets downsampling of flight telemetry.
+//!
+//! LTTB splits the points into as many buckets as points wanted and keeps,
+//! from each bucket, the point forming the largest triangle with the point
+//! kept before it and the average of the next bucket. Unlike averaging, it
+//! keeps the points that shape the chart: climbs, dives and spikes.
+//!
+//! A telemetry point carries many series, so the budget is shared between
+//! height and speed, which shape most charts, and every point kept by either
+//! is kept whole. On top of those, the highest, fastest and lowest-battery
+//! points are always kept, so peaks read exactly as in the raw log, and so
+//! are flight mode changes, photos and video starts and stops, which the
+//! charts mark.
+
+use std::collections::BTreeSet;
+
+use crate::models::TelemetryRecord;
+
+/// Points of `records`, in time order, reduced to about `target` with peaks
+/// and mode changes kept. Returns everything when there are few enough.
+pub fn downsample(records: &[TelemetryRecord], target: usize) -> Vec<TelemetryRecord> {
+    if records.len() <= target {
+        return records.to_vec();
+    }
+
+    let time: Vec<f64> = records.iter().map(|r| r.timestamp_ms as f64).collect();
+    let height = carried(records.iter().map(|r| r.height.or(r.vps_height).or(r.altitude)));
+    let speed = carried(records.iter().map(|r| r.speed));
+
+    let mut keep: BTreeSet<usize> = BTreeSet::new();
+    keep.extend(lttb_indices(&time, &height, target / 2));
+    keep.extend(lttb_indices(&time, &speed, target - target / 2));
+
+    for series in [
+        records.iter().map(|r| r.height).collect::<Vec<_>>(),
+        records.iter().map(|r| r.altitude).collect(),
+        records.iter().map(|r| r.speed).collect(),
+    ] {
+        keep.extend(extreme(&series, |a, b| a > b));
+    }
+    let battery: Vec<Option<f64>> = records.iter().map(|r| r.battery_percent.map(f64::from)).collect();
+    keep.extend(extreme(&battery, |a, b| a < b));
+
+    for (i, pair) in records.windows(2).enumerate() {
+        let (before, after) = (&pair[0], &pair[1]);
+        if after.flight_mode != before.flight_mode || after.is_video != before.is_video || after.is_photo == Some(true) {
+            keep.insert(i + 1);
+        }
+    }
+
+    keep.into_iter().map(|i| records[i].clone()).collect()
+}
+
+/// Indices of the `threshold` points LTTB keeps from `(x, y)`, first and
+/// last included
+pub fn lttb_indices(x: &[f64], y: &[f64], threshold: usize) -> Vec<usize> {
+    let n = x.len().min(y.len());
+    if threshold >= n || threshold < 3 {
+        return (0..n).collect();
+    }
+
+    // Buckets between the first and the last point, which are always kept
+    let every = (n - 2) as f64 / (threshold - 2) as f64;
+    let mut kept = Vec::with_capacity(threshold);
+    let mut a = 0;
+    kept.push(a);
+
+    for i in 0..threshold - 2 {
+        // Average of the next bucket, the third corner of the triangle
+        let next_start = ((i + 1) as f64 * every) as usize + 1;
+        let next_end = (((i + 2) as f64 * every) as usize + 1).min(n);
+        let (next_start, next_end) = if next_start < next_end { (next_start, next_end) } else { (n - 1, n) };
+        let len = (next_end - next_start) as f64;
+        let avg_x = x[next_start..next_end].iter().sum::<f64>() / len;
+        let avg_y = y[next_start..next_end].iter().sum::<f64>() / len;
+
+        let start = (i as f64 * every) as usize + 1;
+        let end = (((i + 1) as f64 * every) as usize + 1).min(n - 1);
+        let mut best = start;
+        let mut best_area = -1.0;
+        for j in start..end.max(start + 1) {
+            let area = ((x[a] - avg_x) * (y[j] - y[a]) - (x[a] - x[j]) * (avg_y - y[a])).abs();
+            if area > best_area {
+                best_area = area;
+                best = j;
+            }
+        }
+        kept.push(best);
+        a = best;
+    }
+
+    kept.push(n - 1);
+    kept
+}
+
+/// Values with gaps filled by the last known value, zero before the first
+fn carried(values: impl Iterator<Item = Option<f64>>) -> Vec<f64> {
+    let mut last = 0.0;
+    values
+        .map(|v| {
+            if let Some(v) = v.filter(|v| v.is_finite()) {
+                last = v;
+            }
+            last
+        })
+        .collect()
+}
+
+/// Index of the first value beating every other by `better`, if any
+fn extreme(values: &[Option<f64>], better: impl Fn(f64, f64) -> bool) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (i, v) in values.iter().enumerate() {
+        if let Some(v) = v.filter(|v| v.is_finite()) {
+            if best.map_or(true, |(_, b)| better(v, b)) {
+                best = Some((i, v));
+            }
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp_ms: i64, height: f64, speed: f64, battery: i32) -> TelemetryRecord {
+        TelemetryRecord {
+            timestamp_ms,
+            latitude: None,
+            longitude: None,
+            altitude: None,
+            height: Some(height),
+            vps_height: None,
+            speed: Some(speed),
+            velocity_x: None,
+            velocity_y: None,
+            velocity_z: None,
+            battery_percent: Some(battery),
+            battery_voltage: None,
+            battery_temp: None,
+            pitch: None,
+            roll: None,
+            yaw: None,
+            satellites: None,
+            flight_mode: Some("GPS".to_string()),
+            rc_signal: None,
+            rc_uplink: None,
+            rc_downlink: None,
+            rc_aileron: None,
+            rc_elevator: None,
+            rc_throttle: None,
+            rc_rudder: None,
+            is_photo: None,
+            is_video: None,
+            compass_error: None,
+            compass_interference: None,
+            imu_status: None,
+            wind_speed: None,
+            wind_direction: None,
+            cumulative_distance: 0.0,
+        }
+    }
+
+    #[test]
+    fn lttb_keeps_the_ends_and_a_spike() {
+        let x: Vec<f64> = (0..1000).map(f64::from).collect();
+        let mut y = vec![0.0; 1000];
+        y[437] = 50.0;
+        let kept = lttb_indices(&x, &y, 20);
+        assert_eq!(kept.len(), 20);
+        assert_eq!((kept[0], kept[19]), (0, 999));
+        assert!(kept.contains(&437));
+        assert!(kept.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn downsampling_keeps_peaks_and_mode_changes() {
+        // Wobbling climb with a single-sample speed spike and a mode change
+        let mut records: Vec<TelemetryRecord> = (0..30_000)
+            .map(|i| record(i * 100, (i as f64 / 300.0).sin() * 5.0 + i as f64 / 1000.0, 8.0 + (i % 7) as f64 * 0.1, 100 - (i / 400) as i32))
+            .collect();
+        records[12_345].speed = Some(21.7);
+        records[20_000..].iter_mut().for_each(|r| r.flight_mode = Some("GoHome".to_string()));
+
+        let sampled = downsample(&records, 2000);
+        assert!(sampled.len() <= 2100);
+        assert!(sampled.windows(2).all(|w| w[0].timestamp_ms < w[1].timestamp_ms));
+        let max = |f: fn(&TelemetryRecord) -> Option<f64>, rs: &[TelemetryRecord]| {
+            rs.iter().filter_map(f).fold(f64::MIN, f64::max)
+        };
+        assert_eq!(max(|r| r.speed, &sampled), 21.7);
+        assert_eq!(max(|r| r.height, &sampled), max(|r| r.height, &records));
+        assert_eq!(sampled.iter().filter_map(|r| r.battery_percent).min(), Some(100 - 74));
+        assert!(sampled.iter().any(|r| r.timestamp_ms == 2_000_000));
+    }
+}
@@ -12,16 +12,57 @@
     windows_subsystem = "windows"
 )]
 
+mod airdata_parser;
 mod api;
+mod autel_parser;
+mod auto_export;
+mod batch_import;
+mod battery_health;
+mod compliance;
+mod coverage;
+mod currency;
+mod dat_parser;
 mod database;
+mod derived;
+mod drone_comparison;
 mod dronelogbook_parser;
+mod energy;
+mod export;
+mod flight_modes;
+mod geocoder;
+mod gps_filter;
+mod library;
 mod litchi_parser;
+mod logbook;
+mod lttb;
+mod mavlink;
+mod media_scanner;
+mod milestones;
+mod mission_parser;
 mod models;
+mod parrot_parser;
 mod parser;
+mod pdf;
+mod phases;
+mod report;
+mod rth_events;
+mod sites;
+mod srt_parser;
+mod sun;
+mod telemetry_pack;
+mod terrain;
+mod timeline;
+mod track_smoothing;
+mod wind;
+mod xlsx;
+mod year_review;
 
 #[cfg(all(feature = "web", not(feature = "tauri-app")))]
 mod server;
 
+#[cfg(feature = "tauri-app")]
+mod watcher;
+
 // ============================================================================
 // TAURI DESKTOP MODE
 // ============================================================================
@@ -35,9 +76,12 @@ mod tauri_app {
     use tauri_plugin_log::{Target, TargetKind};
     use log::LevelFilter;
 
-    use crate::database::{Database, DatabaseError};
-    use crate::models::{Flight, FlightDataResponse, FlightTag, ImportResult, OverviewStats, TelemetryData};
+    use crate::database::{Database, DatabaseError, DEFAULT_AUDIT_LIMIT, DEFAULT_SEARCH_LIMIT, DEFAULT_STITCH_MAX_GAP_SECS};
+    use crate::models::{AuditEntry, AutoExportSettings, Battery, BatchExportResult, BatteryHealth, ChecklistItem, ChecklistTemplate, ComplianceLimits, ComplianceReport, CurrencyReport, CurrencyRequirement, DischargeCurve, DroneComparison, DroneEnergy, DroneProfile, Equipment, ExportTemplate, FirmwareHistoryEntry, Flight, FlightAnnotation, FlightChecklist, FlightDataResponse, FlightDocument, FlightFilter, FlightMedia, FlightModeStats, FlightModeTime, FlightSearchHit, FlightTag, ImportResult, Incident, Library, LibraryCheckReport, LibraryStats, MaintenanceRecord, MaintenanceStatus, MediaScanResult, MilestoneReport, Mission, OverviewStats, PersonalRecords, PilotDetails, RthStats, Site, SiteStats, SmartList, StitchCandidate, TelemetryData, TerrainProfile, TerrainSettings, TrackSmoothing, TrendBucket, TrendPoint, WarningSummary, YearReview};
+    use crate::geocoder::GeocodeProvider;
     use crate::parser::LogParser;
+    use crate::media_scanner::MediaScanner;
+    use crate::mission_parser::MissionParser;
     use crate::api::DjiApi;
 
     /// Application state containing the database connection
@@ -177,9 +221,25 @@ mod tauri_app {
             });
         }
 
+        import_source(&file_path, None, &state, import_start).await
+    }
+
+    /// Parse and save one log, read from `file_path` or, when `data` is given,
+    /// from memory under that name.
+    async fn import_source(
+        file_path: &str,
+        data: Option<&[u8]>,
+        state: &AppState,
+        import_start: std::time::Instant,
+    ) -> Result<ImportResult, String> {
+        let path = std::path::Path::new(file_path);
         let parser = LogParser::new(&state.db);
 
-        let parse_result = match parser.parse_log(&path).await {
+        let parsed = match data {
+            Some(data) => parser.parse_log_data(path, data).await,
+            None => parser.parse_log(path).await,
+        };
+        let parse_result = match parsed {
             Ok(result) => result,
             Err(crate::parser::ParserError::AlreadyImported(matching_flight)) => {
                 log::info!("Skipping already-imported file: {} — matches flight '{}' in database", file_path, matching_flight);
@@ -191,6 +251,26 @@ mod tauri_app {
                     file_hash: None,
                 });
             }
+            Err(crate::parser::ParserError::InTrash(trashed_flight)) => {
+                log::info!("Skipping {} — its flight '{}' is in the trash", file_path, trashed_flight);
+                return Ok(ImportResult {
+                    success: false,
+                    flight_id: None,
+                    message: format!("This flight log belongs to '{}', which is in the trash. Restore it from the trash instead.", trashed_flight),
+                    point_count: 0,
+                    file_hash: None,
+                });
+            }
+            Err(crate::parser::ParserError::LinkedToFlight(flight_id, matching_flight)) => {
+                log::info!("Linked {} as rich data to existing flight '{}'", file_path, matching_flight);
+                return Ok(ImportResult {
+                    success: true,
+                    flight_id: Some(flight_id),
+                    message: format!("Linked as rich data to existing flight '{}'", matching_flight),
+                    point_count: 0,
+                    file_hash: None,
+                });
+            }
             Err(e) => {
                 log::error!("Failed to parse log {}: {}", file_path, e);
                 return Ok(ImportResult {
@@ -232,7 +312,7 @@ mod tauri_app {
             Ok(count) => count,
             Err(e) => {
                 log::error!("Failed to insert telemetry for flight {}: {}. Cleaning up.", flight_id, e);
-                if let Err(cleanup_err) = state.db.delete_flight(flight_id) {
+                if let Err(cleanup_err) = state.db.purge_flight(flight_id) {
                     log::error!("Failed to clean up flight {}: {}", flight_id, cleanup_err);
                 }
                 return Ok(ImportResult {
@@ -245,16 +325,18 @@ mod tauri_app {
             }
         };
 
+        if let Err(e) = state.db.insert_home_points(flight_id, &parse_result.home_points) {
+            log::warn!("Failed to insert home points for flight {}: {}", flight_id, e);
+        }
+        if let Err(e) = state.db.insert_motor_samples(flight_id, &parse_result.motors) {
+            log::warn!("Failed to insert motor samples for flight {}: {}", flight_id, e);
+        }
+        if let Err(e) = state.db.insert_flight_events(flight_id, &parse_result.events) {
+            log::warn!("Failed to insert events for flight {}: {}", flight_id, e);
+        }
+
         // Insert smart tags if the feature is enabled
-        let config_path = state.db.data_dir.join("config.json");
-        let config: serde_json::Value = if config_path.exists() {
-            std::fs::read_to_string(&config_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
+        let config = crate::library::read_config(&state.db.data_dir);
         let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
         
         if tags_enabled {
@@ -278,16 +360,98 @@ mod tauri_app {
             point_count,
             import_start.elapsed().as_secs_f64()
         );
+        crate::geocoder::locate_in_background(state.db.clone());
+        crate::auto_export::export_in_background(state.db.clone());
 
         Ok(ImportResult {
             success: true,
             flight_id: Some(flight_id),
-            message: format!("Successfully imported {} telemetry points", point_count),
+            message: parse_result.import_message(point_count),
             point_count,
             file_hash: parse_result.metadata.file_hash.clone(),
         })
     }
 
+    /// Import every supported log inside a ZIP archive (nested archives included).
+    /// Entries are decompressed on a blocking thread and parsed in memory one
+    /// at a time as they come out; nothing is extracted to disk.
+    #[tauri::command]
+    pub async fn import_zip(file_path: String, state: State<'_, AppState>) -> Result<Vec<ImportResult>, String> {
+        let import_start = std::time::Instant::now();
+        log::info!("Importing ZIP archive: {}", file_path);
+
+        // One entry in flight: the reader waits until the previous log is imported
+        let (sender, mut receiver) = tokio::sync::mpsc::channel::<(String, Vec<u8>)>(1);
+        let archive_path = file_path.clone();
+        let reader = tokio::task::spawn_blocking(move || -> Result<(), crate::parser::ParserError> {
+            let file = std::fs::File::open(&archive_path)?;
+            crate::parser::for_each_zip_entry(std::io::BufReader::new(file), |name, contents| {
+                sender.blocking_send((name, contents)).is_ok()
+            })
+        });
+
+        let mut results = Vec::new();
+        while let Some((entry_name, contents)) = receiver.recv().await {
+            // Keep the original file name: parsers rely on it for format and start time detection
+            let base_name = std::path::Path::new(&entry_name)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("log.txt")
+                .to_string();
+
+            let mut result = import_source(&base_name, Some(&contents), &state, std::time::Instant::now())
+                .await
+                .unwrap_or_else(|e| ImportResult {
+                    success: false,
+                    flight_id: None,
+                    message: e,
+                    point_count: 0,
+                    file_hash: None,
+                });
+
+            result.message = format!("{}: {}", entry_name, result.message);
+            results.push(result);
+        }
+
+        let read_result = reader.await.map_err(|e| format!("Failed to read archive: {}", e))?;
+        if let Err(e) = read_result {
+            // Entries imported before the archive turned out damaged stay imported
+            if results.is_empty() {
+                return Err(format!("Failed to read archive: {}", e));
+            }
+            log::warn!("ZIP archive {} stopped early: {}", file_path, e);
+        }
+
+        log::info!(
+            "ZIP import finished in {:.1}s: {} of {} files imported",
+            import_start.elapsed().as_secs_f64(),
+            results.iter().filter(|r| r.success).count(),
+            results.len()
+        );
+
+        Ok(results)
+    }
+
+    /// Import many log files at once. Files are parsed in parallel and saved in
+    /// batched transactions; an `import-progress` event is emitted after each file.
+    #[tauri::command]
+    pub async fn import_logs_batch(
+        file_paths: Vec<String>,
+        app: AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<ImportResult>, String> {
+        use tauri::Emitter;
+
+        let paths = file_paths.into_iter().map(PathBuf::from).collect();
+        let results = crate::batch_import::import_batch(state.db.clone(), paths, |progress| {
+            if let Err(e) = app.emit("import-progress", progress) {
+                log::warn!("Failed to emit import progress: {}", e);
+            }
+        })
+        .await;
+        Ok(results)
+    }
+
     /// Compute SHA256 hash of a file without importing it
     /// Used to check if a file is blacklisted before importing
     #[tauri::command]
@@ -300,6 +464,40 @@ mod tauri_app {
             .map_err(|e| format!("Failed to compute hash: {}", e))
     }
 
+    #[tauri::command]
+    pub async fn get_import_blacklist(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+        state
+            .db
+            .get_import_blacklist()
+            .map_err(|e| format!("Failed to get import blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn add_to_import_blacklist(file_hashes: Vec<String>, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .add_to_import_blacklist(&file_hashes)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to update import blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn remove_from_import_blacklist(file_hash: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .remove_from_import_blacklist(&file_hash)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to update import blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn clear_import_blacklist(state: State<'_, AppState>) -> Result<usize, String> {
+        state
+            .db
+            .clear_import_blacklist()
+            .map_err(|e| format!("Failed to clear import blacklist: {}", e))
+    }
+
     #[tauri::command]
     pub async fn get_flights(state: State<'_, AppState>) -> Result<Vec<Flight>, String> {
         let start = std::time::Instant::now();
@@ -338,8 +536,8 @@ mod tauri_app {
                 _ => format!("Failed to get telemetry: {}", e),
             })?;
 
-        let telemetry = TelemetryData::from_records(&telemetry_records);
-        let track = telemetry.extract_track(2000);
+        let mut telemetry = TelemetryData::from_records(&telemetry_records);
+        let track = telemetry.extract_track(2000, crate::track_smoothing::method(&state.db.data_dir));
 
         log::debug!(
             "get_flight_data for flight {} complete in {:.1}ms: {} telemetry series, {} track points",
@@ -349,10 +547,31 @@ mod tauri_app {
             track.len()
         );
 
+        let attachments = state.db.get_flight_attachments(flight_id).unwrap_or_default();
+        let home_points = state.db.get_home_points(flight_id).unwrap_or_default();
+        let events = state.db.get_flight_events(flight_id).unwrap_or_default();
+        let timestamps: Vec<i64> = telemetry_records.iter().map(|r| r.timestamp_ms).collect();
+        let motors = state.db.get_motor_series(flight_id, &timestamps).unwrap_or_default();
+        telemetry.cell_voltages = state.db.get_cell_voltage_series(flight_id, &timestamps).unwrap_or_default();
+        (telemetry.distance_from_home, telemetry.distance_from_home_3d) =
+            crate::derived::distance_from_home(&telemetry, &timestamps, &home_points, flight.home_lat.zip(flight.home_lon));
+        (telemetry.estimated_wind_speed, telemetry.estimated_wind_direction) =
+            crate::wind::estimate(&telemetry, flight.drone_model.as_deref());
+        let timeline = crate::timeline::build(&telemetry, &timestamps, &events);
+        let phases = crate::phases::segment(&telemetry);
+        let annotations = state.db.get_flight_annotations(flight_id).unwrap_or_default();
+
         Ok(FlightDataResponse {
             flight,
             telemetry,
             track,
+            attachments,
+            home_points,
+            events,
+            timeline,
+            phases,
+            motors,
+            annotations,
         })
     }
 
@@ -372,9 +591,192 @@ mod tauri_app {
         Ok(stats)
     }
 
+    /// Totals, per-drone and per-month statistics, of `flight_ids` only when given
+    #[tauri::command]
+    pub async fn get_library_stats(flight_ids: Option<Vec<i64>>, state: State<'_, AppState>) -> Result<LibraryStats, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || db.get_library_stats(flight_ids.as_deref()))
+            .await
+            .map_err(|e| format!("Failed to get library stats: {}", e))?
+            .map_err(|e| format!("Failed to get library stats: {}", e))
+    }
+
+    /// Flight count, time and distance per day, week, month or year, of `flight_ids` only when given
+    #[tauri::command]
+    pub async fn get_trends(
+        bucket: TrendBucket,
+        flight_ids: Option<Vec<i64>>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TrendPoint>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || db.get_trends(bucket, flight_ids.as_deref()))
+            .await
+            .map_err(|e| format!("Failed to get trends: {}", e))?
+            .map_err(|e| format!("Failed to get trends: {}", e))
+    }
+
+    /// Record flights: longest, greatest distance, highest, fastest and farthest from home
+    #[tauri::command]
+    pub async fn get_personal_records(state: State<'_, AppState>) -> Result<PersonalRecords, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || db.get_personal_records(None))
+            .await
+            .map_err(|e| format!("Failed to get personal records: {}", e))?
+            .map_err(|e| format!("Failed to get personal records: {}", e))
+    }
+
+    /// Flight, hour and distance milestones with the current and longest streaks
+    #[tauri::command]
+    pub async fn get_milestones(state: State<'_, AppState>) -> Result<MilestoneReport, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::milestones::milestone_report(&db))
+            .await
+            .map_err(|e| format!("Failed to get milestones: {}", e))?
+            .map_err(|e| format!("Failed to get milestones: {}", e))
+    }
+
+    /// Two aircraft (by serial) or two models side by side
+    #[tauri::command]
+    pub async fn compare_drones(first: String, second: String, state: State<'_, AppState>) -> Result<DroneComparison, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::drone_comparison::compare_drones(&db, &first, &second))
+            .await
+            .map_err(|e| format!("Failed to compare drones: {}", e))?
+            .map_err(|e| format!("Failed to compare drones: {}", e))
+    }
+
+    /// Summary of one calendar year of flying
+    #[tauri::command]
+    pub async fn get_year_review(year: i32, state: State<'_, AppState>) -> Result<YearReview, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::year_review::year_review(&db, year))
+            .await
+            .map_err(|e| format!("Failed to get year in review: {}", e))?
+            .map_err(|e| format!("Failed to get year in review: {}", e))
+    }
+
+    /// Write the one-page PDF of a year in review to `dest_path`
+    #[tauri::command]
+    pub async fn export_year_review(
+        year: i32,
+        dest_path: String,
+        units: Option<crate::export::ExportUnits>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db.clone();
+        let pdf = tokio::task::spawn_blocking(move || {
+            crate::year_review::year_review_pdf(&db, year, &units.unwrap_or_default())
+        })
+        .await
+        .map_err(|e| format!("Failed to create year in review: {}", e))?
+        .map_err(|e| format!("Failed to create year in review: {}", e))?;
+        std::fs::write(&dest_path, pdf).map_err(|e| format!("Failed to write year in review: {}", e))?;
+        Ok(true)
+    }
+
+    /// Recent flying of the pilot and each aircraft against the currency requirements
+    #[tauri::command]
+    pub async fn get_currency(state: State<'_, AppState>) -> Result<CurrencyReport, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::currency::currency(&db))
+            .await
+            .map_err(|e| format!("Failed to get currency: {}", e))?
+            .map_err(|e| format!("Failed to get currency: {}", e))
+    }
+
+    /// Flights above the height limit or beyond the distance limit, with the stretches that were
+    #[tauri::command]
+    pub async fn get_compliance_report(state: State<'_, AppState>) -> Result<ComplianceReport, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::compliance::compliance_report(&db))
+            .await
+            .map_err(|e| format!("Failed to get compliance report: {}", e))?
+            .map_err(|e| format!("Failed to get compliance report: {}", e))
+    }
+
+    /// Energy used per flight and the efficiency of each aircraft
+    #[tauri::command]
+    pub async fn get_energy_stats(state: State<'_, AppState>) -> Result<Vec<DroneEnergy>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::energy::energy_stats(&db))
+            .await
+            .map_err(|e| format!("Failed to get energy statistics: {}", e))?
+            .map_err(|e| format!("Failed to get energy statistics: {}", e))
+    }
+
+    /// RTH triggers, failsafe activations and RC signal losses per aircraft and month
+    #[tauri::command]
+    pub async fn get_rth_stats(state: State<'_, AppState>) -> Result<RthStats, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::rth_events::rth_stats(&db))
+            .await
+            .map_err(|e| format!("Failed to get RTH statistics: {}", e))?
+            .map_err(|e| format!("Failed to get RTH statistics: {}", e))
+    }
+
+    /// Time per flight mode and mode category across the library
+    #[tauri::command]
+    pub async fn get_flight_mode_stats(state: State<'_, AppState>) -> Result<FlightModeStats, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::flight_modes::flight_mode_stats(&db))
+            .await
+            .map_err(|e| format!("Failed to get flight mode statistics: {}", e))?
+            .map_err(|e| format!("Failed to get flight mode statistics: {}", e))
+    }
+
+    /// Time per flight mode of one flight, longest first
+    #[tauri::command]
+    pub async fn get_flight_modes(flight_id: i64, state: State<'_, AppState>) -> Result<Vec<FlightModeTime>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::flight_modes::flight_modes(&db, flight_id))
+            .await
+            .map_err(|e| format!("Failed to get flight modes: {}", e))?
+            .map_err(|e| format!("Failed to get flight modes: {}", e))
+    }
+
+    /// Flying sites with their visits and flight time, clustering first
+    /// when flights have no site yet
+    #[tauri::command]
+    pub async fn get_sites(state: State<'_, AppState>) -> Result<Vec<Site>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::sites::sites(&db))
+            .await
+            .map_err(|e| format!("Failed to get sites: {}", e))?
+            .map_err(|e| format!("Failed to get sites: {}", e))
+    }
+
+    /// Flight time, wind, seasons and aircraft of every site
+    #[tauri::command]
+    pub async fn get_site_stats(state: State<'_, AppState>) -> Result<Vec<SiteStats>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::sites::site_stats(&db))
+            .await
+            .map_err(|e| format!("Failed to get site statistics: {}", e))?
+            .map_err(|e| format!("Failed to get site statistics: {}", e))
+    }
+
+    /// Cluster the home points of every flight into sites again
+    #[tauri::command]
+    pub async fn recluster_sites(state: State<'_, AppState>) -> Result<Vec<Site>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::sites::recluster(&db))
+            .await
+            .map_err(|e| format!("Failed to cluster sites: {}", e))?
+            .map_err(|e| format!("Failed to cluster sites: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn rename_site(site_id: i64, name: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .rename_site(site_id, &name)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to rename site: {}", e))
+    }
+
     #[tauri::command]
     pub async fn delete_flight(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
-        log::info!("Deleting flight: {}", flight_id);
+        log::info!("Moving flight to trash: {}", flight_id);
         state
             .db
             .delete_flight(flight_id)
@@ -382,6 +784,43 @@ mod tauri_app {
             .map_err(|e| format!("Failed to delete flight: {}", e))
     }
 
+    #[tauri::command]
+    pub async fn get_deleted_flights(state: State<'_, AppState>) -> Result<Vec<Flight>, String> {
+        state
+            .db
+            .get_deleted_flights()
+            .map_err(|e| format!("Failed to get trash: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn restore_flight(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        log::info!("Restoring flight from trash: {}", flight_id);
+        state
+            .db
+            .restore_flight(flight_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to restore flight: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn purge_flight(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        log::info!("Permanently deleting flight: {}", flight_id);
+        state
+            .db
+            .purge_flight(flight_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to purge flight: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn empty_trash(state: State<'_, AppState>) -> Result<usize, String> {
+        log::warn!("Emptying trash");
+        state
+            .db
+            .empty_trash()
+            .map_err(|e| format!("Failed to empty trash: {}", e))
+    }
+
     #[tauri::command]
     pub async fn delete_all_flights(state: State<'_, AppState>) -> Result<bool, String> {
         log::warn!("Deleting ALL flights and telemetry");
@@ -401,6 +840,37 @@ mod tauri_app {
             .map_err(|e| format!("Failed to deduplicate flights: {}", e))
     }
 
+    #[tauri::command]
+    pub async fn get_stitch_candidates(
+        max_gap_secs: Option<f64>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<StitchCandidate>, String> {
+        let max_gap_secs = max_gap_secs.unwrap_or_else(|| {
+            crate::library::read_config(&state.db.data_dir)
+                .get("stitch_max_gap_secs")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(DEFAULT_STITCH_MAX_GAP_SECS)
+        });
+        state
+            .db
+            .find_stitch_candidates(max_gap_secs)
+            .map_err(|e| format!("Failed to find split flights: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn stitch_flights(
+        first_id: i64,
+        second_id: i64,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        log::info!("Stitching flight {} into {}", second_id, first_id);
+        state
+            .db
+            .merge_flights(first_id, second_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to merge flights: {}", e))
+    }
+
     #[tauri::command]
     pub async fn update_flight_name(
         flight_id: i64,
@@ -442,110 +912,1140 @@ mod tauri_app {
     }
 
     #[tauri::command]
-    pub async fn has_api_key(state: State<'_, AppState>) -> Result<bool, String> {
-        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
-        Ok(api.has_api_key())
+    pub async fn set_flight_favorite(flight_id: i64, favorite: bool, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .set_flight_favorite(flight_id, favorite)
+            .map(|_| favorite)
+            .map_err(|e| format!("Failed to update favorite: {}", e))
     }
 
     #[tauri::command]
-    pub async fn get_api_key_type(state: State<'_, AppState>) -> Result<String, String> {
-        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
-        Ok(api.get_api_key_type())
+    pub async fn set_flight_excluded_from_stats(
+        flight_id: i64,
+        excluded: bool,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        state
+            .db
+            .set_flight_excluded_from_stats(flight_id, excluded)
+            .map(|_| excluded)
+            .map_err(|e| format!("Failed to update statistics exclusion: {}", e))
     }
 
     #[tauri::command]
-    pub async fn set_api_key(api_key: String, state: State<'_, AppState>) -> Result<bool, String> {
-        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
-        api.save_api_key(&api_key)
-            .map(|_| true)
-            .map_err(|e| format!("Failed to save API key: {}", e))
+    pub async fn get_batteries(state: State<'_, AppState>) -> Result<Vec<Battery>, String> {
+        state
+            .db
+            .get_batteries()
+            .map_err(|e| format!("Failed to get batteries: {}", e))
     }
 
+    /// Capacity fade, voltage sag trend and health score of every battery
     #[tauri::command]
-    pub async fn remove_api_key(state: State<'_, AppState>) -> Result<bool, String> {
-        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
-        api.remove_api_key()
-            .map(|_| true)
-            .map_err(|e| format!("Failed to remove API key: {}", e))
+    pub async fn get_battery_health(state: State<'_, AppState>) -> Result<Vec<BatteryHealth>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::battery_health::battery_health(&db))
+            .await
+            .map_err(|e| format!("Failed to get battery health: {}", e))?
+            .map_err(|e| format!("Failed to get battery health: {}", e))
     }
 
+    /// Discharge curves of every flight of a battery, oldest first
     #[tauri::command]
-    pub async fn get_app_data_dir(state: State<'_, AppState>) -> Result<String, String> {
-        Ok(state.db.data_dir.to_string_lossy().to_string())
+    pub async fn get_discharge_curves(serial: String, state: State<'_, AppState>) -> Result<Vec<DischargeCurve>, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || crate::battery_health::discharge_curves(&db, &serial))
+            .await
+            .map_err(|e| format!("Failed to get discharge curves: {}", e))?
+            .map_err(|e| format!("Failed to get discharge curves: {}", e))
     }
 
     #[tauri::command]
-    pub async fn get_app_log_dir(app: AppHandle) -> Result<String, String> {
-        app.path()
-            .app_log_dir()
-            .map_err(|e| format!("Failed to get app log directory: {}", e))
-            .map(|dir| dir.to_string_lossy().to_string())
+    pub async fn get_drone_profiles(state: State<'_, AppState>) -> Result<Vec<DroneProfile>, String> {
+        state
+            .db
+            .get_drone_profiles()
+            .map_err(|e| format!("Failed to get drone profiles: {}", e))
     }
 
+    /// Firmware versions a drone has flown with, oldest first
     #[tauri::command]
-    pub async fn export_backup(dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
-        let path = std::path::PathBuf::from(&dest_path);
-        log::info!("Exporting database backup to: {}", dest_path);
+    pub async fn get_firmware_history(serial: String, state: State<'_, AppState>) -> Result<Vec<FirmwareHistoryEntry>, String> {
         state
             .db
-            .export_backup(&path)
-            .map(|_| true)
-            .map_err(|e| format!("Failed to export backup: {}", e))
+            .get_firmware_history(&serial)
+            .map_err(|e| format!("Failed to get firmware history: {}", e))
     }
 
     #[tauri::command]
-    pub async fn import_backup(src_path: String, state: State<'_, AppState>) -> Result<String, String> {
-        let path = std::path::PathBuf::from(&src_path);
-        log::info!("Importing database backup from: {}", src_path);
+    pub async fn update_drone_profile(
+        serial: String,
+        nickname: Option<String>,
+        purchase_date: Option<String>,
+        notes: Option<String>,
+        registration: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        log::info!("Updating profile of drone {}", serial);
+
         state
             .db
-            .import_backup(&path)
-            .map_err(|e| format!("Failed to import backup: {}", e))
+            .set_drone_profile(
+                &serial,
+                nickname.as_deref(),
+                purchase_date.as_deref(),
+                notes.as_deref(),
+                registration.as_deref(),
+            )
+            .map(|_| true)
+            .map_err(|e| format!("Failed to update drone profile: {}", e))
     }
 
     #[tauri::command]
-    pub async fn add_flight_tag(flight_id: i64, tag: String, state: State<'_, AppState>) -> Result<Vec<FlightTag>, String> {
-        state
-            .db
-            .add_flight_tag(flight_id, &tag)
-            .map_err(|e| format!("Failed to add tag: {}", e))?;
+    pub async fn add_flight_annotation(
+        flight_id: i64,
+        offset_secs: f64,
+        text: String,
+        state: State<'_, AppState>,
+    ) -> Result<FlightAnnotation, String> {
+        if text.trim().is_empty() {
+            return Err("Annotation text is empty".to_string());
+        }
         state
             .db
-            .get_flight_tags(flight_id)
-            .map_err(|e| format!("Failed to get tags: {}", e))
+            .add_flight_annotation(flight_id, offset_secs, &text)
+            .map_err(|e| format!("Failed to add annotation: {}", e))
     }
 
     #[tauri::command]
-    pub async fn remove_flight_tag(flight_id: i64, tag: String, state: State<'_, AppState>) -> Result<Vec<FlightTag>, String> {
+    pub async fn update_flight_annotation(
+        annotation_id: i64,
+        text: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        if text.trim().is_empty() {
+            return Err("Annotation text is empty".to_string());
+        }
         state
             .db
-            .remove_flight_tag(flight_id, &tag)
-            .map_err(|e| format!("Failed to remove tag: {}", e))?;
+            .update_flight_annotation(annotation_id, &text)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to update annotation: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_flight_annotation(annotation_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
         state
             .db
-            .get_flight_tags(flight_id)
-            .map_err(|e| format!("Failed to get tags: {}", e))
+            .delete_flight_annotation(annotation_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete annotation: {}", e))
     }
 
     #[tauri::command]
-    pub async fn get_all_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    pub async fn add_incident(
+        flight_id: i64,
+        offset_secs: f64,
+        kind: String,
+        severity: String,
+        description: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Incident, String> {
         state
             .db
-            .get_all_unique_tags()
-            .map_err(|e| format!("Failed to get tags: {}", e))
+            .add_incident(flight_id, offset_secs, &kind, &severity, description.as_deref())
+            .map_err(|e| format!("Failed to add incident: {}", e))
     }
 
     #[tauri::command]
-    pub async fn remove_all_auto_tags(state: State<'_, AppState>) -> Result<usize, String> {
+    pub async fn get_incidents(
+        flight_id: Option<i64>,
+        kind: Option<String>,
+        severity: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<Incident>, String> {
         state
             .db
-            .remove_all_auto_tags()
-            .map_err(|e| format!("Failed to remove auto tags: {}", e))
+            .get_incidents(flight_id, kind.as_deref(), severity.as_deref())
+            .map_err(|e| format!("Failed to get incidents: {}", e))
     }
 
     #[tauri::command]
-    pub async fn get_smart_tags_enabled(state: State<'_, AppState>) -> Result<bool, String> {
-        let config_path = state.db.data_dir.join("config.json");
+    pub async fn update_incident(
+        incident_id: i64,
+        kind: String,
+        severity: String,
+        description: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        state
+            .db
+            .update_incident(incident_id, &kind, &severity, description.as_deref())
+            .map(|_| true)
+            .map_err(|e| format!("Failed to update incident: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_incident(incident_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_incident(incident_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete incident: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_equipment(state: State<'_, AppState>) -> Result<Vec<Equipment>, String> {
+        state
+            .db
+            .get_equipment()
+            .map_err(|e| format!("Failed to get equipment: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn save_equipment(
+        id: Option<i64>,
+        name: String,
+        category: String,
+        serial: Option<String>,
+        notes: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Equipment, String> {
+        state
+            .db
+            .save_equipment(id, &name, &category, serial.as_deref(), notes.as_deref())
+            .map_err(|e| format!("Failed to save equipment: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_equipment(equipment_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_equipment(equipment_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete equipment: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_flight_equipment(flight_id: i64, state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+        state
+            .db
+            .get_flight_equipment(flight_id)
+            .map_err(|e| format!("Failed to get flight equipment: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn set_flight_equipment(
+        flight_id: i64,
+        equipment_ids: Vec<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        state
+            .db
+            .set_flight_equipment(flight_id, &equipment_ids)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to set flight equipment: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_equipment_flight_ids(equipment_id: i64, state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+        state
+            .db
+            .get_equipment_flight_ids(equipment_id)
+            .map_err(|e| format!("Failed to get equipment flights: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_checklist_templates(state: State<'_, AppState>) -> Result<Vec<ChecklistTemplate>, String> {
+        state
+            .db
+            .get_checklist_templates()
+            .map_err(|e| format!("Failed to get checklist templates: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn save_checklist_template(
+        id: Option<i64>,
+        name: String,
+        items: Vec<String>,
+        state: State<'_, AppState>,
+    ) -> Result<ChecklistTemplate, String> {
+        state
+            .db
+            .save_checklist_template(id, &name, &items)
+            .map_err(|e| format!("Failed to save checklist template: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_checklist_template(template_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_checklist_template(template_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete checklist template: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn record_flight_checklist(
+        flight_id: i64,
+        template_id: Option<i64>,
+        name: String,
+        items: Vec<ChecklistItem>,
+        completed_by: Option<String>,
+        notes: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<FlightChecklist, String> {
+        state
+            .db
+            .record_flight_checklist(flight_id, template_id, &name, &items, completed_by.as_deref(), notes.as_deref())
+            .map_err(|e| format!("Failed to record checklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_flight_checklists(flight_id: i64, state: State<'_, AppState>) -> Result<Vec<FlightChecklist>, String> {
+        state
+            .db
+            .get_flight_checklists(flight_id)
+            .map_err(|e| format!("Failed to get flight checklists: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_flight_checklist(checklist_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_flight_checklist(checklist_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete checklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn add_maintenance_record(
+        target_type: String,
+        serial: String,
+        kind: String,
+        performed_at: Option<String>,
+        description: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<MaintenanceRecord, String> {
+        state
+            .db
+            .add_maintenance_record(&target_type, &serial, &kind, performed_at.as_deref(), description.as_deref())
+            .map_err(|e| format!("Failed to add maintenance record: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_maintenance_records(
+        target_type: Option<String>,
+        serial: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<MaintenanceRecord>, String> {
+        state
+            .db
+            .get_maintenance_records(target_type.as_deref(), serial.as_deref())
+            .map_err(|e| format!("Failed to get maintenance records: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_maintenance_record(record_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_maintenance_record(record_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete maintenance record: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_maintenance_status(state: State<'_, AppState>) -> Result<Vec<MaintenanceStatus>, String> {
+        state
+            .db
+            .get_maintenance_status()
+            .map_err(|e| format!("Failed to get maintenance status: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn has_api_key(state: State<'_, AppState>) -> Result<bool, String> {
+        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
+        Ok(api.has_api_key())
+    }
+
+    #[tauri::command]
+    pub async fn get_api_key_type(state: State<'_, AppState>) -> Result<String, String> {
+        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
+        Ok(api.get_api_key_type())
+    }
+
+    #[tauri::command]
+    pub async fn set_api_key(api_key: String, state: State<'_, AppState>) -> Result<bool, String> {
+        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
+        api.save_api_key(&api_key)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to save API key: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn remove_api_key(state: State<'_, AppState>) -> Result<bool, String> {
+        let api = DjiApi::with_app_data_dir(state.db.data_dir.clone());
+        api.remove_api_key()
+            .map(|_| true)
+            .map_err(|e| format!("Failed to remove API key: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_app_data_dir(state: State<'_, AppState>) -> Result<String, String> {
+        Ok(state.db.data_dir.to_string_lossy().to_string())
+    }
+
+    #[tauri::command]
+    pub async fn get_app_log_dir(app: AppHandle) -> Result<String, String> {
+        app.path()
+            .app_log_dir()
+            .map_err(|e| format!("Failed to get app log directory: {}", e))
+            .map(|dir| dir.to_string_lossy().to_string())
+    }
+
+    #[tauri::command]
+    pub async fn export_backup(dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
+        let path = std::path::PathBuf::from(&dest_path);
+        log::info!("Exporting database backup to: {}", dest_path);
+        state
+            .db
+            .export_backup(&path)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to export backup: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn import_backup(src_path: String, state: State<'_, AppState>) -> Result<String, String> {
+        let path = std::path::PathBuf::from(&src_path);
+        log::info!("Importing database backup from: {}", src_path);
+        state
+            .db
+            .import_backup(&path)
+            .map_err(|e| format!("Failed to import backup: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn export_archive(dest_path: String, state: State<'_, AppState>) -> Result<i64, String> {
+        log::info!("Exporting logbook archive to: {}", dest_path);
+        state
+            .db
+            .export_archive(std::path::Path::new(&dest_path))
+            .map_err(|e| format!("Failed to export archive: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn import_archive(src_path: String, state: State<'_, AppState>) -> Result<String, String> {
+        log::info!("Importing logbook archive from: {}", src_path);
+        state
+            .db
+            .import_archive(std::path::Path::new(&src_path))
+            .map_err(|e| format!("Failed to import archive: {}", e))
+    }
+
+    /// Conflict copies of the open library left by a folder sync tool
+    #[tauri::command]
+    pub async fn get_conflict_copies(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+        Ok(crate::library::conflict_copies(&state.db)
+            .into_iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Merge a diverged copy of the open library into it
+    #[tauri::command]
+    pub async fn reconcile_library(path: String, state: State<'_, AppState>) -> Result<String, String> {
+        log::info!("Reconciling library with: {}", path);
+        state
+            .db
+            .reconcile_library(std::path::Path::new(&path))
+            .map_err(|e| format!("Failed to reconcile library: {}", e))
+    }
+
+    /// Check the library for damage and inconsistencies, repairing them
+    /// unless this is a dry run
+    #[tauri::command]
+    pub async fn check_library(repair: Option<bool>, state: State<'_, AppState>) -> Result<LibraryCheckReport, String> {
+        state
+            .db
+            .check_library(repair.unwrap_or(false))
+            .map_err(|e| format!("Library check failed: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn backup_database(dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
+        log::info!("Writing database snapshot to: {}", dest_path);
+        state
+            .db
+            .backup_database(std::path::Path::new(&dest_path))
+            .map(|_| true)
+            .map_err(|e| format!("Failed to back up database: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn restore_database(src_path: String, state: State<'_, AppState>) -> Result<bool, String> {
+        log::warn!("Replacing database with snapshot: {}", src_path);
+        state
+            .db
+            .restore_database(std::path::Path::new(&src_path))
+            .map(|_| true)
+            .map_err(|e| format!("Failed to restore database: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_libraries(state: State<'_, AppState>) -> Result<Vec<Library>, String> {
+        Ok(crate::library::list_libraries(&state.db))
+    }
+
+    /// Open another library by name or database file path, creating it if
+    /// needed
+    #[tauri::command]
+    pub async fn switch_library(
+        library: String,
+        name: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Library, String> {
+        crate::library::switch_library(&state.db, &library, name.as_deref())
+            .map_err(|e| format!("Failed to switch library: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn forget_library(path: String, state: State<'_, AppState>) -> Result<bool, String> {
+        crate::library::forget_library(&state.db, &path)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to remove library: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_auto_backup_enabled(state: State<'_, AppState>) -> Result<bool, String> {
+        let config_path = state.db.data_dir.join("config.json");
+        if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path)
+                .map_err(|e| format!("Failed to read config: {}", e))?;
+            let val: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse config: {}", e))?;
+            Ok(val.get("auto_backup_enabled").and_then(|v| v.as_bool()).unwrap_or(false))
+        } else {
+            Ok(false)
+        }
+    }
+
+    #[tauri::command]
+    pub async fn set_auto_backup_enabled(enabled: bool, state: State<'_, AppState>) -> Result<bool, String> {
+        let config_path = state.db.data_dir.join("config.json");
+        let mut config: serde_json::Value = if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+        } else {
+            serde_json::json!({})
+        };
+        config["auto_backup_enabled"] = serde_json::json!(enabled);
+        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+        Ok(enabled)
+    }
+
+    /// Write all cached V13+ keychains to a bundle file for an offline machine
+    #[tauri::command]
+    pub async fn export_keychains(dest_path: String, state: State<'_, AppState>) -> Result<usize, String> {
+        let cached = state
+            .db
+            .get_all_cached_keychains()
+            .map_err(|e| format!("Failed to read keychain cache: {}", e))?;
+        let bundle = crate::api::KeychainBundle::new(cached);
+        let json = bundle.to_json().map_err(|e| format!("Failed to export keychains: {}", e))?;
+        std::fs::write(&dest_path, json).map_err(|e| format!("Failed to write keychain file: {}", e))?;
+        log::info!("Exported {} keychains to {}", bundle.entries.len(), dest_path);
+        Ok(bundle.entries.len())
+    }
+
+    /// Load a keychain bundle into the cache so V13+ logs decrypt without network access
+    #[tauri::command]
+    pub async fn import_keychains(src_path: String, state: State<'_, AppState>) -> Result<usize, String> {
+        let content = std::fs::read_to_string(&src_path)
+            .map_err(|e| format!("Failed to read keychain file: {}", e))?;
+        let entries = crate::api::KeychainBundle::from_json(&content)
+            .map_err(|e| e.to_string())?
+            .into_cache_entries();
+        for (file_hash, keychains) in &entries {
+            state
+                .db
+                .cache_keychains(file_hash, keychains)
+                .map_err(|e| format!("Failed to store keychains: {}", e))?;
+        }
+        log::info!("Imported {} keychains from {}", entries.len(), src_path);
+        Ok(entries.len())
+    }
+
+    /// Import a DJI Fly waypoint mission (.kmz) and link it to the flights that flew it
+    #[tauri::command]
+    pub async fn import_mission(file_path: String, state: State<'_, AppState>) -> Result<Mission, String> {
+        let path = PathBuf::from(&file_path);
+        let data = std::fs::read(&path).map_err(|e| format!("Failed to read mission file: {}", e))?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.clone());
+
+        let mission = MissionParser::parse(&data, &file_name).map_err(|e| format!("Failed to parse mission: {}", e))?;
+        if let Some(existing) = state
+            .db
+            .is_mission_imported(&mission.file_hash)
+            .map_err(|e| format!("Failed to check mission: {}", e))?
+        {
+            return Err(format!("This mission has already been imported (matches: {})", existing));
+        }
+
+        let mission_id = state
+            .db
+            .insert_mission(&mission)
+            .map_err(|e| format!("Failed to save mission: {}", e))?;
+        state
+            .db
+            .link_mission_flights(mission_id)
+            .map_err(|e| format!("Failed to link mission to flights: {}", e))?;
+
+        state
+            .db
+            .get_missions()
+            .map_err(|e| format!("Failed to get missions: {}", e))?
+            .into_iter()
+            .find(|m| m.id == mission_id)
+            .ok_or_else(|| "Mission disappeared after import".to_string())
+    }
+
+    #[tauri::command]
+    pub async fn get_missions(state: State<'_, AppState>) -> Result<Vec<Mission>, String> {
+        state
+            .db
+            .get_missions()
+            .map_err(|e| format!("Failed to get missions: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_mission(mission_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_mission(mission_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete mission: {}", e))
+    }
+
+    /// Re-match all missions against the flight library (after importing new flights)
+    #[tauri::command]
+    pub async fn relink_missions(state: State<'_, AppState>) -> Result<usize, String> {
+        state
+            .db
+            .relink_all_missions()
+            .map_err(|e| format!("Failed to link missions: {}", e))
+    }
+
+    /// Scan a folder of photos/videos and associate them with flights by capture time
+    #[tauri::command]
+    pub async fn scan_media_folder(folder_path: String, state: State<'_, AppState>) -> Result<MediaScanResult, String> {
+        let folder = PathBuf::from(&folder_path);
+        if !folder.is_dir() {
+            return Err(format!("Folder does not exist: {}", folder_path));
+        }
+
+        let files = tokio::task::spawn_blocking(move || MediaScanner::scan_folder(&folder))
+            .await
+            .map_err(|e| format!("Media scan failed: {}", e))?;
+        let matched = state
+            .db
+            .link_media_files(&files)
+            .map_err(|e| format!("Failed to associate media: {}", e))?;
+
+        Ok(MediaScanResult {
+            scanned: files.len(),
+            timestamped: files.iter().filter(|f| f.captured_at.is_some()).count(),
+            matched,
+        })
+    }
+
+    #[tauri::command]
+    pub async fn get_flight_media(flight_id: i64, state: State<'_, AppState>) -> Result<Vec<FlightMedia>, String> {
+        state
+            .db
+            .get_flight_media(flight_id)
+            .map_err(|e| format!("Failed to get flight media: {}", e))
+    }
+
+    /// Attach a file on disk to a flight
+    #[tauri::command]
+    pub async fn add_flight_document(
+        flight_id: i64,
+        file_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<FlightDocument, String> {
+        let path = PathBuf::from(&file_path);
+        let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        state
+            .db
+            .add_flight_document(flight_id, &file_name, &data)
+            .map_err(|e| format!("Failed to attach document: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_flight_documents(flight_id: i64, state: State<'_, AppState>) -> Result<Vec<FlightDocument>, String> {
+        state
+            .db
+            .get_flight_documents(flight_id)
+            .map_err(|e| format!("Failed to get flight documents: {}", e))
+    }
+
+    /// Save a copy of an attached document to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_document(
+        document_id: i64,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let (_, path) = state
+            .db
+            .flight_document_file(document_id)
+            .map_err(|e| format!("Failed to get document: {}", e))?;
+        std::fs::copy(&path, &dest_path).map_err(|e| format!("Failed to save document: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write a flight as a GPX track to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_gpx(
+        flight_id: i64,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let gpx = crate::export::flight_gpx(&state.db, flight_id).map_err(|e| format!("Failed to export GPX: {}", e))?;
+        std::fs::write(&dest_path, gpx).map_err(|e| format!("Failed to write GPX file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write a flight as KML to `dest_path`, zipped as KMZ when `kmz` is set.
+    /// `tour` adds a flythrough along the path.
+    #[tauri::command]
+    pub async fn export_flight_kml(
+        flight_id: i64,
+        dest_path: String,
+        tour: Option<bool>,
+        kmz: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let tour = tour.unwrap_or(false);
+        let data = if kmz.unwrap_or(false) {
+            crate::export::flight_kmz(&state.db, flight_id, tour)
+        } else {
+            crate::export::flight_kml(&state.db, flight_id, tour).map(String::into_bytes)
+        }
+        .map_err(|e| format!("Failed to export KML: {}", e))?;
+        std::fs::write(&dest_path, data).map_err(|e| format!("Failed to write KML file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write the telemetry `columns` of a flight (default all) as CSV to
+    /// `dest_path`, in SI units unless `units` says otherwise
+    #[tauri::command]
+    pub async fn export_flight_csv(
+        flight_id: i64,
+        dest_path: String,
+        columns: Option<Vec<String>>,
+        units: Option<crate::export::ExportUnits>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let csv = crate::export::flight_csv(&state.db, flight_id, &columns.unwrap_or_default(), &units.unwrap_or_default())
+            .map_err(|e| format!("Failed to export CSV: {}", e))?;
+        std::fs::write(&dest_path, csv).map_err(|e| format!("Failed to write CSV file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write a flight's track and events as GeoJSON to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_geojson(
+        flight_id: i64,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let geojson =
+            crate::export::flight_geojson(&state.db, flight_id).map_err(|e| format!("Failed to export GeoJSON: {}", e))?;
+        std::fs::write(&dest_path, geojson).map_err(|e| format!("Failed to write GeoJSON file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Export every flight matching `filter` to `dest_dir`, one file per
+    /// flight plus summary.csv. An `export-progress` event is emitted after
+    /// each flight.
+    #[tauri::command]
+    pub async fn export_flights_batch(
+        filter: FlightFilter,
+        format: crate::export::ExportFormat,
+        dest_dir: String,
+        columns: Option<Vec<String>>,
+        units: Option<crate::export::ExportUnits>,
+        app: AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<BatchExportResult, String> {
+        use tauri::Emitter;
+
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || {
+            crate::export::export_batch(
+                &db,
+                &filter,
+                format,
+                &columns.unwrap_or_default(),
+                &units.unwrap_or_default(),
+                std::path::Path::new(&dest_dir),
+                |progress| {
+                    if let Err(e) = app.emit("export-progress", progress) {
+                        log::warn!("Failed to emit export progress: {}", e);
+                    }
+                },
+            )
+        })
+        .await
+        .map_err(|e| format!("Batch export failed: {}", e))?
+        .map_err(|e| format!("Batch export failed: {}", e))
+    }
+
+    /// Write an Excel workbook of every flight matching `filter` to
+    /// `dest_path`, with a telemetry sheet per flight if `telemetry` is set
+    #[tauri::command]
+    pub async fn export_flights_xlsx(
+        filter: FlightFilter,
+        dest_path: String,
+        telemetry: Option<bool>,
+        units: Option<crate::export::ExportUnits>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db.clone();
+        let workbook = tokio::task::spawn_blocking(move || {
+            crate::export::flights_xlsx(&db, &filter, telemetry.unwrap_or(false), &units.unwrap_or_default())
+        })
+        .await
+        .map_err(|e| format!("Failed to export workbook: {}", e))?
+        .map_err(|e| format!("Failed to export workbook: {}", e))?;
+        std::fs::write(&dest_path, workbook).map_err(|e| format!("Failed to write workbook: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write the telemetry of one flight, or of every flight matching
+    /// `filter` (the whole library without one), to `dest_path` as Parquet
+    #[tauri::command]
+    pub async fn export_telemetry_parquet(
+        flight_id: Option<i64>,
+        filter: Option<FlightFilter>,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<usize, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || {
+            let flight_ids = match flight_id {
+                Some(flight_id) => vec![flight_id],
+                None => db.evaluate_flight_filter(&filter.unwrap_or_default())?,
+            };
+            db.export_telemetry_parquet(&flight_ids, std::path::Path::new(&dest_path))
+        })
+        .await
+        .map_err(|e| format!("Failed to export Parquet: {}", e))?
+        .map_err(|e| format!("Failed to export Parquet: {}", e))
+    }
+
+    /// Write the tracks of every flight matching `filter` to `dest_path` as
+    /// one KML or GeoJSON coverage layer
+    #[tauri::command]
+    pub async fn export_coverage(
+        filter: FlightFilter,
+        format: crate::coverage::CoverageFormat,
+        options: Option<crate::coverage::CoverageOptions>,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db.clone();
+        let coverage = tokio::task::spawn_blocking(move || {
+            crate::coverage::coverage(&db, &filter, format, &options.unwrap_or_default())
+        })
+        .await
+        .map_err(|e| format!("Failed to export coverage: {}", e))?
+        .map_err(|e| format!("Failed to export coverage: {}", e))?;
+        std::fs::write(&dest_path, coverage).map_err(|e| format!("Failed to write coverage file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write the operator logbook of every flight matching `filter` to
+    /// `dest_path` as CSV or PDF
+    #[tauri::command]
+    pub async fn export_logbook(
+        filter: FlightFilter,
+        format: crate::logbook::LogbookFormat,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db.clone();
+        let logbook = tokio::task::spawn_blocking(move || crate::logbook::logbook(&db, &filter, format))
+            .await
+            .map_err(|e| format!("Failed to export logbook: {}", e))?
+            .map_err(|e| format!("Failed to export logbook: {}", e))?;
+        std::fs::write(&dest_path, logbook).map_err(|e| format!("Failed to write logbook: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write telemetry on the video's time axis to `dest_path` as CSV, for
+    /// video overlay tools
+    #[tauri::command]
+    pub async fn export_flight_overlay(
+        flight_id: i64,
+        dest_path: String,
+        options: Option<crate::export::OverlayOptions>,
+        units: Option<crate::export::ExportUnits>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let csv = crate::export::flight_overlay_csv(
+            &state.db,
+            flight_id,
+            &options.unwrap_or_default(),
+            &units.unwrap_or_default(),
+        )
+        .map_err(|e| format!("Failed to export overlay data: {}", e))?;
+        std::fs::write(&dest_path, csv).map_err(|e| format!("Failed to write overlay file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write DJI-style subtitles for the `recording`-th video of a flight
+    /// (default the first) to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_srt(
+        flight_id: i64,
+        dest_path: String,
+        recording: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let srt = crate::export::flight_srt(&state.db, flight_id, recording.unwrap_or(0))
+            .map_err(|e| format!("Failed to generate subtitles: {}", e))?;
+        std::fs::write(&dest_path, srt).map_err(|e| format!("Failed to write SRT file: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write a flight's telemetry to `dest_path` as a MAVLink tlog
+    #[tauri::command]
+    pub async fn export_flight_tlog(flight_id: i64, dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
+        let db = state.db.clone();
+        let tlog = tokio::task::spawn_blocking(move || crate::export::flight_tlog(&db, flight_id))
+            .await
+            .map_err(|e| format!("Failed to export tlog: {}", e))?
+            .map_err(|e| format!("Failed to export tlog: {}", e))?;
+        std::fs::write(&dest_path, tlog).map_err(|e| format!("Failed to write tlog: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write the library's aggregate statistics to `dest_path` as JSON
+    #[tauri::command]
+    pub async fn export_library_stats(dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
+        let db = state.db.clone();
+        let json = tokio::task::spawn_blocking(move || crate::export::library_stats_json(&db))
+            .await
+            .map_err(|e| format!("Failed to export statistics: {}", e))?
+            .map_err(|e| format!("Failed to export statistics: {}", e))?;
+        std::fs::write(&dest_path, json).map_err(|e| format!("Failed to write statistics: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write a PDF report of a flight to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_report(
+        flight_id: i64,
+        dest_path: String,
+        units: Option<crate::export::ExportUnits>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db.clone();
+        let pdf = tokio::task::spawn_blocking(move || {
+            crate::report::flight_report_pdf(&db, flight_id, &units.unwrap_or_default())
+        })
+        .await
+        .map_err(|e| format!("Failed to create report: {}", e))?
+        .map_err(|e| format!("Failed to create report: {}", e))?;
+        std::fs::write(&dest_path, pdf).map_err(|e| format!("Failed to write report: {}", e))?;
+        Ok(true)
+    }
+
+    #[tauri::command]
+    pub async fn delete_flight_document(document_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_flight_document(document_id)
+            .map_err(|e| format!("Failed to delete document: {}", e))?;
+        Ok(true)
+    }
+
+    #[tauri::command]
+    pub async fn get_warning_summary(state: State<'_, AppState>) -> Result<Vec<WarningSummary>, String> {
+        state
+            .db
+            .get_warning_summary()
+            .map_err(|e| format!("Failed to get warning summary: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn search_flights(
+        query: String,
+        limit: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<FlightSearchHit>, String> {
+        state
+            .db
+            .search_flights(&query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .map_err(|e| format!("Failed to search flights: {}", e))
+    }
+
+    /// Audit log entries, newest first, optionally only those of one flight
+    #[tauri::command]
+    pub async fn get_audit_log(
+        flight_id: Option<i64>,
+        limit: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<AuditEntry>, String> {
+        state
+            .db
+            .get_audit_log(flight_id, limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+            .map_err(|e| format!("Failed to get audit log: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_smart_lists(state: State<'_, AppState>) -> Result<Vec<SmartList>, String> {
+        state
+            .db
+            .get_smart_lists()
+            .map_err(|e| format!("Failed to get smart lists: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn save_smart_list(
+        id: Option<i64>,
+        name: String,
+        filter: FlightFilter,
+        state: State<'_, AppState>,
+    ) -> Result<SmartList, String> {
+        state
+            .db
+            .save_smart_list(id, &name, &filter)
+            .map_err(|e| format!("Failed to save smart list: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_smart_list(list_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_smart_list(list_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete smart list: {}", e))
+    }
+
+    /// IDs of the flights in a saved smart list, newest first
+    #[tauri::command]
+    pub async fn evaluate_smart_list(list_id: i64, state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+        state
+            .db
+            .evaluate_smart_list(list_id)
+            .map_err(|e| format!("Failed to evaluate smart list: {}", e))
+    }
+
+    /// IDs of the flights matching an unsaved filter, newest first
+    #[tauri::command]
+    pub async fn evaluate_flight_filter(filter: FlightFilter, state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+        state
+            .db
+            .evaluate_flight_filter(&filter)
+            .map_err(|e| format!("Failed to evaluate filter: {}", e))
+    }
+
+    /// Telemetry columns an export template can select
+    #[tauri::command]
+    pub async fn get_telemetry_columns(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+        state
+            .db
+            .get_telemetry_columns()
+            .map_err(|e| format!("Failed to get columns: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_export_templates(state: State<'_, AppState>) -> Result<Vec<ExportTemplate>, String> {
+        state
+            .db
+            .get_export_templates()
+            .map_err(|e| format!("Failed to get export templates: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn save_export_template(
+        id: Option<i64>,
+        name: String,
+        format: crate::export::ExportFormat,
+        columns: Option<Vec<String>>,
+        units: Option<crate::export::ExportUnits>,
+        state: State<'_, AppState>,
+    ) -> Result<ExportTemplate, String> {
+        state
+            .db
+            .save_export_template(id, &name, format, &columns.unwrap_or_default(), &units.unwrap_or_default())
+            .map_err(|e| format!("Failed to save export template: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_export_template(template_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db
+            .delete_export_template(template_id)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete export template: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn add_flight_tag(flight_id: i64, tag: String, state: State<'_, AppState>) -> Result<Vec<FlightTag>, String> {
+        state
+            .db
+            .add_flight_tag(flight_id, &tag)
+            .map_err(|e| format!("Failed to add tag: {}", e))?;
+        state
+            .db
+            .get_flight_tags(flight_id)
+            .map_err(|e| format!("Failed to get tags: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn remove_flight_tag(flight_id: i64, tag: String, state: State<'_, AppState>) -> Result<Vec<FlightTag>, String> {
+        state
+            .db
+            .remove_flight_tag(flight_id, &tag)
+            .map_err(|e| format!("Failed to remove tag: {}", e))?;
+        state
+            .db
+            .get_flight_tags(flight_id)
+            .map_err(|e| format!("Failed to get tags: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_all_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+        state
+            .db
+            .get_all_unique_tags()
+            .map_err(|e| format!("Failed to get tags: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn remove_all_auto_tags(state: State<'_, AppState>) -> Result<usize, String> {
+        state
+            .db
+            .remove_all_auto_tags()
+            .map_err(|e| format!("Failed to remove auto tags: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_smart_tags_enabled(state: State<'_, AppState>) -> Result<bool, String> {
+        let config_path = state.db.data_dir.join("config.json");
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path)
                 .map_err(|e| format!("Failed to read config: {}", e))?;
@@ -572,6 +2072,217 @@ mod tauri_app {
         Ok(enabled)
     }
 
+    #[tauri::command]
+    pub async fn get_geocode_provider(state: State<'_, AppState>) -> Result<String, String> {
+        Ok(GeocodeProvider::from_config(&state.db.data_dir).as_str().to_string())
+    }
+
+    #[tauri::command]
+    pub async fn set_geocode_provider(provider: String, state: State<'_, AppState>) -> Result<String, String> {
+        let provider = GeocodeProvider::parse(&provider)
+            .ok_or_else(|| format!("Unknown geocoding provider: {}", provider))?;
+        let config_path = state.db.data_dir.join("config.json");
+        let mut config: serde_json::Value = if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+        } else {
+            serde_json::json!({})
+        };
+        config["geocode_provider"] = serde_json::json!(provider.as_str());
+        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+        Ok(provider.as_str().to_string())
+    }
+
+    #[tauri::command]
+    pub async fn get_pilot_details(state: State<'_, AppState>) -> Result<PilotDetails, String> {
+        Ok(crate::report::pilot_details(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn set_pilot_details(pilot: PilotDetails, state: State<'_, AppState>) -> Result<PilotDetails, String> {
+        crate::report::save_pilot_details(&state.db.data_dir, &pilot)
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+        Ok(crate::report::pilot_details(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn get_currency_requirements(state: State<'_, AppState>) -> Result<Vec<CurrencyRequirement>, String> {
+        Ok(crate::currency::requirements(&state.db.data_dir))
+    }
+
+    /// Save the recency requirements checked by `get_currency`
+    #[tauri::command]
+    pub async fn set_currency_requirements(
+        requirements: Vec<CurrencyRequirement>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<CurrencyRequirement>, String> {
+        crate::currency::save_requirements(&state.db.data_dir, &requirements)
+            .map_err(|e| format!("Failed to save currency requirements: {}", e))?;
+        Ok(crate::currency::requirements(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn get_compliance_limits(state: State<'_, AppState>) -> Result<ComplianceLimits, String> {
+        Ok(crate::compliance::limits(&state.db.data_dir))
+    }
+
+    /// Save the height and distance limits checked by `get_compliance_report`
+    #[tauri::command]
+    pub async fn set_compliance_limits(
+        limits: ComplianceLimits,
+        state: State<'_, AppState>,
+    ) -> Result<ComplianceLimits, String> {
+        crate::compliance::save_limits(&state.db.data_dir, &limits)
+            .map_err(|e| format!("Failed to save compliance limits: {}", e))?;
+        Ok(crate::compliance::limits(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn get_terrain_settings(state: State<'_, AppState>) -> Result<TerrainSettings, String> {
+        Ok(crate::terrain::settings(&state.db.data_dir))
+    }
+
+    /// Save where ground elevations for the height above terrain come from
+    #[tauri::command]
+    pub async fn set_terrain_settings(
+        settings: TerrainSettings,
+        state: State<'_, AppState>,
+    ) -> Result<TerrainSettings, String> {
+        crate::terrain::save_settings(&state.db.data_dir, &settings)
+            .map_err(|e| format!("Failed to save terrain settings: {}", e))?;
+        Ok(crate::terrain::settings(&state.db.data_dir))
+    }
+
+    /// Ground elevation and height above ground along a flight, aligned to
+    /// its telemetry at `max_points`; None while no terrain provider is set
+    #[tauri::command]
+    pub async fn get_flight_terrain(
+        flight_id: i64,
+        max_points: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Option<TerrainProfile>, String> {
+        crate::terrain::flight_terrain(&state.db, flight_id, max_points)
+            .await
+            .map_err(|e| format!("Failed to look up the terrain: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_track_smoothing(state: State<'_, AppState>) -> Result<TrackSmoothing, String> {
+        Ok(crate::track_smoothing::method(&state.db.data_dir))
+    }
+
+    /// Save the smoothing applied to the map track of opened flights
+    #[tauri::command]
+    pub async fn set_track_smoothing(method: TrackSmoothing, state: State<'_, AppState>) -> Result<TrackSmoothing, String> {
+        crate::track_smoothing::save_method(&state.db.data_dir, method)
+            .map_err(|e| format!("Failed to save track smoothing: {}", e))?;
+        Ok(crate::track_smoothing::method(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn get_auto_export_settings(state: State<'_, AppState>) -> Result<AutoExportSettings, String> {
+        Ok(crate::auto_export::settings(&state.db.data_dir))
+    }
+
+    /// Save where and in which formats newly imported flights are copied
+    #[tauri::command]
+    pub async fn set_auto_export_settings(
+        settings: AutoExportSettings,
+        state: State<'_, AppState>,
+    ) -> Result<AutoExportSettings, String> {
+        crate::auto_export::save_settings(&state.db.data_dir, &settings)
+            .map_err(|e| format!("Failed to save auto-export settings: {}", e))?;
+        Ok(crate::auto_export::settings(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn get_telemetry_storage(state: State<'_, AppState>) -> Result<String, String> {
+        Ok(if state.db.packed_storage_enabled() { "packed" } else { "rows" }.to_string())
+    }
+
+    /// Switch between row and packed telemetry storage and convert every
+    /// flight. Returns the number of flights converted.
+    #[tauri::command]
+    pub async fn set_telemetry_storage(mode: String, state: State<'_, AppState>) -> Result<usize, String> {
+        let packed = match mode.as_str() {
+            "rows" => false,
+            "packed" => true,
+            other => return Err(format!("Unknown telemetry storage mode: {}", other)),
+        };
+        let config_path = state.db.data_dir.join("config.json");
+        let mut config: serde_json::Value = if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+        } else {
+            serde_json::json!({})
+        };
+        config["telemetry_storage"] = serde_json::json!(mode);
+        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+        state
+            .db
+            .convert_telemetry_storage(packed)
+            .map_err(|e| format!("Failed to convert telemetry storage: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_archived_flight_count(state: State<'_, AppState>) -> Result<i64, String> {
+        state
+            .db
+            .archived_flight_count()
+            .map_err(|e| format!("Failed to count archived flights: {}", e))
+    }
+
+    /// Move the telemetry of flights older than `years` to sidecar files.
+    /// Returns the number of flights archived.
+    #[tauri::command]
+    pub async fn archive_old_flights(years: u32, state: State<'_, AppState>) -> Result<usize, String> {
+        state
+            .db
+            .archive_old_flights(years)
+            .map_err(|e| format!("Failed to archive flights: {}", e))
+    }
+
+    /// Move all archived telemetry back into the database. Returns the number
+    /// of flights restored.
+    #[tauri::command]
+    pub async fn restore_archived_flights(state: State<'_, AppState>) -> Result<usize, String> {
+        state
+            .db
+            .restore_archived_flights()
+            .map_err(|e| format!("Failed to restore archived flights: {}", e))
+    }
+
+    /// Fill in the location of flights that have none yet, or of every
+    /// flight when `force` is set (e.g. after switching provider)
+    #[tauri::command]
+    pub async fn backfill_flight_locations(force: Option<bool>, state: State<'_, AppState>) -> Result<usize, String> {
+        crate::geocoder::locate_flights(&state.db, force.unwrap_or(false))
+            .await
+            .map_err(|e| format!("Failed to look up flight locations: {}", e))
+    }
+
+    /// Store the farthest distance from home of flights without one (every flight with `force`)
+    #[tauri::command]
+    pub async fn backfill_max_distance_from_home(force: Option<bool>, state: State<'_, AppState>) -> Result<usize, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || db.backfill_max_distance_from_home(force.unwrap_or(false)))
+            .await
+            .map_err(|e| format!("Failed to compute distances from home: {}", e))?
+            .map_err(|e| format!("Failed to compute distances from home: {}", e))
+    }
+
+    /// Recompute the cached per-flight statistics of every flight
+    #[tauri::command]
+    pub async fn rebuild_stats_cache(state: State<'_, AppState>) -> Result<usize, String> {
+        let db = state.db.clone();
+        tokio::task::spawn_blocking(move || db.refresh_flight_stats_cache(true))
+            .await
+            .map_err(|e| format!("Failed to rebuild the statistics cache: {}", e))?
+            .map_err(|e| format!("Failed to rebuild the statistics cache: {}", e))
+    }
+
     #[tauri::command]
     pub async fn get_enabled_tag_types(state: State<'_, AppState>) -> Result<Vec<String>, String> {
         let config_path = state.db.data_dir.join("config.json");
@@ -611,6 +2322,32 @@ mod tauri_app {
         Ok(types)
     }
 
+    #[tauri::command]
+    pub async fn get_watch_folders(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+        Ok(crate::watcher::read_watch_folders(&state.db.data_dir))
+    }
+
+    #[tauri::command]
+    pub async fn set_watch_folders(folders: Vec<String>, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+        for folder in &folders {
+            if !PathBuf::from(folder).is_dir() {
+                return Err(format!("Folder does not exist: {}", folder));
+            }
+        }
+        let config_path = state.db.data_dir.join("config.json");
+        let mut config: serde_json::Value = if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+        } else {
+            serde_json::json!({})
+        };
+        config["watch_folders"] = serde_json::json!(folders.clone());
+        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+        log::info!("Watched folders updated: {:?}", folders);
+        Ok(folders)
+    }
+
     #[tauri::command]
     pub async fn regenerate_flight_smart_tags(
         state: State<'_, AppState>,
@@ -646,6 +2383,10 @@ mod tauri_app {
             home_lat: flight.home_lat,
             home_lon: flight.home_lon,
             point_count: flight.point_count.unwrap_or(0),
+            mission_name: flight.mission_name.clone(),
+            partial: flight.partial,
+            firmware_version: flight.firmware_version.clone(),
+            max_distance_from_home: flight.max_distance_from_home,
         };
 
         match state.db.get_flight_telemetry(flight_id, Some(50000), None) {
@@ -712,6 +2453,10 @@ mod tauri_app {
                         home_lat: flight.home_lat,
                         home_lon: flight.home_lon,
                         point_count: flight.point_count.unwrap_or(0),
+                        mission_name: flight.mission_name.clone(),
+                        partial: flight.partial,
+                        firmware_version: flight.firmware_version.clone(),
+                        max_distance_from_home: flight.max_distance_from_home,
                     };
 
                     // Get raw telemetry to compute stats
@@ -770,20 +2515,117 @@ mod tauri_app {
             .setup(|app| {
                 let db = init_database(app.handle())?;
                 app.manage(AppState { db: Arc::new(db) });
+                crate::watcher::spawn(app.handle().clone());
                 log::info!("Drone Logbook initialized successfully");
                 Ok(())
             })
             .invoke_handler(tauri::generate_handler![
                 import_log,
+                import_zip,
+                import_logs_batch,
                 compute_file_hash,
+                get_import_blacklist,
+                add_to_import_blacklist,
+                remove_from_import_blacklist,
+                clear_import_blacklist,
                 get_flights,
                 get_flight_data,
                 get_overview_stats,
+                get_library_stats,
+                get_trends,
+                get_personal_records,
+                get_milestones,
+                compare_drones,
+                get_year_review,
+                export_year_review,
+                get_currency,
+                get_compliance_report,
+                get_energy_stats,
+                get_flight_mode_stats,
+                get_rth_stats,
+                get_flight_modes,
+                get_sites,
+                get_site_stats,
+                recluster_sites,
+                rename_site,
                 delete_flight,
                 delete_all_flights,
+                get_deleted_flights,
+                restore_flight,
+                purge_flight,
+                empty_trash,
                 deduplicate_flights,
+                get_stitch_candidates,
+                stitch_flights,
+                import_mission,
+                get_missions,
+                delete_mission,
+                relink_missions,
+                scan_media_folder,
+                get_flight_media,
+                add_flight_document,
+                get_flight_documents,
+                export_flight_document,
+                delete_flight_document,
+                export_flight_gpx,
+                export_flight_kml,
+                export_flight_csv,
+                export_flight_geojson,
+                export_flights_batch,
+                export_flight_report,
+                export_flight_tlog,
+                export_flight_overlay,
+                export_flight_srt,
+                export_flights_xlsx,
+                export_logbook,
+                export_coverage,
+                export_telemetry_parquet,
+                export_library_stats,
+                get_warning_summary,
+                search_flights,
+                get_audit_log,
+                get_smart_lists,
+                save_smart_list,
+                delete_smart_list,
+                evaluate_smart_list,
+                evaluate_flight_filter,
+                get_telemetry_columns,
+                get_export_templates,
+                save_export_template,
+                delete_export_template,
                 update_flight_name,
                 update_flight_notes,
+                set_flight_favorite,
+                set_flight_excluded_from_stats,
+                get_batteries,
+                get_battery_health,
+                get_discharge_curves,
+                get_drone_profiles,
+                get_firmware_history,
+                update_drone_profile,
+                add_flight_annotation,
+                update_flight_annotation,
+                delete_flight_annotation,
+                add_incident,
+                get_incidents,
+                update_incident,
+                delete_incident,
+                get_equipment,
+                save_equipment,
+                delete_equipment,
+                get_flight_equipment,
+                set_flight_equipment,
+                get_equipment_flight_ids,
+                get_checklist_templates,
+                save_checklist_template,
+                delete_checklist_template,
+                record_flight_checklist,
+                get_flight_checklists,
+                delete_flight_checklist,
+                add_maintenance_record,
+                get_maintenance_records,
+                delete_maintenance_record,
+                get_maintenance_status,
                 has_api_key,
                 get_api_key_type,
                 set_api_key,
@@ -792,14 +2634,53 @@ mod tauri_app {
                 get_app_log_dir,
                 export_backup,
                 import_backup,
+                export_archive,
+                import_archive,
+                get_conflict_copies,
+                reconcile_library,
+                check_library,
+                backup_database,
+                restore_database,
+                get_libraries,
+                switch_library,
+                forget_library,
+                get_auto_backup_enabled,
+                set_auto_backup_enabled,
+                export_keychains,
+                import_keychains,
                 add_flight_tag,
                 remove_flight_tag,
                 get_all_tags,
                 remove_all_auto_tags,
                 get_smart_tags_enabled,
                 set_smart_tags_enabled,
+                get_geocode_provider,
+                set_geocode_provider,
+                get_pilot_details,
+                set_pilot_details,
+                get_currency_requirements,
+                set_currency_requirements,
+                get_compliance_limits,
+                set_compliance_limits,
+                get_terrain_settings,
+                set_terrain_settings,
+                get_flight_terrain,
+                get_track_smoothing,
+                set_track_smoothing,
+                get_auto_export_settings,
+                set_auto_export_settings,
+                backfill_flight_locations,
+                backfill_max_distance_from_home,
+                rebuild_stats_cache,
+                get_telemetry_storage,
+                set_telemetry_storage,
+                get_archived_flight_count,
+                archive_old_flights,
+                restore_archived_flights,
                 get_enabled_tag_types,
                 set_enabled_tag_types,
+                get_watch_folders,
+                set_watch_folders,
                 regenerate_flight_smart_tags,
                 regenerate_all_smart_tags,
             ])
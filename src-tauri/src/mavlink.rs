@@ -0,0 +1,160 @@
+//! Minimal MAVLink v1 telemetry log (.tlog) writer.
+//!
+//! A tlog is a plain sequence of MAVLink packets, each preceded by the time
+//! it was received as a big-endian count of microseconds since the Unix
+//! epoch. Only the few common-dialect messages needed to replay a flight are
+//! supported; every packet is sent as system 1, the autopilot component.
+
+/// MAVLink v1 start-of-frame marker
+const STX_V1: u8 = 0xFE;
+const SYSTEM_ID: u8 = 1;
+/// MAV_COMP_ID_AUTOPILOT1
+const COMPONENT_ID: u8 = 1;
+
+/// MAV_TYPE_QUADROTOR
+const MAV_TYPE_QUADROTOR: u8 = 2;
+/// MAV_AUTOPILOT_GENERIC
+const MAV_AUTOPILOT_GENERIC: u8 = 0;
+/// MAV_MODE_FLAG_SAFETY_ARMED
+const MAV_MODE_FLAG_SAFETY_ARMED: u8 = 128;
+/// MAV_STATE_ACTIVE and MAV_STATE_STANDBY
+const MAV_STATE_ACTIVE: u8 = 4;
+const MAV_STATE_STANDBY: u8 = 3;
+
+/// A message of the MAVLink common dialect
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    /// HEARTBEAT (#0): vehicle type and whether it is armed
+    Heartbeat { armed: bool },
+    /// SYS_STATUS (#1): battery state; the sensor fields are left empty
+    SysStatus {
+        voltage_mv: Option<u16>,
+        /// Remaining charge in percent
+        battery_remaining: Option<i8>,
+    },
+    /// ATTITUDE (#30): Euler angles in radians
+    Attitude { time_boot_ms: u32, roll: f32, pitch: f32, yaw: f32 },
+    /// GLOBAL_POSITION_INT (#33)
+    GlobalPositionInt {
+        time_boot_ms: u32,
+        /// Degrees * 1e7
+        lat: i32,
+        lon: i32,
+        /// Millimeters above mean sea level
+        alt: i32,
+        /// Millimeters above home
+        relative_alt: i32,
+        /// North, east and down speed in cm/s
+        vx: i16,
+        vy: i16,
+        vz: i16,
+        /// Heading in centidegrees
+        heading: Option<u16>,
+    },
+}
+
+impl Message {
+    fn id(&self) -> u8 {
+        match self {
+            Message::Heartbeat { .. } => 0,
+            Message::SysStatus { .. } => 1,
+            Message::Attitude { .. } => 30,
+            Message::GlobalPositionInt { .. } => 33,
+        }
+    }
+
+    /// Seed added to the checksum so receivers detect mismatched message definitions
+    fn crc_extra(&self) -> u8 {
+        match self {
+            Message::Heartbeat { .. } => 50,
+            Message::SysStatus { .. } => 124,
+            Message::Attitude { .. } => 39,
+            Message::GlobalPositionInt { .. } => 104,
+        }
+    }
+
+    /// Little-endian payload, fields ordered by size as on the wire
+    fn payload(&self) -> Vec<u8> {
+        let mut p = Vec::with_capacity(32);
+        match *self {
+            Message::Heartbeat { armed } => {
+                p.extend_from_slice(&0u32.to_le_bytes()); // custom_mode
+                p.push(MAV_TYPE_QUADROTOR);
+                p.push(MAV_AUTOPILOT_GENERIC);
+                p.push(if armed { MAV_MODE_FLAG_SAFETY_ARMED } else { 0 });
+                p.push(if armed { MAV_STATE_ACTIVE } else { MAV_STATE_STANDBY });
+                p.push(3); // mavlink_version
+            }
+            Message::SysStatus { voltage_mv, battery_remaining } => {
+                p.extend_from_slice(&[0u8; 12]); // sensors present, enabled, healthy
+                p.extend_from_slice(&0u16.to_le_bytes()); // load
+                p.extend_from_slice(&voltage_mv.unwrap_or(u16::MAX).to_le_bytes());
+                p.extend_from_slice(&(-1i16).to_le_bytes()); // current_battery: unknown
+                p.extend_from_slice(&[0u8; 12]); // drop rate and error counters
+                p.push(battery_remaining.unwrap_or(-1) as u8);
+            }
+            Message::Attitude { time_boot_ms, roll, pitch, yaw } => {
+                p.extend_from_slice(&time_boot_ms.to_le_bytes());
+                for value in [roll, pitch, yaw, 0.0, 0.0, 0.0] {
+                    p.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            Message::GlobalPositionInt { time_boot_ms, lat, lon, alt, relative_alt, vx, vy, vz, heading } => {
+                p.extend_from_slice(&time_boot_ms.to_le_bytes());
+                for value in [lat, lon, alt, relative_alt] {
+                    p.extend_from_slice(&value.to_le_bytes());
+                }
+                for value in [vx, vy, vz] {
+                    p.extend_from_slice(&value.to_le_bytes());
+                }
+                p.extend_from_slice(&heading.unwrap_or(u16::MAX).to_le_bytes());
+            }
+        }
+        p
+    }
+}
+
+/// A tlog being written packet by packet
+#[derive(Debug, Default)]
+pub struct TlogWriter {
+    data: Vec<u8>,
+    sequence: u8,
+}
+
+impl TlogWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a message received at `time_us` microseconds since the Unix epoch
+    pub fn push(&mut self, time_us: u64, message: &Message) {
+        let payload = message.payload();
+        let mut packet = Vec::with_capacity(payload.len() + 8);
+        packet.push(STX_V1);
+        packet.push(payload.len() as u8);
+        packet.push(self.sequence);
+        packet.push(SYSTEM_ID);
+        packet.push(COMPONENT_ID);
+        packet.push(message.id());
+        packet.extend_from_slice(&payload);
+        let mut crc = packet[1..].iter().fold(0xFFFF, |crc, byte| crc_accumulate(crc, *byte));
+        crc = crc_accumulate(crc, message.crc_extra());
+        packet.extend_from_slice(&crc.to_le_bytes());
+
+        self.data.extend_from_slice(&time_us.to_be_bytes());
+        self.data.extend_from_slice(&packet);
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// One byte of MAVLink's CRC-16/MCRF4XX (X.25) checksum
+fn crc_accumulate(crc: u16, byte: u8) -> u16 {
+    let mut tmp = byte ^ (crc & 0xFF) as u8;
+    tmp ^= tmp << 4;
+    let tmp = u16::from(tmp);
+    (crc >> 8) ^ (tmp << 8) ^ (tmp << 3) ^ (tmp >> 4)
+}
@@ -0,0 +1,254 @@
+//! Media folder scanning for matching photos and videos to flights.
+//!
+//! Capture time, GPS position and camera serial are read from:
+//! - Photos (JPEG, DNG, HEIC, ...): EXIF, with DJI's XMP `drone-dji` block as a
+//!   fallback for the position
+//! - Videos (MP4, MOV): the `mvhd` creation time of the QuickTime container
+//!
+//! EXIF times without an `OffsetTimeOriginal` are local wall-clock times. They
+//! are interpreted in this machine's timezone, which is where the photos were
+//! taken for nearly everyone importing their own media.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+use crate::models::MediaFile;
+
+/// Photo extensions with EXIF support
+const PHOTO_EXTENSIONS: &[&str] = &["jpg", "jpeg", "dng", "tif", "tiff", "heic", "heif", "png", "webp"];
+
+/// Video extensions in the QuickTime/MP4 container format
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov"];
+
+/// Bytes scanned for the XMP packet at the start of a photo
+const XMP_SCAN_BYTES: u64 = 256 * 1024;
+
+/// Seconds between the QuickTime epoch (1904-01-01) and the Unix epoch
+const QUICKTIME_EPOCH_OFFSET_SECS: i64 = 2_082_844_800;
+
+/// Photo and video metadata reader
+pub struct MediaScanner;
+
+impl MediaScanner {
+    /// Check if a file name is a photo or video the scanner can read
+    pub fn is_media_file(file_name: &str) -> bool {
+        media_type(file_name).is_some()
+    }
+
+    /// Recursively read every media file under a folder. Files without a
+    /// readable capture time are still returned so callers can report them.
+    pub fn scan_folder(dir: &Path) -> Vec<MediaFile> {
+        let mut paths = Vec::new();
+        collect_media_files(dir, &mut paths);
+        log::info!("Found {} media files under {:?}", paths.len(), dir);
+        paths.iter().filter_map(|p| Self::read_media(p)).collect()
+    }
+
+    /// Read the capture metadata of a single photo or video
+    pub fn read_media(path: &Path) -> Option<MediaFile> {
+        let file_name = path.file_name()?.to_string_lossy().to_string();
+        let kind = media_type(&file_name)?;
+
+        let mut media = MediaFile {
+            file_path: path.to_string_lossy().to_string(),
+            file_name,
+            media_type: kind.to_string(),
+            captured_at: None,
+            lat: None,
+            lon: None,
+            camera_serial: None,
+        };
+
+        let result = match kind {
+            "photo" => read_photo_metadata(path, &mut media),
+            _ => read_video_metadata(path, &mut media),
+        };
+        if let Err(e) = result {
+            log::debug!("No capture metadata in {:?}: {}", path, e);
+        }
+        Some(media)
+    }
+}
+
+/// "photo", "video" or `None` for unsupported files
+fn media_type(file_name: &str) -> Option<&'static str> {
+    let ext = Path::new(file_name).extension()?.to_str()?.to_lowercase();
+    if PHOTO_EXTENSIONS.contains(&ext.as_str()) {
+        Some("photo")
+    } else if VIDEO_EXTENSIONS.contains(&ext.as_str()) {
+        Some("video")
+    } else {
+        None
+    }
+}
+
+/// Recursively collect media files, skipping hidden entries
+fn collect_media_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Cannot read media folder {:?}: {}", dir, e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_media_files(&path, out);
+        } else if MediaScanner::is_media_file(&name) {
+            out.push(path);
+        }
+    }
+}
+
+/// Fill capture time, position and serial from EXIF, then XMP
+fn read_photo_metadata(path: &Path, media: &mut MediaFile) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let exif = exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .map_err(|e| e.to_string());
+
+    if let Ok(exif) = &exif {
+        media.captured_at = exif_capture_time(exif);
+        media.lat = exif_coordinate(exif, exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef, b'S');
+        media.lon = exif_coordinate(exif, exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef, b'W');
+        media.camera_serial = exif_ascii(exif, exif::Tag::BodySerialNumber)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+    }
+
+    if media.lat.is_none() || media.lon.is_none() {
+        if let Some(xmp) = read_xmp_packet(path) {
+            media.lat = xmp_number(&xmp, &["GpsLatitude"]);
+            // Older DJI firmware misspells the longitude attribute
+            media.lon = xmp_number(&xmp, &["GpsLongitude", "GpsLongtitude"]);
+        }
+    }
+
+    exif.map(|_| ())
+}
+
+/// Capture time from DateTimeOriginal, honouring OffsetTimeOriginal if present
+fn exif_capture_time(exif: &exif::Exif) -> Option<DateTime<Utc>> {
+    let raw = exif_ascii(exif, exif::Tag::DateTimeOriginal)
+        .or_else(|| exif_ascii(exif, exif::Tag::DateTime))?;
+    let mut dt = exif::DateTime::from_ascii(raw.as_bytes()).ok()?;
+    if let Some(offset) = exif_ascii(exif, exif::Tag::OffsetTimeOriginal) {
+        let _ = dt.parse_offset(offset.as_bytes());
+    }
+
+    let naive = NaiveDate::from_ymd_opt(dt.year as i32, dt.month as u32, dt.day as u32)?
+        .and_hms_opt(dt.hour as u32, dt.minute as u32, dt.second as u32)?;
+    match dt.offset {
+        Some(minutes) => Some(naive.and_utc() - chrono::Duration::minutes(minutes as i64)),
+        None => Local.from_local_datetime(&naive).earliest().map(|t| t.with_timezone(&Utc)),
+    }
+}
+
+/// Signed decimal degrees from a GPS rational triple and its hemisphere ref
+fn exif_coordinate(exif: &exif::Exif, tag: exif::Tag, ref_tag: exif::Tag, negative_ref: u8) -> Option<f64> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    let parts = match &field.value {
+        exif::Value::Rational(parts) if parts.len() >= 3 => parts,
+        _ => return None,
+    };
+    let degrees = parts[0].to_f64() + parts[1].to_f64() / 60.0 + parts[2].to_f64() / 3600.0;
+    if !degrees.is_finite() || degrees == 0.0 {
+        return None;
+    }
+    let negative = exif_ascii(exif, ref_tag).map_or(false, |r| r.as_bytes().first() == Some(&negative_ref));
+    Some(if negative { -degrees } else { degrees })
+}
+
+/// First string of an ASCII EXIF field
+fn exif_ascii(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Ascii(values) => values.first().map(|v| String::from_utf8_lossy(v).to_string()),
+        _ => None,
+    }
+}
+
+/// Extract the XMP packet from the start of a photo, if there is one
+fn read_xmp_packet(path: &Path) -> Option<String> {
+    let mut buf = Vec::new();
+    File::open(path).ok()?.take(XMP_SCAN_BYTES).read_to_end(&mut buf).ok()?;
+    let text = String::from_utf8_lossy(&buf);
+    let start = text.find("<x:xmpmeta")?;
+    let end = text[start..].find("</x:xmpmeta>")? + start;
+    Some(text[start..end].to_string())
+}
+
+/// Numeric value of the first `drone-dji:<name>` attribute found
+fn xmp_number(xmp: &str, names: &[&str]) -> Option<f64> {
+    names.iter().find_map(|name| {
+        let key = format!("drone-dji:{}=\"", name);
+        let start = xmp.find(&key)? + key.len();
+        let end = xmp[start..].find('"')? + start;
+        xmp[start..end].trim().parse::<f64>().ok().filter(|v| *v != 0.0)
+    })
+}
+
+/// Read the movie creation time from the `moov/mvhd` box
+fn read_video_metadata(path: &Path, media: &mut MediaFile) -> Result<(), String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let (moov_start, moov_len) = find_box(&mut file, 0, file_len, b"moov")?.ok_or("no moov box")?;
+    let (mvhd_start, _) = find_box(&mut file, moov_start, moov_start + moov_len, b"mvhd")?.ok_or("no mvhd box")?;
+
+    file.seek(SeekFrom::Start(mvhd_start)).map_err(|e| e.to_string())?;
+    let mut version = [0u8; 4];
+    file.read_exact(&mut version).map_err(|e| e.to_string())?;
+    let created = if version[0] == 1 {
+        let mut raw = [0u8; 8];
+        file.read_exact(&mut raw).map_err(|e| e.to_string())?;
+        u64::from_be_bytes(raw) as i64
+    } else {
+        let mut raw = [0u8; 4];
+        file.read_exact(&mut raw).map_err(|e| e.to_string())?;
+        u32::from_be_bytes(raw) as i64
+    };
+
+    // Zero means the recorder never set a time
+    if created > 0 {
+        media.captured_at = Utc.timestamp_opt(created - QUICKTIME_EPOCH_OFFSET_SECS, 0).single();
+    }
+    Ok(())
+}
+
+/// Find a child box by type within `[start, end)`. Returns the payload
+/// offset and payload length.
+fn find_box(file: &mut File, start: u64, end: u64, kind: &[u8; 4]) -> Result<Option<(u64, u64)>, String> {
+    let mut pos = start;
+    while pos + 8 <= end {
+        file.seek(SeekFrom::Start(pos)).map_err(|e| e.to_string())?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).map_err(|e| e.to_string())?;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large).map_err(|e| e.to_string())?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - pos;
+        }
+        if size < header_len {
+            return Err("corrupt box header".to_string());
+        }
+        if &header[4..8] == kind {
+            return Ok(Some((pos + header_len, size - header_len)));
+        }
+        pos += size;
+    }
+    Ok(None)
+}
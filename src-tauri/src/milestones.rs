@@ -0,0 +1,185 @@
+//! Milestones and flying streaks.
+//!
+//! Milestones are round numbers of flights, hours and kilometers, each dated
+//! by the flight that reached it. Streaks are runs of consecutive days, or
+//! Monday-to-Sunday weeks, with at least one flight. Days are the UTC dates
+//! of flight starts, as in the rest of the statistics. Flights excluded from
+//! statistics don't count.
+
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{FlightTotals, Milestone, MilestoneReport, Streak};
+
+const FLIGHT_MILESTONES: [f64; 10] = [1.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0];
+const HOUR_MILESTONES: [f64; 9] = [1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+const DISTANCE_MILESTONES_KM: [f64; 9] = [10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0];
+
+/// Milestones and streaks of the flights counted in statistics
+pub fn milestone_report(db: &Database) -> Result<MilestoneReport, DatabaseError> {
+    let flights = db.get_flight_totals()?;
+    Ok(report(&flights, Utc::now().date_naive()))
+}
+
+fn report(flights: &[FlightTotals], today: NaiveDate) -> MilestoneReport {
+    let mut milestones = Vec::new();
+    milestones.extend(milestones_of(flights, "flights", &FLIGHT_MILESTONES, |_| 1.0));
+    milestones.extend(milestones_of(flights, "hours", &HOUR_MILESTONES, |f| f.duration_secs / 3600.0));
+    milestones.extend(milestones_of(flights, "distance", &DISTANCE_MILESTONES_KM, |f| f.distance_m / 1000.0));
+    // Reached ones oldest first; the sort is stable, so the next ones keep their order
+    milestones.sort_by(|a, b| (a.reached_at.is_none(), &a.reached_at).cmp(&(b.reached_at.is_none(), &b.reached_at)));
+
+    let mut dates: Vec<NaiveDate> = flights
+        .iter()
+        .filter_map(|f| NaiveDate::parse_from_str(f.start_time.get(..10)?, "%Y-%m-%d").ok())
+        .collect();
+    dates.sort();
+    dates.dedup();
+
+    let (longest_daily_streak, current_daily_streak) = streaks(&dates, today, |date| date, 1);
+    let (longest_weekly_streak, current_weekly_streak) = streaks(&dates, today, week_start, 7);
+
+    MilestoneReport {
+        milestones,
+        longest_daily_streak,
+        current_daily_streak,
+        longest_weekly_streak,
+        current_weekly_streak,
+    }
+}
+
+/// Reached milestones of one kind, then the next one with the progress
+/// towards it. `amount` is what a flight adds to the running total.
+fn milestones_of(
+    flights: &[FlightTotals],
+    kind: &str,
+    thresholds: &[f64],
+    amount: impl Fn(&FlightTotals) -> f64,
+) -> Vec<Milestone> {
+    let mut milestones = Vec::new();
+    let mut thresholds = thresholds.iter().copied().peekable();
+    let mut total = 0.0;
+    for flight in flights {
+        total += amount(flight).max(0.0);
+        while let Some(threshold) = thresholds.next_if(|threshold| total >= *threshold) {
+            milestones.push(Milestone {
+                kind: kind.to_string(),
+                threshold,
+                flight_id: Some(flight.flight_id),
+                reached_at: Some(flight.start_time.clone()),
+                progress: 1.0,
+            });
+        }
+    }
+    if let Some(threshold) = thresholds.next() {
+        milestones.push(Milestone {
+            kind: kind.to_string(),
+            threshold,
+            flight_id: None,
+            reached_at: None,
+            progress: total / threshold,
+        });
+    }
+    milestones
+}
+
+/// Monday of the week of `date`
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Longest and current run of consecutive periods with flights. `period`
+/// maps a date to the first day of its period, which is `step_days` long.
+/// The current run must reach the period of `today` or the one before.
+fn streaks(
+    dates: &[NaiveDate],
+    today: NaiveDate,
+    period: impl Fn(NaiveDate) -> NaiveDate,
+    step_days: i64,
+) -> (Option<Streak>, Option<Streak>) {
+    // (periods, first date, last date) of each run
+    let mut runs: Vec<(i64, NaiveDate, NaiveDate)> = Vec::new();
+    for &date in dates {
+        match runs.last_mut() {
+            Some((_, _, last)) if period(date) == period(*last) => *last = date,
+            Some((length, _, last)) if period(date) == period(*last) + Duration::days(step_days) => {
+                *length += 1;
+                *last = date;
+            }
+            _ => runs.push((1, date, date)),
+        }
+    }
+
+    let streak = |&(length, first, last): &(i64, NaiveDate, NaiveDate)| Streak {
+        length,
+        start_date: first.format("%Y-%m-%d").to_string(),
+        end_date: last.format("%Y-%m-%d").to_string(),
+    };
+    // Ties go to the most recent run
+    let longest = runs.iter().max_by_key(|run| run.0).map(streak);
+    let current = runs
+        .last()
+        .filter(|run| period(run.2) >= period(today) - Duration::days(step_days))
+        .map(streak);
+    (longest, current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(flight_id: i64, start_time: &str, hours: f64, km: f64) -> FlightTotals {
+        FlightTotals {
+            flight_id,
+            start_time: start_time.to_string(),
+            duration_secs: hours * 3600.0,
+            distance_m: km * 1000.0,
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn milestones_are_dated_by_the_flight_that_reached_them() {
+        let flights = [
+            flight(1, "2024-03-01 10:00:00", 0.5, 4.0),
+            flight(2, "2024-03-02 10:00:00", 0.75, 8.0),
+            flight(3, "2024-03-09 10:00:00", 4.0, 2.0),
+        ];
+        let report = report(&flights, date("2024-06-01"));
+        let hours: Vec<_> = report.milestones.iter().filter(|m| m.kind == "hours").collect();
+        assert_eq!(hours.len(), 3);
+        assert_eq!((hours[0].threshold, hours[0].flight_id), (1.0, Some(2)));
+        // One flight can pass several thresholds
+        assert_eq!((hours[1].threshold, hours[1].flight_id), (5.0, Some(3)));
+        assert_eq!(hours[2].threshold, 10.0);
+        assert_eq!(hours[2].reached_at, None);
+        assert!((hours[2].progress - 0.525).abs() < 1e-9);
+
+        let first_flight = &report.milestones[0];
+        assert_eq!((first_flight.kind.as_str(), first_flight.flight_id), ("flights", Some(1)));
+        assert!(report.milestones.iter().rev().take(3).all(|m| m.reached_at.is_none()));
+    }
+
+    #[test]
+    fn streaks_count_consecutive_days_and_weeks() {
+        let dates: Vec<NaiveDate> = ["2024-05-01", "2024-05-02", "2024-05-03", "2024-05-13", "2024-05-20", "2024-05-21"]
+            .into_iter()
+            .map(date)
+            .collect();
+
+        let (longest, current) = streaks(&dates, date("2024-05-22"), |d| d, 1);
+        let longest = longest.unwrap();
+        assert_eq!((longest.length, longest.start_date.as_str()), (3, "2024-05-01"));
+        assert_eq!(current.unwrap().length, 2);
+        assert!(streaks(&dates, date("2024-05-23"), |d| d, 1).1.is_none());
+
+        // Weeks of 29 Apr, 13 May and 20 May
+        let (longest, current) = streaks(&dates, date("2024-06-02"), week_start, 7);
+        let longest = longest.unwrap();
+        assert_eq!((longest.length, longest.start_date.as_str(), longest.end_date.as_str()), (2, "2024-05-13", "2024-05-21"));
+        assert_eq!(current.unwrap().length, 2);
+    }
+}
@@ -0,0 +1,130 @@
+//! Parser module for DJI waypoint mission files (.kmz).
+//!
+//! DJI Fly and DJI Pilot 2 export missions as a ZIP archive in the WPML
+//! format:
+//! - `wpmz/waylines.wpml`: the executable wayline the aircraft flies
+//! - `wpmz/template.kml`: the editable template shown in the planner
+//!
+//! Both are KML documents with one `Placemark` per waypoint. The wayline file
+//! is preferred because it holds the heights actually flown; the template is
+//! used when an export only contains that one.
+
+use std::io::{Cursor, Read};
+
+use chrono::{TimeZone, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::models::{MissionMetadata, Waypoint};
+use crate::parser::ParserError;
+
+/// Executable wayline inside the KMZ archive
+const WAYLINES_ENTRY: &str = "wpmz/waylines.wpml";
+
+/// Planner template inside the KMZ archive
+const TEMPLATE_ENTRY: &str = "wpmz/template.kml";
+
+/// DJI waypoint mission (.kmz) parser
+pub struct MissionParser;
+
+impl MissionParser {
+    /// Check if a file name looks like a waypoint mission archive
+    pub fn is_mission_kmz(file_name: &str) -> bool {
+        file_name.to_lowercase().ends_with(".kmz")
+    }
+
+    /// Parse a KMZ mission held in memory
+    pub fn parse(data: &[u8], file_name: &str) -> Result<MissionMetadata, ParserError> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(data))
+            .map_err(|e| ParserError::Parse(format!("Not a valid KMZ archive: {}", e)))?;
+
+        let waylines = read_entry(&mut archive, WAYLINES_ENTRY)?;
+        let template = read_entry(&mut archive, TEMPLATE_ENTRY)?;
+
+        let (source, height_tags): (&str, &[&str]) = match (&waylines, &template) {
+            (Some(w), _) => (w, &["executeHeight"]),
+            (None, Some(t)) => (t, &["height", "ellipsoidHeight"]),
+            (None, None) => {
+                return Err(ParserError::Parse(
+                    "KMZ contains no waypoint mission (missing wpmz/waylines.wpml and wpmz/template.kml)".to_string(),
+                ))
+            }
+        };
+
+        let doc = roxmltree::Document::parse(source)
+            .map_err(|e| ParserError::Parse(format!("Invalid mission KML: {}", e)))?;
+
+        let mut waypoints: Vec<Waypoint> = doc
+            .descendants()
+            .filter(|n| n.is_element() && n.tag_name().name() == "Placemark")
+            .filter_map(|placemark| {
+                let coords = child_text(placemark, "coordinates")?;
+                let mut parts = coords.split(',').map(|p| p.trim().parse::<f64>());
+                let lon = parts.next()?.ok()?;
+                let lat = parts.next()?.ok()?;
+                Some(Waypoint {
+                    index: child_text(placemark, "index").and_then(|v| v.parse().ok()).unwrap_or(0),
+                    lat,
+                    lon,
+                    height: height_tags
+                        .iter()
+                        .find_map(|tag| child_text(placemark, tag).and_then(|v| v.parse().ok())),
+                    speed: child_text(placemark, "waypointSpeed").and_then(|v| v.parse().ok()),
+                })
+            })
+            .collect();
+
+        if waypoints.is_empty() {
+            return Err(ParserError::Parse("Mission has no waypoints".to_string()));
+        }
+        waypoints.sort_by_key(|w| w.index);
+
+        // Mission metadata only lives in the template
+        let template_doc = template.as_deref().and_then(|t| roxmltree::Document::parse(t).ok());
+        let root = template_doc.as_ref().map(|d| d.root()).unwrap_or_else(|| doc.root());
+
+        let created_at = child_text(root, "createTime")
+            .and_then(|v| v.parse::<i64>().ok())
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single());
+        let drone_model = child_text(root, "droneEnumValue").map(|v| v.to_string());
+
+        let name = std::path::Path::new(file_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(file_name)
+            .to_string();
+
+        log::info!("Parsed mission '{}' with {} waypoints", name, waypoints.len());
+
+        Ok(MissionMetadata {
+            name,
+            file_name: file_name.to_string(),
+            file_hash: format!("{:x}", Sha256::digest(data)),
+            drone_model,
+            created_at,
+            waypoints,
+        })
+    }
+}
+
+/// Read a text entry from the archive, `None` if it does not exist
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Option<String>, ParserError> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(ParserError::Parse(format!("Failed to read {}: {}", name, e))),
+    };
+    let mut contents = String::new();
+    entry.read_to_string(&mut contents)?;
+    Ok(Some(contents))
+}
+
+/// Trimmed text of the first descendant with the given local tag name
+/// (namespace prefixes such as `wpml:` are ignored)
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, local_name: &str) -> Option<&'a str> {
+    node.descendants()
+        .find(|n| n.is_element() && n.tag_name().name() == local_name)
+        .and_then(|n| n.text())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
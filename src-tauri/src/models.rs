@@ -26,6 +26,18 @@ pub struct FlightMetadata {
     pub home_lat: Option<f64>,
     pub home_lon: Option<f64>,
     pub point_count: i32,
+    /// Planned mission name (GS Pro and other SDK mission apps)
+    #[serde(default)]
+    pub mission_name: Option<String>,
+    /// Recovered from a truncated or corrupt log; telemetry may end early
+    #[serde(default)]
+    pub partial: bool,
+    /// Aircraft firmware version from the log
+    #[serde(default)]
+    pub firmware_version: Option<String>,
+    /// Farthest 3D distance from the home point in meters
+    #[serde(default)]
+    pub max_distance_from_home: Option<f64>,
 }
 
 /// Flight summary for list display
@@ -51,6 +63,50 @@ pub struct Flight {
     #[serde(default)]
     pub tags: Vec<FlightTag>,
     pub notes: Option<String>,
+    pub mission_name: Option<String>,
+    #[serde(default)]
+    pub partial: bool,
+    /// When the flight was moved to the trash (None for active flights)
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// User-set name of the aircraft from its drone profile
+    #[serde(default)]
+    pub drone_nickname: Option<String>,
+    /// Reverse-geocoded from the home point
+    #[serde(default)]
+    pub location_city: Option<String>,
+    #[serde(default)]
+    pub location_region: Option<String>,
+    #[serde(default)]
+    pub location_country: Option<String>,
+    /// Starred by the user
+    #[serde(default)]
+    pub favorite: bool,
+    /// Aircraft firmware version from the log
+    #[serde(default)]
+    pub firmware_version: Option<String>,
+    /// Left out of statistics, e.g. a bench test. Still counts toward
+    /// battery, aircraft and maintenance usage.
+    #[serde(default)]
+    pub excluded_from_stats: bool,
+    /// Farthest 3D distance from the home point in meters
+    #[serde(default)]
+    pub max_distance_from_home: Option<f64>,
+    /// Darkest light flown in at the home point: "day", "civil_twilight" or "night"
+    #[serde(default)]
+    pub daylight: Option<String>,
+    /// Flight time after the end of civil twilight, in seconds
+    #[serde(default)]
+    pub night_secs: Option<f64>,
+}
+
+/// Human-readable place of a coordinate, from the reverse geocoder
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightLocation {
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
 }
 
 /// A tag attached to a flight, with a type indicator
@@ -113,6 +169,20 @@ pub struct TelemetryPoint {
     // Camera state
     pub is_photo: Option<bool>,
     pub is_video: Option<bool>,
+
+    // Individual cell voltages of the smart battery, cell 1 first
+    pub cell_voltages: Option<Vec<f64>>,
+
+    // Sensor health
+    pub compass_error: Option<bool>,
+    /// Deviation of the magnetic field strength from the flight's baseline (%)
+    pub compass_interference: Option<f64>,
+    /// IMU initialisation problem or "Preheating"; `None` while healthy
+    pub imu_status: Option<String>,
+
+    // Wind as reported by the log (m/s, direction the wind blows from in degrees)
+    pub wind_speed: Option<f64>,
+    pub wind_direction: Option<f64>,
 }
 
 /// Telemetry record for frontend consumption (optimized for ECharts)
@@ -146,6 +216,15 @@ pub struct TelemetryRecord {
     pub rc_rudder: Option<f64>,
     pub is_photo: Option<bool>,
     pub is_video: Option<bool>,
+    pub compass_error: Option<bool>,
+    pub compass_interference: Option<f64>,
+    pub imu_status: Option<String>,
+    pub wind_speed: Option<f64>,
+    pub wind_direction: Option<f64>,
+    /// Path distance flown up to this point (m), over every raw point even
+    /// when the records are downsampled
+    #[serde(default)]
+    pub cumulative_distance: f64,
 }
 
 /// Response format optimized for ECharts rendering
@@ -155,6 +234,27 @@ pub struct FlightDataResponse {
     pub flight: Flight,
     pub telemetry: TelemetryData,
     pub track: Vec<[f64; 3]>, // [lng, lat, height] for map
+    /// Extra source files linked to this flight (e.g. aircraft DAT logs)
+    #[serde(default)]
+    pub attachments: Vec<FlightAttachment>,
+    /// Home point history (takeoff point plus mid-flight updates)
+    #[serde(default)]
+    pub home_points: Vec<HomePoint>,
+    /// Capture, settings and warning events
+    #[serde(default)]
+    pub events: Vec<FlightEvent>,
+    /// Takeoffs, landings, mode changes, captures and warnings in time order
+    #[serde(default)]
+    pub timeline: Vec<TimelineEvent>,
+    /// Ascent, cruise, hover, descent and RTH ranges covering the flight
+    #[serde(default)]
+    pub phases: Vec<FlightPhase>,
+    /// Per-motor RPM/current/temperature (only for logs that record them)
+    #[serde(default)]
+    pub motors: Vec<MotorSeries>,
+    /// User annotations pinned to moments of the flight
+    #[serde(default)]
+    pub annotations: Vec<FlightAnnotation>,
 }
 
 /// Overview statistics across all flights
@@ -193,6 +293,129 @@ pub struct DroneUsage {
     pub drone_serial: Option<String>,
     pub aircraft_name: Option<String>,
     pub flight_count: i64,
+    /// User-set name from the drone profile
+    pub nickname: Option<String>,
+}
+
+/// Flight battery registered from the logs, with its usage totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Battery {
+    pub serial: String,
+    /// Aircraft the battery was first flown with
+    pub drone_model: Option<String>,
+    pub first_seen: Option<String>,
+    pub last_used: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+}
+
+/// Maintenance work logged against a drone or battery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceRecord {
+    pub id: i64,
+    /// "drone" or "battery"
+    pub target_type: String,
+    pub serial: String,
+    /// "prop_swap", "firmware_update", "repair", "calibration", "service" or "other"
+    pub kind: String,
+    pub performed_at: String,
+    pub description: Option<String>,
+}
+
+/// Usage of a drone or battery since its last maintenance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStatus {
+    pub target_type: String,
+    pub serial: String,
+    /// None if no maintenance was ever logged (counts then cover every flight)
+    pub last_maintenance: Option<String>,
+    pub last_kind: Option<String>,
+    pub flights_since: i64,
+    pub flight_hours_since: f64,
+}
+
+/// Payload or accessory in the equipment inventory with its usage totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Equipment {
+    pub id: i64,
+    pub name: String,
+    /// "filter", "lens", "payload", "propellers", "controller" or "other"
+    pub category: String,
+    pub serial: Option<String>,
+    pub notes: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub last_used: Option<String>,
+}
+
+/// Reusable pre-flight checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistTemplate {
+    pub id: i64,
+    pub name: String,
+    pub items: Vec<String>,
+    pub updated_at: Option<String>,
+}
+
+/// One line of a completed checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItem {
+    pub text: String,
+    pub checked: bool,
+}
+
+/// A checklist as it was completed for a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightChecklist {
+    pub id: i64,
+    pub flight_id: i64,
+    /// Template the checklist was made from, if it still exists
+    pub template_id: Option<i64>,
+    pub name: String,
+    pub items: Vec<ChecklistItem>,
+    /// True when every item was checked off
+    pub all_checked: bool,
+    pub completed_by: Option<String>,
+    pub notes: Option<String>,
+    pub completed_at: Option<String>,
+}
+
+/// User profile of one aircraft (keyed by serial) with its usage totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneProfile {
+    pub serial: String,
+    pub drone_model: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub nickname: Option<String>,
+    /// YYYY-MM-DD
+    pub purchase_date: Option<String>,
+    pub notes: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub last_flight: Option<String>,
+    /// Firmware version of the most recent flight that logged one
+    pub firmware_version: Option<String>,
+    /// Registration number or operator marking, printed on the logbook
+    pub registration: Option<String>,
+}
+
+/// A firmware version an aircraft flew with, and when
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareHistoryEntry {
+    pub firmware_version: String,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    pub flight_count: i64,
 }
 
 /// Flight count per date for activity heatmap
@@ -223,6 +446,122 @@ pub struct TopDistanceFlight {
     pub start_time: Option<String>,
 }
 
+/// Flight totals for one calendar month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyStats {
+    /// YYYY-MM
+    pub month: String,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+}
+
+/// Period length of a trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendBucket {
+    Day,
+    /// Monday to Sunday
+    Week,
+    Month,
+    Year,
+}
+
+/// Flight totals for one period of a trend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    /// First day of the period, YYYY-MM-DD
+    pub period: String,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+}
+
+/// Flight totals for one aircraft, keyed by serial or, without one, by model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneStats {
+    pub drone_model: String,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    /// User-set name from the drone profile
+    pub nickname: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+}
+
+/// Aggregate statistics of the library or a subset of its flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStats {
+    pub total_flights: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+    pub total_points: i64,
+    pub max_altitude_m: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    /// Days with at least one flight
+    pub days_flown: i64,
+    /// Flight time after the end of civil twilight
+    pub night_duration_secs: f64,
+    /// Flights with some time flown at night
+    pub night_flight_count: i64,
+    /// Flights in civil twilight at the darkest, none at night
+    pub twilight_flight_count: i64,
+    /// Most flown first
+    pub drones: Vec<DroneStats>,
+    /// Most flown first
+    pub batteries: Vec<BatteryUsage>,
+    /// Oldest first
+    pub months: Vec<MonthlyStats>,
+}
+
+/// The flight holding a record and the record value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalRecord {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    /// Seconds, meters or m/s depending on the record
+    pub value: f64,
+}
+
+/// Record flights across the library; a record is None with no qualifying flight
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalRecords {
+    pub longest_duration: Option<PersonalRecord>,
+    pub longest_distance: Option<PersonalRecord>,
+    pub highest_altitude: Option<PersonalRecord>,
+    pub top_speed: Option<PersonalRecord>,
+    pub farthest_from_home: Option<PersonalRecord>,
+}
+
+/// A year of flying summarized, for sharing as a "year in review"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReview {
+    pub year: i32,
+    /// Totals, aircraft, batteries and months of the year's counted flights
+    pub stats: LibraryStats,
+    /// All twelve months, January first, including those without flights
+    pub months: Vec<MonthlyStats>,
+    /// Best flights of the year
+    pub year_records: PersonalRecords,
+    /// All-time records set during the year: `longestDuration`,
+    /// `longestDistance`, `highestAltitude`, `topSpeed`, `farthestFromHome`
+    pub records_set: Vec<String>,
+    /// Sites first flown from during the year
+    pub new_sites: Vec<Site>,
+}
+
 /// Battery health scatter/line point per flight
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -235,9 +574,263 @@ pub struct BatteryHealthPoint {
     pub rate_per_min: f64,
 }
 
-/// Telemetry data formatted for ECharts
+/// Battery figures of one flight, the points of a battery's health trend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryFlightSample {
+    pub flight_id: i64,
+    pub battery_serial: String,
+    pub start_time: Option<String>,
+    pub duration_secs: f64,
+    pub start_percent: Option<f64>,
+    pub end_percent: Option<f64>,
+    /// Mean current drawn in amperes
+    pub avg_current_a: Option<f64>,
+    /// Charge a full battery holds, from the charge drawn and the percentage used
+    pub capacity_mah: Option<f64>,
+    /// Resting voltage minus the lowest voltage over the first 10% of charge used
+    pub voltage_sag_v: Option<f64>,
+    /// Largest voltage difference between cells at one moment
+    pub cell_imbalance_v: Option<f64>,
+}
+
+/// Health trend of one battery across its flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryHealth {
+    pub serial: String,
+    pub flight_count: i64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    /// Capacity lost between the first and the latest flights, in percent
+    pub capacity_fade_percent: Option<f64>,
+    /// Change in voltage sag between the first and the latest flights
+    pub voltage_sag_change_v: Option<f64>,
+    /// Worst cell imbalance of the latest flights
+    pub recent_cell_imbalance_v: Option<f64>,
+    /// 100 for a battery behaving as when new, down to 0
+    pub health_score: f64,
+    /// Oldest first
+    pub flights: Vec<BatteryFlightSample>,
+}
+
+/// Battery discharge during one flight, for comparing a battery's flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DischargeCurve {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    /// [minutes since the log started, charge in percent]
+    pub percent_by_minute: Vec<[f64; 2]>,
+    /// [charge in percent, mean voltage at that charge], full battery first
+    pub voltage_by_percent: Vec<[f64; 2]>,
+}
+
+/// Battery energy used on one flight, integrated from voltage × current
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightEnergy {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub energy_wh: f64,
+    pub duration_secs: f64,
+    pub distance_m: f64,
+    /// None for flights that barely moved
+    pub wh_per_km: Option<f64>,
+    pub wh_per_min: Option<f64>,
+}
+
+/// Energy use and efficiency of one aircraft, keyed like `DroneStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneEnergy {
+    pub drone_model: String,
+    pub drone_serial: Option<String>,
+    pub flight_count: i64,
+    pub energy_wh: f64,
+    pub duration_secs: f64,
+    pub distance_m: f64,
+    pub wh_per_km: Option<f64>,
+    pub wh_per_min: Option<f64>,
+    /// Oldest first
+    pub flights: Vec<FlightEnergy>,
+}
+
+/// Time spent in one flight mode as logged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightModeTime {
+    /// Mode name from the log, e.g. "GPSAtti" or "P-GPS"
+    pub mode: String,
+    /// One of `flight_modes::CATEGORIES`
+    pub category: String,
+    pub duration_secs: f64,
+    /// Flights that were in the mode at some point
+    pub flight_count: i64,
+}
+
+/// Time spent in one group of flight modes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightModeCategory {
+    pub category: String,
+    pub duration_secs: f64,
+    pub flight_count: i64,
+}
+
+/// Time per flight mode across the flights counted in statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightModeStats {
+    /// Time with a logged flight mode
+    pub total_secs: f64,
+    /// Longest first
+    pub categories: Vec<FlightModeCategory>,
+    /// Longest first
+    pub modes: Vec<FlightModeTime>,
+}
+
+/// Home point of a flight, the input of site clustering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteHomePoint {
+    pub flight_id: i64,
+    pub lat: f64,
+    pub lon: f64,
+    /// Geocoded city, region or country, whichever is known first
+    pub place: Option<String>,
+}
+
+/// A place flights are flown from, found by clustering their home points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Site {
+    pub id: i64,
+    pub name: String,
+    /// Named by the user rather than after the geocoded location
+    pub custom_name: bool,
+    pub center_lat: f64,
+    pub center_lon: f64,
+    /// Farthest home point of the site from its center, in meters
+    pub radius_m: f64,
+    /// Counted flights only
+    pub flight_count: i64,
+    /// Days with at least one counted flight
+    pub visit_count: i64,
+    pub duration_secs: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+}
+
+/// Counted flight of a site, the input of per-site statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteFlight {
+    pub site_id: i64,
+    pub start_time: Option<String>,
+    pub duration_secs: f64,
+    pub distance_m: f64,
+    /// Mean logged wind speed (m/s)
+    pub avg_wind_speed: Option<f64>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    /// User-set name from the drone profile
+    pub nickname: Option<String>,
+}
+
+/// Flying at a site in one month of the year, over every year
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteMonthStats {
+    /// 1 = January
+    pub month: u32,
+    pub flight_count: i64,
+    pub duration_secs: f64,
+    /// Mean logged wind speed (m/s), weighted by flight time
+    pub avg_wind_speed: Option<f64>,
+}
+
+/// History of one site: flight time, wind, seasons and aircraft
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteStats {
+    pub site: Site,
+    pub distance_m: f64,
+    /// Median of the flights' mean wind speeds (m/s)
+    pub typical_wind_speed: Option<f64>,
+    /// Strongest mean wind of a flight (m/s)
+    pub max_wind_speed: Option<f64>,
+    /// Flights that logged wind
+    pub wind_flight_count: i64,
+    /// Month of the year with the most flight time
+    pub busiest_month: Option<u32>,
+    /// Month of the year with the lowest mean wind
+    pub calmest_month: Option<u32>,
+    /// Months with flights, January first
+    pub months: Vec<SiteMonthStats>,
+    /// Most flown first
+    pub drones: Vec<DroneUsage>,
+}
+
+/// Start, duration and distance of a flight, the input of milestones
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct FlightTotals {
+    pub flight_id: i64,
+    pub start_time: String,
+    pub duration_secs: f64,
+    pub distance_m: f64,
+}
+
+/// A round number of flights, hours or kilometers and when it was reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Milestone {
+    /// "flights", "hours" or "distance"
+    pub kind: String,
+    /// Flights, hours or kilometers
+    pub threshold: f64,
+    /// Flight that reached it; None while not reached
+    pub flight_id: Option<i64>,
+    pub reached_at: Option<String>,
+    /// Share of the threshold flown so far, 0 to 1
+    pub progress: f64,
+}
+
+/// Run of consecutive days or weeks with at least one flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Streak {
+    /// Days or weeks in the run
+    pub length: i64,
+    /// YYYY-MM-DD of the first flight
+    pub start_date: String,
+    /// YYYY-MM-DD of the last flight
+    pub end_date: String,
+}
+
+/// Milestones and flying streaks of the flights counted in statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneReport {
+    /// Reached milestones oldest first, then the next one of each kind
+    pub milestones: Vec<Milestone>,
+    pub longest_daily_streak: Option<Streak>,
+    /// Run that includes today or yesterday
+    pub current_daily_streak: Option<Streak>,
+    pub longest_weekly_streak: Option<Streak>,
+    /// Run that includes this week or last week
+    pub current_weekly_streak: Option<Streak>,
+}
+
+/// Telemetry data formatted for ECharts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TelemetryData {
     /// Time axis in seconds from flight start
     pub time: Vec<f64>,
@@ -259,6 +852,8 @@ pub struct TelemetryData {
     pub velocity_y: Vec<Option<f64>>,
     /// Velocity Z series (down)
     pub velocity_z: Vec<Option<f64>>,
+    /// Climb rate (m/s, positive up) derived from the height series
+    pub vertical_speed: Vec<Option<f64>>,
     /// Battery percent series
     pub battery: Vec<Option<i32>>,
     /// Battery voltage series
@@ -293,6 +888,34 @@ pub struct TelemetryData {
     pub is_video: Vec<Option<bool>>,
     /// Flight mode (e.g., "GPS", "ATTI", "Sport")
     pub flight_mode: Vec<Option<String>>,
+    /// Compass error flag raised by the flight controller
+    pub compass_error: Vec<Option<bool>>,
+    /// Magnetic field deviation from the flight baseline (%)
+    pub compass_interference: Vec<Option<f64>>,
+    /// IMU problem or calibration state, null while healthy
+    pub imu_status: Vec<Option<String>>,
+    /// Wind speed (m/s) where the log records it
+    pub wind_speed: Vec<Option<f64>>,
+    /// Direction the wind blows from (degrees, 0 = north)
+    pub wind_direction: Vec<Option<f64>>,
+    /// Wind speed (m/s) estimated from the attitude, for logs without
+    /// `wind_speed`; see `wind::estimate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub estimated_wind_speed: Vec<Option<f64>>,
+    /// Direction the estimated wind blows from (degrees, 0 = north)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub estimated_wind_direction: Vec<Option<f64>>,
+    /// Path distance flown so far (m), ending on the flight's total distance
+    pub cumulative_distance: Vec<f64>,
+    /// Horizontal distance from the home point (m), see `derived::distance_from_home`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distance_from_home: Vec<Option<f64>>,
+    /// Distance from the home point including the height above takeoff (m)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distance_from_home_3d: Vec<Option<f64>>,
+    /// Battery cell voltages, one series per cell (empty when not logged)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cell_voltages: Vec<Vec<Option<f64>>>,
 }
 
 impl TelemetryData {
@@ -331,6 +954,12 @@ impl TelemetryData {
         let mut is_photo = Vec::with_capacity(n);
         let mut is_video = Vec::with_capacity(n);
         let mut flight_mode = Vec::with_capacity(n);
+        let mut compass_error = Vec::with_capacity(n);
+        let mut compass_interference = Vec::with_capacity(n);
+        let mut imu_status = Vec::with_capacity(n);
+        let mut wind_speed = Vec::with_capacity(n);
+        let mut wind_direction = Vec::with_capacity(n);
+        let mut cumulative_distance = Vec::with_capacity(n);
 
         for r in records {
             time.push((r.timestamp_ms - base_time) as f64 / 1000.0);
@@ -360,8 +989,21 @@ impl TelemetryData {
             is_photo.push(r.is_photo);
             is_video.push(r.is_video);
             flight_mode.push(r.flight_mode.clone());
+            compass_error.push(r.compass_error);
+            compass_interference.push(r.compass_interference);
+            imu_status.push(r.imu_status.clone());
+            wind_speed.push(r.wind_speed);
+            wind_direction.push(r.wind_direction);
+            cumulative_distance.push(r.cumulative_distance);
         }
 
+        // Height above takeoff, or the altitude for logs without it
+        let vertical_speed = if height.iter().any(Option::is_some) {
+            crate::derived::vertical_speed(&time, &height)
+        } else {
+            crate::derived::vertical_speed(&time, &altitude)
+        };
+
         Self {
             time,
             latitude,
@@ -373,6 +1015,7 @@ impl TelemetryData {
             velocity_x,
             velocity_y,
             velocity_z,
+            vertical_speed,
             battery,
             battery_voltage,
             battery_temp,
@@ -390,29 +1033,43 @@ impl TelemetryData {
             is_photo,
             is_video,
             flight_mode,
+            compass_error,
+            compass_interference,
+            imu_status,
+            wind_speed,
+            wind_direction,
+            cumulative_distance,
+            distance_from_home: Vec::new(),
+            estimated_wind_speed: Vec::new(),
+            estimated_wind_direction: Vec::new(),
+            distance_from_home_3d: Vec::new(),
+            cell_voltages: Vec::new(),
         }
     }
 
     /// Extract a GPS track from the telemetry data for map visualization.
     ///
-    /// Filters out null/zero coordinates and downsamples to `max_points`
-    /// using uniform stride. Returns `[lng, lat, height]` triples.
-    pub fn extract_track(&self, max_points: usize) -> Vec<[f64; 3]> {
+    /// Filters out null/zero coordinates and GPS glitches, smooths the path
+    /// with `smoothing`, and downsamples to `max_points` using uniform
+    /// stride. Returns `[lng, lat, height]` triples.
+    pub fn extract_track(&self, max_points: usize, smoothing: TrackSmoothing) -> Vec<[f64; 3]> {
+        let fixes = crate::gps_filter::filter_positions(
+            self.time.iter()
+                .zip(self.latitude.iter().zip(self.longitude.iter()))
+                .map(|(t, (lat, lng))| ((t * 1000.0) as i64, *lat, *lng)),
+        );
+
         // Collect valid GPS points
-        let valid: Vec<[f64; 3]> = self.latitude.iter()
-            .zip(self.longitude.iter())
+        let (time, mut valid): (Vec<f64>, Vec<[f64; 3]>) = fixes.into_iter()
+            .zip(self.time.iter())
             .zip(self.height.iter().zip(self.vps_height.iter().zip(self.altitude.iter())))
-            .filter_map(|((lat, lng), (h, (vps, alt)))| {
-                let lat_v = (*lat)?;
-                let lng_v = (*lng)?;
-                // Skip 0,0 points
-                if lat_v.abs() < 0.000001 && lng_v.abs() < 0.000001 {
-                    return None;
-                }
+            .filter_map(|((fix, t), (h, (vps, alt)))| {
+                let (lat_v, lng_v) = fix?;
                 let height_v = h.or(*vps).or(*alt).unwrap_or(0.0);
-                Some([lng_v, lat_v, height_v])
+                Some((*t, [lng_v, lat_v, height_v]))
             })
-            .collect();
+            .unzip();
+        crate::track_smoothing::smooth(&time, &mut valid, smoothing);
 
         if valid.len() <= max_points {
             return valid;
@@ -426,6 +1083,49 @@ impl TelemetryData {
     }
 }
 
+/// Smoothing of the map track, see `track_smoothing`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackSmoothing {
+    #[default]
+    Off,
+    /// Exponential moving average
+    Ema,
+    /// Constant-velocity Kalman smoother
+    Kalman,
+}
+
+/// Source of ground elevations, see `terrain`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TerrainProvider {
+    #[default]
+    Off,
+    OpenMeteo,
+    /// SRTM tiles in a folder
+    Local,
+}
+
+/// Where ground elevations for the height above terrain come from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerrainSettings {
+    pub provider: TerrainProvider,
+    /// Folder of `.hgt` tiles for the local provider
+    #[serde(default)]
+    pub dem_directory: Option<String>,
+}
+
+/// Terrain under a flight, aligned to its telemetry points
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerrainProfile {
+    /// Ground elevation above sea level under each point (m)
+    pub ground_elevation: Vec<Option<f64>>,
+    /// Height above the ground under each point (m)
+    pub agl: Vec<Option<f64>>,
+}
+
 /// Import result returned to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -437,6 +1137,144 @@ pub struct ImportResult {
     pub file_hash: Option<String>,
 }
 
+/// Outcome of a batch export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExportResult {
+    /// Flights matching the filter
+    pub total: usize,
+    pub exported: usize,
+    /// Flights that could not be exported; `summary.csv` says why
+    pub failed: usize,
+    /// Folder the files were written to (None for a downloaded archive)
+    pub output_path: Option<String>,
+}
+
+/// Pilot shown on flight reports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PilotDetails {
+    pub name: Option<String>,
+    /// Remote pilot license or operator registration number
+    pub license: Option<String>,
+}
+
+/// A recency requirement, e.g. an insurer's 3 flights in the last 90 days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRequirement {
+    pub window_days: u32,
+    #[serde(default)]
+    pub min_flights: u32,
+    #[serde(default)]
+    pub min_hours: f64,
+}
+
+/// Flights and flight time within the last `days` days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyWindow {
+    pub days: u32,
+    pub flight_count: i64,
+    pub duration_secs: f64,
+}
+
+/// A requirement and whether the recent flying meets it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyCheck {
+    pub requirement: CurrencyRequirement,
+    pub flight_count: i64,
+    pub duration_secs: f64,
+    pub met: bool,
+}
+
+/// Recent flying of the pilot or of one aircraft
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyEntry {
+    /// Pilot name, or the aircraft's nickname or name
+    pub name: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub last_flight: Option<String>,
+    /// Shortest window first
+    pub windows: Vec<CurrencyWindow>,
+    pub checks: Vec<CurrencyCheck>,
+    /// Every requirement is met
+    pub current: bool,
+}
+
+/// Currency of the pilot and of each aircraft
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyReport {
+    pub pilot: CurrencyEntry,
+    /// Most recently flown first
+    pub drones: Vec<CurrencyEntry>,
+}
+
+/// Height and distance limits flights are checked against for compliance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceLimits {
+    /// Height above takeoff in meters; None turns the check off
+    pub max_height_m: Option<f64>,
+    /// Horizontal distance from home in meters; None turns the check off
+    pub max_distance_m: Option<f64>,
+}
+
+/// Stretch of a flight over a compliance limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceSegment {
+    /// "height" or "distance"
+    pub kind: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Highest height or farthest distance within the stretch, in meters
+    pub peak_m: f64,
+    /// Where the stretch began
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+}
+
+/// Flight with stretches over the compliance limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceFlight {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    /// In time order
+    pub segments: Vec<ComplianceSegment>,
+}
+
+/// Flights counted in statistics checked against the compliance limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceReport {
+    pub limits: ComplianceLimits,
+    pub flight_count: i64,
+    /// Flights over the height limit
+    pub height_violations: i64,
+    /// Flights over the distance limit
+    pub distance_violations: i64,
+    /// Flights over a limit, newest first
+    pub flights: Vec<ComplianceFlight>,
+}
+
+/// Copying every newly imported flight to a folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoExportSettings {
+    pub enabled: bool,
+    pub directory: Option<String>,
+    pub formats: Vec<crate::export::ExportFormat>,
+}
+
 /// Statistics for a flight
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -453,3 +1291,483 @@ pub struct FlightStats {
     pub end_battery_percent: Option<i32>,
     pub start_battery_temp: Option<f64>,
 }
+
+/// Two consecutive flights that look like one physical flight split across
+/// log files (same aircraft, short gap, continuous battery level)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StitchCandidate {
+    pub first_id: i64,
+    pub first_name: String,
+    pub second_id: i64,
+    pub second_name: String,
+    pub gap_secs: f64,
+    pub end_battery_percent: Option<i32>,
+    pub start_battery_percent: Option<i32>,
+}
+
+/// Source file linked to an existing flight (e.g. the aircraft DAT log of a
+/// flight imported from the app TXT log)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightAttachment {
+    pub file_hash: String,
+    pub file_name: String,
+    pub source_format: String,
+    pub point_count: i32,
+    pub attached_at: Option<String>,
+}
+
+/// Single waypoint of a planned mission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Waypoint {
+    pub index: i32,
+    pub lat: f64,
+    pub lon: f64,
+    /// Planned height in meters (relative to takeoff unless the mission says otherwise)
+    pub height: Option<f64>,
+    /// Planned speed in m/s
+    pub speed: Option<f64>,
+}
+
+/// Parsed waypoint mission ready for insertion
+#[derive(Debug, Clone)]
+pub struct MissionMetadata {
+    pub name: String,
+    pub file_name: String,
+    pub file_hash: String,
+    pub drone_model: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// Stored waypoint mission with the flights that executed it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mission {
+    pub id: i64,
+    pub name: String,
+    pub file_name: String,
+    pub drone_model: Option<String>,
+    pub created_at: Option<String>,
+    pub imported_at: Option<String>,
+    pub waypoints: Vec<Waypoint>,
+    pub flight_ids: Vec<i64>,
+}
+
+/// Home point position at a moment of the flight. A flight has one entry for
+/// the takeoff home point plus one per mid-flight update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomePoint {
+    pub timestamp_ms: i64,
+    pub lat: f64,
+    pub lon: f64,
+    pub altitude: Option<f64>,
+}
+
+/// User note pinned to a moment of the flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightAnnotation {
+    pub id: i64,
+    /// Seconds from the first telemetry point, on the same axis as `TelemetryData.time`
+    pub offset_secs: f64,
+    pub text: String,
+    pub created_at: Option<String>,
+}
+
+/// Safety incident reported against a moment of a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Incident {
+    pub id: i64,
+    pub flight_id: i64,
+    pub flight_name: Option<String>,
+    pub flight_start_time: Option<String>,
+    /// Seconds from the first telemetry point, on the same axis as `TelemetryData.time`
+    pub offset_secs: f64,
+    /// "crash", "flyaway", "near_miss", "equipment_failure" or "other"
+    pub kind: String,
+    /// "minor", "moderate", "serious" or "critical"
+    pub severity: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// Event along the track: a camera capture, a settings change or an app warning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightEvent {
+    pub timestamp_ms: i64,
+    /// "photo", "video", "camera_settings", "gimbal_mode", "tip" or "warning"
+    pub event_type: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub altitude: Option<f64>,
+    /// Recording length for video events
+    pub duration_ms: Option<i64>,
+    /// New value for settings changes (e.g. "ISO 100, 1/1000, f/2.8", "FPV"),
+    /// message text for tips and warnings
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// "info", "warning" or "critical" for tips and warnings
+    #[serde(default)]
+    pub severity: Option<String>,
+}
+
+/// Entry of a flight's event timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    /// Seconds from the first telemetry point, on the same axis as `TelemetryData.time`
+    pub offset_secs: f64,
+    /// "takeoff", "landing", "mode", "rth", "photo", "video" or "warning"
+    pub kind: String,
+    /// Readable description, e.g. "Mode changed to Sport"
+    pub label: String,
+    /// New mode for mode changes, message text for warnings
+    pub detail: Option<String>,
+    /// Recording length for videos
+    pub duration_secs: Option<f64>,
+    /// "info", "warning" or "critical" for warnings
+    pub severity: Option<String>,
+}
+
+/// Range of a flight spent in one phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightPhase {
+    /// "ground", "ascent", "cruise", "hover", "descent" or "rth"
+    pub phase: String,
+    /// Seconds from the first telemetry point, on the same axis as `TelemetryData.time`
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+/// How often one warning message occurred across all flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarningSummary {
+    pub message: String,
+    pub severity: String,
+    pub occurrences: i64,
+    pub flight_count: i64,
+    pub last_seen: Option<String>,
+}
+
+/// Flight mode change, RC signal loss or app message, the input of RTH statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafetyEvent {
+    pub timestamp_ms: i64,
+    /// "mode", "signal_loss" or "message"
+    pub kind: String,
+    /// New mode of a mode change, text of a message
+    pub detail: Option<String>,
+}
+
+/// Flight counted in statistics with its safety events in time order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightSafetyEvents {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub events: Vec<SafetyEvent>,
+}
+
+/// RTH triggers, failsafe activations and RC signal losses of some flights
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RthEventCounts {
+    pub flight_count: i64,
+    pub rth_count: i64,
+    pub failsafe_count: i64,
+    pub signal_loss_count: i64,
+    /// Flights with at least one of the events
+    pub affected_flights: i64,
+}
+
+/// RTH event counts of one month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthRthEvents {
+    /// YYYY-MM
+    pub month: String,
+    pub counts: RthEventCounts,
+}
+
+/// RTH event counts of one aircraft, keyed like `DroneStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneRthEvents {
+    pub drone_model: String,
+    pub drone_serial: Option<String>,
+    pub counts: RthEventCounts,
+    /// Oldest first
+    pub months: Vec<MonthRthEvents>,
+}
+
+/// RTH event counts of one flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightRthEvents {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub rth_count: i64,
+    pub failsafe_count: i64,
+    pub signal_loss_count: i64,
+}
+
+/// RTH, failsafe and signal-loss statistics of the flights counted in statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RthStats {
+    pub totals: RthEventCounts,
+    /// Most RTH triggers per flight first
+    pub drones: Vec<DroneRthEvents>,
+    /// Oldest first
+    pub months: Vec<MonthRthEvents>,
+    /// Flights with events, newest first
+    pub flights: Vec<FlightRthEvents>,
+}
+
+/// Aggregates of one side of a drone comparison: the flights of an aircraft
+/// (by serial) or of every aircraft of a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneComparisonStats {
+    /// Serial or model the side was asked for
+    pub drone: String,
+    /// Model of the newest flight
+    pub drone_model: Option<String>,
+    /// Set when the flights are from a single aircraft
+    pub drone_serial: Option<String>,
+    /// User-set name from the drone profile
+    pub nickname: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+    pub avg_duration_secs: f64,
+    pub avg_distance_m: f64,
+    pub max_altitude_m: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    pub wh_per_km: Option<f64>,
+    pub wh_per_min: Option<f64>,
+    /// Mean logged wind speed (m/s), weighted by flight time
+    pub avg_wind_speed: Option<f64>,
+    /// Flights that logged wind
+    pub wind_flight_count: i64,
+    /// Incidents reported against the flights
+    pub incident_count: i64,
+    pub safety_events: RthEventCounts,
+}
+
+/// Two aircraft or models side by side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneComparison {
+    pub first: DroneComparisonStats,
+    pub second: DroneComparisonStats,
+}
+
+/// Flight matching a search query, best matches scored highest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightSearchHit {
+    pub flight_id: i64,
+    pub score: f64,
+}
+
+/// Photo or video found while scanning a media folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaFile {
+    pub file_path: String,
+    pub file_name: String,
+    /// "photo" or "video"
+    pub media_type: String,
+    pub captured_at: Option<DateTime<Utc>>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub camera_serial: Option<String>,
+}
+
+/// Media file associated with a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightMedia {
+    pub file_path: String,
+    pub file_name: String,
+    pub media_type: String,
+    pub captured_at: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Seconds since flight start (negative when captured before takeoff)
+    pub offset_secs: Option<f64>,
+}
+
+/// File attached to a flight, such as an airspace authorization or a client brief
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightDocument {
+    pub id: i64,
+    pub flight_id: i64,
+    pub file_name: String,
+    /// SHA-256 of the content, hex encoded
+    pub sha256: String,
+    pub size_bytes: i64,
+    pub added_at: Option<String>,
+}
+
+/// Summary of a media folder scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaScanResult {
+    pub scanned: usize,
+    /// Files with a readable capture time
+    pub timestamped: usize,
+    /// Files associated with a flight
+    pub matched: usize,
+}
+
+/// Motor/ESC reading for one motor at one moment (DAT logs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotorSample {
+    pub timestamp_ms: i64,
+    /// Zero-based motor position as numbered by the flight controller
+    pub motor_index: i32,
+    pub rpm: Option<f64>,
+    /// ESC current in amps
+    pub current: Option<f64>,
+    /// ESC temperature in °C
+    pub temperature: Option<f64>,
+}
+
+/// Motor/ESC series for one motor, one value per `TelemetryData.time` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotorSeries {
+    pub motor_index: i32,
+    pub rpm: Vec<Option<f64>>,
+    pub current: Vec<Option<f64>>,
+    pub temperature: Vec<Option<f64>>,
+}
+
+/// A logbook library: one database file, e.g. a personal or a work logbook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Library {
+    pub name: String,
+    pub path: String,
+    /// The library in the app data directory used before any switch
+    pub is_default: bool,
+    pub is_active: bool,
+    /// False when the file was moved or sits on a drive that isn't attached
+    pub exists: bool,
+    pub last_opened: Option<String>,
+}
+
+/// Outcome of a library integrity check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryCheckReport {
+    /// True when the issues were only reported, not repaired
+    pub dry_run: bool,
+    pub tables_checked: usize,
+    pub issues: Vec<LibraryIssue>,
+}
+
+/// One kind of problem found by a library check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryIssue {
+    /// "unreadable_table", "orphaned_rows", "dangling_reference",
+    /// "missing_file", "unused_file" or "empty_flights"
+    pub kind: String,
+    pub table: Option<String>,
+    pub description: String,
+    /// Rows, files or flights affected
+    pub count: i64,
+    pub repairable: bool,
+}
+
+/// Filter definition of a smart list, mirroring the flight list filters.
+/// Every criterion that is set must match; with `inverted` a flight must
+/// match none of them instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlightFilter {
+    /// First and last day, inclusive (YYYY-MM-DD)
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    /// Drone serials, or `model:<name>` for drones that report none
+    pub drones: Vec<String>,
+    pub batteries: Vec<String>,
+    /// Site ids; see `sites`
+    pub sites: Vec<i64>,
+    /// Flights must carry all of these tags
+    pub tags: Vec<String>,
+    pub min_duration_mins: Option<f64>,
+    pub max_duration_mins: Option<f64>,
+    /// Bounds on max altitude in meters
+    pub min_altitude: Option<f64>,
+    pub max_altitude: Option<f64>,
+    /// Bounds on total distance in meters
+    pub min_distance: Option<f64>,
+    pub max_distance: Option<f64>,
+    /// Full-text search; always applies, even when `inverted`
+    pub search: Option<String>,
+    /// Only starred flights; always applies, even when `inverted`
+    pub favorites_only: bool,
+    pub inverted: bool,
+}
+
+/// One entry of the audit log: an import, merge, deletion or edit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: i64,
+    pub logged_at: String,
+    /// `import`, `merge`, `delete`, `restore`, `purge`, `rename`, ...
+    pub action: String,
+    /// None for actions on the whole library
+    pub flight_id: Option<i64>,
+    /// Log file and hash of the flight at the time of the action
+    pub source_file: Option<String>,
+    pub file_hash: Option<String>,
+    pub app_version: Option<String>,
+    pub details: Option<String>,
+}
+
+/// A saved, named flight filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartList {
+    pub id: i64,
+    pub name: String,
+    pub filter: FlightFilter,
+    pub updated_at: Option<String>,
+}
+
+/// A saved export setup, so a recurring export is one click: the file
+/// format, the telemetry columns of CSV files (all when empty) and the units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTemplate {
+    pub id: i64,
+    pub name: String,
+    pub format: crate::export::ExportFormat,
+    pub columns: Vec<String>,
+    pub units: crate::export::ExportUnits,
+    pub updated_at: Option<String>,
+}
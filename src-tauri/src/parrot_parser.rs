@@ -0,0 +1,236 @@
+//! Parser module for Parrot Anafi / Bebop PUD flight logs (JSON flavour).
+//!
+//! A PUD JSON file has top-level metadata (`product_name`, `serial_number`,
+//! `date`, ...) plus a columnar body: `details_headers` names the columns and
+//! `details_data` holds one array per sample. Units follow Parrot's convention:
+//! time in milliseconds, altitude in millimeters, angles in radians, and GPS
+//! coordinates set to 500.0 when no fix is available.
+
+use std::io::Read;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, LogSource, ParseResult, ParserError};
+
+/// Sentinel coordinate Parrot writes when GPS is unavailable
+const PARROT_NO_GPS: f64 = 500.0;
+
+/// Parrot PUD JSON Parser
+pub struct ParrotParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> ParrotParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Check if a file is a Parrot PUD JSON log
+    pub fn is_parrot_log(source: &LogSource) -> bool {
+        let path = source.path();
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !ext.eq_ignore_ascii_case("pud") && !ext.eq_ignore_ascii_case("json") {
+            return false;
+        }
+
+        // The headers appear near the top of the file; avoid reading the whole body
+        let mut head = Vec::with_capacity(4096);
+        match source.open().and_then(|f| f.take(4096).read_to_end(&mut head)) {
+            Ok(_) => {
+                let head = String::from_utf8_lossy(&head);
+                head.trim_start().starts_with('{') && head.contains("\"details_headers\"")
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Parse a Parrot PUD JSON file
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing Parrot PUD file: {:?}", file_path);
+
+        let mut content = String::new();
+        source.open()?.read_to_string(&mut content)?;
+        let root: Value = serde_json::from_str(&content)
+            .map_err(|e| ParserError::Parse(format!("Invalid PUD JSON: {}", e)))?;
+
+        let headers: Vec<String> = root
+            .get("details_headers")
+            .and_then(|h| h.as_array())
+            .ok_or_else(|| ParserError::Parse("PUD file has no details_headers".to_string()))?
+            .iter()
+            .map(|h| h.as_str().unwrap_or("").to_string())
+            .collect();
+        let col = |name: &str| headers.iter().position(|h| h == name);
+
+        let rows = root
+            .get("details_data")
+            .and_then(|d| d.as_array())
+            .ok_or(ParserError::NoTelemetryData)?;
+
+        let idx_time = col("time");
+        let idx_lat = col("product_gps_latitude");
+        let idx_lon = col("product_gps_longitude");
+        let idx_sats = col("product_gps_sv_number");
+        let idx_alt = col("altitude");
+        let idx_vx = col("speed_vx");
+        let idx_vy = col("speed_vy");
+        let idx_vz = col("speed_vz");
+        let idx_speed = col("speed");
+        let idx_roll = col("angle_phi");
+        let idx_pitch = col("angle_theta");
+        let idx_yaw = col("angle_psi");
+        let idx_battery = col("battery_level");
+        let idx_state = col("flying_state");
+        let idx_wifi = col("wifi_signal");
+
+        let num = |row: &[Value], idx: Option<usize>| -> Option<f64> {
+            row.get(idx?).and_then(|v| v.as_f64()).filter(|v| v.is_finite())
+        };
+
+        let mut points = Vec::with_capacity(rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            let row = match row.as_array() {
+                Some(r) => r.as_slice(),
+                None => continue,
+            };
+
+            let lat = num(row, idx_lat);
+            let lon = num(row, idx_lon);
+            let has_gps = matches!((lat, lon), (Some(la), Some(lo))
+                if la != PARROT_NO_GPS && lo != PARROT_NO_GPS && la.abs() <= 90.0 && lo.abs() <= 180.0
+                    && !(la.abs() < 1e-6 && lo.abs() < 1e-6));
+
+            let vx = num(row, idx_vx);
+            let vy = num(row, idx_vy);
+            let speed = num(row, idx_speed).or_else(|| match (vx, vy) {
+                (Some(x), Some(y)) => Some((x * x + y * y).sqrt()),
+                _ => None,
+            });
+            let altitude = num(row, idx_alt).map(|mm| mm / 1000.0);
+
+            points.push(TelemetryPoint {
+                timestamp_ms: num(row, idx_time).map(|t| t as i64).unwrap_or(i as i64 * 100),
+                latitude: if has_gps { lat } else { None },
+                longitude: if has_gps { lon } else { None },
+                altitude,
+                height: altitude,
+                speed,
+                velocity_x: vx,
+                velocity_y: vy,
+                velocity_z: num(row, idx_vz),
+                pitch: num(row, idx_pitch).map(f64::to_degrees),
+                roll: num(row, idx_roll).map(f64::to_degrees),
+                yaw: num(row, idx_yaw).map(f64::to_degrees),
+                battery_percent: num(row, idx_battery).map(|v| v as i32),
+                flight_mode: num(row, idx_state).map(|v| Self::flying_state_name(v as i64).to_string()),
+                satellites: num(row, idx_sats).map(|v| v as i32),
+                rc_signal: num(row, idx_wifi).map(|v| v as i32),
+                ..Default::default()
+            });
+        }
+
+        if !points.iter().any(|p| p.latitude.is_some() && p.longitude.is_some()) {
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let text = |key: &str| {
+            root.get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let start_time = text("date").and_then(|d| Self::parse_pud_date(&d));
+        let drone_model = text("product_name")
+            .map(|name| if name.to_lowercase().starts_with("parrot") { name } else { format!("Parrot {}", name) });
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model,
+            drone_serial: text("serial_number").map(|s| s.to_uppercase()),
+            aircraft_name: None,
+            battery_serial: None,
+            start_time,
+            end_time: start_time
+                .map(|s| s + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64)),
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(&points, stats.home_location),
+        };
+
+        log::info!(
+            "Parrot parse complete in {:.1}s: model={:?}, duration={:.1}s, distance={:.0}m, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            metadata.drone_model,
+            stats.duration_secs,
+            stats.total_distance_m,
+            points.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "Parrot".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors: Vec::new(), events: Vec::new() })
+    }
+
+    /// Parse the PUD `date` field, e.g. `2021-06-05T142231+0200`
+    fn parse_pud_date(s: &str) -> Option<DateTime<Utc>> {
+        if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%dT%H%M%S%z") {
+            return Some(dt.with_timezone(&Utc));
+        }
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            return Some(dt.with_timezone(&Utc));
+        }
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H%M%S")
+            .ok()
+            .map(|ndt| DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc))
+    }
+
+    /// Map the ARCommands flying state enum to a readable name
+    fn flying_state_name(state: i64) -> &'static str {
+        match state {
+            0 => "Landed",
+            1 => "TakingOff",
+            2 => "Hovering",
+            3 => "Flying",
+            4 => "Landing",
+            5 => "Emergency",
+            6 => "UserTakeOff",
+            7 => "MotorRamping",
+            8 => "EmergencyLanding",
+            _ => "Unknown",
+        }
+    }
+}
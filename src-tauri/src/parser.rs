@@ -18,18 +18,258 @@ use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tokio::time::timeout;
 
-use dji_log_parser::frame::Frame;
+use dji_log_parser::frame::{records_to_frames, Frame};
+use dji_log_parser::keychain::KeychainFeaturePoint;
+use dji_log_parser::record::Record;
 use dji_log_parser::DJILog;
 
+use crate::airdata_parser::AirDataParser;
 use crate::api::DjiApi;
+use crate::autel_parser::AutelParser;
+use crate::dat_parser::DatParser;
 use crate::database::Database;
 use crate::dronelogbook_parser::DroneLogbookParser;
 use crate::litchi_parser::LitchiParser;
-use crate::models::{FlightMetadata, FlightStats, TelemetryPoint};
+use crate::models::{FlightEvent, FlightMetadata, FlightStats, HomePoint, MotorSample, TelemetryPoint};
+use crate::parrot_parser::ParrotParser;
+use crate::srt_parser::SrtParser;
 
 /// Maximum time allowed for parsing a single log file (seconds)
 const PARSE_TIMEOUT_SECS: u64 = 40;
 
+/// Size of the TXT log prefix (header) before the records area
+const LOG_PREFIX_LEN: usize = 100;
+
+/// Prefix size of the first log versions
+const LOG_PREFIX_LEN_OLD: usize = 12;
+
+/// Offset of the format version byte inside the prefix
+const LOG_VERSION_OFFSET: usize = 10;
+
+/// Versions below this have a shorter prefix
+const LOG_FULL_PREFIX_VERSION: u8 = 6;
+
+/// From this version on the details block follows the prefix and the records
+/// run to the end of the file, instead of the details trailing the records
+const LOG_DETAILS_FIRST_VERSION: u8 = 12;
+
+/// How far back from the end of the records area to cut when salvaging a
+/// damaged log, tried in order until the records decode cleanly
+const RECOVERY_TRIM_FRACTIONS: &[f64] = &[0.0, 0.005, 0.02, 0.05, 0.1, 0.25];
+
+/// Highest TXT record version written by DJI GO / GO 4 (V1–V12).
+/// These logs are unencrypted but often leave header fields empty.
+const LEGACY_LOG_VERSION_MAX: u8 = 12;
+
+/// Any start time before 2010-01-01 is treated as an unset header value
+const MIN_VALID_START_TIMESTAMP: i64 = 1_262_304_000;
+
+/// File extensions accepted by the importer (lowercase, without dot)
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["txt", "csv", "dat", "pud", "json", "srt"];
+
+/// Check whether a file name has one of the supported log extensions
+pub fn is_supported_log_file(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .any(|ext| lower.ends_with(&format!(".{}", ext)))
+}
+
+/// Whether a file found by a folder scan (sync, watcher) should be imported.
+/// JSON and SRT files are mostly app settings and video subtitles, so those
+/// only count when their content looks like a Parrot log or DJI telemetry.
+pub fn is_importable_log_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    if !is_supported_log_file(name) {
+        return false;
+    }
+    match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+        Some("json") => ParrotParser::is_parrot_log(&LogSource::File(path)),
+        Some("srt") => SrtParser::is_dji_srt(&LogSource::File(path)),
+        _ => true,
+    }
+}
+
+/// Where the bytes of a log come from: a file on disk, or an archive entry
+/// already read into memory. Parsers read through `open` and take the file
+/// name from `path`, so both are parsed the same way.
+#[derive(Clone, Copy)]
+pub enum LogSource<'s> {
+    File(&'s Path),
+    /// `path` names the entry (only its file name is used)
+    Memory { path: &'s Path, data: &'s [u8] },
+}
+
+impl<'s> LogSource<'s> {
+    pub fn path(&self) -> &'s Path {
+        match *self {
+            LogSource::File(path) => path,
+            LogSource::Memory { path, .. } => path,
+        }
+    }
+
+    /// Reader over the log's bytes from the start
+    pub fn open(&self) -> std::io::Result<Box<dyn Read + 's>> {
+        match *self {
+            LogSource::File(path) => Ok(Box::new(File::open(path)?)),
+            LogSource::Memory { data, .. } => Ok(Box::new(data)),
+        }
+    }
+
+    /// Size in bytes, 0 if unknown
+    pub fn size(&self) -> u64 {
+        match *self {
+            LogSource::File(path) => fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            LogSource::Memory { data, .. } => data.len() as u64,
+        }
+    }
+
+    /// Modified time of a file on disk; archive entries have none we trust
+    pub fn modified(&self) -> Option<std::time::SystemTime> {
+        match *self {
+            LogSource::File(path) => fs::metadata(path).and_then(|m| m.modified()).ok(),
+            LogSource::Memory { .. } => None,
+        }
+    }
+
+    /// All of the log's bytes
+    pub fn read_all(&self) -> std::io::Result<Vec<u8>> {
+        match *self {
+            LogSource::File(path) => fs::read(path),
+            LogSource::Memory { data, .. } => Ok(data.to_vec()),
+        }
+    }
+}
+
+/// Warning texts that indicate the aircraft itself is in trouble rather than
+/// a condition the pilot should watch (matched case-insensitively)
+const CRITICAL_WARNING_KEYWORDS: &[&str] = &[
+    "critical",
+    "forced landing",
+    "landing immediately",
+    "motor",
+    "propulsion",
+    "esc ",
+    "imu error",
+    "compass error",
+    "sensor error",
+    "overheat",
+    "aircraft disconnected",
+];
+
+/// Minimum move of the recorded home point to count as a home point update
+const HOME_POINT_MIN_SHIFT_M: f64 = 5.0;
+
+/// Grid size of the trajectory footprint (degrees, ~110 m)
+const TRAJECTORY_CELL_DEG: f64 = 0.001;
+
+/// Time the aircraft must spend in a grid cell for it to count towards the
+/// trajectory footprint. Filters out corners clipped by only one source.
+const TRAJECTORY_MIN_DWELL_MS: i64 = 2_000;
+
+/// Longest sample interval credited to a cell (bridges GPS dropouts only briefly)
+const TRAJECTORY_MAX_STEP_MS: i64 = 1_000;
+
+/// Share of grid cells two tracks must have in common to be the same flight.
+/// Below 1 so cells one log only grazes near a boundary don't break the match.
+const TRAJECTORY_MIN_OVERLAP: f64 = 0.8;
+
+/// Shortest stretch two tracks must share when lining them up in time
+const TRACK_ALIGN_MIN_SECS: usize = 30;
+
+/// Maximum nesting depth when descending into ZIP archives inside ZIP archives
+const MAX_ZIP_DEPTH: usize = 4;
+
+/// Largest ZIP entry read into memory. The size in the entry header is not
+/// trusted; reading stops here and the entry is skipped.
+const MAX_ZIP_ENTRY_LEN: u64 = 512 * 1024 * 1024;
+
+/// Total bytes decompressed from one archive, nested archives included;
+/// whatever is left once it is used up is skipped
+const MAX_ZIP_TOTAL_LEN: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Walk every supported log file in a ZIP archive, handing each one to
+/// `visit` as soon as it is decompressed.
+///
+/// Nested `.zip` entries are opened in memory as well, so nothing is
+/// extracted to disk and only the current log (plus the nested archives
+/// around it) is held at a time. `visit` gets the entry path, joined with `/`
+/// for nested files (e.g. `2023.zip/DJIFlightRecord_x.txt`), and the contents,
+/// and returns false to stop the walk.
+pub fn for_each_zip_entry<R, F>(reader: R, mut visit: F) -> Result<(), ParserError>
+where
+    R: Read + std::io::Seek,
+    F: FnMut(String, Vec<u8>) -> bool,
+{
+    let mut budget = MAX_ZIP_TOTAL_LEN;
+    walk_zip_entries(reader, "", 0, &mut budget, &mut visit).map(|_| ())
+}
+
+/// Returns false once the walk has been stopped, by `visit` or the budget
+fn walk_zip_entries<R: Read + std::io::Seek>(
+    reader: R,
+    prefix: &str,
+    depth: usize,
+    budget: &mut u64,
+    visit: &mut dyn FnMut(String, Vec<u8>) -> bool,
+) -> Result<bool, ParserError> {
+    let mut archive =
+        zip::ZipArchive::new(reader).map_err(|e| ParserError::Parse(format!("Invalid ZIP archive: {}", e)))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| ParserError::Parse(format!("Failed to read ZIP entry: {}", e)))?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        let name = format!("{}{}", prefix, entry.name());
+        let lower = name.to_lowercase();
+        let is_nested_zip = lower.ends_with(".zip");
+        if !is_nested_zip && !is_supported_log_file(&name) {
+            continue;
+        }
+
+        // Header sizes are not trusted: read up to the limits and check what came out
+        let mut contents = Vec::new();
+        entry.by_ref().take(MAX_ZIP_ENTRY_LEN.min(*budget) + 1).read_to_end(&mut contents)?;
+        let read = contents.len() as u64;
+        if read > *budget {
+            log::warn!(
+                "Stopping at {} — archive holds more than {} MB of data",
+                name,
+                MAX_ZIP_TOTAL_LEN / (1024 * 1024)
+            );
+            return Ok(false);
+        }
+        *budget -= read;
+        if read > MAX_ZIP_ENTRY_LEN {
+            log::warn!("Skipping {} — larger than {} MB", name, MAX_ZIP_ENTRY_LEN / (1024 * 1024));
+            continue;
+        }
+
+        if is_nested_zip {
+            if depth + 1 >= MAX_ZIP_DEPTH {
+                log::warn!("Skipping nested archive {} — nesting too deep", name);
+                continue;
+            }
+            // A corrupt inner archive should not abort the rest of the outer one
+            match walk_zip_entries(std::io::Cursor::new(contents), &format!("{}/", name), depth + 1, budget, visit) {
+                Ok(true) => {}
+                Ok(false) => return Ok(false),
+                Err(e) => log::warn!("Skipping nested archive {}: {}", name, e),
+            }
+        } else if !visit(name, contents) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
 #[derive(Error, Debug)]
 pub enum ParserError {
     #[error("IO error: {0}")]
@@ -41,6 +281,12 @@ pub enum ParserError {
     #[error("File already imported (matches: {0})")]
     AlreadyImported(String),
 
+    #[error("File belongs to a flight in the trash: {0}")]
+    InTrash(String),
+
+    #[error("Linked as rich data to existing flight: {1}")]
+    LinkedToFlight(i64, String),
+
     #[error("No valid telemetry data found")]
     NoTelemetryData,
 
@@ -56,7 +302,7 @@ pub enum ParserError {
     #[error("Parsing timed out after {0} seconds — file may be corrupt or unsupported")]
     Timeout(u64),
 
-    #[error("Incompatible file format — only DJI flight logs (.txt), Litchi CSV exports, and Drone Logbook CSV exports are supported")]
+    #[error("Incompatible file format — only DJI flight logs (.txt), DJI flight controller logs (.DAT), Litchi/AirData/Autel CSV exports, Parrot PUD logs, DJI video SRT files, and Drone Logbook CSV exports are supported")]
     IncompatibleFile,
 }
 
@@ -65,6 +311,36 @@ pub struct ParseResult {
     pub metadata: FlightMetadata,
     pub points: Vec<TelemetryPoint>,
     pub tags: Vec<String>,
+    /// Set when the log was salvaged from a truncated or corrupt file
+    pub recovery: Option<PartialRecovery>,
+    /// Home point as recorded at takeoff and after every mid-flight update
+    pub home_points: Vec<HomePoint>,
+    /// Per-motor ESC readings (flight controller DAT logs only)
+    pub motors: Vec<MotorSample>,
+    /// Settings changes read from the log (capture events are derived from
+    /// telemetry when the flight is stored)
+    pub events: Vec<FlightEvent>,
+}
+
+/// How much of a damaged log could be recovered
+#[derive(Debug, Clone)]
+pub struct PartialRecovery {
+    pub bytes_recovered: usize,
+    pub total_bytes: usize,
+    pub records: usize,
+}
+
+impl ParseResult {
+    /// Summary shown to the user after the flight is saved
+    pub fn import_message(&self, point_count: usize) -> String {
+        match &self.recovery {
+            Some(r) => format!(
+                "Successfully imported {} telemetry points (partial log: recovered {} of {} bytes, {} records)",
+                point_count, r.bytes_recovered, r.total_bytes, r.records
+            ),
+            None => format!("Successfully imported {} telemetry points", point_count),
+        }
+    }
 }
 
 /// DJI Log Parser wrapper
@@ -99,18 +375,94 @@ impl<'a> LogParser<'a> {
         Ok(format!("{:x}", hasher.finalize()))
     }
 
-    /// Parse a flight log file (DJI .txt or Litchi .csv) and extract all telemetry data
+    /// Look for an existing flight recorded by another source (app TXT log)
+    /// that is the same physical flight as this DAT log. Candidates are the
+    /// flights taking off from the same place, nearest to the file's time
+    /// first; the one whose ground track covers most of the same grid cells
+    /// wins if the overlap is high enough. Returns the flight and the shift that puts this log's
+    /// timestamps on the flight's timeline.
+    fn find_cross_format_match(&self, result: &ParseResult) -> Option<(i64, String, i64)> {
+        let (home_lat, home_lon) = (result.metadata.home_lat?, result.metadata.home_lon?);
+        let track: Vec<(i64, f64, f64)> = result
+            .points
+            .iter()
+            .filter_map(|p| match (p.latitude, p.longitude) {
+                (Some(lat), Some(lon)) if !(lat == 0.0 && lon == 0.0) => Some((p.timestamp_ms, lat, lon)),
+                _ => None,
+            })
+            .collect();
+        let cells = trajectory_cells(&track);
+        if cells.is_empty() {
+            return None;
+        }
+
+        let candidates = match self.db.find_cross_format_candidates(
+            result.metadata.drone_serial.as_deref(),
+            result.metadata.start_time,
+            home_lat,
+            home_lon,
+        ) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("Cross-format duplicate check failed: {}", e);
+                return None;
+            }
+        };
+
+        let (overlap, flight_id, name, flight_track) = candidates
+            .into_iter()
+            .filter_map(|(flight_id, name)| {
+                let flight_track = self.db.get_flight_track(flight_id).ok()?;
+                let overlap = trajectory_overlap(&cells, &trajectory_cells(&flight_track));
+                Some((overlap, flight_id, name, flight_track))
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))?;
+        if overlap < TRAJECTORY_MIN_OVERLAP {
+            log::debug!("Closest flight {} shares only {:.0}% of the DAT track", flight_id, overlap * 100.0);
+            return None;
+        }
+
+        // Both logs start around power-up, so line up the first samples when
+        // the tracks are too short to line up by position
+        let offset_ms = track_time_offset(&track, &flight_track).unwrap_or_else(|| {
+            let dat_start = result.points.first().map_or(0, |p| p.timestamp_ms);
+            flight_track.first().map_or(0, |p| p.0) - dat_start
+        });
+        log::info!(
+            "DAT log matches existing flight {} ('{}') by trajectory ({:.0}% overlap, {} ms offset)",
+            flight_id,
+            name,
+            overlap * 100.0,
+            offset_ms
+        );
+        Some((flight_id, name, offset_ms))
+    }
+
+    /// Parse a flight log file (DJI .txt/.DAT, Litchi/AirData/Autel .csv, Parrot .pud, DJI .srt) and extract all telemetry data
     pub async fn parse_log(&self, file_path: &Path) -> Result<ParseResult, ParserError> {
+        self.parse_source(&LogSource::File(file_path)).await
+    }
+
+    /// Parse a log held in memory, e.g. an entry read out of a ZIP archive.
+    /// `path` names the log; nothing is read from or written to disk.
+    pub async fn parse_log_data(&self, path: &Path, data: &[u8]) -> Result<ParseResult, ParserError> {
+        self.parse_source(&LogSource::Memory { path, data }).await
+    }
+
+    async fn parse_source(&self, source: &LogSource<'_>) -> Result<ParseResult, ParserError> {
         let parse_start = std::time::Instant::now();
-        let file_size = fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+        let file_path = source.path();
         log::info!(
             "Parsing log file: {:?} (size: {:.1} KB)",
             file_path,
-            file_size as f64 / 1024.0
+            source.size() as f64 / 1024.0
         );
 
         // Calculate file hash to check for duplicates
-        let file_hash = Self::calculate_file_hash(file_path)?;
+        let file_hash = match *source {
+            LogSource::File(path) => Self::calculate_file_hash(path)?,
+            LogSource::Memory { data, .. } => format!("{:x}", Sha256::digest(data)),
+        };
         log::debug!("File hash: {}", file_hash);
 
         if let Some(matching_flight) = self
@@ -121,60 +473,108 @@ impl<'a> LogParser<'a> {
             log::info!("File already imported (hash match), skipping — matches flight: {}", matching_flight);
             return Err(ParserError::AlreadyImported(matching_flight));
         }
+        if let Some(trashed_flight) = self
+            .db
+            .trashed_flight_for_file(&file_hash)
+            .map_err(|e| ParserError::Parse(e.to_string()))?
+        {
+            log::info!("File belongs to trashed flight '{}', skipping", trashed_flight);
+            return Err(ParserError::InTrash(trashed_flight));
+        }
 
         // Detect file format and route to appropriate parser
         // Check for Drone Logbook CSV format first (our own export)
-        if DroneLogbookParser::is_dronelogbook_csv(file_path) {
+        if DroneLogbookParser::is_dronelogbook_csv(source) {
             log::info!("Detected Drone Logbook CSV format, using DroneLogbookParser");
             let dronelogbook_parser = DroneLogbookParser::new(self.db);
-            return dronelogbook_parser.parse(file_path, &file_hash);
+            return dronelogbook_parser.parse(source, &file_hash);
+        }
+
+        // Check for AirData CSV export
+        if AirDataParser::is_airdata_csv(source) {
+            log::info!("Detected AirData CSV format, using AirDataParser");
+            let airdata_parser = AirDataParser::new(self.db);
+            return airdata_parser.parse(source, &file_hash);
+        }
+
+        // Check for Autel Explorer / Sky CSV export
+        if AutelParser::is_autel_csv(source) {
+            log::info!("Detected Autel CSV format, using AutelParser");
+            let autel_parser = AutelParser::new(self.db);
+            return autel_parser.parse(source, &file_hash);
         }
 
         // Check for Litchi CSV format
-        if LitchiParser::is_litchi_csv(file_path) {
+        if LitchiParser::is_litchi_csv(source) {
             log::info!("Detected Litchi CSV format, using LitchiParser");
             let litchi_parser = LitchiParser::new(self.db);
-            return litchi_parser.parse(file_path, &file_hash);
+            return litchi_parser.parse(source, &file_hash);
+        }
+
+        // Check for Parrot PUD JSON logs
+        if ParrotParser::is_parrot_log(source) {
+            log::info!("Detected Parrot PUD format, using ParrotParser");
+            let parrot_parser = ParrotParser::new(self.db);
+            return parrot_parser.parse(source, &file_hash);
+        }
+
+        // Check for DJI video subtitle telemetry
+        if SrtParser::is_dji_srt(source) {
+            log::info!("Detected DJI SRT subtitle telemetry, using SrtParser");
+            let srt_parser = SrtParser::new(self.db);
+            return srt_parser.parse(source, &file_hash);
+        }
+
+        // Check for flight controller DAT logs pulled from the aircraft
+        if DatParser::is_dat_file(source) {
+            log::info!("Detected DJI flight controller DAT format, using DatParser");
+            let dat_parser = DatParser::new(self.db);
+            let result = dat_parser.parse(source, &file_hash)?;
+            if let Some((flight_id, name, offset_ms)) = self.find_cross_format_match(&result) {
+                // Keeps the motor and compass data the app log lacks
+                self.db
+                    .attach_dat_log(flight_id, &file_hash, &result.metadata.file_name, &result.points, &result.motors, offset_ms)
+                    .map_err(|e| ParserError::Parse(e.to_string()))?;
+                return Err(ParserError::LinkedToFlight(flight_id, name));
+            }
+            return Ok(result);
         }
 
         // Check if this looks like a valid DJI log file
         let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if ext.eq_ignore_ascii_case("acl") {
+            return Err(ParserError::Parse(
+                "Autel .acl flight records are not supported; export the flight as CSV from Autel Explorer or Autel Sky"
+                    .to_string(),
+            ));
+        }
         if !ext.eq_ignore_ascii_case("txt") {
             log::warn!("Unsupported file extension: .{}", ext);
             return Err(ParserError::IncompatibleFile);
         }
 
         // Read the file
-        let file_data = fs::read(file_path)?;
+        let file_data = source.read_all()?;
         log::debug!("File read into memory: {} bytes", file_data.len());
 
-        // Parse with dji-log-parser inside spawn_blocking + catch_unwind
-        // This prevents a panicking/hanging parser from killing the app
-        let parser = {
-            let data = file_data.clone();
-            let result = timeout(
-                Duration::from_secs(PARSE_TIMEOUT_SECS),
-                tokio::task::spawn_blocking(move || {
-                    panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                        DJILog::from_bytes(data)
-                    }))
-                }),
-            )
-            .await;
-
-            match result {
-                Err(_) => return Err(ParserError::Timeout(PARSE_TIMEOUT_SECS)),
-                Ok(Err(join_err)) => return Err(ParserError::Panic(format!("Task join error: {}", join_err))),
-                Ok(Ok(Err(panic_val))) => {
-                    let msg = panic_val
-                        .downcast_ref::<String>()
-                        .map(|s| s.clone())
-                        .or_else(|| panic_val.downcast_ref::<&str>().map(|s| s.to_string()))
-                        .unwrap_or_else(|| "unknown panic".to_string());
-                    return Err(ParserError::Panic(msg));
-                }
-                Ok(Ok(Ok(parse_result))) => {
-                    parse_result.map_err(|e| ParserError::Parse(e.to_string()))?
+        // The buffer is handed over rather than cloned so large logs are only held once
+        let (parser, frames, firmware_version, recovery) = match self.decode_log(file_data, &file_hash).await {
+            Ok((parser, frames, firmware_version)) if !frames.is_empty() => (parser, frames, firmware_version, None),
+            Err(e) if !matches!(e, ParserError::Parse(_) | ParserError::Panic(_)) => return Err(e),
+            first_attempt => {
+                let recovered = match source.read_all() {
+                    Ok(data) => self.recover_partial_log(&data, &file_hash).await,
+                    Err(_) => None,
+                };
+                match recovered {
+                    Some((parser, frames, firmware_version, recovery)) => {
+                        (parser, frames, firmware_version, Some(recovery))
+                    }
+                    None => {
+                        first_attempt?;
+                        log::warn!("No frames extracted from log file — file may be empty or corrupt");
+                        return Err(ParserError::NoTelemetryData);
+                    }
                 }
             }
         };
@@ -189,18 +589,29 @@ impl<'a> LogParser<'a> {
             parser.details.total_time
         );
 
-        // Check if we need an encryption key for V13+ logs
-        let frames = self.get_frames(&parser).await?;
+        let is_legacy = parser.version <= LEGACY_LOG_VERSION_MAX;
+        if is_legacy {
+            log::info!(
+                "Legacy DJI GO / GO 4 log (version {}), header fields will be backfilled from frames",
+                parser.version
+            );
+        }
+
         log::info!("Extracted {} frames from log", frames.len());
+        if let Some(ref firmware) = firmware_version {
+            log::info!("Aircraft firmware version: {}", firmware);
+        }
 
-        if frames.is_empty() {
-            log::warn!("No frames extracted from log file — file may be empty or corrupt");
-            return Err(ParserError::NoTelemetryData);
+        // FPV-series aircraft (DJI FPV, Avata) log through the goggles and
+        // leave some OSD fields unset, so validation is relaxed for them
+        let is_fpv = Self::is_fpv_series(&parser);
+        if is_fpv {
+            log::info!("FPV-series aircraft detected ({:?})", parser.details.product_type);
         }
 
         // Extract telemetry points
         let details_total_time_secs = parser.details.total_time as f64;
-        let points = self.extract_telemetry(&frames, details_total_time_secs);
+        let mut points = self.extract_telemetry(&frames, details_total_time_secs, is_fpv);
         log::info!(
             "Extracted {} valid telemetry points from {} frames ({} skipped)",
             points.len(),
@@ -213,6 +624,14 @@ impl<'a> LogParser<'a> {
             return Err(ParserError::NoTelemetryData);
         }
 
+        if is_fpv {
+            for point in points.iter_mut() {
+                if let Some(mode) = point.flight_mode.as_deref().and_then(Self::fpv_flight_mode) {
+                    point.flight_mode = Some(mode.to_string());
+                }
+            }
+        }
+
         // Calculate statistics
         let stats = self.calculate_stats(&points);
 
@@ -230,32 +649,54 @@ impl<'a> LogParser<'a> {
             .unwrap_or(&file_name)
             .to_string();
 
+        let duration_secs = if details_total_time_secs > 0.0 {
+            details_total_time_secs
+        } else {
+            stats.duration_secs
+        };
+        let start_time = self.extract_start_time(&parser, &frames, &file_name);
+        let mission_name = Self::extract_mission_name(&points, &file_name);
+        if let Some(ref mission) = mission_name {
+            log::info!("Mission flight detected: {}", mission);
+        }
+
         let metadata = FlightMetadata {
             id: self.db.generate_flight_id(),
-            file_name,
+            file_name: file_name.clone(),
             display_name,
             file_hash: Some(file_hash),
             drone_model: self.extract_drone_model(&parser),
-            drone_serial: self.extract_serial(&parser),
-            aircraft_name: self.extract_aircraft_name(&parser),
-            battery_serial: self.extract_battery_serial(&parser),
-            start_time: self.extract_start_time(&parser),
-            end_time: self.extract_end_time(&parser),
-            duration_secs: Some(
-                if details_total_time_secs > 0.0 {
-                    details_total_time_secs
-                } else {
-                    stats.duration_secs
-                }
-            ),
+            drone_serial: self
+                .extract_serial(&parser)
+                .or_else(|| Self::recover_field(&frames, |f| f.recover.aircraft_sn.clone()).map(|s| s.to_uppercase())),
+            aircraft_name: self
+                .extract_aircraft_name(&parser)
+                .or_else(|| Self::recover_field(&frames, |f| f.recover.aircraft_name.clone())),
+            battery_serial: self
+                .extract_battery_serial(&parser)
+                .or_else(|| Self::recover_field(&frames, |f| f.recover.battery_sn.clone()).map(|s| s.to_uppercase())),
+            start_time,
+            end_time: start_time.map(|start| start + chrono::Duration::milliseconds((duration_secs * 1000.0) as i64)),
+            duration_secs: Some(duration_secs),
             total_distance: Some(stats.total_distance_m),
             max_altitude: Some(stats.max_altitude_m),
             max_speed: Some(stats.max_speed_ms),
             home_lat: stats.home_location.map(|h| h[1]),
             home_lon: stats.home_location.map(|h| h[0]),
             point_count: points.len() as i32,
+            mission_name,
+            partial: recovery.is_some(),
+            firmware_version,
+            max_distance_from_home: max_distance_from_home(&points, stats.home_location),
         };
 
+        if is_legacy {
+            log::debug!(
+                "Legacy header resolution: model={:?}, serial={:?}, start_time={:?}",
+                metadata.drone_model, metadata.drone_serial, metadata.start_time
+            );
+        }
+
         log::info!(
             "Parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, max_speed={:.1}m/s, home={:?}, points={}",
             parse_start.elapsed().as_secs_f64(),
@@ -268,10 +709,201 @@ impl<'a> LogParser<'a> {
         );
 
         // Generate smart tags based on flight characteristics
-        let tags = Self::generate_smart_tags(&metadata, &stats);
+        let mut tags = Self::generate_smart_tags(&metadata, &stats);
+        if metadata.mission_name.is_some() {
+            tags.insert(0, "Mission".to_string());
+        }
+        if is_fpv {
+            tags.insert(0, "FPV".to_string());
+            if points.iter().any(|p| p.flight_mode.as_deref() == Some("Manual (Rate)")) {
+                tags.push("Rate Mode".to_string());
+            }
+        }
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult { metadata, points, tags })
+        let home_points = Self::extract_home_points(&frames);
+        if home_points.len() > 1 {
+            log::info!("Home point was updated {} times during the flight", home_points.len() - 1);
+        }
+
+        let mut events = Self::extract_gimbal_mode_events(&frames);
+        events.extend(Self::extract_message_events(&frames));
+        events.sort_by_key(|e| e.timestamp_ms);
+        let warning_count = events.iter().filter(|e| e.event_type == "warning").count();
+        if warning_count > 0 {
+            log::info!("Flight has {} app warnings", warning_count);
+        }
+        locate_events(&mut events, &points);
+
+        Ok(ParseResult { metadata, points, tags, recovery, home_points, motors: Vec::new(), events })
+    }
+
+    /// Parse the log container and decode its frames and aircraft firmware version.
+    /// Both steps run inside spawn_blocking + catch_unwind so a panicking or
+    /// hanging parser cannot kill the app.
+    async fn decode_log(
+        &self,
+        data: Vec<u8>,
+        file_hash: &str,
+    ) -> Result<(DJILog, Vec<Frame>, Option<String>), ParserError> {
+        let parser = Self::parse_container(data).await?;
+        // Check if we need an encryption key for V13+ logs
+        let keychains = self.get_keychains(&parser, file_hash)?;
+        let (frames, firmware_version) = Self::get_frames(&parser, keychains).await?;
+        Ok((parser, frames, firmware_version))
+    }
+
+    /// Parse the log container (prefix, details and record layout)
+    async fn parse_container(data: Vec<u8>) -> Result<DJILog, ParserError> {
+        let result = timeout(
+            Duration::from_secs(PARSE_TIMEOUT_SECS),
+            tokio::task::spawn_blocking(move || {
+                panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                    DJILog::from_bytes(data)
+                }))
+            }),
+        )
+        .await;
+
+        match result {
+            Err(_) => Err(ParserError::Timeout(PARSE_TIMEOUT_SECS)),
+            Ok(Err(join_err)) => Err(ParserError::Panic(format!("Task join error: {}", join_err))),
+            Ok(Ok(Err(panic_val))) => {
+                let msg = panic_val
+                    .downcast_ref::<String>()
+                    .map(|s| s.clone())
+                    .or_else(|| panic_val.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                Err(ParserError::Panic(msg))
+            }
+            Ok(Ok(Ok(parse_result))) => {
+                parse_result.map_err(|e| ParserError::Parse(e.to_string()))
+            }
+        }
+    }
+
+    /// Salvage what we can from a truncated or corrupt TXT log.
+    ///
+    /// Up to version 11 the records sit between the prefix and the details
+    /// block. A log cut off mid-write still has a header whose detail offset
+    /// points past the end of the file, so the parser rejects it outright. We
+    /// re-bound the records area to the bytes actually present (trimming
+    /// further back if the last records are damaged too), reattach the
+    /// details block — or an empty one when it was lost — and decode again.
+    /// From version 12 the details come first and the records run to the end
+    /// of the file, so cutting off the damaged tail is enough.
+    ///
+    /// Keychains of encrypted logs are looked up once, not per attempt.
+    async fn recover_partial_log(
+        &self,
+        data: &[u8],
+        file_hash: &str,
+    ) -> Option<(DJILog, Vec<Frame>, Option<String>, PartialRecovery)> {
+        let version = *data.get(LOG_VERSION_OFFSET)?;
+        let prefix_len = if version < LOG_FULL_PREFIX_VERSION { LOG_PREFIX_LEN_OLD } else { LOG_PREFIX_LEN };
+        if data.len() <= prefix_len {
+            return None;
+        }
+        let detail_offset = u64::from_le_bytes(data[0..8].try_into().ok()?) as usize;
+        let detail_len = u16::from_le_bytes([data[8], data[9]]) as usize;
+        let details_first = version >= LOG_DETAILS_FIRST_VERSION;
+
+        let (records_start, records_end, truncated) = if details_first {
+            // The file length bounds the records; the full length already failed
+            (detail_offset.saturating_add(detail_len), data.len(), false)
+        } else {
+            let truncated = detail_offset.saturating_add(detail_len) > data.len();
+            (prefix_len, detail_offset.min(data.len()), truncated)
+        };
+        if records_end <= records_start {
+            return None;
+        }
+        let details = match (details_first, truncated) {
+            (true, _) => Vec::new(),
+            (false, true) => vec![0u8; detail_len],
+            (false, false) => data[detail_offset..detail_offset + detail_len].to_vec(),
+        };
+
+        log::warn!(
+            "Attempting partial recovery of version {} log ({}): {} of {} record bytes present",
+            version,
+            if truncated { "truncated file" } else { "corrupt records" },
+            records_end - records_start,
+            if details_first { records_end - records_start } else { detail_offset.saturating_sub(prefix_len) }
+        );
+
+        let records_len = records_end - records_start;
+        let mut keychains = None;
+        for &trim in RECOVERY_TRIM_FRACTIONS {
+            // An intact file already failed at its full length
+            if trim == 0.0 && !truncated {
+                continue;
+            }
+            let end = records_end - (records_len as f64 * trim) as usize;
+            let mut patched = Vec::with_capacity(end + details.len());
+            patched.extend_from_slice(&data[..end]);
+            if !details_first {
+                patched[0..8].copy_from_slice(&(end as u64).to_le_bytes());
+                patched.extend_from_slice(&details);
+            }
+
+            let parser = match Self::parse_container(patched).await {
+                Ok(parser) => parser,
+                Err(e) => {
+                    log::debug!("Recovery attempt at {} bytes failed: {}", end, e);
+                    continue;
+                }
+            };
+            if keychains.is_none() {
+                match self.get_keychains(&parser, file_hash) {
+                    Ok(found) => keychains = Some(found),
+                    Err(e) => {
+                        log::warn!("Partial recovery stopped: {}", e);
+                        return None;
+                    }
+                }
+            }
+            match Self::get_frames(&parser, keychains.clone().flatten()).await {
+                Ok((frames, firmware_version)) if !frames.is_empty() => {
+                    log::warn!(
+                        "Recovered {} frames from the first {} of {} bytes",
+                        frames.len(),
+                        end,
+                        data.len()
+                    );
+                    let recovery = PartialRecovery {
+                        bytes_recovered: end,
+                        total_bytes: data.len(),
+                        records: frames.len(),
+                    };
+                    return Some((parser, frames, firmware_version, recovery));
+                }
+                Ok(_) => log::debug!("Recovery attempt at {} bytes produced no frames", end),
+                Err(e) => log::debug!("Recovery attempt at {} bytes failed: {}", end, e),
+            }
+        }
+
+        None
+    }
+
+    /// Check whether the log comes from an FPV-series aircraft (DJI FPV, Avata).
+    /// Their VTX link quality is logged as the OFDM downlink and lands in
+    /// `rc_downlink` (charted as Video Downlink); the flight record carries no
+    /// VTX channel, bitrate or latency.
+    fn is_fpv_series(parser: &DJILog) -> bool {
+        let product = format!("{:?}", parser.details.product_type).to_lowercase();
+        product.contains("fpv") || product.contains("avata")
+    }
+
+    /// Map flight controller states to the N/S/M mode names shown in the goggles.
+    /// M mode is full manual (rate/acro) flight.
+    fn fpv_flight_mode(mode: &str) -> Option<&'static str> {
+        match mode {
+            "GPSAtti" | "Atti" | "GPSNovice" => Some("Normal"),
+            "GPSSport" | "Sport" => Some("Sport"),
+            "Manual" | "FPV" => Some("Manual (Rate)"),
+            _ => None,
+        }
     }
 
     /// Generate smart tags based on flight metadata and statistics
@@ -454,7 +1086,7 @@ impl<'a> LogParser<'a> {
     }
 
     /// Map ISO 3166-1 alpha-2 country code to country name.
-    fn country_from_cc(cc: &str) -> Option<&'static str> {
+    pub(crate) fn country_from_cc(cc: &str) -> Option<&'static str> {
         match cc {
             "AD" => Some("Andorra"), "AE" => Some("UAE"), "AF" => Some("Afghanistan"),
             "AG" => Some("Antigua and Barbuda"), "AI" => Some("Anguilla"), "AL" => Some("Albania"),
@@ -573,30 +1205,61 @@ impl<'a> LogParser<'a> {
         }
     }
 
-    /// Get frames from the parser, handling encryption if needed.
-    /// Runs the CPU-bound parsing in spawn_blocking with catch_unwind
-    /// to prevent panics from crashing the application.
-    async fn get_frames(&self, parser: &DJILog) -> Result<Vec<Frame>, ParserError> {
+    /// Keychains needed to decrypt the log, None for logs before version 13.
+    /// Taken from the cache when this file was decrypted before, otherwise
+    /// fetched from the DJI API and cached.
+    fn get_keychains(&self, parser: &DJILog, file_hash: &str) -> Result<Option<Vec<Vec<KeychainFeaturePoint>>>, ParserError> {
         // Version 13+ requires keychains for decryption
         let keychains = if parser.version >= 13 {
-            log::info!("Log version {} >= 13, fetching keychains for decryption", parser.version);
-            let api_key = self.api.get_api_key().ok_or_else(|| {
-                log::error!("No DJI API key configured — cannot decrypt V13+ log");
-                ParserError::EncryptionKeyRequired
-            })?;
-            let kc = parser
-                .fetch_keychains(&api_key)
-                .map_err(|e| {
-                    log::error!("Keychain fetch failed: {}", e);
-                    ParserError::Api(e.to_string())
+            let cached = self
+                .db
+                .get_cached_keychains(file_hash)
+                .unwrap_or(None)
+                .and_then(|json| serde_json::from_str::<Vec<Vec<KeychainFeaturePoint>>>(&json).ok());
+
+            if let Some(kc) = cached {
+                log::info!("Using cached keychains for log version {} ({} chains)", parser.version, kc.len());
+                Some(kc)
+            } else {
+                log::info!("Log version {} >= 13, fetching keychains for decryption", parser.version);
+                let api_key = self.api.get_api_key().ok_or_else(|| {
+                    log::error!("No DJI API key configured — cannot decrypt V13+ log");
+                    ParserError::EncryptionKeyRequired
                 })?;
-            log::info!("Keychains fetched successfully ({} chains)", kc.len());
-            Some(kc)
+                let kc = parser
+                    .fetch_keychains(&api_key)
+                    .map_err(|e| {
+                        log::error!("Keychain fetch failed: {}", e);
+                        ParserError::Api(e.to_string())
+                    })?;
+                log::info!("Keychains fetched successfully ({} chains)", kc.len());
+
+                // Keep them so re-parsing this log later works offline
+                match serde_json::to_string(&kc) {
+                    Ok(json) => {
+                        if let Err(e) = self.db.cache_keychains(file_hash, &json) {
+                            log::warn!("Failed to cache keychains: {}", e);
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to serialize keychains: {}", e),
+                }
+                Some(kc)
+            }
         } else {
             log::debug!("Log version {} < 13, no decryption needed", parser.version);
             None
         };
+        Ok(keychains)
+    }
 
+    /// Get frames and the aircraft firmware version from the parser, with the
+    /// keychains from `get_keychains`. Runs the CPU-bound parsing in
+    /// spawn_blocking with catch_unwind to prevent panics from crashing the
+    /// application.
+    async fn get_frames(
+        parser: &DJILog,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<(Vec<Frame>, Option<String>), ParserError> {
         // Clone what we need to move into spawn_blocking
         // DJILog doesn't implement Clone, so we need to use a raw pointer trick
         // Instead, we'll re-read the data inside the blocking task
@@ -610,7 +1273,11 @@ impl<'a> LogParser<'a> {
             tokio::task::spawn_blocking(move || {
                 let parser_ref = unsafe { &*(parser_ptr as *const DJILog) };
                 panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                    parser_ref.frames(keychains)
+                    // Same as frames(), keeping the firmware records frames drop
+                    parser_ref.records(keychains).map(|records| {
+                        let firmware_version = firmware_version_from_records(&records);
+                        (records_to_frames(records, parser_ref.details.clone()), firmware_version)
+                    })
                 }))
             }),
         )
@@ -643,8 +1310,10 @@ impl<'a> LogParser<'a> {
         }
     }
 
-    /// Extract telemetry points from parsed frames
-    fn extract_telemetry(&self, frames: &[Frame], details_total_time_secs: f64) -> Vec<TelemetryPoint> {
+    /// Extract telemetry points from parsed frames.
+    /// With `relaxed` set, only the position has to be finite; other non-finite
+    /// OSD values are dropped individually instead of discarding the frame.
+    fn extract_telemetry(&self, frames: &[Frame], details_total_time_secs: f64, relaxed: bool) -> Vec<TelemetryPoint> {
         let mut points = Vec::with_capacity(frames.len());
         let mut timestamp_ms: i64 = 0;
 
@@ -681,13 +1350,14 @@ impl<'a> LogParser<'a> {
 
             // Validate core numeric fields — skip entire frame if data is corrupt
             // (e.g. the parser produced garbage like lat=-6.6e-136, lon=5.7e+139)
+            let osd_finite = is_finite_f32(osd.altitude)
+                && is_finite_f32(osd.height)
+                && is_finite_f32(osd.x_speed)
+                && is_finite_f32(osd.y_speed)
+                && is_finite_f32(osd.z_speed);
             if !is_finite_f64(osd.latitude)
                 || !is_finite_f64(osd.longitude)
-                || !is_finite_f32(osd.altitude)
-                || !is_finite_f32(osd.height)
-                || !is_finite_f32(osd.x_speed)
-                || !is_finite_f32(osd.y_speed)
-                || !is_finite_f32(osd.z_speed)
+                || (!osd_finite && !relaxed)
             {
                 // Increment timestamp and skip this corrupt frame
                 if skipped_corrupt < 5 {
@@ -739,6 +1409,14 @@ impl<'a> LogParser<'a> {
             point.velocity_x = if has_gps_lock && gps_in_range { Some(osd.x_speed as f64) } else { None };
             point.velocity_y = if has_gps_lock && gps_in_range { Some(osd.y_speed as f64) } else { None };
             point.velocity_z = if has_gps_lock && gps_in_range { Some(osd.z_speed as f64) } else { None };
+
+            if !osd_finite {
+                // Relaxed mode: the range clamps above already reject NaN/Inf
+                // altitude and speed, velocities need an explicit check
+                point.velocity_x = point.velocity_x.filter(|v| v.is_finite());
+                point.velocity_y = point.velocity_y.filter(|v| v.is_finite());
+                point.velocity_z = point.velocity_z.filter(|v| v.is_finite());
+            }
             point.pitch = Some(osd.pitch as f64);
             point.roll = Some(osd.roll as f64);
             point.yaw = Some(osd.yaw as f64);
@@ -754,16 +1432,40 @@ impl<'a> LogParser<'a> {
             point.battery_voltage = Some(battery.voltage as f64);
             point.battery_current = Some(battery.current as f64);
             point.battery_temp = Some(battery.temperature as f64);
+            // Older aircraft only report pack voltage; the parser then fills
+            // cells with pack/cell_num estimates, which carry no information
+            if !battery.is_cell_voltage_estimated && !battery.cell_voltages.is_empty() {
+                point.cell_voltages = Some(battery.cell_voltages.iter().map(|&v| v as f64).collect());
+            }
 
             point.rc_uplink = rc.uplink_signal.map(i32::from);
             point.rc_downlink = rc.downlink_signal.map(i32::from);
             point.rc_signal = rc.downlink_signal.or(rc.uplink_signal).map(i32::from);
 
-            // RC stick inputs: raw u16 centered at 1024 (range 0..2048) → normalized to -100..+100
-            point.rc_aileron = Some(((rc.aileron as f64) - 1024.0) / 1024.0 * 100.0);
-            point.rc_elevator = Some(((rc.elevator as f64) - 1024.0) / 1024.0 * 100.0);
-            point.rc_throttle = Some(((rc.throttle as f64) - 1024.0) / 1024.0 * 100.0);
-            point.rc_rudder = Some(((rc.rudder as f64) - 1024.0) / 1024.0 * 100.0);
+            // RC stick inputs: raw u16 centered at 1024 (range 0..2048) → normalized to -100..+100.
+            // Frames logged without an RC record (controller disconnected,
+            // SDK/automated flights) leave every channel at 0; store nothing
+            // rather than four sticks pinned at full deflection.
+            if rc.aileron != 0 || rc.elevator != 0 || rc.throttle != 0 || rc.rudder != 0 {
+                let normalize = |raw: f64| (raw - 1024.0) / 1024.0 * 100.0;
+                point.rc_aileron = Some(normalize(rc.aileron as f64));
+                point.rc_elevator = Some(normalize(rc.elevator as f64));
+                point.rc_throttle = Some(normalize(rc.throttle as f64));
+                point.rc_rudder = Some(normalize(rc.rudder as f64));
+            }
+
+            // Sensor health. The IMU reports an init failure reason (e.g.
+            // CompassModTooLarge, McVibrated) while it refuses to arm; show
+            // warm-up as "Preheating" and leave healthy frames unset.
+            point.compass_error = Some(osd.is_compass_error);
+            point.imu_status = osd
+                .imu_init_fail_reason
+                .map(|reason| format!("{:?}", reason))
+                .filter(|reason| reason != "None")
+                .or_else(|| (!osd.is_imu_preheated).then(|| "Preheating".to_string()));
+
+            // Wind: the frames decoded by dji-log-parser carry no wind
+            // estimate, so wind_speed / wind_direction stay unset for TXT logs
 
             // Camera state: extract is_photo and is_video from frame.camera
             let camera = &frame.camera;
@@ -792,6 +1494,121 @@ impl<'a> LogParser<'a> {
         points
     }
 
+    /// Collect the home point history from the HOME records. The first valid
+    /// home point is always kept; later ones only when the point moved by more
+    /// than `HOME_POINT_MIN_SHIFT_M` (dynamic home, RTH point refresh).
+    fn extract_home_points(frames: &[Frame]) -> Vec<HomePoint> {
+        let mut history: Vec<HomePoint> = Vec::new();
+        let mut timestamp_ms: i64 = 0;
+
+        for frame in frames {
+            if frame.osd.fly_time > 0.0 {
+                timestamp_ms = (frame.osd.fly_time * 1000.0) as i64;
+            }
+
+            let home = &frame.home;
+            if !is_finite_f64(home.latitude)
+                || !is_finite_f64(home.longitude)
+                || (home.latitude.abs() < 1e-6 && home.longitude.abs() < 1e-6)
+                || home.latitude.abs() > 90.0
+                || home.longitude.abs() > 180.0
+            {
+                continue;
+            }
+
+            let moved = history.last().map_or(true, |last| {
+                haversine_distance(last.lat, last.lon, home.latitude, home.longitude) > HOME_POINT_MIN_SHIFT_M
+            });
+            if moved {
+                history.push(HomePoint {
+                    timestamp_ms,
+                    lat: home.latitude,
+                    lon: home.longitude,
+                    altitude: is_finite_f32(home.altitude).then(|| home.altitude as f64),
+                });
+            }
+        }
+
+        history
+    }
+
+    /// Record every change of the gimbal mode (Follow, FPV, Free). Frames
+    /// without a gimbal record leave the mode unset and are ignored.
+    fn extract_gimbal_mode_events(frames: &[Frame]) -> Vec<FlightEvent> {
+        let mut events: Vec<FlightEvent> = Vec::new();
+        let mut last_mode: Option<String> = None;
+        let mut timestamp_ms: i64 = 0;
+
+        for frame in frames {
+            if frame.osd.fly_time > 0.0 {
+                timestamp_ms = (frame.osd.fly_time * 1000.0) as i64;
+            }
+
+            let Some(mode) = frame.gimbal.mode.map(|m| format!("{:?}", m)) else {
+                continue;
+            };
+            if last_mode.as_deref() == Some(mode.as_str()) {
+                continue;
+            }
+            events.push(FlightEvent {
+                timestamp_ms,
+                event_type: "gimbal_mode".to_string(),
+                lat: None,
+                lon: None,
+                altitude: None,
+                duration_ms: None,
+                detail: Some(mode.clone()),
+                severity: None,
+            });
+            last_mode = Some(mode);
+        }
+
+        events
+    }
+
+    /// Collect the tip and warning messages the DJI app showed during the
+    /// flight (compass interference, altitude limit, strong wind, low
+    /// battery, ...). A message is recorded when it appears; frames that keep
+    /// repeating the same text do not add events.
+    fn extract_message_events(frames: &[Frame]) -> Vec<FlightEvent> {
+        let mut events: Vec<FlightEvent> = Vec::new();
+        let mut last_tip = String::new();
+        let mut last_warn = String::new();
+        let mut timestamp_ms: i64 = 0;
+
+        for frame in frames {
+            if frame.osd.fly_time > 0.0 {
+                timestamp_ms = (frame.osd.fly_time * 1000.0) as i64;
+            }
+
+            for (event_type, text, last) in [
+                ("tip", frame.app.tip.trim(), &mut last_tip),
+                ("warning", frame.app.warn.trim(), &mut last_warn),
+            ] {
+                if text == last.as_str() {
+                    continue;
+                }
+                *last = text.to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                let severity = if event_type == "tip" { "info" } else { warning_severity(text) };
+                events.push(FlightEvent {
+                    timestamp_ms,
+                    event_type: event_type.to_string(),
+                    lat: None,
+                    lon: None,
+                    altitude: None,
+                    duration_ms: None,
+                    detail: Some(text.to_string()),
+                    severity: Some(severity.to_string()),
+                });
+            }
+        }
+
+        events
+    }
+
     /// Calculate flight statistics from telemetry points
     pub fn calculate_stats(&self, points: &[TelemetryPoint]) -> FlightStats {
         let duration_secs = points.last().map(|p| p.timestamp_ms as f64 / 1000.0).unwrap_or(0.0);
@@ -801,13 +1618,16 @@ impl<'a> LogParser<'a> {
             .filter_map(|p| p.height.or(p.altitude))
             .fold(f64::NEG_INFINITY, f64::max);
 
-        let max_speed = points
-            .iter()
-            .filter_map(|p| p.speed)
-            .fold(f64::NEG_INFINITY, f64::max);
+        // Speeds and positions without single-sample glitches
+        let speeds: Vec<f64> = crate::gps_filter::filter_speeds(points.iter().map(|p| (p.timestamp_ms, p.speed)))
+            .into_iter()
+            .flatten()
+            .collect();
+        let fixes = crate::gps_filter::filter_positions(points.iter().map(|p| (p.timestamp_ms, p.latitude, p.longitude)));
+
+        let max_speed = speeds.iter().copied().fold(f64::NEG_INFINITY, f64::max);
 
         let avg_speed: f64 = {
-            let speeds: Vec<f64> = points.iter().filter_map(|p| p.speed).collect();
             if speeds.is_empty() {
                 0.0
             } else {
@@ -825,21 +1645,14 @@ impl<'a> LogParser<'a> {
         let total_distance = self.calculate_total_distance(points);
 
         // Home location is the first valid GPS point
-        let home_location = points
-            .iter()
-            .find_map(|p| match (p.longitude, p.latitude) {
-                (Some(lon), Some(lat)) => Some([lon, lat]),
-                _ => None,
-            });
+        let home_location = fixes.iter().find_map(|fix| fix.map(|(lat, lon)| [lon, lat]));
 
         // Max distance from home
         let max_distance_from_home = if let Some(home) = home_location {
-            points
+            fixes
                 .iter()
-                .filter_map(|p| match (p.latitude, p.longitude) {
-                    (Some(lat), Some(lon)) => Some(haversine_distance(home[1], home[0], lat, lon)),
-                    _ => None,
-                })
+                .flatten()
+                .map(|&(lat, lon)| haversine_distance(home[1], home[0], lat, lon))
                 .fold(0.0_f64, f64::max)
         } else {
             0.0
@@ -873,21 +1686,7 @@ impl<'a> LogParser<'a> {
 
     /// Calculate total distance traveled using haversine formula
     fn calculate_total_distance(&self, points: &[TelemetryPoint]) -> f64 {
-        let mut total = 0.0;
-        let mut prev_lat: Option<f64> = None;
-        let mut prev_lon: Option<f64> = None;
-
-        for point in points {
-            if let (Some(lat), Some(lon)) = (point.latitude, point.longitude) {
-                if let (Some(p_lat), Some(p_lon)) = (prev_lat, prev_lon) {
-                    total += haversine_distance(p_lat, p_lon, lat, lon);
-                }
-                prev_lat = Some(lat);
-                prev_lon = Some(lon);
-            }
-        }
-
-        total
+        crate::derived::path_distance(points.iter().map(|p| (p.timestamp_ms, p.latitude, p.longitude)))
     }
 
     /// Extract drone model from parser metadata
@@ -930,17 +1729,128 @@ impl<'a> LogParser<'a> {
         }
     }
 
-    /// Extract flight start time
-    fn extract_start_time(&self, parser: &DJILog) -> Option<DateTime<Utc>> {
-        Some(parser.details.start_time)
+    /// Extract flight start time.
+    ///
+    /// Legacy (V1–V12) logs frequently carry a zeroed header timestamp, so fall
+    /// back to the first frame with a plausible clock, then to the date/time
+    /// embedded in the DJI GO file name.
+    fn extract_start_time(&self, parser: &DJILog, frames: &[Frame], file_name: &str) -> Option<DateTime<Utc>> {
+        if parser.details.start_time.timestamp() >= MIN_VALID_START_TIMESTAMP {
+            return Some(parser.details.start_time);
+        }
+
+        if let Some(frame_time) = frames
+            .iter()
+            .map(|f| f.custom.date_time)
+            .find(|t| t.timestamp() >= MIN_VALID_START_TIMESTAMP)
+        {
+            log::debug!("Header start_time unset, using first frame clock: {}", frame_time);
+            return Some(frame_time);
+        }
+
+        let from_name = parse_start_time_from_file_name(file_name);
+        if from_name.is_some() {
+            log::debug!("Header start_time unset, using file name timestamp: {:?}", from_name);
+        } else {
+            log::warn!("Could not determine start time for {}", file_name);
+        }
+        from_name
+    }
+
+    /// Extract the planned mission name of a waypoint flight.
+    ///
+    /// The OSD records mark waypoint legs with the NaviGo / waypoint controller
+    /// state, but no record the log decoder exposes carries the mission's name.
+    /// That comes from the file name: DJI GO / Fly name records
+    /// `DJIFlightRecord_<date>_[<time>].txt`, while GS Pro and other SDK mission
+    /// apps prefix it with the mission name, e.g.
+    /// `Field North Survey_2019-04-02_[10-11-12].txt`. A waypoint flight whose
+    /// file carries no name is called "Waypoint mission". Flights without
+    /// waypoint legs are never missions, however their file was renamed.
+    fn extract_mission_name(points: &[TelemetryPoint], file_name: &str) -> Option<String> {
+        let flew_waypoints = points.iter().any(|p| {
+            p.flight_mode.as_deref().is_some_and(|mode| {
+                let mode = mode.to_lowercase();
+                mode.contains("waypoint") || mode.contains("navigo")
+            })
+        });
+        if !flew_waypoints {
+            return None;
+        }
+        Some(Self::mission_name_from_file_name(file_name).unwrap_or_else(|| "Waypoint mission".to_string()))
+    }
+
+    /// Anything before the date portion of a mission app's record file name
+    fn mission_name_from_file_name(file_name: &str) -> Option<String> {
+        let stem = file_name.rsplit_once('.').map(|(s, _)| s).unwrap_or(file_name);
+        if stem.to_lowercase().starts_with("djiflightrecord") {
+            return None;
+        }
+
+        // Find the start of a YYYY date token
+        let bytes = stem.as_bytes();
+        let date_pos = (0..bytes.len().saturating_sub(9)).find(|&i| {
+            (i == 0 || matches!(bytes[i - 1], b'_' | b'-' | b' ' | b'['))
+                && bytes[i..i + 4].iter().all(u8::is_ascii_digit)
+                && matches!(bytes[i + 4], b'-' | b'_' | b'.')
+                && (bytes[i..i + 2] == *b"19" || bytes[i..i + 2] == *b"20")
+        })?;
+
+        let name = stem[..date_pos].trim_end_matches(['_', '-', ' ', '[']).trim();
+        if name.is_empty() {
+            None
+        } else {
+            Some(name.replace('_', " "))
+        }
+    }
+
+    /// First non-empty string from the RECOVER records (legacy logs keep
+    /// aircraft/battery identity there instead of in the header)
+    fn recover_field<F>(frames: &[Frame], get: F) -> Option<String>
+    where
+        F: Fn(&Frame) -> String,
+    {
+        frames
+            .iter()
+            .map(get)
+            .map(|s| s.trim().to_string())
+            .find(|s| !s.is_empty())
     }
+}
 
-    /// Extract flight end time
-    fn extract_end_time(&self, parser: &DJILog) -> Option<DateTime<Utc>> {
-        let start = self.extract_start_time(parser)?;
-        let duration_ms = (parser.details.total_time * 1000.0) as i64;
-        Some(start + chrono::Duration::milliseconds(duration_ms))
+/// Aircraft firmware version from the FIRMWARE records: the flight
+/// controller's, or the first one reported if none is marked as such
+fn firmware_version_from_records(records: &[Record]) -> Option<String> {
+    let versions: Vec<(String, &str)> = records
+        .iter()
+        .filter_map(|record| match record {
+            Record::Firmware(firmware) => Some((format!("{:?}", firmware.sender_type), firmware.version.trim())),
+            _ => None,
+        })
+        .filter(|(_, version)| !version.is_empty() && version.chars().any(|c| c != '0' && c != '.'))
+        .collect();
+    versions
+        .iter()
+        .find(|(sender, _)| sender == "MC")
+        .or_else(|| versions.first())
+        .map(|(_, version)| version.to_string())
+}
+
+/// Parse the timestamp embedded in DJI GO / GO 4 / Fly log names, e.g.
+/// `DJIFlightRecord_2017-05-21_[14-03-12].txt`. Names are local time, which
+/// we store as UTC since the device timezone is not recorded.
+fn parse_start_time_from_file_name(file_name: &str) -> Option<DateTime<Utc>> {
+    let stem = file_name.rsplit_once('.').map(|(s, _)| s).unwrap_or(file_name);
+    let (_, rest) = stem.split_once('_')?;
+    let digits: String = rest.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    if digits.len() < 14 {
+        return None;
     }
+
+    chrono::NaiveDateTime::parse_from_str(&digits[..14], "%Y%m%d%H%M%S")
+        .ok()
+        .map(|dt| dt.and_utc())
 }
 
 /// Calculate FlightStats from stored TelemetryRecords (for tag regeneration without re-parsing files)
@@ -952,12 +1862,16 @@ pub fn calculate_stats_from_records(records: &[crate::models::TelemetryRecord])
         .filter_map(|r| r.height.or(r.altitude))
         .fold(0.0_f64, f64::max);
 
-    let max_speed = records.iter()
-        .filter_map(|r| r.speed)
-        .fold(0.0_f64, f64::max);
+    // Speeds and positions without single-sample glitches, as at import
+    let speeds: Vec<f64> = crate::gps_filter::filter_speeds(records.iter().map(|r| (r.timestamp_ms, r.speed)))
+        .into_iter()
+        .flatten()
+        .collect();
+    let fixes = crate::gps_filter::filter_positions(records.iter().map(|r| (r.timestamp_ms, r.latitude, r.longitude)));
+
+    let max_speed = speeds.iter().copied().fold(0.0_f64, f64::max);
 
     let avg_speed: f64 = {
-        let speeds: Vec<f64> = records.iter().filter_map(|r| r.speed).collect();
         if speeds.is_empty() { 0.0 } else { speeds.iter().sum::<f64>() / speeds.len() as f64 }
     };
 
@@ -966,33 +1880,15 @@ pub fn calculate_stats_from_records(records: &[crate::models::TelemetryRecord])
         .min()
         .unwrap_or(0);
 
-    // Total distance using haversine
-    let mut total_distance = 0.0;
-    let mut prev_lat: Option<f64> = None;
-    let mut prev_lon: Option<f64> = None;
-    for r in records {
-        if let (Some(lat), Some(lon)) = (r.latitude, r.longitude) {
-            if lat.abs() < 0.0001 && lon.abs() < 0.0001 { continue; }
-            if let (Some(plat), Some(plon)) = (prev_lat, prev_lon) {
-                total_distance += haversine_distance(plat, plon, lat, lon);
-            }
-            prev_lat = Some(lat);
-            prev_lon = Some(lon);
-        }
-    }
+    // Total distance using haversine, as stored at import
+    let total_distance = crate::derived::path_distance(records.iter().map(|r| (r.timestamp_ms, r.latitude, r.longitude)));
 
-    let home_location = records.iter()
-        .find_map(|r| match (r.longitude, r.latitude) {
-            (Some(lon), Some(lat)) if lat.abs() > 0.0001 || lon.abs() > 0.0001 => Some([lon, lat]),
-            _ => None,
-        });
+    let home_location = fixes.iter().find_map(|fix| fix.map(|(lat, lon)| [lon, lat]));
 
     let max_distance_from_home = if let Some(home) = home_location {
-        records.iter()
-            .filter_map(|r| match (r.latitude, r.longitude) {
-                (Some(lat), Some(lon)) => Some(haversine_distance(home[1], home[0], lat, lon)),
-                _ => None,
-            })
+        fixes.iter()
+            .flatten()
+            .map(|&(lat, lon)| haversine_distance(home[1], home[0], lat, lon))
             .fold(0.0_f64, f64::max)
     } else {
         0.0
@@ -1017,6 +1913,81 @@ pub fn calculate_stats_from_records(records: &[crate::models::TelemetryRecord])
     }
 }
 
+/// Footprint of the ground track: the ~110 m grid cells the aircraft spent
+/// at least a couple of seconds in. Independent of sample rate, so a TXT and
+/// a DAT log of the same flight cover nearly the same cells.
+/// Input is `(timestamp_ms, lat, lon)` in time order. Empty without GPS.
+pub fn trajectory_cells(track: &[(i64, f64, f64)]) -> std::collections::HashSet<(i64, i64)> {
+    let mut dwell: std::collections::HashMap<(i64, i64), i64> = std::collections::HashMap::new();
+    for pair in track.windows(2) {
+        let (t0, lat, lon) = pair[0];
+        let step = (pair[1].0 - t0).clamp(0, TRAJECTORY_MAX_STEP_MS);
+        let cell = (
+            (lat / TRAJECTORY_CELL_DEG).round() as i64,
+            (lon / TRAJECTORY_CELL_DEG).round() as i64,
+        );
+        *dwell.entry(cell).or_insert(0) += step;
+    }
+
+    dwell
+        .into_iter()
+        .filter(|(_, ms)| *ms >= TRAJECTORY_MIN_DWELL_MS)
+        .map(|(cell, _)| cell)
+        .collect()
+}
+
+/// Share of cells two footprints have in common (Jaccard index), 0 when
+/// either is empty
+pub fn trajectory_overlap(a: &std::collections::HashSet<(i64, i64)>, b: &std::collections::HashSet<(i64, i64)>) -> f64 {
+    let shared = a.intersection(b).count();
+    let total = a.len() + b.len() - shared;
+    if total == 0 {
+        0.0
+    } else {
+        shared as f64 / total as f64
+    }
+}
+
+/// Shift in ms to add to `track` timestamps to line it up with `reference`:
+/// the whole second at which the two are closest on average, over the
+/// positions of each second. Needs the tracks to share at least half of the
+/// shorter one, so a stretch parked on the ground cannot win on its own.
+/// None when they don't overlap long enough.
+pub fn track_time_offset(track: &[(i64, f64, f64)], reference: &[(i64, f64, f64)]) -> Option<i64> {
+    let per_second = |track: &[(i64, f64, f64)]| {
+        let mut seconds: std::collections::BTreeMap<i64, (f64, f64)> = std::collections::BTreeMap::new();
+        for &(t, lat, lon) in track {
+            seconds.entry(t.div_euclid(1000)).or_insert((lat, lon));
+        }
+        seconds
+    };
+    let moving = per_second(track);
+    let fixed = per_second(reference);
+    let min_shared = (moving.len().min(fixed.len()) / 2).max(TRACK_ALIGN_MIN_SECS);
+    let (first, last) = (*moving.keys().next()?, *moving.keys().next_back()?);
+    let (ref_first, ref_last) = (*fixed.keys().next()?, *fixed.keys().next_back()?);
+
+    let mut best: Option<(f64, i64)> = None;
+    for shift in (ref_first - last)..=(ref_last - first) {
+        let mut total = 0.0;
+        let mut shared = 0usize;
+        for (&t, &(lat, lon)) in &moving {
+            if let Some(&(ref_lat, ref_lon)) = fixed.get(&(t + shift)) {
+                total += haversine_distance(lat, lon, ref_lat, ref_lon);
+                shared += 1;
+            }
+        }
+        if shared < min_shared {
+            continue;
+        }
+        let mean = total / shared as f64;
+        if best.map_or(true, |(best_mean, _)| mean < best_mean) {
+            best = Some((mean, shift));
+        }
+    }
+    best.map(|(_, shift)| shift * 1000)
+}
+
 /// Haversine distance calculation in meters
 pub fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     const R: f64 = 6_371_000.0; // Earth's radius in meters
@@ -1034,6 +2005,51 @@ pub fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     R * c
 }
 
+/// Farthest 3D distance in meters from the home point (`[lon, lat]`, the
+/// first position when None): the ground distance combined with the height
+/// gained since that first position. GPS glitches are left out.
+pub fn max_distance_from_home(points: &[TelemetryPoint], home: Option<[f64; 2]>) -> Option<f64> {
+    let fixes = crate::gps_filter::filter_positions(points.iter().map(|p| (p.timestamp_ms, p.latitude, p.longitude)));
+    let positions: Vec<(f64, f64, Option<f64>)> = points
+        .iter()
+        .zip(fixes)
+        .filter_map(|(p, fix)| fix.map(|(lat, lon)| (lat, lon, p.height.or(p.altitude))))
+        .collect();
+    let &(first_lat, first_lon, first_height) = positions.first()?;
+    let [home_lon, home_lat] = home.unwrap_or([first_lon, first_lat]);
+    let base_height = first_height.unwrap_or(0.0);
+    positions
+        .iter()
+        .map(|&(lat, lon, height)| {
+            let ground = haversine_distance(home_lat, home_lon, lat, lon);
+            ground.hypot(height.map_or(0.0, |h| h - base_height))
+        })
+        .reduce(f64::max)
+}
+
+/// Severity of an app warning: "critical" for aircraft faults, otherwise "warning"
+fn warning_severity(text: &str) -> &'static str {
+    let lower = text.to_lowercase();
+    if CRITICAL_WARNING_KEYWORDS.iter().any(|k| lower.contains(k)) {
+        "critical"
+    } else {
+        "warning"
+    }
+}
+
+/// Position events at the last telemetry point with a GPS fix at or before
+/// their timestamp. `points` must be in time order.
+pub fn locate_events(events: &mut [FlightEvent], points: &[TelemetryPoint]) {
+    for event in events.iter_mut() {
+        let end = points.partition_point(|p| p.timestamp_ms <= event.timestamp_ms);
+        if let Some(p) = points[..end].iter().rev().find(|p| p.latitude.is_some() && p.longitude.is_some()) {
+            event.lat = p.latitude;
+            event.lon = p.longitude;
+            event.altitude = p.height.or(p.altitude);
+        }
+    }
+}
+
 /// Check if an f64 value is finite (not NaN, not Inf)
 #[inline]
 fn is_finite_f64(v: f64) -> bool {
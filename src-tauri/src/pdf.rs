@@ -0,0 +1,309 @@
+//! Minimal PDF writer for generated reports.
+//!
+//! Pages are A4 and laid out in points from the top-left corner. Only what
+//! the reports need is supported: text in the standard Helvetica fonts,
+//! lines, rectangles and dots. Nothing is embedded, so text is limited to the
+//! characters of the WinAnsi (Latin-1) encoding; anything else prints as `?`.
+
+use std::fmt::Write;
+
+pub const PAGE_WIDTH: f64 = 595.0;
+pub const PAGE_HEIGHT: f64 = 842.0;
+
+/// Advance widths of Helvetica for ASCII 32..=126, in 1/1000 em
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // space to /
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // 0-9
+    278, 278, 584, 584, 584, 556, 1015, // : to @
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // A-M
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // N-Z
+    278, 278, 278, 469, 556, 333, // [ to `
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // a-m
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // n-z
+    334, 260, 334, 584, // { to ~
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+        }
+    }
+}
+
+/// RGB color, components 0-1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color(pub f64, pub f64, pub f64);
+
+impl Color {
+    pub const WHITE: Color = Color(1.0, 1.0, 1.0);
+
+    /// Color from a `0xRRGGBB` value
+    pub const fn hex(rgb: u32) -> Color {
+        Color(
+            ((rgb >> 16) & 0xff) as f64 / 255.0,
+            ((rgb >> 8) & 0xff) as f64 / 255.0,
+            (rgb & 0xff) as f64 / 255.0,
+        )
+    }
+}
+
+/// Width of `text` set in `font` at `size` points. Widths of bold text are
+/// estimated from the regular widths; non-ASCII characters count as an `n`.
+pub fn text_width(text: &str, size: f64, font: Font) -> f64 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => u32::from(HELVETICA_WIDTHS[(code - 32) as usize]),
+            _ => 556,
+        })
+        .sum();
+    let scale = if font == Font::Bold { 1.06 } else { 1.0 };
+    units as f64 * size / 1000.0 * scale
+}
+
+/// Break `text` into lines no wider than `max_width`
+pub fn wrap_text(text: &str, size: f64, font: Font, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if !line.is_empty() && text_width(&candidate, size, font) > max_width {
+                lines.push(std::mem::take(&mut line));
+                line = word.to_string();
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Drawing operations of one page
+#[derive(Debug, Default)]
+pub struct Page {
+    content: String,
+}
+
+impl Page {
+    /// Text with its baseline at `y`
+    pub fn text(&mut self, x: f64, y: f64, size: f64, font: Font, color: Color, text: &str) {
+        let _ = writeln!(
+            self.content,
+            "BT /{} {:.1} Tf {} rg {:.2} {:.2} Td ({}) Tj ET",
+            font.resource(),
+            size,
+            rgb(color),
+            x,
+            PAGE_HEIGHT - y,
+            encode_text(text)
+        );
+    }
+
+    /// Text ending at `right`
+    pub fn text_right(&mut self, right: f64, y: f64, size: f64, font: Font, color: Color, text: &str) {
+        self.text(right - text_width(text, size, font), y, size, font, color, text);
+    }
+
+    /// Open polyline through `points`
+    pub fn polyline(&mut self, points: &[(f64, f64)], width: f64, color: Color) {
+        let Some((first, rest)) = points.split_first() else { return };
+        let _ = writeln!(self.content, "{:.2} w 1 j 1 J {} RG", width, rgb(color));
+        let _ = writeln!(self.content, "{:.2} {:.2} m", first.0, PAGE_HEIGHT - first.1);
+        for (x, y) in rest {
+            let _ = writeln!(self.content, "{:.2} {:.2} l", x, PAGE_HEIGHT - y);
+        }
+        self.content.push_str("S\n");
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), width: f64, color: Color) {
+        self.polyline(&[from, to], width, color);
+    }
+
+    pub fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        let _ = writeln!(
+            self.content,
+            "{} rg {:.2} {:.2} {:.2} {:.2} re f",
+            rgb(color),
+            x,
+            PAGE_HEIGHT - y - height,
+            width,
+            height
+        );
+    }
+
+    pub fn stroke_rect(&mut self, x: f64, y: f64, width: f64, height: f64, line_width: f64, color: Color) {
+        let _ = writeln!(
+            self.content,
+            "{:.2} w {} RG {:.2} {:.2} {:.2} {:.2} re S",
+            line_width,
+            rgb(color),
+            x,
+            PAGE_HEIGHT - y - height,
+            width,
+            height
+        );
+    }
+
+    /// Filled circle, drawn as four Bézier arcs
+    pub fn dot(&mut self, x: f64, y: f64, radius: f64, color: Color) {
+        const K: f64 = 0.552_284_75;
+        let (cx, cy, r, k) = (x, PAGE_HEIGHT - y, radius, radius * K);
+        let _ = writeln!(
+            self.content,
+            "{} rg {:.2} {:.2} m \
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c f",
+            rgb(color),
+            cx + r, cy,
+            cx + r, cy + k, cx + k, cy + r, cx, cy + r,
+            cx - k, cy + r, cx - r, cy + k, cx - r, cy,
+            cx - r, cy - k, cx - k, cy - r, cx, cy - r,
+            cx + k, cy - r, cx + r, cy - k, cx + r, cy
+        );
+    }
+}
+
+/// A document being built page by page
+#[derive(Debug, Default)]
+pub struct PdfDocument {
+    pages: Vec<Page>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new page and return it for drawing
+    pub fn add_page(&mut self) -> &mut Page {
+        self.pages.push(Page::default());
+        self.pages.last_mut().unwrap()
+    }
+
+    /// The page being drawn on, starting one if there is none yet
+    pub fn current_page(&mut self) -> &mut Page {
+        if self.pages.is_empty() {
+            self.pages.push(Page::default());
+        }
+        self.pages.last_mut().unwrap()
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut Page> {
+        self.pages.get_mut(index)
+    }
+
+    /// Serialize the document. `title` goes into the document properties.
+    pub fn finish(self, title: &str) -> Vec<u8> {
+        // Objects: 1 catalog, 2 page tree, 3-4 fonts, 5 info, then a page
+        // and its content stream for every page
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 6 + i * 2).collect();
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+            format!(
+                "<< /Title ({}) /Producer ({}) >>",
+                encode_text(title),
+                encode_text(concat!("Drone Logbook ", env!("CARGO_PKG_VERSION")))
+            )
+            .into_bytes(),
+        ];
+        for (page, page_id) in self.pages.iter().zip(&page_ids) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    page_id + 1
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(page.content.as_bytes());
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        let xref_offset = pdf.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        );
+        pdf.extend_from_slice(xref.as_bytes());
+        pdf
+    }
+}
+
+fn rgb(color: Color) -> String {
+    format!("{:.3} {:.3} {:.3}", color.0, color.1, color.2)
+}
+
+/// Text as the inside of a PDF string literal in WinAnsi encoding, with
+/// everything outside printable ASCII written as octal escapes
+fn encode_text(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        let byte = match c {
+            '\u{20ac}' => 0x80,
+            '\u{2026}' => 0x85,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201c}' => 0x93,
+            '\u{201d}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\t' | '\n' | '\r' => b' ',
+            c if (' '..='~').contains(&c) || ('\u{a0}'..='\u{ff}').contains(&c) => c as u8,
+            _ => b'?',
+        };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                encoded.push('\\');
+                encoded.push(byte as char);
+            }
+            0x20..=0x7e => encoded.push(byte as char),
+            _ => {
+                let _ = write!(encoded, "\\{:03o}", byte);
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,125 @@
+//! Flight phases: what the aircraft was doing over each stretch of time.
+//!
+//! Every point is put in one phase, then runs of the same phase are joined
+//! into ranges on the `TelemetryData.time` axis:
+//!
+//! - `rth` while the flight mode is a return to home, whatever the motion
+//! - `ground` below `GROUND_HEIGHT_M` and slower than `MOVING_SPEED_MS`
+//! - `ascent` / `descent` climbing or sinking faster than `CLIMB_RATE_MS`
+//! - `cruise` moving faster than `MOVING_SPEED_MS`, otherwise `hover`
+//!
+//! Runs shorter than `MIN_PHASE_SECS` (a gust, a brief correction) are
+//! folded into the phase before them so the ranges read as the flight did.
+
+use crate::models::{FlightPhase, TelemetryData};
+
+/// Height above takeoff below which a slow aircraft is on the ground
+const GROUND_HEIGHT_M: f64 = 0.5;
+
+/// Horizontal speed above which the aircraft is going somewhere
+const MOVING_SPEED_MS: f64 = 1.5;
+
+/// Vertical speed above which the aircraft is climbing or descending
+const CLIMB_RATE_MS: f64 = 0.8;
+
+/// Shortest run kept as a phase of its own
+const MIN_PHASE_SECS: f64 = 3.0;
+
+/// Phases of a flight, contiguous and in time order
+pub fn segment(telemetry: &TelemetryData) -> Vec<FlightPhase> {
+    let time = &telemetry.time;
+    let value = |series: &[Option<f64>], i: usize| series.get(i).copied().flatten().filter(|v| v.is_finite());
+    let climb = crate::derived::vertical_speed(time, &telemetry.height);
+
+    let mut runs: Vec<(&'static str, usize)> = Vec::new();
+    for i in 0..time.len() {
+        let speed = value(&telemetry.speed, i).or_else(|| {
+            value(&telemetry.velocity_x, i).zip(value(&telemetry.velocity_y, i)).map(|(x, y)| x.hypot(y))
+        });
+        let moving = speed.is_some_and(|s| s > MOVING_SPEED_MS);
+        let rate = climb.get(i).copied().flatten().unwrap_or(0.0);
+        let mode = telemetry.flight_mode.get(i).and_then(|m| m.as_deref());
+
+        let phase = if mode.is_some_and(|m| crate::flight_modes::category(m) == "RTH") {
+            "rth"
+        } else if value(&telemetry.height, i).is_some_and(|h| h < GROUND_HEIGHT_M) && !moving {
+            "ground"
+        } else if rate > CLIMB_RATE_MS {
+            "ascent"
+        } else if rate < -CLIMB_RATE_MS {
+            "descent"
+        } else if moving {
+            "cruise"
+        } else {
+            "hover"
+        };
+        if runs.last().map_or(true, |&(last, _)| last != phase) {
+            runs.push((phase, i));
+        }
+    }
+
+    // End time of each run: the start of the next, or the last point
+    let end = |runs: &[(&str, usize)], k: usize| runs.get(k + 1).map_or(time[time.len() - 1], |&(_, i)| time[i]);
+    let mut merged: Vec<(&'static str, usize)> = Vec::with_capacity(runs.len());
+    for k in 0..runs.len() {
+        let (phase, start) = runs[k];
+        let short = end(&runs, k) - time[start] < MIN_PHASE_SECS;
+        match merged.last() {
+            Some(&(previous, _)) if short || previous == phase => {}
+            _ => merged.push((phase, start)),
+        }
+    }
+
+    (0..merged.len())
+        .map(|k| FlightPhase {
+            phase: merged[k].0.to_string(),
+            start_secs: time[merged[k].1],
+            end_secs: end(&merged, k),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_is_split_into_phases() {
+        // 1 Hz: ground, climb to 30 m, hover, fly out with a 1 s stop, come home, land
+        let mut height = Vec::new();
+        let mut speed = Vec::new();
+        let mut mode = Vec::new();
+        for t in 0..120 {
+            let (h, v, m) = match t {
+                0..=9 => (0.0, 0.0, "P-GPS"),
+                10..=19 => (f64::from(t - 9) * 3.0, 0.0, "P-GPS"),
+                20..=39 => (30.0, 0.0, "P-GPS"),
+                50 => (30.0, 0.0, "P-GPS"),
+                40..=69 => (30.0, 8.0, "P-GPS"),
+                70..=89 => (30.0, 8.0, "Go Home"),
+                90..=109 => (30.0 - f64::from(t - 89) * 1.5, 0.0, "P-GPS"),
+                _ => (0.0, 0.0, "P-GPS"),
+            };
+            height.push(Some(h));
+            speed.push(Some(v));
+            mode.push(Some(m.to_string()));
+        }
+        let telemetry = TelemetryData {
+            time: (0..120).map(f64::from).collect(),
+            height,
+            speed,
+            flight_mode: mode,
+            ..Default::default()
+        };
+
+        let phases = segment(&telemetry);
+        let names: Vec<&str> = phases.iter().map(|p| p.phase.as_str()).collect();
+        assert_eq!(names, vec!["ground", "ascent", "hover", "cruise", "rth", "descent", "ground"]);
+        assert_eq!(phases[0].start_secs, 0.0);
+        assert_eq!(phases[6].end_secs, 119.0);
+        assert!(phases.windows(2).all(|w| w[0].end_secs == w[1].start_secs));
+        // Climb rates are windowed, so the boundaries are within a second
+        assert!((phases[3].start_secs - 40.0).abs() <= 1.0);
+        assert!((phases[4].start_secs - 70.0).abs() <= 1.0);
+    }
+}
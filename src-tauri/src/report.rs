@@ -0,0 +1,586 @@
+//! Per-flight PDF reports for clients and insurers.
+//!
+//! A report has a summary of the flight, the aircraft and pilot, a drawing of
+//! the flight path, altitude/speed/battery charts and the warnings the
+//! aircraft raised. The path is drawn on a plain grid with a scale bar: the
+//! report is built offline, so there are no map tiles behind it.
+//!
+//! The pilot's name and license number are read from `pilot_name` and
+//! `pilot_license` in config.json.
+
+use std::path::Path;
+
+use chrono::Utc;
+
+use crate::database::Database;
+use crate::export::{flight_start, max_distance_from_home, position, ExportError, ExportUnits, LengthUnit};
+use crate::library::read_config;
+use crate::models::{Flight, FlightEvent, PilotDetails, TelemetryRecord};
+use crate::pdf::{text_width, wrap_text, Color, Font, Page, PdfDocument, PAGE_HEIGHT, PAGE_WIDTH};
+
+const MARGIN: f64 = 40.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - 2.0 * MARGIN;
+/// Lowest baseline before content moves to the next page
+const CONTENT_BOTTOM: f64 = PAGE_HEIGHT - 50.0;
+
+/// Points per chart line and map path; more don't show at print size
+const MAX_PLOT_POINTS: usize = 800;
+
+/// Warnings listed before the rest are summarized as a count
+const MAX_WARNINGS: usize = 60;
+
+const HEADER_COLOR: Color = Color::hex(0x111827);
+const TEXT_COLOR: Color = Color::hex(0x111827);
+const MUTED_COLOR: Color = Color::hex(0x6b7280);
+const RULE_COLOR: Color = Color::hex(0xd1d5db);
+const GRID_COLOR: Color = Color::hex(0xe5e7eb);
+const TRACK_COLOR: Color = Color::hex(0xea580c);
+const TAKEOFF_COLOR: Color = Color::hex(0x16a34a);
+const LANDING_COLOR: Color = Color::hex(0xdc2626);
+const HOME_COLOR: Color = Color::hex(0x2563eb);
+
+/// Pilot details saved in config.json
+pub fn pilot_details(data_dir: &Path) -> PilotDetails {
+    let config = read_config(data_dir);
+    let text = |key: &str| {
+        config
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    PilotDetails {
+        name: text("pilot_name"),
+        license: text("pilot_license"),
+    }
+}
+
+/// Save the pilot details to config.json; empty values are removed
+pub fn save_pilot_details(data_dir: &Path, pilot: &PilotDetails) -> std::io::Result<()> {
+    let config_path = data_dir.join("config.json");
+    let mut config: serde_json::Value = std::fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    for (key, value) in [("pilot_name", &pilot.name), ("pilot_license", &pilot.license)] {
+        match value.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(value) => config[key] = serde_json::json!(value),
+            None => {
+                if let Some(object) = config.as_object_mut() {
+                    object.remove(key);
+                }
+            }
+        }
+    }
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+}
+
+/// PDF report of a flight, with values in `units`
+pub fn flight_report_pdf(db: &Database, flight_id: i64, units: &ExportUnits) -> Result<Vec<u8>, ExportError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(flight_id, None, flight.point_count.map(i64::from))?;
+    let events = db.get_flight_events(flight_id)?;
+    let pilot = pilot_details(&db.data_dir);
+
+    let mut report = Report::new(units);
+    report.header(&flight);
+    report.summary(&flight, &records);
+    report.aircraft(&flight, &records, &pilot);
+    report.flight_path(&flight, &records);
+    report.notes(&flight);
+    report.charts(&records);
+    report.warnings(&records, &events);
+    Ok(report.finish(&flight))
+}
+
+/// Layout state: the document and the top of the free space on the current page
+struct Report<'a> {
+    doc: PdfDocument,
+    y: f64,
+    units: &'a ExportUnits,
+}
+
+impl<'a> Report<'a> {
+    fn new(units: &'a ExportUnits) -> Self {
+        let mut doc = PdfDocument::new();
+        doc.add_page();
+        Self { doc, y: MARGIN, units }
+    }
+
+    fn page(&mut self) -> &mut Page {
+        self.doc.current_page()
+    }
+
+    /// Move to a new page unless `height` points still fit on this one
+    fn reserve(&mut self, height: f64) {
+        if self.y + height > CONTENT_BOTTOM {
+            self.doc.add_page();
+            self.y = MARGIN;
+        }
+    }
+
+    fn section(&mut self, title: &str) {
+        self.reserve(40.0);
+        let y = self.y + 14.0;
+        let page = self.page();
+        page.text(MARGIN, y, 12.0, Font::Bold, TEXT_COLOR, title);
+        page.line((MARGIN, y + 6.0), (MARGIN + CONTENT_WIDTH, y + 6.0), 0.5, RULE_COLOR);
+        self.y = y + 22.0;
+    }
+
+    /// Label/value pairs in two columns
+    fn fields(&mut self, fields: &[(&str, String)]) {
+        let column_width = CONTENT_WIDTH / 2.0;
+        for row in fields.chunks(2) {
+            self.reserve(28.0);
+            for (i, (label, value)) in row.iter().enumerate() {
+                let x = MARGIN + i as f64 * column_width;
+                let y = self.y;
+                let value = truncate(value, 10.0, Font::Bold, column_width - 12.0);
+                let page = self.page();
+                page.text(x, y, 8.0, Font::Regular, MUTED_COLOR, label);
+                page.text(x, y + 12.0, 10.0, Font::Bold, TEXT_COLOR, &value);
+            }
+            self.y += 28.0;
+        }
+    }
+
+    fn header(&mut self, flight: &Flight) {
+        let generated = format!("Generated {}", Utc::now().format("%Y-%m-%d %H:%M UTC"));
+        let name = truncate(&flight.display_name, 11.0, Font::Regular, CONTENT_WIDTH);
+        let page = self.page();
+        page.fill_rect(0.0, 0.0, PAGE_WIDTH, 72.0, HEADER_COLOR);
+        page.text(MARGIN, 34.0, 18.0, Font::Bold, Color::WHITE, "Flight Report");
+        page.text(MARGIN, 54.0, 11.0, Font::Regular, Color::hex(0xd1d5db), &name);
+        page.text_right(MARGIN + CONTENT_WIDTH, 34.0, 8.0, Font::Regular, Color::hex(0x9ca3af), &generated);
+        self.y = 84.0;
+    }
+
+    fn summary(&mut self, flight: &Flight, records: &[TelemetryRecord]) {
+        let start = flight_start(flight).map(|s| s.format("%Y-%m-%d %H:%M:%S UTC").to_string());
+        let location = [&flight.location_city, &flight.location_region, &flight.location_country]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let home = match (flight.home_lat, flight.home_lon) {
+            (Some(lat), Some(lon)) => format!("{:.6}, {:.6}", lat, lon),
+            _ => "-".to_string(),
+        };
+
+        let fields = [
+            ("Takeoff", start.unwrap_or_else(|| "-".to_string())),
+            ("Duration", flight.duration_secs.map(format_duration).unwrap_or_else(|| "-".to_string())),
+            ("Distance flown", flight.total_distance.map(|d| self.distance(d)).unwrap_or_else(|| "-".to_string())),
+            ("Max distance from home", max_distance_from_home(flight, records).map(|d| self.distance(d)).unwrap_or_else(|| "-".to_string())),
+            ("Max altitude", flight.max_altitude.map(|a| self.length(a)).unwrap_or_else(|| "-".to_string())),
+            ("Max speed", flight.max_speed.map(|s| self.speed(s)).unwrap_or_else(|| "-".to_string())),
+            ("Location", if location.is_empty() { "-".to_string() } else { location }),
+            ("Home point", home),
+        ];
+        self.section("Summary");
+        self.fields(&fields);
+    }
+
+    fn aircraft(&mut self, flight: &Flight, records: &[TelemetryRecord], pilot: &PilotDetails) {
+        let or_dash = |value: Option<&str>| value.filter(|v| !v.trim().is_empty()).unwrap_or("-").to_string();
+        let aircraft = match (flight.drone_model.as_deref(), flight.drone_nickname.as_deref()) {
+            (Some(model), Some(nickname)) if !nickname.trim().is_empty() => format!("{} ({})", model, nickname),
+            (model, _) => or_dash(model),
+        };
+        let battery_levels: Vec<i32> = records.iter().filter_map(|r| r.battery_percent).collect();
+        let battery = match (battery_levels.first(), battery_levels.last()) {
+            (Some(first), Some(last)) => format!("{}% to {}%", first, last),
+            _ => "-".to_string(),
+        };
+        let min_voltage = records.iter().filter_map(|r| r.battery_voltage).fold(None, |min: Option<f64>, v| {
+            Some(min.map_or(v, |m| m.min(v)))
+        });
+        let max_temp = records.iter().filter_map(|r| r.battery_temp).fold(None, |max: Option<f64>, v| {
+            Some(max.map_or(v, |m| m.max(v)))
+        });
+
+        let fields = [
+            ("Pilot", or_dash(pilot.name.as_deref())),
+            ("Pilot license", or_dash(pilot.license.as_deref())),
+            ("Aircraft", aircraft),
+            ("Aircraft serial", or_dash(flight.drone_serial.as_deref())),
+            ("Firmware", or_dash(flight.firmware_version.as_deref())),
+            ("Battery serial", or_dash(flight.battery_serial.as_deref())),
+            ("Battery charge", battery),
+            (
+                "Lowest voltage / highest temperature",
+                format!(
+                    "{} / {}",
+                    min_voltage.map(|v| format!("{:.2} V", v)).unwrap_or_else(|| "-".to_string()),
+                    max_temp
+                        .map(|t| format!("{:.1} {}", self.units.temperature.convert(t), self.units.temperature.label()))
+                        .unwrap_or_else(|| "-".to_string())
+                ),
+            ),
+        ];
+        self.section("Aircraft & pilot");
+        self.fields(&fields);
+    }
+
+    fn flight_path(&mut self, flight: &Flight, records: &[TelemetryRecord]) {
+        let points: Vec<(f64, f64)> = records.iter().filter_map(position).collect();
+        if points.is_empty() {
+            return;
+        }
+        let (width, height) = (CONTENT_WIDTH, 270.0);
+        self.reserve(height + 64.0);
+        self.section("Flight path");
+        let (x, y) = (MARGIN, self.y);
+
+        // Equirectangular projection around the middle of the flight,
+        // scaled to fit the box with the same scale on both axes
+        let (min_lat, max_lat) = min_max(points.iter().map(|p| p.0));
+        let (min_lon, max_lon) = min_max(points.iter().map(|p| p.1));
+        let lat0 = (min_lat + max_lat) / 2.0;
+        let cos_lat = lat0.to_radians().cos().max(0.01);
+        let meters_per_degree = 111_320.0;
+        let span_x = ((max_lon - min_lon) * cos_lat * meters_per_degree).max(10.0);
+        let span_y = ((max_lat - min_lat) * meters_per_degree).max(10.0);
+        let padding = 20.0;
+        let scale = ((width - 2.0 * padding) / span_x).min((height - 2.0 * padding) / span_y); // points per meter
+        let center_x = x + width / 2.0;
+        let center_y = y + height / 2.0;
+        let mid_lon = (min_lon + max_lon) / 2.0;
+        let project = |lat: f64, lon: f64| {
+            (
+                center_x + (lon - mid_lon) * cos_lat * meters_per_degree * scale,
+                center_y - (lat - lat0) * meters_per_degree * scale,
+            )
+        };
+
+        let (scale_meters, scale_label) = self.scale_bar(width / 4.0 / scale);
+        let page = self.page();
+        page.fill_rect(x, y, width, height, Color::hex(0xf9fafb));
+        for i in 1..6 {
+            let gx = x + width * i as f64 / 6.0;
+            page.line((gx, y), (gx, y + height), 0.3, GRID_COLOR);
+        }
+        for i in 1..4 {
+            let gy = y + height * i as f64 / 4.0;
+            page.line((x, gy), (x + width, gy), 0.3, GRID_COLOR);
+        }
+        page.stroke_rect(x, y, width, height, 0.5, RULE_COLOR);
+
+        let path: Vec<(f64, f64)> = sample(&points, MAX_PLOT_POINTS).into_iter().map(|(lat, lon)| project(lat, lon)).collect();
+        page.polyline(&path, 1.5, TRACK_COLOR);
+        if let (Some(home_lat), Some(home_lon)) = (flight.home_lat, flight.home_lon) {
+            let (hx, hy) = project(home_lat, home_lon);
+            if hx >= x && hx <= x + width && hy >= y && hy <= y + height {
+                page.dot(hx, hy, 5.0, HOME_COLOR);
+            }
+        }
+        if let (Some(first), Some(last)) = (path.first(), path.last()) {
+            page.dot(first.0, first.1, 4.0, TAKEOFF_COLOR);
+            page.dot(last.0, last.1, 4.0, LANDING_COLOR);
+        }
+
+        // Scale bar and north arrow
+        let bar = scale_meters * scale;
+        let (bx, by) = (x + 12.0, y + height - 12.0);
+        page.line((bx, by), (bx + bar, by), 1.5, TEXT_COLOR);
+        page.line((bx, by - 4.0), (bx, by), 1.0, TEXT_COLOR);
+        page.line((bx + bar, by - 4.0), (bx + bar, by), 1.0, TEXT_COLOR);
+        page.text(bx, by - 6.0, 7.5, Font::Regular, TEXT_COLOR, &scale_label);
+        let (nx, ny) = (x + width - 16.0, y + 14.0);
+        page.line((nx, ny + 16.0), (nx, ny), 1.0, TEXT_COLOR);
+        page.line((nx - 3.0, ny + 5.0), (nx, ny), 1.0, TEXT_COLOR);
+        page.line((nx + 3.0, ny + 5.0), (nx, ny), 1.0, TEXT_COLOR);
+        page.text(nx - 3.0, ny + 26.0, 8.0, Font::Bold, TEXT_COLOR, "N");
+
+        // Legend
+        let ly = y + height + 14.0;
+        let mut lx = x;
+        for (label, color) in [("Takeoff", TAKEOFF_COLOR), ("Landing", LANDING_COLOR), ("Home point", HOME_COLOR)] {
+            page.dot(lx + 4.0, ly - 3.0, 3.5, color);
+            page.text(lx + 11.0, ly, 8.0, Font::Regular, MUTED_COLOR, label);
+            lx += 20.0 + text_width(label, 8.0, Font::Regular);
+        }
+        self.y = y + height + 24.0;
+    }
+
+    fn notes(&mut self, flight: &Flight) {
+        let tags: Vec<&str> = flight.tags.iter().map(|t| t.tag.as_str()).collect();
+        let notes = flight.notes.as_deref().map(str::trim).filter(|n| !n.is_empty());
+        if tags.is_empty() && notes.is_none() {
+            return;
+        }
+        self.section("Notes");
+        let mut lines = Vec::new();
+        if !tags.is_empty() {
+            lines.extend(wrap_text(&format!("Tags: {}", tags.join(", ")), 9.5, Font::Regular, CONTENT_WIDTH));
+        }
+        if let Some(notes) = notes {
+            lines.extend(wrap_text(notes, 9.5, Font::Regular, CONTENT_WIDTH));
+        }
+        for line in lines {
+            self.reserve(14.0);
+            let y = self.y;
+            self.page().text(MARGIN, y, 9.5, Font::Regular, TEXT_COLOR, &line);
+            self.y += 14.0;
+        }
+    }
+
+    fn charts(&mut self, records: &[TelemetryRecord]) {
+        if records.is_empty() {
+            return;
+        }
+        let first_ts = records[0].timestamp_ms;
+        let times: Vec<f64> = records.iter().map(|r| (r.timestamp_ms - first_ts) as f64 / 1000.0).collect();
+        let length = self.units.length;
+        let speed = self.units.speed;
+        let series: [(String, Vec<Option<f64>>, Color); 3] = [
+            (
+                format!("Height above takeoff ({})", length.label()),
+                records.iter().map(|r| r.height.or(r.altitude).map(|h| length.convert(h))).collect(),
+                Color::hex(0x2563eb),
+            ),
+            (
+                format!("Speed ({})", speed.label()),
+                records.iter().map(|r| r.speed.map(|s| speed.convert(s))).collect(),
+                Color::hex(0x7c3aed),
+            ),
+            (
+                "Battery (%)".to_string(),
+                records.iter().map(|r| r.battery_percent.map(f64::from)).collect(),
+                Color::hex(0x16a34a),
+            ),
+        ];
+
+        // Charts start on a fresh page so all three stay together
+        self.doc.add_page();
+        self.y = MARGIN;
+        self.section("Telemetry");
+        for (title, values, color) in &series {
+            self.reserve(170.0);
+            let y = self.y;
+            draw_chart(self.page(), (MARGIN + 36.0, y, CONTENT_WIDTH - 36.0, 130.0), title, &times, values, *color);
+            self.y += 170.0;
+        }
+    }
+
+    fn warnings(&mut self, records: &[TelemetryRecord], events: &[FlightEvent]) {
+        let first_ts = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+        let warnings: Vec<&FlightEvent> = events.iter().filter(|e| e.event_type == "warning").collect();
+        self.section("Warnings");
+        if warnings.is_empty() {
+            let y = self.y;
+            self.page().text(MARGIN, y, 9.5, Font::Regular, MUTED_COLOR, "No warnings were recorded.");
+            self.y += 14.0;
+            return;
+        }
+        for event in warnings.iter().take(MAX_WARNINGS) {
+            let severity = event.severity.as_deref().unwrap_or("warning");
+            let color = match severity {
+                "critical" => LANDING_COLOR,
+                "warning" => Color::hex(0xd97706),
+                _ => MUTED_COLOR,
+            };
+            let message = event.detail.as_deref().unwrap_or("Warning");
+            let lines = wrap_text(message, 9.5, Font::Regular, CONTENT_WIDTH - 110.0);
+            self.reserve(14.0 * lines.len() as f64 + 4.0);
+            let y = self.y;
+            let offset = format_duration(((event.timestamp_ms - first_ts) as f64 / 1000.0).max(0.0));
+            let page = self.page();
+            page.text(MARGIN, y, 9.5, Font::Regular, MUTED_COLOR, &offset);
+            page.text(MARGIN + 50.0, y, 9.5, Font::Bold, color, &capitalize(severity));
+            for (i, line) in lines.iter().enumerate() {
+                page.text(MARGIN + 110.0, y + i as f64 * 14.0, 9.5, Font::Regular, TEXT_COLOR, line);
+            }
+            self.y += 14.0 * lines.len() as f64 + 4.0;
+        }
+        if warnings.len() > MAX_WARNINGS {
+            self.reserve(14.0);
+            let y = self.y;
+            let more = format!("... and {} more", warnings.len() - MAX_WARNINGS);
+            self.page().text(MARGIN, y, 9.5, Font::Regular, MUTED_COLOR, &more);
+            self.y += 14.0;
+        }
+    }
+
+    /// Page numbers in the footer, then the finished file
+    fn finish(mut self, flight: &Flight) -> Vec<u8> {
+        let total = self.doc.page_count();
+        let footer = truncate(&flight.display_name, 8.0, Font::Regular, CONTENT_WIDTH - 80.0);
+        for index in 0..total {
+            if let Some(page) = self.doc.page_mut(index) {
+                let y = PAGE_HEIGHT - 24.0;
+                page.line((MARGIN, y - 12.0), (MARGIN + CONTENT_WIDTH, y - 12.0), 0.5, RULE_COLOR);
+                page.text(MARGIN, y, 8.0, Font::Regular, MUTED_COLOR, &footer);
+                page.text_right(MARGIN + CONTENT_WIDTH, y, 8.0, Font::Regular, MUTED_COLOR, &format!("Page {} of {}", index + 1, total));
+            }
+        }
+        self.doc.finish(&format!("Flight Report - {}", flight.display_name))
+    }
+
+    fn length(&self, meters: f64) -> String {
+        format!("{:.1} {}", self.units.length.convert(meters), self.units.length.label())
+    }
+
+    /// Longer distances in km or miles, following the length unit
+    fn distance(&self, meters: f64) -> String {
+        match self.units.length {
+            LengthUnit::Meters if meters >= 1000.0 => format!("{:.2} km", meters / 1000.0),
+            LengthUnit::Feet if meters >= 1609.344 => format!("{:.2} mi", meters / 1609.344),
+            unit => format!("{:.0} {}", unit.convert(meters), unit.label()),
+        }
+    }
+
+    /// Length of a map scale bar of about `meters`, rounded to a 1, 2 or 5
+    /// step in the display unit, and its label
+    fn scale_bar(&self, meters: f64) -> (f64, String) {
+        let (step, unit, label) = match self.units.length {
+            LengthUnit::Meters => match nice_step(meters) {
+                step if step >= 1000.0 => (step / 1000.0, 1000.0, "km"),
+                step => (step, 1.0, "m"),
+            },
+            LengthUnit::Feet if meters >= 1609.344 => (nice_step(meters / 1609.344), 1609.344, "mi"),
+            LengthUnit::Feet => (nice_step(meters / 0.3048), 0.3048, "ft"),
+        };
+        (step * unit, format!("{} {}", format_axis(step), label))
+    }
+
+    fn speed(&self, meters_per_second: f64) -> String {
+        format!(
+            "{:.1} {}",
+            self.units.speed.convert(meters_per_second),
+            self.units.speed.label()
+        )
+    }
+}
+
+/// Line chart of `values` over `times` (seconds) in the `(x, y, width,
+/// height)` area, with axes and gridlines. Gaps in the values break the line.
+fn draw_chart(
+    page: &mut Page,
+    (x, y, width, height): (f64, f64, f64, f64),
+    title: &str,
+    times: &[f64],
+    values: &[Option<f64>],
+    color: Color,
+) {
+    page.text(x - 36.0, y - 8.0, 9.5, Font::Bold, TEXT_COLOR, title);
+    page.stroke_rect(x, y, width, height, 0.5, RULE_COLOR);
+
+    let present: Vec<f64> = values.iter().flatten().copied().filter(|v| v.is_finite()).collect();
+    let duration = times.last().copied().unwrap_or(0.0).max(1.0);
+    if present.is_empty() {
+        page.text(x + width / 2.0 - 20.0, y + height / 2.0, 9.0, Font::Regular, MUTED_COLOR, "No data");
+        return;
+    }
+    let (min, max) = min_max(present.iter().copied());
+    let step = nice_step((max - min).max(1.0) / 4.0);
+    let low = (min / step).floor() * step;
+    let high = ((max / step).ceil() * step).max(low + step);
+
+    // Horizontal gridlines with value labels
+    let mut value = low;
+    while value <= high + step / 2.0 {
+        let gy = y + height - (value - low) / (high - low) * height;
+        page.line((x, gy), (x + width, gy), 0.3, GRID_COLOR);
+        page.text_right(x - 4.0, gy + 3.0, 7.5, Font::Regular, MUTED_COLOR, &format_axis(value));
+        value += step;
+    }
+    // Time labels
+    for i in 0..=4 {
+        let t = duration * i as f64 / 4.0;
+        let gx = x + width * i as f64 / 4.0;
+        let label = format_duration(t);
+        let lx = (gx - text_width(&label, 7.5, Font::Regular) / 2.0).clamp(x, x + width - text_width(&label, 7.5, Font::Regular));
+        page.text(lx, y + height + 11.0, 7.5, Font::Regular, MUTED_COLOR, &label);
+    }
+
+    let step = values.len().div_ceil(MAX_PLOT_POINTS).max(1);
+    let mut segment: Vec<(f64, f64)> = Vec::new();
+    for (t, v) in times.iter().zip(values).step_by(step) {
+        match v.filter(|v| v.is_finite()) {
+            Some(v) => segment.push((
+                x + t / duration * width,
+                y + height - (v - low) / (high - low) * height,
+            )),
+            None => {
+                page.polyline(&segment, 1.2, color);
+                segment.clear();
+            }
+        }
+    }
+    page.polyline(&segment, 1.2, color);
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)))
+}
+
+/// Every n-th point so at most `max` remain, always keeping the last one
+fn sample<T: Copy>(points: &[T], max: usize) -> Vec<T> {
+    let step = points.len().div_ceil(max).max(1);
+    let mut sampled: Vec<T> = points.iter().step_by(step).copied().collect();
+    if let Some(last) = points.last() {
+        if !(points.len() - 1).is_multiple_of(step) {
+            sampled.push(*last);
+        }
+    }
+    sampled
+}
+
+/// Round a step up to 1, 2 or 5 times a power of ten
+fn nice_step(raw: f64) -> f64 {
+    if !raw.is_finite() || raw <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let fraction = raw / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+fn format_axis(value: f64) -> String {
+    if value.abs() >= 10.0 || value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let total = secs.round() as i64;
+    if total >= 3600 {
+        format!("{}h {:02}m {:02}s", total / 3600, (total % 3600) / 60, total % 60)
+    } else {
+        format!("{}m {:02}s", total / 60, total % 60)
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Shorten text with an ellipsis so it fits `max_width`
+fn truncate(text: &str, size: f64, font: Font, max_width: f64) -> String {
+    if text_width(text, size, font) <= max_width {
+        return text.to_string();
+    }
+    let mut shortened: String = text.to_string();
+    while !shortened.is_empty() && text_width(&format!("{}\u{2026}", shortened), size, font) > max_width {
+        shortened.pop();
+    }
+    format!("{}\u{2026}", shortened.trim_end())
+}
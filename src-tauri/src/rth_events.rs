@@ -0,0 +1,261 @@
+//! RTH triggers, failsafe activations and RC signal losses across the library.
+//!
+//! Each flight's safety events come from two places: flight mode changes
+//! and RC signal drops in the telemetry, and the tips and warnings the app
+//! logged. Not every format logs both, so per flight:
+//!
+//! - RTH triggers are switches into an RTH mode, or RTH messages for flights
+//!   without flight modes
+//! - signal losses are the RC signal dropping to zero or "signal lost"
+//!   messages, whichever counts more
+//! - failsafes are failsafe messages or RTH triggers shortly after a signal
+//!   loss, whichever counts more
+//!
+//! Apps repeat a warning for as long as it applies, so a message only counts
+//! again after `MESSAGE_REPEAT_MS` without it.
+
+use std::collections::BTreeMap;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{
+    DroneRthEvents, FlightRthEvents, FlightSafetyEvents, MonthRthEvents, RthEventCounts, RthStats, SafetyEvent,
+};
+
+/// Gap after which a repeated message counts as a new event
+const MESSAGE_REPEAT_MS: i64 = 30_000;
+
+/// Longest time from a signal loss to an RTH trigger for it to be a failsafe
+const FAILSAFE_WINDOW_MS: i64 = 15_000;
+
+const RTH_KEYWORDS: &[&str] = &["return to home", "returning to home", "returning home", "return home", "go home", "going home"];
+const FAILSAFE_KEYWORDS: &[&str] = &["failsafe", "fail-safe", "fail safe"];
+const SIGNAL_LOSS_KEYWORDS: &[&str] = &[
+    "signal lost",
+    "signal loss",
+    "lost connection",
+    "connection lost",
+    "aircraft disconnected",
+    "remote controller disconnected",
+];
+
+/// Counts of one flight
+#[derive(Debug, Default, PartialEq)]
+struct FlightCounts {
+    rth: i64,
+    failsafe: i64,
+    signal_loss: i64,
+}
+
+/// RTH, failsafe and signal-loss statistics of the flights counted in statistics
+pub fn rth_stats(db: &Database) -> Result<RthStats, DatabaseError> {
+    Ok(summarize(db.get_safety_events()?))
+}
+
+/// Event counts in total, per aircraft, per month and per flight
+pub(crate) fn summarize(flights: Vec<FlightSafetyEvents>) -> RthStats {
+    let mut totals = RthEventCounts::default();
+    let mut months: BTreeMap<String, RthEventCounts> = BTreeMap::new();
+    // Same key as the library statistics: the serial when known, otherwise the model
+    let mut drones: BTreeMap<String, (DroneRthEvents, BTreeMap<String, RthEventCounts>)> = BTreeMap::new();
+    let mut flagged = Vec::new();
+
+    for flight in flights {
+        let counts = count_events(&flight.events);
+        let month = flight.start_time.as_deref().and_then(|t| t.get(..7)).unwrap_or("Unknown").to_string();
+        let key = match flight.drone_serial.as_deref().map(|s| s.trim().to_uppercase()) {
+            Some(serial) if !serial.is_empty() => serial,
+            _ => format!("model:{}", flight.drone_model.as_deref().unwrap_or("Unknown")),
+        };
+
+        let (drone, drone_months) = drones.entry(key).or_insert_with(|| {
+            (
+                DroneRthEvents {
+                    drone_model: "Unknown".to_string(),
+                    drone_serial: None,
+                    counts: RthEventCounts::default(),
+                    months: Vec::new(),
+                },
+                BTreeMap::new(),
+            )
+        });
+        // Newest flight names the aircraft, as flights are oldest first
+        if let Some(model) = &flight.drone_model {
+            drone.drone_model = model.clone();
+        }
+        if flight.drone_serial.is_some() {
+            drone.drone_serial = flight.drone_serial.clone();
+        }
+        add(&mut totals, &counts);
+        add(&mut drone.counts, &counts);
+        add(months.entry(month.clone()).or_default(), &counts);
+        add(drone_months.entry(month).or_default(), &counts);
+
+        if counts != FlightCounts::default() {
+            flagged.push(FlightRthEvents {
+                flight_id: flight.flight_id,
+                display_name: flight.display_name,
+                start_time: flight.start_time,
+                drone_model: flight.drone_model,
+                drone_serial: flight.drone_serial,
+                rth_count: counts.rth,
+                failsafe_count: counts.failsafe,
+                signal_loss_count: counts.signal_loss,
+            });
+        }
+    }
+
+    let month_list = |months: BTreeMap<String, RthEventCounts>| -> Vec<MonthRthEvents> {
+        months.into_iter().map(|(month, counts)| MonthRthEvents { month, counts }).collect()
+    };
+    let rate = |counts: &RthEventCounts| counts.rth_count as f64 / counts.flight_count.max(1) as f64;
+    let mut drones: Vec<DroneRthEvents> = drones
+        .into_values()
+        .map(|(mut drone, drone_months)| {
+            drone.months = month_list(drone_months);
+            drone
+        })
+        .collect();
+    drones.sort_by(|a, b| rate(&b.counts).total_cmp(&rate(&a.counts)).then(b.counts.flight_count.cmp(&a.counts.flight_count)));
+    flagged.reverse();
+
+    RthStats {
+        totals,
+        drones,
+        months: month_list(months),
+        flights: flagged,
+    }
+}
+
+fn add(counts: &mut RthEventCounts, flight: &FlightCounts) {
+    counts.flight_count += 1;
+    counts.rth_count += flight.rth;
+    counts.failsafe_count += flight.failsafe;
+    counts.signal_loss_count += flight.signal_loss;
+    if *flight != FlightCounts::default() {
+        counts.affected_flights += 1;
+    }
+}
+
+/// What a message reports, if anything of interest
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MessageKind {
+    Rth,
+    Failsafe,
+    SignalLoss,
+}
+
+fn message_kinds(text: &str) -> Vec<MessageKind> {
+    let lower = text.to_lowercase();
+    let has = |keywords: &[&str]| keywords.iter().any(|k| lower.contains(k));
+    let mut kinds = Vec::new();
+    // "RTH" as a word only, not inside "north" or "earth"
+    if has(RTH_KEYWORDS) || lower.split(|c: char| !c.is_ascii_alphanumeric()).any(|w| w == "rth") {
+        kinds.push(MessageKind::Rth);
+    }
+    if has(FAILSAFE_KEYWORDS) {
+        kinds.push(MessageKind::Failsafe);
+    }
+    if has(SIGNAL_LOSS_KEYWORDS) {
+        kinds.push(MessageKind::SignalLoss);
+    }
+    kinds
+}
+
+/// Counts of one flight from its events in time order
+fn count_events(events: &[SafetyEvent]) -> FlightCounts {
+    let mut has_modes = false;
+    let mut in_rth = false;
+    let mut mode_rth = Vec::new();
+    let mut telemetry_losses = Vec::new();
+    let mut message_rth = Vec::new();
+    let mut message_failsafes = 0;
+    let mut message_losses = Vec::new();
+    // Last time each message kind was seen, to skip repeats
+    let mut last_seen: [Option<i64>; 3] = [None; 3];
+
+    for event in events {
+        match event.kind.as_str() {
+            "mode" => {
+                has_modes = true;
+                let rth = event
+                    .detail
+                    .as_deref()
+                    .is_some_and(|mode| crate::flight_modes::category(mode) == "RTH");
+                if rth && !in_rth {
+                    mode_rth.push(event.timestamp_ms);
+                }
+                in_rth = rth;
+            }
+            "signal_loss" => telemetry_losses.push(event.timestamp_ms),
+            "message" => {
+                for kind in message_kinds(event.detail.as_deref().unwrap_or_default()) {
+                    let slot = &mut last_seen[kind as usize];
+                    let repeated = slot.is_some_and(|t| event.timestamp_ms - t < MESSAGE_REPEAT_MS);
+                    *slot = Some(event.timestamp_ms);
+                    if repeated {
+                        continue;
+                    }
+                    match kind {
+                        MessageKind::Rth => message_rth.push(event.timestamp_ms),
+                        MessageKind::Failsafe => message_failsafes += 1,
+                        MessageKind::SignalLoss => message_losses.push(event.timestamp_ms),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let rth = if has_modes { mode_rth } else { message_rth };
+    let losses: Vec<i64> = telemetry_losses.iter().chain(&message_losses).copied().collect();
+    let rth_after_loss = rth
+        .iter()
+        .filter(|&&t| losses.iter().any(|&loss| t >= loss && t - loss <= FAILSAFE_WINDOW_MS))
+        .count() as i64;
+
+    FlightCounts {
+        rth: rth.len() as i64,
+        failsafe: message_failsafes.max(rth_after_loss),
+        signal_loss: (telemetry_losses.len() as i64).max(message_losses.len() as i64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp_ms: i64, kind: &str, detail: Option<&str>) -> SafetyEvent {
+        SafetyEvent {
+            timestamp_ms,
+            kind: kind.to_string(),
+            detail: detail.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn rth_after_signal_loss_is_a_failsafe() {
+        let events = [
+            event(0, "mode", Some("GPS")),
+            event(60_000, "signal_loss", None),
+            event(62_000, "message", Some("Remote controller signal lost. Returning to home")),
+            event(64_000, "mode", Some("GoHome")),
+            event(65_000, "mode", Some("GoHome_Ascending")),
+            event(90_000, "mode", Some("GPS")),
+            event(200_000, "mode", Some("Go Home")),
+            event(230_000, "mode", Some("AutoLanding")),
+        ];
+        let counts = count_events(&events);
+        assert_eq!(counts, FlightCounts { rth: 2, failsafe: 1, signal_loss: 1 });
+    }
+
+    #[test]
+    fn messages_count_when_modes_are_missing() {
+        let events = [
+            event(10_000, "message", Some("Low battery. Aircraft returning to home")),
+            event(20_000, "message", Some("Low battery. Aircraft returning to home")),
+            event(100_000, "message", Some("Failsafe RTH activated")),
+        ];
+        let counts = count_events(&events);
+        assert_eq!(counts, FlightCounts { rth: 2, failsafe: 1, signal_loss: 0 });
+    }
+}
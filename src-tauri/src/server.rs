@@ -17,8 +17,11 @@ use tower_http::cors::{Any, CorsLayer};
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 use crate::api::DjiApi;
-use crate::database::Database;
-use crate::models::{FlightDataResponse, FlightTag, ImportResult, OverviewStats, TelemetryData};
+use crate::database::{Database, DatabaseError, DEFAULT_AUDIT_LIMIT, DEFAULT_SEARCH_LIMIT, DEFAULT_STITCH_MAX_GAP_SECS};
+use crate::geocoder::{GeocodeError, GeocodeProvider};
+use crate::media_scanner::MediaScanner;
+use crate::models::{AuditEntry, AutoExportSettings, Battery, BatteryHealth, ChecklistItem, ChecklistTemplate, ComplianceLimits, ComplianceReport, CurrencyReport, CurrencyRequirement, DischargeCurve, DroneComparison, DroneEnergy, DroneProfile, Equipment, ExportTemplate, FirmwareHistoryEntry, FlightAnnotation, FlightChecklist, FlightDataResponse, FlightDocument, FlightFilter, FlightMedia, FlightModeStats, FlightModeTime, FlightSearchHit, FlightTag, ImportResult, Incident, Library, LibraryCheckReport, LibraryStats, MaintenanceRecord, MaintenanceStatus, MediaScanResult, MilestoneReport, Mission, OverviewStats, PersonalRecords, PilotDetails, RthStats, Site, SiteStats, SmartList, StitchCandidate, TelemetryData, TerrainProfile, TerrainSettings, TrackSmoothing, TrendBucket, TrendPoint, WarningSummary, YearReview};
+use crate::mission_parser::MissionParser;
 use crate::parser::LogParser;
 
 /// Shared application state for Axum handlers
@@ -48,6 +51,14 @@ fn compute_file_hash(path: &std::path::Path) -> Result<String, String> {
         .map_err(|e| format!("Failed to compute hash: {}", e))
 }
 
+/// Whether sync must skip a file because its flight was deleted
+fn is_blacklisted(state: &WebAppState, path: &std::path::Path) -> bool {
+    compute_file_hash(path)
+        .ok()
+        .and_then(|hash| state.db.is_file_blacklisted(&hash).ok())
+        .unwrap_or(false)
+}
+
 // ============================================================================
 // ROUTE HANDLERS
 // ============================================================================
@@ -100,6 +111,26 @@ async fn import_log(
                 file_hash: None,
             }));
         }
+        Err(crate::parser::ParserError::InTrash(trashed_flight)) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return Ok(Json(ImportResult {
+                success: false,
+                flight_id: None,
+                message: format!("This flight log belongs to '{}', which is in the trash. Restore it from the trash instead.", trashed_flight),
+                point_count: 0,
+                file_hash: None,
+            }));
+        }
+        Err(crate::parser::ParserError::LinkedToFlight(flight_id, matching_flight)) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return Ok(Json(ImportResult {
+                success: true,
+                flight_id: Some(flight_id),
+                message: format!("Linked as rich data to existing flight '{}'", matching_flight),
+                point_count: 0,
+                file_hash: None,
+            }));
+        }
         Err(e) => {
             let _ = std::fs::remove_file(&temp_path);
             log::error!("Failed to parse log {}: {}", file_name, e);
@@ -143,7 +174,7 @@ async fn import_log(
         Ok(count) => count,
         Err(e) => {
             log::error!("Failed to insert telemetry for flight {}: {}. Cleaning up.", flight_id, e);
-            if let Err(cleanup_err) = state.db.delete_flight(flight_id) {
+            if let Err(cleanup_err) = state.db.purge_flight(flight_id) {
                 log::error!("Failed to clean up flight {}: {}", flight_id, cleanup_err);
             }
             return Ok(Json(ImportResult {
@@ -156,6 +187,16 @@ async fn import_log(
         }
     };
 
+    if let Err(e) = state.db.insert_home_points(flight_id, &parse_result.home_points) {
+        log::warn!("Failed to insert home points for flight {}: {}", flight_id, e);
+    }
+    if let Err(e) = state.db.insert_motor_samples(flight_id, &parse_result.motors) {
+        log::warn!("Failed to insert motor samples for flight {}: {}", flight_id, e);
+    }
+    if let Err(e) = state.db.insert_flight_events(flight_id, &parse_result.events) {
+        log::warn!("Failed to insert events for flight {}: {}", flight_id, e);
+    }
+
     // Insert smart tags if the feature is enabled
     let config_path = state.db.data_dir.join("config.json");
     let config: serde_json::Value = if config_path.exists() {
@@ -189,11 +230,13 @@ async fn import_log(
         point_count,
         import_start.elapsed().as_secs_f64()
     );
+    crate::geocoder::locate_in_background(state.db.clone());
+    crate::auto_export::export_in_background(state.db.clone());
 
     Ok(Json(ImportResult {
         success: true,
         flight_id: Some(flight_id),
-        message: format!("Successfully imported {} telemetry points", point_count),
+        message: parse_result.import_message(point_count),
         point_count,
         file_hash: parse_result.metadata.file_hash.clone(),
     }))
@@ -233,13 +276,34 @@ async fn get_flight_data(
         .get_flight_telemetry(params.flight_id, params.max_points, known_point_count)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get telemetry: {}", e)))?;
 
-    let telemetry = TelemetryData::from_records(&telemetry_records);
-    let track = telemetry.extract_track(2000);
+    let mut telemetry = TelemetryData::from_records(&telemetry_records);
+    let track = telemetry.extract_track(2000, crate::track_smoothing::method(&state.db.data_dir));
+
+    let attachments = state.db.get_flight_attachments(params.flight_id).unwrap_or_default();
+    let home_points = state.db.get_home_points(params.flight_id).unwrap_or_default();
+    let events = state.db.get_flight_events(params.flight_id).unwrap_or_default();
+    let timestamps: Vec<i64> = telemetry_records.iter().map(|r| r.timestamp_ms).collect();
+    let motors = state.db.get_motor_series(params.flight_id, &timestamps).unwrap_or_default();
+    telemetry.cell_voltages = state.db.get_cell_voltage_series(params.flight_id, &timestamps).unwrap_or_default();
+    (telemetry.distance_from_home, telemetry.distance_from_home_3d) =
+        crate::derived::distance_from_home(&telemetry, &timestamps, &home_points, flight.home_lat.zip(flight.home_lon));
+    (telemetry.estimated_wind_speed, telemetry.estimated_wind_direction) =
+        crate::wind::estimate(&telemetry, flight.drone_model.as_deref());
+    let timeline = crate::timeline::build(&telemetry, &timestamps, &events);
+    let phases = crate::phases::segment(&telemetry);
+    let annotations = state.db.get_flight_annotations(params.flight_id).unwrap_or_default();
 
     Ok(Json(FlightDataResponse {
         flight,
         telemetry,
         track,
+        attachments,
+        home_points,
+        events,
+        timeline,
+        phases,
+        motors,
+        annotations,
     }))
 }
 
@@ -254,7 +318,272 @@ async fn get_overview_stats(
     Ok(Json(stats))
 }
 
-/// DELETE /api/flights/:id — Delete a flight
+/// POST /api/overview/stats — Totals, per-drone and per-month statistics,
+/// of `flight_ids` only when given
+#[derive(Deserialize)]
+struct LibraryStatsPayload {
+    flight_ids: Option<Vec<i64>>,
+}
+
+async fn get_library_stats(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<LibraryStatsPayload>,
+) -> Result<Json<LibraryStats>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let stats = tokio::task::spawn_blocking(move || db.get_library_stats(payload.flight_ids.as_deref()))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Stats task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get library stats: {}", e)))?;
+    Ok(Json(stats))
+}
+
+/// POST /api/overview/trends — Flight count, time and distance per day,
+/// week, month or year, of `flight_ids` only when given
+#[derive(Deserialize)]
+struct TrendsPayload {
+    bucket: TrendBucket,
+    flight_ids: Option<Vec<i64>>,
+}
+
+async fn get_trends(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<TrendsPayload>,
+) -> Result<Json<Vec<TrendPoint>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let trends = tokio::task::spawn_blocking(move || db.get_trends(payload.bucket, payload.flight_ids.as_deref()))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Trends task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get trends: {}", e)))?;
+    Ok(Json(trends))
+}
+
+/// GET /api/overview/records — Record flights with their ids
+async fn get_personal_records(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<PersonalRecords>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let records = tokio::task::spawn_blocking(move || db.get_personal_records(None))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Records task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get personal records: {}", e)))?;
+    Ok(Json(records))
+}
+
+/// GET /api/overview/milestones — Flight, hour and distance milestones with
+/// the current and longest streaks
+async fn get_milestones(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<MilestoneReport>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let report = tokio::task::spawn_blocking(move || crate::milestones::milestone_report(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Milestones task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get milestones: {}", e)))?;
+    Ok(Json(report))
+}
+
+/// GET /api/overview/compare — Two aircraft (by serial) or two models side by side
+#[derive(Deserialize)]
+struct CompareDronesQuery {
+    first: String,
+    second: String,
+}
+
+async fn compare_drones(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<CompareDronesQuery>,
+) -> Result<Json<DroneComparison>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let comparison = tokio::task::spawn_blocking(move || crate::drone_comparison::compare_drones(&db, &params.first, &params.second))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Comparison task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to compare drones: {}", e)))?;
+    Ok(Json(comparison))
+}
+
+/// GET /api/overview/currency — Recent flying of the pilot and each aircraft
+/// against the currency requirements
+async fn get_currency(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<CurrencyReport>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let report = tokio::task::spawn_blocking(move || crate::currency::currency(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Currency task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get currency: {}", e)))?;
+    Ok(Json(report))
+}
+
+/// GET /api/overview/compliance — Flights above the height limit or beyond the
+/// distance limit, with the stretches that were
+async fn get_compliance_report(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<ComplianceReport>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let report = tokio::task::spawn_blocking(move || crate::compliance::compliance_report(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Compliance task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get compliance report: {}", e)))?;
+    Ok(Json(report))
+}
+
+/// GET /api/overview/energy — Energy used per flight and the efficiency of each aircraft
+async fn get_energy_stats(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<DroneEnergy>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let drones = tokio::task::spawn_blocking(move || crate::energy::energy_stats(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Energy statistics task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get energy statistics: {}", e)))?;
+    Ok(Json(drones))
+}
+
+/// GET /api/overview/modes — Time per flight mode and mode category across the library
+async fn get_flight_mode_stats(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<FlightModeStats>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let stats = tokio::task::spawn_blocking(move || crate::flight_modes::flight_mode_stats(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Flight mode task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight mode statistics: {}", e)))?;
+    Ok(Json(stats))
+}
+
+/// GET /api/overview/rth — RTH triggers, failsafe activations and RC signal
+/// losses per aircraft and month
+async fn get_rth_stats(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<RthStats>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let stats = tokio::task::spawn_blocking(move || crate::rth_events::rth_stats(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("RTH statistics task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get RTH statistics: {}", e)))?;
+    Ok(Json(stats))
+}
+
+/// GET /api/sites — Flying sites with their visits and flight time
+async fn get_sites(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<Site>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let sites = tokio::task::spawn_blocking(move || crate::sites::sites(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Sites task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get sites: {}", e)))?;
+    Ok(Json(sites))
+}
+
+/// GET /api/sites/stats — Flight time, wind, seasons and aircraft of every site
+async fn get_site_stats(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<SiteStats>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let stats = tokio::task::spawn_blocking(move || crate::sites::site_stats(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Sites task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get site statistics: {}", e)))?;
+    Ok(Json(stats))
+}
+
+/// POST /api/sites/recluster — Cluster the home points of every flight into sites again
+async fn recluster_sites(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<Site>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let sites = tokio::task::spawn_blocking(move || crate::sites::recluster(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Sites task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to cluster sites: {}", e)))?;
+    Ok(Json(sites))
+}
+
+/// PUT /api/sites/name — Give a site a custom name
+#[derive(Deserialize)]
+struct RenameSitePayload {
+    site_id: i64,
+    name: String,
+}
+
+async fn rename_site(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<RenameSitePayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .rename_site(payload.site_id, &payload.name)
+        .map(|_| Json(true))
+        .map_err(|e| match e {
+            DatabaseError::InvalidSite(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to rename site: {}", other)),
+        })
+}
+
+/// GET /api/overview/year — Summary of one calendar year of flying
+#[derive(Deserialize)]
+struct YearReviewQuery {
+    year: i32,
+}
+
+async fn get_year_review(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<YearReviewQuery>,
+) -> Result<Json<YearReview>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let review = tokio::task::spawn_blocking(move || crate::year_review::year_review(&db, params.year))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Year in review task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get year in review: {}", e)))?;
+    Ok(Json(review))
+}
+
+/// GET /api/overview/year/pdf — Download the one-page PDF of a year in review,
+/// with values in `length` (m/ft) and `speed` (ms/kmh/mph/kn) units
+#[derive(Deserialize)]
+struct YearReviewPdfQuery {
+    year: i32,
+    #[serde(default)]
+    length: crate::export::LengthUnit,
+    #[serde(default)]
+    speed: crate::export::SpeedUnit,
+}
+
+async fn export_year_review(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<YearReviewPdfQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let units = crate::export::ExportUnits {
+        length: params.length,
+        speed: params.speed,
+        ..Default::default()
+    };
+    let year = params.year;
+    let pdf = tokio::task::spawn_blocking(move || crate::year_review::year_review_pdf(&db, year, &units))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+        .map_err(|e| export_error_response("year in review", e))?;
+
+    Ok(attachment_response("application/pdf", &format!("{}-in-review.pdf", year), pdf))
+}
+
+/// GET /api/overview/export — Download the library's aggregate statistics as JSON
+async fn export_library_stats(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let json = tokio::task::spawn_blocking(move || crate::export::library_stats_json(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+        .map_err(|e| export_error_response("statistics", e))?;
+
+    let file_name = format!("flight_stats_{}.json", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    Ok(attachment_response("application/json", &file_name, json.into_bytes()))
+}
+
+/// DELETE /api/flights/delete — Move a flight to the trash
 #[derive(Deserialize)]
 struct DeleteFlightQuery {
     flight_id: i64,
@@ -264,7 +593,7 @@ async fn delete_flight(
     AxumState(state): AxumState<WebAppState>,
     Query(params): Query<DeleteFlightQuery>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
-    log::info!("Deleting flight: {}", params.flight_id);
+    log::info!("Moving flight to trash: {}", params.flight_id);
     state
         .db
         .delete_flight(params.flight_id)
@@ -272,6 +601,60 @@ async fn delete_flight(
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete flight: {}", e)))
 }
 
+/// GET /api/flights/trash — Flights in the trash
+async fn get_deleted_flights(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<crate::models::Flight>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_deleted_flights()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get trash: {}", e)))
+}
+
+/// POST /api/flights/restore — Take a flight back out of the trash
+#[derive(Deserialize)]
+struct RestoreFlightPayload {
+    flight_id: i64,
+}
+
+async fn restore_flight(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<RestoreFlightPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    log::info!("Restoring flight from trash: {}", payload.flight_id);
+    state
+        .db
+        .restore_flight(payload.flight_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to restore flight: {}", e)))
+}
+
+/// DELETE /api/flights/purge — Permanently delete a flight
+async fn purge_flight(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DeleteFlightQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    log::info!("Permanently deleting flight: {}", params.flight_id);
+    state
+        .db
+        .purge_flight(params.flight_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to purge flight: {}", e)))
+}
+
+/// DELETE /api/flights/trash — Permanently delete every flight in the trash
+async fn empty_trash(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    log::warn!("Emptying trash");
+    state
+        .db
+        .empty_trash()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to empty trash: {}", e)))
+}
+
 /// DELETE /api/flights — Delete all flights
 async fn delete_all_flights(
     AxumState(state): AxumState<WebAppState>,
@@ -296,6 +679,53 @@ async fn deduplicate_flights(
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to deduplicate flights: {}", e)))
 }
 
+/// GET /api/flights/stitch_candidates — Find flights split across log files
+#[derive(Deserialize)]
+struct StitchCandidatesQuery {
+    max_gap_secs: Option<f64>,
+}
+
+async fn get_stitch_candidates(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<StitchCandidatesQuery>,
+) -> Result<Json<Vec<StitchCandidate>>, (StatusCode, Json<ErrorResponse>)> {
+    let max_gap_secs = params.max_gap_secs.unwrap_or_else(|| {
+        crate::library::read_config(&state.db.data_dir)
+            .get("stitch_max_gap_secs")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(DEFAULT_STITCH_MAX_GAP_SECS)
+    });
+    state
+        .db
+        .find_stitch_candidates(max_gap_secs)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to find split flights: {}", e)))
+}
+
+/// POST /api/flights/stitch — Merge a split flight into its first segment
+#[derive(Deserialize)]
+struct StitchPayload {
+    first_id: i64,
+    second_id: i64,
+}
+
+async fn stitch_flights(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<StitchPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    log::info!("Stitching flight {} into {}", payload.second_id, payload.first_id);
+    state
+        .db
+        .merge_flights(payload.first_id, payload.second_id)
+        .map(|_| Json(true))
+        .map_err(|e| match e {
+            DatabaseError::InvalidMerge(_) | DatabaseError::FlightNotFound(_) => {
+                err_response(StatusCode::BAD_REQUEST, format!("Failed to merge flights: {}", e))
+            }
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to merge flights: {}", e)),
+        })
+}
+
 /// PUT /api/flights/name — Update flight display name
 #[derive(Deserialize)]
 struct UpdateNamePayload {
@@ -345,6 +775,146 @@ async fn update_flight_notes(
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update flight notes: {}", e)))
 }
 
+/// PUT /api/flights/favorite — Star or unstar a flight
+#[derive(Deserialize)]
+struct FavoritePayload {
+    flight_id: i64,
+    favorite: bool,
+}
+
+async fn set_flight_favorite(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<FavoritePayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .set_flight_favorite(payload.flight_id, payload.favorite)
+        .map(|_| Json(payload.favorite))
+        .map_err(|e| match e {
+            DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update favorite: {}", other)),
+        })
+}
+
+/// PUT /api/flights/excluded — Leave a flight out of statistics or count it again
+#[derive(Deserialize)]
+struct ExcludedFromStatsPayload {
+    flight_id: i64,
+    excluded: bool,
+}
+
+async fn set_flight_excluded_from_stats(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<ExcludedFromStatsPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .set_flight_excluded_from_stats(payload.flight_id, payload.excluded)
+        .map(|_| Json(payload.excluded))
+        .map_err(|e| match e {
+            DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update statistics exclusion: {}", other)),
+        })
+}
+
+/// GET /api/batteries — Every battery seen in the logs with its usage
+async fn get_batteries(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<Battery>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_batteries()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get batteries: {}", e)))
+}
+
+/// GET /api/batteries/health — Capacity fade, voltage sag trend and health score of every battery
+async fn get_battery_health(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<BatteryHealth>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let health = tokio::task::spawn_blocking(move || crate::battery_health::battery_health(&db))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Battery health task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get battery health: {}", e)))?;
+    Ok(Json(health))
+}
+
+/// GET /api/batteries/discharge — Discharge curves of every flight of a battery, oldest first
+#[derive(Deserialize)]
+struct DischargeCurvesQuery {
+    serial: String,
+}
+
+async fn get_discharge_curves(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DischargeCurvesQuery>,
+) -> Result<Json<Vec<DischargeCurve>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let curves = tokio::task::spawn_blocking(move || crate::battery_health::discharge_curves(&db, &params.serial))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Discharge curves task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get discharge curves: {}", e)))?;
+    Ok(Json(curves))
+}
+
+/// GET /api/drones — Every aircraft in the logbook with its profile
+async fn get_drone_profiles(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<DroneProfile>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_drone_profiles()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get drone profiles: {}", e)))
+}
+
+/// GET /api/drones/firmware — Firmware versions a drone has flown with, oldest first
+#[derive(Deserialize)]
+struct FirmwareHistoryQuery {
+    serial: String,
+}
+
+async fn get_firmware_history(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FirmwareHistoryQuery>,
+) -> Result<Json<Vec<FirmwareHistoryEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_firmware_history(&params.serial)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get firmware history: {}", e)))
+}
+
+/// PUT /api/drones — Save the nickname, purchase date, notes and registration of a drone
+#[derive(Deserialize)]
+struct UpdateDroneProfilePayload {
+    serial: String,
+    nickname: Option<String>,
+    purchase_date: Option<String>,
+    notes: Option<String>,
+    registration: Option<String>,
+}
+
+async fn update_drone_profile(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<UpdateDroneProfilePayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    log::info!("Updating profile of drone {}", payload.serial);
+
+    state
+        .db
+        .set_drone_profile(
+            &payload.serial,
+            payload.nickname.as_deref(),
+            payload.purchase_date.as_deref(),
+            payload.notes.as_deref(),
+            payload.registration.as_deref(),
+        )
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to update drone profile: {}", e)))
+}
+
 /// GET /api/has_api_key — Check if DJI API key is configured
 async fn has_api_key(
     AxumState(state): AxumState<WebAppState>,
@@ -431,34 +1001,1627 @@ async fn export_backup(
     ).into_response())
 }
 
-/// POST /api/backup/restore — Upload and restore a backup file
-async fn import_backup(
+/// POST /api/backup/restore — Upload and restore a backup file
+async fn import_backup(
+    AxumState(state): AxumState<WebAppState>,
+    mut multipart: Multipart,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let temp_path = std::env::temp_dir().join(format!("dji-logbook-restore-{}.db.backup", uuid::Uuid::new_v4()));
+    std::fs::write(&temp_path, &data)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+    let msg = state
+        .db
+        .import_backup(&temp_path)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Restore failed: {}", e)))?;
+
+    let _ = std::fs::remove_file(&temp_path);
+
+    Ok(Json(msg))
+}
+
+/// GET /api/archive — Download the whole library as a portable archive
+async fn export_archive(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::body::Body;
+    use axum::response::IntoResponse;
+
+    let temp_path = std::env::temp_dir().join(format!("dji-logbook-dl-{}.logbook", uuid::Uuid::new_v4()));
+
+    state
+        .db
+        .export_archive(&temp_path)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Archive export failed: {}", e)))?;
+
+    let file_bytes = tokio::fs::read(&temp_path)
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read archive file: {}", e)))?;
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/octet-stream"),
+            (axum::http::header::CONTENT_DISPOSITION, "attachment; filename=\"Drone_logbook.logbook\""),
+        ],
+        Body::from(file_bytes),
+    ).into_response())
+}
+
+/// POST /api/archive/import — Upload an archive and merge it into the library
+async fn import_archive(
+    AxumState(state): AxumState<WebAppState>,
+    mut multipart: Multipart,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let temp_path = std::env::temp_dir().join(format!("dji-logbook-archive-{}.logbook", uuid::Uuid::new_v4()));
+    std::fs::write(&temp_path, &data)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+    let result = state.db.import_archive(&temp_path);
+    let _ = std::fs::remove_file(&temp_path);
+
+    result
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Archive import failed: {}", e)))
+}
+
+/// GET /api/libraries — List the default and recently opened libraries
+async fn get_libraries(AxumState(state): AxumState<WebAppState>) -> Json<Vec<Library>> {
+    Json(crate::library::list_libraries(&state.db))
+}
+
+/// POST /api/libraries/switch — Open another library, creating it if needed
+#[derive(Deserialize)]
+struct SwitchLibraryPayload {
+    library: String,
+    name: Option<String>,
+}
+
+async fn switch_library(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SwitchLibraryPayload>,
+) -> Result<Json<Library>, (StatusCode, Json<ErrorResponse>)> {
+    // Arbitrary server paths are off limits: only new names and libraries already in the list
+    let is_known = crate::library::is_known_library(&state.db.data_dir, std::path::Path::new(payload.library.trim()));
+    if !crate::library::is_library_name(payload.library.trim()) && !is_known {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Libraries are opened by name in the web version"));
+    }
+
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || crate::library::switch_library(&db, &payload.library, payload.name.as_deref()))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Library switch task failed: {}", e)))?
+        .map(Json)
+        .map_err(|e| match e {
+            crate::library::LibraryError::Invalid(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to switch library: {}", e)),
+        })
+}
+
+/// DELETE /api/libraries — Remove a library from the recent list, keeping its file
+#[derive(Deserialize)]
+struct ForgetLibraryQuery {
+    path: String,
+}
+
+async fn forget_library(
+    AxumState(state): AxumState<WebAppState>,
+    Query(query): Query<ForgetLibraryQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    crate::library::forget_library(&state.db, &query.path)
+        .map(|_| Json(true))
+        .map_err(|e| match e {
+            crate::library::LibraryError::Invalid(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove library: {}", e)),
+        })
+}
+
+/// GET /api/libraries/conflicts — Conflict copies of the open library left by a folder sync tool
+async fn get_conflict_copies(AxumState(state): AxumState<WebAppState>) -> Json<Vec<String>> {
+    Json(
+        crate::library::conflict_copies(&state.db)
+            .into_iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+    )
+}
+
+/// POST /api/libraries/reconcile — Merge a diverged copy of the open library into it
+#[derive(Deserialize)]
+struct ReconcileLibraryPayload {
+    path: String,
+}
+
+async fn reconcile_library(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<ReconcileLibraryPayload>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    // Only conflict copies and libraries already in the list, never arbitrary server paths
+    let path = std::path::PathBuf::from(payload.path.trim());
+    let allowed = crate::library::conflict_copies(&state.db).contains(&path)
+        || crate::library::is_known_library(&state.db.data_dir, &path);
+    if !allowed {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Only conflict copies and listed libraries can be reconciled"));
+    }
+
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db.reconcile_library(&path))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Reconcile task failed: {}", e)))?
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to reconcile library: {}", e)))
+}
+
+/// POST /api/library/check — Check the library for damage; with `repair` also fix what can be fixed
+#[derive(Deserialize)]
+struct CheckLibraryPayload {
+    #[serde(default)]
+    repair: bool,
+}
+
+async fn check_library(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<CheckLibraryPayload>,
+) -> Result<Json<LibraryCheckReport>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db.check_library(payload.repair))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Library check task failed: {}", e)))?
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Library check failed: {}", e)))
+}
+
+/// GET /api/keychains/export — Download cached V13+ keychains as a bundle file
+async fn export_keychains(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+
+    let cached = state
+        .db
+        .get_all_cached_keychains()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read keychain cache: {}", e)))?;
+    let json = crate::api::KeychainBundle::new(cached)
+        .to_json()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to export keychains: {}", e)))?;
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/json"),
+            (axum::http::header::CONTENT_DISPOSITION, "attachment; filename=\"Drone_logbook.keychains.json\""),
+        ],
+        json,
+    ).into_response())
+}
+
+/// POST /api/keychains/import — Upload a keychain bundle into the cache
+async fn import_keychains(
+    AxumState(state): AxumState<WebAppState>,
+    mut multipart: Multipart,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let entries = crate::api::KeychainBundle::from_json(&String::from_utf8_lossy(&data))
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e.to_string()))?
+        .into_cache_entries();
+    for (file_hash, keychains) in &entries {
+        state
+            .db
+            .cache_keychains(file_hash, keychains)
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store keychains: {}", e)))?;
+    }
+    log::info!("Imported {} keychains from uploaded bundle", entries.len());
+
+    Ok(Json(entries.len()))
+}
+
+// ============================================================================
+// MISSION ENDPOINTS
+// ============================================================================
+
+/// POST /api/missions/import — Upload a DJI Fly waypoint mission (.kmz)
+async fn import_mission(
+    AxumState(state): AxumState<WebAppState>,
+    mut multipart: Multipart,
+) -> Result<Json<Mission>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    let file_name = field.file_name().unwrap_or("mission.kmz").to_string();
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let mission = MissionParser::parse(&data, &file_name)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to parse mission: {}", e)))?;
+    if let Some(existing) = state
+        .db
+        .is_mission_imported(&mission.file_hash)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to check mission: {}", e)))?
+    {
+        return Err(err_response(
+            StatusCode::CONFLICT,
+            format!("This mission has already been imported (matches: {})", existing),
+        ));
+    }
+
+    let mission_id = state
+        .db
+        .insert_mission(&mission)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save mission: {}", e)))?;
+    state
+        .db
+        .link_mission_flights(mission_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to link mission to flights: {}", e)))?;
+
+    state
+        .db
+        .get_missions()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get missions: {}", e)))?
+        .into_iter()
+        .find(|m| m.id == mission_id)
+        .map(Json)
+        .ok_or_else(|| err_response(StatusCode::INTERNAL_SERVER_ERROR, "Mission disappeared after import"))
+}
+
+/// GET /api/missions — List missions with their linked flights
+async fn get_missions(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<Mission>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_missions()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get missions: {}", e)))
+}
+
+/// DELETE /api/missions/delete — Delete a mission
+#[derive(Deserialize)]
+struct DeleteMissionQuery {
+    mission_id: i64,
+}
+
+async fn delete_mission(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DeleteMissionQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_mission(params.mission_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete mission: {}", e)))
+}
+
+/// POST /api/missions/relink — Re-match all missions against the flight library
+async fn relink_missions(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .relink_all_missions()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to link missions: {}", e)))
+}
+
+/// POST /api/media/scan — Associate photos/videos in a server-side folder with flights
+#[derive(Deserialize)]
+struct ScanMediaPayload {
+    folder_path: String,
+}
+
+/// Whether `folder` lies inside a folder mounted for the server to read:
+/// `MEDIA_PATH` or `SYNC_LOGS_PATH`. Both sides are canonicalized, so `..`
+/// and symlinks cannot step outside.
+fn is_mounted_media_folder(folder: &std::path::Path) -> bool {
+    let Ok(folder) = folder.canonicalize() else {
+        return false;
+    };
+    ["MEDIA_PATH", "SYNC_LOGS_PATH"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .filter_map(|root| PathBuf::from(root).canonicalize().ok())
+        .any(|root| folder.starts_with(root))
+}
+
+async fn scan_media_folder(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<ScanMediaPayload>,
+) -> Result<Json<MediaScanResult>, (StatusCode, Json<ErrorResponse>)> {
+    let folder = PathBuf::from(&payload.folder_path);
+    // Arbitrary server paths are off limits (checked first so their existence isn't revealed)
+    if !is_mounted_media_folder(&folder) {
+        return Err(err_response(
+            StatusCode::FORBIDDEN,
+            "Only folders inside MEDIA_PATH or SYNC_LOGS_PATH can be scanned in the web version",
+        ));
+    }
+    if !folder.is_dir() {
+        return Err(err_response(
+            StatusCode::BAD_REQUEST,
+            format!("Folder does not exist: {}", payload.folder_path),
+        ));
+    }
+
+    let files = tokio::task::spawn_blocking(move || MediaScanner::scan_folder(&folder))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Media scan failed: {}", e)))?;
+    let matched = state
+        .db
+        .link_media_files(&files)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to associate media: {}", e)))?;
+
+    Ok(Json(MediaScanResult {
+        scanned: files.len(),
+        timestamped: files.iter().filter(|f| f.captured_at.is_some()).count(),
+        matched,
+    }))
+}
+
+/// GET /api/flights/media — Media files associated with a flight
+#[derive(Deserialize)]
+struct FlightMediaQuery {
+    flight_id: i64,
+}
+
+async fn get_flight_media(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightMediaQuery>,
+) -> Result<Json<Vec<FlightMedia>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_flight_media(params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight media: {}", e)))
+}
+
+/// GET /api/flights/modes — Time per flight mode of one flight, longest first
+#[derive(Deserialize)]
+struct FlightModesQuery {
+    flight_id: i64,
+}
+
+async fn get_flight_modes(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightModesQuery>,
+) -> Result<Json<Vec<FlightModeTime>>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let modes = tokio::task::spawn_blocking(move || crate::flight_modes::flight_modes(&db, params.flight_id))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Flight mode task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight modes: {}", e)))?;
+    Ok(Json(modes))
+}
+
+/// POST /api/flights/documents — Upload a file and attach it to a flight
+#[derive(Deserialize)]
+struct FlightDocumentsQuery {
+    flight_id: i64,
+}
+
+async fn add_flight_document(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightDocumentsQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<FlightDocument>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    let file_name = field.file_name().unwrap_or("document").to_string();
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    state
+        .db
+        .add_flight_document(params.flight_id, &file_name, &data)
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to attach document: {}", other)),
+        })
+}
+
+/// GET /api/flights/documents — Documents attached to a flight
+async fn get_flight_documents(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightDocumentsQuery>,
+) -> Result<Json<Vec<FlightDocument>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_flight_documents(params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight documents: {}", e)))
+}
+
+/// GET /api/flights/documents/file — Download an attached document
+#[derive(Deserialize)]
+struct FlightDocumentQuery {
+    document_id: i64,
+}
+
+async fn download_flight_document(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightDocumentQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::body::Body;
+    use axum::response::IntoResponse;
+
+    let (document, path) = state.db.flight_document_file(params.document_id).map_err(|e| match e {
+        DatabaseError::DocumentNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+        other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get document: {}", other)),
+    })?;
+    let file_bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read document: {}", e)))?;
+
+    let file_name = document.file_name.replace(['"', '\\', '\r', '\n'], "_");
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        Body::from(file_bytes),
+    ).into_response())
+}
+
+/// GET /api/flights/export/gpx — Download a flight as a GPX track
+#[derive(Deserialize)]
+struct ExportFlightQuery {
+    flight_id: i64,
+}
+
+async fn export_flight_gpx(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportFlightQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let flight_id = params.flight_id;
+    let (file_name, gpx) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(flight_id)?;
+        let gpx = crate::export::flight_gpx(&db, flight_id)?;
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, "gpx"), gpx))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("GPX", e))?;
+
+    Ok(attachment_response("application/gpx+xml", &file_name, gpx.into_bytes()))
+}
+
+/// GET /api/flights/export/kml — Download a flight as KML, or KMZ with `kmz=true`
+#[derive(Deserialize)]
+struct ExportKmlQuery {
+    flight_id: i64,
+    /// Add a flythrough tour along the path
+    #[serde(default)]
+    tour: bool,
+    #[serde(default)]
+    kmz: bool,
+}
+
+async fn export_flight_kml(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportKmlQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let kmz = params.kmz;
+    let (file_name, data) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let data = if kmz {
+            crate::export::flight_kmz(&db, params.flight_id, params.tour)?
+        } else {
+            crate::export::flight_kml(&db, params.flight_id, params.tour)?.into_bytes()
+        };
+        let extension = if kmz { "kmz" } else { "kml" };
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, extension), data))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("KML", e))?;
+
+    let content_type = if kmz {
+        "application/vnd.google-earth.kmz"
+    } else {
+        "application/vnd.google-earth.kml+xml"
+    };
+    Ok(attachment_response(content_type, &file_name, data))
+}
+
+/// GET /api/flights/export/csv — Download the telemetry of a flight as CSV,
+/// converted to `length` (m/ft), `speed` (ms/kmh/mph/kn) and `temperature`
+/// (c/f) units. `columns` is a comma-separated list of the telemetry columns
+/// to include; without it every stored column is.
+#[derive(Deserialize)]
+struct ExportCsvQuery {
+    flight_id: i64,
+    #[serde(default)]
+    columns: Option<String>,
+    #[serde(default)]
+    length: crate::export::LengthUnit,
+    #[serde(default)]
+    speed: crate::export::SpeedUnit,
+    #[serde(default)]
+    temperature: crate::export::TemperatureUnit,
+}
+
+async fn export_flight_csv(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportCsvQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let units = crate::export::ExportUnits {
+        length: params.length,
+        speed: params.speed,
+        temperature: params.temperature,
+    };
+    let columns: Vec<String> = params
+        .columns
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .collect();
+    let (file_name, csv) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let csv = crate::export::flight_csv(&db, params.flight_id, &columns, &units)?;
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, "csv"), csv))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("CSV", e))?;
+
+    Ok(attachment_response("text/csv; charset=utf-8", &file_name, csv.into_bytes()))
+}
+
+/// GET /api/flights/export/geojson — Download a flight's track and events as GeoJSON
+async fn export_flight_geojson(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportFlightQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let (file_name, geojson) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let geojson = crate::export::flight_geojson(&db, params.flight_id)?;
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, "geojson"), geojson))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("GeoJSON", e))?;
+
+    Ok(attachment_response("application/geo+json", &file_name, geojson.into_bytes()))
+}
+
+/// GET /api/flights/export/tlog — Download a flight's telemetry as a MAVLink tlog
+async fn export_flight_tlog(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportFlightQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let (file_name, tlog) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let tlog = crate::export::flight_tlog(&db, params.flight_id)?;
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, "tlog"), tlog))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("tlog", e))?;
+
+    Ok(attachment_response("application/octet-stream", &file_name, tlog))
+}
+
+/// GET /api/flights/export/overlay — Download telemetry on a video's time
+/// axis as CSV. `offset_secs` is the flight time at which the video starts
+/// (default: the first recording in the log); rows are `interval_secs` apart.
+#[derive(Deserialize)]
+struct ExportOverlayQuery {
+    flight_id: i64,
+    offset_secs: Option<f64>,
+    interval_secs: Option<f64>,
+    #[serde(default)]
+    length: crate::export::LengthUnit,
+    #[serde(default)]
+    speed: crate::export::SpeedUnit,
+}
+
+async fn export_flight_overlay(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportOverlayQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let mut options = crate::export::OverlayOptions {
+        offset_secs: params.offset_secs,
+        ..Default::default()
+    };
+    if let Some(interval) = params.interval_secs {
+        options.interval_secs = interval;
+    }
+    let units = crate::export::ExportUnits {
+        length: params.length,
+        speed: params.speed,
+        ..Default::default()
+    };
+    let (file_name, csv) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let csv = crate::export::flight_overlay_csv(&db, params.flight_id, &options, &units)?;
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, "overlay.csv"), csv))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("overlay data", e))?;
+
+    Ok(attachment_response("text/csv; charset=utf-8", &file_name, csv.into_bytes()))
+}
+
+/// GET /api/flights/export/srt — Download DJI-style subtitles for a video
+/// recorded during a flight; `recording` picks the video (0-based, default the first)
+#[derive(Deserialize)]
+struct ExportSrtQuery {
+    flight_id: i64,
+    #[serde(default)]
+    recording: usize,
+}
+
+async fn export_flight_srt(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportSrtQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let (file_name, srt) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let srt = crate::export::flight_srt(&db, params.flight_id, params.recording)?;
+        let extension = if params.recording == 0 { "srt".to_string() } else { format!("{}.srt", params.recording + 1) };
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, &extension), srt))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("subtitles", e))?;
+
+    Ok(attachment_response("application/x-subrip; charset=utf-8", &file_name, srt.into_bytes()))
+}
+
+/// GET /api/flights/export/report — Download a PDF report of a flight, with
+/// values in `length` (m/ft), `speed` (ms/kmh/mph/kn) and `temperature` (c/f) units
+#[derive(Deserialize)]
+struct ExportReportQuery {
+    flight_id: i64,
+    #[serde(default)]
+    length: crate::export::LengthUnit,
+    #[serde(default)]
+    speed: crate::export::SpeedUnit,
+    #[serde(default)]
+    temperature: crate::export::TemperatureUnit,
+}
+
+async fn export_flight_report(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportReportQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let units = crate::export::ExportUnits {
+        length: params.length,
+        speed: params.speed,
+        temperature: params.temperature,
+    };
+    let (file_name, pdf) = tokio::task::spawn_blocking(move || {
+        let flight = db.get_flight_by_id(params.flight_id)?;
+        let pdf = crate::report::flight_report_pdf(&db, params.flight_id, &units)?;
+        Ok::<_, crate::export::ExportError>((crate::export::file_name(&flight, "pdf"), pdf))
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("report", e))?;
+
+    Ok(attachment_response("application/pdf", &file_name, pdf))
+}
+
+/// POST /api/flights/export/batch — Download every flight matching a filter
+/// as a ZIP archive with one file per flight plus summary.csv
+#[derive(Deserialize)]
+struct BatchExportPayload {
+    #[serde(default)]
+    filter: FlightFilter,
+    format: crate::export::ExportFormat,
+    #[serde(default)]
+    columns: Vec<String>,
+    #[serde(default)]
+    units: crate::export::ExportUnits,
+}
+
+async fn export_flights_batch(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<BatchExportPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let (result, archive) = tokio::task::spawn_blocking(move || {
+        crate::export::export_batch_zip(&db, &payload.filter, payload.format, &payload.columns, &payload.units)
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Batch export failed: {}", e)))?;
+    log::info!("Batch export archive: {} of {} flights exported", result.exported, result.total);
+
+    let file_name = format!("flights_export_{}.zip", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    Ok(attachment_response("application/zip", &file_name, archive))
+}
+
+/// POST /api/flights/export/xlsx — Download every flight matching a filter
+/// as an Excel workbook, optionally with a telemetry sheet per flight
+#[derive(Deserialize)]
+struct XlsxExportPayload {
+    #[serde(default)]
+    filter: FlightFilter,
+    #[serde(default)]
+    telemetry: bool,
+    #[serde(default)]
+    units: crate::export::ExportUnits,
+}
+
+async fn export_flights_xlsx(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<XlsxExportPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let workbook = tokio::task::spawn_blocking(move || {
+        crate::export::flights_xlsx(&db, &payload.filter, payload.telemetry, &payload.units)
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("workbook", e))?;
+
+    let file_name = format!("flights_{}.xlsx", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    Ok(attachment_response(
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        &file_name,
+        workbook,
+    ))
+}
+
+/// POST /api/flights/export/parquet — Download the telemetry of one flight,
+/// or of every flight matching a filter, as a Parquet file
+#[derive(Deserialize)]
+struct ParquetExportPayload {
+    flight_id: Option<i64>,
+    #[serde(default)]
+    filter: FlightFilter,
+}
+
+async fn export_telemetry_parquet(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<ParquetExportPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let temp_path = std::env::temp_dir().join(format!("dji-logbook-dl-{}.parquet", uuid::Uuid::new_v4()));
+    let path = temp_path.clone();
+    let file_name = tokio::task::spawn_blocking(move || {
+        let (flight_ids, file_name) = match payload.flight_id {
+            Some(flight_id) => {
+                let flight = db.get_flight_by_id(flight_id)?;
+                (vec![flight_id], crate::export::file_name(&flight, "parquet"))
+            }
+            None => (
+                db.evaluate_flight_filter(&payload.filter)?,
+                format!("flights_telemetry_{}.parquet", chrono::Utc::now().format("%Y%m%d_%H%M%S")),
+            ),
+        };
+        db.export_telemetry_parquet(&flight_ids, &path)?;
+        Ok::<_, crate::export::ExportError>(file_name)
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("Parquet", e))?;
+
+    let file_bytes = tokio::fs::read(&temp_path)
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read Parquet file: {}", e)))?;
+    let _ = tokio::fs::remove_file(&temp_path).await;
+
+    Ok(attachment_response("application/vnd.apache.parquet", &file_name, file_bytes))
+}
+
+/// POST /api/flights/export/coverage — Download the tracks of every flight
+/// matching a filter as one KML or GeoJSON layer, optionally with a heat grid
+#[derive(Deserialize)]
+struct CoverageExportPayload {
+    #[serde(default)]
+    filter: FlightFilter,
+    format: crate::coverage::CoverageFormat,
+    #[serde(default)]
+    options: crate::coverage::CoverageOptions,
+}
+
+async fn export_coverage(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<CoverageExportPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let format = payload.format;
+    let coverage = tokio::task::spawn_blocking(move || {
+        crate::coverage::coverage(&db, &payload.filter, format, &payload.options)
+    })
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+    .map_err(|e| export_error_response("coverage", e))?;
+
+    let file_name = format!("coverage_{}.{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"), format.extension());
+    Ok(attachment_response(format.content_type(), &file_name, coverage.into_bytes()))
+}
+
+/// POST /api/flights/export/logbook — Download the operator logbook of every
+/// flight matching a filter as CSV or PDF
+#[derive(Deserialize)]
+struct LogbookExportPayload {
+    #[serde(default)]
+    filter: FlightFilter,
+    format: crate::logbook::LogbookFormat,
+}
+
+async fn export_logbook(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<LogbookExportPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let format = payload.format;
+    let logbook = tokio::task::spawn_blocking(move || crate::logbook::logbook(&db, &payload.filter, format))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Export task failed: {}", e)))?
+        .map_err(|e| export_error_response("logbook", e))?;
+
+    let file_name = format!("logbook_{}.{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"), format.extension());
+    Ok(attachment_response(format.content_type(), &file_name, logbook))
+}
+
+/// Map an export failure to a status: unknown flight or recording 404,
+/// flight without positions or telemetry 400
+fn export_error_response(format: &str, e: crate::export::ExportError) -> (StatusCode, Json<ErrorResponse>) {
+    match e {
+        crate::export::ExportError::Database(DatabaseError::FlightNotFound(_))
+        | crate::export::ExportError::NoRecording(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+        crate::export::ExportError::NoPositions(_) | crate::export::ExportError::NoTelemetry(_) => {
+            err_response(StatusCode::BAD_REQUEST, e.to_string())
+        }
+        other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to export {}: {}", format, other)),
+    }
+}
+
+/// File download response
+fn attachment_response(content_type: &str, file_name: &str, data: Vec<u8>) -> axum::response::Response {
+    use axum::body::Body;
+    use axum::response::IntoResponse;
+
+    (
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        Body::from(data),
+    )
+        .into_response()
+}
+
+/// DELETE /api/flights/documents — Remove an attached document
+async fn delete_flight_document(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightDocumentQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_flight_document(params.document_id)
+        .map(|_| Json(true))
+        .map_err(|e| match e {
+            DatabaseError::DocumentNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete document: {}", other)),
+        })
+}
+
+/// GET /api/warnings/summary — App tips and warnings counted across all flights
+async fn get_warning_summary(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<WarningSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_warning_summary()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get warning summary: {}", e)))
+}
+
+/// GET /api/flights/search — Ranked full-text search over flights
+#[derive(Deserialize)]
+struct SearchFlightsQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+async fn search_flights(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<SearchFlightsQuery>,
+) -> Result<Json<Vec<FlightSearchHit>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .search_flights(&params.q, params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to search flights: {}", e)))
+}
+
+// ============================================================================
+// AUDIT LOG ENDPOINTS
+// ============================================================================
+
+/// GET /api/audit_log — Audit log entries, newest first, optionally of one flight
+#[derive(Deserialize)]
+struct AuditLogQuery {
+    flight_id: Option<i64>,
+    limit: Option<usize>,
+}
+
+async fn get_audit_log(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<AuditLogQuery>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_audit_log(params.flight_id, params.limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get audit log: {}", e)))
+}
+
+// ============================================================================
+// SMART LIST ENDPOINTS
+// ============================================================================
+
+/// GET /api/smart_lists — Every saved smart list
+async fn get_smart_lists(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<SmartList>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_smart_lists()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get smart lists: {}", e)))
+}
+
+/// POST /api/smart_lists — Create a smart list, or replace it when `id` is set
+#[derive(Deserialize)]
+struct SaveSmartListPayload {
+    id: Option<i64>,
+    name: String,
+    filter: FlightFilter,
+}
+
+async fn save_smart_list(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SaveSmartListPayload>,
+) -> Result<Json<SmartList>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .save_smart_list(payload.id, &payload.name, &payload.filter)
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidSmartList(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save smart list: {}", other)),
+        })
+}
+
+/// DELETE /api/smart_lists — Remove a smart list
+#[derive(Deserialize)]
+struct SmartListQuery {
+    list_id: i64,
+}
+
+async fn delete_smart_list(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<SmartListQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_smart_list(params.list_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete smart list: {}", e)))
+}
+
+/// GET /api/smart_lists/flights — IDs of the flights in a smart list, newest first
+async fn evaluate_smart_list(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<SmartListQuery>,
+) -> Result<Json<Vec<i64>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .evaluate_smart_list(params.list_id)
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidSmartList(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to evaluate smart list: {}", other)),
+        })
+}
+
+/// POST /api/flights/filter — IDs of the flights matching an unsaved filter, newest first
+async fn evaluate_flight_filter(
+    AxumState(state): AxumState<WebAppState>,
+    Json(filter): Json<FlightFilter>,
+) -> Result<Json<Vec<i64>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .evaluate_flight_filter(&filter)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to evaluate filter: {}", e)))
+}
+
+// ============================================================================
+// EXPORT TEMPLATE ENDPOINTS
+// ============================================================================
+
+/// GET /api/export_templates/columns — Telemetry columns a template can select
+async fn get_telemetry_columns(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_telemetry_columns()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get columns: {}", e)))
+}
+
+/// GET /api/export_templates — Every saved export template
+async fn get_export_templates(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<ExportTemplate>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_export_templates()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get export templates: {}", e)))
+}
+
+/// POST /api/export_templates — Create an export template, or replace it when `id` is set
+#[derive(Deserialize)]
+struct SaveExportTemplatePayload {
+    id: Option<i64>,
+    name: String,
+    format: crate::export::ExportFormat,
+    #[serde(default)]
+    columns: Vec<String>,
+    #[serde(default)]
+    units: crate::export::ExportUnits,
+}
+
+async fn save_export_template(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SaveExportTemplatePayload>,
+) -> Result<Json<ExportTemplate>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .save_export_template(payload.id, &payload.name, payload.format, &payload.columns, &payload.units)
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidExportTemplate(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save export template: {}", other)),
+        })
+}
+
+/// DELETE /api/export_templates — Remove an export template
+#[derive(Deserialize)]
+struct ExportTemplateQuery {
+    template_id: i64,
+}
+
+async fn delete_export_template(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<ExportTemplateQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_export_template(params.template_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete export template: {}", e)))
+}
+
+// ============================================================================
+// ANNOTATION ENDPOINTS
+// ============================================================================
+
+/// POST /api/flights/annotations/add — Pin a note to a moment of a flight
+#[derive(Deserialize)]
+struct AddAnnotationPayload {
+    flight_id: i64,
+    offset_secs: f64,
+    text: String,
+}
+
+async fn add_flight_annotation(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<AddAnnotationPayload>,
+) -> Result<Json<FlightAnnotation>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.text.trim().is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Annotation text is empty"));
+    }
+    state
+        .db
+        .add_flight_annotation(payload.flight_id, payload.offset_secs, &payload.text)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to add annotation: {}", e)))
+}
+
+/// PUT /api/flights/annotations — Change the text of an annotation
+#[derive(Deserialize)]
+struct UpdateAnnotationPayload {
+    annotation_id: i64,
+    text: String,
+}
+
+async fn update_flight_annotation(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<UpdateAnnotationPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.text.trim().is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Annotation text is empty"));
+    }
+    state
+        .db
+        .update_flight_annotation(payload.annotation_id, &payload.text)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update annotation: {}", e)))
+}
+
+/// POST /api/flights/annotations/remove — Delete an annotation
+#[derive(Deserialize)]
+struct RemoveAnnotationPayload {
+    annotation_id: i64,
+}
+
+async fn delete_flight_annotation(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<RemoveAnnotationPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_flight_annotation(payload.annotation_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete annotation: {}", e)))
+}
+
+// ============================================================================
+// INCIDENT ENDPOINTS
+// ============================================================================
+
+/// POST /api/incidents — Report an incident at a moment of a flight
+#[derive(Deserialize)]
+struct AddIncidentPayload {
+    flight_id: i64,
+    offset_secs: f64,
+    kind: String,
+    severity: String,
+    description: Option<String>,
+}
+
+async fn add_incident(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<AddIncidentPayload>,
+) -> Result<Json<Incident>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .add_incident(
+            payload.flight_id,
+            payload.offset_secs,
+            &payload.kind,
+            &payload.severity,
+            payload.description.as_deref(),
+        )
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidIncident(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to add incident: {}", other)),
+        })
+}
+
+/// GET /api/incidents — Incidents, optionally for one flight, kind or severity
+#[derive(Deserialize)]
+struct IncidentsQuery {
+    flight_id: Option<i64>,
+    kind: Option<String>,
+    severity: Option<String>,
+}
+
+async fn get_incidents(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<IncidentsQuery>,
+) -> Result<Json<Vec<Incident>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_incidents(params.flight_id, params.kind.as_deref(), params.severity.as_deref())
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get incidents: {}", e)))
+}
+
+/// PUT /api/incidents — Change the kind, severity and description of an incident
+#[derive(Deserialize)]
+struct UpdateIncidentPayload {
+    incident_id: i64,
+    kind: String,
+    severity: String,
+    description: Option<String>,
+}
+
+async fn update_incident(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<UpdateIncidentPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .update_incident(payload.incident_id, &payload.kind, &payload.severity, payload.description.as_deref())
+        .map(|_| Json(true))
+        .map_err(|e| match e {
+            DatabaseError::InvalidIncident(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update incident: {}", other)),
+        })
+}
+
+/// DELETE /api/incidents — Remove an incident
+#[derive(Deserialize)]
+struct DeleteIncidentQuery {
+    incident_id: i64,
+}
+
+async fn delete_incident(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DeleteIncidentQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_incident(params.incident_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete incident: {}", e)))
+}
+
+// ============================================================================
+// EQUIPMENT ENDPOINTS
+// ============================================================================
+
+/// GET /api/equipment — The equipment inventory with usage totals
+async fn get_equipment(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<Equipment>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_equipment()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get equipment: {}", e)))
+}
+
+/// POST /api/equipment — Add an item to the inventory, or update it when `id` is set
+#[derive(Deserialize)]
+struct SaveEquipmentPayload {
+    id: Option<i64>,
+    name: String,
+    category: String,
+    serial: Option<String>,
+    notes: Option<String>,
+}
+
+async fn save_equipment(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SaveEquipmentPayload>,
+) -> Result<Json<Equipment>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .save_equipment(
+            payload.id,
+            &payload.name,
+            &payload.category,
+            payload.serial.as_deref(),
+            payload.notes.as_deref(),
+        )
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidEquipment(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save equipment: {}", other)),
+        })
+}
+
+/// DELETE /api/equipment — Remove an item from the inventory
+#[derive(Deserialize)]
+struct EquipmentIdQuery {
+    equipment_id: i64,
+}
+
+async fn delete_equipment(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<EquipmentIdQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_equipment(params.equipment_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete equipment: {}", e)))
+}
+
+/// GET /api/equipment/flights — IDs of the flights that used a piece of equipment
+async fn get_equipment_flight_ids(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<EquipmentIdQuery>,
+) -> Result<Json<Vec<i64>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_equipment_flight_ids(params.equipment_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get equipment flights: {}", e)))
+}
+
+/// GET /api/flights/equipment — IDs of the equipment used on a flight
+#[derive(Deserialize)]
+struct FlightEquipmentQuery {
+    flight_id: i64,
+}
+
+async fn get_flight_equipment(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightEquipmentQuery>,
+) -> Result<Json<Vec<i64>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_flight_equipment(params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight equipment: {}", e)))
+}
+
+/// PUT /api/flights/equipment — Replace the equipment used on a flight
+#[derive(Deserialize)]
+struct SetFlightEquipmentPayload {
+    flight_id: i64,
+    equipment_ids: Vec<i64>,
+}
+
+async fn set_flight_equipment(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SetFlightEquipmentPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .set_flight_equipment(payload.flight_id, &payload.equipment_ids)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to set flight equipment: {}", e)))
+}
+
+// ============================================================================
+// CHECKLIST ENDPOINTS
+// ============================================================================
+
+/// GET /api/checklists/templates — Every checklist template
+async fn get_checklist_templates(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<ChecklistTemplate>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_checklist_templates()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get checklist templates: {}", e)))
+}
+
+/// POST /api/checklists/templates — Create a checklist template, or replace it when `id` is set
+#[derive(Deserialize)]
+struct SaveChecklistTemplatePayload {
+    id: Option<i64>,
+    name: String,
+    items: Vec<String>,
+}
+
+async fn save_checklist_template(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SaveChecklistTemplatePayload>,
+) -> Result<Json<ChecklistTemplate>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .save_checklist_template(payload.id, &payload.name, &payload.items)
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidChecklist(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save checklist template: {}", other)),
+        })
+}
+
+/// DELETE /api/checklists/templates — Remove a checklist template
+#[derive(Deserialize)]
+struct DeleteChecklistTemplateQuery {
+    template_id: i64,
+}
+
+async fn delete_checklist_template(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DeleteChecklistTemplateQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_checklist_template(params.template_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete checklist template: {}", e)))
+}
+
+/// POST /api/flights/checklists — Record a completed checklist for a flight
+#[derive(Deserialize)]
+struct RecordChecklistPayload {
+    flight_id: i64,
+    template_id: Option<i64>,
+    name: String,
+    items: Vec<ChecklistItem>,
+    completed_by: Option<String>,
+    notes: Option<String>,
+}
+
+async fn record_flight_checklist(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<RecordChecklistPayload>,
+) -> Result<Json<FlightChecklist>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .record_flight_checklist(
+            payload.flight_id,
+            payload.template_id,
+            &payload.name,
+            &payload.items,
+            payload.completed_by.as_deref(),
+            payload.notes.as_deref(),
+        )
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidChecklist(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record checklist: {}", other)),
+        })
+}
+
+/// GET /api/flights/checklists — Checklists completed for a flight
+#[derive(Deserialize)]
+struct FlightChecklistsQuery {
+    flight_id: i64,
+}
+
+async fn get_flight_checklists(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightChecklistsQuery>,
+) -> Result<Json<Vec<FlightChecklist>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_flight_checklists(params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight checklists: {}", e)))
+}
+
+/// DELETE /api/flights/checklists — Remove a completed checklist from a flight
+#[derive(Deserialize)]
+struct DeleteFlightChecklistQuery {
+    checklist_id: i64,
+}
+
+async fn delete_flight_checklist(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DeleteFlightChecklistQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .delete_flight_checklist(params.checklist_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete checklist: {}", e)))
+}
+
+// ============================================================================
+// MAINTENANCE ENDPOINTS
+// ============================================================================
+
+/// POST /api/maintenance — Log maintenance work on a drone or battery
+#[derive(Deserialize)]
+struct AddMaintenancePayload {
+    target_type: String,
+    serial: String,
+    kind: String,
+    performed_at: Option<String>,
+    description: Option<String>,
+}
+
+async fn add_maintenance_record(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<AddMaintenancePayload>,
+) -> Result<Json<MaintenanceRecord>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .add_maintenance_record(
+            &payload.target_type,
+            &payload.serial,
+            &payload.kind,
+            payload.performed_at.as_deref(),
+            payload.description.as_deref(),
+        )
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidMaintenance(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            other => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to add maintenance record: {}", other)),
+        })
+}
+
+/// GET /api/maintenance — Maintenance history, optionally for one drone or battery
+#[derive(Deserialize)]
+struct MaintenanceRecordsQuery {
+    target_type: Option<String>,
+    serial: Option<String>,
+}
+
+async fn get_maintenance_records(
     AxumState(state): AxumState<WebAppState>,
-    mut multipart: Multipart,
-) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
-    let field = multipart
-        .next_field()
-        .await
-        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
-        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
-
-    let data = field
-        .bytes()
-        .await
-        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+    Query(params): Query<MaintenanceRecordsQuery>,
+) -> Result<Json<Vec<MaintenanceRecord>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_maintenance_records(params.target_type.as_deref(), params.serial.as_deref())
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get maintenance records: {}", e)))
+}
 
-    let temp_path = std::env::temp_dir().join(format!("dji-logbook-restore-{}.db.backup", uuid::Uuid::new_v4()));
-    std::fs::write(&temp_path, &data)
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+/// DELETE /api/maintenance — Remove a maintenance record
+#[derive(Deserialize)]
+struct DeleteMaintenanceQuery {
+    record_id: i64,
+}
 
-    let msg = state
+async fn delete_maintenance_record(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<DeleteMaintenanceQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
         .db
-        .import_backup(&temp_path)
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Restore failed: {}", e)))?;
-
-    let _ = std::fs::remove_file(&temp_path);
+        .delete_maintenance_record(params.record_id)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete maintenance record: {}", e)))
+}
 
-    Ok(Json(msg))
+/// GET /api/maintenance/status — Flights and flight hours since each drone's and battery's last maintenance
+async fn get_maintenance_status(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<MaintenanceStatus>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_maintenance_status()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get maintenance status: {}", e)))
 }
 
 // ============================================================================
@@ -572,6 +2735,307 @@ async fn set_smart_tags_enabled(
     Ok(Json(payload.enabled))
 }
 
+/// GET /api/settings/geocode_provider — Get the reverse geocoding provider
+async fn get_geocode_provider(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<String> {
+    Json(GeocodeProvider::from_config(&state.db.data_dir).as_str().to_string())
+}
+
+/// POST /api/settings/geocode_provider — Set the reverse geocoding provider ('offline' or 'nominatim')
+#[derive(Deserialize)]
+struct GeocodeProviderPayload {
+    provider: String,
+}
+
+async fn set_geocode_provider(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<GeocodeProviderPayload>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    let provider = GeocodeProvider::parse(&payload.provider).ok_or_else(|| {
+        err_response(StatusCode::BAD_REQUEST, format!("Unknown geocoding provider: {}", payload.provider))
+    })?;
+    let config_path = state.db.data_dir.join("config.json");
+    let mut config: serde_json::Value = if config_path.exists() {
+        let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    config["geocode_provider"] = serde_json::json!(provider.as_str());
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
+    Ok(Json(provider.as_str().to_string()))
+}
+
+/// GET /api/settings/pilot — Pilot name and license shown on flight reports
+async fn get_pilot_details(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<PilotDetails> {
+    Json(crate::report::pilot_details(&state.db.data_dir))
+}
+
+/// POST /api/settings/pilot — Set the pilot name and license; empty values are cleared
+async fn set_pilot_details(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<PilotDetails>,
+) -> Result<Json<PilotDetails>, (StatusCode, Json<ErrorResponse>)> {
+    crate::report::save_pilot_details(&state.db.data_dir, &payload)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
+    Ok(Json(crate::report::pilot_details(&state.db.data_dir)))
+}
+
+/// GET /api/settings/auto_export — Folder and formats newly imported flights are copied to
+async fn get_auto_export_settings(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<AutoExportSettings> {
+    Json(crate::auto_export::settings(&state.db.data_dir))
+}
+
+/// POST /api/settings/auto_export — Set the auto-export folder (on the server) and formats
+async fn set_auto_export_settings(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<AutoExportSettings>,
+) -> Result<Json<AutoExportSettings>, (StatusCode, Json<ErrorResponse>)> {
+    crate::auto_export::save_settings(&state.db.data_dir, &payload).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidInput => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+        _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)),
+    })?;
+    Ok(Json(crate::auto_export::settings(&state.db.data_dir)))
+}
+
+/// GET /api/settings/currency — Recency requirements checked by the currency report
+async fn get_currency_requirements(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<Vec<CurrencyRequirement>> {
+    Json(crate::currency::requirements(&state.db.data_dir))
+}
+
+/// POST /api/settings/currency — Replace the recency requirements
+async fn set_currency_requirements(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<Vec<CurrencyRequirement>>,
+) -> Result<Json<Vec<CurrencyRequirement>>, (StatusCode, Json<ErrorResponse>)> {
+    crate::currency::save_requirements(&state.db.data_dir, &payload).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidInput => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+        _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)),
+    })?;
+    Ok(Json(crate::currency::requirements(&state.db.data_dir)))
+}
+
+/// GET /api/settings/compliance — Height and distance limits checked by the compliance report
+async fn get_compliance_limits(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<ComplianceLimits> {
+    Json(crate::compliance::limits(&state.db.data_dir))
+}
+
+/// POST /api/settings/compliance — Replace the height and distance limits
+async fn set_compliance_limits(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<ComplianceLimits>,
+) -> Result<Json<ComplianceLimits>, (StatusCode, Json<ErrorResponse>)> {
+    crate::compliance::save_limits(&state.db.data_dir, &payload).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidInput => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+        _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)),
+    })?;
+    Ok(Json(crate::compliance::limits(&state.db.data_dir)))
+}
+
+/// GET /api/settings/terrain — Where ground elevations for the height above terrain come from
+async fn get_terrain_settings(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<TerrainSettings> {
+    Json(crate::terrain::settings(&state.db.data_dir))
+}
+
+/// POST /api/settings/terrain — Set the terrain provider and the folder of local elevation tiles
+async fn set_terrain_settings(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<TerrainSettings>,
+) -> Result<Json<TerrainSettings>, (StatusCode, Json<ErrorResponse>)> {
+    crate::terrain::save_settings(&state.db.data_dir, &payload).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidInput => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+        _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)),
+    })?;
+    Ok(Json(crate::terrain::settings(&state.db.data_dir)))
+}
+
+/// GET /api/flights/terrain — Ground elevation and height above ground along a flight (null while off)
+async fn get_flight_terrain(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<FlightDataQuery>,
+) -> Result<Json<Option<TerrainProfile>>, (StatusCode, Json<ErrorResponse>)> {
+    crate::terrain::flight_terrain(&state.db, params.flight_id, params.max_points)
+        .await
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to look up the terrain: {}", e)))
+}
+
+/// GET /api/settings/track_smoothing — Smoothing applied to the map track ('off', 'ema' or 'kalman')
+async fn get_track_smoothing(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<TrackSmoothing> {
+    Json(crate::track_smoothing::method(&state.db.data_dir))
+}
+
+/// POST /api/settings/track_smoothing — Set the smoothing applied to the map track
+#[derive(Deserialize)]
+struct TrackSmoothingPayload {
+    method: TrackSmoothing,
+}
+
+async fn set_track_smoothing(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<TrackSmoothingPayload>,
+) -> Result<Json<TrackSmoothing>, (StatusCode, Json<ErrorResponse>)> {
+    crate::track_smoothing::save_method(&state.db.data_dir, payload.method)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
+    Ok(Json(crate::track_smoothing::method(&state.db.data_dir)))
+}
+
+/// GET /api/settings/telemetry_storage — Get the telemetry storage mode ('rows' or 'packed')
+async fn get_telemetry_storage(
+    AxumState(state): AxumState<WebAppState>,
+) -> Json<String> {
+    Json(if state.db.packed_storage_enabled() { "packed" } else { "rows" }.to_string())
+}
+
+/// POST /api/settings/telemetry_storage — Switch the telemetry storage mode and convert every flight
+#[derive(Deserialize)]
+struct TelemetryStoragePayload {
+    mode: String,
+}
+
+async fn set_telemetry_storage(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<TelemetryStoragePayload>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let packed = match payload.mode.as_str() {
+        "rows" => false,
+        "packed" => true,
+        other => {
+            return Err(err_response(StatusCode::BAD_REQUEST, format!("Unknown telemetry storage mode: {}", other)))
+        }
+    };
+    let config_path = state.db.data_dir.join("config.json");
+    let mut config: serde_json::Value = if config_path.exists() {
+        let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    config["telemetry_storage"] = serde_json::json!(payload.mode);
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
+
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db.convert_telemetry_storage(packed))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Conversion task failed: {}", e)))?
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to convert telemetry storage: {}", e)))
+}
+
+/// GET /api/telemetry_archive — Number of flights whose telemetry is archived
+async fn get_archived_flight_count(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<i64>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .archived_flight_count()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to count archived flights: {}", e)))
+}
+
+/// POST /api/telemetry_archive — Archive the telemetry of flights older than N years
+#[derive(Deserialize)]
+struct ArchiveFlightsPayload {
+    years: u32,
+}
+
+async fn archive_old_flights(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<ArchiveFlightsPayload>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db.archive_old_flights(payload.years))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Archive task failed: {}", e)))?
+        .map(Json)
+        .map_err(|e| match e {
+            DatabaseError::InvalidArchiveAge => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to archive flights: {}", e)),
+        })
+}
+
+/// POST /api/telemetry_archive/restore — Move all archived telemetry back into the database
+async fn restore_archived_flights(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db.restore_archived_flights())
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Restore task failed: {}", e)))?
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to restore archived flights: {}", e)))
+}
+
+/// POST /api/locations/backfill — Look up the location of flights that have none
+#[derive(Deserialize)]
+struct BackfillLocationsPayload {
+    #[serde(default)]
+    force: bool,
+}
+
+async fn backfill_flight_locations(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<BackfillLocationsPayload>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    match crate::geocoder::locate_flights(&state.db, payload.force).await {
+        Ok(located) => Ok(Json(located)),
+        Err(GeocodeError::AlreadyRunning) => Err(err_response(
+            StatusCode::CONFLICT,
+            GeocodeError::AlreadyRunning.to_string(),
+        )),
+        Err(e) => Err(err_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to look up flight locations: {}", e),
+        )),
+    }
+}
+
+/// POST /api/flights/home_distance — Store the farthest distance from home of flights without one
+#[derive(Deserialize)]
+struct BackfillDistancePayload {
+    #[serde(default)]
+    force: bool,
+}
+
+async fn backfill_max_distance_from_home(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<BackfillDistancePayload>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let updated = tokio::task::spawn_blocking(move || db.backfill_max_distance_from_home(payload.force))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Distance from home task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to compute distances from home: {}", e)))?;
+    Ok(Json(updated))
+}
+
+/// POST /api/overview/cache/rebuild — Recompute the cached per-flight statistics of every flight
+async fn rebuild_stats_cache(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let db = state.db.clone();
+    let rebuilt = tokio::task::spawn_blocking(move || db.refresh_flight_stats_cache(true))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Statistics cache task failed: {}", e)))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to rebuild the statistics cache: {}", e)))?;
+    Ok(Json(rebuilt))
+}
+
 /// GET /api/settings/enabled_tag_types — Get enabled smart tag types
 async fn get_enabled_tag_types(
     AxumState(state): AxumState<WebAppState>,
@@ -664,6 +3128,10 @@ async fn regenerate_flight_smart_tags(
         home_lat: flight.home_lat,
         home_lon: flight.home_lon,
         point_count: flight.point_count.unwrap_or(0),
+        mission_name: flight.mission_name.clone(),
+        partial: flight.partial,
+        firmware_version: flight.firmware_version.clone(),
+        max_distance_from_home: flight.max_distance_from_home,
     };
 
     match state.db.get_flight_telemetry(flight_id, Some(50000), None) {
@@ -731,6 +3199,10 @@ async fn regenerate_smart_tags(
                     home_lat: flight.home_lat,
                     home_lon: flight.home_lon,
                     point_count: flight.point_count.unwrap_or(0),
+                    mission_name: flight.mission_name.clone(),
+                    partial: flight.partial,
+                    firmware_version: flight.firmware_version.clone(),
+                    max_distance_from_home: flight.max_distance_from_home,
                 };
 
                 match state.db.get_flight_telemetry(*flight_id, Some(50000), None) {
@@ -803,6 +3275,63 @@ struct SyncFileResponse {
     file_hash: Option<String>,
 }
 
+/// GET /api/sync/blacklist — Hashes of deleted files that sync skips
+async fn get_import_blacklist(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .get_import_blacklist()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import blacklist: {}", e)))
+}
+
+/// POST /api/sync/blacklist — Add file hashes to the blacklist
+#[derive(Deserialize)]
+struct BlacklistPayload {
+    file_hashes: Vec<String>,
+}
+
+async fn add_to_import_blacklist(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<BlacklistPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .add_to_import_blacklist(&payload.file_hashes)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update import blacklist: {}", e)))
+}
+
+/// POST /api/sync/blacklist/remove — Allow a file to be synced again
+#[derive(Deserialize)]
+struct UnblacklistPayload {
+    file_hash: String,
+}
+
+async fn remove_from_import_blacklist(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<UnblacklistPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .db
+        .remove_from_import_blacklist(&payload.file_hash)
+        .map(|_| Json(true))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update import blacklist: {}", e)))
+}
+
+/// DELETE /api/sync/blacklist — Empty the blacklist
+async fn clear_import_blacklist(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    log::warn!("Clearing import blacklist");
+    state
+        .db
+        .clear_import_blacklist()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to clear import blacklist: {}", e)))
+}
+
 /// GET /api/sync/config — Get the sync folder path configuration
 async fn get_sync_config() -> Json<SyncResponse> {
     let sync_path = std::env::var("SYNC_LOGS_PATH").ok();
@@ -851,10 +3380,11 @@ async fn get_sync_files(
         }
     };
 
-    // Get existing file hashes to filter out already-imported files
+    // Get existing and blacklisted file hashes to filter out files sync must skip
     let existing_hashes: std::collections::HashSet<String> = state.db.get_all_file_hashes()
         .unwrap_or_default()
         .into_iter()
+        .chain(state.db.get_import_blacklist().unwrap_or_default())
         .collect();
 
     let files: Vec<String> = entries
@@ -862,18 +3392,17 @@ async fn get_sync_files(
         .filter(|entry| {
             if let Ok(file_type) = entry.file_type() {
                 if file_type.is_file() {
-                    let name = entry.file_name().to_string_lossy().to_lowercase();
-                    return name.ends_with(".txt") || name.ends_with(".csv");
+                    return crate::parser::is_importable_log_file(&entry.path());
                 }
             }
             false
         })
         .filter_map(|entry| {
             let path = entry.path();
-            // Check if file is already imported by hash
+            // Check if file is already imported or blacklisted by hash
             if let Ok(hash) = compute_file_hash(&path) {
                 if existing_hashes.contains(&hash) {
-                    return None; // Skip already imported files
+                    return None; // Skip already imported and deleted files
                 }
             }
             Some(entry.file_name().to_string_lossy().to_string())
@@ -916,6 +3445,14 @@ async fn sync_single_file(
         }));
     }
 
+    if is_blacklisted(&state, &file_path) {
+        return Ok(Json(SyncFileResponse {
+            success: false,
+            message: "Skipped: the flight from this file was deleted".to_string(),
+            file_hash: None,
+        }));
+    }
+
     // Check smart tags setting
     let config_path = state.db.data_dir.join("config.json");
     let config: serde_json::Value = if config_path.exists() {
@@ -939,6 +3476,20 @@ async fn sync_single_file(
                 file_hash: None,
             }));
         }
+        Err(crate::parser::ParserError::InTrash(trashed_flight)) => {
+            return Ok(Json(SyncFileResponse {
+                success: false,
+                message: format!("In the trash (matches '{}')", trashed_flight),
+                file_hash: None,
+            }));
+        }
+        Err(crate::parser::ParserError::LinkedToFlight(_, matching_flight)) => {
+            return Ok(Json(SyncFileResponse {
+                success: true,
+                message: format!("Linked as rich data to existing flight '{}'", matching_flight),
+                file_hash: None,
+            }));
+        }
         Err(e) => {
             return Ok(Json(SyncFileResponse {
                 success: false,
@@ -975,7 +3526,7 @@ async fn sync_single_file(
 
     // Insert telemetry
     if let Err(e) = state.db.bulk_insert_telemetry(flight_id, &parse_result.points) {
-        let _ = state.db.delete_flight(flight_id);
+        let _ = state.db.purge_flight(flight_id);
         return Ok(Json(SyncFileResponse {
             success: false,
             message: format!("Failed to insert telemetry: {}", e),
@@ -983,6 +3534,16 @@ async fn sync_single_file(
         }));
     }
 
+    if let Err(e) = state.db.insert_home_points(flight_id, &parse_result.home_points) {
+        log::warn!("Failed to insert home points for flight {}: {}", flight_id, e);
+    }
+    if let Err(e) = state.db.insert_motor_samples(flight_id, &parse_result.motors) {
+        log::warn!("Failed to insert motor samples for flight {}: {}", flight_id, e);
+    }
+    if let Err(e) = state.db.insert_flight_events(flight_id, &parse_result.events) {
+        log::warn!("Failed to insert events for flight {}: {}", flight_id, e);
+    }
+
     // Insert smart tags if enabled
     if tags_enabled {
         // Filter tags based on enabled_tag_types if configured
@@ -998,6 +3559,8 @@ async fn sync_single_file(
             log::warn!("Failed to insert tags: {}", e);
         }
     }
+    crate::geocoder::locate_in_background(state.db.clone());
+    crate::auto_export::export_in_background(state.db.clone());
 
     Ok(Json(SyncFileResponse {
         success: true,
@@ -1055,8 +3618,7 @@ async fn sync_from_folder(
         .filter(|entry| {
             if let Ok(file_type) = entry.file_type() {
                 if file_type.is_file() {
-                    let name = entry.file_name().to_string_lossy().to_lowercase();
-                    return name.ends_with(".txt") || name.ends_with(".csv");
+                    return crate::parser::is_importable_log_file(&entry.path());
                 }
             }
             false
@@ -1094,6 +3656,12 @@ async fn sync_from_folder(
 
     for file_path in log_files {
         let file_name = file_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        if is_blacklisted(&state, &file_path) {
+            log::debug!("Skipping blacklisted file: {}", file_name);
+            skipped += 1;
+            continue;
+        }
         
         let parse_result = match parser.parse_log(&file_path).await {
             Ok(result) => result,
@@ -1102,6 +3670,16 @@ async fn sync_from_folder(
                 skipped += 1;
                 continue;
             }
+            Err(crate::parser::ParserError::InTrash(trashed_flight)) => {
+                log::debug!("Skipping file of trashed flight: {} — matches flight '{}'", file_name, trashed_flight);
+                skipped += 1;
+                continue;
+            }
+            Err(crate::parser::ParserError::LinkedToFlight(_, matching_flight)) => {
+                log::info!("Linked {} as rich data to existing flight '{}'", file_name, matching_flight);
+                skipped += 1;
+                continue;
+            }
             Err(e) => {
                 log::warn!("Failed to parse {}: {}", file_name, e);
                 errors += 1;
@@ -1133,11 +3711,21 @@ async fn sync_from_folder(
         // Insert telemetry
         if let Err(e) = state.db.bulk_insert_telemetry(flight_id, &parse_result.points) {
             log::warn!("Failed to insert telemetry for {}: {}", file_name, e);
-            let _ = state.db.delete_flight(flight_id);
+            let _ = state.db.purge_flight(flight_id);
             errors += 1;
             continue;
         }
 
+        if let Err(e) = state.db.insert_home_points(flight_id, &parse_result.home_points) {
+            log::warn!("Failed to insert home points for flight {}: {}", flight_id, e);
+        }
+        if let Err(e) = state.db.insert_motor_samples(flight_id, &parse_result.motors) {
+            log::warn!("Failed to insert motor samples for flight {}: {}", flight_id, e);
+        }
+        if let Err(e) = state.db.insert_flight_events(flight_id, &parse_result.events) {
+            log::warn!("Failed to insert events for flight {}: {}", flight_id, e);
+        }
+
         // Insert smart tags if enabled
         if tags_enabled {
             // Filter tags based on enabled_tag_types if configured
@@ -1164,6 +3752,10 @@ async fn sync_from_folder(
         processed, skipped, errors, elapsed
     );
     log::info!("{}", msg);
+    if processed > 0 {
+        crate::geocoder::locate_in_background(state.db.clone());
+        crate::auto_export::export_in_background(state.db.clone());
+    }
 
     Ok(Json(SyncResponse {
         processed,
@@ -1191,11 +3783,67 @@ pub fn build_router(state: WebAppState) -> Router {
         .route("/api/flights", get(get_flights))
         .route("/api/flight_data", get(get_flight_data))
         .route("/api/overview", get(get_overview_stats))
+        .route("/api/overview/stats", post(get_library_stats))
+        .route("/api/overview/trends", post(get_trends))
+        .route("/api/overview/records", get(get_personal_records))
+        .route("/api/overview/milestones", get(get_milestones))
+        .route("/api/overview/compare", get(compare_drones))
+        .route("/api/overview/currency", get(get_currency))
+        .route("/api/overview/compliance", get(get_compliance_report))
+        .route("/api/overview/energy", get(get_energy_stats))
+        .route("/api/overview/modes", get(get_flight_mode_stats))
+        .route("/api/overview/rth", get(get_rth_stats))
+        .route("/api/overview/export", get(export_library_stats))
+        .route("/api/overview/cache/rebuild", post(rebuild_stats_cache))
+        .route("/api/overview/year", get(get_year_review))
+        .route("/api/overview/year/pdf", get(export_year_review))
+        .route("/api/sites", get(get_sites))
+        .route("/api/sites/stats", get(get_site_stats))
+        .route("/api/sites/recluster", post(recluster_sites))
+        .route("/api/sites/name", put(rename_site))
         .route("/api/flights/delete", delete(delete_flight))
+        .route("/api/flights/trash", get(get_deleted_flights).delete(empty_trash))
+        .route("/api/flights/restore", post(restore_flight))
+        .route("/api/flights/purge", delete(purge_flight))
         .route("/api/flights/delete_all", delete(delete_all_flights))
         .route("/api/flights/deduplicate", post(deduplicate_flights))
+        .route("/api/flights/stitch_candidates", get(get_stitch_candidates))
+        .route("/api/flights/stitch", post(stitch_flights))
         .route("/api/flights/name", put(update_flight_name))
         .route("/api/flights/notes", put(update_flight_notes))
+        .route("/api/flights/favorite", put(set_flight_favorite))
+        .route("/api/flights/excluded", put(set_flight_excluded_from_stats))
+        .route("/api/batteries", get(get_batteries))
+        .route("/api/batteries/health", get(get_battery_health))
+        .route("/api/batteries/discharge", get(get_discharge_curves))
+        .route("/api/drones", get(get_drone_profiles).put(update_drone_profile))
+        .route("/api/drones/firmware", get(get_firmware_history))
+        .route("/api/flights/annotations/add", post(add_flight_annotation))
+        .route("/api/flights/annotations", put(update_flight_annotation))
+        .route("/api/flights/annotations/remove", post(delete_flight_annotation))
+        .route(
+            "/api/equipment",
+            get(get_equipment).post(save_equipment).delete(delete_equipment),
+        )
+        .route("/api/equipment/flights", get(get_equipment_flight_ids))
+        .route("/api/flights/equipment", get(get_flight_equipment).put(set_flight_equipment))
+        .route(
+            "/api/checklists/templates",
+            get(get_checklist_templates).post(save_checklist_template).delete(delete_checklist_template),
+        )
+        .route(
+            "/api/flights/checklists",
+            get(get_flight_checklists).post(record_flight_checklist).delete(delete_flight_checklist),
+        )
+        .route(
+            "/api/incidents",
+            get(get_incidents).post(add_incident).put(update_incident).delete(delete_incident),
+        )
+        .route(
+            "/api/maintenance",
+            get(get_maintenance_records).post(add_maintenance_record).delete(delete_maintenance_record),
+        )
+        .route("/api/maintenance/status", get(get_maintenance_status))
         .route("/api/flights/tags/add", post(add_flight_tag))
         .route("/api/flights/tags/remove", post(remove_flight_tag))
         .route("/api/tags", get(get_all_tags))
@@ -1204,6 +3852,23 @@ pub fn build_router(state: WebAppState) -> Router {
         .route("/api/settings/smart_tags", post(set_smart_tags_enabled))
         .route("/api/settings/enabled_tag_types", get(get_enabled_tag_types))
         .route("/api/settings/enabled_tag_types", post(set_enabled_tag_types))
+        .route("/api/settings/geocode_provider", get(get_geocode_provider))
+        .route("/api/settings/geocode_provider", post(set_geocode_provider))
+        .route("/api/settings/pilot", get(get_pilot_details))
+        .route("/api/settings/pilot", post(set_pilot_details))
+        .route("/api/settings/auto_export", get(get_auto_export_settings))
+        .route("/api/settings/auto_export", post(set_auto_export_settings))
+        .route("/api/settings/currency", get(get_currency_requirements).post(set_currency_requirements))
+        .route("/api/settings/compliance", get(get_compliance_limits).post(set_compliance_limits))
+        .route("/api/settings/terrain", get(get_terrain_settings).post(set_terrain_settings))
+        .route("/api/settings/track_smoothing", get(get_track_smoothing).post(set_track_smoothing))
+        .route("/api/settings/telemetry_storage", get(get_telemetry_storage))
+        .route("/api/settings/telemetry_storage", post(set_telemetry_storage))
+        .route("/api/telemetry_archive", get(get_archived_flight_count))
+        .route("/api/telemetry_archive", post(archive_old_flights))
+        .route("/api/telemetry_archive/restore", post(restore_archived_flights))
+        .route("/api/locations/backfill", post(backfill_flight_locations))
+        .route("/api/flights/home_distance", post(backfill_max_distance_from_home))
         .route("/api/regenerate_smart_tags", post(regenerate_smart_tags))
         .route("/api/regenerate_flight_smart_tags/:id", post(regenerate_flight_smart_tags))
         .route("/api/has_api_key", get(has_api_key))
@@ -1214,10 +3879,62 @@ pub fn build_router(state: WebAppState) -> Router {
         .route("/api/app_log_dir", get(get_app_log_dir))
         .route("/api/backup", get(export_backup))
         .route("/api/backup/restore", post(import_backup))
+        .route("/api/archive", get(export_archive))
+        .route("/api/archive/import", post(import_archive))
+        .route("/api/libraries", get(get_libraries))
+        .route("/api/libraries", delete(forget_library))
+        .route("/api/libraries/switch", post(switch_library))
+        .route("/api/libraries/conflicts", get(get_conflict_copies))
+        .route("/api/libraries/reconcile", post(reconcile_library))
+        .route("/api/library/check", post(check_library))
+        .route("/api/keychains/export", get(export_keychains))
+        .route("/api/keychains/import", post(import_keychains))
+        .route("/api/missions", get(get_missions))
+        .route("/api/missions/import", post(import_mission))
+        .route("/api/missions/delete", delete(delete_mission))
+        .route("/api/missions/relink", post(relink_missions))
+        .route("/api/media/scan", post(scan_media_folder))
+        .route("/api/flights/media", get(get_flight_media))
+        .route("/api/flights/modes", get(get_flight_modes))
+        .route("/api/flights/terrain", get(get_flight_terrain))
+        .route(
+            "/api/flights/documents",
+            get(get_flight_documents).post(add_flight_document).delete(delete_flight_document),
+        )
+        .route("/api/flights/documents/file", get(download_flight_document))
+        .route("/api/flights/export/gpx", get(export_flight_gpx))
+        .route("/api/flights/export/kml", get(export_flight_kml))
+        .route("/api/flights/export/csv", get(export_flight_csv))
+        .route("/api/flights/export/geojson", get(export_flight_geojson))
+        .route("/api/flights/export/tlog", get(export_flight_tlog))
+        .route("/api/flights/export/report", get(export_flight_report))
+        .route("/api/flights/export/overlay", get(export_flight_overlay))
+        .route("/api/flights/export/srt", get(export_flight_srt))
+        .route("/api/flights/export/batch", post(export_flights_batch))
+        .route("/api/flights/export/xlsx", post(export_flights_xlsx))
+        .route("/api/flights/export/logbook", post(export_logbook))
+        .route("/api/flights/export/coverage", post(export_coverage))
+        .route("/api/flights/export/parquet", post(export_telemetry_parquet))
+        .route("/api/warnings/summary", get(get_warning_summary))
+        .route("/api/flights/search", get(search_flights))
+        .route("/api/flights/filter", post(evaluate_flight_filter))
+        .route("/api/export_templates", get(get_export_templates))
+        .route("/api/export_templates", post(save_export_template))
+        .route("/api/export_templates", delete(delete_export_template))
+        .route("/api/export_templates/columns", get(get_telemetry_columns))
+        .route("/api/audit_log", get(get_audit_log))
+        .route("/api/smart_lists", get(get_smart_lists))
+        .route("/api/smart_lists", post(save_smart_list))
+        .route("/api/smart_lists", delete(delete_smart_list))
+        .route("/api/smart_lists/flights", get(evaluate_smart_list))
         .route("/api/sync/config", get(get_sync_config))
         .route("/api/sync/files", get(get_sync_files))
         .route("/api/sync/file", post(sync_single_file))
         .route("/api/sync", post(sync_from_folder))
+        .route("/api/sync/blacklist", get(get_import_blacklist))
+        .route("/api/sync/blacklist", post(add_to_import_blacklist))
+        .route("/api/sync/blacklist", delete(clear_import_blacklist))
+        .route("/api/sync/blacklist/remove", post(remove_from_import_blacklist))
         .layer(cors)
         .layer(DefaultBodyLimit::max(250 * 1024 * 1024)) // 250 MB
         .with_state(state)
@@ -1321,8 +4038,7 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
         .filter(|entry| {
             if let Ok(file_type) = entry.file_type() {
                 if file_type.is_file() {
-                    let name = entry.file_name().to_string_lossy().to_lowercase();
-                    return name.ends_with(".txt") || name.ends_with(".csv");
+                    return crate::parser::is_importable_log_file(&entry.path());
                 }
             }
             false
@@ -1353,10 +4069,17 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
     
     for file_path in log_files {
         let file_name = file_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        if is_blacklisted(state, &file_path) {
+            skipped += 1;
+            continue;
+        }
         
         let parse_result = match parser.parse_log(&file_path).await {
             Ok(result) => result,
-            Err(crate::parser::ParserError::AlreadyImported(_)) => {
+            Err(crate::parser::ParserError::AlreadyImported(_))
+            | Err(crate::parser::ParserError::InTrash(_))
+            | Err(crate::parser::ParserError::LinkedToFlight(_, _)) => {
                 skipped += 1;
                 continue;
             }
@@ -1390,11 +4113,21 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
         // Insert telemetry
         if let Err(e) = state.db.bulk_insert_telemetry(flight_id, &parse_result.points) {
             log::warn!("Scheduled sync: Failed to insert telemetry for {}: {}", file_name, e);
-            let _ = state.db.delete_flight(flight_id);
+            let _ = state.db.purge_flight(flight_id);
             errors += 1;
             continue;
         }
         
+        if let Err(e) = state.db.insert_home_points(flight_id, &parse_result.home_points) {
+            log::warn!("Failed to insert home points for flight {}: {}", flight_id, e);
+        }
+        if let Err(e) = state.db.insert_motor_samples(flight_id, &parse_result.motors) {
+            log::warn!("Failed to insert motor samples for flight {}: {}", flight_id, e);
+        }
+        if let Err(e) = state.db.insert_flight_events(flight_id, &parse_result.events) {
+            log::warn!("Failed to insert events for flight {}: {}", flight_id, e);
+        }
+
         // Insert smart tags if enabled
         if tags_enabled {
             // Filter tags based on enabled_tag_types if configured
@@ -1414,6 +4147,10 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
         processed += 1;
         log::debug!("Scheduled sync: Imported {}", file_name);
     }
+    if processed > 0 {
+        crate::geocoder::locate_in_background(state.db.clone());
+        crate::auto_export::export_in_background(state.db.clone());
+    }
     
     Ok((processed, skipped, errors))
 }
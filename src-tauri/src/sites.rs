@@ -0,0 +1,376 @@
+//! Flying sites: flights grouped by where they took off.
+//!
+//! Home points are clustered with DBSCAN: two home points within
+//! `SITE_RADIUS_M` of each other belong to the same site, and clusters grow
+//! through chains of such neighbours. A home point with no neighbour is a
+//! site of its own. Sites are named after the most common geocoded place of
+//! their flights, or their coordinates when none is known.
+//!
+//! The clustering is stored in the `sites` and `flight_sites` tables. It
+//! runs again whenever flights without a site show up, or on request; a new
+//! site takes over the id and any custom name of the old site nearest to it.
+//!
+//! Per-site statistics group the flights of each site by month of the year
+//! and by aircraft. Wind is each flight's mean logged wind speed; flights
+//! without wind readings don't count towards the wind figures.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{DroneUsage, Site, SiteFlight, SiteMonthStats, SiteStats};
+use crate::parser::haversine_distance;
+
+/// Largest distance between neighbouring home points of one site
+const SITE_RADIUS_M: f64 = 300.0;
+/// Home points within `SITE_RADIUS_M`, itself included, that make a core point
+const MIN_POINTS: usize = 2;
+/// Meters per degree of latitude
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// Sites with their stats, clustering first when flights have no site yet
+pub fn sites(db: &Database) -> Result<Vec<Site>, DatabaseError> {
+    if db.count_flights_without_site()? > 0 {
+        return recluster(db);
+    }
+    db.get_sites()
+}
+
+/// Cluster the home points of every flight again and store the sites
+pub fn recluster(db: &Database) -> Result<Vec<Site>, DatabaseError> {
+    let start = std::time::Instant::now();
+    let flights = db.get_site_home_points()?;
+    let points: Vec<[f64; 2]> = flights.iter().map(|f| [f.lat, f.lon]).collect();
+    let labels = dbscan(&points, SITE_RADIUS_M, MIN_POINTS);
+
+    let mut members: Vec<Vec<usize>> = Vec::new();
+    for (index, label) in labels.iter().enumerate() {
+        if *label >= members.len() {
+            members.resize_with(label + 1, Vec::new);
+        }
+        members[*label].push(index);
+    }
+
+    let previous = db.get_sites()?;
+    let mut taken = vec![false; previous.len()];
+    let mut used_names: HashMap<String, usize> = HashMap::new();
+    let mut sites = Vec::with_capacity(members.len());
+    let mut assignments = Vec::with_capacity(flights.len());
+
+    // Biggest sites first, so they win the old ids and names
+    members.sort_by_key(|m| std::cmp::Reverse(m.len()));
+    for indices in &members {
+        let n = indices.len() as f64;
+        let center_lat = indices.iter().map(|&i| points[i][0]).sum::<f64>() / n;
+        let center_lon = indices.iter().map(|&i| points[i][1]).sum::<f64>() / n;
+        let radius_m = indices
+            .iter()
+            .map(|&i| haversine_distance(center_lat, center_lon, points[i][0], points[i][1]))
+            .fold(0.0, f64::max);
+
+        let nearest = previous
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !taken[*i])
+            .map(|(i, site)| (i, haversine_distance(center_lat, center_lon, site.center_lat, site.center_lon)))
+            .filter(|(_, distance)| *distance <= SITE_RADIUS_M.max(radius_m))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let (id, custom_name) = match nearest {
+            Some((i, _)) => {
+                taken[i] = true;
+                let old = &previous[i];
+                (old.id, old.custom_name.then(|| old.name.clone()))
+            }
+            None => (db.generate_flight_id(), None),
+        };
+
+        let name = match custom_name.clone() {
+            Some(name) => name,
+            None => {
+                let base = place_name(indices.iter().filter_map(|&i| flights[i].place.as_deref()))
+                    .unwrap_or_else(|| format!("{:.4}, {:.4}", center_lat, center_lon));
+                let count = used_names.entry(base.clone()).or_insert(0);
+                *count += 1;
+                if *count > 1 {
+                    format!("{} ({})", base, count)
+                } else {
+                    base
+                }
+            }
+        };
+
+        for &i in indices {
+            assignments.push((flights[i].flight_id, id));
+        }
+        sites.push(Site {
+            id,
+            name,
+            custom_name: custom_name.is_some(),
+            center_lat,
+            center_lon,
+            radius_m,
+            flight_count: 0,
+            visit_count: 0,
+            duration_secs: 0.0,
+            first_flight: None,
+            last_flight: None,
+        });
+    }
+
+    db.replace_sites(&sites, &assignments)?;
+    log::info!(
+        "Clustered {} flights into {} sites in {:.1}ms",
+        flights.len(),
+        sites.len(),
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    db.get_sites()
+}
+
+/// Statistics of every site, in the order of `sites`
+pub fn site_stats(db: &Database) -> Result<Vec<SiteStats>, DatabaseError> {
+    let sites = sites(db)?;
+    Ok(summarize(sites, db.get_site_flights()?))
+}
+
+fn summarize(sites: Vec<Site>, flights: Vec<SiteFlight>) -> Vec<SiteStats> {
+    let mut by_site: HashMap<i64, Vec<SiteFlight>> = HashMap::new();
+    for flight in flights {
+        by_site.entry(flight.site_id).or_default().push(flight);
+    }
+    sites
+        .into_iter()
+        .map(|site| {
+            let flights = by_site.remove(&site.id).unwrap_or_default();
+            stats_of(site, &flights)
+        })
+        .collect()
+}
+
+/// Mean wind of flights weighted by flight time; None without wind readings
+fn weighted_wind<'a>(flights: impl Iterator<Item = &'a SiteFlight>) -> Option<f64> {
+    let (sum, secs) = flights
+        .filter_map(|f| Some((f.avg_wind_speed?, f.duration_secs.max(0.0))))
+        .fold((0.0, 0.0), |(sum, secs), (wind, d)| (sum + wind * d, secs + d));
+    (secs > 0.0).then(|| sum / secs)
+}
+
+fn stats_of(site: Site, flights: &[SiteFlight]) -> SiteStats {
+    let mut winds: Vec<f64> = flights.iter().filter_map(|f| f.avg_wind_speed).collect();
+    winds.sort_by(f64::total_cmp);
+    let typical_wind_speed = match winds.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(winds[n / 2]),
+        n => Some((winds[n / 2 - 1] + winds[n / 2]) / 2.0),
+    };
+
+    let mut months: BTreeMap<u32, Vec<&SiteFlight>> = BTreeMap::new();
+    for flight in flights {
+        if let Some(month) = flight.start_time.as_deref().and_then(|t| t.get(5..7)?.parse().ok()) {
+            months.entry(month).or_default().push(flight);
+        }
+    }
+    let months: Vec<SiteMonthStats> = months
+        .into_iter()
+        .map(|(month, flights)| SiteMonthStats {
+            month,
+            flight_count: flights.len() as i64,
+            duration_secs: flights.iter().map(|f| f.duration_secs).sum(),
+            avg_wind_speed: weighted_wind(flights.into_iter()),
+        })
+        .collect();
+    let busiest_month = months
+        .iter()
+        .max_by(|a, b| a.duration_secs.total_cmp(&b.duration_secs).then(b.month.cmp(&a.month)))
+        .map(|m| m.month);
+    let calmest_month = months
+        .iter()
+        .filter_map(|m| Some((m.month, m.avg_wind_speed?)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(month, _)| month);
+
+    // Same key as the library statistics: the serial when known, otherwise the model
+    let mut drones: HashMap<String, DroneUsage> = HashMap::new();
+    for flight in flights {
+        let model = flight.drone_model.clone().unwrap_or_else(|| "Unknown".to_string());
+        let key = flight.drone_serial.clone().unwrap_or_else(|| format!("model:{}", model));
+        let drone = drones.entry(key).or_insert_with(|| DroneUsage {
+            drone_model: model.clone(),
+            drone_serial: flight.drone_serial.clone(),
+            aircraft_name: None,
+            flight_count: 0,
+            nickname: None,
+        });
+        // Newest flight names the aircraft, as flights are oldest first
+        drone.drone_model = model;
+        if flight.aircraft_name.is_some() {
+            drone.aircraft_name = flight.aircraft_name.clone();
+        }
+        drone.nickname = flight.nickname.clone().or(drone.nickname.take());
+        drone.flight_count += 1;
+    }
+    let mut drones: Vec<DroneUsage> = drones.into_values().collect();
+    drones.sort_by(|a, b| b.flight_count.cmp(&a.flight_count).then_with(|| a.drone_model.cmp(&b.drone_model)));
+
+    SiteStats {
+        distance_m: flights.iter().map(|f| f.distance_m).sum(),
+        max_wind_speed: winds.last().copied(),
+        wind_flight_count: winds.len() as i64,
+        typical_wind_speed,
+        busiest_month,
+        calmest_month,
+        months,
+        drones,
+        site,
+    }
+}
+
+/// Most common place name; ties go to the name first in alphabetical order
+fn place_name<'a>(places: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for place in places.map(str::trim).filter(|p| !p.is_empty()) {
+        *counts.entry(place).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(place, _)| place.to_string())
+}
+
+/// DBSCAN over `[lat, lon]` points. Returns a cluster label per point;
+/// noise points get a label of their own.
+pub fn dbscan(points: &[[f64; 2]], eps_m: f64, min_points: usize) -> Vec<usize> {
+    // Points sorted by latitude, so neighbours are found in a narrow band
+    let mut by_lat: Vec<usize> = (0..points.len()).collect();
+    by_lat.sort_by(|&a, &b| points[a][0].total_cmp(&points[b][0]));
+    let lats: Vec<f64> = by_lat.iter().map(|&i| points[i][0]).collect();
+    let band = eps_m / METERS_PER_DEGREE;
+
+    let neighbours = |index: usize| -> Vec<usize> {
+        let [lat, lon] = points[index];
+        let from = lats.partition_point(|l| *l < lat - band);
+        let to = lats.partition_point(|l| *l <= lat + band);
+        by_lat[from..to]
+            .iter()
+            .copied()
+            .filter(|&j| haversine_distance(lat, lon, points[j][0], points[j][1]) <= eps_m)
+            .collect()
+    };
+
+    let mut labels: Vec<Option<usize>> = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut next_label = 0;
+    for index in 0..points.len() {
+        if visited[index] {
+            continue;
+        }
+        visited[index] = true;
+        let found = neighbours(index);
+        if found.len() < min_points {
+            continue;
+        }
+        let label = next_label;
+        next_label += 1;
+        labels[index] = Some(label);
+
+        let mut queue = found;
+        while let Some(j) = queue.pop() {
+            // Border points stay with the first cluster that reaches them
+            labels[j].get_or_insert(label);
+            if visited[j] {
+                continue;
+            }
+            visited[j] = true;
+            let found = neighbours(j);
+            if found.len() >= min_points {
+                queue.extend(found);
+            }
+        }
+    }
+
+    labels
+        .into_iter()
+        .map(|label| {
+            label.unwrap_or_else(|| {
+                next_label += 1;
+                next_label - 1
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearby_home_points_share_a_site() {
+        let points = [
+            [47.0, 8.0],
+            [47.001, 8.0], // ~111 m north of the first
+            [47.002, 8.0], // chained through the second
+            [47.1, 8.0], // ~11 km away
+            [-33.9, 151.2],
+        ];
+        let labels = dbscan(&points, SITE_RADIUS_M, MIN_POINTS);
+        assert_eq!(labels[0], labels[1]);
+        assert_eq!(labels[1], labels[2]);
+        assert_ne!(labels[0], labels[3]);
+        assert_ne!(labels[3], labels[4]);
+        assert_eq!(labels.iter().collect::<std::collections::HashSet<_>>().len(), 3);
+    }
+
+    #[test]
+    fn sites_are_named_after_the_most_common_place() {
+        let places = ["Zurich", "Basel", "Zurich", " "];
+        assert_eq!(place_name(places.into_iter()).as_deref(), Some("Zurich"));
+        assert_eq!(place_name(["Bern", "Aarau"].into_iter()).as_deref(), Some("Aarau"));
+        assert_eq!(place_name(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn site_stats_group_flights_by_month_and_aircraft() {
+        let flight = |start_time: &str, minutes: f64, wind: Option<f64>, serial: Option<&str>| SiteFlight {
+            site_id: 1,
+            start_time: Some(start_time.to_string()),
+            duration_secs: minutes * 60.0,
+            distance_m: 1000.0,
+            avg_wind_speed: wind,
+            drone_model: Some("Mini 4 Pro".to_string()),
+            drone_serial: serial.map(str::to_string),
+            aircraft_name: None,
+            nickname: None,
+        };
+        let site = Site {
+            id: 1,
+            name: "Field".to_string(),
+            custom_name: false,
+            center_lat: 47.0,
+            center_lon: 8.0,
+            radius_m: 0.0,
+            flight_count: 4,
+            visit_count: 4,
+            duration_secs: 0.0,
+            first_flight: None,
+            last_flight: None,
+        };
+        let flights = vec![
+            flight("2023-04-02 10:00:00", 10.0, Some(6.0), Some("SN1")),
+            flight("2024-04-20 10:00:00", 30.0, Some(2.0), Some("SN1")),
+            flight("2024-07-01 10:00:00", 20.0, Some(5.0), None),
+            flight("2024-11-11 10:00:00", 15.0, None, Some("SN1")),
+        ];
+        let stats = summarize(vec![site], flights).remove(0);
+
+        assert_eq!(stats.months.iter().map(|m| m.month).collect::<Vec<_>>(), [4, 7, 11]);
+        // April: (6 × 10 + 2 × 30) / 40 minutes
+        assert!((stats.months[0].avg_wind_speed.unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(stats.months[2].avg_wind_speed, None);
+        assert_eq!(stats.busiest_month, Some(4));
+        assert_eq!(stats.calmest_month, Some(4));
+        assert_eq!(stats.typical_wind_speed, Some(5.0));
+        assert_eq!(stats.max_wind_speed, Some(6.0));
+        assert_eq!(stats.wind_flight_count, 3);
+        assert_eq!(stats.drones.len(), 2);
+        assert_eq!((stats.drones[0].drone_serial.as_deref(), stats.drones[0].flight_count), (Some("SN1"), 3));
+    }
+}
@@ -0,0 +1,382 @@
+//! Parser module for DJI video subtitle (.SRT) telemetry.
+//!
+//! DJI aircraft write an SRT file next to each video with one subtitle block
+//! per video frame. The payload format changed over the years:
+//! - Mavic Pro / Phantom 4: `GPS(lon,lat,alt) BAROMETER:12.3`
+//! - Mini 2 / Air 2S: `F/2.8, SS 1000, ISO 100, ..., GPS (lon, lat, 19), D 10.5m, H 5.2m, HS 1.2m/s, VS 0.0m/s`
+//! - Mavic 3 / Mini 3+: `[iso: 100] [latitude: 47.1] [longitude: 8.1] [rel_alt: 10.5 abs_alt: 500.1]`
+//!
+//! Frames are thinned to ~10 Hz since the video frame rate adds no useful detail.
+//! Exposure settings (ISO, shutter, aperture, EV) are read from every block and
+//! recorded as `camera_settings` events whenever they change.
+
+use std::io::Read;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::database::Database;
+use crate::models::{FlightEvent, FlightMetadata, TelemetryPoint};
+use crate::parser::{haversine_distance, locate_events, LogParser, LogSource, ParseResult, ParserError};
+
+/// Minimum spacing between kept subtitle frames (ms)
+const MIN_SAMPLE_INTERVAL_MS: i64 = 100;
+
+/// DJI SRT Parser
+pub struct SrtParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> SrtParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Check if a file is a DJI SRT subtitle file with telemetry
+    pub fn is_dji_srt(source: &LogSource) -> bool {
+        let path = source.path();
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !ext.eq_ignore_ascii_case("srt") {
+            return false;
+        }
+
+        // Only the first cues are needed; video subtitles can be large
+        let mut head = Vec::with_capacity(2048);
+        match source.open().and_then(|f| f.take(2048).read_to_end(&mut head)) {
+            Ok(_) => {
+                let head = String::from_utf8_lossy(&head);
+                head.contains("-->")
+                    && (head.contains("latitude") || head.contains("GPS"))
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Parse an SRT file
+    pub fn parse(&self, source: &LogSource, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let file_path = source.path();
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing DJI SRT file: {:?}", file_path);
+
+        let mut content = String::new();
+        source.open()?.read_to_string(&mut content)?;
+        let content = content.replace("\r\n", "\n");
+
+        let mut points: Vec<TelemetryPoint> = Vec::new();
+        let mut events: Vec<FlightEvent> = Vec::new();
+        let mut start_time: Option<DateTime<Utc>> = None;
+        let mut blocks = 0usize;
+
+        for block in content.split("\n\n") {
+            let mut lines = block.lines().map(str::trim).filter(|l| !l.is_empty());
+
+            // Block index, then the cue timing line
+            let timing = match lines.find(|l| l.contains("-->")) {
+                Some(t) => t,
+                None => continue,
+            };
+            let timestamp_ms = match timing.split("-->").next().and_then(parse_cue_time) {
+                Some(ts) => ts,
+                None => continue,
+            };
+            blocks += 1;
+
+            let text: String = lines.collect::<Vec<_>>().join(" ");
+            let text = strip_tags(&text);
+
+            if start_time.is_none() {
+                start_time = find_datetime(&text);
+            }
+
+            if let Some(settings) = Self::parse_camera_settings(&text) {
+                let changed = events.last().map_or(true, |e| e.detail.as_deref() != Some(settings.as_str()));
+                if changed {
+                    events.push(FlightEvent {
+                        timestamp_ms,
+                        event_type: "camera_settings".to_string(),
+                        lat: None,
+                        lon: None,
+                        altitude: None,
+                        duration_ms: None,
+                        detail: Some(settings),
+                        severity: None,
+                    });
+                }
+            }
+
+            if let Some(last) = points.last() {
+                if timestamp_ms - last.timestamp_ms < MIN_SAMPLE_INTERVAL_MS {
+                    continue;
+                }
+            }
+
+            if let Some(point) = Self::parse_block(&text, timestamp_ms) {
+                points.push(point);
+            }
+        }
+
+        if !points.iter().any(|p| p.latitude.is_some() && p.longitude.is_some()) {
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        Self::derive_speeds(&mut points);
+        locate_events(&mut events, &points);
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: None,
+            drone_serial: None,
+            aircraft_name: None,
+            battery_serial: None,
+            start_time,
+            end_time: start_time
+                .map(|s| s + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64)),
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            mission_name: None,
+            partial: false,
+            firmware_version: None,
+            max_distance_from_home: crate::parser::max_distance_from_home(&points, stats.home_location),
+        };
+
+        log::info!(
+            "SRT parse complete in {:.1}s: {} subtitle blocks -> {} points, duration={:.1}s, distance={:.0}m",
+            parse_start.elapsed().as_secs_f64(),
+            blocks,
+            points.len(),
+            stats.duration_secs,
+            stats.total_distance_m
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "SRT".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParseResult { metadata, points, tags, recovery: None, home_points: Vec::new(), motors: Vec::new(), events })
+    }
+
+    /// Parse one subtitle block's text into a telemetry point
+    fn parse_block(text: &str, timestamp_ms: i64) -> Option<TelemetryPoint> {
+        // Newer bracketed format
+        let (mut lat, mut lon) = (find_number(text, "latitude"), find_number(text, "longitude"));
+        let mut height = find_number(text, "rel_alt");
+        let altitude_abs = find_number(text, "abs_alt");
+
+        // Older GPS(lon,lat,alt) / GPS (lon, lat, n) format
+        if lat.is_none() || lon.is_none() {
+            if let Some(start) = text.find("GPS") {
+                let rest = &text[start..];
+                if let (Some(open), Some(close)) = (rest.find('('), rest.find(')')) {
+                    let parts: Vec<f64> = rest[open + 1..close]
+                        .split(',')
+                        .filter_map(|p| p.trim().parse().ok())
+                        .collect();
+                    if parts.len() >= 2 {
+                        lon = Some(parts[0]);
+                        lat = Some(parts[1]);
+                    }
+                }
+            }
+        }
+
+        height = height
+            .or_else(|| find_number(text, "BAROMETER"))
+            .or_else(|| find_number(text, "H"));
+        let speed = find_number(text, "HS");
+        let velocity_z = find_number(text, "VS");
+
+        let (lat, lon) = match (lat, lon) {
+            (Some(la), Some(lo)) if la.abs() <= 90.0 && lo.abs() <= 180.0 && (la.abs() > 1e-6 || lo.abs() > 1e-6) => {
+                (Some(la), Some(lo))
+            }
+            _ => (None, None),
+        };
+
+        if lat.is_none() && height.is_none() {
+            return None;
+        }
+
+        Some(TelemetryPoint {
+            timestamp_ms,
+            latitude: lat,
+            longitude: lon,
+            altitude: height,
+            height,
+            altitude_abs,
+            speed,
+            velocity_z,
+            ..Default::default()
+        })
+    }
+
+    /// Summarise the exposure settings of a block as `ISO 100, 1/1000, f/2.8, EV 0`
+    fn parse_camera_settings(text: &str) -> Option<String> {
+        let iso = find_number(text, "iso").or_else(|| find_number(text, "ISO"));
+        let shutter = find_value(text, "shutter", shutter_speed).or_else(|| find_value(text, "SS", shutter_speed));
+        // Newer firmware writes the f-number times 100 (`[fnum: 280]`)
+        let aperture = find_number(text, "fnum")
+            .map(|f| if f >= 100.0 { f / 100.0 } else { f })
+            .or_else(|| text.find("F/").and_then(|i| leading_number(&text[i + 2..])))
+            .filter(|f| *f > 0.0);
+        let ev = find_number(text, "ev").or_else(|| find_number(text, "EV"));
+
+        if iso.is_none() && shutter.is_none() && aperture.is_none() {
+            return None;
+        }
+
+        let mut parts = Vec::new();
+        if let Some(iso) = iso {
+            parts.push(format!("ISO {}", iso));
+        }
+        if let Some(shutter) = shutter {
+            parts.push(shutter);
+        }
+        if let Some(aperture) = aperture {
+            parts.push(format!("f/{}", aperture));
+        }
+        if let Some(ev) = ev {
+            parts.push(format!("EV {}", ev));
+        }
+        Some(parts.join(", "))
+    }
+
+    /// Fill in ground speed from consecutive positions where the SRT has none
+    fn derive_speeds(points: &mut [TelemetryPoint]) {
+        let mut prev: Option<(i64, f64, f64)> = None;
+        for p in points.iter_mut() {
+            if let (Some(lat), Some(lon)) = (p.latitude, p.longitude) {
+                if p.speed.is_none() {
+                    if let Some((ts, plat, plon)) = prev {
+                        let dt = (p.timestamp_ms - ts) as f64 / 1000.0;
+                        if dt > 0.0 {
+                            p.speed = Some(haversine_distance(plat, plon, lat, lon) / dt);
+                        }
+                    }
+                }
+                prev = Some((p.timestamp_ms, lat, lon));
+            }
+        }
+    }
+}
+
+/// Parse an SRT cue time like `00:01:02,345` into milliseconds
+fn parse_cue_time(s: &str) -> Option<i64> {
+    let s = s.trim().replace(',', ".");
+    let mut parts = s.split(':');
+    let h: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let sec: f64 = parts.next()?.parse().ok()?;
+    Some(h * 3_600_000 + m * 60_000 + (sec * 1000.0).round() as i64)
+}
+
+/// Remove `<font ...>` style markup
+fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Find the number that follows `key` as a whole word, with an optional `:`
+/// separator (`latitude: 47.1`, `BAROMETER:12.3`, `H 5.2m`).
+fn find_number(text: &str, key: &str) -> Option<f64> {
+    find_value(text, key, leading_number)
+}
+
+/// Parse the number at the start of `s`, ignoring any unit suffix
+fn leading_number(s: &str) -> Option<f64> {
+    let num: String = s
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    num.parse::<f64>().ok()
+}
+
+/// Normalise a shutter value (`1/1000.0`, `1000`, `1/50`) to `1/1000`
+fn shutter_speed(s: &str) -> Option<String> {
+    let denominator = match s.strip_prefix("1/") {
+        Some(rest) => leading_number(rest)?,
+        None => leading_number(s)?,
+    };
+    (denominator > 0.0).then(|| format!("1/{}", denominator))
+}
+
+/// Find the whole-word `key` followed by `:` or a space and apply `parse` to
+/// the text after the separator. Later occurrences are tried until one parses.
+fn find_value<T>(text: &str, key: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let mut search_from = 0;
+    while let Some(rel) = text[search_from..].find(key) {
+        let start = search_from + rel;
+        let end = start + key.len();
+        search_from = end;
+
+        // Must be a standalone token, not part of a longer word
+        let before_ok = text[..start]
+            .chars()
+            .last()
+            .map_or(true, |c| !c.is_ascii_alphanumeric() && c != '_');
+        let rest = &text[end..];
+        let after_ok = rest
+            .chars()
+            .next()
+            .map_or(false, |c| c == ':' || c == ' ');
+        if !before_ok || !after_ok {
+            continue;
+        }
+
+        let rest = rest.trim_start_matches([':', ' ']);
+        if let Some(v) = parse(rest) {
+            return Some(v);
+        }
+    }
+    None
+}
+
+/// Find a `YYYY-MM-DD HH:MM:SS(.fff)` wall clock in the block text
+fn find_datetime(text: &str) -> Option<DateTime<Utc>> {
+    let bytes = text.as_bytes();
+    for i in 0..bytes.len().saturating_sub(18) {
+        if bytes[i].is_ascii_digit() && (bytes[i + 4] == b'-' || bytes[i + 4] == b'.') {
+            let candidate: String = text[i..].chars().take(23).collect();
+            for fmt in ["%Y-%m-%d %H:%M:%S%.f", "%Y.%m.%d %H:%M:%S%.f"] {
+                for len in [candidate.len(), 19] {
+                    if let Some(slice) = candidate.get(..len) {
+                        if let Ok(ndt) = NaiveDateTime::parse_from_str(slice, fmt) {
+                            return Some(DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,154 @@
+//! Position of the sun, for telling day flights from night flights.
+//!
+//! Uses the NOAA solar position equations, which are good to a fraction of a
+//! degree for dates within a few centuries of 2000. Atmospheric refraction is
+//! ignored: it matters near the horizon, not at the twilight threshold, and
+//! sunrise and sunset use the conventional elevation that allows for it.
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+
+/// Sun elevation below which it is night for logbook purposes: the end of
+/// civil twilight, as used by the FAA and EASA
+pub const CIVIL_TWILIGHT_DEG: f64 = -6.0;
+
+/// Sun elevation at sunrise and sunset: the upper edge of the sun on the
+/// horizon, with standard refraction
+pub const SUNRISE_DEG: f64 = -0.833;
+
+/// Time between sun checks along a flight
+const FLIGHT_STEP_SECS: f64 = 60.0;
+
+/// Light at a place and time, darkest last
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Daylight {
+    Day,
+    /// Between sunset and the end of civil twilight, or the same before sunrise
+    CivilTwilight,
+    Night,
+}
+
+impl Daylight {
+    /// Name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Daylight::Day => "day",
+            Daylight::CivilTwilight => "civil_twilight",
+            Daylight::Night => "night",
+        }
+    }
+}
+
+/// Elevation of the sun above the horizon in degrees at a place and time
+pub fn solar_elevation(lat: f64, lon: f64, time: DateTime<Utc>) -> f64 {
+    let julian_day = time.timestamp_millis() as f64 / 86400000.0 + 2440587.5;
+    let t = (julian_day - 2451545.0) / 36525.0;
+
+    let mean_longitude = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360.0);
+    let mean_anomaly = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+    let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+    let m = mean_anomaly.to_radians();
+    let center = m.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + (2.0 * m).sin() * (0.019993 - 0.000101 * t)
+        + (3.0 * m).sin() * 0.000289;
+
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_longitude = (mean_longitude + center - 0.00569 - 0.00478 * omega.sin()).to_radians();
+    let mean_obliquity = 23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+    let declination = (obliquity.sin() * apparent_longitude.sin()).asin();
+
+    // Equation of time, in minutes
+    let y = (obliquity / 2.0).tan().powi(2);
+    let l = mean_longitude.to_radians();
+    let equation_of_time = 4.0
+        * (y * (2.0 * l).sin() - 2.0 * eccentricity * m.sin()
+            + 4.0 * eccentricity * y * m.sin() * (2.0 * l).cos()
+            - 0.5 * y * y * (4.0 * l).sin()
+            - 1.25 * eccentricity * eccentricity * (2.0 * m).sin())
+        .to_degrees();
+
+    let minutes = time.num_seconds_from_midnight() as f64 / 60.0;
+    let solar_time = (minutes + equation_of_time + 4.0 * lon).rem_euclid(1440.0);
+    let hour_angle = (solar_time / 4.0 - 180.0).to_radians();
+
+    let lat = lat.to_radians();
+    let cos_zenith = lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Whether the sun is below civil twilight at a place and time
+pub fn is_night(lat: f64, lon: f64, time: DateTime<Utc>) -> bool {
+    solar_elevation(lat, lon, time) < CIVIL_TWILIGHT_DEG
+}
+
+/// Light at a place and time
+pub fn daylight(lat: f64, lon: f64, time: DateTime<Utc>) -> Daylight {
+    let elevation = solar_elevation(lat, lon, time);
+    if elevation < CIVIL_TWILIGHT_DEG {
+        Daylight::Night
+    } else if elevation < SUNRISE_DEG {
+        Daylight::CivilTwilight
+    } else {
+        Daylight::Day
+    }
+}
+
+/// Darkest light of a flight from `start` at a home point, and the seconds
+/// of it flown at night. The sun is checked every `FLIGHT_STEP_SECS`.
+pub fn classify_flight(lat: f64, lon: f64, start: DateTime<Utc>, duration_secs: f64) -> (Daylight, f64) {
+    let duration_secs = duration_secs.max(0.0);
+    let at = |offset_secs: f64| start + Duration::milliseconds((offset_secs * 1000.0) as i64);
+    let mut darkest = daylight(lat, lon, start).max(daylight(lat, lon, at(duration_secs)));
+    let mut night_secs = 0.0;
+    let mut offset = 0.0;
+    while offset < duration_secs {
+        let step = (duration_secs - offset).min(FLIGHT_STEP_SECS);
+        // Each step counts with the light at its middle
+        let light = daylight(lat, lon, at(offset + step / 2.0));
+        if light == Daylight::Night {
+            night_secs += step;
+        }
+        darkest = darkest.max(light);
+        offset += step;
+    }
+    (darkest, night_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn noon_sun_at_the_equinox_is_overhead_the_equator() {
+        let time = Utc.with_ymd_and_hms(2024, 3, 20, 12, 7, 0).unwrap();
+        assert!(solar_elevation(0.0, 0.0, time) > 88.0);
+    }
+
+    #[test]
+    fn tells_day_from_night() {
+        // London: midsummer afternoon, midwinter evening
+        let day = Utc.with_ymd_and_hms(2024, 6, 21, 15, 0, 0).unwrap();
+        let night = Utc.with_ymd_and_hms(2024, 12, 21, 18, 0, 0).unwrap();
+        assert!(!is_night(51.5, -0.13, day));
+        assert!(is_night(51.5, -0.13, night));
+        // Sydney at 18:00 UTC is early morning, before dawn in midwinter
+        let sydney = Utc.with_ymd_and_hms(2024, 6, 21, 18, 0, 0).unwrap();
+        assert!(is_night(-33.87, 151.21, sydney));
+    }
+
+    #[test]
+    fn flights_into_the_dusk_count_their_night_time() {
+        // London, 21 Dec: sunset ~15:54, civil twilight ends ~16:36 UTC
+        let afternoon = Utc.with_ymd_and_hms(2024, 12, 21, 13, 0, 0).unwrap();
+        assert_eq!(classify_flight(51.5, -0.13, afternoon, 1200.0), (Daylight::Day, 0.0));
+
+        let dusk = Utc.with_ymd_and_hms(2024, 12, 21, 16, 0, 0).unwrap();
+        assert_eq!(classify_flight(51.5, -0.13, dusk, 1200.0).0, Daylight::CivilTwilight);
+
+        let into_night = Utc.with_ymd_and_hms(2024, 12, 21, 16, 20, 0).unwrap();
+        let (light, night_secs) = classify_flight(51.5, -0.13, into_night, 1800.0);
+        assert_eq!(light, Daylight::Night);
+        assert!(night_secs > 600.0 && night_secs < 1800.0, "{}", night_secs);
+    }
+}
@@ -0,0 +1,262 @@
+//! Compact per-series encoding of telemetry for the `packed` storage mode.
+//!
+//! Each telemetry column of a flight becomes one blob: a null bitmap followed
+//! by the non-null values, deflate-compressed as a whole.
+//! - integers: zigzag varints of the delta to the previous value
+//! - floats: each value's bits XORed with the previous value's, so slowly
+//!   changing series turn into long runs of zero bytes
+//! - booleans: one byte per value
+//! - text: a dictionary of the distinct strings plus a varint index per value
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PackError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Corrupt telemetry series: {0}")]
+    Corrupt(&'static str),
+
+    #[error("Unsupported column type: {0}")]
+    UnsupportedType(String),
+}
+
+/// How the values of a series are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+    Int,
+    Float,
+    Bool,
+    Text,
+}
+
+impl SeriesKind {
+    /// Pick the encoding for a DuckDB column type
+    pub fn for_sql_type(sql_type: &str) -> Result<Self, PackError> {
+        match sql_type.to_ascii_uppercase().as_str() {
+            "TINYINT" | "SMALLINT" | "INTEGER" | "BIGINT" => Ok(Self::Int),
+            "FLOAT" | "REAL" | "DOUBLE" => Ok(Self::Float),
+            "BOOLEAN" => Ok(Self::Bool),
+            "VARCHAR" => Ok(Self::Text),
+            other => Err(PackError::UnsupportedType(other.to_string())),
+        }
+    }
+}
+
+/// The values of one telemetry column, in timestamp order
+#[derive(Debug, Clone, PartialEq)]
+pub enum Series {
+    Int(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Bool(Vec<Option<bool>>),
+    Text(Vec<Option<String>>),
+}
+
+impl Series {
+    /// An empty series to be filled with values of `kind`
+    pub fn new(kind: SeriesKind) -> Self {
+        match kind {
+            SeriesKind::Int => Self::Int(Vec::new()),
+            SeriesKind::Float => Self::Float(Vec::new()),
+            SeriesKind::Bool => Self::Bool(Vec::new()),
+            SeriesKind::Text => Self::Text(Vec::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Int(v) => v.len(),
+            Self::Float(v) => v.len(),
+            Self::Bool(v) => v.len(),
+            Self::Text(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Compress a series into a blob
+pub fn encode(series: &Series) -> Result<Vec<u8>, PackError> {
+    let mut raw = Vec::new();
+    match series {
+        Series::Int(values) => {
+            raw.extend(null_bitmap(values));
+            let mut prev = 0i64;
+            for &v in values.iter().flatten() {
+                put_varint(&mut raw, zigzag(v.wrapping_sub(prev)));
+                prev = v;
+            }
+        }
+        Series::Float(values) => {
+            raw.extend(null_bitmap(values));
+            let mut prev = 0u64;
+            for v in values.iter().flatten() {
+                let bits = v.to_bits();
+                raw.extend_from_slice(&(bits ^ prev).to_be_bytes());
+                prev = bits;
+            }
+        }
+        Series::Bool(values) => {
+            raw.extend(null_bitmap(values));
+            raw.extend(values.iter().flatten().map(|&b| b as u8));
+        }
+        Series::Text(values) => {
+            raw.extend(null_bitmap(values));
+            let mut dictionary: Vec<&str> = Vec::new();
+            let mut codes_by_text: HashMap<&str, u64> = HashMap::new();
+            let mut codes = Vec::new();
+            for text in values.iter().flatten() {
+                let code = *codes_by_text.entry(text.as_str()).or_insert_with(|| {
+                    dictionary.push(text.as_str());
+                    dictionary.len() as u64 - 1
+                });
+                put_varint(&mut codes, code);
+            }
+            put_varint(&mut raw, dictionary.len() as u64);
+            for text in &dictionary {
+                put_varint(&mut raw, text.len() as u64);
+                raw.extend_from_slice(text.as_bytes());
+            }
+            raw.extend(codes);
+        }
+    }
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&raw)?;
+    Ok(encoder.finish()?)
+}
+
+/// Restore a series of `len` values from a blob made by `encode`
+pub fn decode(kind: SeriesKind, data: &[u8], len: usize) -> Result<Series, PackError> {
+    let mut raw = Vec::new();
+    DeflateDecoder::new(data).read_to_end(&mut raw)?;
+    let mut cursor = Cursor { data: &raw, pos: 0 };
+    let bitmap = cursor.take(len.div_ceil(8))?.to_vec();
+    let present = |i: usize| bitmap[i / 8] & (1 << (i % 8)) != 0;
+
+    let series = match kind {
+        SeriesKind::Int => {
+            let mut prev = 0i64;
+            Series::Int(read_values(len, present, || {
+                prev = prev.wrapping_add(unzigzag(cursor.varint()?));
+                Ok(prev)
+            })?)
+        }
+        SeriesKind::Float => {
+            let mut prev = 0u64;
+            Series::Float(read_values(len, present, || {
+                let bytes: [u8; 8] = cursor.take(8)?.try_into().map_err(|_| PackError::Corrupt("short float"))?;
+                prev ^= u64::from_be_bytes(bytes);
+                Ok(f64::from_bits(prev))
+            })?)
+        }
+        SeriesKind::Bool => Series::Bool(read_values(len, present, || Ok(cursor.take(1)?[0] != 0))?),
+        SeriesKind::Text => {
+            let dictionary_len = cursor.varint()? as usize;
+            let mut dictionary = Vec::with_capacity(dictionary_len);
+            for _ in 0..dictionary_len {
+                let text_len = cursor.varint()? as usize;
+                let text = std::str::from_utf8(cursor.take(text_len)?)
+                    .map_err(|_| PackError::Corrupt("invalid UTF-8"))?;
+                dictionary.push(text.to_string());
+            }
+            Series::Text(read_values(len, present, || {
+                let code = cursor.varint()? as usize;
+                dictionary.get(code).cloned().ok_or(PackError::Corrupt("unknown dictionary code"))
+            })?)
+        }
+    };
+    Ok(series)
+}
+
+fn read_values<T>(
+    len: usize,
+    present: impl Fn(usize) -> bool,
+    mut next: impl FnMut() -> Result<T, PackError>,
+) -> Result<Vec<Option<T>>, PackError> {
+    (0..len).map(|i| if present(i) { next().map(Some) } else { Ok(None) }).collect()
+}
+
+fn null_bitmap<T>(values: &[Option<T>]) -> Vec<u8> {
+    let mut bitmap = vec![0u8; values.len().div_ceil(8)];
+    for (i, value) in values.iter().enumerate() {
+        if value.is_some() {
+            bitmap[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bitmap
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+fn unzigzag(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], PackError> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.data.len());
+        let end = end.ok_or(PackError::Corrupt("unexpected end of data"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, PackError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(PackError::Corrupt("varint too long"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_round_trip() {
+        let cases = [
+            (SeriesKind::Int, Series::Int(vec![Some(0), Some(100), None, Some(-5), Some(i64::MAX), Some(i64::MIN)])),
+            (SeriesKind::Float, Series::Float(vec![Some(51.5), Some(51.50001), None, Some(-0.0), Some(f64::MAX)])),
+            (SeriesKind::Bool, Series::Bool(vec![Some(true), None, Some(false), Some(true)])),
+            (
+                SeriesKind::Text,
+                Series::Text(vec![Some("GPS".into()), Some("GPS".into()), None, Some("Sport".into()), Some(String::new())]),
+            ),
+        ];
+        for (kind, series) in cases {
+            let blob = encode(&series).unwrap();
+            assert_eq!(decode(kind, &blob, series.len()).unwrap(), series);
+        }
+    }
+}
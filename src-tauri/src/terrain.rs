@@ -0,0 +1,285 @@
+//! Ground elevation along flight tracks, for the height above the terrain.
+//!
+//! Logged heights count from the takeoff point, so over a slope or a valley
+//! they say little about the clearance below the aircraft. With a provider
+//! picked as `terrain` in config.json, the ground elevation is looked up
+//! under every point:
+//! - `off` (default): no lookups
+//! - `open-meteo`: the Open-Meteo elevation API (Copernicus 90 m DEM), online
+//! - `local`: SRTM `.hgt` tiles (1 or 3 arc-second, named like `N46E007.hgt`)
+//!   in `dem_directory`
+//!
+//! The height above ground (AGL) is the takeoff elevation plus the logged
+//! height, minus the elevation under the point. Online lookups are cached
+//! per ~50 m cell in the `elevation_cache` table.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::library::read_config;
+use crate::models::{TerrainProfile, TerrainProvider, TerrainSettings};
+
+/// Open-Meteo elevation endpoint
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/elevation";
+
+/// Coordinates Open-Meteo takes per request
+const OPEN_METEO_BATCH: usize = 100;
+
+/// Size in degrees of the cells online lookups are cached on (~50 m)
+const CACHE_CELL_DEG: f64 = 0.0005;
+
+/// Elevation of SRTM voids
+const HGT_VOID: i16 = -32768;
+
+#[derive(Error, Debug)]
+pub enum TerrainError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Unexpected elevation response: {0}")]
+    Response(String),
+}
+
+/// Saved terrain settings; off until configured
+pub fn settings(data_dir: &Path) -> TerrainSettings {
+    read_config(data_dir)
+        .get("terrain")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Save the terrain settings to config.json
+pub fn save_settings(data_dir: &Path, settings: &TerrainSettings) -> std::io::Result<()> {
+    let dem_directory = settings.dem_directory.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    if settings.provider == TerrainProvider::Local && !dem_directory.is_some_and(|d| Path::new(d).is_dir()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Choose the folder holding the .hgt elevation tiles",
+        ));
+    }
+
+    let mut config = read_config(data_dir);
+    config["terrain"] = serde_json::json!(TerrainSettings {
+        provider: settings.provider,
+        dem_directory: dem_directory.map(str::to_string),
+    });
+    std::fs::write(data_dir.join("config.json"), serde_json::to_string_pretty(&config).unwrap())
+}
+
+/// Ground elevation and height above ground at the points of a flight's
+/// telemetry, downsampled to `max_points` as for the charts. None while the
+/// provider is off.
+pub async fn flight_terrain(
+    db: &Database,
+    flight_id: i64,
+    max_points: Option<usize>,
+) -> Result<Option<TerrainProfile>, TerrainError> {
+    let settings = settings(&db.data_dir);
+    if settings.provider == TerrainProvider::Off {
+        return Ok(None);
+    }
+
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(flight_id, max_points, flight.point_count.map(i64::from))?;
+    let fixes = crate::gps_filter::filter_positions(records.iter().map(|r| (r.timestamp_ms, r.latitude, r.longitude)));
+    let home = flight
+        .home_lat
+        .zip(flight.home_lon)
+        .and_then(|(lat, lon)| crate::gps_filter::valid_position(Some(lat), Some(lon)))
+        .or_else(|| fixes.iter().find_map(|fix| *fix));
+    let Some(home) = home else {
+        return Ok(Some(TerrainProfile::default()));
+    };
+
+    // Home first, then every fix
+    let positions: Vec<(f64, f64)> = std::iter::once(home).chain(fixes.iter().flatten().copied()).collect();
+    let mut elevations = match settings.provider {
+        TerrainProvider::Off => return Ok(None),
+        TerrainProvider::OpenMeteo => open_meteo_elevations(db, &positions).await?,
+        TerrainProvider::Local => {
+            let mut tiles = DemTiles::new(settings.dem_directory.as_deref().unwrap_or_default());
+            positions.iter().map(|&(lat, lon)| tiles.elevation(lat, lon)).collect()
+        }
+    }
+    .into_iter();
+
+    let home_ground = elevations.next().flatten();
+    let ground_elevation: Vec<Option<f64>> =
+        fixes.iter().map(|fix| fix.and_then(|_| elevations.next().flatten())).collect();
+    let heights: Vec<Option<f64>> = records.iter().map(|r| r.height.or(r.altitude)).collect();
+    Ok(Some(TerrainProfile {
+        agl: agl(&heights, &ground_elevation, home_ground),
+        ground_elevation,
+    }))
+}
+
+/// Height above ground of points at `heights` above takeoff, over ground at
+/// `ground` elevations, after taking off from `home_ground`
+fn agl(heights: &[Option<f64>], ground: &[Option<f64>], home_ground: Option<f64>) -> Vec<Option<f64>> {
+    heights
+        .iter()
+        .zip(ground)
+        .map(|(height, ground)| Some(home_ground? + (*height)? - (*ground)?))
+        .collect()
+}
+
+/// Elevations of `positions` from Open-Meteo, through the cache
+async fn open_meteo_elevations(db: &Database, positions: &[(f64, f64)]) -> Result<Vec<Option<f64>>, TerrainError> {
+    let cell = |v: f64| (v / CACHE_CELL_DEG).round() as i64;
+    let key = |index: i64| index as f64 * CACHE_CELL_DEG;
+
+    let mut cells: BTreeMap<(i64, i64), Option<f64>> = BTreeMap::new();
+    for &(lat, lon) in positions {
+        cells.entry((cell(lat), cell(lon))).or_insert(None);
+    }
+    let mut missing = Vec::new();
+    for (&(lat, lon), elevation) in cells.iter_mut() {
+        match db.get_cached_elevation("open-meteo", key(lat), key(lon))? {
+            Some(cached) => *elevation = Some(cached),
+            None => missing.push((lat, lon)),
+        }
+    }
+
+    if !missing.is_empty() {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("drone-logbook/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(20))
+            .build()?;
+        for batch in missing.chunks(OPEN_METEO_BATCH) {
+            let join = |f: fn(&(i64, i64)) -> i64| {
+                batch.iter().map(|c| format!("{:.4}", key(f(c)))).collect::<Vec<_>>().join(",")
+            };
+            let body: serde_json::Value = client
+                .get(OPEN_METEO_URL)
+                .query(&[("latitude", join(|c| c.0)), ("longitude", join(|c| c.1))])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let values = body
+                .get("elevation")
+                .and_then(|v| v.as_array())
+                .filter(|v| v.len() == batch.len())
+                .ok_or_else(|| TerrainError::Response(body.to_string()))?;
+            for (&(lat, lon), value) in batch.iter().zip(values) {
+                let elevation = value.as_f64();
+                if let Some(elevation) = elevation {
+                    db.cache_elevation("open-meteo", key(lat), key(lon), elevation)?;
+                }
+                cells.insert((lat, lon), elevation);
+            }
+        }
+        log::info!("Looked up the ground elevation of {} cells on Open-Meteo", missing.len());
+    }
+
+    Ok(positions.iter().map(|&(lat, lon)| cells.get(&(cell(lat), cell(lon))).copied().flatten()).collect())
+}
+
+/// SRTM tiles of a folder, read as they are needed
+struct DemTiles {
+    directory: PathBuf,
+    /// Samples per side and samples, None for tiles that aren't there
+    tiles: HashMap<(i32, i32), Option<(usize, Vec<i16>)>>,
+}
+
+impl DemTiles {
+    fn new(directory: &str) -> Self {
+        Self {
+            directory: PathBuf::from(directory),
+            tiles: HashMap::new(),
+        }
+    }
+
+    /// Elevation at a position, None outside the tiles or over a void
+    fn elevation(&mut self, lat: f64, lon: f64) -> Option<f64> {
+        let corner = (lat.floor() as i32, lon.floor() as i32);
+        let directory = &self.directory;
+        let (size, samples) = self
+            .tiles
+            .entry(corner)
+            .or_insert_with(|| read_hgt(directory, corner.0, corner.1))
+            .as_ref()?;
+        interpolate(*size, samples, lat - f64::from(corner.0), lon - f64::from(corner.1))
+    }
+}
+
+/// Name of the tile whose south-west corner is at `lat`, `lon`
+fn hgt_name(lat: i32, lon: i32) -> String {
+    format!(
+        "{}{:02}{}{:03}.hgt",
+        if lat >= 0 { 'N' } else { 'S' },
+        lat.unsigned_abs(),
+        if lon >= 0 { 'E' } else { 'W' },
+        lon.unsigned_abs()
+    )
+}
+
+fn read_hgt(directory: &Path, lat: i32, lon: i32) -> Option<(usize, Vec<i16>)> {
+    let name = hgt_name(lat, lon);
+    let bytes = std::fs::read(directory.join(&name))
+        .or_else(|_| std::fs::read(directory.join(name.to_lowercase())))
+        .ok()?;
+    let size = match bytes.len() {
+        n if n == 3601 * 3601 * 2 => 3601,
+        n if n == 1201 * 1201 * 2 => 1201,
+        n => {
+            log::warn!("Skipping elevation tile {} of unexpected size {}", name, n);
+            return None;
+        }
+    };
+    let samples = bytes.chunks_exact(2).map(|b| i16::from_be_bytes([b[0], b[1]])).collect();
+    Some((size, samples))
+}
+
+/// Bilinear elevation from a tile's samples, rows running north to south,
+/// at `north` and `east` degrees from its south-west corner
+fn interpolate(size: usize, samples: &[i16], north: f64, east: f64) -> Option<f64> {
+    let last = (size - 1) as f64;
+    let row = ((1.0 - north) * last).clamp(0.0, last);
+    let col = (east * last).clamp(0.0, last);
+    let (r0, c0) = (row.floor() as usize, col.floor() as usize);
+    let (r1, c1) = ((r0 + 1).min(size - 1), (c0 + 1).min(size - 1));
+    let sample = |r: usize, c: usize| samples.get(r * size + c).copied().filter(|&v| v != HGT_VOID).map(f64::from);
+    let (fr, fc) = (row - r0 as f64, col - c0 as f64);
+    let top = sample(r0, c0)? * (1.0 - fc) + sample(r0, c1)? * fc;
+    let bottom = sample(r1, c0)? * (1.0 - fc) + sample(r1, c1)? * fc;
+    Some(top * (1.0 - fr) + bottom * fr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_are_named_by_their_south_west_corner() {
+        assert_eq!(hgt_name(46, 7), "N46E007.hgt");
+        assert_eq!(hgt_name(-34, -71), "S34W071.hgt");
+    }
+
+    #[test]
+    fn elevations_are_interpolated_within_a_tile() {
+        // 3x3 tile rising 100 m per sample eastwards, with a void in the north-west
+        let mut samples: Vec<i16> = (0..9).map(|i| (i % 3) * 100).collect();
+        samples[0] = HGT_VOID;
+        assert_eq!(interpolate(3, &samples, 0.0, 0.0), Some(0.0));
+        assert_eq!(interpolate(3, &samples, 0.25, 0.75), Some(150.0));
+        assert_eq!(interpolate(3, &samples, 1.0, 1.0), Some(200.0));
+        assert_eq!(interpolate(3, &samples, 0.9, 0.1), None);
+    }
+
+    #[test]
+    fn agl_counts_from_the_takeoff_ground() {
+        let heights = [Some(0.0), Some(50.0), Some(50.0), None];
+        let ground = [Some(400.0), Some(430.0), None, Some(420.0)];
+        assert_eq!(agl(&heights, &ground, Some(400.0)), vec![Some(0.0), Some(20.0), None, None]);
+        assert_eq!(agl(&heights, &ground, None), vec![None; 4]);
+    }
+}
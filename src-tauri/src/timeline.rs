@@ -0,0 +1,135 @@
+//! Readable sequence of what happened during a flight.
+//!
+//! Takeoffs, landings and mode changes come from transitions in the
+//! telemetry; captures and warnings from the events parsed at import. Each
+//! event is placed on the `TelemetryData.time` axis so the charts can mark
+//! it.
+//!
+//! Heights drift a little on the ground, so the aircraft counts as airborne
+//! once it climbs past `TAKEOFF_HEIGHT_M` and as landed once it is back
+//! below `LANDED_HEIGHT_M` and slower than `LANDED_SPEED_MS`. Logs started
+//! in the air or stopped before touchdown get no takeoff or landing.
+
+use crate::models::{FlightEvent, TelemetryData, TimelineEvent};
+
+/// Height above takeoff that counts as airborne
+const TAKEOFF_HEIGHT_M: f64 = 1.0;
+
+/// Height above takeoff below which a slow aircraft counts as landed
+const LANDED_HEIGHT_M: f64 = 0.5;
+
+/// Ground speed below which a low aircraft counts as landed
+const LANDED_SPEED_MS: f64 = 1.0;
+
+/// Timeline of a flight, in time order. `timestamps` are the log times of
+/// the telemetry points, the same clock as the events' `timestamp_ms`.
+pub fn build(telemetry: &TelemetryData, timestamps: &[i64], events: &[FlightEvent]) -> Vec<TimelineEvent> {
+    let Some(&base_ms) = timestamps.first() else {
+        return Vec::new();
+    };
+    let mut timeline = Vec::new();
+    let value = |series: &[Option<f64>], i: usize| series.get(i).copied().flatten().filter(|v| v.is_finite());
+
+    let mut airborne: Option<bool> = None;
+    let mut mode: Option<&str> = None;
+    for (i, &t) in telemetry.time.iter().enumerate() {
+        if let Some(height) = value(&telemetry.height, i) {
+            let slow = value(&telemetry.speed, i).map_or(true, |s| s < LANDED_SPEED_MS);
+            match airborne {
+                None => airborne = Some(height >= TAKEOFF_HEIGHT_M),
+                Some(false) if height >= TAKEOFF_HEIGHT_M => {
+                    airborne = Some(true);
+                    timeline.push(event(t, "takeoff", "Took off".to_string()));
+                }
+                Some(true) if height < LANDED_HEIGHT_M && slow => {
+                    airborne = Some(false);
+                    timeline.push(event(t, "landing", "Landed".to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        let Some(current) = telemetry.flight_mode.get(i).and_then(|m| m.as_deref()).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        if mode.is_some_and(|previous| previous != current) {
+            let (kind, label) = if crate::flight_modes::category(current) == "RTH" {
+                ("rth", "Return to home".to_string())
+            } else {
+                ("mode", format!("Mode changed to {}", current))
+            };
+            timeline.push(TimelineEvent {
+                detail: Some(current.to_string()),
+                ..event(t, kind, label)
+            });
+        }
+        mode = Some(current);
+    }
+
+    for e in events {
+        let t = (e.timestamp_ms - base_ms) as f64 / 1000.0;
+        let entry = match e.event_type.as_str() {
+            "photo" => event(t, "photo", "Photo taken".to_string()),
+            "video" => TimelineEvent {
+                duration_secs: e.duration_ms.map(|d| d as f64 / 1000.0),
+                ..event(t, "video", "Video recorded".to_string())
+            },
+            "warning" => TimelineEvent {
+                detail: e.detail.clone(),
+                severity: e.severity.clone(),
+                ..event(t, "warning", e.detail.clone().unwrap_or_else(|| "Warning".to_string()))
+            },
+            _ => continue,
+        };
+        timeline.push(entry);
+    }
+
+    // Stable, so telemetry transitions stay ahead of events at the same time
+    timeline.sort_by(|a, b| a.offset_secs.total_cmp(&b.offset_secs));
+    timeline
+}
+
+fn event(offset_secs: f64, kind: &str, label: String) -> TimelineEvent {
+    TimelineEvent {
+        offset_secs,
+        kind: kind.to_string(),
+        label,
+        detail: None,
+        duration_secs: None,
+        severity: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flight_transitions_and_events_are_ordered() {
+        let modes = ["P-GPS", "P-GPS", "Sport", "Sport", "Go Home", "Go Home", "Go Home"];
+        let telemetry = TelemetryData {
+            time: (0..7).map(|i| f64::from(i) * 10.0).collect(),
+            height: vec![Some(0.2), Some(12.0), Some(40.0), Some(0.8), Some(30.0), Some(5.0), Some(0.1)],
+            speed: vec![Some(0.0), Some(2.0), Some(10.0), Some(0.3), Some(8.0), Some(3.0), Some(0.0)],
+            flight_mode: modes.iter().map(|m| Some(m.to_string())).collect(),
+            ..Default::default()
+        };
+        let timestamps: Vec<i64> = (0..7).map(|i| 5_000 + i * 10_000).collect();
+        let events = [FlightEvent {
+            timestamp_ms: 30_000,
+            event_type: "photo".to_string(),
+            lat: None,
+            lon: None,
+            altitude: None,
+            duration_ms: None,
+            detail: None,
+            severity: None,
+        }];
+
+        let timeline = build(&telemetry, &timestamps, &events);
+        let kinds: Vec<(f64, &str)> = timeline.iter().map(|e| (e.offset_secs, e.kind.as_str())).collect();
+        // 0.8 m is neither airborne nor landed: no landing at 30 s
+        assert_eq!(kinds, vec![(10.0, "takeoff"), (20.0, "mode"), (25.0, "photo"), (40.0, "rth"), (60.0, "landing")]);
+        assert_eq!(timeline[1].detail.as_deref(), Some("Sport"));
+    }
+}
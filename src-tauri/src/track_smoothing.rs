@@ -0,0 +1,174 @@
+//! Optional smoothing of the map track.
+//!
+//! Flights logged with a poor GPS wander a few meters from sample to sample,
+//! which draws a jagged path. The method is saved as `track_smoothing` in
+//! config.json and is off until chosen; only the track sent to the map is
+//! smoothed, never the stored telemetry or the statistics.
+//!
+//! - `Ema` averages each point with the ones before it, weighted by time,
+//!   once forwards and once backwards so the path doesn't lag behind.
+//! - `Kalman` follows a constant-velocity model with a forward filter and a
+//!   backward (Rauch-Tung-Striebel) pass, which keeps turns tighter than the
+//!   moving average.
+//!
+//! Both work in meters east and north of the first point, on the horizontal
+//! position only.
+
+use std::path::Path;
+
+use crate::library::read_config;
+use crate::models::TrackSmoothing;
+
+/// Time constant of the moving average
+const EMA_TAU_SECS: f64 = 2.0;
+
+/// Spread of a GPS fix around the true position
+const GPS_SIGMA_M: f64 = 5.0;
+
+/// Spread of the aircraft's acceleration, the process noise
+const ACCEL_SIGMA_MS2: f64 = 2.0;
+
+/// Meters per degree of latitude
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// Saved smoothing method; off until configured
+pub fn method(data_dir: &Path) -> TrackSmoothing {
+    read_config(data_dir)
+        .get("track_smoothing")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Save the smoothing method to config.json
+pub fn save_method(data_dir: &Path, method: TrackSmoothing) -> std::io::Result<()> {
+    let config_path = data_dir.join("config.json");
+    let mut config = read_config(data_dir);
+    config["track_smoothing"] = serde_json::json!(method);
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+}
+
+/// Smooth the `[lng, lat, height]` points of `track`, logged at `time`
+/// seconds, in place
+pub fn smooth(time: &[f64], track: &mut [[f64; 3]], method: TrackSmoothing) {
+    let n = time.len().min(track.len());
+    if method == TrackSmoothing::Off || n < 3 {
+        return;
+    }
+
+    let [lng0, lat0, _] = track[0];
+    let lng_scale = METERS_PER_DEGREE * lat0.to_radians().cos();
+    let east: Vec<f64> = track[..n].iter().map(|p| (p[0] - lng0) * lng_scale).collect();
+    let north: Vec<f64> = track[..n].iter().map(|p| (p[1] - lat0) * METERS_PER_DEGREE).collect();
+    let (east, north) = match method {
+        TrackSmoothing::Off => return,
+        TrackSmoothing::Ema => (ema(time, &east), ema(time, &north)),
+        TrackSmoothing::Kalman => (kalman(time, &east), kalman(time, &north)),
+    };
+    for (point, (x, y)) in track.iter_mut().zip(east.into_iter().zip(north)) {
+        point[0] = lng0 + x / lng_scale;
+        point[1] = lat0 + y / METERS_PER_DEGREE;
+    }
+}
+
+/// Time-weighted moving average run forwards, then backwards
+fn ema(time: &[f64], values: &[f64]) -> Vec<f64> {
+    let weight = |i: usize, j: usize| 1.0 - (-(time[i] - time[j]).abs() / EMA_TAU_SECS).exp();
+    let mut smoothed = values.to_vec();
+    for i in 1..smoothed.len() {
+        smoothed[i] = smoothed[i - 1] + weight(i, i - 1) * (smoothed[i] - smoothed[i - 1]);
+    }
+    for i in (0..smoothed.len() - 1).rev() {
+        smoothed[i] = smoothed[i + 1] + weight(i, i + 1) * (smoothed[i] - smoothed[i + 1]);
+    }
+    smoothed
+}
+
+/// Constant-velocity Kalman filter with a Rauch-Tung-Striebel backward pass.
+/// Covariances are symmetric 2x2, stored as `[position, cross, velocity]`.
+fn kalman(time: &[f64], values: &[f64]) -> Vec<f64> {
+    let n = values.len();
+    let r = GPS_SIGMA_M * GPS_SIGMA_M;
+    let q = ACCEL_SIGMA_MS2 * ACCEL_SIGMA_MS2;
+
+    // Filtered states and covariances, and the predictions they were updated from
+    let mut filtered: Vec<([f64; 2], [f64; 3])> = Vec::with_capacity(n);
+    let mut predicted: Vec<([f64; 2], [f64; 3])> = Vec::with_capacity(n);
+    let initial = ([values[0], 0.0], [r, 0.0, 100.0]);
+    filtered.push(initial);
+    predicted.push(initial);
+
+    for k in 1..n {
+        let dt = (time[k] - time[k - 1]).max(0.0);
+        let ([x, v], [p00, p01, p11]) = filtered[k - 1];
+        let state = [x + dt * v, v];
+        let cov = [
+            p00 + 2.0 * dt * p01 + dt * dt * p11 + q * dt.powi(4) / 4.0,
+            p01 + dt * p11 + q * dt.powi(3) / 2.0,
+            p11 + q * dt * dt,
+        ];
+        predicted.push((state, cov));
+
+        let s = cov[0] + r;
+        let (k0, k1) = (cov[0] / s, cov[1] / s);
+        let residual = values[k] - state[0];
+        filtered.push((
+            [state[0] + k0 * residual, state[1] + k1 * residual],
+            [(1.0 - k0) * cov[0], (1.0 - k0) * cov[1], cov[2] - k1 * cov[1]],
+        ));
+    }
+
+    let mut smoothed = vec![filtered[n - 1].0; n];
+    for k in (0..n - 1).rev() {
+        let dt = (time[k + 1] - time[k]).max(0.0);
+        let (state, [p00, p01, p11]) = filtered[k];
+        let (next_state, [n00, n01, n11]) = predicted[k + 1];
+        let det = n00 * n11 - n01 * n01;
+        if det.abs() < f64::EPSILON {
+            smoothed[k] = state;
+            continue;
+        }
+        // Gain C = P F^T (P')^-1
+        let a = [[p00 + dt * p01, p01], [p01 + dt * p11, p11]];
+        let inverse = [[n11 / det, -n01 / det], [-n01 / det, n00 / det]];
+        let gain = |row: usize, col: usize| a[row][0] * inverse[0][col] + a[row][1] * inverse[1][col];
+        let diff = [smoothed[k + 1][0] - next_state[0], smoothed[k + 1][1] - next_state[1]];
+        smoothed[k] = [
+            state[0] + gain(0, 0) * diff[0] + gain(0, 1) * diff[1],
+            state[1] + gain(1, 0) * diff[0] + gain(1, 1) * diff[1],
+        ];
+    }
+    smoothed.into_iter().map(|[x, _]| x).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smoothing_steadies_a_noisy_straight_path() {
+        // 1 Hz eastwards at 5 m/s with ±4 m of alternating north error
+        let time: Vec<f64> = (0..120).map(f64::from).collect();
+        let raw: Vec<[f64; 3]> = time
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| {
+                let error = if i % 2 == 0 { 4.0 } else { -4.0 };
+                [7.0 + t * 5.0 / (METERS_PER_DEGREE * 45f64.to_radians().cos()), 45.0 + error / METERS_PER_DEGREE, 30.0]
+            })
+            .collect();
+        let wander = |track: &[[f64; 3]]| track[10..110].iter().map(|p| ((p[1] - 45.0) * METERS_PER_DEGREE).abs()).fold(0.0, f64::max);
+
+        let mut off = raw.clone();
+        smooth(&time, &mut off, TrackSmoothing::Off);
+        assert_eq!(off, raw);
+
+        for method in [TrackSmoothing::Ema, TrackSmoothing::Kalman] {
+            let mut track = raw.clone();
+            smooth(&time, &mut track, method);
+            assert!(wander(&track) < 1.5, "{:?} wanders {:.1} m", method, wander(&track));
+            // Progress along the path is kept
+            assert!((track[60][0] - raw[60][0]).abs() * METERS_PER_DEGREE * 45f64.to_radians().cos() < 1.0);
+            assert!(track.iter().all(|p| p[2] == 30.0));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Watched-folder auto-import for the desktop app.
+//!
+//! Periodically scans the folders listed under `watch_folders` in
+//! `config.json` and imports new log files. A file is only imported once its
+//! size and modified time are unchanged between two scans, so logs that are
+//! still being written by a sync tool (Syncthing, cloud drives) are not picked
+//! up half-finished. Every successful import emits a `watch-import` event so
+//! the frontend can refresh its flight list.
+//!
+//! Handled files are remembered in the database with the size and modified
+//! time they had, so unchanged files are not hashed again on every launch.
+//! Files of deleted flights are on the import blacklist and are skipped.
+//! Scanning and hashing run on the blocking thread pool, and symlinked
+//! folders are not followed, so a link loop cannot recurse forever.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::library::read_config;
+use crate::models::ImportResult;
+use crate::parser::{is_importable_log_file, LogParser};
+use crate::tauri_app::{import_log, AppState};
+
+/// Event emitted to the frontend after a watched file is imported
+pub const WATCH_IMPORT_EVENT: &str = "watch-import";
+
+/// Delay between folder scans
+const SCAN_INTERVAL_SECS: u64 = 15;
+
+/// Payload of the `watch-import` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchImportEvent {
+    pub file_path: String,
+    pub result: ImportResult,
+}
+
+/// Read the watched folder list from config.json
+pub fn read_watch_folders(data_dir: &Path) -> Vec<String> {
+    read_config(data_dir)
+        .get("watch_folders")
+        .and_then(|v| v.as_array())
+        .map(|folders| {
+            folders
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Start the background scan loop. Folder changes in settings are picked up
+/// on the next scan, so the loop never needs restarting.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Last observed signature of files waiting to settle
+        let mut pending: HashMap<PathBuf, Signature> = HashMap::new();
+        // Files already handed to the importer, in the state they were in
+        let mut handled: HashMap<PathBuf, Signature> = app
+            .state::<AppState>()
+            .db
+            .get_watched_files()
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to load watched files: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|(path, size, modified_ms)| (PathBuf::from(path), (size, modified_ms)))
+            .collect();
+
+        loop {
+            let state = app.state::<AppState>();
+            let folders = read_watch_folders(&state.db.data_dir);
+            let files = tokio::task::spawn_blocking(move || scan_folders(&folders))
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("Watched folder scan failed: {}", e);
+                    Vec::new()
+                });
+
+            for (path, signature) in files {
+                if handled.get(&path) == Some(&signature) {
+                    continue;
+                }
+                if pending.insert(path.clone(), signature) != Some(signature) {
+                    // New or still changing — check again next scan
+                    continue;
+                }
+                pending.remove(&path);
+                handled.insert(path.clone(), signature);
+
+                let file_path = path.to_string_lossy().to_string();
+                let hash_path = path.clone();
+                let hash = tokio::task::spawn_blocking(move || LogParser::calculate_file_hash(&hash_path).ok())
+                    .await
+                    .ok()
+                    .flatten();
+                let blacklisted = hash
+                    .as_deref()
+                    .and_then(|hash| state.db.is_file_blacklisted(hash).ok())
+                    .unwrap_or(false);
+                if blacklisted {
+                    log::debug!("Skipping watched file of a deleted flight: {}", file_path);
+                    remember(&state, &file_path, signature);
+                    continue;
+                }
+
+                let result = import_log(file_path.clone(), state.clone())
+                    .await
+                    .unwrap_or_else(|e| ImportResult {
+                        success: false,
+                        flight_id: None,
+                        message: e,
+                        point_count: 0,
+                        file_hash: None,
+                    });
+
+                // Files that failed to parse are retried on the next launch;
+                // imported, linked and duplicate ones are settled for good
+                let in_library = hash
+                    .as_deref()
+                    .and_then(|hash| state.db.is_file_imported(hash).ok().flatten())
+                    .is_some();
+                if result.success || result.file_hash.is_some() || in_library {
+                    remember(&state, &file_path, signature);
+                }
+
+                if result.success {
+                    log::info!("Auto-imported watched file: {}", file_path);
+                    if let Err(e) = app.emit(WATCH_IMPORT_EVENT, WatchImportEvent { file_path, result }) {
+                        log::warn!("Failed to emit watch import event: {}", e);
+                    }
+                } else {
+                    log::debug!("Watched file not imported: {} — {}", file_path, result.message);
+                }
+            }
+
+            // Forget files that vanished before settling
+            pending.retain(|path, _| path.exists());
+
+            tokio::time::sleep(Duration::from_secs(SCAN_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+/// Persist that a watched file was handled, so later launches skip it
+fn remember(state: &AppState, file_path: &str, signature: Signature) {
+    if let Err(e) = state.db.mark_watched_file(file_path, signature.0, signature.1) {
+        log::warn!("Failed to remember watched file {}: {}", file_path, e);
+    }
+}
+
+/// Size and modified time (ms since the epoch) of a file
+type Signature = (u64, Option<i64>);
+
+fn signature(metadata: &fs::Metadata) -> Signature {
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64);
+    (metadata.len(), modified_ms)
+}
+
+/// Supported log files under the watched folders, with their current signature
+fn scan_folders(folders: &[String]) -> Vec<(PathBuf, Signature)> {
+    let mut files = Vec::new();
+    for folder in folders {
+        collect_log_files(Path::new(folder), &mut files);
+    }
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((path, signature(&metadata)))
+        })
+        .collect()
+}
+
+/// Recursively collect supported log files under a folder. Symlinked
+/// folders are skipped: following them could loop forever.
+fn collect_log_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::debug!("Cannot read watched folder {:?}: {}", dir, e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Skip hidden files and sync-tool temp folders (.stfolder, .stversions, ...)
+        if name.starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_log_files(&path, out);
+        } else if file_type.is_symlink() && path.is_dir() {
+            log::debug!("Not following symlinked folder {:?}", path);
+        } else if is_importable_log_file(&path) {
+            out.push(path);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Wind estimated from the aircraft's attitude, for logs that don't record it.
+//!
+//! A multirotor holds its speed through the air by tilting into it: drag
+//! grows with the square of the airspeed and the tilted thrust balances it,
+//! so `tan(tilt) = k * airspeed²` with a drag constant `k` per model. The
+//! tilt gives the airspeed and, with the heading, the direction flown
+//! through the air; the wind is what's left of the ground velocity.
+//!
+//! Accelerating tilts the aircraft too, so the estimate is averaged over
+//! `WIND_WINDOW_SECS` each way, and points on the ground are skipped. It
+//! reads a few m/s off at best, which is enough to tell a calm flight from
+//! one fighting a headwind.
+
+use crate::models::TelemetryData;
+
+/// Time around a point, each way, that the estimate is averaged over
+const WIND_WINDOW_SECS: f64 = 5.0;
+
+/// Height above takeoff below which the aircraft is taken to be on the ground
+const MIN_HEIGHT_M: f64 = 1.0;
+
+/// Drag constants `tan(tilt) / airspeed²` by model name, from the top speed
+/// of each family and the tilt it flies it at; first match wins
+const DRAG_CONSTANTS: &[(&str, f64)] = &[
+    ("mini", 0.0027),
+    ("spark", 0.0035),
+    ("avata", 0.0030),
+    ("air", 0.0019),
+    ("mavic 3", 0.0016),
+    ("mavic", 0.0018),
+    ("phantom", 0.0018),
+    ("inspire", 0.0011),
+    ("matrice", 0.0014),
+];
+
+/// Drag constant for models not in the table
+const DEFAULT_DRAG_CONSTANT: f64 = 0.0020;
+
+fn drag_constant(drone_model: Option<&str>) -> f64 {
+    let model = drone_model.unwrap_or_default().to_lowercase();
+    DRAG_CONSTANTS
+        .iter()
+        .find(|(name, _)| model.contains(name))
+        .map_or(DEFAULT_DRAG_CONSTANT, |&(_, k)| k)
+}
+
+/// Estimated wind speed (m/s) and the direction it blows from (degrees,
+/// 0 = north) at each point of `telemetry`. Both are empty when the log
+/// records wind or has no attitude and velocity to estimate it from.
+pub fn estimate(telemetry: &TelemetryData, drone_model: Option<&str>) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+    if telemetry.wind_speed.iter().any(Option::is_some) {
+        return (Vec::new(), Vec::new());
+    }
+    let k = drag_constant(drone_model);
+    let value = |series: &[Option<f64>], i: usize| series.get(i).copied().flatten().filter(|v| v.is_finite());
+
+    // (index, time, wind north, wind east) of the points with everything needed
+    let mut samples: Vec<(usize, f64, f64, f64)> = Vec::new();
+    for (i, &t) in telemetry.time.iter().enumerate() {
+        if value(&telemetry.height, i).is_some_and(|h| h < MIN_HEIGHT_M) {
+            continue;
+        }
+        let (Some(pitch), Some(roll), Some(yaw), Some(north), Some(east)) = (
+            value(&telemetry.pitch, i),
+            value(&telemetry.roll, i),
+            value(&telemetry.yaw, i),
+            value(&telemetry.velocity_x, i),
+            value(&telemetry.velocity_y, i),
+        ) else {
+            continue;
+        };
+        // Nose down (negative pitch) flies forwards, right side down flies right
+        let (forward, right) = (-pitch.to_radians().tan(), roll.to_radians().tan());
+        let tilt = forward.hypot(right);
+        let (air_north, air_east) = if tilt > 1e-6 {
+            let airspeed = (tilt / k).sqrt();
+            let (sin, cos) = yaw.to_radians().sin_cos();
+            (
+                airspeed * (forward * cos - right * sin) / tilt,
+                airspeed * (forward * sin + right * cos) / tilt,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        samples.push((i, t, north - air_north, east - air_east));
+    }
+
+    if samples.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let mut speed = vec![None; telemetry.time.len()];
+    let mut direction = vec![None; telemetry.time.len()];
+    let (mut first, mut last) = (0, 0);
+    for &(i, t, _, _) in &samples {
+        while samples[first].1 < t - WIND_WINDOW_SECS {
+            first += 1;
+        }
+        while last + 1 < samples.len() && samples[last + 1].1 <= t + WIND_WINDOW_SECS {
+            last += 1;
+        }
+        let window = &samples[first..=last];
+        let count = window.len() as f64;
+        let north = window.iter().map(|s| s.2).sum::<f64>() / count;
+        let east = window.iter().map(|s| s.3).sum::<f64>() / count;
+        speed[i] = Some(north.hypot(east));
+        // Blowing towards north-east comes from the south-west
+        direction[i] = Some((-east).atan2(-north).to_degrees().rem_euclid(360.0));
+    }
+    (speed, direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headwind_tilt_reads_as_wind() {
+        // Hovering over one spot heading east, leaning into a 6 m/s wind from the east
+        let k = drag_constant(Some("Mini 4 Pro"));
+        let pitch = -(k * 36.0_f64).atan().to_degrees();
+        let n = 60;
+        let telemetry = TelemetryData {
+            time: (0..n).map(f64::from).collect(),
+            height: vec![Some(30.0); n as usize],
+            pitch: vec![Some(pitch); n as usize],
+            roll: vec![Some(0.0); n as usize],
+            yaw: vec![Some(90.0); n as usize],
+            velocity_x: vec![Some(0.0); n as usize],
+            velocity_y: vec![Some(0.0); n as usize],
+            ..Default::default()
+        };
+
+        let (speed, direction) = estimate(&telemetry, Some("Mini 4 Pro"));
+        assert!((speed[30].unwrap() - 6.0).abs() < 1e-6);
+        assert!((direction[30].unwrap() - 90.0).abs() < 1e-6);
+
+        // Logs that record wind keep theirs
+        let logged = TelemetryData {
+            wind_speed: vec![Some(3.0); n as usize],
+            ..telemetry
+        };
+        assert_eq!(estimate(&logged, None), (Vec::new(), Vec::new()));
+    }
+}
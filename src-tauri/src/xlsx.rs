@@ -0,0 +1,357 @@
+//! Minimal XLSX (Office Open XML spreadsheet) writer for exports.
+//!
+//! Supports what the exports need: several sheets of numbers, text and date
+//! cells, a bold frozen header row with an autofilter, and column widths
+//! estimated from the content. Strings are written inline, so there is no
+//! shared string table; Excel, LibreOffice and Numbers all open the result.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Excel's limit on sheet name length
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// Widest a column gets from its content, in characters
+const MAX_COLUMN_WIDTH: usize = 60;
+
+/// Style indexes into the `cellXfs` of `styles.xml`
+const STYLE_HEADER: u8 = 1;
+const STYLE_DATE_TIME: u8 = 2;
+const STYLE_DURATION: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Number(f64),
+    Text(String),
+    /// Shown as `yyyy-mm-dd hh:mm:ss`
+    DateTime(DateTime<Utc>),
+    /// Seconds, shown as `[h]:mm:ss`
+    Duration(f64),
+}
+
+impl From<Option<f64>> for Cell {
+    fn from(value: Option<f64>) -> Self {
+        value.filter(|v| v.is_finite()).map_or(Cell::Empty, Cell::Number)
+    }
+}
+
+impl From<Option<&str>> for Cell {
+    fn from(value: Option<&str>) -> Self {
+        value.filter(|v| !v.is_empty()).map_or(Cell::Empty, |v| Cell::Text(v.to_string()))
+    }
+}
+
+/// One worksheet; the first row is the header
+#[derive(Debug, Default)]
+pub struct Sheet {
+    name: String,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Sheet {
+    pub fn push_row(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    pub fn push_header<S: AsRef<str>>(&mut self, titles: &[S]) {
+        self.rows.push(titles.iter().map(|t| Cell::Text(t.as_ref().to_string())).collect());
+    }
+
+    fn to_xml(&self) -> String {
+        let columns = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">",
+        );
+        if self.rows.len() > 1 {
+            xml.push_str(
+                "<sheetViews><sheetView workbookViewId=\"0\">\
+                 <pane ySplit=\"1\" topLeftCell=\"A2\" activePane=\"bottomLeft\" state=\"frozen\"/>\
+                 </sheetView></sheetViews>",
+            );
+        }
+        if columns > 0 {
+            xml.push_str("<cols>");
+            for column in 0..columns {
+                let _ = write!(
+                    xml,
+                    "<col min=\"{0}\" max=\"{0}\" width=\"{1}\" customWidth=\"1\"/>",
+                    column + 1,
+                    self.column_width(column)
+                );
+            }
+            xml.push_str("</cols>");
+        }
+
+        xml.push_str("<sheetData>");
+        for (row_index, row) in self.rows.iter().enumerate() {
+            let _ = write!(xml, "<row r=\"{}\">", row_index + 1);
+            for (column, cell) in row.iter().enumerate() {
+                let reference = format!("{}{}", column_name(column), row_index + 1);
+                let header_style = if row_index == 0 { Some(STYLE_HEADER) } else { None };
+                match cell {
+                    Cell::Empty => {}
+                    Cell::Number(value) if !value.is_finite() => {}
+                    Cell::Number(value) => {
+                        let _ = write!(xml, "<c r=\"{}\"{}><v>{}</v></c>", reference, style(header_style), value);
+                    }
+                    Cell::Text(text) => {
+                        let _ = write!(
+                            xml,
+                            "<c r=\"{}\"{} t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                            reference,
+                            style(header_style),
+                            xml_escape(text)
+                        );
+                    }
+                    Cell::DateTime(time) => {
+                        let _ = write!(
+                            xml,
+                            "<c r=\"{}\"{}><v>{}</v></c>",
+                            reference,
+                            style(Some(STYLE_DATE_TIME)),
+                            excel_date(time)
+                        );
+                    }
+                    Cell::Duration(secs) => {
+                        let _ = write!(
+                            xml,
+                            "<c r=\"{}\"{}><v>{}</v></c>",
+                            reference,
+                            style(Some(STYLE_DURATION)),
+                            secs / 86_400.0
+                        );
+                    }
+                }
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData>");
+        if self.rows.len() > 1 && columns > 0 {
+            let _ = write!(xml, "<autoFilter ref=\"A1:{}{}\"/>", column_name(columns - 1), self.rows.len());
+        }
+        xml.push_str("</worksheet>");
+        xml
+    }
+
+    /// Width in characters that fits the column's first rows
+    fn column_width(&self, column: usize) -> usize {
+        let widest = self
+            .rows
+            .iter()
+            .take(200)
+            .filter_map(|row| row.get(column))
+            .map(|cell| match cell {
+                Cell::Empty => 0,
+                Cell::Number(value) => value.to_string().len(),
+                Cell::Text(text) => text.chars().count(),
+                Cell::DateTime(_) => 19,
+                Cell::Duration(_) => 8,
+            })
+            .max()
+            .unwrap_or(0);
+        (widest + 2).clamp(8, MAX_COLUMN_WIDTH)
+    }
+}
+
+/// A workbook being built sheet by sheet
+#[derive(Debug, Default)]
+pub struct Workbook {
+    sheets: Vec<Sheet>,
+    used_names: HashSet<String>,
+}
+
+impl Workbook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sheet. The name is cut to what Excel allows and made unique.
+    pub fn add_sheet(&mut self, name: &str) -> &mut Sheet {
+        let cleaned: String = name
+            .chars()
+            .map(|c| if matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\') { '_' } else { c })
+            .collect();
+        let cleaned = cleaned.trim().trim_matches('\'');
+        let base = if cleaned.is_empty() { format!("Sheet{}", self.sheets.len() + 1) } else { cleaned.to_string() };
+
+        let mut name: String = base.chars().take(MAX_SHEET_NAME_LEN).collect::<String>().trim_end().to_string();
+        let mut counter = 2;
+        while !self.used_names.insert(name.to_lowercase()) {
+            let suffix = format!(" ({})", counter);
+            let keep = MAX_SHEET_NAME_LEN - suffix.len();
+            name = format!("{}{}", base.chars().take(keep).collect::<String>().trim_end(), suffix);
+            counter += 1;
+        }
+        self.sheets.push(Sheet { name, rows: Vec::new() });
+        self.sheets.last_mut().unwrap()
+    }
+
+    pub fn sheet_mut(&mut self, index: usize) -> Option<&mut Sheet> {
+        self.sheets.get_mut(index)
+    }
+
+    /// Package the workbook as an .xlsx file
+    pub fn finish(self) -> Result<Vec<u8>, zip::result::ZipError> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        let mut content_types = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+             <Override PartName=\"/xl/workbook.xml\" \
+             ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+             <Override PartName=\"/xl/styles.xml\" \
+             ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>",
+        );
+        let mut workbook = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>",
+        );
+        let mut workbook_rels = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+        );
+        let mut defined_names = String::new();
+        for (index, sheet) in self.sheets.iter().enumerate() {
+            let number = index + 1;
+            let _ = write!(
+                content_types,
+                "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
+                 ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+                number
+            );
+            let _ = write!(
+                workbook,
+                "<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+                xml_escape(&sheet.name),
+                number,
+                number
+            );
+            let _ = write!(
+                workbook_rels,
+                "<Relationship Id=\"rId{}\" \
+                 Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
+                 Target=\"worksheets/sheet{}.xml\"/>",
+                number, number
+            );
+            // Excel expects the autofilter range to be named as well
+            let columns = sheet.rows.iter().map(Vec::len).max().unwrap_or(0);
+            if sheet.rows.len() > 1 && columns > 0 {
+                let _ = write!(
+                    defined_names,
+                    "<definedName name=\"_xlnm._FilterDatabase\" localSheetId=\"{}\" hidden=\"1\">'{}'!$A$1:${}${}</definedName>",
+                    index,
+                    xml_escape(&sheet.name.replace('\'', "''")),
+                    column_name(columns - 1),
+                    sheet.rows.len()
+                );
+            }
+        }
+        content_types.push_str("</Types>");
+        workbook.push_str("</sheets>");
+        if !defined_names.is_empty() {
+            let _ = write!(workbook, "<definedNames>{}</definedNames>", defined_names);
+        }
+        workbook.push_str("</workbook>");
+        let _ = write!(
+            workbook_rels,
+            "<Relationship Id=\"rId{}\" \
+             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" \
+             Target=\"styles.xml\"/></Relationships>",
+            self.sheets.len() + 1
+        );
+
+        zip.start_file("[Content_Types].xml", options)?;
+        zip.write_all(content_types.as_bytes())?;
+        zip.start_file("_rels/.rels", options)?;
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+              <Relationship Id=\"rId1\" \
+              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" \
+              Target=\"xl/workbook.xml\"/></Relationships>",
+        )?;
+        zip.start_file("xl/workbook.xml", options)?;
+        zip.write_all(workbook.as_bytes())?;
+        zip.start_file("xl/_rels/workbook.xml.rels", options)?;
+        zip.write_all(workbook_rels.as_bytes())?;
+        zip.start_file("xl/styles.xml", options)?;
+        zip.write_all(STYLES.as_bytes())?;
+        for (index, sheet) in self.sheets.iter().enumerate() {
+            zip.start_file(format!("xl/worksheets/sheet{}.xml", index + 1), options)?;
+            zip.write_all(sheet.to_xml().as_bytes())?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+/// Cell formats: 0 default, 1 bold header, 2 date and time, 3 duration
+const STYLES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<numFmts count=\"2\">\
+<numFmt numFmtId=\"164\" formatCode=\"yyyy-mm-dd hh:mm:ss\"/>\
+<numFmt numFmtId=\"165\" formatCode=\"[h]:mm:ss\"/>\
+</numFmts>\
+<fonts count=\"2\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font>\
+<font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"2\"><fill><patternFill patternType=\"none\"/></fill>\
+<fill><patternFill patternType=\"gray125\"/></fill></fills>\
+<borders count=\"1\"><border><left/><right/><top/><bottom/><diagonal/></border></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"4\">\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/>\
+<xf numFmtId=\"164\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"165\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+</cellXfs>\
+<cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\" builtinId=\"0\"/></cellStyles>\
+</styleSheet>";
+
+fn style(style: Option<u8>) -> String {
+    style.map(|s| format!(" s=\"{}\"", s)).unwrap_or_default()
+}
+
+/// Column letters for a 0-based index: A, B, ..., Z, AA, AB, ...
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Excel serial date: days since 1899-12-30, the time as the fraction
+fn excel_date(time: &DateTime<Utc>) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    (time.naive_utc() - epoch).num_milliseconds() as f64 / 86_400_000.0
+}
+
+/// Escape text for XML, dropping control characters XML can't hold
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
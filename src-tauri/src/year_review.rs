@@ -0,0 +1,317 @@
+//! "Year in review": a summary of one calendar year of flying to share.
+//!
+//! The summary holds the year's totals, flight time per month, the most
+//! flown aircraft and battery, the best flights of the year (and which of
+//! them are all-time records) and the sites first flown from that year. It
+//! is returned as JSON for the app to show, or drawn as a one-page PDF.
+//!
+//! Flights excluded from statistics don't count. Years follow the UTC date
+//! of each takeoff.
+
+use chrono::Utc;
+
+use crate::database::{Database, DatabaseError};
+use crate::export::{ExportError, ExportUnits, LengthUnit};
+use crate::models::{FlightFilter, MonthlyStats, PersonalRecord, PersonalRecords, YearReview};
+use crate::pdf::{text_width, Color, Font, PdfDocument, PAGE_HEIGHT, PAGE_WIDTH};
+
+const MARGIN: f64 = 40.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// New sites listed by name in the PDF
+const MAX_LISTED_SITES: usize = 6;
+
+const HEADER_COLOR: Color = Color::hex(0x111827);
+const TEXT_COLOR: Color = Color::hex(0x111827);
+const MUTED_COLOR: Color = Color::hex(0x6b7280);
+const RULE_COLOR: Color = Color::hex(0xd1d5db);
+const TILE_COLOR: Color = Color::hex(0xf3f4f6);
+const BAR_COLOR: Color = Color::hex(0x0ea5e9);
+const RECORD_COLOR: Color = Color::hex(0xea580c);
+
+const MONTH_NAMES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Summary of the flights that took off in `year`
+pub fn year_review(db: &Database, year: i32) -> Result<YearReview, DatabaseError> {
+    let filter = FlightFilter {
+        date_from: Some(format!("{:04}-01-01", year)),
+        date_to: Some(format!("{:04}-12-31", year)),
+        ..Default::default()
+    };
+    let flight_ids = db.evaluate_flight_filter(&filter)?;
+    let stats = db.get_library_stats(Some(&flight_ids))?;
+    let year_records = db.get_personal_records(Some(&flight_ids))?;
+    let all_time = db.get_personal_records(None)?;
+
+    let prefix = format!("{:04}-", year);
+    let mut new_sites: Vec<_> = crate::sites::sites(db)?
+        .into_iter()
+        .filter(|site| site.first_flight.as_deref().is_some_and(|t| t.starts_with(&prefix)))
+        .collect();
+    new_sites.sort_by(|a, b| a.first_flight.cmp(&b.first_flight));
+
+    Ok(YearReview {
+        year,
+        months: all_months(year, &stats.months),
+        records_set: records_set(&year_records, &all_time),
+        stats,
+        year_records,
+        new_sites,
+    })
+}
+
+/// Every month of `year`, with zeros for those missing from `months`
+fn all_months(year: i32, months: &[MonthlyStats]) -> Vec<MonthlyStats> {
+    (1..=12)
+        .map(|month| {
+            let key = format!("{:04}-{:02}", year, month);
+            months.iter().find(|m| m.month == key).cloned().unwrap_or(MonthlyStats {
+                month: key,
+                flight_count: 0,
+                total_duration_secs: 0.0,
+                total_distance_m: 0.0,
+            })
+        })
+        .collect()
+}
+
+/// The records with their key and label, in display order
+fn record_entries(records: &PersonalRecords) -> [(&'static str, &'static str, Option<&PersonalRecord>); 5] {
+    [
+        ("longestDuration", "Longest flight", records.longest_duration.as_ref()),
+        ("longestDistance", "Longest distance", records.longest_distance.as_ref()),
+        ("highestAltitude", "Highest altitude", records.highest_altitude.as_ref()),
+        ("topSpeed", "Top speed", records.top_speed.as_ref()),
+        ("farthestFromHome", "Farthest from home", records.farthest_from_home.as_ref()),
+    ]
+}
+
+/// Keys of the all-time records held by a flight of the year. Both are
+/// ranked the same way, so the year's best is the all-time record exactly
+/// when it is the same flight.
+fn records_set(year_records: &PersonalRecords, all_time: &PersonalRecords) -> Vec<String> {
+    record_entries(year_records)
+        .into_iter()
+        .zip(record_entries(all_time))
+        .filter(|((_, _, best), (_, _, record))| {
+            matches!((best, record), (Some(best), Some(record)) if best.flight_id == record.flight_id)
+        })
+        .map(|((key, _, _), _)| key.to_string())
+        .collect()
+}
+
+/// One-page PDF of the year in review, with values in `units`
+pub fn year_review_pdf(db: &Database, year: i32, units: &ExportUnits) -> Result<Vec<u8>, ExportError> {
+    let review = year_review(db, year)?;
+    let pilot = crate::report::pilot_details(&db.data_dir);
+    let stats = &review.stats;
+
+    let mut doc = PdfDocument::new();
+    let page = doc.add_page();
+
+    page.fill_rect(0.0, 0.0, PAGE_WIDTH, 96.0, HEADER_COLOR);
+    page.text(MARGIN, 48.0, 26.0, Font::Bold, Color::WHITE, &format!("{} in review", year));
+    let subtitle = pilot.name.as_deref().unwrap_or("Drone Logbook");
+    page.text(MARGIN, 72.0, 11.0, Font::Regular, Color::hex(0xd1d5db), subtitle);
+
+    // Headline figures
+    let tiles = [
+        ("Flights", stats.total_flights.to_string()),
+        ("Flight time", format_hours(stats.total_duration_secs)),
+        ("Distance flown", distance(stats.total_distance_m, units)),
+        ("Days flown", stats.days_flown.to_string()),
+    ];
+    let gap = 10.0;
+    let tile_width = (CONTENT_WIDTH - gap * (tiles.len() - 1) as f64) / tiles.len() as f64;
+    for (i, (label, value)) in tiles.iter().enumerate() {
+        let x = MARGIN + i as f64 * (tile_width + gap);
+        page.fill_rect(x, 116.0, tile_width, 62.0, TILE_COLOR);
+        page.text(x + 10.0, 134.0, 8.0, Font::Regular, MUTED_COLOR, label);
+        page.text(x + 10.0, 162.0, 18.0, Font::Bold, TEXT_COLOR, value);
+    }
+
+    // Flight time per month
+    let mut y = 210.0;
+    page.text(MARGIN, y, 12.0, Font::Bold, TEXT_COLOR, "Flight time per month");
+    page.line((MARGIN, y + 6.0), (MARGIN + CONTENT_WIDTH, y + 6.0), 0.5, RULE_COLOR);
+    let (chart_top, chart_height) = (y + 30.0, 130.0);
+    let baseline = chart_top + chart_height;
+    let max_secs = review.months.iter().map(|m| m.total_duration_secs).fold(0.0, f64::max);
+    let slot = CONTENT_WIDTH / 12.0;
+    for (i, month) in review.months.iter().enumerate() {
+        let x = MARGIN + i as f64 * slot;
+        let center = x + slot / 2.0;
+        if max_secs > 0.0 && month.total_duration_secs > 0.0 {
+            let height = (month.total_duration_secs / max_secs * chart_height).max(1.0);
+            page.fill_rect(x + slot * 0.2, baseline - height, slot * 0.6, height, BAR_COLOR);
+            let label = format_hours(month.total_duration_secs);
+            let width = text_width(&label, 7.0, Font::Regular);
+            page.text(center - width / 2.0, baseline - height - 4.0, 7.0, Font::Regular, MUTED_COLOR, &label);
+        }
+        let name = MONTH_NAMES[i];
+        let width = text_width(name, 8.0, Font::Regular);
+        page.text(center - width / 2.0, baseline + 14.0, 8.0, Font::Regular, TEXT_COLOR, name);
+    }
+    page.line((MARGIN, baseline), (MARGIN + CONTENT_WIDTH, baseline), 0.5, RULE_COLOR);
+
+    // Highlights
+    y = baseline + 48.0;
+    page.text(MARGIN, y, 12.0, Font::Bold, TEXT_COLOR, "Highlights");
+    page.line((MARGIN, y + 6.0), (MARGIN + CONTENT_WIDTH, y + 6.0), 0.5, RULE_COLOR);
+    let busiest = review
+        .months
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.flight_count > 0)
+        .max_by(|a, b| a.1.total_duration_secs.total_cmp(&b.1.total_duration_secs))
+        .map(|(i, m)| format!("{} ({})", MONTH_NAMES[i], format_hours(m.total_duration_secs)));
+    let drone = stats.drones.first().map(|d| {
+        let name = d.nickname.as_deref().or(d.aircraft_name.as_deref()).unwrap_or(&d.drone_model);
+        format!("{} ({})", name, format_hours(d.total_duration_secs))
+    });
+    let battery = stats
+        .batteries
+        .first()
+        .map(|b| format!("{} ({} flights)", b.battery_serial, b.flight_count));
+    let highlights = [
+        ("Most flown aircraft", drone),
+        ("Most used battery", battery),
+        ("Busiest month", busiest),
+        ("New sites", Some(review.new_sites.len().to_string())),
+    ];
+    let column_width = CONTENT_WIDTH / 2.0;
+    y += 28.0;
+    for row in highlights.chunks(2) {
+        for (i, (label, value)) in row.iter().enumerate() {
+            let x = MARGIN + i as f64 * column_width;
+            let value = fit(value.as_deref().unwrap_or("-"), 10.0, Font::Bold, column_width - 12.0);
+            page.text(x, y, 8.0, Font::Regular, MUTED_COLOR, label);
+            page.text(x, y + 13.0, 10.0, Font::Bold, TEXT_COLOR, &value);
+        }
+        y += 30.0;
+    }
+    if !review.new_sites.is_empty() {
+        let mut names: Vec<&str> = review.new_sites.iter().take(MAX_LISTED_SITES).map(|s| s.name.as_str()).collect();
+        let more = review.new_sites.len().saturating_sub(MAX_LISTED_SITES);
+        let more_label = format!("and {} more", more);
+        if more > 0 {
+            names.push(&more_label);
+        }
+        let line = fit(&format!("First flown from: {}", names.join(", ")), 8.0, Font::Regular, CONTENT_WIDTH);
+        page.text(MARGIN, y, 8.0, Font::Regular, MUTED_COLOR, &line);
+        y += 16.0;
+    }
+
+    // Best flights of the year
+    y += 24.0;
+    page.text(MARGIN, y, 12.0, Font::Bold, TEXT_COLOR, "Best of the year");
+    page.line((MARGIN, y + 6.0), (MARGIN + CONTENT_WIDTH, y + 6.0), 0.5, RULE_COLOR);
+    y += 26.0;
+    for (key, label, record) in record_entries(&review.year_records) {
+        let Some(record) = record else { continue };
+        let value = match key {
+            "longestDuration" => format_hours(record.value),
+            "highestAltitude" => format!("{:.0} {}", units.length.convert(record.value), units.length.label()),
+            "topSpeed" => format!("{:.1} {}", units.speed.convert(record.value), units.speed.label()),
+            _ => distance(record.value, units),
+        };
+        let date = record.start_time.as_deref().map(|t| t.chars().take(10).collect::<String>());
+        let flight = match date {
+            Some(date) => format!("{} \u{b7} {}", record.display_name, date),
+            None => record.display_name.clone(),
+        };
+        page.text(MARGIN, y, 9.0, Font::Regular, MUTED_COLOR, label);
+        page.text(MARGIN + 120.0, y, 10.0, Font::Bold, TEXT_COLOR, &value);
+        page.text(MARGIN + 220.0, y, 9.0, Font::Regular, TEXT_COLOR, &fit(&flight, 9.0, Font::Regular, 200.0));
+        if review.records_set.iter().any(|k| k == key) {
+            page.text_right(MARGIN + CONTENT_WIDTH, y, 8.0, Font::Bold, RECORD_COLOR, "All-time record");
+        }
+        y += 20.0;
+    }
+
+    let footer_y = PAGE_HEIGHT - 24.0;
+    let generated = format!("Generated {}", Utc::now().format("%Y-%m-%d"));
+    page.line((MARGIN, footer_y - 12.0), (MARGIN + CONTENT_WIDTH, footer_y - 12.0), 0.5, RULE_COLOR);
+    page.text(MARGIN, footer_y, 8.0, Font::Regular, MUTED_COLOR, "Drone Logbook");
+    page.text_right(MARGIN + CONTENT_WIDTH, footer_y, 8.0, Font::Regular, MUTED_COLOR, &generated);
+
+    Ok(doc.finish(&format!("{} in review", year)))
+}
+
+fn format_hours(secs: f64) -> String {
+    let minutes = (secs / 60.0).round() as i64;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Longer distances in km or miles, following the length unit
+fn distance(meters: f64, units: &ExportUnits) -> String {
+    match units.length {
+        LengthUnit::Meters if meters >= 1000.0 => format!("{:.1} km", meters / 1000.0),
+        LengthUnit::Feet if meters >= 1609.344 => format!("{:.1} mi", meters / 1609.344),
+        unit => format!("{:.0} {}", unit.convert(meters), unit.label()),
+    }
+}
+
+/// `text` shortened with an ellipsis to fit `max_width`
+fn fit(text: &str, size: f64, font: Font, max_width: f64) -> String {
+    if text_width(text, size, font) <= max_width {
+        return text.to_string();
+    }
+    let mut shortened = text.to_string();
+    while !shortened.is_empty() && text_width(&format!("{}\u{2026}", shortened), size, font) > max_width {
+        shortened.pop();
+    }
+    format!("{}\u{2026}", shortened.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(flight_id: i64, value: f64) -> Option<PersonalRecord> {
+        Some(PersonalRecord {
+            flight_id,
+            display_name: "Flight".to_string(),
+            start_time: None,
+            value,
+        })
+    }
+
+    #[test]
+    fn months_without_flights_are_filled_in() {
+        let months = all_months(
+            2025,
+            &[MonthlyStats {
+                month: "2025-03".to_string(),
+                flight_count: 4,
+                total_duration_secs: 3600.0,
+                total_distance_m: 12_000.0,
+            }],
+        );
+        assert_eq!(months.len(), 12);
+        assert_eq!(months[0].month, "2025-01");
+        assert_eq!(months[0].flight_count, 0);
+        assert_eq!(months[2].flight_count, 4);
+        assert_eq!(months[11].month, "2025-12");
+    }
+
+    #[test]
+    fn records_set_are_the_years_bests_that_hold_all_time() {
+        let year = PersonalRecords {
+            longest_duration: record(7, 1500.0),
+            top_speed: record(8, 15.0),
+            ..Default::default()
+        };
+        let all_time = PersonalRecords {
+            longest_duration: record(7, 1500.0),
+            top_speed: record(2, 20.0),
+            highest_altitude: record(2, 120.0),
+            ..Default::default()
+        };
+        assert_eq!(records_set(&year, &all_time), vec!["longestDuration".to_string()]);
+    }
+}
@@ -1,29 +1,53 @@
 /**
  * Telemetry charts component using ECharts
- * Displays height, VPS height, speed, battery, attitude, RC, GPS, distance to home, and velocity data
+ * Displays height, VPS height, speed, battery, battery cells, attitude, RC signal, RC sticks, motors, GPS, distance to home, and velocity data
  * Optimized for performance with large datasets
  */
 
 import { useMemo, useRef, useCallback, useState, useEffect } from 'react';
 import ReactECharts from 'echarts-for-react';
 import type { EChartsOption, ECharts, LineSeriesOption } from 'echarts';
-import type { TelemetryData } from '@/types';
-import type { UnitSystem } from '@/lib/utils';
+import type { FlightAnnotation, FlightPhase, MotorSeries, TelemetryData, TimelineEvent } from '@/types';
+import { flightPhaseColor, type UnitSystem } from '@/lib/utils';
 import { useFlightStore } from '@/stores/flightStore';
 
 interface TelemetryChartsProps {
   data: TelemetryData;
   unitSystem: UnitSystem;
   startTime?: string | null;
+  /** Per-motor ESC series (flight controller DAT logs only) */
+  motors?: MotorSeries[];
+  /** Flight the charts belong to (enables adding annotations) */
+  flightId?: number;
+  /** User notes pinned to moments of the flight */
+  annotations?: FlightAnnotation[];
+  /** Takeoffs, landings, mode changes, captures and warnings */
+  timeline?: TimelineEvent[];
+  /** Phase ranges shaded behind the altitude chart */
+  phases?: FlightPhase[];
 }
 
-export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryChartsProps) {
+export function TelemetryCharts({
+  data,
+  unitSystem,
+  startTime,
+  motors = [],
+  flightId,
+  annotations = [],
+  timeline = [],
+  phases = [],
+}: TelemetryChartsProps) {
   const chartsRef = useRef<ECharts[]>([]);
   const isSyncingRef = useRef(false);
   const themeMode = useFlightStore((state) => state.themeMode);
   const mapSyncEnabled = useFlightStore((state) => state.mapSyncEnabled);
   const setMapSyncEnabled = useFlightStore((state) => state.setMapSyncEnabled);
   const mapReplayProgress = useFlightStore((state) => state.mapReplayProgress);
+  const addAnnotation = useFlightStore((state) => state.addAnnotation);
+  const removeAnnotation = useFlightStore((state) => state.removeAnnotation);
+  const [annotationText, setAnnotationText] = useState('');
+  const [annotationTime, setAnnotationTime] = useState('');
+  const [isTimelineOpen, setIsTimelineOpen] = useState(false);
   const resolvedTheme = useMemo(() => resolveThemeMode(themeMode), [themeMode]);
   const splitLineColor = resolvedTheme === 'light' ? '#e2e8f0' : '#2a2a4e';
   const tooltipFormatter = useMemo(
@@ -153,6 +177,22 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
     });
   }, [mapSyncEnabled, mapReplayProgress, data.time]);
 
+  // Time of the replay cursor, used as the default position for a new annotation
+  const replayOffsetSecs = useMemo(() => {
+    const time = data.time ?? [];
+    if (time.length === 0) return 0;
+    return time[Math.round(mapReplayProgress * (time.length - 1))] ?? 0;
+  }, [mapReplayProgress, data.time]);
+
+  const handleAddAnnotation = useCallback(async () => {
+    const text = annotationText.trim();
+    if (flightId === undefined || !text) return;
+    const typed = parseOffset(annotationTime);
+    await addAnnotation(flightId, typed ?? replayOffsetSecs, text);
+    setAnnotationText('');
+    setAnnotationTime('');
+  }, [addAnnotation, annotationText, annotationTime, flightId, replayOffsetSecs]);
+
   // Memoize chart options to prevent unnecessary re-renders
   const altitudeSpeedOption = useMemo(
     () =>
@@ -161,14 +201,22 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
         unitSystem,
         splitLineColor,
         tooltipFormatter,
-        tooltipColors
+        tooltipColors,
+        annotations,
+        timeline,
+        phases
       ),
-    [data, splitLineColor, tooltipColors, tooltipFormatter, unitSystem]
+    [annotations, data, phases, splitLineColor, timeline, tooltipColors, tooltipFormatter, unitSystem]
   );
   const batteryOption = useMemo(
     () => createBatteryChart(data, splitLineColor, tooltipFormatter, tooltipColors),
     [data, splitLineColor, tooltipColors, tooltipFormatter]
   );
+  const cellVoltages = data.cellVoltages ?? [];
+  const cellVoltageOption = useMemo(
+    () => createCellVoltageChart(data, splitLineColor, tooltipFormatter, tooltipColors),
+    [data, splitLineColor, tooltipColors, tooltipFormatter]
+  );
   const attitudeOption = useMemo(
     () => createAttitudeChart(data, splitLineColor, tooltipFormatter, tooltipColors),
     [data, splitLineColor, tooltipColors, tooltipFormatter]
@@ -177,6 +225,42 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
     () => createRcSignalChart(data, splitLineColor, tooltipFormatter, tooltipColors),
     [data, splitLineColor, tooltipColors, tooltipFormatter]
   );
+  const hasRcSticks = useMemo(
+    () =>
+      [data.rcAileron, data.rcElevator, data.rcThrottle, data.rcRudder].some((series) =>
+        (series ?? []).some((val) => val !== null && val !== undefined)
+      ),
+    [data.rcAileron, data.rcElevator, data.rcThrottle, data.rcRudder]
+  );
+  const rcSticksOption = useMemo(
+    () => createRcSticksChart(data, splitLineColor, tooltipFormatter, tooltipColors),
+    [data, splitLineColor, tooltipColors, tooltipFormatter]
+  );
+  const hasSensorHealth = useMemo(
+    () =>
+      (data.compassInterference ?? []).some((val) => val !== null && val !== undefined) ||
+      (data.compassError ?? []).some((val) => val === true) ||
+      (data.imuStatus ?? []).some((val) => !!val),
+    [data.compassInterference, data.compassError, data.imuStatus]
+  );
+  const sensorHealthOption = useMemo(
+    () => createSensorHealthChart(data, splitLineColor, tooltipFormatter, tooltipColors),
+    [data, splitLineColor, tooltipColors, tooltipFormatter]
+  );
+  const hasWind = useMemo(
+    () =>
+      (data.windSpeed ?? []).some((val) => val !== null && val !== undefined) ||
+      (data.estimatedWindSpeed ?? []).some((val) => val !== null && val !== undefined),
+    [data.windSpeed, data.estimatedWindSpeed]
+  );
+  const windOption = useMemo(
+    () => createWindChart(data, unitSystem, splitLineColor, tooltipFormatter, tooltipColors),
+    [data, splitLineColor, tooltipColors, tooltipFormatter, unitSystem]
+  );
+  const motorOption = useMemo(
+    () => createMotorChart(data, motors, splitLineColor, tooltipFormatter, tooltipColors),
+    [data, motors, splitLineColor, tooltipColors, tooltipFormatter]
+  );
   const distanceToHomeOption = useMemo(
     () => createDistanceToHomeChart(data, unitSystem, splitLineColor, tooltipFormatter, tooltipColors),
     [data, splitLineColor, tooltipColors, tooltipFormatter, unitSystem]
@@ -240,6 +324,84 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
         />
       </div>
 
+      {/* Annotations (pinned to the altitude chart) */}
+      {flightId !== undefined && (
+        <div className="space-y-1.5">
+          {annotations.length > 0 && (
+            <div className="flex flex-wrap gap-1.5">
+              {annotations.map((annotation) => (
+                <span
+                  key={annotation.id}
+                  className="inline-flex items-center gap-1 text-xs rounded px-2 py-0.5 bg-amber-500/10 text-amber-300 border border-amber-500/30"
+                >
+                  <span className="font-mono">{formatOffset(annotation.offsetSecs)}</span>
+                  <span>{annotation.text}</span>
+                  <button
+                    onClick={() => removeAnnotation(flightId, annotation.id)}
+                    className="ml-0.5 text-amber-300/70 hover:text-red-400"
+                    title="Delete annotation"
+                  >
+                    ×
+                  </button>
+                </span>
+              ))}
+            </div>
+          )}
+          <div className="flex gap-1.5">
+            <input
+              type="text"
+              value={annotationTime}
+              onChange={(e) => setAnnotationTime(e.target.value)}
+              placeholder={formatOffset(replayOffsetSecs)}
+              className="input w-16 text-xs px-2 py-1 font-mono"
+              title="Time as mm:ss (defaults to the replay position)"
+            />
+            <input
+              type="text"
+              value={annotationText}
+              onChange={(e) => setAnnotationText(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') handleAddAnnotation();
+              }}
+              placeholder="Add a note at this moment…"
+              className="input flex-1 text-xs px-2 py-1"
+            />
+            <button
+              onClick={handleAddAnnotation}
+              disabled={!annotationText.trim()}
+              className="text-xs text-gray-400 hover:text-white border border-gray-700 rounded px-2 py-1 disabled:opacity-50"
+            >
+              Annotate
+            </button>
+          </div>
+        </div>
+      )}
+
+      {/* Flight timeline (also marked on the altitude chart) */}
+      {timeline.length > 0 && (
+        <div className="text-xs">
+          <button
+            onClick={() => setIsTimelineOpen((open) => !open)}
+            className="text-gray-400 hover:text-white"
+          >
+            {isTimelineOpen ? '▾' : '▸'} Flight timeline ({timeline.length})
+          </button>
+          {isTimelineOpen && (
+            <ol className="mt-1 max-h-48 overflow-auto space-y-0.5">
+              {timeline.map((event, i) => (
+                <li key={`${event.offsetSecs}-${i}`} className="flex gap-2">
+                  <span className="font-mono text-gray-500">{formatOffset(event.offsetSecs)}</span>
+                  <span style={{ color: TIMELINE_COLORS[event.kind] }}>
+                    {event.label}
+                    {event.durationSecs !== null && ` (${formatOffset(event.durationSecs)})`}
+                  </span>
+                </li>
+              ))}
+            </ol>
+          )}
+        </div>
+      )}
+
       {/* Battery Chart */}
       <div className="h-56">
         <ReactECharts
@@ -251,6 +413,19 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
         />
       </div>
 
+      {/* Cell Voltage Chart (only for logs with per-cell data) */}
+      {cellVoltages.length > 0 && (
+        <div className="h-56">
+          <ReactECharts
+            option={cellVoltageOption}
+            style={{ height: '100%', width: '100%' }}
+            opts={{ renderer: 'canvas' }}
+            notMerge={true}
+            onChartReady={registerChart}
+          />
+        </div>
+      )}
+
       {/* Attitude Chart */}
       <div className="h-60">
         <ReactECharts
@@ -273,6 +448,58 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
         />
       </div>
 
+      {/* RC Stick Inputs Chart (only for logs that record stick positions) */}
+      {hasRcSticks && (
+        <div className="h-56">
+          <ReactECharts
+            option={rcSticksOption}
+            style={{ height: '100%', width: '100%' }}
+            opts={{ renderer: 'canvas' }}
+            notMerge={true}
+            onChartReady={registerChart}
+          />
+        </div>
+      )}
+
+      {/* Compass & IMU Health Chart (only when the log reports sensor state) */}
+      {hasSensorHealth && (
+        <div className="h-48">
+          <ReactECharts
+            option={sensorHealthOption}
+            style={{ height: '100%', width: '100%' }}
+            opts={{ renderer: 'canvas' }}
+            notMerge={true}
+            onChartReady={registerChart}
+          />
+        </div>
+      )}
+
+      {/* Wind Chart (logged wind, or estimated from the attitude) */}
+      {hasWind && (
+        <div className="h-48">
+          <ReactECharts
+            option={windOption}
+            style={{ height: '100%', width: '100%' }}
+            opts={{ renderer: 'canvas' }}
+            notMerge={true}
+            onChartReady={registerChart}
+          />
+        </div>
+      )}
+
+      {/* Motor RPM & ESC Temperature Chart (DAT logs only) */}
+      {motors.length > 0 && (
+        <div className="h-60">
+          <ReactECharts
+            option={motorOption}
+            style={{ height: '100%', width: '100%' }}
+            opts={{ renderer: 'canvas' }}
+            notMerge={true}
+            onChartReady={registerChart}
+          />
+        </div>
+      )}
+
       {/* Distance to Home Chart */}
       <div className="h-48">
         <ReactECharts
@@ -433,7 +660,10 @@ function createAltitudeSpeedChart(
   unitSystem: UnitSystem,
   splitLineColor: string,
   tooltipFormatter: TooltipFormatter,
-  tooltipColors: TooltipColors
+  tooltipColors: TooltipColors,
+  annotations: FlightAnnotation[] = [],
+  timeline: TimelineEvent[] = [],
+  phases: FlightPhase[] = []
 ): EChartsOption {
   const hasHeight = data.height.some((val) => val !== null);
   const fallbackHeight = data.altitude ?? [];
@@ -446,6 +676,10 @@ function createAltitudeSpeedChart(
     unitSystem === 'imperial'
       ? data.vpsHeight.map((val) => (val === null ? null : val * 3.28084))
       : data.vpsHeight;
+  const aglSeries = (data.agl ?? []).map((val) =>
+    val === null || val === undefined ? null : unitSystem === 'imperial' ? val * 3.28084 : val
+  );
+  const hasAgl = aglSeries.some((val) => val !== null);
   const speedSeries =
     unitSystem === 'imperial'
       ? data.speed.map((val) => (val === null ? null : val * 2.236936))
@@ -455,6 +689,7 @@ function createAltitudeSpeedChart(
   const heightRange = computeRange([
     ...heightSeries,
     ...vpsHeightSeries,
+    ...aglSeries,
   ]);
   const speedRange = computeRange(speedSeries);
 
@@ -469,7 +704,7 @@ function createAltitudeSpeedChart(
     },
     legend: {
       ...baseChartConfig.legend,
-      data: ['Height', 'VPS Height', 'Speed'],
+      data: hasAgl ? ['Height', 'VPS Height', 'Above Ground', 'Speed'] : ['Height', 'VPS Height', 'Speed'],
     },
     xAxis: {
       ...createTimeAxis(data.time),
@@ -526,6 +761,20 @@ function createAltitudeSpeedChart(
         yAxisIndex: 0,
         smooth: true,
         symbol: 'none',
+        markLine: {
+          silent: true,
+          symbol: 'none',
+          lineStyle: { color: '#f59e0b', type: 'dashed', width: 1 },
+          label: { color: '#f59e0b', fontSize: 10, formatter: '{b}' },
+          data: [
+            ...annotationMarkLines(data.time, annotations),
+            ...timelineMarkLines(data.time, timeline),
+          ] as any,
+        },
+        markArea: {
+          silent: true,
+          data: phaseMarkAreas(phases) as any,
+        },
         itemStyle: {
           color: '#00A0DC',
         },
@@ -562,6 +811,26 @@ function createAltitudeSpeedChart(
           width: 1.5,
         },
       },
+      ...(hasAgl
+        ? [
+            {
+              name: 'Above Ground',
+              type: 'line' as const,
+              data: aglSeries,
+              yAxisIndex: 0,
+              smooth: true,
+              symbol: 'none',
+              itemStyle: {
+                color: '#a855f7',
+              },
+              lineStyle: {
+                color: '#a855f7',
+                width: 1.5,
+                type: 'dashed' as const,
+              },
+            },
+          ]
+        : []),
       {
         name: 'Speed',
         type: 'line',
@@ -733,6 +1002,211 @@ function createBatteryChart(
   };
 }
 
+const CELL_COLORS = ['#22c55e', '#3b82f6', '#eab308', '#f97316', '#a855f7', '#ec4899'];
+
+/** Per-cell voltages (left axis) with the spread between the highest and
+ * lowest cell in mV (right axis). A growing spread is the earliest sign of a
+ * failing cell. */
+function createCellVoltageChart(
+  data: TelemetryData,
+  splitLineColor: string,
+  tooltipFormatter: TooltipFormatter,
+  tooltipColors: TooltipColors
+): EChartsOption {
+  const cells = data.cellVoltages ?? [];
+  const cellNames = cells.map((_, i) => `Cell ${i + 1}`);
+  const deviation = data.time.map((_, index) => {
+    const values = cells
+      .map((cell) => cell[index])
+      .filter((v): v is number => v !== null && v !== undefined);
+    if (values.length < 2) return null;
+    return Math.round((Math.max(...values) - Math.min(...values)) * 1000);
+  });
+  const voltageRange = computeRange(cells.flat());
+
+  return {
+    ...baseChartConfig,
+    tooltip: {
+      ...baseChartConfig.tooltip,
+      backgroundColor: tooltipColors.background,
+      borderColor: tooltipColors.border,
+      textStyle: { color: tooltipColors.text },
+      formatter: tooltipFormatter,
+    },
+    legend: {
+      ...baseChartConfig.legend,
+      data: [...cellNames, 'Cell Spread'],
+    },
+    xAxis: {
+      ...createTimeAxis(data.time),
+    },
+    yAxis: [
+      {
+        type: 'value',
+        name: 'Cell (V)',
+        min: voltageRange.min,
+        max: voltageRange.max,
+        axisLine: {
+          lineStyle: {
+            color: '#22c55e',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          lineStyle: {
+            color: splitLineColor,
+          },
+        },
+      },
+      {
+        type: 'value',
+        name: 'Spread (mV)',
+        min: 0,
+        axisLine: {
+          lineStyle: {
+            color: '#ef4444',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          show: false,
+        },
+      },
+    ],
+    series: [
+      ...cells.map((cell, i) => ({
+        name: cellNames[i],
+        type: 'line' as const,
+        data: cell,
+        symbol: 'none',
+        yAxisIndex: 0,
+        itemStyle: { color: CELL_COLORS[i % CELL_COLORS.length] },
+        lineStyle: { color: CELL_COLORS[i % CELL_COLORS.length], width: 1.2 },
+      })),
+      {
+        name: 'Cell Spread',
+        type: 'line' as const,
+        data: deviation,
+        symbol: 'none',
+        yAxisIndex: 1,
+        itemStyle: { color: '#ef4444' },
+        lineStyle: { color: '#ef4444', width: 1, type: 'dashed' as const },
+      },
+    ],
+  };
+}
+
+/** Compass interference (left axis) and the compass error flag (right axis).
+ * Periods where the IMU reports a problem or is still warming up are shaded
+ * and labelled with the reported state. */
+function createSensorHealthChart(
+  data: TelemetryData,
+  splitLineColor: string,
+  tooltipFormatter: TooltipFormatter,
+  tooltipColors: TooltipColors
+): EChartsOption {
+  const labels = data.time.map((t) => t.toFixed(1));
+  const imuStatus = data.imuStatus ?? [];
+  const imuAreas: { xAxis: string; name?: string }[][] = [];
+  let start = -1;
+  for (let i = 0; i <= imuStatus.length; i++) {
+    const status = imuStatus[i] ?? null;
+    const previous = i > 0 ? imuStatus[i - 1] ?? null : null;
+    if (status === previous) continue;
+    if (previous !== null && start >= 0) {
+      imuAreas.push([{ xAxis: labels[start], name: `IMU: ${previous}` }, { xAxis: labels[i - 1] }]);
+    }
+    start = status !== null ? i : -1;
+  }
+
+  return {
+    ...baseChartConfig,
+    tooltip: {
+      ...baseChartConfig.tooltip,
+      backgroundColor: tooltipColors.background,
+      borderColor: tooltipColors.border,
+      textStyle: { color: tooltipColors.text },
+      formatter: tooltipFormatter,
+    },
+    legend: {
+      ...baseChartConfig.legend,
+      data: ['Compass Interference', 'Compass Error'],
+    },
+    xAxis: {
+      ...createTimeAxis(data.time),
+    },
+    yAxis: [
+      {
+        type: 'value',
+        name: 'Interference (%)',
+        min: 0,
+        axisLine: {
+          lineStyle: {
+            color: '#f97316',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          lineStyle: {
+            color: splitLineColor,
+          },
+        },
+      },
+      {
+        type: 'value',
+        name: 'Error',
+        min: 0,
+        max: 1,
+        interval: 1,
+        axisLine: {
+          lineStyle: {
+            color: '#ef4444',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          show: false,
+        },
+      },
+    ],
+    series: [
+      {
+        name: 'Compass Interference',
+        type: 'line',
+        data: data.compassInterference ?? [],
+        symbol: 'none',
+        yAxisIndex: 0,
+        itemStyle: { color: '#f97316' },
+        lineStyle: { color: '#f97316', width: 1.5 },
+        markArea: {
+          silent: true,
+          itemStyle: { color: 'rgba(168, 85, 247, 0.15)' },
+          label: { color: '#c084fc', fontSize: 10 },
+          data: imuAreas as any,
+        },
+      },
+      {
+        name: 'Compass Error',
+        type: 'line',
+        step: 'end',
+        data: (data.compassError ?? []).map((v) => (v === null || v === undefined ? null : v ? 1 : 0)),
+        symbol: 'none',
+        yAxisIndex: 1,
+        itemStyle: { color: '#ef4444' },
+        lineStyle: { color: '#ef4444', width: 1.5 },
+      },
+    ],
+  };
+}
+
 function createAttitudeChart(
   data: TelemetryData,
   splitLineColor: string,
@@ -840,6 +1314,8 @@ function createRcSignalChart(
   const hasUplink = rcUplink.some((val) => val !== null && val !== undefined);
   const hasDownlink = rcDownlink.some((val) => val !== null && val !== undefined);
   const showCombined = !hasUplink && !hasDownlink;
+  // Shade stretches where a link dropped out, to line up with position and terrain
+  const dropouts = signalDropouts(data.time, showCombined ? [data.rcSignal] : [rcUplink, rcDownlink]);
   const series: LineSeriesOption[] = [
     ...(showCombined
       ? [
@@ -849,6 +1325,11 @@ function createRcSignalChart(
             data: data.rcSignal,
             smooth: true,
             symbol: 'none',
+            markArea: {
+              silent: true,
+              itemStyle: { color: 'rgba(239, 68, 68, 0.18)' },
+              data: dropouts as any,
+            },
             itemStyle: {
               color: '#22c55e',
             },
@@ -865,6 +1346,11 @@ function createRcSignalChart(
             data: rcUplink,
             smooth: true,
             symbol: 'none',
+            markArea: {
+              silent: true,
+              itemStyle: { color: 'rgba(239, 68, 68, 0.18)' },
+              data: dropouts as any,
+            },
             itemStyle: {
               color: '#22c55e',
             },
@@ -874,7 +1360,7 @@ function createRcSignalChart(
             },
           },
           {
-            name: 'RC Downlink',
+            name: 'Video Downlink',
             type: 'line' as const,
             data: rcDownlink,
             smooth: true,
@@ -900,7 +1386,7 @@ function createRcSignalChart(
     },
     legend: {
       ...baseChartConfig.legend,
-      data: showCombined ? ['RC Signal'] : ['RC Uplink', 'RC Downlink'],
+      data: showCombined ? ['RC Signal'] : ['RC Uplink', 'Video Downlink'],
     },
     xAxis: {
       ...createTimeAxis(data.time),
@@ -930,21 +1416,33 @@ function createRcSignalChart(
   };
 }
 
-function createDistanceToHomeChart(
+/** Time ranges where any of the signal series reads 0 */
+function signalDropouts(time: number[], signals: (number | null | undefined)[][]) {
+  const ranges: [{ xAxis: string }, { xAxis: string }][] = [];
+  let start: number | null = null;
+  for (let i = 0; i < time.length; i++) {
+    const lost = signals.some((signal) => signal[i] === 0);
+    if (lost && start === null) start = i;
+    if (start !== null && (!lost || i === time.length - 1)) {
+      ranges.push([{ xAxis: time[start].toFixed(1) }, { xAxis: time[i].toFixed(1) }]);
+      start = null;
+    }
+  }
+  return ranges;
+}
+
+function createRcSticksChart(
   data: TelemetryData,
-  unitSystem: UnitSystem,
   splitLineColor: string,
   tooltipFormatter: TooltipFormatter,
   tooltipColors: TooltipColors
 ): EChartsOption {
-  const distances = computeDistanceToHomeSeries(data);
-  const distanceSeries =
-    unitSystem === 'imperial'
-      ? distances.map((val) => (val === null ? null : val * 3.28084))
-      : distances;
-  const distanceUnit = unitSystem === 'imperial' ? 'ft' : 'm';
-  const distanceRange = computeRange(distanceSeries, { clampMin: 0 });
-
+  const sticks: Array<{ name: string; values: (number | null)[] | undefined; color: string }> = [
+    { name: 'Aileron', values: data.rcAileron, color: '#f97316' },
+    { name: 'Elevator', values: data.rcElevator, color: '#3b82f6' },
+    { name: 'Throttle', values: data.rcThrottle, color: '#eab308' },
+    { name: 'Rudder', values: data.rcRudder, color: '#a855f7' },
+  ];
   return {
     ...baseChartConfig,
     tooltip: {
@@ -956,19 +1454,20 @@ function createDistanceToHomeChart(
     },
     legend: {
       ...baseChartConfig.legend,
-      data: ['Distance to Home'],
+      data: sticks.map((stick) => stick.name),
     },
     xAxis: {
       ...createTimeAxis(data.time),
     },
     yAxis: {
       type: 'value',
-      name: `Distance (${distanceUnit})`,
-      min: distanceRange.min,
-      max: distanceRange.max,
+      name: 'Sticks (%)',
+      min: -100,
+      max: 100,
+      interval: 50,
       axisLine: {
         lineStyle: {
-          color: '#22c55e',
+          color: '#f97316',
         },
       },
       axisLabel: {
@@ -980,6 +1479,192 @@ function createDistanceToHomeChart(
         },
       },
     },
+    series: sticks.map((stick) => ({
+      name: stick.name,
+      type: 'line' as const,
+      data: stick.values ?? [],
+      symbol: 'none',
+      itemStyle: {
+        color: stick.color,
+      },
+      lineStyle: {
+        color: stick.color,
+        width: 1.2,
+      },
+    })),
+  };
+}
+
+const MOTOR_COLORS = ['#ef4444', '#f59e0b', '#10b981', '#3b82f6', '#a855f7', '#ec4899', '#14b8a6', '#84cc16'];
+
+/** RPM per motor (solid, left axis) with ESC temperature (dashed, right axis).
+ * A motor running hotter or slower than its siblings stands out immediately. */
+function createMotorChart(
+  data: TelemetryData,
+  motors: MotorSeries[],
+  splitLineColor: string,
+  tooltipFormatter: TooltipFormatter,
+  tooltipColors: TooltipColors
+): EChartsOption {
+  const rpmNames = motors.map((m) => `M${m.motorIndex + 1} RPM`);
+  const tempNames = motors.map((m) => `M${m.motorIndex + 1} Temp`);
+  const series: LineSeriesOption[] = motors.flatMap((motor, i) => {
+    const color = MOTOR_COLORS[i % MOTOR_COLORS.length];
+    return [
+      {
+        name: rpmNames[i],
+        type: 'line' as const,
+        data: motor.rpm,
+        symbol: 'none',
+        yAxisIndex: 0,
+        itemStyle: { color },
+        lineStyle: { color, width: 1.2 },
+      },
+      {
+        name: tempNames[i],
+        type: 'line' as const,
+        data: motor.temperature,
+        symbol: 'none',
+        yAxisIndex: 1,
+        itemStyle: { color },
+        lineStyle: { color, width: 1, type: 'dashed' as const },
+      },
+    ];
+  });
+
+  return {
+    ...baseChartConfig,
+    tooltip: {
+      ...baseChartConfig.tooltip,
+      backgroundColor: tooltipColors.background,
+      borderColor: tooltipColors.border,
+      textStyle: { color: tooltipColors.text },
+      formatter: tooltipFormatter,
+    },
+    legend: {
+      ...baseChartConfig.legend,
+      data: [...rpmNames, ...tempNames],
+    },
+    xAxis: {
+      ...createTimeAxis(data.time),
+    },
+    yAxis: [
+      {
+        type: 'value',
+        name: 'RPM',
+        min: 0,
+        axisLine: {
+          lineStyle: {
+            color: '#ef4444',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          lineStyle: {
+            color: splitLineColor,
+          },
+        },
+      },
+      {
+        type: 'value',
+        name: 'ESC °C',
+        axisLine: {
+          lineStyle: {
+            color: '#f59e0b',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          show: false,
+        },
+      },
+    ],
+    series,
+  };
+}
+
+function createDistanceToHomeChart(
+  data: TelemetryData,
+  unitSystem: UnitSystem,
+  splitLineColor: string,
+  tooltipFormatter: TooltipFormatter,
+  tooltipColors: TooltipColors
+): EChartsOption {
+  // The backend's series follows mid-flight home point updates
+  const distances = data.distanceFromHome ?? computeDistanceToHomeSeries(data);
+  const toUnit = (values: Array<number | null>) =>
+    unitSystem === 'imperial' ? values.map((val) => (val === null ? null : val * 3.28084)) : values;
+  const distanceSeries = toUnit(distances);
+  const distance3dSeries = toUnit(data.distanceFromHome3d ?? []);
+  const distanceUnit = unitSystem === 'imperial' ? 'ft' : 'm';
+  const distanceRange = computeRange([...distanceSeries, ...distance3dSeries], { clampMin: 0 });
+  // Path flown so far, in km or mi as it quickly outgrows the distance to home
+  const flownSeries = (data.cumulativeDistance ?? []).map((val) =>
+    unitSystem === 'imperial' ? val / 1609.344 : val / 1000
+  );
+  const flownUnit = unitSystem === 'imperial' ? 'mi' : 'km';
+
+  return {
+    ...baseChartConfig,
+    tooltip: {
+      ...baseChartConfig.tooltip,
+      backgroundColor: tooltipColors.background,
+      borderColor: tooltipColors.border,
+      textStyle: { color: tooltipColors.text },
+      formatter: tooltipFormatter,
+    },
+    legend: {
+      ...baseChartConfig.legend,
+      data: [
+        'Distance to Home',
+        ...(distance3dSeries.length > 0 ? ['Distance to Home (3D)'] : []),
+        ...(flownSeries.length > 0 ? ['Distance Flown'] : []),
+      ],
+    },
+    xAxis: {
+      ...createTimeAxis(data.time),
+    },
+    yAxis: [
+      {
+        type: 'value',
+        name: `Distance (${distanceUnit})`,
+        min: distanceRange.min,
+        max: distanceRange.max,
+        axisLine: {
+          lineStyle: {
+            color: '#22c55e',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          lineStyle: {
+            color: splitLineColor,
+          },
+        },
+      },
+      {
+        type: 'value',
+        name: `Flown (${flownUnit})`,
+        min: 0,
+        axisLine: {
+          lineStyle: {
+            color: '#a855f7',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          show: false,
+        },
+      },
+    ],
     series: [
       {
         name: 'Distance to Home',
@@ -995,6 +1680,133 @@ function createDistanceToHomeChart(
           width: 1.5,
         },
       },
+      {
+        name: 'Distance to Home (3D)',
+        type: 'line',
+        data: distance3dSeries,
+        smooth: true,
+        symbol: 'none',
+        itemStyle: {
+          color: '#14b8a6',
+        },
+        lineStyle: {
+          color: '#14b8a6',
+          width: 1.5,
+          type: 'dashed',
+        },
+      },
+      {
+        name: 'Distance Flown',
+        type: 'line',
+        data: flownSeries,
+        yAxisIndex: 1,
+        smooth: true,
+        symbol: 'none',
+        itemStyle: {
+          color: '#a855f7',
+        },
+        lineStyle: {
+          color: '#a855f7',
+          width: 1.5,
+        },
+      },
+    ],
+  };
+}
+
+/**
+ * Wind speed (left axis) and the direction it blows from (right axis, dots),
+ * as logged or, for logs without wind, as estimated from the attitude
+ */
+function createWindChart(
+  data: TelemetryData,
+  unitSystem: UnitSystem,
+  splitLineColor: string,
+  tooltipFormatter: TooltipFormatter,
+  tooltipColors: TooltipColors
+): EChartsOption {
+  const speedSeriesFactor = unitSystem === 'imperial' ? 2.236936 : 3.6;
+  const speedUnit = unitSystem === 'imperial' ? 'mph' : 'km/h';
+  const estimated = !(data.windSpeed ?? []).some((val) => val !== null && val !== undefined);
+  const speedName = estimated ? 'Wind Speed (est.)' : 'Wind Speed';
+  const directionName = estimated ? 'Wind Direction (est.)' : 'Wind Direction';
+  const windSpeed = ((estimated ? data.estimatedWindSpeed : data.windSpeed) ?? []).map((val) =>
+    val === null || val === undefined ? null : val * speedSeriesFactor
+  );
+
+  return {
+    ...baseChartConfig,
+    tooltip: {
+      ...baseChartConfig.tooltip,
+      backgroundColor: tooltipColors.background,
+      borderColor: tooltipColors.border,
+      textStyle: { color: tooltipColors.text },
+      formatter: tooltipFormatter,
+    },
+    legend: {
+      ...baseChartConfig.legend,
+      data: [speedName, directionName],
+    },
+    xAxis: {
+      ...createTimeAxis(data.time),
+    },
+    yAxis: [
+      {
+        type: 'value',
+        name: `Wind (${speedUnit})`,
+        min: 0,
+        axisLine: {
+          lineStyle: {
+            color: '#06b6d4',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          lineStyle: {
+            color: splitLineColor,
+          },
+        },
+      },
+      {
+        type: 'value',
+        name: 'Direction (°)',
+        min: 0,
+        max: 360,
+        interval: 90,
+        axisLine: {
+          lineStyle: {
+            color: '#a3a3a3',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          show: false,
+        },
+      },
+    ],
+    series: [
+      {
+        name: speedName,
+        type: 'line',
+        data: windSpeed,
+        smooth: true,
+        symbol: 'none',
+        yAxisIndex: 0,
+        itemStyle: { color: '#06b6d4' },
+        lineStyle: { color: '#06b6d4', width: 1.5 },
+      },
+      {
+        name: directionName,
+        type: 'scatter',
+        data: (estimated ? data.estimatedWindDirection : data.windDirection) ?? [],
+        symbolSize: 3,
+        yAxisIndex: 1,
+        itemStyle: { color: '#a3a3a3' },
+      },
     ],
   };
 }
@@ -1013,8 +1825,11 @@ function createVelocityChart(
   const xSeries = velocityX.map((val) => (val === null || val === undefined ? null : val * speedSeriesFactor));
   const ySeries = velocityY.map((val) => (val === null || val === undefined ? null : val * speedSeriesFactor));
   const zSeries = velocityZ.map((val) => (val === null || val === undefined ? null : val * speedSeriesFactor));
+  const climbSeries = (data.verticalSpeed ?? []).map((val) =>
+    val === null || val === undefined ? null : val * speedSeriesFactor
+  );
   const speedUnit = unitSystem === 'imperial' ? 'mph' : 'km/h';
-  const speedRange = computeRange([...xSeries, ...ySeries, ...zSeries]);
+  const speedRange = computeRange([...xSeries, ...ySeries, ...zSeries, ...climbSeries]);
 
   return {
     ...baseChartConfig,
@@ -1027,7 +1842,7 @@ function createVelocityChart(
     },
     legend: {
       ...baseChartConfig.legend,
-      data: ['X Speed', 'Y Speed', 'Z Speed'],
+      data: ['X Speed', 'Y Speed', 'Z Speed', 'Climb Rate'],
     },
     xAxis: {
       ...createTimeAxis(data.time),
@@ -1094,6 +1909,21 @@ function createVelocityChart(
           width: 1.5,
         },
       },
+      {
+        name: 'Climb Rate',
+        type: 'line',
+        data: climbSeries,
+        smooth: true,
+        symbol: 'none',
+        itemStyle: {
+          color: '#22c55e',
+        },
+        lineStyle: {
+          color: '#22c55e',
+          width: 1.5,
+          type: 'dashed',
+        },
+      },
     ],
   };
 }
@@ -1333,6 +2163,81 @@ function formatNumericValue(value: number): string {
   return value.toFixed(2).replace(/\.00$/, '');
 }
 
+/** Vertical marker per annotation, snapped to the nearest time label */
+function annotationMarkLines(time: number[], annotations: FlightAnnotation[]) {
+  if (time.length === 0) return [];
+  return annotations.map((annotation) => {
+    let nearest = 0;
+    for (let i = 1; i < time.length; i++) {
+      if (Math.abs(time[i] - annotation.offsetSecs) < Math.abs(time[nearest] - annotation.offsetSecs)) {
+        nearest = i;
+      }
+    }
+    return { xAxis: time[nearest].toFixed(1), name: annotation.text };
+  });
+}
+
+/** Marker color per timeline event kind */
+const TIMELINE_COLORS: Record<TimelineEvent['kind'], string> = {
+  takeoff: '#00D4AA',
+  landing: '#00D4AA',
+  mode: '#06b6d4',
+  rth: '#f97316',
+  photo: '#a855f7',
+  video: '#a855f7',
+  warning: '#ef4444',
+};
+
+/** Vertical marker per timeline event; photos are left to the list, they can run into the hundreds */
+function timelineMarkLines(time: number[], timeline: TimelineEvent[]) {
+  if (time.length === 0) return [];
+  return timeline
+    .filter((event) => event.kind !== 'photo')
+    .map((event) => {
+      let nearest = 0;
+      for (let i = 1; i < time.length; i++) {
+        if (Math.abs(time[i] - event.offsetSecs) < Math.abs(time[nearest] - event.offsetSecs)) {
+          nearest = i;
+        }
+      }
+      const color = TIMELINE_COLORS[event.kind];
+      return {
+        xAxis: time[nearest].toFixed(1),
+        name: event.kind === 'mode' ? event.detail ?? event.label : event.label,
+        lineStyle: { color, type: 'dotted', width: 1 },
+        label: { color },
+      };
+    });
+}
+
+/** Shaded background per flight phase; time on the ground is left clear */
+function phaseMarkAreas(phases: FlightPhase[]) {
+  return phases
+    .filter((p) => p.phase !== 'ground')
+    .map((p) => [
+      {
+        xAxis: p.startSecs.toFixed(1),
+        itemStyle: { color: flightPhaseColor(p.phase), opacity: 0.08 },
+      },
+      { xAxis: p.endSecs.toFixed(1) },
+    ]);
+}
+
+/** Format seconds from flight start as m:ss */
+function formatOffset(secs: number): string {
+  const total = Math.max(0, Math.round(secs));
+  return `${Math.floor(total / 60)}:${String(total % 60).padStart(2, '0')}`;
+}
+
+/** Parse "m:ss" or plain seconds; null when empty or invalid */
+function parseOffset(value: string): number | null {
+  const trimmed = value.trim();
+  if (!trimmed) return null;
+  const parts = trimmed.split(':').map(Number);
+  if (parts.some((p) => Number.isNaN(p))) return null;
+  return parts.reduce((acc, p) => acc * 60 + p, 0);
+}
+
 function createTimeAxis(time: number[]): EChartsOption['xAxis'] {
   const values = time.map((t) => t.toFixed(1));
   return {
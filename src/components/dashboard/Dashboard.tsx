@@ -475,6 +475,11 @@ export function Dashboard() {
                       data={currentFlightData!.telemetry}
                       unitSystem={unitSystem}
                       startTime={currentFlightData!.flight.startTime}
+                      motors={currentFlightData!.motors}
+                      flightId={currentFlightData!.flight.id}
+                      annotations={currentFlightData!.annotations}
+                      timeline={currentFlightData!.timeline}
+                      phases={currentFlightData!.phases}
                     />
                   </div>
                 </div>
@@ -498,6 +503,7 @@ export function Dashboard() {
                     track={currentFlightData!.track}
                     homeLat={currentFlightData!.flight.homeLat}
                     homeLon={currentFlightData!.flight.homeLon}
+                    homePoints={currentFlightData!.homePoints}
                     durationSecs={currentFlightData!.flight.durationSecs}
                     telemetry={currentFlightData!.telemetry}
                     themeMode={themeMode}
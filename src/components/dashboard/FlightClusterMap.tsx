@@ -316,7 +316,7 @@ export function FlightClusterMap({
           totalDistance: f.totalDistance ?? 0,
           maxAltitude: f.maxAltitude ?? 0,
           droneModel: f.droneModel ?? '',
-          aircraftName: f.aircraftName ?? '',
+          aircraftName: f.droneNickname ?? f.aircraftName ?? '',
         },
       }));
 
@@ -630,10 +630,10 @@ export function FlightClusterMap({
                   <p className="text-[13px] font-semibold truncate leading-tight" style={{ color: '#ffffff' }}>
                     {(() => { const name = popupInfo.flight.displayName || popupInfo.flight.fileName; return name.length > 20 ? name.slice(0, 20) + '…' : name; })()}
                   </p>
-                  {(popupInfo.flight.aircraftName || popupInfo.flight.droneModel) && (
+                  {(popupInfo.flight.droneNickname || popupInfo.flight.aircraftName || popupInfo.flight.droneModel) && (
                     <p className="text-[11px] truncate mt-0.5" style={{ color: '#c7d2fe' }}>
-                      {popupInfo.flight.aircraftName || popupInfo.flight.droneModel}
-                      {popupInfo.flight.aircraftName && popupInfo.flight.droneModel
+                      {popupInfo.flight.droneNickname || popupInfo.flight.aircraftName || popupInfo.flight.droneModel}
+                      {(popupInfo.flight.droneNickname || popupInfo.flight.aircraftName) && popupInfo.flight.droneModel
                         ? ` · ${popupInfo.flight.droneModel}`
                         : ''}
                     </p>
@@ -13,12 +13,26 @@
 
 import { useCallback, useState, useEffect, useRef } from 'react';
 import { useDropzone } from 'react-dropzone';
-import { isWebMode, pickFiles, computeFileHash, getFlights, getSyncConfig, getSyncFiles, syncSingleFile } from '@/lib/api';
+import {
+  isWebMode,
+  pickFiles,
+  computeFileHash,
+  importLogsBatch,
+  getFlights,
+  getSyncConfig,
+  getSyncFiles,
+  syncSingleFile,
+  getImportBlacklist,
+  addToImportBlacklist,
+  removeFromImportBlacklist,
+  clearImportBlacklist,
+} from '@/lib/api';
+import type { ImportResult } from '@/types';
 import { useFlightStore } from '@/stores/flightStore';
 
-// Storage keys for sync folder, blacklist, and autoscan
+// Storage keys for sync folder, legacy blacklist, and autoscan
 const SYNC_FOLDER_KEY = 'syncFolderPath';
-const BLACKLIST_KEY = 'importBlacklist';
+const LEGACY_BLACKLIST_KEY = 'importBlacklist';
 const AUTOSCAN_KEY = 'autoscanEnabled';
 
 // Get autoscan enabled setting from localStorage
@@ -50,43 +64,47 @@ export function setSyncFolderPath(path: string | null): void {
   }
 }
 
-// Get blacklisted file hashes (used when deleting flights)
-export function getBlacklist(): Set<string> {
-  if (typeof localStorage === 'undefined') return new Set();
+// Move a blacklist left in localStorage by older versions to the backend,
+// which now keeps it so the folder watcher and server sync can honour it
+async function migrateLegacyBlacklist(): Promise<void> {
+  if (typeof localStorage === 'undefined') return;
+  const stored = localStorage.getItem(LEGACY_BLACKLIST_KEY);
+  if (!stored) return;
+  let hashes: unknown = [];
   try {
-    const stored = localStorage.getItem(BLACKLIST_KEY);
-    return stored ? new Set(JSON.parse(stored)) : new Set();
+    hashes = JSON.parse(stored);
   } catch {
-    return new Set();
+    // Unreadable, nothing to keep
   }
+  if (Array.isArray(hashes) && hashes.length > 0) {
+    await addToImportBlacklist(hashes.filter((h): h is string => typeof h === 'string'));
+  }
+  localStorage.removeItem(LEGACY_BLACKLIST_KEY);
 }
 
-// Add hash to blacklist (called when deleting a flight)
-export function addToBlacklist(hash: string): void {
-  if (typeof localStorage === 'undefined' || !hash) return;
-  const blacklist = getBlacklist();
-  blacklist.add(hash);
-  localStorage.setItem(BLACKLIST_KEY, JSON.stringify([...blacklist]));
+// Get blacklisted file hashes (files of deleted flights, skipped during sync)
+export async function getBlacklist(): Promise<Set<string>> {
+  try {
+    await migrateLegacyBlacklist();
+    return new Set(await getImportBlacklist());
+  } catch {
+    return new Set();
+  }
 }
 
 // Remove hash from blacklist (when manually importing)
-export function removeFromBlacklist(hash: string): void {
-  if (typeof localStorage === 'undefined' || !hash) return;
-  const blacklist = getBlacklist();
-  blacklist.delete(hash);
-  localStorage.setItem(BLACKLIST_KEY, JSON.stringify([...blacklist]));
+export async function removeFromBlacklist(hash: string): Promise<void> {
+  if (!hash) return;
+  try {
+    await removeFromImportBlacklist(hash);
+  } catch (err) {
+    console.warn(`Failed to update import blacklist: ${err}`);
+  }
 }
 
 // Clear entire blacklist (e.g., when user wants to reset)
-export function clearBlacklist(): void {
-  if (typeof localStorage === 'undefined') return;
-  localStorage.removeItem(BLACKLIST_KEY);
-}
-
-// Check if a hash is blacklisted
-export function isBlacklisted(hash: string | null | undefined): boolean {
-  if (!hash) return false;
-  return getBlacklist().has(hash);
+export async function clearBlacklist(): Promise<void> {
+  await clearImportBlacklist();
 }
 
 export function FlightImporter() {
@@ -159,7 +177,7 @@ export function FlightImporter() {
     };
 
     // Get blacklist for sync mode (check before import to avoid wasted work)
-    const blacklist = !isManualImport ? getBlacklist() : new Set<string>();
+    const blacklist = !isManualImport ? await getBlacklist() : new Set<string>();
     
     // Helper to check if file is blacklisted (for sync mode only)
     // Returns hash if blacklisted, null otherwise
@@ -188,28 +206,9 @@ export function FlightImporter() {
       let blacklisted = 0;
       const REFRESH_INTERVAL = 2;
 
-      for (let index = 0; index < items.length; index += 1) {
-        const item = items[index];
-        setBatchIndex(index + 1);
-        const name =
-          typeof item === 'string'
-            ? getShortFileName(item)
-            : item.name.length <= 50
-            ? item.name
-            : `${item.name.slice(0, 50)}…`;
-        setCurrentFileName(name);
-
-        // For sync mode: check blacklist BEFORE importing (much faster than import+delete)
-        const blacklistedHash = await checkBlacklist(item);
-        if (blacklistedHash) {
-          blacklisted += 1;
-          continue;
-        }
-
-        // Import without refreshing flight list (skipRefresh = true)
-        const result = await importLog(item, true);
+      const countResult = (result: ImportResult) => {
         if (!result.success) {
-          if (result.message.toLowerCase().includes('already been imported')) {
+          if (/already been imported|in the trash/.test(result.message.toLowerCase())) {
             skipped += 1;
           } else if (result.message.toLowerCase().includes('duplicate flight')) {
             duplicates += 1;
@@ -223,8 +222,54 @@ export function FlightImporter() {
           if (isManualImport && result.fileHash) {
             removeFromBlacklist(result.fileHash);
           }
+        }
+      };
+
+      const paths = items.filter((item): item is string => typeof item === 'string');
+      if (!isWebMode() && paths.length === items.length) {
+        // Desktop: parse in parallel on the backend and follow its progress events
+        const toImport: string[] = [];
+        for (const path of paths) {
+          if (await checkBlacklist(path)) {
+            blacklisted += 1;
+          } else {
+            toImport.push(path);
+          }
+        }
+        try {
+          const results = await importLogsBatch(toImport, (progress) => {
+            setBatchIndex(progress.processed);
+            setCurrentFileName(progress.currentFile);
+          });
+          results.forEach(countResult);
+        } catch (err) {
+          console.warn(`Batch import failed: ${err}`);
+          invalidFiles += toImport.length;
+        }
+      } else {
+        for (let index = 0; index < items.length; index += 1) {
+          const item = items[index];
+          setBatchIndex(index + 1);
+          const name =
+            typeof item === 'string'
+              ? getShortFileName(item)
+              : item.name.length <= 50
+              ? item.name
+              : `${item.name.slice(0, 50)}…`;
+          setCurrentFileName(name);
+
+          // For sync mode: check blacklist BEFORE importing (much faster than import+delete)
+          const blacklistedHash = await checkBlacklist(item);
+          if (blacklistedHash) {
+            blacklisted += 1;
+            continue;
+          }
+
+          // Import without refreshing flight list (skipRefresh = true)
+          const result = await importLog(item, true);
+          countResult(result);
           // Refresh flight list periodically so user sees progress
-          if (processed % REFRESH_INTERVAL === 0) {
+          if (result.success && processed % REFRESH_INTERVAL === 0) {
             refreshFlightListBackground();
           }
         }
@@ -282,7 +327,7 @@ export function FlightImporter() {
         // Use skipRefresh=true to defer refresh until batch completes
         const result = await importLog(item, true);
         if (!result.success) {
-          if (result.message.toLowerCase().includes('already been imported')) {
+          if (/already been imported|in the trash/.test(result.message.toLowerCase())) {
             skipped += 1;
           } else if (result.message.toLowerCase().includes('duplicate flight')) {
             duplicates += 1;
@@ -349,7 +394,7 @@ export function FlightImporter() {
         filters: [
           {
             name: 'Drone Log Files',
-            extensions: ['txt', 'dat', 'log', 'csv'],
+            extensions: ['txt', 'dat', 'log', 'csv', 'pud', 'json', 'srt'],
           },
         ],
       });
@@ -416,7 +461,7 @@ export function FlightImporter() {
             const paths = event.payload.paths;
             // Filter to supported extensions
             const supported = paths.filter((p: string) =>
-              /\.(txt|dat|log|csv)$/i.test(p)
+              /\.(txt|dat|log|csv|pud|json|srt)$/i.test(p)
             );
             if (supported.length > 0) {
               // Cancel background sync - user action takes priority
@@ -437,6 +482,29 @@ export function FlightImporter() {
     };
   }, []);
 
+  // Refresh the flight list when the watched-folder service imports a file
+  useEffect(() => {
+    if (isWebMode()) return;
+
+    let unlisten: (() => void) | null = null;
+
+    (async () => {
+      try {
+        const { listen } = await import('@tauri-apps/api/event');
+        unlisten = await listen('watch-import', () => {
+          const { loadFlights, loadAllTags } = useFlightStore.getState();
+          loadFlights().then(() => loadAllTags());
+        });
+      } catch (e) {
+        console.warn('Tauri watch-import listener not available:', e);
+      }
+    })();
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
   // Background automatic sync on startup (lazy loaded, non-blocking)
   useEffect(() => {
     // Only run once
@@ -599,7 +667,7 @@ export function FlightImporter() {
         // Get existing file hashes to check for new files
         const existingFlights = await getFlights();
         const existingHashes = new Set(existingFlights.map(f => f.fileHash).filter(Boolean));
-        const blacklist = getBlacklist();
+        const blacklist = await getBlacklist();
         
         // Find truly new files (not already imported, not blacklisted)
         const newFiles: string[] = [];
@@ -16,9 +16,9 @@ import { isWebMode, downloadFile, downloadBlob } from '@/lib/api';
 import { useFlightStore } from '@/stores/flightStore';
 import { formatDuration, formatDateTime, formatDistance, formatAltitude, normalizeSerial } from '@/lib/utils';
 import { DayPicker, type DateRange } from 'react-day-picker';
-import type { FlightDataResponse, Flight, TelemetryData } from '@/types';
-import { addToBlacklist } from './FlightImporter';
+import type { ExportTemplate, ExportUnits, FlightDataResponse, FlightFilter, Flight, SmartList, TelemetryData } from '@/types';
 import { FlyCardGenerator } from './FlyCardGenerator';
+import { Select } from '@/components/ui/Select';
 import 'react-day-picker/dist/style.css';
 import JSZip from 'jszip';
 
@@ -121,6 +121,7 @@ export function FlightList({
     deleteFlight,
     updateFlightName,
     updateFlightNotes,
+    setFlightFavorite,
     unitSystem,
     themeMode,
     getBatteryDisplayName,
@@ -179,8 +180,11 @@ export function FlightList({
   const [distanceFilterMin, setDistanceFilterMin] = useState<number | null>(null);
   const [distanceFilterMax, setDistanceFilterMax] = useState<number | null>(null);
   const [searchQuery, setSearchQuery] = useState('');
+  const [favoritesOnly, setFavoritesOnly] = useState(false);
+  // Relevance score per flight ID from the backend search (null until results arrive)
+  const [searchScores, setSearchScores] = useState<Map<number, number> | null>(null);
   const [sortOption, setSortOption] = useState<
-    'name' | 'date' | 'duration' | 'distance'
+    'name' | 'date' | 'duration' | 'distance' | 'range'
   >('date');
   const [sortDirection, setSortDirection] = useState<'asc' | 'desc'>('desc');
   const [isSortOpen, setIsSortOpen] = useState(false);
@@ -194,6 +198,7 @@ export function FlightList({
   });
   const [isExportDropdownOpen, setIsExportDropdownOpen] = useState(false);
   const [exportHighlightedIndex, setExportHighlightedIndex] = useState(0);
+  const [exportTemplates, setExportTemplates] = useState<ExportTemplate[]>([]);
   const [tagHighlightedIndex, setTagHighlightedIndex] = useState(0);
   const [droneHighlightedIndex, setDroneHighlightedIndex] = useState(0);
   const [batteryHighlightedIndex, setBatteryHighlightedIndex] = useState(0);
@@ -215,6 +220,9 @@ export function FlightList({
   // FlyCard generator state
   const [flyCardFlightId, setFlyCardFlightId] = useState<number | null>(null);
   const [flyCardPending, setFlyCardPending] = useState<number | null>(null); // Flight ID waiting for map load
+  // Smart lists (saved filters)
+  const [smartLists, setSmartLists] = useState<SmartList[]>([]);
+  const [activeSmartListId, setActiveSmartListId] = useState<number | null>(null);
   // Notes modal state
   const [notesModalFlightId, setNotesModalFlightId] = useState<number | null>(null);
   const [notesInput, setNotesInput] = useState('');
@@ -349,6 +357,7 @@ export function FlightList({
       return;
     }
     exportDropdownRef.current?.focus();
+    api.getExportTemplates().then(setExportTemplates).catch(() => setExportTemplates([]));
   }, [isExportDropdownOpen]);
 
   // Tag dropdown keyboard navigation
@@ -365,7 +374,7 @@ export function FlightList({
         const serial = normalizeSerial(flight.droneSerial);
         // Use serial as unique key if available, otherwise fall back to model
         const key = serial || `model:${flight.droneModel ?? 'Unknown'}`;
-        const fallback = flight.aircraftName || flight.droneModel || 'Unknown';
+        const fallback = flight.droneNickname || flight.aircraftName || flight.droneModel || 'Unknown';
         const displayName = flight.droneSerial
           ? getDroneDisplayName(flight.droneSerial, fallback)
           : fallback;
@@ -452,6 +461,107 @@ export function FlightList({
     };
   }, [flights]);
 
+  // Ask the backend for ranked search results shortly after typing stops
+  useEffect(() => {
+    const query = searchQuery.trim();
+    if (!query) {
+      setSearchScores(null);
+      return;
+    }
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      api
+        .searchFlights(query)
+        .then((hits) => {
+          if (!cancelled) setSearchScores(new Map(hits.map((hit) => [hit.flightId, hit.score])));
+        })
+        .catch((err) => {
+          console.error('Flight search failed:', err);
+          if (!cancelled) setSearchScores(null);
+        });
+    }, 250);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [searchQuery, flights]);
+
+  useEffect(() => {
+    api.getSmartLists().then(setSmartLists).catch(() => setSmartLists([]));
+  }, []);
+
+  // Current filters as a smart list definition (the map area filter is not saved)
+  const currentFilter = (): FlightFilter => {
+    const toDay = (d: Date | undefined) =>
+      d ? `${d.getFullYear()}-${String(d.getMonth() + 1).padStart(2, '0')}-${String(d.getDate()).padStart(2, '0')}` : null;
+    return {
+      dateFrom: toDay(dateRange?.from),
+      dateTo: toDay(dateRange?.to),
+      drones: selectedDrones,
+      batteries: selectedBatteries,
+      tags: selectedTags,
+      minDurationMins: durationFilterMin,
+      maxDurationMins: durationFilterMax,
+      minAltitude: altitudeFilterMin,
+      maxAltitude: altitudeFilterMax,
+      minDistance: distanceFilterMin,
+      maxDistance: distanceFilterMax,
+      search: searchQuery.trim() || null,
+      favoritesOnly,
+      inverted: isFilterInverted,
+    };
+  };
+
+  const applySmartList = (list: SmartList) => {
+    const { filter } = list;
+    const fromDay = (day: string | null | undefined) => {
+      if (!day) return undefined;
+      const [y, m, d] = day.split('-').map(Number);
+      return new Date(y, m - 1, d);
+    };
+    const from = fromDay(filter.dateFrom);
+    const to = fromDay(filter.dateTo);
+    setDateRange(from || to ? { from, to } : undefined);
+    setSelectedDrones(filter.drones ?? []);
+    setSelectedBatteries(filter.batteries ?? []);
+    setSelectedTags(filter.tags ?? []);
+    setDurationFilterMin(filter.minDurationMins ?? null);
+    setDurationFilterMax(filter.maxDurationMins ?? null);
+    setAltitudeFilterMin(filter.minAltitude ?? null);
+    setAltitudeFilterMax(filter.maxAltitude ?? null);
+    setDistanceFilterMin(filter.minDistance ?? null);
+    setDistanceFilterMax(filter.maxDistance ?? null);
+    setSearchQuery(filter.search ?? '');
+    setFavoritesOnly(filter.favoritesOnly ?? false);
+    setIsFilterInverted(filter.inverted ?? false);
+    setMapAreaFilterEnabled(false);
+    setActiveSmartListId(list.id);
+  };
+
+  const handleSaveSmartList = async () => {
+    const active = smartLists.find((l) => l.id === activeSmartListId);
+    const name = window.prompt('Save the current filters as a smart list named:', active?.name ?? '');
+    if (!name?.trim()) return;
+    try {
+      const saved = await api.saveSmartList(name.trim(), currentFilter(), active?.name === name.trim() ? active.id : undefined);
+      setSmartLists(await api.getSmartLists());
+      setActiveSmartListId(saved.id);
+    } catch (err) {
+      console.error('Failed to save smart list:', err);
+    }
+  };
+
+  const handleDeleteSmartList = async () => {
+    if (activeSmartListId === null) return;
+    try {
+      await api.deleteSmartList(activeSmartListId);
+      setSmartLists((lists) => lists.filter((l) => l.id !== activeSmartListId));
+      setActiveSmartListId(null);
+    } catch (err) {
+      console.error('Failed to delete smart list:', err);
+    }
+  };
+
   const filteredFlights = useMemo(() => {
     // Always apply filters
     const start = dateRange?.from ?? null;
@@ -459,7 +569,7 @@ export function FlightList({
     if (end) end.setHours(23, 59, 59, 999);
     const normalizedSearch = searchQuery.trim().toLowerCase();
 
-    const hasAnyFilter = !!(start || end || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || (mapAreaFilterEnabled && mapVisibleBounds) || normalizedSearch);
+    const hasAnyFilter = !!(start || end || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || (mapAreaFilterEnabled && mapVisibleBounds) || normalizedSearch || favoritesOnly);
 
     return flights.filter((flight) => {
       // When no filters are active, show all
@@ -525,6 +635,9 @@ export function FlightList({
         if (isFilterInverted ? matchesTags : !matchesTags) return false;
       }
 
+      // Favorites filter (not affected by inversion - always AND)
+      if (favoritesOnly && !flight.favorite) return false;
+
       // Map area filter (not affected by inversion - always AND)
       if (mapAreaFilterEnabled && mapVisibleBounds) {
         if (flight.homeLat == null || flight.homeLon == null) return false;
@@ -534,15 +647,21 @@ export function FlightList({
         if (!inBounds) return false;
       }
 
-      // Search filter (not affected by inversion - always AND)
+      // Search filter (not affected by inversion - always AND).
+      // Uses the backend search (names, drones, notes, tags, locations) once it
+      // has answered, and a plain title match while it is still pending.
       if (normalizedSearch) {
-        const title = (flight.displayName || flight.fileName || '').toString().toLowerCase();
-        if (!title.includes(normalizedSearch)) return false;
+        if (searchScores) {
+          if (!searchScores.has(flight.id)) return false;
+        } else {
+          const title = (flight.displayName || flight.fileName || '').toString().toLowerCase();
+          if (!title.includes(normalizedSearch)) return false;
+        }
       }
 
       return true;
     });
-  }, [dateRange, flights, selectedBatteries, selectedDrones, durationFilterMin, durationFilterMax, altitudeFilterMin, altitudeFilterMax, distanceFilterMin, distanceFilterMax, selectedTags, isFilterInverted, mapAreaFilterEnabled, mapVisibleBounds, searchQuery]);
+  }, [dateRange, flights, selectedBatteries, selectedDrones, durationFilterMin, durationFilterMax, altitudeFilterMin, altitudeFilterMax, distanceFilterMin, distanceFilterMax, selectedTags, isFilterInverted, mapAreaFilterEnabled, mapVisibleBounds, searchQuery, searchScores, favoritesOnly]);
 
   // Sync filtered flight IDs to the store so Overview can use them
   // Use useLayoutEffect to ensure sync happens synchronously before browser paint
@@ -574,6 +693,11 @@ export function FlightList({
   const sortedFlights = useMemo(() => {
     const list = [...filteredFlights];
     list.sort((a, b) => {
+      // Best search matches first; the chosen sort breaks ties
+      if (searchScores && searchQuery.trim()) {
+        const diff = (searchScores.get(b.id) ?? 0) - (searchScores.get(a.id) ?? 0);
+        if (diff !== 0) return diff;
+      }
       if (sortOption === 'name') {
         const nameA = getFlightTitle(a).toLowerCase();
         const nameB = getFlightTitle(b).toLowerCase();
@@ -594,12 +718,17 @@ export function FlightList({
           ? aDistance - bDistance
           : bDistance - aDistance;
       }
+      if (sortOption === 'range') {
+        const aRange = a.maxDistanceFromHome ?? 0;
+        const bRange = b.maxDistanceFromHome ?? 0;
+        return sortDirection === 'asc' ? aRange - bRange : bRange - aRange;
+      }
       const aDate = a.startTime ? new Date(a.startTime).getTime() : 0;
       const bDate = b.startTime ? new Date(b.startTime).getTime() : 0;
       return sortDirection === 'asc' ? aDate - bDate : bDate - aDate;
     });
     return list;
-  }, [getFlightTitle, filteredFlights, sortDirection, sortOption]);
+  }, [getFlightTitle, filteredFlights, sortDirection, sortOption, searchScores, searchQuery]);
 
   // Keyboard navigation: Up/Down arrows to navigate flights
   useEffect(() => {
@@ -686,6 +815,7 @@ export function FlightList({
       { value: 'date', label: 'Date' },
       { value: 'duration', label: 'Duration' },
       { value: 'distance', label: 'Distance' },
+      { value: 'range', label: 'Distance from home' },
     ],
     []
   );
@@ -1058,7 +1188,7 @@ ${points}
       const takeoffLon = flight.homeLon ?? (data.telemetry.longitude?.[0] || null);
       
       // Get aircraft name - use edited name if available, otherwise fall back to aircraftName or droneModel
-      const fallbackName = flight.aircraftName || flight.droneModel || '';
+      const fallbackName = flight.droneNickname || flight.aircraftName || flight.droneModel || '';
       const aircraftName = flight.droneSerial 
         ? getDroneDisplayNameFn(flight.droneSerial, fallbackName) 
         : fallbackName;
@@ -1084,7 +1214,51 @@ ${points}
     return [headers.join(','), ...rows].join('\n');
   };
 
+  /** Export the filtered flights on the backend, straight from the database */
+  const handleBackendExport = async (
+    format: 'gpx' | 'kml' | 'csv' | 'json',
+    options: { units?: ExportUnits; columns?: string[] } = {}
+  ) => {
+    try {
+      setIsExporting(true);
+      setExportProgress({
+        done: 0,
+        total: filteredFlights.length,
+        currentFile: isWebMode() ? 'Exporting on server...' : '',
+      });
+      await api.exportFlightsBatch(currentFilter(), format, {
+        ...options,
+        onProgress: (progress) =>
+          setExportProgress({
+            done: progress.processed,
+            total: progress.total,
+            currentFile: progress.currentFlight,
+          }),
+      });
+      setExportProgress((prev) => ({ done: prev.total, total: prev.total, currentFile: '' }));
+      setTimeout(() => setIsExporting(false), 1000);
+    } catch (err) {
+      console.error('Export failed:', err);
+      setIsExporting(false);
+    }
+  };
+
+  const handleTemplateExport = async (templateId: number) => {
+    const template = exportTemplates.find((t) => t.id === templateId);
+    // The map area filter can't be expressed as a saved filter
+    if (!template || mapAreaFilterEnabled) return;
+    await handleBackendExport(template.format, { units: template.units, columns: template.columns });
+  };
+
   const handleBulkExport = async (format: string, extension: string) => {
+    // The backend exports straight from the database. CSV keeps the
+    // re-importable per-flight format built here, and the map area filter
+    // can't be expressed as a saved filter.
+    if ((format === 'gpx' || format === 'kml' || format === 'json') && !mapAreaFilterEnabled) {
+      await handleBackendExport(format);
+      return;
+    }
+
     try {
       setIsExporting(true);
       setExportProgress({ done: 0, total: filteredFlights.length, currentFile: '' });
@@ -1205,6 +1379,77 @@ ${points}
     }
   };
 
+  const handleXlsxExport = async (telemetry: boolean) => {
+    // The workbook is built by the backend, so the map area filter can't apply
+    if (mapAreaFilterEnabled) return;
+
+    try {
+      setIsExporting(true);
+      setExportProgress({ done: 0, total: filteredFlights.length, currentFile: 'Building workbook...' });
+      const timestamp = new Date().toISOString().slice(0, 10);
+      await api.exportFlightsXlsx(currentFilter(), `drone_flights_${timestamp}.xlsx`, {
+        telemetry,
+        units: unitSystem === 'imperial' ? { length: 'ft', speed: 'mph', temperature: 'f' } : {},
+      });
+      setExportProgress({ done: filteredFlights.length, total: filteredFlights.length, currentFile: '' });
+      setTimeout(() => setIsExporting(false), 1000);
+    } catch (err) {
+      console.error('Excel export failed:', err);
+      setIsExporting(false);
+    }
+  };
+
+  const handleCoverageExport = async (format: 'kml' | 'geojson') => {
+    // The layer is built by the backend, so the map area filter can't apply
+    if (mapAreaFilterEnabled) return;
+
+    try {
+      setIsExporting(true);
+      setExportProgress({ done: 0, total: filteredFlights.length, currentFile: 'Building coverage map...' });
+      const timestamp = new Date().toISOString().slice(0, 10);
+      await api.exportCoverage(currentFilter(), format, `drone_coverage_${timestamp}.${format}`);
+      setExportProgress({ done: filteredFlights.length, total: filteredFlights.length, currentFile: '' });
+      setTimeout(() => setIsExporting(false), 1000);
+    } catch (err) {
+      console.error('Coverage export failed:', err);
+      setIsExporting(false);
+    }
+  };
+
+  const handleParquetExport = async () => {
+    // The file is written by the backend, so the map area filter can't apply
+    if (mapAreaFilterEnabled) return;
+
+    try {
+      setIsExporting(true);
+      setExportProgress({ done: 0, total: filteredFlights.length, currentFile: 'Writing Parquet file...' });
+      const timestamp = new Date().toISOString().slice(0, 10);
+      await api.exportTelemetryParquet({ filter: currentFilter() }, `drone_telemetry_${timestamp}.parquet`);
+      setExportProgress({ done: filteredFlights.length, total: filteredFlights.length, currentFile: '' });
+      setTimeout(() => setIsExporting(false), 1000);
+    } catch (err) {
+      console.error('Parquet export failed:', err);
+      setIsExporting(false);
+    }
+  };
+
+  const handleLogbookExport = async (format: 'csv' | 'pdf') => {
+    // The logbook is built by the backend, so the map area filter can't apply
+    if (mapAreaFilterEnabled) return;
+
+    try {
+      setIsExporting(true);
+      setExportProgress({ done: 0, total: filteredFlights.length, currentFile: 'Building logbook...' });
+      const timestamp = new Date().toISOString().slice(0, 10);
+      await api.exportLogbook(currentFilter(), format, `drone_logbook_${timestamp}.${format}`);
+      setExportProgress({ done: filteredFlights.length, total: filteredFlights.length, currentFile: '' });
+      setTimeout(() => setIsExporting(false), 1000);
+    } catch (err) {
+      console.error('Logbook export failed:', err);
+      setIsExporting(false);
+    }
+  };
+
   const handleBulkDelete = async () => {
     try {
       setIsDeleting(true);
@@ -1214,12 +1459,8 @@ ${points}
       for (let i = 0; i < filteredFlights.length; i++) {
         const flight = filteredFlights[i];
         setDeleteProgress({ done: i, total: filteredFlights.length, currentFile: flight.fileName || '' });
-        
-        // Add to blacklist before deleting (so sync won't re-import)
-        if (flight.fileHash) {
-          addToBlacklist(flight.fileHash);
-        }
-        
+
+        // Deleting blacklists the file in the backend (so sync won't re-import)
         await deleteFlight(flight.id);
       }
 
@@ -1474,12 +1715,12 @@ ${points}
           className="w-full flex items-center justify-between px-3 py-2 text-xs text-gray-400 hover:text-white transition-colors"
         >
           <span className="flex items-center gap-1.5">
-            <span className={`font-medium ${(dateRange?.from || dateRange?.to || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || mapAreaFilterEnabled || searchQuery.trim()) ? (isFilterInverted ? 'text-red-400' : 'text-emerald-400') : ''}`}>
-              {dateRange?.from || dateRange?.to || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || mapAreaFilterEnabled || searchQuery.trim()
+            <span className={`font-medium ${(dateRange?.from || dateRange?.to || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || mapAreaFilterEnabled || searchQuery.trim() || favoritesOnly) ? (isFilterInverted ? 'text-red-400' : 'text-emerald-400') : ''}`}>
+              {dateRange?.from || dateRange?.to || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || mapAreaFilterEnabled || searchQuery.trim() || favoritesOnly
                 ? isFilterInverted ? 'Filters — Active — Inverted' : 'Filters — Active'
                 : isFiltersCollapsed ? 'Filters — click to expand' : 'Filters'}
             </span>
-            {(dateRange?.from || dateRange?.to || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || mapAreaFilterEnabled || searchQuery.trim()) && (
+            {(dateRange?.from || dateRange?.to || selectedDrones.length > 0 || selectedBatteries.length > 0 || durationFilterMin !== null || durationFilterMax !== null || altitudeFilterMin !== null || altitudeFilterMax !== null || distanceFilterMin !== null || distanceFilterMax !== null || selectedTags.length > 0 || mapAreaFilterEnabled || searchQuery.trim() || favoritesOnly) && (
               <button
                 type="button"
                 onClick={(e) => {
@@ -2194,6 +2435,14 @@ ${points}
           <span className="text-xs text-gray-400">
             {filteredFlights.length} of {flights.length} logs selected
           </span>
+          <button
+            onClick={() => setFavoritesOnly((v) => !v)}
+            className={`ml-auto mr-3 flex items-center gap-1 text-xs ${favoritesOnly ? 'text-amber-400' : 'text-gray-400 hover:text-white'}`}
+            title={favoritesOnly ? 'Show all flights' : 'Show only starred flights'}
+          >
+            <StarIcon filled={favoritesOnly} />
+            Favorites
+          </button>
           <button
             onClick={() => {
               setDateRange(undefined);
@@ -2209,6 +2458,8 @@ ${points}
               setIsFilterInverted(false);
               setMapAreaFilterEnabled(false);
               setSearchQuery('');
+              setFavoritesOnly(false);
+              setActiveSmartListId(null);
             }}
             className="text-xs text-gray-400 hover:text-white"
           >
@@ -2216,6 +2467,37 @@ ${points}
           </button>
         </div>
 
+        {/* Smart lists: saved filter combinations */}
+        <div className="flex items-center gap-2">
+          {smartLists.length > 0 && (
+            <Select
+              value={activeSmartListId !== null ? String(activeSmartListId) : ''}
+              onChange={(value) => {
+                const list = smartLists.find((l) => String(l.id) === value);
+                if (list) applySmartList(list);
+              }}
+              options={[{ value: '', label: 'Smart lists…' }, ...smartLists.map((l) => ({ value: String(l.id), label: l.name }))]}
+              className="flex-1 text-xs"
+            />
+          )}
+          <button
+            onClick={handleSaveSmartList}
+            className="text-xs text-gray-400 hover:text-white whitespace-nowrap"
+            title="Save the current filters as a smart list"
+          >
+            Save as smart list
+          </button>
+          {activeSmartListId !== null && (
+            <button
+              onClick={handleDeleteSmartList}
+              className="text-xs text-gray-400 hover:text-red-400"
+              title="Delete this smart list"
+            >
+              Delete
+            </button>
+          )}
+        </div>
+
         {/* Export and Delete Filtered Buttons */}
         <div className="flex items-center gap-2">
           {/* Export Dropdown */}
@@ -2248,6 +2530,19 @@ ${points}
                       { id: 'gpx', label: 'GPX', ext: 'gpx', disabled: false },
                       { id: 'kml', label: 'KML', ext: 'kml', disabled: false },
                       { id: 'summary', label: 'Summary', ext: 'csv', disabled: filteredFlights.length <= 1 },
+                      { id: 'xlsx', label: 'Excel', ext: 'xlsx', disabled: mapAreaFilterEnabled },
+                      { id: 'xlsx_telemetry', label: 'Excel + telemetry', ext: 'xlsx', disabled: mapAreaFilterEnabled },
+                      { id: 'logbook_csv', label: 'Logbook CSV', ext: 'csv', disabled: mapAreaFilterEnabled },
+                      { id: 'logbook_pdf', label: 'Logbook PDF', ext: 'pdf', disabled: mapAreaFilterEnabled },
+                      { id: 'parquet', label: 'Parquet (telemetry)', ext: 'parquet', disabled: mapAreaFilterEnabled },
+                      { id: 'coverage_kml', label: 'Coverage map KML', ext: 'kml', disabled: mapAreaFilterEnabled },
+                      { id: 'coverage_geojson', label: 'Coverage map GeoJSON', ext: 'geojson', disabled: mapAreaFilterEnabled },
+                      ...exportTemplates.map((t) => ({
+                        id: `template:${t.id}`,
+                        label: t.name,
+                        ext: t.format,
+                        disabled: mapAreaFilterEnabled,
+                      })),
                     ];
                     const enabledOptions = exportOptions.filter(o => !o.disabled);
                     if (e.key === 'ArrowDown') {
@@ -2278,6 +2573,16 @@ ${points}
                         setIsExportDropdownOpen(false);
                         if (opt.id === 'summary') {
                           handleSummaryExport();
+                        } else if (opt.id === 'xlsx' || opt.id === 'xlsx_telemetry') {
+                          handleXlsxExport(opt.id === 'xlsx_telemetry');
+                        } else if (opt.id === 'coverage_kml' || opt.id === 'coverage_geojson') {
+                          handleCoverageExport(opt.ext as 'kml' | 'geojson');
+                        } else if (opt.id === 'parquet') {
+                          handleParquetExport();
+                        } else if (opt.id === 'logbook_csv' || opt.id === 'logbook_pdf') {
+                          handleLogbookExport(opt.ext as 'csv' | 'pdf');
+                        } else if (opt.id.startsWith('template:')) {
+                          handleTemplateExport(Number(opt.id.slice(9)));
                         } else {
                           handleBulkExport(opt.id, opt.ext);
                         }
@@ -2296,6 +2601,19 @@ ${points}
                       { id: 'gpx', label: 'GPX', ext: 'gpx', disabled: false },
                       { id: 'kml', label: 'KML', ext: 'kml', disabled: false },
                       { id: 'summary', label: 'Summary', ext: 'csv', disabled: filteredFlights.length <= 1 },
+                      { id: 'xlsx', label: 'Excel', ext: 'xlsx', disabled: mapAreaFilterEnabled },
+                      { id: 'xlsx_telemetry', label: 'Excel + telemetry', ext: 'xlsx', disabled: mapAreaFilterEnabled },
+                      { id: 'logbook_csv', label: 'Logbook CSV', ext: 'csv', disabled: mapAreaFilterEnabled },
+                      { id: 'logbook_pdf', label: 'Logbook PDF', ext: 'pdf', disabled: mapAreaFilterEnabled },
+                      { id: 'parquet', label: 'Parquet (telemetry)', ext: 'parquet', disabled: mapAreaFilterEnabled },
+                      { id: 'coverage_kml', label: 'Coverage map KML', ext: 'kml', disabled: mapAreaFilterEnabled },
+                      { id: 'coverage_geojson', label: 'Coverage map GeoJSON', ext: 'geojson', disabled: mapAreaFilterEnabled },
+                      ...exportTemplates.map((t) => ({
+                        id: `template:${t.id}`,
+                        label: t.name,
+                        ext: t.format,
+                        disabled: mapAreaFilterEnabled,
+                      })),
                     ].map((opt, index) => (
                       <button
                         key={opt.id}
@@ -2304,6 +2622,16 @@ ${points}
                           setIsExportDropdownOpen(false);
                           if (opt.id === 'summary') {
                             handleSummaryExport();
+                          } else if (opt.id === 'xlsx' || opt.id === 'xlsx_telemetry') {
+                            handleXlsxExport(opt.id === 'xlsx_telemetry');
+                          } else if (opt.id === 'coverage_kml' || opt.id === 'coverage_geojson') {
+                            handleCoverageExport(opt.ext as 'kml' | 'geojson');
+                          } else if (opt.id === 'parquet') {
+                            handleParquetExport();
+                          } else if (opt.id === 'logbook_csv' || opt.id === 'logbook_pdf') {
+                            handleLogbookExport(opt.ext as 'csv' | 'pdf');
+                          } else if (opt.id.startsWith('template:')) {
+                            handleTemplateExport(Number(opt.id.slice(9)));
                           } else {
                             handleBulkExport(opt.id, opt.ext);
                           }
@@ -2581,7 +2909,7 @@ ${points}
           ) : (
             <div className="flex items-center justify-between gap-1">
               <p
-                className="text-sm text-gray-300 truncate flex-1 min-w-0"
+                className={`text-sm truncate flex-1 min-w-0 ${flight.excludedFromStats ? 'text-gray-500' : 'text-gray-300'}`}
                 onDoubleClick={(e) => {
                   e.stopPropagation();
                   setEditingId(flight.id);
@@ -2593,13 +2921,28 @@ ${points}
                   `Start: ${formatDateTime(flight.startTime)}`,
                   `Duration: ${formatDuration(flight.durationSecs)}`,
                   `Distance: ${formatDistance(flight.totalDistance, unitSystem)}`,
+                  flight.maxDistanceFromHome != null
+                    ? `Max distance from home: ${formatDistance(flight.maxDistanceFromHome, unitSystem)}`
+                    : null,
                   `Max Altitude: ${formatAltitude(flight.maxAltitude, unitSystem)}`,
+                  flight.firmwareVersion ? `Firmware: ${flight.firmwareVersion}` : null,
+                  flight.excludedFromStats ? 'Excluded from statistics' : null,
                   flight.notes ? `Notes: ${flight.notes}` : null
                 ].filter(Boolean).join('\n')}
               >
                 {flight.displayName || flight.fileName}
               </p>
               <div className="flex items-center gap-0.5 flex-shrink-0">
+                <button
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    setFlightFavorite(flight.id, !flight.favorite);
+                  }}
+                  className={`p-0.5 ${flight.favorite ? 'text-amber-400 hover:text-amber-300' : 'text-gray-500 hover:text-amber-400'}`}
+                  title={flight.favorite ? 'Remove from favorites' : 'Add to favorites'}
+                >
+                  <StarIcon filled={!!flight.favorite} />
+                </button>
                 <button
                   onClick={(e) => {
                     e.stopPropagation();
@@ -2642,10 +2985,7 @@ ${points}
               <button
                 onClick={(e) => {
                   e.stopPropagation();
-                  // Add to blacklist before deleting (so sync won't re-import)
-                  if (flight.fileHash) {
-                    addToBlacklist(flight.fileHash);
-                  }
+                  // Deleting blacklists the file in the backend (so sync won't re-import)
                   deleteFlight(flight.id);
                   setConfirmDeleteId(null);
                 }}
@@ -3028,6 +3368,14 @@ function PencilIcon() {
   );
 }
 
+function StarIcon({ filled }: { filled: boolean }) {
+  return (
+    <svg className="w-3.5 h-3.5" fill={filled ? 'currentColor' : 'none'} stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" viewBox="0 0 24 24">
+      <path d="M12 2l3.09 6.26L22 9.27l-5 4.87 1.18 6.88L12 17.77l-6.18 3.25L7 14.14 2 9.27l6.91-1.01L12 2z" />
+    </svg>
+  );
+}
+
 function CalendarIcon() {
   return (
     <svg
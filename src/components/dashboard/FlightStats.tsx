@@ -3,8 +3,27 @@
  * Displays key metrics for the selected flight
  */
 
-import type { FlightDataResponse } from '@/types';
-import { isWebMode, downloadFile } from '@/lib/api';
+import type { ExportTemplate, FlightDataResponse, FlightDocument, FlightMedia, FlightModeTime, FlightPhase } from '@/types';
+import {
+  isWebMode,
+  downloadFile,
+  getFlightMedia,
+  getFlightModes,
+  getFlightDocuments,
+  addFlightDocument,
+  downloadFlightDocument,
+  deleteFlightDocument,
+  exportFlightGpx,
+  exportFlightKml,
+  exportFlightCsv,
+  exportFlightGeojson,
+  exportFlightTlog,
+  exportFlightReport,
+  exportFlightOverlay,
+  exportFlightSrt,
+  exportTelemetryParquet,
+  getExportTemplates,
+} from '@/lib/api';
 import { useMemo, useState, useRef, useEffect } from 'react';
 import { WeatherModal } from './WeatherModal';
 import weatherIcon from '@/assets/weather-icon.svg';
@@ -14,6 +33,8 @@ import {
   formatSpeed,
   formatAltitude,
   formatDateTime,
+  flightModeColor,
+  flightPhaseColor,
 } from '@/lib/utils';
 import { useFlightStore } from '@/stores/flightStore';
 
@@ -22,10 +43,19 @@ interface FlightStatsProps {
 }
 
 export function FlightStats({ data }: FlightStatsProps) {
-  const { flight, telemetry } = data;
-  const { unitSystem, getBatteryDisplayName, addTag, removeTag, allTags, getDisplaySerial } = useFlightStore();
+  const { flight, telemetry, attachments = [], events = [], phases = [] } = data;
+  const {
+    unitSystem,
+    getBatteryDisplayName,
+    addTag,
+    removeTag,
+    allTags,
+    getDisplaySerial,
+    setFlightExcludedFromStats,
+  } = useFlightStore();
   const [isExportOpen, setIsExportOpen] = useState(false);
   const [isExporting, setIsExporting] = useState(false);
+  const [exportTemplates, setExportTemplates] = useState<ExportTemplate[]>([]);
   const [isWeatherOpen, setIsWeatherOpen] = useState(false);
   const [isAddingTag, setIsAddingTag] = useState(false);
   const [newTagValue, setNewTagValue] = useState('');
@@ -34,6 +64,81 @@ export function FlightStats({ data }: FlightStatsProps) {
   const addTagContainerRef = useRef<HTMLDivElement>(null);
 
   const flightTags = flight.tags ?? [];
+  const [media, setMedia] = useState<FlightMedia[]>([]);
+  const [documents, setDocuments] = useState<FlightDocument[]>([]);
+  const [modes, setModes] = useState<FlightModeTime[]>([]);
+  const documentInputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    getFlightMedia(flight.id)
+      .then((items) => {
+        if (!cancelled) setMedia(items);
+      })
+      .catch(() => {
+        if (!cancelled) setMedia([]);
+      });
+    getFlightDocuments(flight.id)
+      .then((items) => {
+        if (!cancelled) setDocuments(items);
+      })
+      .catch(() => {
+        if (!cancelled) setDocuments([]);
+      });
+    getFlightModes(flight.id)
+      .then((items) => {
+        if (!cancelled) setModes(items);
+      })
+      .catch(() => {
+        if (!cancelled) setModes([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [flight.id]);
+
+  const handleAddDocument = async (file?: File) => {
+    try {
+      const added = await addFlightDocument(flight.id, file);
+      if (added) setDocuments((prev) => [...prev, added]);
+    } catch (err) {
+      console.error('Failed to attach document:', err);
+    }
+  };
+
+  const handleDeleteDocument = async (doc: FlightDocument) => {
+    try {
+      await deleteFlightDocument(doc.id);
+      setDocuments((prev) => prev.filter((d) => d.id !== doc.id));
+    } catch (err) {
+      console.error('Failed to remove document:', err);
+    }
+  };
+
+  // Templates may have been edited in settings since the menu was last open
+  useEffect(() => {
+    if (!isExportOpen) return;
+    getExportTemplates().then(setExportTemplates).catch(() => setExportTemplates([]));
+  }, [isExportOpen]);
+
+  const recordingCount = useMemo(
+    () => events.filter((e) => e.eventType === 'video').length,
+    [events]
+  );
+
+  const captureSummary = useMemo(() => {
+    const photoCount = events.filter((e) => e.eventType === 'photo').length;
+    const recordingMs = events
+      .filter((e) => e.eventType === 'video')
+      .reduce((sum, e) => sum + (e.durationMs ?? 0), 0);
+    return { photoCount, recordingSecs: recordingMs / 1000 };
+  }, [events]);
+
+  const warnings = useMemo(
+    () => events.filter((e) => e.eventType === 'warning'),
+    [events]
+  );
+  const hasCriticalWarning = warnings.some((e) => e.severity === 'critical');
 
   // Filter suggestions based on input
   useEffect(() => {
@@ -96,18 +201,36 @@ export function FlightStats({ data }: FlightStatsProps) {
   const exportOptions = useMemo(
     () => [
       { id: 'csv', label: 'CSV', extension: 'csv' },
+      { id: 'csv_full', label: 'CSV (all columns)', extension: 'csv' },
       { id: 'json', label: 'JSON', extension: 'json' },
       { id: 'gpx', label: 'GPX', extension: 'gpx' },
       { id: 'kml', label: 'KML', extension: 'kml' },
+      { id: 'kmz', label: 'KMZ + Tour', extension: 'kmz' },
+      { id: 'geojson', label: 'GeoJSON', extension: 'geojson' },
+      { id: 'parquet', label: 'Parquet (telemetry)', extension: 'parquet' },
+      { id: 'tlog', label: 'MAVLink tlog', extension: 'tlog' },
+      { id: 'overlay', label: 'Video Overlay CSV', extension: 'csv' },
+      { id: 'report', label: 'PDF Report', extension: 'pdf' },
+      // Subtitles for each video recorded during the flight
+      ...Array.from({ length: recordingCount }, (_, index) => ({
+        id: `srt:${index}`,
+        label: recordingCount > 1 ? `SRT (video ${index + 1})` : 'SRT (video)',
+        extension: 'srt',
+      })),
+      ...exportTemplates.map((template) => ({
+        id: `template:${template.id}`,
+        label: template.name,
+        extension: template.format,
+      })),
     ],
-    []
+    [recordingCount, exportTemplates]
   );
 
   const buildCsv = () => {
     const trackAligned = data.track.length === telemetry.time.length;
     const latSeries = telemetry.latitude ?? [];
     const lngSeries = telemetry.longitude ?? [];
-    const distanceToHome = computeDistanceToHomeSeries(telemetry);
+    const distanceToHome = telemetry.distanceFromHome ?? computeDistanceToHomeSeries(telemetry);
 
     // Build metadata JSON for the first row's metadata column
     const appVersion = typeof __APP_VERSION__ !== 'undefined' ? __APP_VERSION__ : 'unknown';
@@ -165,6 +288,8 @@ export function FlightStats({ data }: FlightStatsProps) {
       'is_photo',
       'is_video',
       'flight_mode',
+      'wind_speed_ms',
+      'wind_dir_deg',
       'metadata',
     ];
 
@@ -228,6 +353,8 @@ export function FlightStats({ data }: FlightStatsProps) {
         getBoolValue(telemetry.isPhoto, index),
         getBoolValue(telemetry.isVideo, index),
         getStrValue(telemetry.flightMode, index),
+        getValue(telemetry.windSpeed, index),
+        getValue(telemetry.windDirection, index),
         // Metadata JSON only on first row (time 0)
         index === 0 ? metadataJson : '',
       ].map(escapeCsv);
@@ -250,7 +377,7 @@ export function FlightStats({ data }: FlightStatsProps) {
         telemetry,
         track: data.track,
         derived: {
-          distanceToHome: computeDistanceToHomeSeries(telemetry),
+          distanceToHome: telemetry.distanceFromHome ?? computeDistanceToHomeSeries(telemetry),
         },
       },
       null,
@@ -258,75 +385,6 @@ export function FlightStats({ data }: FlightStatsProps) {
     );
   };
 
-  const buildGpx = () => {
-    const name = flight.displayName || flight.fileName || 'Drone Flight';
-    // Get flight start time as Unix timestamp in milliseconds
-    const startTimeMs = flight.startTime ? new Date(flight.startTime).getTime() : null;
-    
-    // Use telemetry arrays directly (they're all aligned) instead of track which may be downsampled differently
-    const points = telemetry.time
-      .map((relativeTime, index) => {
-        const lat = telemetry.latitude?.[index];
-        const lng = telemetry.longitude?.[index];
-        const alt = telemetry.altitude?.[index];
-        if (lat == null || lng == null) return null;
-        // telemetry.time is seconds from flight start, convert to absolute timestamp
-        const absoluteTimeMs = startTimeMs != null && relativeTime != null 
-          ? startTimeMs + (relativeTime * 1000) 
-          : null;
-        return `      <trkpt lat="${lat}" lon="${lng}">
-        ${alt != null ? `<ele>${alt}</ele>` : ''}
-        ${absoluteTimeMs != null ? `<time>${new Date(absoluteTimeMs).toISOString()}</time>` : ''}
-      </trkpt>`;
-      })
-      .filter(Boolean)
-      .join('\n');
-
-    return `<?xml version="1.0" encoding="UTF-8"?>
-<gpx version="1.1" creator="Drone Logbook" xmlns="http://www.topografix.com/GPX/1/1">
-  <metadata>
-    <name>${escapeXml(name)}</name>
-    <time>${new Date().toISOString()}</time>
-  </metadata>
-  <trk>
-    <name>${escapeXml(name)}</name>
-    <trkseg>
-${points}
-    </trkseg>
-  </trk>
-</gpx>`;
-  };
-
-  const buildKml = () => {
-    const name = flight.displayName || flight.fileName || 'Drone Flight';
-    const coordinates = data.track
-      .map(([lng, lat, alt]) => `${lng},${lat},${alt}`)
-      .join(' ');
-    return `<?xml version="1.0" encoding="UTF-8"?>
-<kml xmlns="http://www.opengis.net/kml/2.2">
-  <Document>
-    <name>${escapeXml(name)}</name>
-    <Placemark>
-      <name>${escapeXml(name)}</name>
-      <LineString>
-        <tessellate>1</tessellate>
-        <altitudeMode>relativeToGround</altitudeMode>
-        <coordinates>${coordinates}</coordinates>
-      </LineString>
-    </Placemark>
-  </Document>
-</kml>`;
-  };
-
-  const escapeXml = (value: string) => {
-    return value
-      .replace(/&/g, '&amp;')
-      .replace(/</g, '&lt;')
-      .replace(/>/g, '&gt;')
-      .replace(/"/g, '&quot;')
-      .replace(/'/g, '&apos;');
-  };
-
   const handleExport = async (format: string, extension: string) => {
     if (isExporting) return;
     setIsExporting(true);
@@ -337,20 +395,87 @@ ${points}
         .replace(/^_+|_+$/g, '')
         .slice(0, 80);
 
+      if (format.startsWith('srt:')) {
+        const recording = Number(format.slice(4));
+        const suffix = recordingCount > 1 ? `_video${recording + 1}` : '';
+        await exportFlightSrt(flight.id, `${baseName || 'flight'}${suffix}.${extension}`, recording);
+        return;
+      }
+
+      if (format.startsWith('template:')) {
+        const template = exportTemplates.find((t) => t.id === Number(format.slice(9)));
+        if (!template) return;
+        const fileName = `${baseName || 'flight'}.${extension}`;
+        switch (template.format) {
+          case 'csv':
+            await exportFlightCsv(flight.id, fileName, template.units, template.columns);
+            return;
+          case 'gpx':
+            await exportFlightGpx(flight.id, fileName);
+            return;
+          case 'kml':
+            await exportFlightKml(flight.id, fileName);
+            return;
+          case 'json':
+            // JSON keeps the stored units, as the built-in JSON export does
+            format = 'json';
+            break;
+        }
+      }
+
       let content = '';
       switch (format) {
         case 'csv':
           content = buildCsv();
           break;
+        case 'csv_full':
+          await exportFlightCsv(
+            flight.id,
+            `${baseName || 'flight'}_telemetry.${extension}`,
+            unitSystem === 'imperial' ? { length: 'ft', speed: 'mph', temperature: 'f' } : {}
+          );
+          return;
         case 'json':
           content = buildJson();
           break;
         case 'gpx':
-          content = buildGpx();
-          break;
+          // Built by the backend from the full-resolution telemetry
+          await exportFlightGpx(flight.id, `${baseName || 'flight'}.${extension}`);
+          return;
         case 'kml':
-          content = buildKml();
-          break;
+          await exportFlightKml(flight.id, `${baseName || 'flight'}.${extension}`);
+          return;
+        case 'kmz':
+          await exportFlightKml(flight.id, `${baseName || 'flight'}.${extension}`, {
+            kmz: true,
+            tour: true,
+          });
+          return;
+        case 'geojson':
+          await exportFlightGeojson(flight.id, `${baseName || 'flight'}.${extension}`);
+          return;
+        case 'tlog':
+          await exportFlightTlog(flight.id, `${baseName || 'flight'}.${extension}`);
+          return;
+        case 'parquet':
+          await exportTelemetryParquet({ flightId: flight.id }, `${baseName || 'flight'}.${extension}`);
+          return;
+        case 'overlay':
+          // Aligned to the first recording in the log, 10 rows per second
+          await exportFlightOverlay(
+            flight.id,
+            `${baseName || 'flight'}_overlay.${extension}`,
+            {},
+            unitSystem === 'imperial' ? { length: 'ft', speed: 'mph' } : {}
+          );
+          return;
+        case 'report':
+          await exportFlightReport(
+            flight.id,
+            `${baseName || 'flight'}_report.${extension}`,
+            unitSystem === 'imperial' ? { length: 'ft', speed: 'mph', temperature: 'f' } : {}
+          );
+          return;
         default:
           return;
       }
@@ -399,9 +524,9 @@ ${points}
           )}
           <div className="text-sm text-gray-400 flex flex-wrap items-center gap-2 mt-2">
             {formatDateTime(flight.startTime)}
-            {flight.aircraftName && (
+            {(flight.droneNickname || flight.aircraftName) && (
               <span className="px-2 py-0.5 rounded-full text-xs border border-drone-primary/40 text-drone-primary bg-drone-primary/10">
-                Device: {flight.aircraftName}
+                Device: {flight.droneNickname || flight.aircraftName}
               </span>
             )}
             {flight.droneSerial && (
@@ -409,11 +534,93 @@ ${points}
                 SN: {getDisplaySerial(flight.droneSerial)}
               </span>
             )}
+            {flight.firmwareVersion && (
+              <span className="px-2 py-0.5 rounded-full text-xs border border-gray-600/60 text-gray-400 bg-drone-surface/60">
+                FW: {flight.firmwareVersion}
+              </span>
+            )}
+            {(flight.locationCity || flight.locationCountry) && (
+              <span className="px-2 py-0.5 rounded-full text-xs border border-gray-600/60 text-gray-400 bg-drone-surface/60">
+                {[flight.locationCity, flight.locationRegion, flight.locationCountry].filter(Boolean).join(', ')}
+              </span>
+            )}
             {flight.batterySerial && (
               <span className="px-2 py-0.5 rounded-full text-xs border border-drone-accent/40 text-drone-accent bg-drone-accent/10">
                 Battery: {getBatteryDisplayName(flight.batterySerial)}
               </span>
             )}
+            {attachments.map((a) => (
+              <span
+                key={a.fileHash}
+                className="px-2 py-0.5 rounded-full text-xs border border-emerald-500/40 text-emerald-400 bg-emerald-500/10"
+                title={`Rich data linked from ${a.fileName} (${a.pointCount} points)`}
+              >
+                {a.sourceFormat.toUpperCase()} attached
+              </span>
+            ))}
+            {captureSummary.photoCount > 0 && (
+              <span className="px-2 py-0.5 rounded-full text-xs border border-sky-500/40 text-sky-300 bg-sky-500/10">
+                {captureSummary.photoCount} {captureSummary.photoCount === 1 ? 'photo' : 'photos'}
+              </span>
+            )}
+            {captureSummary.recordingSecs > 0 && (
+              <span
+                className="px-2 py-0.5 rounded-full text-xs border border-rose-500/40 text-rose-300 bg-rose-500/10"
+                title={`${Math.round((captureSummary.recordingSecs / Math.max(flight.durationSecs ?? 0, 1)) * 100)}% of the flight`}
+              >
+                Recording: {formatDuration(captureSummary.recordingSecs)}
+              </span>
+            )}
+            {media.length > 0 && (
+              <span
+                className="px-2 py-0.5 rounded-full text-xs border border-amber-500/40 text-amber-300 bg-amber-500/10"
+                title={media.map((m) => m.fileName).join('\n')}
+              >
+                {media.length} media {media.length === 1 ? 'file' : 'files'}
+              </span>
+            )}
+            {documents.map((doc) => (
+              <span
+                key={doc.id}
+                className="group relative px-2 py-0.5 rounded-full text-xs border border-slate-400/40 text-slate-300 bg-slate-400/10"
+              >
+                <button
+                  type="button"
+                  onClick={() => {
+                    downloadFlightDocument(doc).catch((err) => console.error('Failed to save document:', err));
+                  }}
+                  className="hover:text-white"
+                  title={`${doc.fileName} (${(doc.sizeBytes / 1024).toFixed(0)} KB) — click to save a copy`}
+                >
+                  {doc.fileName}
+                </button>
+                <button
+                  type="button"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleDeleteDocument(doc);
+                  }}
+                  className="absolute -top-1.5 -right-1.5 w-4 h-4 rounded-full bg-red-500 text-white flex items-center justify-center opacity-0 group-hover:opacity-100 transition-opacity shadow-sm hover:bg-red-400"
+                  title={`Remove document: ${doc.fileName}`}
+                >
+                  <svg width="8" height="8" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="3" strokeLinecap="round"><path d="M18 6L6 18M6 6l12 12" /></svg>
+                </button>
+              </span>
+            ))}
+            {warnings.length > 0 && (
+              <span
+                className={`px-2 py-0.5 rounded-full text-xs border ${
+                  hasCriticalWarning
+                    ? 'border-red-500/40 text-red-300 bg-red-500/10'
+                    : 'border-yellow-500/40 text-yellow-300 bg-yellow-500/10'
+                }`}
+                title={warnings
+                  .map((e) => `${formatDuration(e.timestampMs / 1000)}  ${e.detail ?? ''}`)
+                  .join('\n')}
+              >
+                {warnings.length} {warnings.length === 1 ? 'warning' : 'warnings'}
+              </span>
+            )}
             {/* Flight Tags */}
             {flightTags.map((tagObj) => {
               const tagName = typeof tagObj === 'string' ? tagObj : tagObj.tag;
@@ -489,6 +696,42 @@ ${points}
                 </button>
               )}
             </div>
+            {/* Statistics exclusion toggle */}
+            <button
+              type="button"
+              onClick={() => setFlightExcludedFromStats(flight.id, !flight.excludedFromStats)}
+              className={`h-5 px-2 rounded-full text-xs flex items-center transition-colors ${
+                flight.excludedFromStats
+                  ? 'border border-orange-500/40 text-orange-300 bg-orange-500/10 hover:bg-orange-500/20'
+                  : 'border border-dashed border-gray-500 text-gray-400 hover:border-orange-400 hover:text-orange-300'
+              }`}
+              title={
+                flight.excludedFromStats
+                  ? 'This flight is left out of totals, averages and records. Click to count it again.'
+                  : 'Leave this flight out of totals, averages and records, e.g. a bench test or hover check'
+              }
+            >
+              {flight.excludedFromStats ? 'Excluded from stats' : 'Exclude from stats'}
+            </button>
+            {/* Attach document button */}
+            <button
+              type="button"
+              onClick={() => (isWebMode() ? documentInputRef.current?.click() : handleAddDocument())}
+              className="h-5 px-2 rounded-full border border-dashed border-gray-500 text-gray-400 text-xs flex items-center hover:border-slate-300 hover:text-slate-200 transition-colors"
+              title="Attach a document, e.g. an airspace authorization or a client brief"
+            >
+              + Document
+            </button>
+            <input
+              ref={documentInputRef}
+              type="file"
+              className="hidden"
+              onChange={(e) => {
+                const file = e.target.files?.[0];
+                e.target.value = '';
+                if (file) handleAddDocument(file);
+              }}
+            />
           </div>
         </div>
 
@@ -496,6 +739,16 @@ ${points}
           <p className="text-xs text-gray-500">
             {flight.pointCount?.toLocaleString() || 0} data points
           </p>
+          {flight.daylight && flight.daylight !== 'day' && (
+            <p
+              className="text-xs text-indigo-300"
+              title="Sun below the horizon at the home point: civil twilight down to 6°, night below that"
+            >
+              {flight.daylight === 'night'
+                ? `Night flight · ${formatDuration(flight.nightSecs ?? 0)} of night time`
+                : 'Civil twilight flight'}
+            </p>
+          )}
         </div>
       </div>
 
@@ -573,6 +826,12 @@ ${points}
         </div>
       </div>
 
+      {/* Time per flight mode */}
+      {modes.length > 0 && <FlightModeBar modes={modes} />}
+
+      {/* Time per flight phase */}
+      {phases.length > 0 && <FlightPhaseSummary phases={phases} />}
+
       {/* Weather Modal */}
       {flight.homeLat != null && flight.homeLon != null && flight.startTime && (
         <WeatherModal
@@ -588,6 +847,64 @@ ${points}
   );
 }
 
+function FlightModeBar({ modes }: { modes: FlightModeTime[] }) {
+  const total = modes.reduce((sum, m) => sum + m.durationSecs, 0);
+  if (total <= 0) return null;
+
+  return (
+    <div className="mt-2">
+      <div className="flex h-2 rounded-full overflow-hidden bg-gray-700/50">
+        {modes.map((m) => (
+          <div
+            key={m.mode}
+            style={{ width: `${(m.durationSecs / total) * 100}%`, backgroundColor: flightModeColor(m.category) }}
+            title={`${m.mode} (${m.category}): ${formatDuration(m.durationSecs)}`}
+          />
+        ))}
+      </div>
+      <div className="flex flex-wrap gap-x-3 gap-y-0.5 mt-1 text-[10px] text-gray-400">
+        {modes.map((m) => (
+          <span key={m.mode} className="flex items-center gap-1">
+            <span className="w-2 h-2 rounded-full" style={{ backgroundColor: flightModeColor(m.category) }} />
+            {m.mode} {((m.durationSecs / total) * 100).toFixed(0)}%
+          </span>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+const PHASE_LABELS: Record<FlightPhase['phase'], string> = {
+  ground: 'Ground',
+  ascent: 'Ascent',
+  cruise: 'Transit',
+  hover: 'Hover',
+  descent: 'Descent',
+  rth: 'RTH',
+};
+
+function FlightPhaseSummary({ phases }: { phases: FlightPhase[] }) {
+  const totals = new Map<FlightPhase['phase'], number>();
+  for (const p of phases) {
+    if (p.phase === 'ground') continue;
+    totals.set(p.phase, (totals.get(p.phase) ?? 0) + (p.endSecs - p.startSecs));
+  }
+  if (totals.size === 0) return null;
+
+  return (
+    <div className="flex flex-wrap gap-x-3 gap-y-0.5 mt-1 text-[10px] text-gray-400">
+      {(Object.keys(PHASE_LABELS) as FlightPhase['phase'][])
+        .filter((phase) => totals.has(phase))
+        .map((phase) => (
+          <span key={phase} className="flex items-center gap-1">
+            <span className="w-2 h-2 rounded-sm" style={{ backgroundColor: flightPhaseColor(phase) }} />
+            {PHASE_LABELS[phase]} {formatDuration(totals.get(phase) ?? 0)}
+          </span>
+        ))}
+    </div>
+  );
+}
+
 interface StatCardProps {
   label: string;
   value: string;
@@ -8,7 +8,8 @@ import { useMemo, useState, useRef, useEffect } from 'react';
 import ReactECharts from 'echarts-for-react';
 import { DayPicker, type DateRange } from 'react-day-picker';
 import 'react-day-picker/dist/style.css';
-import type { BatteryHealthPoint, Flight, OverviewStats } from '@/types';
+import type { BatteryHealth, BatteryHealthPoint, ComplianceReport, CurrencyEntry, CurrencyReport, DischargeCurve, DroneComparison, DroneComparisonStats, DroneEnergy, DroneRthEvents, DroneUsage, Flight, FlightModeStats, LibraryStats, Milestone, MilestoneReport, OverviewStats, PersonalRecord, PersonalRecords, RthStats, Site, SiteStats, TrendBucket, TrendPoint, YearReview } from '@/types';
+import { compareDrones, getBatteryHealth, getComplianceReport, getCurrency, getDischargeCurves, getEnergyStats, getFlightModeStats, getLibraryStats, getMilestones, getPersonalRecords, getRthStats, getSiteStats, getSites, getTrends, getYearReview, exportYearReviewPdf, downloadFile, isWebMode, reclusterSites, renameSite } from '@/lib/api';
 import {
   formatDistance,
   formatDuration,
@@ -16,6 +17,7 @@ import {
   formatAltitude,
   formatDateTime,
   normalizeSerial,
+  flightModeColor,
   type UnitSystem,
 } from '@/lib/utils';
 import { useFlightStore } from '@/stores/flightStore';
@@ -63,17 +65,108 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
     return flights.filter((f) => sidebarFilteredFlightIds.has(f.id));
   }, [flights, sidebarFilteredFlightIds]);
 
+  // Flights excluded from statistics stay on the map but out of every figure
+  const statsFlights = useMemo(() => filteredFlights.filter((f) => !f.excludedFromStats), [filteredFlights]);
+
+  // Totals and per-drone figures are aggregated by the backend
+  const [libraryStats, setLibraryStats] = useState<LibraryStats | null>(null);
+  useEffect(() => {
+    let cancelled = false;
+    const ids = sidebarFilteredFlightIds ? filteredFlights.map((f) => f.id) : undefined;
+    getLibraryStats(ids)
+      .then((result) => {
+        if (!cancelled) setLibraryStats(result);
+      })
+      .catch((err) => console.error('Failed to load library stats:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [filteredFlights, sidebarFilteredFlightIds]);
+
+  // All-time records, independent of the sidebar filter
+  const [personalRecords, setPersonalRecords] = useState<PersonalRecords | null>(null);
+  useEffect(() => {
+    getPersonalRecords()
+      .then(setPersonalRecords)
+      .catch((err) => console.error('Failed to load personal records:', err));
+  }, [flights]);
+
+  // Milestones and streaks over the whole library
+  const [milestones, setMilestones] = useState<MilestoneReport | null>(null);
+  useEffect(() => {
+    getMilestones()
+      .then(setMilestones)
+      .catch((err) => console.error('Failed to load milestones:', err));
+  }, [flights]);
+
+  // Recency against the requirements set in settings
+  const [currency, setCurrency] = useState<CurrencyReport | null>(null);
+  useEffect(() => {
+    getCurrency()
+      .then(setCurrency)
+      .catch((err) => console.error('Failed to load currency:', err));
+  }, [flights]);
+
+  // Flights over the height and distance limits set in settings
+  const [compliance, setCompliance] = useState<ComplianceReport | null>(null);
+  useEffect(() => {
+    getComplianceReport()
+      .then(setCompliance)
+      .catch((err) => console.error('Failed to load compliance report:', err));
+  }, [flights]);
+
+  // Energy used per aircraft from battery voltage × current
+  const [energy, setEnergy] = useState<DroneEnergy[]>([]);
+  useEffect(() => {
+    getEnergyStats()
+      .then(setEnergy)
+      .catch((err) => console.error('Failed to load energy statistics:', err));
+  }, [flights]);
+
+  // Time per flight mode category across the library
+  const [modeStats, setModeStats] = useState<FlightModeStats | null>(null);
+  useEffect(() => {
+    getFlightModeStats()
+      .then(setModeStats)
+      .catch((err) => console.error('Failed to load flight mode statistics:', err));
+  }, [flights]);
+
+  // RTH triggers, failsafes and RC signal losses per aircraft and month
+  const [rthStats, setRthStats] = useState<RthStats | null>(null);
+  useEffect(() => {
+    getRthStats()
+      .then(setRthStats)
+      .catch((err) => console.error('Failed to load RTH statistics:', err));
+  }, [flights]);
+
+  // Flying sites, clustered from the flights' home points
+  const [sites, setSites] = useState<Site[]>([]);
+  useEffect(() => {
+    getSites()
+      .then(setSites)
+      .catch((err) => console.error('Failed to load sites:', err));
+  }, [flights]);
+
+  // Health score per battery from capacity, voltage sag and cell trends
+  const [batteryHealth, setBatteryHealth] = useState<Map<string, BatteryHealth>>(new Map());
+  useEffect(() => {
+    getBatteryHealth()
+      .then((list) => setBatteryHealth(new Map(list.map((b) => [normalizeSerial(b.serial), b]))))
+      .catch((err) => console.error('Failed to load battery health:', err));
+  }, [flights]);
+
   // Compute filtered stats
   const filteredStats = useMemo(() => {
-    const totalFlights = filteredFlights.length;
-    const totalDistanceM = filteredFlights.reduce((sum, f) => sum + (f.totalDistance ?? 0), 0);
-    const totalDurationSecs = filteredFlights.reduce((sum, f) => sum + (f.durationSecs ?? 0), 0);
-    const totalPoints = filteredFlights.reduce((sum, f) => sum + (f.pointCount ?? 0), 0);
-    const maxAltitudeM = Math.max(0, ...filteredFlights.map((f) => f.maxAltitude ?? 0));
+    const totalFlights = libraryStats?.totalFlights ?? 0;
+    const totalDistanceM = libraryStats?.totalDistanceM ?? 0;
+    const totalDurationSecs = libraryStats?.totalDurationSecs ?? 0;
+    const totalPoints = libraryStats?.totalPoints ?? 0;
+    const maxAltitudeM = libraryStats?.maxAltitudeM ?? 0;
+    const nightDurationSecs = libraryStats?.nightDurationSecs ?? 0;
 
     // Battery usage (normalize serials for consistent aggregation)
     const batteryMap = new Map<string, { count: number; duration: number }>();
-    filteredFlights.forEach((f) => {
+    statsFlights.forEach((f) => {
       const serial = normalizeSerial(f.batterySerial);
       if (serial) {
         const existing = batteryMap.get(serial) || { count: 0, duration: 0 };
@@ -91,33 +184,16 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
       }))
       .sort((a, b) => b.flightCount - a.flightCount);
 
-    // Drone usage with disambiguation for same model names (normalize serials)
+    // Drone usage with disambiguation for same model names
     const droneMap = new Map<string, { model: string; serial: string | null; name: string | null; count: number; totalDurationSecs: number }>();
-    filteredFlights.forEach((f) => {
-      const serial = normalizeSerial(f.droneSerial);
-      // Use serial as the unique key if available, otherwise fall back to model
-      const key = serial || `model:${f.droneModel ?? 'Unknown'}`;
-      const existing = droneMap.get(key);
-      if (existing) {
-        existing.count++;
-        existing.totalDurationSecs += f.durationSecs ?? 0;
-        // Keep the first non-null aircraft name encountered
-        if (!existing.name && f.aircraftName) {
-          existing.name = f.aircraftName;
-        }
-        // Prefer a more specific model name if available
-        if (f.droneModel && existing.model === 'Unknown') {
-          existing.model = f.droneModel;
-        }
-      } else {
-        droneMap.set(key, {
-          model: f.droneModel ?? 'Unknown',
-          serial: serial || null,
-          name: f.aircraftName ?? null,
-          count: 1,
-          totalDurationSecs: f.durationSecs ?? 0,
-        });
-      }
+    (libraryStats?.drones ?? []).forEach((d) => {
+      droneMap.set(d.droneSerial || `model:${d.droneModel}`, {
+        model: d.droneModel,
+        serial: d.droneSerial,
+        name: d.aircraftName,
+        count: d.flightCount,
+        totalDurationSecs: d.totalDurationSecs,
+      });
     });
 
     // Check if any display names are duplicated (using renamed names)
@@ -152,7 +228,7 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
       if (Number.isNaN(date.getTime())) return value.split('T')[0];
       return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
     };
-    filteredFlights.forEach((f) => {
+    statsFlights.forEach((f) => {
       if (f.startTime) {
         const date = toDateKey(f.startTime);
         dateMap.set(date, (dateMap.get(date) || 0) + 1);
@@ -162,10 +238,10 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
       .map(([date, count]) => ({ date, count }))
       .sort((a, b) => a.date.localeCompare(b.date));
 
-    const filteredIdSet = new Set(filteredFlights.map((f) => f.id));
+    const filteredIdSet = new Set(statsFlights.map((f) => f.id));
 
     // Top 3 longest flights
-    const topFlights = [...filteredFlights]
+    const topFlights = [...statsFlights]
       .filter((f) => f.durationSecs !== null)
       .sort((a, b) => (b.durationSecs ?? 0) - (a.durationSecs ?? 0))
       .slice(0, 3)
@@ -190,28 +266,29 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
       totalDurationSecs,
       totalPoints,
       maxAltitudeM,
+      nightDurationSecs,
       maxDistanceFromHomeM,
       batteriesUsed,
       dronesUsed,
       flightsByDate,
       topFlights,
     };
-  }, [filteredFlights, stats.maxDistanceFromHomeM, stats.topDistanceFlights, getDroneDisplayName, droneNameMap]);
+  }, [statsFlights, libraryStats, stats.maxDistanceFromHomeM, stats.topDistanceFlights, getDroneDisplayName, droneNameMap]);
 
   const filteredHealthPoints = useMemo(() => {
     if (!stats.batteryHealthPoints.length) return [] as BatteryHealthPoint[];
-    const idSet = new Set(filteredFlights.map((flight) => flight.id));
+    const idSet = new Set(statsFlights.map((flight) => flight.id));
     return stats.batteryHealthPoints.filter((point) => idSet.has(point.flightId));
-  }, [filteredFlights, stats.batteryHealthPoints]);
+  }, [statsFlights, stats.batteryHealthPoints]);
 
   const filteredTopDistanceFlights = useMemo(() => {
     if (!stats.topDistanceFlights?.length) return [] as typeof stats.topDistanceFlights;
-    const idSet = new Set(filteredFlights.map((flight) => flight.id));
+    const idSet = new Set(statsFlights.map((flight) => flight.id));
     return stats.topDistanceFlights
       .filter((flight) => idSet.has(flight.id))
       .sort((a, b) => b.maxDistanceFromHomeM - a.maxDistanceFromHomeM)
       .slice(0, 3);
-  }, [filteredFlights, stats.topDistanceFlights]);
+  }, [statsFlights, stats.topDistanceFlights]);
 
   const avgDistancePerFlight =
     filteredStats.totalFlights > 0
@@ -240,7 +317,7 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
       </div>
 
       {/* Secondary Stats */}
-        <div className="grid grid-cols-5 gap-3">
+        <div className="grid grid-cols-6 gap-3">
         <StatCard label="Max Altitude" value={formatAltitude(filteredStats.maxAltitudeM, unitSystem)} small />
         <StatCard
           label="Max Distance from Home"
@@ -250,8 +327,70 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
         <StatCard label="Avg Distance / Flight" value={formatDistance(avgDistancePerFlight, unitSystem)} small />
         <StatCard label="Avg Duration / Flight" value={formatDuration(avgDurationPerFlight)} small />
         <StatCard label="Avg Speed" value={formatSpeed(avgSpeed, unitSystem)} small />
+        <StatCard label="Night Time" value={formatDuration(filteredStats.nightDurationSecs)} small />
       </div>
 
+      {/* Trends */}
+      {filteredStats.totalFlights > 0 && (
+        <TrendsCard
+          flightIds={sidebarFilteredFlightIds ? filteredFlights.map((f) => f.id) : undefined}
+          unitSystem={unitSystem}
+        />
+      )}
+
+      {/* Personal Records */}
+      {personalRecords && (
+        <PersonalRecordsCard records={personalRecords} unitSystem={unitSystem} onSelectFlight={onSelectFlight} />
+      )}
+
+      {/* Milestones and streaks */}
+      {milestones && milestones.milestones.some((m) => m.reachedAt) && (
+        <MilestonesCard report={milestones} onSelectFlight={onSelectFlight} />
+      )}
+
+      {/* Currency */}
+      {currency && currency.drones.length > 0 && (
+        <CurrencyCard report={currency} getDroneDisplayName={getDroneDisplayName} />
+      )}
+
+      {/* Compliance */}
+      {compliance && (compliance.limits.maxHeightM !== null || compliance.limits.maxDistanceM !== null) && (
+        <ComplianceCard report={compliance} unitSystem={unitSystem} onSelectFlight={onSelectFlight} />
+      )}
+
+      {/* Energy */}
+      {energy.length > 0 && (
+        <EnergyCard drones={energy} unitSystem={unitSystem} getDroneDisplayName={getDroneDisplayName} />
+      )}
+
+      {/* Flight Modes */}
+      {modeStats && modeStats.totalSecs > 0 && <FlightModesCard stats={modeStats} />}
+
+      {/* RTH and signal loss */}
+      {rthStats && rthStats.totals.affectedFlights > 0 && (
+        <RthEventsCard
+          stats={rthStats}
+          getDroneDisplayName={getDroneDisplayName}
+          onSelectFlight={onSelectFlight}
+        />
+      )}
+
+      {/* Drone comparison */}
+      <DroneComparisonCard flights={flights} unitSystem={unitSystem} getDroneDisplayName={getDroneDisplayName} />
+
+      {/* Sites */}
+      {sites.length > 0 && (
+        <SitesCard
+          sites={sites}
+          unitSystem={unitSystem}
+          getDroneDisplayName={getDroneDisplayName}
+          onSitesChange={setSites}
+        />
+      )}
+
+      {/* Year in Review */}
+      <YearReviewCard flights={flights} unitSystem={unitSystem} />
+
       {/* Activity Heatmap + Drone Flight Time Row */}
       <div className="grid gap-4" style={{ gridTemplateColumns: '60% minmax(0, 1fr)', minHeight: '240px' }}>
         {/* Activity Heatmap */}
@@ -303,7 +442,7 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
           <DonutChart
             data={(() => {
               let short = 0, mid = 0, long = 0;
-              filteredFlights.forEach((f) => {
+              statsFlights.forEach((f) => {
                 const dur = f.durationSecs ?? 0;
                 if (dur < 600) short++;
                 else if (dur < 1200) mid++;
@@ -340,6 +479,7 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
           <BatteryHealthList
             batteries={filteredStats.batteriesUsed}
             points={filteredHealthPoints}
+            health={batteryHealth}
             isLight={resolvedTheme === 'light'}
             getBatteryDisplayName={getBatteryDisplayName}
             renameBattery={renameBattery}
@@ -463,6 +603,1022 @@ function StatCard({
 }
 
 // Milestone icon components
+function PersonalRecordsCard({
+  records,
+  unitSystem,
+  onSelectFlight,
+}: {
+  records: PersonalRecords;
+  unitSystem: UnitSystem;
+  onSelectFlight?: (flightId: number) => void;
+}) {
+  const items: { label: string; record: PersonalRecord | null; format: (value: number) => string }[] = [
+    { label: 'Longest Flight', record: records.longestDuration, format: formatDuration },
+    { label: 'Greatest Distance', record: records.longestDistance, format: (v) => formatDistance(v, unitSystem) },
+    { label: 'Highest Altitude', record: records.highestAltitude, format: (v) => formatAltitude(v, unitSystem) },
+    { label: 'Top Speed', record: records.topSpeed, format: (v) => formatSpeed(v, unitSystem) },
+    { label: 'Farthest from Home', record: records.farthestFromHome, format: (v) => formatDistance(v, unitSystem) },
+  ];
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Personal Records</h3>
+      <div className="grid grid-cols-5 gap-3">
+        {items.map(({ label, record, format }) => (
+          <button
+            key={label}
+            type="button"
+            disabled={!record}
+            onClick={() => record && onSelectFlight?.(record.flightId)}
+            className="text-left p-2 rounded-lg hover:bg-gray-700/30 transition-colors disabled:cursor-default disabled:hover:bg-transparent"
+            title={record ? `Open ${record.displayName}` : undefined}
+          >
+            <p className="text-xs text-gray-400">{label}</p>
+            <p className="text-lg font-bold text-drone-accent">{record ? format(record.value) : '—'}</p>
+            {record && (
+              <>
+                <p className="text-xs text-white truncate">{record.displayName}</p>
+                <p className="text-xs text-gray-500">{formatDateTime(record.startTime)}</p>
+              </>
+            )}
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+function milestoneLabel(milestone: Milestone): string {
+  const value = milestone.threshold.toLocaleString();
+  switch (milestone.kind) {
+    case 'flights':
+      return milestone.threshold === 1 ? 'First flight' : `${value} flights`;
+    case 'hours':
+      return `${value} ${milestone.threshold === 1 ? 'hour' : 'hours'}`;
+    default:
+      return `${value} km`;
+  }
+}
+
+function MilestonesCard({
+  report,
+  onSelectFlight,
+}: {
+  report: MilestoneReport;
+  onSelectFlight?: (flightId: number) => void;
+}) {
+  const reached = report.milestones.filter((m) => m.reachedAt).slice(-6).reverse();
+  const upcoming = report.milestones.filter((m) => !m.reachedAt);
+  const streaks = [
+    { label: 'Current Streak', streak: report.currentDailyStreak, unit: 'day' },
+    { label: 'Longest Streak', streak: report.longestDailyStreak, unit: 'day' },
+    { label: 'Current Weekly Streak', streak: report.currentWeeklyStreak, unit: 'week' },
+    { label: 'Longest Weekly Streak', streak: report.longestWeeklyStreak, unit: 'week' },
+  ];
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Milestones &amp; Streaks</h3>
+      <div className="grid grid-cols-4 gap-3 mb-4">
+        {streaks.map(({ label, streak, unit }) => (
+          <div key={label} className="p-2" title={streak ? `${streak.startDate} – ${streak.endDate}` : undefined}>
+            <p className="text-xs text-gray-400">{label}</p>
+            <p className="text-lg font-bold text-drone-accent">
+              {streak ? `${streak.length} ${unit}${streak.length === 1 ? '' : 's'}` : '—'}
+            </p>
+            {streak && (
+              <p className="text-xs text-gray-500">
+                {streak.startDate === streak.endDate ? streak.startDate : `${streak.startDate} – ${streak.endDate}`}
+              </p>
+            )}
+          </div>
+        ))}
+      </div>
+      <div className="grid gap-6" style={{ gridTemplateColumns: 'minmax(0, 1fr) minmax(0, 1fr)' }}>
+        <div>
+          <p className="text-xs text-gray-400 mb-2">Recently reached</p>
+          <div className="space-y-1">
+            {reached.map((milestone) => (
+              <button
+                key={`${milestone.kind}-${milestone.threshold}`}
+                type="button"
+                onClick={() => milestone.flightId !== null && onSelectFlight?.(milestone.flightId)}
+                className="w-full flex justify-between gap-2 text-xs px-1 py-0.5 rounded hover:bg-gray-700/30 transition-colors"
+              >
+                <span className="text-white">{milestoneLabel(milestone)}</span>
+                <span className="text-gray-500">{formatDateTime(milestone.reachedAt)}</span>
+              </button>
+            ))}
+          </div>
+        </div>
+        <div>
+          <p className="text-xs text-gray-400 mb-2">Next up</p>
+          <div className="space-y-2">
+            {upcoming.map((milestone) => (
+              <div key={`${milestone.kind}-${milestone.threshold}`}>
+                <div className="flex justify-between text-xs mb-1">
+                  <span className="text-gray-300">{milestoneLabel(milestone)}</span>
+                  <span className="text-gray-500">{Math.floor(milestone.progress * 100)}%</span>
+                </div>
+                <div className="h-1.5 rounded-full bg-gray-700/50">
+                  <div
+                    className="h-full rounded-full bg-drone-primary"
+                    style={{ width: `${Math.min(milestone.progress, 1) * 100}%` }}
+                  />
+                </div>
+              </div>
+            ))}
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
+
+function CurrencyCard({
+  report,
+  getDroneDisplayName,
+}: {
+  report: CurrencyReport;
+  getDroneDisplayName: (serial: string, fallbackName: string) => string;
+}) {
+  const label = (entry: CurrencyEntry) => {
+    const fallback = entry.name || entry.droneModel || 'Unknown';
+    return entry.droneSerial ? getDroneDisplayName(entry.droneSerial, fallback) : fallback;
+  };
+  const rows = [{ entry: report.pilot, name: report.pilot.name || 'Pilot (all aircraft)' }].concat(
+    report.drones.map((entry) => ({ entry, name: label(entry) }))
+  );
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Currency</h3>
+      <table className="w-full text-sm">
+        <thead>
+          <tr className="text-xs text-gray-400 text-left">
+            <th className="font-normal pb-2"></th>
+            {report.pilot.windows.map((window) => (
+              <th key={window.days} className="font-normal pb-2 text-right">
+                Last {window.days} days
+              </th>
+            ))}
+            <th className="font-normal pb-2 text-right">Status</th>
+          </tr>
+        </thead>
+        <tbody>
+          {rows.map(({ entry, name }, index) => (
+            <tr key={entry.droneSerial ?? `${entry.droneModel}-${index}`} className="border-t border-gray-700/50">
+              <td className={`py-1.5 truncate ${index === 0 ? 'text-white font-medium' : 'text-gray-300'}`}>{name}</td>
+              {entry.windows.map((window) => (
+                <td key={window.days} className="py-1.5 text-right text-gray-300">
+                  {window.flightCount} · {(window.durationSecs / 3600).toFixed(1)} h
+                </td>
+              ))}
+              <td className="py-1.5 text-right">
+                {entry.checks.length === 0 ? (
+                  <span className="text-xs text-gray-500">—</span>
+                ) : (
+                  <span
+                    className={`text-xs px-2 py-0.5 rounded-full ${
+                      entry.current ? 'bg-green-500/20 text-green-400' : 'bg-red-500/20 text-red-400'
+                    }`}
+                    title={entry.checks
+                      .map(
+                        (c) =>
+                          `${c.met ? '✓' : '✗'} ${c.requirement.minFlights} flights / ${c.requirement.minHours} h in ${c.requirement.windowDays} days: ${c.flightCount} flights, ${(c.durationSecs / 3600).toFixed(1)} h`
+                      )
+                      .join('\n')}
+                  >
+                    {entry.current ? 'Current' : 'Lapsed'}
+                  </span>
+                )}
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </div>
+  );
+}
+
+function ComplianceCard({
+  report,
+  unitSystem,
+  onSelectFlight,
+}: {
+  report: ComplianceReport;
+  unitSystem: UnitSystem;
+  onSelectFlight?: (flightId: number) => void;
+}) {
+  const [showAll, setShowAll] = useState(false);
+  const flights = showAll ? report.flights : report.flights.slice(0, 8);
+  const checks = [
+    { kind: 'height', label: 'Height', limit: report.limits.maxHeightM, count: report.heightViolations, format: (m: number) => formatAltitude(m, unitSystem) },
+    { kind: 'distance', label: 'Distance', limit: report.limits.maxDistanceM, count: report.distanceViolations, format: (m: number) => formatDistance(m, unitSystem) },
+  ].filter((check) => check.limit !== null);
+
+  return (
+    <div className="card p-4">
+      <div className="flex items-center justify-between mb-3">
+        <h3 className="text-sm font-semibold text-white">Compliance</h3>
+        <span className="text-xs text-gray-500">Limits can be changed in settings</span>
+      </div>
+      <div className="grid grid-cols-4 gap-3 mb-3">
+        {checks.map((check) => (
+          <div key={check.kind} className="p-2">
+            <p className="text-xs text-gray-400">
+              {check.label} over {check.format(check.limit as number)}
+            </p>
+            <p className={`text-lg font-bold ${check.count > 0 ? 'text-red-400' : 'text-green-400'}`}>
+              {check.count} / {report.flightCount}
+            </p>
+            <p className="text-xs text-gray-500">flights</p>
+          </div>
+        ))}
+      </div>
+      {report.flights.length > 0 && (
+        <>
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-xs text-gray-400 text-left">
+                <th className="font-normal pb-2">Flight</th>
+                <th className="font-normal pb-2">Date</th>
+                <th className="font-normal pb-2">Over the limit</th>
+              </tr>
+            </thead>
+            <tbody>
+              {flights.map((flight) => (
+                <tr
+                  key={flight.flightId}
+                  onClick={() => onSelectFlight?.(flight.flightId)}
+                  className="border-t border-gray-700/50 cursor-pointer hover:bg-gray-700/30"
+                >
+                  <td className="py-1.5 text-white truncate max-w-[260px]">{flight.displayName}</td>
+                  <td className="py-1.5 text-gray-400 text-xs whitespace-nowrap">{formatDateTime(flight.startTime)}</td>
+                  <td className="py-1.5 text-xs text-gray-300">
+                    {flight.segments.map((segment) => (
+                      <span
+                        key={`${segment.kind}-${segment.startMs}`}
+                        className="inline-block mr-2 px-2 py-0.5 rounded-full bg-red-500/20 text-red-400"
+                        title={`From ${formatDuration(segment.startMs / 1000)} into the flight`}
+                      >
+                        {segment.kind === 'height' ? '↑' : '↔'}{' '}
+                        {segment.kind === 'height'
+                          ? formatAltitude(segment.peakM, unitSystem)
+                          : formatDistance(segment.peakM, unitSystem)}{' '}
+                        · {formatDuration((segment.endMs - segment.startMs) / 1000)}
+                      </span>
+                    ))}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+          {report.flights.length > 8 && (
+            <button
+              type="button"
+              onClick={() => setShowAll((prev) => !prev)}
+              className="mt-2 text-xs text-gray-400 hover:text-white"
+            >
+              {showAll ? 'Show fewer' : `Show all ${report.flights.length} flights`}
+            </button>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
+
+function EnergyCard({
+  drones,
+  unitSystem,
+  getDroneDisplayName,
+}: {
+  drones: DroneEnergy[];
+  unitSystem: UnitSystem;
+  getDroneDisplayName: (serial: string, fallbackName: string) => string;
+}) {
+  const perDistance = (whPerKm: number | null) => {
+    if (whPerKm === null) return '—';
+    return unitSystem === 'imperial' ? `${(whPerKm * 1.609344).toFixed(1)} Wh/mi` : `${whPerKm.toFixed(1)} Wh/km`;
+  };
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Energy Efficiency</h3>
+      <table className="w-full text-sm">
+        <thead>
+          <tr className="text-xs text-gray-400 text-left">
+            <th className="font-normal pb-2"></th>
+            <th className="font-normal pb-2 text-right">Flights</th>
+            <th className="font-normal pb-2 text-right">Energy</th>
+            <th className="font-normal pb-2 text-right">Per distance</th>
+            <th className="font-normal pb-2 text-right">Per minute</th>
+          </tr>
+        </thead>
+        <tbody>
+          {drones.map((drone) => (
+            <tr key={drone.droneSerial ?? drone.droneModel} className="border-t border-gray-700/50">
+              <td className="py-1.5 truncate text-gray-300">
+                {drone.droneSerial ? getDroneDisplayName(drone.droneSerial, drone.droneModel) : drone.droneModel}
+              </td>
+              <td className="py-1.5 text-right text-gray-300">{drone.flightCount}</td>
+              <td className="py-1.5 text-right text-gray-300">{drone.energyWh.toFixed(0)} Wh</td>
+              <td className="py-1.5 text-right text-gray-300">{perDistance(drone.whPerKm)}</td>
+              <td className="py-1.5 text-right text-gray-300">
+                {drone.whPerMin === null ? '—' : `${drone.whPerMin.toFixed(2)} Wh/min`}
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </div>
+  );
+}
+
+function FlightModesCard({ stats }: { stats: FlightModeStats }) {
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Flight Modes</h3>
+      <div className="space-y-2">
+        {stats.categories.map((category) => {
+          const percent = (category.durationSecs / stats.totalSecs) * 100;
+          const modes = stats.modes.filter((m) => m.category === category.category);
+          return (
+            <div
+              key={category.category}
+              className="grid items-center gap-2 text-xs"
+              style={{ gridTemplateColumns: '120px 1fr 48px 160px' }}
+              title={modes.map((m) => `${m.mode}: ${formatDuration(m.durationSecs)}`).join('\n')}
+            >
+              <span className="text-gray-300 text-right truncate">{category.category}</span>
+              <div className="relative h-2 bg-gray-700/50 rounded-full overflow-hidden">
+                <div
+                  className="absolute inset-y-0 left-0 rounded-full"
+                  style={{ width: `${percent}%`, backgroundColor: flightModeColor(category.category) }}
+                />
+              </div>
+              <span className="text-gray-400 text-right" style={{ fontVariantNumeric: 'tabular-nums' }}>
+                {percent.toFixed(1)}%
+              </span>
+              <span className="text-gray-400 text-[10px] truncate">
+                {formatDuration(category.durationSecs)} · {category.flightCount} flight{category.flightCount === 1 ? '' : 's'}
+              </span>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
+
+type TrendMetric = 'flights' | 'hours' | 'distance';
+
+function TrendsCard({ flightIds, unitSystem }: { flightIds?: number[]; unitSystem: UnitSystem }) {
+  const [bucket, setBucket] = useState<TrendBucket>('month');
+  const [metric, setMetric] = useState<TrendMetric>('flights');
+  const [points, setPoints] = useState<TrendPoint[]>([]);
+
+  // Bucketed by the backend, so only one row per period crosses over
+  const idsKey = flightIds ? flightIds.join(',') : null;
+  useEffect(() => {
+    let cancelled = false;
+    getTrends(bucket, flightIds)
+      .then((result) => {
+        if (!cancelled) setPoints(result);
+      })
+      .catch((err) => console.error('Failed to load trends:', err));
+    return () => {
+      cancelled = true;
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [bucket, idsKey]);
+
+  const imperial = unitSystem === 'imperial';
+  const value = (point: TrendPoint) => {
+    if (metric === 'flights') return point.flightCount;
+    if (metric === 'hours') return Number((point.totalDurationSecs / 3600).toFixed(2));
+    return Number((point.totalDistanceM / (imperial ? 1609.344 : 1000)).toFixed(2));
+  };
+  const label = (period: string) => {
+    if (bucket === 'year') return period.slice(0, 4);
+    if (bucket === 'month') return period.slice(0, 7);
+    return period;
+  };
+  const metricName = { flights: 'Flights', hours: 'Hours', distance: imperial ? 'Miles' : 'Kilometers' }[metric];
+
+  const chartOption = {
+    tooltip: {
+      trigger: 'axis' as const,
+      backgroundColor: 'rgba(22, 33, 62, 0.95)',
+      borderColor: '#374151',
+      textStyle: { color: '#e5e7eb' },
+    },
+    grid: { left: 40, right: 10, top: 10, bottom: 20 },
+    xAxis: {
+      type: 'category' as const,
+      data: points.map((p) => label(p.period)),
+      axisLabel: { color: '#9ca3af', fontSize: 10 },
+    },
+    yAxis: {
+      type: 'value' as const,
+      minInterval: metric === 'flights' ? 1 : undefined,
+      axisLabel: { color: '#9ca3af', fontSize: 10 },
+      splitLine: { lineStyle: { color: 'rgba(75, 85, 99, 0.3)' } },
+    },
+    series: [
+      {
+        name: metricName,
+        type: 'bar' as const,
+        itemStyle: { color: '#00a0dc' },
+        data: points.map(value),
+      },
+    ],
+  };
+
+  return (
+    <div className="card p-4">
+      <div className="flex items-center justify-between mb-3">
+        <h3 className="text-sm font-semibold text-white">Trends</h3>
+        <div className="flex items-center gap-2">
+          <select
+            value={metric}
+            onChange={(e) => setMetric(e.target.value as TrendMetric)}
+            className="input h-6 text-xs px-1 py-0"
+          >
+            <option value="flights">Flights</option>
+            <option value="hours">Hours</option>
+            <option value="distance">Distance</option>
+          </select>
+          <select
+            value={bucket}
+            onChange={(e) => setBucket(e.target.value as TrendBucket)}
+            className="input h-6 text-xs px-1 py-0"
+          >
+            <option value="week">Per week</option>
+            <option value="month">Per month</option>
+            <option value="year">Per year</option>
+          </select>
+        </div>
+      </div>
+      <div className="h-[220px]">
+        <ReactECharts option={chartOption} style={{ height: '100%' }} notMerge />
+      </div>
+    </div>
+  );
+}
+
+function RthEventsCard({
+  stats,
+  getDroneDisplayName,
+  onSelectFlight,
+}: {
+  stats: RthStats;
+  getDroneDisplayName: (serial: string, fallbackName: string) => string;
+  onSelectFlight?: (flightId: number) => void;
+}) {
+  const [selectedKey, setSelectedKey] = useState<string | null>(null);
+  const droneKey = (drone: DroneRthEvents) => drone.droneSerial ?? `model:${drone.droneModel}`;
+  const droneName = (model: string | null, serial: string | null) =>
+    serial ? getDroneDisplayName(serial, model || 'Unknown') : model || 'Unknown';
+  const selected = stats.drones.find((d) => droneKey(d) === selectedKey) ?? null;
+  const months = selected ? selected.months : stats.months;
+
+  const chartOption = {
+    tooltip: {
+      trigger: 'axis' as const,
+      backgroundColor: 'rgba(22, 33, 62, 0.95)',
+      borderColor: '#374151',
+      textStyle: { color: '#e5e7eb' },
+    },
+    legend: { top: 0, textStyle: { color: '#9ca3af', fontSize: 11 } },
+    grid: { left: 30, right: 10, top: 30, bottom: 20 },
+    xAxis: {
+      type: 'category' as const,
+      data: months.map((m) => m.month),
+      axisLabel: { color: '#9ca3af', fontSize: 10 },
+    },
+    yAxis: {
+      type: 'value' as const,
+      minInterval: 1,
+      axisLabel: { color: '#9ca3af', fontSize: 10 },
+      splitLine: { lineStyle: { color: 'rgba(75, 85, 99, 0.3)' } },
+    },
+    series: [
+      { name: 'RTH', key: 'rthCount', color: '#f59e0b' },
+      { name: 'Failsafe', key: 'failsafeCount', color: '#ef4444' },
+      { name: 'Signal loss', key: 'signalLossCount', color: '#8b5cf6' },
+    ].map(({ name, key, color }) => ({
+      name,
+      type: 'bar' as const,
+      stack: 'events',
+      itemStyle: { color },
+      data: months.map((m) => m.counts[key as 'rthCount' | 'failsafeCount' | 'signalLossCount']),
+    })),
+  };
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">RTH &amp; Signal Loss</h3>
+      <div className="grid gap-6" style={{ gridTemplateColumns: 'minmax(0, 1fr) minmax(0, 1fr)' }}>
+        <div>
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-xs text-gray-400 text-left">
+                <th className="font-normal pb-2">Aircraft</th>
+                <th className="font-normal pb-2 text-right">Flights</th>
+                <th className="font-normal pb-2 text-right">RTH</th>
+                <th className="font-normal pb-2 text-right" title="RTH triggers per 10 flights">RTH / 10</th>
+                <th className="font-normal pb-2 text-right">Failsafe</th>
+                <th className="font-normal pb-2 text-right">Signal loss</th>
+              </tr>
+            </thead>
+            <tbody>
+              {stats.drones.map((drone) => {
+                const key = droneKey(drone);
+                return (
+                  <tr
+                    key={key}
+                    onClick={() => setSelectedKey(key === selectedKey ? null : key)}
+                    className={`border-t border-gray-700/50 cursor-pointer hover:bg-gray-700/30 ${
+                      key === selectedKey ? 'bg-gray-700/40' : ''
+                    }`}
+                    title={`${drone.counts.affectedFlights} of ${drone.counts.flightCount} flights with events`}
+                  >
+                    <td className="py-1.5 text-gray-300 truncate">{droneName(drone.droneModel, drone.droneSerial)}</td>
+                    <td className="py-1.5 text-right text-gray-300">{drone.counts.flightCount}</td>
+                    <td className="py-1.5 text-right text-gray-300">{drone.counts.rthCount}</td>
+                    <td className="py-1.5 text-right text-drone-accent">
+                      {((drone.counts.rthCount / Math.max(drone.counts.flightCount, 1)) * 10).toFixed(1)}
+                    </td>
+                    <td className="py-1.5 text-right text-gray-300">{drone.counts.failsafeCount}</td>
+                    <td className="py-1.5 text-right text-gray-300">{drone.counts.signalLossCount}</td>
+                  </tr>
+                );
+              })}
+            </tbody>
+          </table>
+          <p className="text-xs text-gray-400 mt-4 mb-2">Recent flights with events</p>
+          <div className="space-y-1">
+            {stats.flights.slice(0, 5).map((flight) => (
+              <button
+                key={flight.flightId}
+                type="button"
+                onClick={() => onSelectFlight?.(flight.flightId)}
+                className="w-full flex justify-between gap-2 text-xs px-1 py-0.5 rounded hover:bg-gray-700/30 transition-colors"
+              >
+                <span className="text-white truncate">{flight.displayName}</span>
+                <span className="text-gray-500 whitespace-nowrap">
+                  {[
+                    flight.rthCount > 0 && `${flight.rthCount} RTH`,
+                    flight.failsafeCount > 0 && `${flight.failsafeCount} failsafe`,
+                    flight.signalLossCount > 0 && `${flight.signalLossCount} signal loss`,
+                  ]
+                    .filter(Boolean)
+                    .join(' · ')}{' '}
+                  · {formatDateTime(flight.startTime)}
+                </span>
+              </button>
+            ))}
+          </div>
+        </div>
+        <div>
+          <p className="text-xs text-gray-400 mb-1">
+            Per month · {selected ? droneName(selected.droneModel, selected.droneSerial) : 'All aircraft'}
+          </p>
+          <div className="h-[240px]">
+            <ReactECharts option={chartOption} style={{ height: '100%' }} notMerge />
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
+
+function DroneComparisonCard({
+  flights,
+  unitSystem,
+  getDroneDisplayName,
+}: {
+  flights: Flight[];
+  unitSystem: UnitSystem;
+  getDroneDisplayName: (serial: string, fallbackName: string) => string;
+}) {
+  // Each aircraft by serial, most flown first, then each model flown by more than one aircraft
+  const options = useMemo(() => {
+    const aircraft = new Map<string, { label: string; count: number }>();
+    const models = new Map<string, Set<string>>();
+    flights.forEach((flight) => {
+      const serial = flight.droneSerial?.trim().toUpperCase();
+      const model = flight.droneModel ?? 'Unknown';
+      if (serial) {
+        const entry = aircraft.get(serial) ?? { label: getDroneDisplayName(serial, model), count: 0 };
+        entry.count += 1;
+        aircraft.set(serial, entry);
+      }
+      if (flight.droneModel) {
+        const serials = models.get(flight.droneModel) ?? new Set<string>();
+        serials.add(serial ?? '');
+        models.set(flight.droneModel, serials);
+      }
+    });
+    return [
+      ...Array.from(aircraft.entries())
+        .sort((a, b) => b[1].count - a[1].count)
+        .map(([serial, { label }]) => ({ value: serial, label })),
+      ...Array.from(models.entries())
+        .filter(([, serials]) => serials.size > 1 || aircraft.size === 0)
+        .map(([model]) => ({ value: model, label: `All ${model}` })),
+    ];
+  }, [flights, getDroneDisplayName]);
+  const [first, setFirst] = useState<string | null>(null);
+  const [second, setSecond] = useState<string | null>(null);
+  const [comparison, setComparison] = useState<DroneComparison | null>(null);
+  const values = options.map((o) => o.value);
+  const selectedFirst = first !== null && values.includes(first) ? first : values[0] ?? null;
+  const selectedSecond = second !== null && values.includes(second) ? second : values.find((v) => v !== selectedFirst) ?? null;
+
+  useEffect(() => {
+    if (selectedFirst === null || selectedSecond === null) return;
+    compareDrones(selectedFirst, selectedSecond)
+      .then(setComparison)
+      .catch((err) => console.error('Failed to compare drones:', err));
+  }, [selectedFirst, selectedSecond, flights]);
+
+  if (
+    selectedFirst === null ||
+    selectedSecond === null ||
+    !comparison ||
+    comparison.first.drone !== selectedFirst ||
+    comparison.second.drone !== selectedSecond
+  ) {
+    return null;
+  }
+
+  const perDistance = (whPerKm: number | null) => {
+    if (whPerKm === null) return '—';
+    return unitSystem === 'imperial' ? `${(whPerKm * 1.609344).toFixed(1)} Wh/mi` : `${whPerKm.toFixed(1)} Wh/km`;
+  };
+  const rows: { label: string; value: (side: DroneComparisonStats) => string }[] = [
+    { label: 'Flights', value: (side) => side.flightCount.toLocaleString() },
+    { label: 'Flight time', value: (side) => formatDuration(side.totalDurationSecs) },
+    { label: 'Distance', value: (side) => formatDistance(side.totalDistanceM, unitSystem) },
+    { label: 'Avg flight time', value: (side) => formatDuration(side.avgDurationSecs) },
+    { label: 'Avg flight distance', value: (side) => formatDistance(side.avgDistanceM, unitSystem) },
+    { label: 'Max altitude', value: (side) => formatAltitude(side.maxAltitudeM, unitSystem) },
+    { label: 'Energy per distance', value: (side) => perDistance(side.whPerKm) },
+    { label: 'Energy per minute', value: (side) => (side.whPerMin === null ? '—' : `${side.whPerMin.toFixed(2)} Wh/min`) },
+    {
+      label: 'Avg wind',
+      value: (side) =>
+        side.avgWindSpeed === null
+          ? '—'
+          : `${formatSpeed(side.avgWindSpeed, unitSystem)} (${side.windFlightCount} flight${side.windFlightCount === 1 ? '' : 's'})`,
+    },
+    { label: 'RTH triggers', value: (side) => side.safetyEvents.rthCount.toLocaleString() },
+    { label: 'Failsafes', value: (side) => side.safetyEvents.failsafeCount.toLocaleString() },
+    { label: 'Signal losses', value: (side) => side.safetyEvents.signalLossCount.toLocaleString() },
+    { label: 'Reported incidents', value: (side) => side.incidentCount.toLocaleString() },
+  ];
+  const picker = (value: string, onChange: (value: string) => void) => (
+    <select value={value} onChange={(e) => onChange(e.target.value)} className="input h-6 text-xs px-1 py-0 max-w-[180px]">
+      {options.map((option) => (
+        <option key={option.value} value={option.value}>
+          {option.label}
+        </option>
+      ))}
+    </select>
+  );
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Compare Aircraft</h3>
+      <table className="w-full text-sm">
+        <thead>
+          <tr className="text-xs text-gray-400 text-left">
+            <th className="font-normal pb-2"></th>
+            <th className="font-normal pb-2 text-right">{picker(selectedFirst, setFirst)}</th>
+            <th className="font-normal pb-2 text-right">{picker(selectedSecond, setSecond)}</th>
+          </tr>
+        </thead>
+        <tbody>
+          {rows.map((row) => (
+            <tr key={row.label} className="border-t border-gray-700/50">
+              <td className="py-1.5 text-gray-400">{row.label}</td>
+              <td className="py-1.5 text-right text-gray-300">{row.value(comparison.first)}</td>
+              <td className="py-1.5 text-right text-gray-300">{row.value(comparison.second)}</td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </div>
+  );
+}
+
+function SitesCard({
+  sites,
+  unitSystem,
+  getDroneDisplayName,
+  onSitesChange,
+}: {
+  sites: Site[];
+  unitSystem: UnitSystem;
+  getDroneDisplayName: (serial: string, fallbackName: string) => string;
+  onSitesChange: (sites: Site[]) => void;
+}) {
+  const [editingId, setEditingId] = useState<number | null>(null);
+  const [draftName, setDraftName] = useState('');
+  const [isClustering, setIsClustering] = useState(false);
+  const [siteStats, setSiteStats] = useState<Map<number, SiteStats>>(new Map());
+
+  useEffect(() => {
+    getSiteStats()
+      .then((stats) => setSiteStats(new Map(stats.map((s) => [s.site.id, s]))))
+      .catch((err) => console.error('Failed to load site statistics:', err));
+  }, [sites]);
+
+  const monthName = (month: number | null) =>
+    month === null ? '—' : new Date(2000, month - 1, 1).toLocaleString(undefined, { month: 'short' });
+  const droneName = (drone: DroneUsage) =>
+    drone.droneSerial ? getDroneDisplayName(drone.droneSerial, drone.droneModel) : drone.droneModel;
+
+  const handleRecluster = async () => {
+    setIsClustering(true);
+    try {
+      onSitesChange(await reclusterSites());
+    } catch (err) {
+      console.error('Failed to cluster sites:', err);
+    } finally {
+      setIsClustering(false);
+    }
+  };
+
+  const handleSaveRename = async (site: Site) => {
+    const name = draftName.trim();
+    setEditingId(null);
+    if (!name || name === site.name) return;
+    try {
+      await renameSite(site.id, name);
+      onSitesChange(sites.map((s) => (s.id === site.id ? { ...s, name, customName: true } : s)));
+    } catch (err) {
+      console.error('Failed to rename site:', err);
+    }
+  };
+
+  return (
+    <div className="card p-4">
+      <div className="flex items-center justify-between mb-3">
+        <h3 className="text-sm font-semibold text-white">Flying Sites</h3>
+        <button
+          onClick={handleRecluster}
+          disabled={isClustering}
+          className="text-xs text-gray-400 hover:text-white disabled:opacity-50"
+          title="Group all flights by home point again"
+        >
+          {isClustering ? 'Clustering…' : 'Recluster'}
+        </button>
+      </div>
+      <div className="max-h-72 overflow-y-auto">
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="text-xs text-gray-400 text-left">
+              <th className="font-normal pb-2"></th>
+              <th className="font-normal pb-2 text-right">Flights</th>
+              <th className="font-normal pb-2 text-right">Days</th>
+              <th className="font-normal pb-2 text-right">Time</th>
+              <th className="font-normal pb-2 text-right" title="Median of the flights' mean wind speed">Typical wind</th>
+              <th className="font-normal pb-2 text-right" title="Month of the year with the lowest mean wind">Calmest</th>
+              <th className="font-normal pb-2 text-right" title="Month of the year with the most flight time">Busiest</th>
+              <th className="font-normal pb-2">Aircraft</th>
+              <th className="font-normal pb-2 text-right">Last flown</th>
+            </tr>
+          </thead>
+          <tbody>
+            {sites.map((site) => {
+              const stats = siteStats.get(site.id);
+              return (
+                <tr key={site.id} className="border-t border-gray-700/50">
+                  <td className="py-1.5 text-gray-300">
+                    {editingId === site.id ? (
+                      <input
+                        value={draftName}
+                        onChange={(e) => setDraftName(e.target.value)}
+                        onKeyDown={(e) => {
+                          if (e.key === 'Enter') handleSaveRename(site);
+                          if (e.key === 'Escape') setEditingId(null);
+                        }}
+                        onBlur={() => handleSaveRename(site)}
+                        className="input h-6 text-xs px-2 w-full"
+                        placeholder="Site name"
+                        autoFocus
+                      />
+                    ) : (
+                      <button
+                        onClick={() => {
+                          setEditingId(site.id);
+                          setDraftName(site.name);
+                        }}
+                        className="truncate text-left hover:text-white"
+                        title={`${site.centerLat.toFixed(5)}, ${site.centerLon.toFixed(5)} · click to rename`}
+                      >
+                        {site.name}
+                      </button>
+                    )}
+                  </td>
+                  <td className="py-1.5 text-right text-gray-300">{site.flightCount}</td>
+                  <td className="py-1.5 text-right text-gray-300">{site.visitCount}</td>
+                  <td className="py-1.5 text-right text-gray-300">{formatDuration(site.durationSecs)}</td>
+                  <td
+                    className="py-1.5 text-right text-gray-300"
+                    title={stats?.maxWindSpeed != null ? `Windiest flight: ${formatSpeed(stats.maxWindSpeed, unitSystem)}` : undefined}
+                  >
+                    {stats?.typicalWindSpeed != null ? formatSpeed(stats.typicalWindSpeed, unitSystem) : '—'}
+                  </td>
+                  <td className="py-1.5 text-right text-gray-300">{monthName(stats?.calmestMonth ?? null)}</td>
+                  <td className="py-1.5 text-right text-gray-300">{monthName(stats?.busiestMonth ?? null)}</td>
+                  <td
+                    className="py-1.5 pl-3 text-gray-400 text-xs truncate max-w-[160px]"
+                    title={stats?.drones.map((d) => `${droneName(d)}: ${d.flightCount} flight${d.flightCount === 1 ? '' : 's'}`).join('\n')}
+                  >
+                    {stats && stats.drones.length > 0
+                      ? `${droneName(stats.drones[0])}${stats.drones.length > 1 ? ` +${stats.drones.length - 1}` : ''}`
+                      : '—'}
+                  </td>
+                  <td className="py-1.5 text-right text-gray-400 text-xs">
+                    {site.lastFlight ? formatDateTime(site.lastFlight) : '—'}
+                  </td>
+                </tr>
+              );
+            })}
+          </tbody>
+        </table>
+      </div>
+    </div>
+  );
+}
+
+const RECORD_LABELS: Record<keyof PersonalRecords, string> = {
+  longestDuration: 'Longest flight',
+  longestDistance: 'Longest distance',
+  highestAltitude: 'Highest altitude',
+  topSpeed: 'Top speed',
+  farthestFromHome: 'Farthest from home',
+};
+
+function YearReviewCard({ flights, unitSystem }: { flights: Flight[]; unitSystem: UnitSystem }) {
+  const years = useMemo(() => {
+    const set = new Set<number>();
+    flights.forEach((flight) => {
+      if (flight.startTime) set.add(new Date(flight.startTime).getUTCFullYear());
+    });
+    return Array.from(set).filter(Number.isFinite).sort((a, b) => b - a);
+  }, [flights]);
+  const [year, setYear] = useState<number | null>(null);
+  const [review, setReview] = useState<YearReview | null>(null);
+  const [isExporting, setIsExporting] = useState(false);
+  const selectedYear = year !== null && years.includes(year) ? year : years[0] ?? null;
+
+  useEffect(() => {
+    if (selectedYear === null) return;
+    getYearReview(selectedYear)
+      .then(setReview)
+      .catch((err) => console.error('Failed to load year in review:', err));
+  }, [selectedYear, flights]);
+
+  if (selectedYear === null || !review || review.year !== selectedYear) return null;
+
+  const handleExportPdf = async () => {
+    setIsExporting(true);
+    try {
+      await exportYearReviewPdf(
+        review.year,
+        `${review.year}_in_review.pdf`,
+        unitSystem === 'imperial' ? { length: 'ft', speed: 'mph' } : {}
+      );
+    } catch (err) {
+      console.error('Failed to export year in review:', err);
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  const handleExportJson = async () => {
+    const fileName = `${review.year}_in_review.json`;
+    const content = JSON.stringify(review, null, 2);
+    try {
+      if (isWebMode()) {
+        downloadFile(fileName, content, 'application/json');
+      } else {
+        const { save } = await import('@tauri-apps/plugin-dialog');
+        const { writeTextFile } = await import('@tauri-apps/plugin-fs');
+        const filePath = await save({
+          defaultPath: fileName,
+          filters: [{ name: 'JSON', extensions: ['json'] }],
+        });
+        if (!filePath) return;
+        await writeTextFile(filePath, content);
+      }
+    } catch (err) {
+      console.error('Failed to export year in review:', err);
+    }
+  };
+
+  const topDrone = review.stats.drones[0];
+  const topBattery = review.stats.batteries[0];
+  const maxMonthSecs = Math.max(...review.months.map((m) => m.totalDurationSecs), 1);
+  const monthLetters = 'JFMAMJJASOND';
+
+  return (
+    <div className="card p-4">
+      <div className="flex items-center justify-between mb-3">
+        <div className="flex items-center gap-2">
+          <h3 className="text-sm font-semibold text-white">Year in Review</h3>
+          <select
+            value={selectedYear}
+            onChange={(e) => setYear(Number(e.target.value))}
+            className="input h-6 text-xs px-1 py-0"
+          >
+            {years.map((y) => (
+              <option key={y} value={y}>
+                {y}
+              </option>
+            ))}
+          </select>
+        </div>
+        <div className="flex items-center gap-3">
+          <button onClick={handleExportJson} className="text-xs text-gray-400 hover:text-white">
+            Save JSON
+          </button>
+          <button
+            onClick={handleExportPdf}
+            disabled={isExporting}
+            className="text-xs text-gray-400 hover:text-white disabled:opacity-50"
+          >
+            {isExporting ? 'Saving…' : 'Save PDF'}
+          </button>
+        </div>
+      </div>
+
+      <div className="grid grid-cols-4 gap-3 mb-4">
+        <StatCard label="Flights" value={review.stats.totalFlights.toLocaleString()} small />
+        <StatCard label="Flight Time" value={formatDuration(review.stats.totalDurationSecs)} small />
+        <StatCard label="Distance" value={formatDistance(review.stats.totalDistanceM, unitSystem)} small />
+        <StatCard label="Days Flown" value={review.stats.daysFlown.toLocaleString()} small />
+      </div>
+
+      <div className="grid gap-6" style={{ gridTemplateColumns: 'minmax(0, 1fr) minmax(0, 1fr)' }}>
+        <div>
+          <p className="text-xs text-gray-400 mb-2">Flight time per month</p>
+          <div className="flex items-end gap-1 h-24">
+            {review.months.map((month, i) => (
+              <div
+                key={month.month}
+                className="flex-1 flex flex-col items-center justify-end h-full"
+                title={`${month.month}: ${month.flightCount} flights, ${formatDuration(month.totalDurationSecs)}`}
+              >
+                <div
+                  className="w-full rounded-sm bg-drone-primary"
+                  style={{ height: `${(month.totalDurationSecs / maxMonthSecs) * 100}%` }}
+                />
+                <span className="text-[10px] text-gray-500 mt-1">{monthLetters[i]}</span>
+              </div>
+            ))}
+          </div>
+        </div>
+        <div className="space-y-1.5 text-xs">
+          <div className="flex justify-between gap-2">
+            <span className="text-gray-400">Most flown aircraft</span>
+            <span className="text-gray-200 truncate">
+              {topDrone ? topDrone.nickname || topDrone.aircraftName || topDrone.droneModel : '—'}
+            </span>
+          </div>
+          <div className="flex justify-between gap-2">
+            <span className="text-gray-400">Most used battery</span>
+            <span className="text-gray-200 truncate">{topBattery ? topBattery.batterySerial : '—'}</span>
+          </div>
+          <div className="flex justify-between gap-2">
+            <span className="text-gray-400">New sites</span>
+            <span
+              className="text-gray-200 truncate"
+              title={review.newSites.map((site) => site.name).join('\n')}
+            >
+              {review.newSites.length}
+            </span>
+          </div>
+          <div className="flex justify-between gap-2">
+            <span className="text-gray-400">All-time records set</span>
+            <span className="text-gray-200 truncate text-right">
+              {review.recordsSet.length > 0
+                ? review.recordsSet.map((key) => RECORD_LABELS[key]).join(', ')
+                : '—'}
+            </span>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
+
 function MilestoneIconBeginner() {
   return (
     <svg className="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -1273,6 +2429,7 @@ function DonutChart({
 function BatteryHealthList({
   batteries,
   points,
+  health,
   isLight,
   getBatteryDisplayName,
   renameBattery,
@@ -1280,6 +2437,8 @@ function BatteryHealthList({
 }: {
   batteries: { batterySerial: string; flightCount: number; totalDurationSecs: number }[];
   points: BatteryHealthPoint[];
+  /** Backend health trends keyed by normalized serial */
+  health: Map<string, BatteryHealth>;
   isLight: boolean;
   getBatteryDisplayName: (serial: string) => string;
   renameBattery: (serial: string, displayName: string) => void;
@@ -1288,12 +2447,13 @@ function BatteryHealthList({
   const [editingSerial, setEditingSerial] = useState<string | null>(null);
   const [draftName, setDraftName] = useState('');
   const [renameError, setRenameError] = useState<string | null>(null);
+  const [curveSerial, setCurveSerial] = useState<string | null>(null);
 
   if (batteries.length === 0) {
     return <p className="text-sm text-gray-400">No battery data available.</p>;
   }
 
-  // Estimate health based on flight count (assuming 400 cycles = end of life)
+  // Without a backend trend, estimate health from flight count (assuming 400 cycles = end of life)
   const maxCycles = 400;
 
   const handleStartRename = (serial: string) => {
@@ -1497,7 +2657,21 @@ function BatteryHealthList({
     <div className="space-y-4">
       <div className="space-y-2 max-h-[200px] overflow-y-auto" style={{ padding: '0 16px 0 10px' }}>
         {batteries.map((battery) => {
-          const healthPercent = Math.max(0, 100 - (battery.flightCount / maxCycles) * 100);
+          const trend = health.get(battery.batterySerial);
+          const healthPercent = trend
+            ? trend.healthScore
+            : Math.max(0, 100 - (battery.flightCount / maxCycles) * 100);
+          const trendDetails = trend
+            ? [
+                trend.capacityFadePercent !== null && `Capacity fade: ${trend.capacityFadePercent.toFixed(1)}%`,
+                trend.voltageSagChangeV !== null &&
+                  `Voltage sag change: ${trend.voltageSagChangeV >= 0 ? '+' : ''}${(trend.voltageSagChangeV * 1000).toFixed(0)} mV`,
+                trend.recentCellImbalanceV !== null &&
+                  `Cell imbalance: ${(trend.recentCellImbalanceV * 1000).toFixed(0)} mV`,
+              ]
+                .filter(Boolean)
+                .join('\n') || 'Not enough data for a trend yet'
+            : 'Estimated from flight count';
           const healthColor =
             healthPercent > 70 ? '#10b981' : healthPercent > 40 ? '#f59e0b' : '#ef4444';
           const displayName = getBatteryDisplayName(battery.batterySerial);
@@ -1566,12 +2740,20 @@ function BatteryHealthList({
                       }}
                     />
                   </div>
-                  <span className="text-[10px] text-gray-500 text-right" style={{ fontVariantNumeric: 'tabular-nums', minWidth: '32px' }}>
+                  <span
+                    className="text-[10px] text-gray-500 text-right"
+                    style={{ fontVariantNumeric: 'tabular-nums', minWidth: '32px' }}
+                    title={trendDetails}
+                  >
                     {healthPercent.toFixed(0)}%
                   </span>
-                  <span className="text-gray-400 text-[10px] text-left truncate">
+                  <button
+                    onClick={() => setCurveSerial(curveSerial === battery.batterySerial ? null : battery.batterySerial)}
+                    className={`text-[10px] text-left truncate ${curveSerial === battery.batterySerial ? 'text-drone-primary' : 'text-gray-400 hover:text-gray-300'}`}
+                    title="Compare discharge curves"
+                  >
                     {battery.flightCount} flights · {formatDuration(battery.totalDurationSecs)}
-                  </span>
+                  </button>
                 </div>
               )}
             </div>
@@ -1588,6 +2770,135 @@ function BatteryHealthList({
       ) : (
         <p className="text-xs text-gray-500">No battery usage points available.</p>
       )}
+
+      {curveSerial && (
+        <DischargeCurvesChart
+          serial={curveSerial}
+          displayName={getBatteryDisplayName(curveSerial)}
+          isLight={isLight}
+        />
+      )}
+    </div>
+  );
+}
+
+/** Discharge of every flight of one battery, colored from oldest (blue) to newest (red) */
+function DischargeCurvesChart({
+  serial,
+  displayName,
+  isLight,
+}: {
+  serial: string;
+  displayName: string;
+  isLight: boolean;
+}) {
+  const [curves, setCurves] = useState<DischargeCurve[] | null>(null);
+  const [mode, setMode] = useState<'time' | 'voltage'>('time');
+
+  useEffect(() => {
+    let cancelled = false;
+    setCurves(null);
+    getDischargeCurves(serial)
+      .then((result) => {
+        if (!cancelled) setCurves(result);
+      })
+      .catch((err) => {
+        console.error('Failed to load discharge curves:', err);
+        if (!cancelled) setCurves([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [serial]);
+
+  const axisLineColor = isLight ? '#cbd5f5' : '#374151';
+  const splitLineColor = isLight ? '#e2e8f0' : '#1f2937';
+  const axisLabelColor = isLight ? '#475569' : '#9ca3af';
+  const tooltipStyle = isLight
+    ? { background: '#ffffff', border: '#e2e8f0', text: '#0f172a' }
+    : { background: 'rgba(22, 33, 62, 0.95)', border: '#374151', text: '#e5e7eb' };
+
+  const shown = (curves ?? []).filter((c) =>
+    mode === 'time' ? c.percentByMinute.length > 1 : c.voltageByPercent.length > 1
+  );
+  const series = shown.map((curve, i) => {
+    const age = shown.length > 1 ? i / (shown.length - 1) : 1;
+    const date = curve.startTime ? new Date(curve.startTime).toLocaleDateString() : 'Unknown date';
+    return {
+      name: `${curve.displayName} (${date})`,
+      type: 'line' as const,
+      showSymbol: false,
+      lineStyle: { width: 1.5 },
+      color: `hsl(${Math.round(210 - 210 * age)}, 75%, 55%)`,
+      data: mode === 'time' ? curve.percentByMinute : curve.voltageByPercent,
+    };
+  });
+
+  const option = {
+    tooltip: {
+      trigger: 'item' as const,
+      backgroundColor: tooltipStyle.background,
+      borderColor: tooltipStyle.border,
+      textStyle: { color: tooltipStyle.text },
+      formatter: (params: { seriesName: string; value: [number, number] }) =>
+        mode === 'time'
+          ? `${params.seriesName}<br/>${params.value[0].toFixed(1)} min: ${params.value[1]}%`
+          : `${params.seriesName}<br/>${params.value[0]}%: ${params.value[1].toFixed(2)} V`,
+    },
+    grid: { left: 16, right: 16, top: 24, bottom: 8, containLabel: true },
+    xAxis: {
+      type: 'value' as const,
+      name: mode === 'time' ? 'min' : '%',
+      inverse: mode === 'voltage',
+      min: mode === 'voltage' ? 0 : undefined,
+      max: mode === 'voltage' ? 100 : undefined,
+      nameTextStyle: { color: axisLabelColor, fontSize: 10 },
+      axisLine: { lineStyle: { color: axisLineColor } },
+      axisLabel: { color: axisLabelColor, fontSize: 10 },
+      splitLine: { lineStyle: { color: splitLineColor } },
+    },
+    yAxis: {
+      type: 'value' as const,
+      name: mode === 'time' ? '%' : 'V',
+      scale: mode === 'voltage',
+      min: mode === 'time' ? 0 : undefined,
+      max: mode === 'time' ? 100 : undefined,
+      nameTextStyle: { color: axisLabelColor, fontSize: 10 },
+      axisLine: { lineStyle: { color: axisLineColor } },
+      axisLabel: { color: axisLabelColor, fontSize: 10 },
+      splitLine: { lineStyle: { color: splitLineColor } },
+    },
+    series,
+  };
+
+  return (
+    <div>
+      <div className="flex items-center justify-between mb-1">
+        <span className="text-xs text-gray-400 truncate">Discharge curves · {displayName}</span>
+        <div className="flex gap-2">
+          <button
+            onClick={() => setMode('time')}
+            className={`text-[10px] ${mode === 'time' ? 'text-drone-primary' : 'text-gray-400 hover:text-gray-300'}`}
+          >
+            % vs time
+          </button>
+          <button
+            onClick={() => setMode('voltage')}
+            className={`text-[10px] ${mode === 'voltage' ? 'text-drone-primary' : 'text-gray-400 hover:text-gray-300'}`}
+          >
+            Voltage vs %
+          </button>
+        </div>
+      </div>
+      {curves === null ? (
+        <p className="text-xs text-gray-500">Loading discharge curves...</p>
+      ) : series.length === 0 ? (
+        <p className="text-xs text-gray-500">No discharge data logged for this battery.</p>
+      ) : (
+        <div className="h-[220px]">
+          <ReactECharts option={option} style={{ height: '100%' }} notMerge />
+        </div>
+      )}
     </div>
   );
 }
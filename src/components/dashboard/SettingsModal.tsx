@@ -9,6 +9,7 @@ import { useFlightStore } from '@/stores/flightStore';
 import { Select } from '@/components/ui/Select';
 import { getBlacklist, clearBlacklist } from './FlightImporter';
 import { SMART_TAG_TYPES, getEnabledSmartTagTypes, setEnabledSmartTagTypes, SmartTagTypeId } from '@/lib/api';
+import type { AuditEntry, AutoExportSettings, ComplianceLimits, CurrencyRequirement, DroneProfile, ExportTemplate, FirmwareHistoryEntry, Flight, Library, LibraryCheckReport, Mission, StitchCandidate, TerrainSettings, TrackSmoothing } from '@/types';
 
 interface SettingsModalProps {
   isOpen: boolean;
@@ -31,10 +32,44 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const [isRestoring, setIsRestoring] = useState(false);
   const [isDeleting, setIsDeleting] = useState(false);
   const [isDeduplicating, setIsDeduplicating] = useState(false);
+  const [isStitching, setIsStitching] = useState(false);
+  const [stitchMaxGap, setStitchMaxGap] = useState(120);
+  const [stitchCandidates, setStitchCandidates] = useState<StitchCandidate[] | null>(null);
+  const [trashedFlights, setTrashedFlights] = useState<Flight[] | null>(null);
+  const [autoBackupEnabled, setAutoBackupEnabled] = useState(false);
+  const [confirmRestoreSnapshot, setConfirmRestoreSnapshot] = useState(false);
   const [confirmRemoveAutoTags, setConfirmRemoveAutoTags] = useState(false);
   const [enabledTagTypes, setEnabledTagTypes] = useState<SmartTagTypeId[]>(() => getEnabledSmartTagTypes());
   const [isTagTypeDropdownOpen, setIsTagTypeDropdownOpen] = useState(false);
   const [tagTypeSearch, setTagTypeSearch] = useState('');
+  const [watchFolders, setWatchFolders] = useState<string[]>([]);
+  const [missions, setMissions] = useState<Mission[]>([]);
+  const [isScanningMedia, setIsScanningMedia] = useState(false);
+  const [geocodeProvider, setGeocodeProvider] = useState<api.GeocodeProvider>('offline');
+  const [isLocating, setIsLocating] = useState(false);
+  const [isComputingDistances, setIsComputingDistances] = useState(false);
+  const [isRebuildingStats, setIsRebuildingStats] = useState(false);
+  const [pilotName, setPilotName] = useState('');
+  const [pilotLicense, setPilotLicense] = useState('');
+  const [autoExport, setAutoExport] = useState<AutoExportSettings>({ enabled: false, directory: null, formats: ['gpx', 'csv'] });
+  const [currencyRequirements, setCurrencyRequirements] = useState<CurrencyRequirement[]>([]);
+  const [complianceLimits, setComplianceLimits] = useState<ComplianceLimits>({ maxHeightM: 120, maxDistanceM: null });
+  const [trackSmoothing, setTrackSmoothing] = useState<TrackSmoothing>('off');
+  const [terrain, setTerrain] = useState<TerrainSettings>({ provider: 'off', demDirectory: null });
+  const [telemetryStorage, setTelemetryStorage] = useState<api.TelemetryStorage>('rows');
+  const [isConvertingStorage, setIsConvertingStorage] = useState(false);
+  const [archivedFlightCount, setArchivedFlightCount] = useState(0);
+  const [archiveAfterYears, setArchiveAfterYears] = useState(3);
+  const [isArchiving, setIsArchiving] = useState(false);
+  const [auditEntries, setAuditEntries] = useState<AuditEntry[] | null>(null);
+  const [libraryCheck, setLibraryCheck] = useState<LibraryCheckReport | null>(null);
+  const [isCheckingLibrary, setIsCheckingLibrary] = useState(false);
+  const [libraries, setLibraries] = useState<Library[]>([]);
+  const [newLibraryName, setNewLibraryName] = useState('');
+  const [isSwitchingLibrary, setIsSwitchingLibrary] = useState(false);
+  const [conflictCopies, setConflictCopies] = useState<string[]>([]);
+  const [isReconciling, setIsReconciling] = useState(false);
+  const [mediaFolderPath, setMediaFolderPath] = useState('');
   const tagTypeDropdownRef = useRef<HTMLDivElement>(null);
 
   const {
@@ -45,6 +80,7 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     loadFlights,
     loadOverview,
     clearSelection,
+    clearFlightDataCache,
     donationAcknowledged,
     setDonationAcknowledged,
     smartTagsEnabled,
@@ -62,6 +98,10 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     loadApiKeyType,
     hideSerialNumbers,
     setHideSerialNumbers,
+    getDisplaySerial,
+    droneProfiles,
+    loadDroneProfiles,
+    updateDroneProfile,
   } = useFlightStore();
 
   const [showBadgeModal, setShowBadgeModal] = useState(false);
@@ -108,7 +148,7 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   };
 
   // True when any long-running destructive/IO operation is in progress
-  const isBusy = isBackingUp || isRestoring || isDeleting || isRegenerating || isRemovingAutoTags || isDeduplicating;
+  const isBusy = isBackingUp || isRestoring || isDeleting || isRegenerating || isRemovingAutoTags || isDeduplicating || isStitching || isScanningMedia || isLocating || isComputingDistances || isRebuildingStats || isConvertingStorage || isArchiving || isSwitchingLibrary || isReconciling || isCheckingLibrary;
 
   // Check if API key exists on mount
   useEffect(() => {
@@ -118,9 +158,37 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
       getAppLogDir();
       loadSmartTagsEnabled();
       fetchAppVersion();
-      setBlacklistCount(getBlacklist().size);
+      getBlacklist().then((blacklist) => setBlacklistCount(blacklist.size));
       // Load enabled tag types from backend
       api.loadEnabledSmartTagTypes().then(setEnabledTagTypes);
+      api.getWatchFolders().then(setWatchFolders).catch(() => setWatchFolders([]));
+      api.getMissions().then(setMissions).catch(() => setMissions([]));
+      loadDroneProfiles();
+      api.getGeocodeProvider().then(setGeocodeProvider).catch(() => setGeocodeProvider('offline'));
+      api
+        .getPilotDetails()
+        .then((pilot) => {
+          setPilotName(pilot.name ?? '');
+          setPilotLicense(pilot.license ?? '');
+        })
+        .catch(() => {
+          setPilotName('');
+          setPilotLicense('');
+        });
+      api.getAutoExportSettings().then(setAutoExport).catch(() => {});
+      api.getCurrencyRequirements().then(setCurrencyRequirements).catch(() => setCurrencyRequirements([]));
+      api.getComplianceLimits().then(setComplianceLimits).catch(() => {});
+      api.getTrackSmoothing().then(setTrackSmoothing).catch(() => setTrackSmoothing('off'));
+      api.getTerrainSettings().then(setTerrain).catch(() => {});
+      api.getTelemetryStorage().then(setTelemetryStorage).catch(() => setTelemetryStorage('rows'));
+      api.getArchivedFlightCount().then(setArchivedFlightCount).catch(() => setArchivedFlightCount(0));
+      api.getLibraries().then(setLibraries).catch(() => setLibraries([]));
+      api.getConflictCopies().then(setConflictCopies).catch(() => setConflictCopies([]));
+      setAuditEntries(null);
+      setLibraryCheck(null);
+      if (!api.isWebMode()) {
+        api.getAutoBackupEnabled().then(setAutoBackupEnabled).catch(() => setAutoBackupEnabled(false));
+      }
     }
   }, [isOpen]);
 
@@ -251,7 +319,7 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
         clearSelection();
         await loadFlights();
         await loadOverview();
-        setMessage({ type: 'success', text: `Removed ${removed} duplicate flight${removed === 1 ? '' : 's'}.` });
+        setMessage({ type: 'success', text: `Moved ${removed} duplicate flight${removed === 1 ? '' : 's'} to the trash.` });
       } else {
         setMessage({ type: 'success', text: 'No duplicate flights found.' });
       }
@@ -262,6 +330,87 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     }
   };
 
+  const handleShowTrash = async () => {
+    setMessage(null);
+    try {
+      const flights = await api.getDeletedFlights();
+      setTrashedFlights(flights);
+      if (flights.length === 0) {
+        setMessage({ type: 'success', text: 'Trash is empty.' });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to load trash: ${err}` });
+    }
+  };
+
+  const handleRestoreFlight = async (flight: Flight) => {
+    setMessage(null);
+    try {
+      await api.restoreFlight(flight.id);
+      setTrashedFlights((prev) => prev?.filter((f) => f.id !== flight.id) ?? null);
+      await loadFlights();
+      await loadOverview();
+      setMessage({ type: 'success', text: `Restored ${flight.displayName || flight.fileName}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to restore: ${err}` });
+    }
+  };
+
+  const handlePurgeFlight = async (flight: Flight) => {
+    setMessage(null);
+    try {
+      await api.purgeFlight(flight.id);
+      setTrashedFlights((prev) => prev?.filter((f) => f.id !== flight.id) ?? null);
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to delete: ${err}` });
+    }
+  };
+
+  const handleEmptyTrash = async () => {
+    setMessage(null);
+    try {
+      const purged = await api.emptyTrash();
+      setTrashedFlights([]);
+      setMessage({ type: 'success', text: `Permanently deleted ${purged} flight${purged === 1 ? '' : 's'}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to empty trash: ${err}` });
+    }
+  };
+
+  const handleFindSplitFlights = async () => {
+    setIsStitching(true);
+    setMessage(null);
+    try {
+      const candidates = await api.getStitchCandidates(stitchMaxGap);
+      setStitchCandidates(candidates);
+      if (candidates.length === 0) {
+        setMessage({ type: 'success', text: 'No split flights found.' });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to find split flights: ${err}` });
+    } finally {
+      setIsStitching(false);
+    }
+  };
+
+  const handleStitch = async (candidate: StitchCandidate) => {
+    setIsStitching(true);
+    setMessage(null);
+    try {
+      await api.stitchFlights(candidate.firstId, candidate.secondId);
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+      // Pairs touching the merged flight are no longer valid; rescan
+      setStitchCandidates(await api.getStitchCandidates(stitchMaxGap));
+      setMessage({ type: 'success', text: `Merged "${candidate.secondName}" into "${candidate.firstName}".` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Merge failed: ${err}` });
+    } finally {
+      setIsStitching(false);
+    }
+  };
+
   const handleBackup = async () => {
     setIsBackingUp(true);
     setMessage(null);
@@ -275,6 +424,446 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     }
   };
 
+  const handleExportStats = async () => {
+    setIsBackingUp(true);
+    setMessage(null);
+    try {
+      const timestamp = new Date().toISOString().slice(0, 10);
+      await api.exportLibraryStats(`flight_stats_${timestamp}.json`);
+    } catch (err) {
+      setMessage({ type: 'error', text: `Statistics export failed: ${err}` });
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
+  const handleExportArchive = async () => {
+    setIsBackingUp(true);
+    setMessage(null);
+    try {
+      if (await api.exportArchive()) {
+        setMessage({ type: 'success', text: 'Logbook archive exported.' });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Archive export failed: ${err}` });
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
+  const handleImportArchive = async () => {
+    setIsRestoring(true);
+    setMessage(null);
+    try {
+      let msg: string;
+      if (api.isWebMode()) {
+        const files = await api.pickFiles('.logbook', false);
+        if (files.length === 0) return;
+        msg = await api.importArchive(files[0]);
+      } else {
+        msg = await api.importArchive();
+        if (!msg) return;
+      }
+      setMessage({ type: 'success', text: msg });
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+    } catch (err) {
+      setMessage({ type: 'error', text: `Archive import failed: ${err}` });
+    } finally {
+      setIsRestoring(false);
+    }
+  };
+
+  const handleSnapshot = async () => {
+    setIsBackingUp(true);
+    setMessage(null);
+    try {
+      if (await api.snapshotDatabase()) {
+        setMessage({ type: 'success', text: 'Database snapshot saved.' });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Snapshot failed: ${err}` });
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
+  const handleRestoreSnapshot = async () => {
+    setConfirmRestoreSnapshot(false);
+    setIsRestoring(true);
+    setMessage(null);
+    try {
+      if (!(await api.restoreSnapshot())) return;
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+      setMessage({ type: 'success', text: 'Snapshot restored. The previous library was saved to the backups folder.' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Restore failed: ${err}` });
+    } finally {
+      setIsRestoring(false);
+    }
+  };
+
+  const handleToggleAutoBackup = async () => {
+    try {
+      setAutoBackupEnabled(await api.setAutoBackupEnabled(!autoBackupEnabled));
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to update setting: ${err}` });
+    }
+  };
+
+  const updateWatchFolders = async (folders: string[]) => {
+    try {
+      setWatchFolders(await api.setWatchFolders(folders));
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to update watched folders: ${err}` });
+    }
+  };
+
+  const handleAddWatchFolder = async () => {
+    const { open } = await import('@tauri-apps/plugin-dialog');
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected === 'string' && !watchFolders.includes(selected)) {
+      await updateWatchFolders([...watchFolders, selected]);
+    }
+  };
+
+  const handleImportMission = async () => {
+    try {
+      let mission: Mission | null;
+      if (api.isWebMode()) {
+        const files = await api.pickFiles('.kmz', false);
+        if (files.length === 0) return;
+        mission = await api.importMission(files[0]);
+      } else {
+        mission = await api.importMission();
+      }
+      if (mission) {
+        setMissions(await api.getMissions());
+        const linked = mission.flightIds.length;
+        setMessage({
+          type: 'success',
+          text: `Imported "${mission.name}" (${mission.waypoints.length} waypoints, linked to ${linked} flight${linked === 1 ? '' : 's'}).`,
+        });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Mission import failed: ${err}` });
+    }
+  };
+
+  const handleRelinkMissions = async () => {
+    try {
+      const linked = await api.relinkMissions();
+      setMissions(await api.getMissions());
+      setMessage({ type: 'success', text: `Missions linked to ${linked} flight${linked === 1 ? '' : 's'}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Mission linking failed: ${err}` });
+    }
+  };
+
+  const handleDeleteMission = async (missionId: number) => {
+    try {
+      await api.deleteMission(missionId);
+      setMissions((prev) => prev.filter((m) => m.id !== missionId));
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to delete mission: ${err}` });
+    }
+  };
+
+  const handleScanMedia = async () => {
+    let folder = mediaFolderPath.trim();
+    if (!api.isWebMode()) {
+      const { open } = await import('@tauri-apps/plugin-dialog');
+      const selected = await open({ directory: true, multiple: false });
+      if (typeof selected !== 'string') return;
+      folder = selected;
+    }
+    if (!folder) return;
+
+    setIsScanningMedia(true);
+    setMessage(null);
+    try {
+      const result = await api.scanMediaFolder(folder);
+      setMessage({
+        type: 'success',
+        text: `Scanned ${result.scanned} files: ${result.matched} matched to flights, ${result.scanned - result.timestamped} without a capture time.`,
+      });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Media scan failed: ${err}` });
+    } finally {
+      setIsScanningMedia(false);
+    }
+  };
+
+  const handleGeocodeProviderChange = async (provider: api.GeocodeProvider) => {
+    try {
+      setGeocodeProvider(await api.setGeocodeProvider(provider));
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to change geocoding provider: ${err}` });
+    }
+  };
+
+  const handleSavePilotDetails = async () => {
+    try {
+      const pilot = await api.setPilotDetails({ name: pilotName, license: pilotLicense });
+      setPilotName(pilot.name ?? '');
+      setPilotLicense(pilot.license ?? '');
+      setMessage({ type: 'success', text: 'Pilot details saved' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to save pilot details: ${err}` });
+    }
+  };
+
+  const handleSaveCurrencyRequirements = async () => {
+    try {
+      setCurrencyRequirements(await api.setCurrencyRequirements(currencyRequirements));
+      setMessage({ type: 'success', text: 'Currency requirements saved' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to save currency requirements: ${err}` });
+    }
+  };
+
+  const updateCurrencyRequirement = (index: number, patch: Partial<CurrencyRequirement>) => {
+    setCurrencyRequirements((prev) => prev.map((r, i) => (i === index ? { ...r, ...patch } : r)));
+  };
+
+  const handleSaveComplianceLimits = async () => {
+    try {
+      setComplianceLimits(await api.setComplianceLimits(complianceLimits));
+      setMessage({ type: 'success', text: 'Compliance limits saved' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to save compliance limits: ${err}` });
+    }
+  };
+
+  // Limits are stored in meters and edited in the display unit
+  const limitFactor = unitSystem === 'imperial' ? 3.28084 : 1;
+  const limitInputValue = (meters: number | null) => (meters === null ? '' : Math.round(meters * limitFactor));
+  const parseLimitInput = (value: string) => {
+    const number = Number(value);
+    return value.trim() === '' || !(number > 0) ? null : number / limitFactor;
+  };
+
+  const handleSaveAutoExport = async (settings: AutoExportSettings) => {
+    try {
+      setAutoExport(await api.setAutoExportSettings(settings));
+      setMessage({ type: 'success', text: 'Auto-export settings saved' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to save auto-export settings: ${err}` });
+    }
+  };
+
+  const handlePickAutoExportFolder = async () => {
+    const { open } = await import('@tauri-apps/plugin-dialog');
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected === 'string') {
+      setAutoExport((prev) => ({ ...prev, directory: selected }));
+    }
+  };
+
+  const handleBackfillLocations = async (force: boolean) => {
+    setIsLocating(true);
+    setMessage(null);
+    try {
+      const located = await api.backfillFlightLocations(force);
+      await loadFlights();
+      setMessage({ type: 'success', text: `Found the location of ${located} flight${located === 1 ? '' : 's'}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Location lookup failed: ${err}` });
+    } finally {
+      setIsLocating(false);
+    }
+  };
+
+  const handleRecomputeDistances = async () => {
+    setIsComputingDistances(true);
+    setMessage(null);
+    try {
+      const updated = await api.backfillMaxDistanceFromHome(true);
+      await loadFlights();
+      setMessage({ type: 'success', text: `Updated the distance from home of ${updated} flight${updated === 1 ? '' : 's'}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Distance calculation failed: ${err}` });
+    } finally {
+      setIsComputingDistances(false);
+    }
+  };
+
+  const handleRebuildStats = async () => {
+    setIsRebuildingStats(true);
+    setMessage(null);
+    try {
+      const rebuilt = await api.rebuildStatsCache();
+      await loadFlights();
+      setMessage({ type: 'success', text: `Recomputed the statistics of ${rebuilt} flight${rebuilt === 1 ? '' : 's'}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Statistics recalculation failed: ${err}` });
+    } finally {
+      setIsRebuildingStats(false);
+    }
+  };
+
+  const handleTrackSmoothingChange = async (method: TrackSmoothing) => {
+    try {
+      setTrackSmoothing(await api.setTrackSmoothing(method));
+      // Cached flights were loaded with the previous smoothing
+      clearFlightDataCache();
+      setMessage({ type: 'success', text: 'Track smoothing saved; it applies to flights opened from now on' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to save track smoothing: ${err}` });
+    }
+  };
+
+  const handleSaveTerrain = async () => {
+    try {
+      setTerrain(await api.setTerrainSettings(terrain));
+      // Cached flights were loaded with the previous provider
+      clearFlightDataCache();
+      setMessage({ type: 'success', text: 'Terrain settings saved; they apply to flights opened from now on' });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to save terrain settings: ${err}` });
+    }
+  };
+
+  const handlePickDemFolder = async () => {
+    const { open } = await import('@tauri-apps/plugin-dialog');
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected === 'string') {
+      setTerrain((prev) => ({ ...prev, demDirectory: selected }));
+    }
+  };
+
+  const handleTelemetryStorageChange = async (mode: api.TelemetryStorage) => {
+    setIsConvertingStorage(true);
+    setMessage(null);
+    try {
+      const converted = await api.setTelemetryStorage(mode);
+      setTelemetryStorage(mode);
+      setMessage({ type: 'success', text: `Converted ${converted} flight${converted === 1 ? '' : 's'}.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Storage conversion failed: ${err}` });
+    } finally {
+      setIsConvertingStorage(false);
+    }
+  };
+
+  const handleArchiveFlights = async (restore: boolean) => {
+    setIsArchiving(true);
+    setMessage(null);
+    try {
+      const count = restore ? await api.restoreArchivedFlights() : await api.archiveOldFlights(archiveAfterYears);
+      const verb = restore ? 'Restored' : 'Archived';
+      setMessage({ type: 'success', text: `${verb} ${count} flight${count === 1 ? '' : 's'}.` });
+      setArchivedFlightCount(await api.getArchivedFlightCount());
+    } catch (err) {
+      setMessage({ type: 'error', text: `${restore ? 'Restore' : 'Archiving'} failed: ${err}` });
+    } finally {
+      setIsArchiving(false);
+    }
+  };
+
+  const handleCheckLibrary = async (repair: boolean) => {
+    setIsCheckingLibrary(true);
+    setMessage(null);
+    try {
+      const report = await api.checkLibrary(repair);
+      if (repair) {
+        const repaired = report.issues.filter((i) => i.repairable).reduce((sum, i) => sum + i.count, 0);
+        setMessage({ type: 'success', text: `Repaired ${repaired} problem${repaired === 1 ? '' : 's'}.` });
+        setLibraryCheck(await api.checkLibrary(false));
+        await loadFlights();
+        await loadOverview();
+      } else {
+        setLibraryCheck(report);
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Library check failed: ${err}` });
+    } finally {
+      setIsCheckingLibrary(false);
+    }
+  };
+
+  const handleSwitchLibrary = async (library: string) => {
+    setIsSwitchingLibrary(true);
+    setMessage(null);
+    try {
+      const opened = await api.switchLibrary(library);
+      setNewLibraryName('');
+      setMessage({ type: 'success', text: `Opened library "${opened.name}".` });
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+      setLibraries(await api.getLibraries());
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to open library: ${err}` });
+    } finally {
+      setIsSwitchingLibrary(false);
+    }
+  };
+
+  const handleBrowseLibrary = async () => {
+    const path = await api.pickLibraryFile();
+    if (path) await handleSwitchLibrary(path);
+  };
+
+  const handleReconcileLibrary = async (path: string | null) => {
+    if (!path) return;
+    setIsReconciling(true);
+    setMessage(null);
+    try {
+      const summary = await api.reconcileLibrary(path);
+      setMessage({ type: 'success', text: summary });
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+      setConflictCopies(await api.getConflictCopies());
+    } catch (err) {
+      setMessage({ type: 'error', text: `Reconcile failed: ${err}` });
+    } finally {
+      setIsReconciling(false);
+    }
+  };
+
+  const handleForgetLibrary = async (path: string) => {
+    try {
+      await api.forgetLibrary(path);
+      setLibraries(await api.getLibraries());
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to remove library: ${err}` });
+    }
+  };
+
+  const handleExportKeychains = async () => {
+    try {
+      const count = await api.exportKeychains();
+      if (count !== null) {
+        setMessage({ type: 'success', text: `Exported ${count} keychain${count === 1 ? '' : 's'}.` });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Keychain export failed: ${err}` });
+    }
+  };
+
+  const handleImportKeychains = async () => {
+    try {
+      let count: number | null;
+      if (api.isWebMode()) {
+        const files = await api.pickFiles('.json', false);
+        if (files.length === 0) return;
+        count = await api.importKeychains(files[0]);
+      } else {
+        count = await api.importKeychains();
+      }
+      if (count !== null) {
+        setMessage({ type: 'success', text: `Imported ${count} keychain${count === 1 ? '' : 's'}.` });
+      }
+    } catch (err) {
+      setMessage({ type: 'error', text: `Keychain import failed: ${err}` });
+    }
+  };
+
   const handleRestore = async () => {
     setIsRestoring(true);
     setMessage(null);
@@ -332,6 +921,7 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
               {isRestoring && 'Restoring backup…'}
               {isDeleting && 'Deleting all logs…'}
               {isDeduplicating && 'Removing duplicate flights…'}
+              {isStitching && 'Checking split flights…'}
               {isRemovingAutoTags && 'Removing auto-generated tags…'}
               {isRegenerating && (
                 <>
@@ -604,20 +1194,629 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     </button>
                   </div>
                 </div>
-              ) : (
+              ) : (
+                <button
+                  type="button"
+                  onClick={() => setConfirmRemoveAutoTags(true)}
+                  disabled={isBusy}
+                  className="mt-3 w-full py-2 px-3 rounded-lg border border-orange-600 text-orange-400 hover:bg-orange-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  <span className="flex items-center justify-center gap-2">
+                    <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16" />
+                    </svg>
+                    Remove auto tags
+                  </span>
+                </button>
+              )}
+            </div>
+
+            {/* Watched Folders (desktop only) */}
+            {!api.isWebMode() && (
+              <div className="pt-4 border-t border-gray-700">
+                <p className="text-sm font-medium text-gray-300 mb-2">Watched Folders</p>
+                <p className="text-xs text-gray-500 mb-3">
+                  New logs appearing in these folders are imported automatically.
+                </p>
+                {watchFolders.length > 0 && (
+                  <ul className="mb-3 space-y-1">
+                    {watchFolders.map((folder) => (
+                      <li key={folder} className="flex items-center justify-between gap-2 text-xs text-gray-300">
+                        <span className="truncate font-mono" title={folder}>{folder}</span>
+                        <button
+                          type="button"
+                          onClick={() => updateWatchFolders(watchFolders.filter((f) => f !== folder))}
+                          className="text-gray-400 hover:text-red-400 flex-shrink-0"
+                        >
+                          Remove
+                        </button>
+                      </li>
+                    ))}
+                  </ul>
+                )}
+                <button
+                  type="button"
+                  onClick={handleAddWatchFolder}
+                  className="w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors text-sm"
+                >
+                  Add folder
+                </button>
+              </div>
+            )}
+
+            {/* Drone Profiles */}
+            {droneProfiles.length > 0 && (
+              <div className="pt-4 border-t border-gray-700">
+                <p className="text-sm font-medium text-gray-300 mb-2">Drones</p>
+                <p className="text-xs text-gray-500 mb-3">
+                  Nicknames are shown in the flight list and filters instead of the model and serial.
+                </p>
+                <ul className="space-y-3">
+                  {droneProfiles.map((profile) => (
+                    <DroneProfileRow
+                      key={profile.serial}
+                      profile={profile}
+                      displaySerial={getDisplaySerial(profile.serial)}
+                      onSave={(nickname, purchaseDate, notes, registration) =>
+                        updateDroneProfile(profile.serial, nickname, purchaseDate, notes, registration)
+                      }
+                    />
+                  ))}
+                </ul>
+              </div>
+            )}
+
+            {/* Waypoint Missions */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Waypoint Missions</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Import DJI Fly .kmz missions to link them with the flights that flew them.
+              </p>
+              {missions.length > 0 && (
+                <ul className="mb-3 space-y-1">
+                  {missions.map((mission) => (
+                    <li key={mission.id} className="flex items-center justify-between gap-2 text-xs text-gray-300">
+                      <span className="truncate" title={mission.fileName}>
+                        {mission.name}
+                        <span className="text-gray-500">
+                          {' '}· {mission.waypoints.length} waypoints · {mission.flightIds.length} flight{mission.flightIds.length === 1 ? '' : 's'}
+                        </span>
+                      </span>
+                      <button
+                        type="button"
+                        onClick={() => handleDeleteMission(mission.id)}
+                        className="text-gray-400 hover:text-red-400 flex-shrink-0"
+                      >
+                        Remove
+                      </button>
+                    </li>
+                  ))}
+                </ul>
+              )}
+              <div className="grid grid-cols-2 gap-2">
+                <button
+                  type="button"
+                  onClick={handleImportMission}
+                  disabled={isBusy}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  Import mission
+                </button>
+                <button
+                  type="button"
+                  onClick={handleRelinkMissions}
+                  disabled={isBusy || missions.length === 0}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  Re-match flights
+                </button>
+              </div>
+            </div>
+
+            {/* Media Library */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Media Library</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Match photos and videos to the flights they were captured on, using their EXIF capture time and GPS.
+              </p>
+              {api.isWebMode() && (
+                <input
+                  type="text"
+                  value={mediaFolderPath}
+                  onChange={(e) => setMediaFolderPath(e.target.value)}
+                  placeholder="/media/drone"
+                  className="input w-full text-sm font-mono mb-2"
+                />
+              )}
+              <button
+                type="button"
+                onClick={handleScanMedia}
+                disabled={isBusy || (api.isWebMode() && !mediaFolderPath.trim())}
+                className="w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                {isScanningMedia ? 'Scanning media…' : api.isWebMode() ? 'Scan folder' : 'Scan media folder'}
+              </button>
+            </div>
+
+            {/* Flight Locations */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Flight Locations</p>
+              <p className="text-xs text-gray-500 mb-3">
+                City, region and country of each takeoff point. Nominatim (OpenStreetMap) is more precise but sends
+                coordinates online and is limited to one lookup per second.
+              </p>
+              <Select
+                value={geocodeProvider}
+                onChange={(v) => handleGeocodeProviderChange(v as api.GeocodeProvider)}
+                className="w-full mb-2"
+                options={[
+                  { value: 'offline', label: 'Offline (GeoNames)' },
+                  { value: 'nominatim', label: 'Nominatim (online)' },
+                ]}
+              />
+              <div className="grid grid-cols-2 gap-2">
+                <button
+                  type="button"
+                  onClick={() => handleBackfillLocations(false)}
+                  disabled={isBusy}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  {isLocating ? 'Looking up…' : 'Fill in missing'}
+                </button>
+                <button
+                  type="button"
+                  onClick={() => handleBackfillLocations(true)}
+                  disabled={isBusy}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  Look up all again
+                </button>
+              </div>
+              <button
+                type="button"
+                onClick={handleRecomputeDistances}
+                disabled={isBusy}
+                className="w-full mt-2 py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                title="Farthest 3D distance from the home point of every flight, used in the flight list and records"
+              >
+                {isComputingDistances ? 'Calculating…' : 'Recalculate distances from home'}
+              </button>
+              <button
+                type="button"
+                onClick={handleRebuildStats}
+                disabled={isBusy}
+                className="w-full mt-2 py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                title="Per-flight figures used by the overview are cached and updated as flights change; this computes all of them again"
+              >
+                {isRebuildingStats ? 'Recomputing…' : 'Recompute statistics'}
+              </button>
+            </div>
+
+            {/* Pilot */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Pilot</p>
+              <p className="text-xs text-gray-500 mb-3">Shown on PDF flight reports.</p>
+              <input
+                type="text"
+                value={pilotName}
+                onChange={(e) => setPilotName(e.target.value)}
+                placeholder="Pilot name"
+                className="input w-full text-sm mb-2"
+              />
+              <input
+                type="text"
+                value={pilotLicense}
+                onChange={(e) => setPilotLicense(e.target.value)}
+                placeholder="License or operator ID"
+                className="input w-full text-sm mb-2"
+              />
+              <button
+                type="button"
+                onClick={handleSavePilotDetails}
+                disabled={isBusy}
+                className="w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                Save pilot details
+              </button>
+            </div>
+
+            {/* Currency */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Currency</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Minimum flights or hours within a number of days, e.g. what your insurer requires.
+                Checked on the overview for you and each aircraft.
+              </p>
+              {currencyRequirements.map((requirement, index) => (
+                <div key={index} className="flex items-center gap-2 mb-2 text-xs text-gray-300">
+                  <input
+                    type="number"
+                    min={0}
+                    value={requirement.minFlights}
+                    onChange={(e) => updateCurrencyRequirement(index, { minFlights: Math.max(0, Number(e.target.value) || 0) })}
+                    className="input w-16 text-sm"
+                  />
+                  <span>flights,</span>
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.5}
+                    value={requirement.minHours}
+                    onChange={(e) => updateCurrencyRequirement(index, { minHours: Math.max(0, Number(e.target.value) || 0) })}
+                    className="input w-16 text-sm"
+                  />
+                  <span>hours in</span>
+                  <input
+                    type="number"
+                    min={1}
+                    max={3650}
+                    value={requirement.windowDays}
+                    onChange={(e) => updateCurrencyRequirement(index, { windowDays: Math.max(1, Number(e.target.value) || 1) })}
+                    className="input w-16 text-sm"
+                  />
+                  <span className="flex-1">days</span>
+                  <button
+                    type="button"
+                    onClick={() => setCurrencyRequirements((prev) => prev.filter((_, i) => i !== index))}
+                    className="text-gray-400 hover:text-red-400"
+                    title="Remove requirement"
+                  >
+                    ✕
+                  </button>
+                </div>
+              ))}
+              <div className="flex gap-2">
+                <button
+                  type="button"
+                  onClick={() => setCurrencyRequirements((prev) => [...prev, { windowDays: 90, minFlights: 3, minHours: 0 }])}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors text-sm"
+                >
+                  Add
+                </button>
+                <button
+                  type="button"
+                  onClick={handleSaveCurrencyRequirements}
+                  disabled={isBusy}
+                  className="flex-1 py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  Save currency requirements
+                </button>
+              </div>
+            </div>
+
+            {/* Compliance */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Compliance limits</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Flights above this height over takeoff or beyond this distance from home are flagged on the
+                overview. Leave a limit empty to skip it.
+              </p>
+              <div className="flex items-center gap-2 mb-2 text-xs text-gray-300">
+                <span className="w-20">Max height</span>
+                <input
+                  type="number"
+                  min={1}
+                  value={limitInputValue(complianceLimits.maxHeightM)}
+                  onChange={(e) => setComplianceLimits((prev) => ({ ...prev, maxHeightM: parseLimitInput(e.target.value) }))}
+                  placeholder="Off"
+                  className="input w-24 text-sm"
+                />
+                <span>{unitSystem === 'imperial' ? 'ft' : 'm'}</span>
+              </div>
+              <div className="flex items-center gap-2 mb-2 text-xs text-gray-300">
+                <span className="w-20">Max distance</span>
+                <input
+                  type="number"
+                  min={1}
+                  value={limitInputValue(complianceLimits.maxDistanceM)}
+                  onChange={(e) => setComplianceLimits((prev) => ({ ...prev, maxDistanceM: parseLimitInput(e.target.value) }))}
+                  placeholder="Off"
+                  className="input w-24 text-sm"
+                />
+                <span>{unitSystem === 'imperial' ? 'ft' : 'm'}</span>
+              </div>
+              <button
+                type="button"
+                onClick={handleSaveComplianceLimits}
+                disabled={isBusy}
+                className="w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                Save compliance limits
+              </button>
+            </div>
+
+            {/* Auto-export */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Auto-export</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Write a copy of every newly imported flight to a folder, e.g. to archive it next to the footage.
+                Flights already in the logbook are not exported.
+              </p>
+              <button
+                type="button"
+                onClick={() => setAutoExport((prev) => ({ ...prev, enabled: !prev.enabled }))}
+                className="flex items-center justify-between gap-3 w-full text-xs text-gray-300 mb-2"
+                aria-pressed={autoExport.enabled}
+              >
+                <span>Export new flights</span>
+                <span
+                  className={`relative inline-flex h-5 w-9 items-center rounded-full border transition-all ${
+                    autoExport.enabled
+                      ? 'bg-drone-primary/90 border-drone-primary'
+                      : 'bg-drone-surface border-gray-600 toggle-track-off'
+                  }`}
+                >
+                  <span
+                    className={`inline-block h-4 w-4 transform rounded-full bg-white shadow transition-transform ${
+                      autoExport.enabled ? 'translate-x-4' : 'translate-x-1'
+                    }`}
+                  />
+                </span>
+              </button>
+              {api.isWebMode() ? (
+                <input
+                  type="text"
+                  value={autoExport.directory ?? ''}
+                  onChange={(e) => setAutoExport((prev) => ({ ...prev, directory: e.target.value || null }))}
+                  placeholder="Folder on the server, e.g. /data/exports"
+                  className="input w-full text-sm mb-2"
+                />
+              ) : (
+                <div className="flex items-center gap-2 mb-2">
+                  <p className="flex-1 text-xs text-gray-400 truncate" title={autoExport.directory ?? undefined}>
+                    {autoExport.directory ?? 'No folder chosen'}
+                  </p>
+                  <button
+                    type="button"
+                    onClick={handlePickAutoExportFolder}
+                    className="py-1 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors text-sm"
+                  >
+                    Choose folder
+                  </button>
+                </div>
+              )}
+              <div className="flex gap-4 mb-2">
+                {(['gpx', 'kml', 'csv', 'json'] as const).map((format) => (
+                  <label key={format} className="flex items-center gap-1.5 text-xs text-gray-300">
+                    <input
+                      type="checkbox"
+                      checked={autoExport.formats.includes(format)}
+                      onChange={() =>
+                        setAutoExport((prev) => ({
+                          ...prev,
+                          formats: prev.formats.includes(format)
+                            ? prev.formats.filter((f) => f !== format)
+                            : [...prev.formats, format],
+                        }))
+                      }
+                    />
+                    {format.toUpperCase()}
+                  </label>
+                ))}
+              </div>
+              <button
+                type="button"
+                onClick={() => handleSaveAutoExport(autoExport)}
+                disabled={isBusy}
+                className="w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                Save auto-export settings
+              </button>
+            </div>
+
+            {/* Export Templates */}
+            <ExportTemplatesSection
+              isOpen={isOpen}
+              disabled={isBusy}
+              onMessage={setMessage}
+            />
+
+            {/* Track Smoothing */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Map Track Smoothing</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Smooth the flight path drawn on the map, for flights logged with poor GPS. Statistics and exports
+                keep the logged positions.
+              </p>
+              <Select
+                value={trackSmoothing}
+                onChange={(v) => {
+                  if (v !== trackSmoothing) handleTrackSmoothingChange(v as TrackSmoothing);
+                }}
+                className="w-full"
+                options={[
+                  { value: 'off', label: 'Off' },
+                  { value: 'ema', label: 'Moving average (light)' },
+                  { value: 'kalman', label: 'Kalman (keeps turns sharper)' },
+                ]}
+              />
+            </div>
+
+            {/* Terrain */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Height Above Terrain</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Logged heights count from the takeoff point. Look up the ground elevation along each flight to chart
+                the height above the terrain below. Open-Meteo sends the flight's positions online; local SRTM tiles
+                (.hgt files) keep everything on this {api.isWebMode() ? 'server' : 'computer'}.
+              </p>
+              <Select
+                value={terrain.provider}
+                onChange={(v) => setTerrain((prev) => ({ ...prev, provider: v as TerrainSettings['provider'] }))}
+                className="w-full mb-2"
+                options={[
+                  { value: 'off', label: 'Off' },
+                  { value: 'open-meteo', label: 'Open-Meteo (online)' },
+                  { value: 'local', label: 'Local SRTM tiles' },
+                ]}
+              />
+              {terrain.provider === 'local' &&
+                (api.isWebMode() ? (
+                  <input
+                    type="text"
+                    value={terrain.demDirectory ?? ''}
+                    onChange={(e) => setTerrain((prev) => ({ ...prev, demDirectory: e.target.value || null }))}
+                    placeholder="Folder on the server, e.g. /data/dem"
+                    className="input w-full text-sm mb-2"
+                  />
+                ) : (
+                  <div className="flex items-center gap-2 mb-2">
+                    <p className="flex-1 text-xs text-gray-400 truncate" title={terrain.demDirectory ?? undefined}>
+                      {terrain.demDirectory ?? 'No folder chosen'}
+                    </p>
+                    <button
+                      type="button"
+                      onClick={handlePickDemFolder}
+                      className="py-1 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors text-sm"
+                    >
+                      Choose folder
+                    </button>
+                  </div>
+                ))}
+              <button
+                type="button"
+                onClick={handleSaveTerrain}
+                disabled={isBusy}
+                className="w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                Save terrain settings
+              </button>
+            </div>
+
+            {/* Telemetry Storage */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Telemetry Storage</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Packed storage compresses each flight's telemetry several times smaller. Charts, maps and exports work
+                as usual; distance records, battery health trends and stitch suggestions only use unpacked flights.
+              </p>
+              <Select
+                value={telemetryStorage}
+                onChange={(v) => {
+                  if (!isBusy && v !== telemetryStorage) handleTelemetryStorageChange(v as api.TelemetryStorage);
+                }}
+                className="w-full"
+                options={[
+                  { value: 'rows', label: 'Rows (fastest statistics)' },
+                  { value: 'packed', label: 'Packed (smallest database)' },
+                ]}
+              />
+              {isConvertingStorage && <p className="text-xs text-gray-500 mt-2">Converting flights…</p>}
+            </div>
+
+            {/* Telemetry Archive */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Telemetry Archive</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Move the telemetry of old flights into compressed files next to the database. Their details stay in
+                the logbook and the telemetry is loaded again when a flight is opened.
+                {archivedFlightCount > 0 && ` ${archivedFlightCount} flight${archivedFlightCount === 1 ? ' is' : 's are'} archived.`}
+              </p>
+              <div className="flex items-center gap-2">
+                <span className="text-sm text-gray-400">Older than</span>
+                <input
+                  type="number"
+                  min={1}
+                  value={archiveAfterYears}
+                  onChange={(e) => setArchiveAfterYears(Math.max(1, parseInt(e.target.value, 10) || 1))}
+                  className="input w-16 text-sm"
+                />
+                <span className="text-sm text-gray-400 flex-1">years</span>
+                <button
+                  type="button"
+                  onClick={() => handleArchiveFlights(false)}
+                  disabled={isBusy}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  {isArchiving ? 'Working…' : 'Archive'}
+                </button>
+                <button
+                  type="button"
+                  onClick={() => handleArchiveFlights(true)}
+                  disabled={isBusy || archivedFlightCount === 0}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  Restore all
+                </button>
+              </div>
+            </div>
+
+            {/* Library Check */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Library Check</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Read every table and look for leftover rows, flights without telemetry and missing or unused files.
+                Repairing moves flights without telemetry to the trash and removes what no flight can reach.
+              </p>
+              {libraryCheck && (
+                <div className="mb-3 space-y-1">
+                  {libraryCheck.issues.length === 0 ? (
+                    <p className="text-xs text-gray-400">
+                      No problems found in {libraryCheck.tablesChecked} tables.
+                    </p>
+                  ) : (
+                    libraryCheck.issues.map((issue) => (
+                      <div key={`${issue.kind}-${issue.table ?? ''}`} className="text-xs text-gray-400 flex gap-2">
+                        <span className={issue.repairable ? 'text-amber-300' : 'text-red-400'}>{issue.count}</span>
+                        <span className="truncate" title={issue.table ?? undefined}>
+                          {issue.description}
+                          {issue.table && <span className="text-gray-500"> ({issue.table})</span>}
+                        </span>
+                      </div>
+                    ))
+                  )}
+                </div>
+              )}
+              <div className="flex items-center gap-2">
                 <button
                   type="button"
-                  onClick={() => setConfirmRemoveAutoTags(true)}
+                  onClick={() => handleCheckLibrary(false)}
                   disabled={isBusy}
-                  className="mt-3 w-full py-2 px-3 rounded-lg border border-orange-600 text-orange-400 hover:bg-orange-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
                 >
-                  <span className="flex items-center justify-center gap-2">
-                    <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16" />
-                    </svg>
-                    Remove auto tags
-                  </span>
+                  {isCheckingLibrary ? 'Checking…' : 'Check library'}
+                </button>
+                {libraryCheck?.issues.some((i) => i.repairable) && (
+                  <button
+                    type="button"
+                    onClick={() => handleCheckLibrary(true)}
+                    disabled={isBusy}
+                    className="py-2 px-3 rounded-lg border border-amber-500/60 text-amber-300 hover:bg-amber-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                  >
+                    Repair
+                  </button>
+                )}
+              </div>
+            </div>
+
+            {/* Audit Log */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Audit Log</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Every import, merge, deletion and edit, with the log file each flight came from.
+              </p>
+              {auditEntries === null ? (
+                <button
+                  type="button"
+                  onClick={() => api.getAuditLog(undefined, 200).then(setAuditEntries).catch(() => setAuditEntries([]))}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors text-sm"
+                >
+                  Show recent activity
                 </button>
+              ) : auditEntries.length === 0 ? (
+                <p className="text-xs text-gray-500">No activity recorded yet.</p>
+              ) : (
+                <div className="max-h-48 overflow-y-auto space-y-1">
+                  {auditEntries.map((entry) => (
+                    <div key={entry.id} className="text-xs text-gray-400 flex gap-2">
+                      <span className="text-gray-500 whitespace-nowrap">{entry.loggedAt.slice(0, 19)}</span>
+                      <span className="text-gray-300">{entry.action}</span>
+                      <span className="truncate" title={[entry.sourceFile, entry.details, entry.appVersion && `v${entry.appVersion}`].filter(Boolean).join('\n')}>
+                        {[entry.sourceFile, entry.details].filter(Boolean).join(' — ')}
+                      </span>
+                    </div>
+                  ))}
+                </div>
               )}
             </div>
 
@@ -694,6 +1893,29 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                 {isSaving ? 'Saving...' : hasKey ? 'Update API Key' : 'Save API Key'}
               </button>
 
+              {/* Keychain bundles for decrypting on machines without internet */}
+              <div className="mt-3 grid grid-cols-2 gap-2">
+                <button
+                  type="button"
+                  onClick={handleExportKeychains}
+                  disabled={isBusy}
+                  className="py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                >
+                  Export keychains
+                </button>
+                <button
+                  type="button"
+                  onClick={handleImportKeychains}
+                  disabled={isBusy}
+                  className="py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                >
+                  Import keychains
+                </button>
+              </div>
+              <p className="text-xs text-gray-500 mt-1">
+                Move fetched decryption keys to an offline machine.
+              </p>
+
               {/* Message (auto-dismisses after 5s) */}
               {message && (
                 <p
@@ -824,6 +2046,113 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
               </p>
             </div>
 
+            {/* Libraries */}
+            <div className="pt-4 border-t border-gray-700">
+              <p className="text-sm font-medium text-gray-300 mb-2">Libraries</p>
+              <p className="text-xs text-gray-500 mb-3">
+                Keep separate logbooks, e.g. personal and work flights, in their own database files. Settings and
+                keychains are shared by all libraries.
+              </p>
+              <div className="space-y-1 mb-2">
+                {libraries.map((library) => (
+                  <div key={library.path} className="flex items-center gap-2 text-sm">
+                    <span
+                      className={`flex-1 truncate ${library.isActive ? 'text-sky-400' : library.exists ? 'text-gray-300' : 'text-gray-500'}`}
+                      title={library.path}
+                    >
+                      {library.name}
+                      {library.isActive && ' (open)'}
+                      {!library.exists && ' (missing)'}
+                    </span>
+                    {!library.isActive && library.exists && (
+                      <button
+                        type="button"
+                        onClick={() => handleSwitchLibrary(library.path)}
+                        disabled={isBusy}
+                        className="text-xs text-sky-400 hover:text-sky-300 disabled:opacity-50 disabled:cursor-not-allowed"
+                      >
+                        Open
+                      </button>
+                    )}
+                    {!library.isActive && !library.isDefault && (
+                      <button
+                        type="button"
+                        onClick={() => handleForgetLibrary(library.path)}
+                        disabled={isBusy}
+                        className="text-xs text-gray-500 hover:text-red-400 disabled:opacity-50 disabled:cursor-not-allowed"
+                        title="Remove from list (the file is kept)"
+                      >
+                        Remove
+                      </button>
+                    )}
+                  </div>
+                ))}
+              </div>
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={newLibraryName}
+                  onChange={(e) => setNewLibraryName(e.target.value)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter' && newLibraryName.trim()) handleSwitchLibrary(newLibraryName.trim());
+                  }}
+                  placeholder="Library name (created if new)"
+                  className="input flex-1 text-sm"
+                />
+                <button
+                  type="button"
+                  onClick={() => handleSwitchLibrary(newLibraryName.trim())}
+                  disabled={isBusy || !newLibraryName.trim()}
+                  className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  {isSwitchingLibrary ? 'Opening…' : 'Open'}
+                </button>
+                {!api.isWebMode() && (
+                  <button
+                    type="button"
+                    onClick={handleBrowseLibrary}
+                    disabled={isBusy}
+                    className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                  >
+                    Browse…
+                  </button>
+                )}
+              </div>
+              {conflictCopies.length > 0 && (
+                <div className="mt-3 space-y-1">
+                  <p className="text-xs text-amber-400">
+                    Sync conflict copies of this library were found. Reconcile merges their new flights and edits
+                    into the open library; the copy itself is left as is.
+                  </p>
+                  {conflictCopies.map((path) => (
+                    <div key={path} className="flex items-center gap-2 text-sm">
+                      <span className="flex-1 truncate text-gray-400" title={path}>
+                        {path.split(/[\\/]/).pop()}
+                      </span>
+                      <button
+                        type="button"
+                        onClick={() => handleReconcileLibrary(path)}
+                        disabled={isBusy}
+                        className="text-xs text-sky-400 hover:text-sky-300 disabled:opacity-50 disabled:cursor-not-allowed"
+                      >
+                        Reconcile
+                      </button>
+                    </div>
+                  ))}
+                </div>
+              )}
+              {!api.isWebMode() && (
+                <button
+                  type="button"
+                  onClick={async () => handleReconcileLibrary(await api.pickLibraryCopy())}
+                  disabled={isBusy}
+                  className="mt-2 text-xs text-gray-400 hover:text-white disabled:opacity-50 disabled:cursor-not-allowed"
+                >
+                  {isReconciling ? 'Reconciling…' : 'Reconcile with another copy…'}
+                </button>
+              )}
+            </div>
+
             {/* Backup & Restore */}
             <div className="pt-4 border-t border-gray-700">
               <div className="flex gap-3">
@@ -873,6 +2202,98 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                 </button>
               </div>
 
+              {/* Portable archive for moving the library between machines */}
+              <div className="mt-3 flex gap-3">
+                <button
+                  onClick={handleExportArchive}
+                  disabled={isBusy}
+                  className="flex-1 py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                  title="Database, settings and keychains in one file"
+                >
+                  Export archive
+                </button>
+                <button
+                  onClick={handleImportArchive}
+                  disabled={isBusy}
+                  className="flex-1 py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                  title="Merge another library; flights you already have are skipped"
+                >
+                  Merge archive
+                </button>
+                <button
+                  onClick={handleExportStats}
+                  disabled={isBusy}
+                  className="flex-1 py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                  title="Totals, per-drone and per-month figures and record flights as JSON"
+                >
+                  Export statistics
+                </button>
+              </div>
+
+              {/* Full database snapshots (desktop only) */}
+              {!api.isWebMode() && (
+                <div className="mt-3 space-y-2">
+                  <div className="flex gap-3">
+                    <button
+                      onClick={handleSnapshot}
+                      disabled={isBusy}
+                      className="flex-1 py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                    >
+                      Save snapshot
+                    </button>
+                    <button
+                      onClick={() => setConfirmRestoreSnapshot(true)}
+                      disabled={isBusy}
+                      className="flex-1 py-1.5 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-xs"
+                    >
+                      Restore snapshot
+                    </button>
+                  </div>
+                  {confirmRestoreSnapshot && (
+                    <div className="rounded-lg border border-amber-600/60 bg-amber-500/10 p-3">
+                      <p className="text-xs text-amber-200">
+                        Replace the whole library with a snapshot? The current library is saved to the backups folder first.
+                      </p>
+                      <div className="mt-2 flex items-center gap-3">
+                        <button
+                          onClick={handleRestoreSnapshot}
+                          className="text-xs text-amber-300 hover:text-amber-200"
+                        >
+                          Choose file
+                        </button>
+                        <button
+                          onClick={() => setConfirmRestoreSnapshot(false)}
+                          className="text-xs text-gray-400 hover:text-gray-200"
+                        >
+                          Cancel
+                        </button>
+                      </div>
+                    </div>
+                  )}
+                  <button
+                    type="button"
+                    onClick={handleToggleAutoBackup}
+                    className="flex items-center justify-between gap-3 w-full text-xs text-gray-300"
+                    aria-pressed={autoBackupEnabled}
+                  >
+                    <span>Snapshot before large imports (keeps last 5)</span>
+                    <span
+                      className={`relative inline-flex h-5 w-9 items-center rounded-full border transition-all ${
+                        autoBackupEnabled
+                          ? 'bg-drone-primary/90 border-drone-primary'
+                          : 'bg-drone-surface border-gray-600 toggle-track-off'
+                      }`}
+                    >
+                      <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white shadow transition-transform ${
+                          autoBackupEnabled ? 'translate-x-4' : 'translate-x-1'
+                        }`}
+                      />
+                    </span>
+                  </button>
+                </div>
+              )}
+
               {confirmDeleteAll ? (
                 <div className="mt-4 rounded-lg border border-red-600/60 bg-red-500/10 p-3">
                   <p className="text-xs text-red-200">
@@ -927,6 +2348,104 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                 )}
               </button>
 
+              {/* Stitch Split Flights */}
+              <div className="mt-3 flex items-center gap-2">
+                <button
+                  onClick={handleFindSplitFlights}
+                  disabled={isBusy}
+                  className="flex-1 py-2 px-3 rounded-lg border border-violet-600 text-violet-400 hover:bg-violet-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+                >
+                  Find split flights
+                </button>
+                <label className="flex items-center gap-1 text-xs text-gray-400" title="Max gap between two log files of the same flight">
+                  <input
+                    type="number"
+                    min={1}
+                    value={stitchMaxGap}
+                    onChange={(e) => setStitchMaxGap(Math.max(1, Number(e.target.value) || 1))}
+                    className="input w-16 py-1 px-2 text-xs"
+                  />
+                  s gap
+                </label>
+              </div>
+              {stitchCandidates && stitchCandidates.length > 0 && (
+                <div className="mt-2 space-y-2">
+                  {stitchCandidates.map((c) => (
+                    <div
+                      key={`${c.firstId}-${c.secondId}`}
+                      className="flex items-center justify-between gap-2 rounded-lg border border-gray-700 px-3 py-2"
+                    >
+                      <div className="min-w-0 text-xs">
+                        <p className="truncate text-gray-300" title={`${c.firstName} + ${c.secondName}`}>
+                          {c.firstName} + {c.secondName}
+                        </p>
+                        <p className="text-gray-500">
+                          {Math.round(c.gapSecs)}s gap
+                          {c.endBatteryPercent != null && c.startBatteryPercent != null &&
+                            ` · battery ${c.endBatteryPercent}% → ${c.startBatteryPercent}%`}
+                        </p>
+                      </div>
+                      <button
+                        onClick={() => handleStitch(c)}
+                        disabled={isBusy}
+                        className="shrink-0 text-xs text-violet-400 hover:text-violet-300 disabled:opacity-50"
+                      >
+                        Merge
+                      </button>
+                    </div>
+                  ))}
+                </div>
+              )}
+
+              {/* Trash */}
+              <button
+                onClick={handleShowTrash}
+                disabled={isBusy}
+                className="mt-3 w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                Show deleted flights
+              </button>
+              {trashedFlights && trashedFlights.length > 0 && (
+                <div className="mt-2 space-y-2">
+                  {trashedFlights.map((flight) => (
+                    <div
+                      key={flight.id}
+                      className="flex items-center justify-between gap-2 rounded-lg border border-gray-700 px-3 py-2"
+                    >
+                      <div className="min-w-0 text-xs">
+                        <p className="truncate text-gray-300" title={flight.fileName}>
+                          {flight.displayName || flight.fileName}
+                        </p>
+                        {flight.deletedAt && (
+                          <p className="text-gray-500">Deleted {new Date(flight.deletedAt).toLocaleString()}</p>
+                        )}
+                      </div>
+                      <div className="shrink-0 flex items-center gap-3">
+                        <button
+                          onClick={() => handleRestoreFlight(flight)}
+                          className="text-xs text-emerald-400 hover:text-emerald-300"
+                        >
+                          Restore
+                        </button>
+                        <button
+                          onClick={() => handlePurgeFlight(flight)}
+                          className="text-xs text-red-400 hover:text-red-300"
+                          title="Delete permanently"
+                        >
+                          Delete
+                        </button>
+                      </div>
+                    </div>
+                  ))}
+                  <button
+                    onClick={handleEmptyTrash}
+                    className="w-full text-xs text-red-400 hover:text-red-300 py-1"
+                  >
+                    Empty trash
+                  </button>
+                </div>
+              )}
+
               {/* Clear Sync Blacklist */}
               {blacklistCount > 0 && (
                 <>
@@ -937,11 +2456,15 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                       </p>
                       <div className="mt-2 flex items-center gap-3">
                         <button
-                          onClick={() => {
-                            clearBlacklist();
-                            setBlacklistCount(0);
+                          onClick={async () => {
                             setConfirmClearBlacklist(false);
-                            setMessage({ type: 'success', text: 'Blacklist cleared.' });
+                            try {
+                              await clearBlacklist();
+                              setBlacklistCount(0);
+                              setMessage({ type: 'success', text: 'Blacklist cleared.' });
+                            } catch (err) {
+                              setMessage({ type: 'error', text: `Failed to clear blacklist: ${err}` });
+                            }
                           }}
                           className="text-xs text-amber-300 hover:text-amber-200"
                         >
@@ -1073,3 +2596,327 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     </div>
   );
 }
+
+/** Editable nickname, registration, purchase date and notes of one aircraft */
+function DroneProfileRow({
+  profile,
+  displaySerial,
+  onSave,
+}: {
+  profile: DroneProfile;
+  displaySerial: string;
+  onSave: (
+    nickname: string | null,
+    purchaseDate: string | null,
+    notes: string | null,
+    registration: string | null
+  ) => Promise<void>;
+}) {
+  const [nickname, setNickname] = useState(profile.nickname ?? '');
+  const [purchaseDate, setPurchaseDate] = useState(profile.purchaseDate ?? '');
+  const [notes, setNotes] = useState(profile.notes ?? '');
+  const [registration, setRegistration] = useState(profile.registration ?? '');
+  const [isSaving, setIsSaving] = useState(false);
+  const [firmwareHistory, setFirmwareHistory] = useState<FirmwareHistoryEntry[] | null>(null);
+
+  useEffect(() => {
+    setNickname(profile.nickname ?? '');
+    setPurchaseDate(profile.purchaseDate ?? '');
+    setNotes(profile.notes ?? '');
+    setRegistration(profile.registration ?? '');
+  }, [profile.nickname, profile.purchaseDate, profile.notes, profile.registration]);
+
+  const isDirty =
+    nickname !== (profile.nickname ?? '') ||
+    purchaseDate !== (profile.purchaseDate ?? '') ||
+    notes !== (profile.notes ?? '') ||
+    registration !== (profile.registration ?? '');
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      await onSave(nickname.trim() || null, purchaseDate || null, notes.trim() || null, registration.trim() || null);
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <li className="text-xs text-gray-300 space-y-1.5">
+      <p className="truncate text-gray-400">
+        {profile.aircraftName || profile.droneModel || 'Unknown'}
+        <span className="text-gray-500">
+          {' '}· SN {displaySerial} · {profile.flightCount} flight{profile.flightCount === 1 ? '' : 's'}
+          {profile.firmwareVersion && ` · FW ${profile.firmwareVersion}`}
+        </span>
+        {profile.firmwareVersion && (
+          <button
+            type="button"
+            onClick={() =>
+              firmwareHistory
+                ? setFirmwareHistory(null)
+                : api.getFirmwareHistory(profile.serial).then(setFirmwareHistory).catch(() => setFirmwareHistory([]))
+            }
+            className="ml-2 text-gray-500 hover:text-white"
+          >
+            {firmwareHistory ? 'Hide firmware history' : 'Firmware history'}
+          </button>
+        )}
+      </p>
+      {firmwareHistory && firmwareHistory.length > 0 && (
+        <ul className="pl-2 space-y-0.5 text-gray-500">
+          {firmwareHistory.map((entry) => (
+            <li key={entry.firmwareVersion}>
+              {entry.firmwareVersion} · {entry.firstFlight?.slice(0, 10) ?? '?'} – {entry.lastFlight?.slice(0, 10) ?? '?'} ·{' '}
+              {entry.flightCount} flight{entry.flightCount === 1 ? '' : 's'}
+            </li>
+          ))}
+        </ul>
+      )}
+      <div className="grid grid-cols-3 gap-2">
+        <input
+          type="text"
+          value={nickname}
+          onChange={(e) => setNickname(e.target.value)}
+          placeholder="Nickname"
+          className="input w-full text-sm"
+        />
+        <input
+          type="text"
+          value={registration}
+          onChange={(e) => setRegistration(e.target.value)}
+          placeholder="Registration"
+          title="Registration number, printed on the logbook"
+          className="input w-full text-sm"
+        />
+        <input
+          type="date"
+          value={purchaseDate}
+          onChange={(e) => setPurchaseDate(e.target.value)}
+          title="Purchase date"
+          className="input w-full text-sm"
+        />
+      </div>
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={notes}
+          onChange={(e) => setNotes(e.target.value)}
+          placeholder="Notes"
+          className="input flex-1 text-sm"
+        />
+        <button
+          type="button"
+          onClick={handleSave}
+          disabled={!isDirty || isSaving}
+          className="py-1 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+        >
+          Save
+        </button>
+      </div>
+    </li>
+  );
+}
+
+type ExportTemplateDraft = Pick<ExportTemplate, 'name' | 'format' | 'columns' | 'units'>;
+
+const EMPTY_EXPORT_TEMPLATE: ExportTemplateDraft = { name: '', format: 'csv', columns: [], units: {} };
+
+/** Saved export setups, offered next to the built-in formats when exporting */
+function ExportTemplatesSection({
+  isOpen,
+  disabled,
+  onMessage,
+}: {
+  isOpen: boolean;
+  disabled: boolean;
+  onMessage: (message: { type: 'success' | 'error'; text: string } | null) => void;
+}) {
+  const [templates, setTemplates] = useState<ExportTemplate[]>([]);
+  const [telemetryColumns, setTelemetryColumns] = useState<string[]>([]);
+  const [draft, setDraft] = useState<ExportTemplateDraft>(EMPTY_EXPORT_TEMPLATE);
+  const [editingId, setEditingId] = useState<number | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    api.getExportTemplates().then(setTemplates).catch(() => setTemplates([]));
+    api.getTelemetryColumns().then(setTelemetryColumns).catch(() => setTelemetryColumns([]));
+    setDraft(EMPTY_EXPORT_TEMPLATE);
+    setEditingId(null);
+  }, [isOpen]);
+
+  const toggleColumn = (column: string) => {
+    setDraft((prev) => ({
+      ...prev,
+      columns: prev.columns.includes(column)
+        ? prev.columns.filter((c) => c !== column)
+        : [...prev.columns, column],
+    }));
+  };
+
+  const handleSave = async () => {
+    try {
+      const saved = await api.saveExportTemplate(
+        { ...draft, columns: draft.format === 'csv' ? draft.columns : [] },
+        editingId ?? undefined
+      );
+      setTemplates((prev) =>
+        [...prev.filter((t) => t.id !== saved.id), saved].sort((a, b) => a.name.localeCompare(b.name))
+      );
+      setDraft(EMPTY_EXPORT_TEMPLATE);
+      setEditingId(null);
+      onMessage({ type: 'success', text: `Export template "${saved.name}" saved` });
+    } catch (err) {
+      onMessage({ type: 'error', text: `Failed to save export template: ${err}` });
+    }
+  };
+
+  const handleDelete = async (template: ExportTemplate) => {
+    try {
+      await api.deleteExportTemplate(template.id);
+      setTemplates((prev) => prev.filter((t) => t.id !== template.id));
+      if (editingId === template.id) {
+        setDraft(EMPTY_EXPORT_TEMPLATE);
+        setEditingId(null);
+      }
+    } catch (err) {
+      onMessage({ type: 'error', text: `Failed to delete export template: ${err}` });
+    }
+  };
+
+  return (
+    <div className="pt-4 border-t border-gray-700">
+      <p className="text-sm font-medium text-gray-300 mb-2">Export Templates</p>
+      <p className="text-xs text-gray-500 mb-3">
+        Save a format, units and, for CSV, a choice of telemetry columns under a name. Templates are offered in the
+        flight and flight list export menus.
+      </p>
+      {templates.length > 0 && (
+        <ul className="space-y-1 mb-3">
+          {templates.map((template) => (
+            <li key={template.id} className="flex items-center gap-2 text-xs text-gray-300">
+              <span className="flex-1 truncate">
+                {template.name}
+                <span className="text-gray-500">
+                  {' '}· {template.format.toUpperCase()}
+                  {template.format === 'csv' &&
+                    ` · ${template.columns.length > 0 ? `${template.columns.length} columns` : 'all columns'}`}
+                </span>
+              </span>
+              <button
+                type="button"
+                onClick={() => {
+                  setDraft({ name: template.name, format: template.format, columns: template.columns, units: template.units });
+                  setEditingId(template.id);
+                }}
+                className="text-gray-500 hover:text-white"
+              >
+                Edit
+              </button>
+              <button
+                type="button"
+                onClick={() => handleDelete(template)}
+                disabled={disabled}
+                className="text-gray-500 hover:text-red-400 disabled:opacity-50"
+              >
+                Delete
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+      <input
+        type="text"
+        value={draft.name}
+        onChange={(e) => setDraft((prev) => ({ ...prev, name: e.target.value }))}
+        placeholder="Template name, e.g. Insurance report"
+        className="input w-full text-sm mb-2"
+      />
+      <div className="grid grid-cols-2 gap-2 mb-2">
+        <Select
+          value={draft.format}
+          onChange={(v) => setDraft((prev) => ({ ...prev, format: v as ExportTemplate['format'] }))}
+          className="w-full"
+          options={[
+            { value: 'csv', label: 'CSV' },
+            { value: 'json', label: 'JSON' },
+            { value: 'gpx', label: 'GPX' },
+            { value: 'kml', label: 'KML' },
+          ]}
+        />
+        <Select
+          value={draft.units.length ?? 'm'}
+          onChange={(v) => setDraft((prev) => ({ ...prev, units: { ...prev.units, length: v as 'm' | 'ft' } }))}
+          className="w-full"
+          options={[
+            { value: 'm', label: 'Meters' },
+            { value: 'ft', label: 'Feet' },
+          ]}
+        />
+        <Select
+          value={draft.units.speed ?? 'ms'}
+          onChange={(v) =>
+            setDraft((prev) => ({ ...prev, units: { ...prev.units, speed: v as 'ms' | 'kmh' | 'mph' | 'kn' } }))
+          }
+          className="w-full"
+          options={[
+            { value: 'ms', label: 'm/s' },
+            { value: 'kmh', label: 'km/h' },
+            { value: 'mph', label: 'mph' },
+            { value: 'kn', label: 'Knots' },
+          ]}
+        />
+        <Select
+          value={draft.units.temperature ?? 'c'}
+          onChange={(v) => setDraft((prev) => ({ ...prev, units: { ...prev.units, temperature: v as 'c' | 'f' } }))}
+          className="w-full"
+          options={[
+            { value: 'c', label: '°C' },
+            { value: 'f', label: '°F' },
+          ]}
+        />
+      </div>
+      {draft.format === 'csv' && telemetryColumns.length > 0 && (
+        <div className="mb-2">
+          <p className="text-xs text-gray-500 mb-1">
+            Columns, in the order picked ({draft.columns.length > 0 ? draft.columns.length : 'all'})
+          </p>
+          <div className="max-h-40 overflow-y-auto grid grid-cols-2 gap-x-2 border border-gray-700 rounded-lg p-2">
+            {telemetryColumns.map((column) => (
+              <label key={column} className="flex items-center gap-1.5 text-xs text-gray-300 truncate">
+                <input
+                  type="checkbox"
+                  checked={draft.columns.includes(column)}
+                  onChange={() => toggleColumn(column)}
+                />
+                {column}
+              </label>
+            ))}
+          </div>
+        </div>
+      )}
+      <div className="flex gap-2">
+        <button
+          type="button"
+          onClick={handleSave}
+          disabled={disabled || !draft.name.trim()}
+          className="flex-1 py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+        >
+          {editingId !== null ? 'Update template' : 'Save template'}
+        </button>
+        {editingId !== null && (
+          <button
+            type="button"
+            onClick={() => {
+              setDraft(EMPTY_EXPORT_TEMPLATE);
+              setEditingId(null);
+            }}
+            className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-700/50 transition-colors text-sm"
+          >
+            Cancel
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
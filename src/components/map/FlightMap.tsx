@@ -13,18 +13,20 @@ import 'maplibre-gl/dist/maplibre-gl.css';
 import { getTrackCenter, calculateBounds, formatAltitude, formatSpeed, formatDistance } from '@/lib/utils';
 import { useFlightStore } from '@/stores/flightStore';
 import { Select } from '@/components/ui/Select';
-import type { TelemetryData } from '@/types';
+import type { HomePoint, TelemetryData } from '@/types';
 
 interface FlightMapProps {
   track: [number, number, number][]; // [lng, lat, alt][]
   homeLat?: number | null;
   homeLon?: number | null;
+  /** Home point history; later entries replace the home point mid-flight */
+  homePoints?: HomePoint[];
   durationSecs?: number | null;
   telemetry?: TelemetryData;
   themeMode: 'system' | 'dark' | 'light';
 }
 
-type ColorByMode = 'progress' | 'height' | 'speed' | 'distance' | 'videoSegment';
+type ColorByMode = 'progress' | 'height' | 'speed' | 'distance' | 'videoSegment' | 'signal';
 
 const MAP_STYLES = {
   dark: 'https://basemaps.cartocdn.com/gl/dark-matter-gl-style/style.json',
@@ -184,6 +186,15 @@ const RAMP_DISTANCE: [number, number, number][] = [
   [239, 68, 68],
 ];
 
+// Red → Yellow → Green  (weak→strong link)
+const RAMP_SIGNAL: [number, number, number][] = [
+  [239, 68, 68],
+  [250, 204, 21],
+  [34, 197, 94],
+];
+// Gray where the log has no signal reading
+const COLOR_NO_SIGNAL: [number, number, number] = [107, 114, 128];
+
 // Blue for normal flight, Red for video recording segments
 const COLOR_VIDEO_NORMAL: [number, number, number] = [59, 130, 246]; // Blue
 const COLOR_VIDEO_RECORDING: [number, number, number] = [239, 68, 68]; // Red
@@ -194,6 +205,7 @@ const COLOR_BY_OPTIONS: { value: ColorByMode; label: string }[] = [
   { value: 'speed', label: 'Speed' },
   { value: 'distance', label: 'Dist. from Home' },
   { value: 'videoSegment', label: 'Video Segment' },
+  { value: 'signal', label: 'Signal' },
 ];
 
 // ─── Arrow icon for replay marker ───────────────────────────────────────────
@@ -304,7 +316,7 @@ function StickArrows({ up, down, left, right }: StickArrowsProps) {
   );
 }
 
-export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, themeMode }: FlightMapProps) {
+export function FlightMap({ track, homeLat, homeLon, homePoints = [], durationSecs, telemetry, themeMode }: FlightMapProps) {
   const [viewState, setViewState] = useState({
     longitude: 0,
     latitude: 0,
@@ -324,7 +336,7 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
   const [hoverInfo, setHoverInfo] = useState<{
     x: number; y: number;
     height: number; speed: number; distance: number; progress: number;
-    lat: number; lng: number;
+    lat: number; lng: number; signal: number | null;
   } | null>(null);
   const { unitSystem, mapSyncEnabled, setMapReplayProgress } = useFlightStore();
   const mapRef = useRef<MapRef | null>(null);
@@ -603,6 +615,8 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
     const roll = lerp(telemetry.roll);
     const yaw = lerp(telemetry.yaw);
     const rcSignal = telemetry.rcSignal?.[lo] ?? null;
+    const rcUplink = telemetry.rcUplink?.[lo] ?? null;
+    const rcDownlink = telemetry.rcDownlink?.[lo] ?? null;
     const batteryVoltage = lerp(telemetry.batteryVoltage);
     const batteryTemp = lerp(telemetry.batteryTemp);
     const rcAileron = lerp(telemetry.rcAileron);
@@ -613,8 +627,11 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
     // Compute distance from home at this point
     const lat = lerp(telemetry.latitude);
     const lng = lerp(telemetry.longitude);
-    const hLat = homeLat ?? (track.length > 0 ? track[0]?.[1] : null) ?? 0;
-    const hLon = homeLon ?? (track.length > 0 ? track[0]?.[0] : null) ?? 0;
+    // Use the home point that was active at this moment (dynamic home / RTH refresh)
+    const replayMs = (durationSecs ?? 0) * 1000 * replayProgress;
+    const activeHome = homePoints.filter((h) => h.timestampMs <= replayMs).pop();
+    const hLat = activeHome?.lat ?? homeLat ?? (track.length > 0 ? track[0]?.[1] : null) ?? 0;
+    const hLon = activeHome?.lon ?? homeLon ?? (track.length > 0 ? track[0]?.[0] : null) ?? 0;
     const distHome = lat !== null && lng !== null
       ? haversineM(hLat, hLon, lat, lng)
       : null;
@@ -626,11 +643,11 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
 
     return {
       height, speed, battery, satellites, altitude, vpsHeight,
-      pitch, roll, yaw, rcSignal, batteryVoltage, batteryTemp,
+      pitch, roll, yaw, rcSignal, rcUplink, rcDownlink, batteryVoltage, batteryTemp,
       rcAileron, rcElevator, rcThrottle, rcRudder,
       distHome, timeSecs, lat, lng,
     };
-  }, [telemetry, track, replayProgress, homeLat, homeLon, durationSecs]);
+  }, [telemetry, track, replayProgress, homeLat, homeLon, homePoints, durationSecs]);
 
   const handlePlayPause = useCallback(() => {
     if (isPlaying) {
@@ -694,6 +711,11 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
     const n = smoothedTrack.length;
     const rawN = track.length;
     const telemetryN = telemetry?.isVideo?.length ?? 0;
+    // Map smoothed point → raw track index → telemetry index
+    const telemetryIndexOf = (i: number, count: number) => {
+      const rawTrackIndex = Math.round((i / Math.max(1, n - 1)) * Math.max(1, rawN - 1));
+      return Math.round((rawTrackIndex / Math.max(1, rawN - 1)) * Math.max(1, count - 1));
+    };
 
     // Pre-compute per-point values depending on colorBy mode
     let values: number[] | null = null;
@@ -706,14 +728,29 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
     if (colorBy === 'videoSegment' && telemetry?.isVideo && telemetryN > 0) {
       isVideoAtIndex = [];
       for (let i = 0; i < n; i++) {
-        // Map smoothed point → raw track index → telemetry index
-        const rawTrackIndex = Math.round((i / Math.max(1, n - 1)) * Math.max(1, rawN - 1));
-        const telemetryIndex = Math.round((rawTrackIndex / Math.max(1, rawN - 1)) * Math.max(1, telemetryN - 1));
-        const isRecording = telemetry.isVideo[telemetryIndex] === true;
+        const isRecording = telemetry.isVideo[telemetryIndexOf(i, telemetryN)] === true;
         isVideoAtIndex.push(isRecording);
       }
     }
 
+    // Weakest of the uplink and downlink (or the combined RC signal) at each point, 0-100
+    const signalN = telemetry?.time.length ?? 0;
+    const signalAtIndex: (number | null)[] = [];
+    for (let i = 0; i < n; i++) {
+      if (!telemetry || signalN === 0) {
+        signalAtIndex.push(null);
+        continue;
+      }
+      const t = telemetryIndexOf(i, signalN);
+      const links = [telemetry.rcUplink?.[t], telemetry.rcDownlink?.[t]].filter(
+        (v): v is number => v !== null && v !== undefined
+      );
+      const combined = telemetry.rcSignal?.[t];
+      signalAtIndex.push(
+        links.length > 0 ? Math.min(...links) : combined !== null && combined !== undefined ? combined : null
+      );
+    }
+
     if (colorBy === 'height') {
       values = smoothedTrack.map((p) => p[2]);
       minVal = Math.min(...values);
@@ -764,7 +801,7 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
     const segments: {
       path: [number, number, number][];
       color: [number, number, number];
-      meta: { height: number; speed: number; distance: number; progress: number; lat: number; lng: number };
+      meta: { height: number; speed: number; distance: number; progress: number; lat: number; lng: number; signal: number | null };
     }[] = [];
 
     for (let i = 0; i < n - 1; i++) {
@@ -777,6 +814,9 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
         // Use red for video recording, blue for normal flight
         const isRecording = isVideoAtIndex ? isVideoAtIndex[i] : false;
         color = isRecording ? COLOR_VIDEO_RECORDING : COLOR_VIDEO_NORMAL;
+      } else if (colorBy === 'signal') {
+        const signal = signalAtIndex[i];
+        color = signal === null ? COLOR_NO_SIGNAL : valueToColor(signal / 100, RAMP_SIGNAL);
       } else {
         const t = values ? (values[i] - minVal) / range : i / Math.max(1, n - 2);
         color = valueToColor(t, ramp);
@@ -797,6 +837,7 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
           progress: i / Math.max(1, n - 2),
           lat: lat1,
           lng: lng1,
+          signal: signalAtIndex[i],
         },
       });
     }
@@ -1338,6 +1379,18 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
           </Marker>
         )}
 
+        {/* Updated home points recorded mid-flight */}
+        {homePoints.slice(1).map((h) => (
+          <Marker key={`home-${h.timestampMs}`} longitude={h.lon} latitude={h.lat} anchor="center">
+            <div
+              className="w-5 h-5 rounded-full border-2 border-white/80 bg-sky-500/60 flex items-center justify-center shadow"
+              title={`Home point updated at ${Math.round(h.timestampMs / 1000)}s`}
+            >
+              <span className="text-[9px] font-bold text-white leading-none">H</span>
+            </div>
+          </Marker>
+        ))}
+
         {/* Home Marker — "H" in a circle */}
         {homeLat != null && homeLon != null && Math.abs(homeLat) > 0.000001 && (
           <Marker longitude={homeLon} latitude={homeLat} anchor="center">
@@ -1406,6 +1459,12 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
               <span className="text-gray-400">Dist. Home</span>
               <span className="font-medium text-white">{formatDistance(hoverInfo.distance, unitSystem)}</span>
             </div>
+            {hoverInfo.signal !== null && (
+              <div className="flex justify-between gap-4">
+                <span className="text-gray-400">Signal</span>
+                <span className="font-medium text-white">{Math.round(hoverInfo.signal)}%</span>
+              </div>
+            )}
             <div className="border-t border-gray-700/60 mt-1 pt-1 flex justify-between gap-4">
               <span className="text-gray-500">Lat</span>
               <span className="text-gray-400">{hoverInfo.lat.toFixed(6)}</span>
@@ -1469,6 +1528,22 @@ export function FlightMap({ track, homeLat, homeLon, durationSecs, telemetry, th
               </div>
             )}
 
+            {/* Signal */}
+            {(replayTelemetry.rcUplink !== null || replayTelemetry.rcDownlink !== null) ? (
+              <div className="mt-2 pt-2 border-t border-gray-700/50 space-y-1">
+                {replayTelemetry.rcUplink !== null && (
+                  <ReplayStatRow label="RC Uplink" value={`${replayTelemetry.rcUplink}%`} />
+                )}
+                {replayTelemetry.rcDownlink !== null && (
+                  <ReplayStatRow label="Video Downlink" value={`${replayTelemetry.rcDownlink}%`} />
+                )}
+              </div>
+            ) : replayTelemetry.rcSignal !== null && (
+              <div className="mt-2 pt-2 border-t border-gray-700/50 space-y-1">
+                <ReplayStatRow label="RC Signal" value={`${replayTelemetry.rcSignal}%`} />
+              </div>
+            )}
+
             {/* Attitude */}
             {(replayTelemetry.pitch !== null || replayTelemetry.roll !== null || replayTelemetry.yaw !== null) && (
               <div className="mt-2 pt-2 border-t border-gray-700/50 space-y-1">
@@ -10,7 +10,7 @@
  * - "web"  (set when building for Docker/web deployment)
  */
 
-import type { Flight, FlightDataResponse, FlightTag, ImportResult, OverviewStats } from '@/types';
+import type { AuditEntry, AutoExportSettings, BatchExportResult, Battery, BatteryHealth, ChecklistItem, ChecklistTemplate, ComplianceLimits, ComplianceReport, CoverageOptions, CurrencyReport, CurrencyRequirement, DischargeCurve, DroneComparison, DroneEnergy, DroneProfile, Equipment, EquipmentCategory, ExportTemplate, ExportUnits, FirmwareHistoryEntry, Flight, FlightAnnotation, FlightChecklist, FlightDataResponse, FlightDocument, FlightFilter, FlightMedia, FlightModeStats, FlightModeTime, FlightSearchHit, FlightTag, ImportResult, Incident, IncidentKind, IncidentSeverity, Library, LibraryCheckReport, LibraryStats, MaintenanceKind, MaintenanceRecord, MaintenanceStatus, MediaScanResult, MilestoneReport, Mission, OverlayOptions, OverviewStats, PersonalRecords, PilotDetails, RthStats, Site, SiteStats, SmartList, StitchCandidate, TerrainProfile, TerrainSettings, TrackSmoothing, TrendBucket, TrendPoint, WarningSummary, YearReview } from '@/types';
 
 const isWeb = import.meta.env.VITE_BACKEND === 'web';
 
@@ -74,6 +74,145 @@ export async function getOverviewStats(): Promise<OverviewStats> {
   return invoke('get_overview_stats') as Promise<OverviewStats>;
 }
 
+/** Totals, per-drone and per-month statistics, of `flightIds` only when given */
+export async function getLibraryStats(flightIds?: number[]): Promise<LibraryStats> {
+  if (isWeb) {
+    return fetchJson<LibraryStats>('/overview/stats', {
+      method: 'POST',
+      body: JSON.stringify({ flight_ids: flightIds ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_library_stats', { flightIds: flightIds ?? null }) as Promise<LibraryStats>;
+}
+
+/** Flight count, time and distance per period, gaps filled with zeros, of `flightIds` only when given */
+export async function getTrends(bucket: TrendBucket, flightIds?: number[]): Promise<TrendPoint[]> {
+  if (isWeb) {
+    return fetchJson<TrendPoint[]>('/overview/trends', {
+      method: 'POST',
+      body: JSON.stringify({ bucket, flight_ids: flightIds ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_trends', { bucket, flightIds: flightIds ?? null }) as Promise<TrendPoint[]>;
+}
+
+/** Record flights (longest, farthest, highest, fastest), each with its flight id */
+export async function getPersonalRecords(): Promise<PersonalRecords> {
+  if (isWeb) {
+    return fetchJson<PersonalRecords>('/overview/records');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_personal_records') as Promise<PersonalRecords>;
+}
+
+/** Reached and upcoming flight, hour and distance milestones with flying streaks */
+export async function getMilestones(): Promise<MilestoneReport> {
+  if (isWeb) {
+    return fetchJson<MilestoneReport>('/overview/milestones');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_milestones') as Promise<MilestoneReport>;
+}
+
+/** Two aircraft (by serial) or two models side by side */
+export async function compareDrones(first: string, second: string): Promise<DroneComparison> {
+  if (isWeb) {
+    const params = new URLSearchParams({ first, second });
+    return fetchJson<DroneComparison>(`/overview/compare?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('compare_drones', { first, second }) as Promise<DroneComparison>;
+}
+
+/** Flights and hours of the pilot and each aircraft in the last 30/90/365 days, against the requirements */
+export async function getCurrency(): Promise<CurrencyReport> {
+  if (isWeb) {
+    return fetchJson<CurrencyReport>('/overview/currency');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_currency') as Promise<CurrencyReport>;
+}
+
+/** Flights above the height limit or beyond the distance limit, with the stretches that were */
+export async function getComplianceReport(): Promise<ComplianceReport> {
+  if (isWeb) {
+    return fetchJson<ComplianceReport>('/overview/compliance');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_compliance_report') as Promise<ComplianceReport>;
+}
+
+export async function getEnergyStats(): Promise<DroneEnergy[]> {
+  if (isWeb) {
+    return fetchJson<DroneEnergy[]>('/overview/energy');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_energy_stats') as Promise<DroneEnergy[]>;
+}
+
+export async function getFlightModeStats(): Promise<FlightModeStats> {
+  if (isWeb) {
+    return fetchJson<FlightModeStats>('/overview/modes');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_mode_stats') as Promise<FlightModeStats>;
+}
+
+/** RTH triggers, failsafe activations and RC signal losses per aircraft and month */
+export async function getRthStats(): Promise<RthStats> {
+  if (isWeb) {
+    return fetchJson<RthStats>('/overview/rth');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_rth_stats') as Promise<RthStats>;
+}
+
+export async function getFlightModes(flightId: number): Promise<FlightModeTime[]> {
+  if (isWeb) {
+    return fetchJson<FlightModeTime[]>(`/flights/modes?flight_id=${flightId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_modes', { flightId }) as Promise<FlightModeTime[]>;
+}
+
+export async function getSites(): Promise<Site[]> {
+  if (isWeb) {
+    return fetchJson<Site[]>('/sites');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_sites') as Promise<Site[]>;
+}
+
+/** Flight time, wind, seasons and aircraft of every site, in the order of `getSites` */
+export async function getSiteStats(): Promise<SiteStats[]> {
+  if (isWeb) {
+    return fetchJson<SiteStats[]>('/sites/stats');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_site_stats') as Promise<SiteStats[]>;
+}
+
+export async function reclusterSites(): Promise<Site[]> {
+  if (isWeb) {
+    return fetchJson<Site[]>('/sites/recluster', { method: 'POST' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('recluster_sites') as Promise<Site[]>;
+}
+
+export async function renameSite(siteId: number, name: string): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/sites/name', {
+      method: 'PUT',
+      body: JSON.stringify({ site_id: siteId, name }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('rename_site', { siteId, name }) as Promise<boolean>;
+}
+
 export async function getFlightData(
   flightId: number,
   maxPoints?: number,
@@ -118,6 +257,52 @@ export async function importLog(
   return invoke('import_log', { filePath: fileOrPath as string }) as Promise<ImportResult>;
 }
 
+/** Progress reported by the desktop batch importer after each file */
+export interface BatchImportProgress {
+  currentFile: string;
+  processed: number;
+  total: number;
+  percent: number;
+  failures: number;
+}
+
+/**
+ * Import many files in one call. The backend parses them in parallel and
+ * commits flights in transactions, reporting progress via `import-progress`.
+ * Tauri-only: results are returned in the same order as `filePaths`.
+ */
+export async function importLogsBatch(
+  filePaths: string[],
+  onProgress?: (progress: BatchImportProgress) => void,
+): Promise<ImportResult[]> {
+  if (isWeb) {
+    throw new Error('Batch import is not supported in web mode.');
+  }
+  let unlisten: (() => void) | null = null;
+  if (onProgress) {
+    const { listen } = await import('@tauri-apps/api/event');
+    unlisten = await listen<BatchImportProgress>('import-progress', (event) => onProgress(event.payload));
+  }
+  try {
+    const invoke = await getTauriInvoke();
+    return (await invoke('import_logs_batch', { filePaths })) as ImportResult[];
+  } finally {
+    if (unlisten) unlisten();
+  }
+}
+
+/**
+ * Import every log inside a ZIP archive (nested archives included).
+ * Tauri-only: returns one result per contained log file.
+ */
+export async function importZip(filePath: string): Promise<ImportResult[]> {
+  if (isWeb) {
+    throw new Error('ZIP import is not supported in web mode.');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('import_zip', { filePath }) as Promise<ImportResult[]>;
+}
+
 /**
  * Compute file hash without importing.
  * Tauri-only: used to check blacklist before importing.
@@ -130,6 +315,44 @@ export async function computeFileHash(filePath: string): Promise<string> {
   return invoke('compute_file_hash', { filePath }) as Promise<string>;
 }
 
+export async function getImportBlacklist(): Promise<string[]> {
+  if (isWeb) {
+    return fetchJson<string[]>('/sync/blacklist');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_import_blacklist') as Promise<string[]>;
+}
+
+export async function addToImportBlacklist(fileHashes: string[]): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/sync/blacklist', {
+      method: 'POST',
+      body: JSON.stringify({ file_hashes: fileHashes }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('add_to_import_blacklist', { fileHashes }) as Promise<boolean>;
+}
+
+export async function removeFromImportBlacklist(fileHash: string): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/sync/blacklist/remove', {
+      method: 'POST',
+      body: JSON.stringify({ file_hash: fileHash }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('remove_from_import_blacklist', { fileHash }) as Promise<boolean>;
+}
+
+export async function clearImportBlacklist(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/sync/blacklist', { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('clear_import_blacklist') as Promise<number>;
+}
+
 export async function deleteFlight(flightId: number): Promise<boolean> {
   if (isWeb) {
     return fetchJson<boolean>(`/flights/delete?flight_id=${flightId}`, {
@@ -140,6 +363,43 @@ export async function deleteFlight(flightId: number): Promise<boolean> {
   return invoke('delete_flight', { flightId }) as Promise<boolean>;
 }
 
+export async function getDeletedFlights(): Promise<Flight[]> {
+  if (isWeb) {
+    return fetchJson<Flight[]>('/flights/trash');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_deleted_flights') as Promise<Flight[]>;
+}
+
+export async function restoreFlight(flightId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/flights/restore', {
+      method: 'POST',
+      body: JSON.stringify({ flight_id: flightId }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('restore_flight', { flightId }) as Promise<boolean>;
+}
+
+export async function purgeFlight(flightId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>(`/flights/purge?flight_id=${flightId}`, {
+      method: 'DELETE',
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('purge_flight', { flightId }) as Promise<boolean>;
+}
+
+export async function emptyTrash(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/flights/trash', { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('empty_trash') as Promise<number>;
+}
+
 export async function deleteAllFlights(): Promise<boolean> {
   if (isWeb) {
     return fetchJson<boolean>('/flights/delete_all', { method: 'DELETE' });
@@ -162,6 +422,26 @@ export async function deduplicateFlights(): Promise<number> {
   return invoke('deduplicate_flights') as Promise<number>;
 }
 
+export async function getStitchCandidates(maxGapSecs?: number): Promise<StitchCandidate[]> {
+  if (isWeb) {
+    const query = maxGapSecs !== undefined ? `?max_gap_secs=${maxGapSecs}` : '';
+    return fetchJson<StitchCandidate[]>(`/flights/stitch_candidates${query}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_stitch_candidates', { maxGapSecs: maxGapSecs ?? null }) as Promise<StitchCandidate[]>;
+}
+
+export async function stitchFlights(firstId: number, secondId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/flights/stitch', {
+      method: 'POST',
+      body: JSON.stringify({ first_id: firstId, second_id: secondId }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('stitch_flights', { firstId, secondId }) as Promise<boolean>;
+}
+
 export async function updateFlightName(
   flightId: number,
   displayName: string,
@@ -190,408 +470,2022 @@ export async function updateFlightNotes(
   return invoke('update_flight_notes', { flightId, notes }) as Promise<boolean>;
 }
 
-export async function hasApiKey(): Promise<boolean> {
+export async function setFlightFavorite(
+  flightId: number,
+  favorite: boolean,
+): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<boolean>('/has_api_key');
+    return fetchJson<boolean>('/flights/favorite', {
+      method: 'PUT',
+      body: JSON.stringify({ flight_id: flightId, favorite }),
+    });
   }
   const invoke = await getTauriInvoke();
-  return invoke('has_api_key') as Promise<boolean>;
+  return invoke('set_flight_favorite', { flightId, favorite }) as Promise<boolean>;
 }
 
-export async function getApiKeyType(): Promise<string> {
+export async function setFlightExcludedFromStats(
+  flightId: number,
+  excluded: boolean,
+): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<string>('/api_key_type');
+    return fetchJson<boolean>('/flights/excluded', {
+      method: 'PUT',
+      body: JSON.stringify({ flight_id: flightId, excluded }),
+    });
   }
   const invoke = await getTauriInvoke();
-  return invoke('get_api_key_type') as Promise<string>;
+  return invoke('set_flight_excluded_from_stats', { flightId, excluded }) as Promise<boolean>;
 }
 
-export async function setApiKey(apiKey: string): Promise<boolean> {
+export async function addIncident(
+  flightId: number,
+  offsetSecs: number,
+  kind: IncidentKind,
+  severity: IncidentSeverity,
+  description: string | null,
+): Promise<Incident> {
   if (isWeb) {
-    return fetchJson<boolean>('/set_api_key', {
+    return fetchJson<Incident>('/incidents', {
       method: 'POST',
-      body: JSON.stringify({ api_key: apiKey }),
+      body: JSON.stringify({ flight_id: flightId, offset_secs: offsetSecs, kind, severity, description }),
     });
   }
   const invoke = await getTauriInvoke();
-  return invoke('set_api_key', { apiKey }) as Promise<boolean>;
+  return invoke('add_incident', { flightId, offsetSecs, kind, severity, description }) as Promise<Incident>;
 }
 
-export async function removeApiKey(): Promise<boolean> {
+export async function getIncidents(filter: {
+  flightId?: number;
+  kind?: IncidentKind;
+  severity?: IncidentSeverity;
+} = {}): Promise<Incident[]> {
   if (isWeb) {
-    return fetchJson<boolean>('/remove_api_key', {
-      method: 'DELETE',
-    });
+    const params = new URLSearchParams();
+    if (filter.flightId !== undefined) params.set('flight_id', String(filter.flightId));
+    if (filter.kind) params.set('kind', filter.kind);
+    if (filter.severity) params.set('severity', filter.severity);
+    return fetchJson<Incident[]>(`/incidents?${params.toString()}`);
   }
   const invoke = await getTauriInvoke();
-  return invoke('remove_api_key') as Promise<boolean>;
+  return invoke('get_incidents', {
+    flightId: filter.flightId ?? null,
+    kind: filter.kind ?? null,
+    severity: filter.severity ?? null,
+  }) as Promise<Incident[]>;
 }
 
-export async function getAppDataDir(): Promise<string> {
+export async function updateIncident(
+  incidentId: number,
+  kind: IncidentKind,
+  severity: IncidentSeverity,
+  description: string | null,
+): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<string>('/app_data_dir');
+    return fetchJson<boolean>('/incidents', {
+      method: 'PUT',
+      body: JSON.stringify({ incident_id: incidentId, kind, severity, description }),
+    });
   }
   const invoke = await getTauriInvoke();
-  return invoke('get_app_data_dir') as Promise<string>;
+  return invoke('update_incident', { incidentId, kind, severity, description }) as Promise<boolean>;
 }
 
-export async function getAppLogDir(): Promise<string> {
+export async function deleteIncident(incidentId: number): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<string>('/app_log_dir');
+    return fetchJson<boolean>(`/incidents?incident_id=${incidentId}`, { method: 'DELETE' });
   }
   const invoke = await getTauriInvoke();
-  return invoke('get_app_log_dir') as Promise<string>;
+  return invoke('delete_incident', { incidentId }) as Promise<boolean>;
 }
 
-// ============================================================================
-// Tag Management
-// ============================================================================
+export async function getChecklistTemplates(): Promise<ChecklistTemplate[]> {
+  if (isWeb) {
+    return fetchJson<ChecklistTemplate[]>('/checklists/templates');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_checklist_templates') as Promise<ChecklistTemplate[]>;
+}
 
-export async function addFlightTag(flightId: number, tag: string): Promise<FlightTag[]> {
+/** Create a checklist template, or replace it when `id` is given */
+export async function saveChecklistTemplate(
+  id: number | null,
+  name: string,
+  items: string[],
+): Promise<ChecklistTemplate> {
   if (isWeb) {
-    return fetchJson<FlightTag[]>('/flights/tags/add', {
+    return fetchJson<ChecklistTemplate>('/checklists/templates', {
       method: 'POST',
-      body: JSON.stringify({ flight_id: flightId, tag }),
+      body: JSON.stringify({ id, name, items }),
     });
   }
   const invoke = await getTauriInvoke();
-  return invoke('add_flight_tag', { flightId, tag }) as Promise<FlightTag[]>;
+  return invoke('save_checklist_template', { id, name, items }) as Promise<ChecklistTemplate>;
 }
 
-export async function removeFlightTag(flightId: number, tag: string): Promise<FlightTag[]> {
+export async function deleteChecklistTemplate(templateId: number): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<FlightTag[]>('/flights/tags/remove', {
+    return fetchJson<boolean>(`/checklists/templates?template_id=${templateId}`, { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_checklist_template', { templateId }) as Promise<boolean>;
+}
+
+export async function recordFlightChecklist(
+  flightId: number,
+  templateId: number | null,
+  name: string,
+  items: ChecklistItem[],
+  completedBy: string | null,
+  notes: string | null,
+): Promise<FlightChecklist> {
+  if (isWeb) {
+    return fetchJson<FlightChecklist>('/flights/checklists', {
       method: 'POST',
-      body: JSON.stringify({ flight_id: flightId, tag }),
+      body: JSON.stringify({
+        flight_id: flightId,
+        template_id: templateId,
+        name,
+        items,
+        completed_by: completedBy,
+        notes,
+      }),
     });
   }
   const invoke = await getTauriInvoke();
-  return invoke('remove_flight_tag', { flightId, tag }) as Promise<FlightTag[]>;
+  return invoke('record_flight_checklist', { flightId, templateId, name, items, completedBy, notes }) as Promise<FlightChecklist>;
 }
 
-export async function getAllTags(): Promise<string[]> {
+export async function getFlightChecklists(flightId: number): Promise<FlightChecklist[]> {
   if (isWeb) {
-    return fetchJson<string[]>('/tags');
+    return fetchJson<FlightChecklist[]>(`/flights/checklists?flight_id=${flightId}`);
   }
   const invoke = await getTauriInvoke();
-  return invoke('get_all_tags') as Promise<string[]>;
+  return invoke('get_flight_checklists', { flightId }) as Promise<FlightChecklist[]>;
 }
 
-export async function getSmartTagsEnabled(): Promise<boolean> {
+export async function deleteFlightChecklist(checklistId: number): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<boolean>('/settings/smart_tags');
+    return fetchJson<boolean>(`/flights/checklists?checklist_id=${checklistId}`, { method: 'DELETE' });
   }
   const invoke = await getTauriInvoke();
-  return invoke('get_smart_tags_enabled') as Promise<boolean>;
+  return invoke('delete_flight_checklist', { checklistId }) as Promise<boolean>;
 }
 
-export async function setSmartTagsEnabled(enabled: boolean): Promise<boolean> {
+export async function getEquipment(): Promise<Equipment[]> {
   if (isWeb) {
-    return fetchJson<boolean>('/settings/smart_tags', {
-      method: 'POST',
-      body: JSON.stringify({ enabled }),
-    });
+    return fetchJson<Equipment[]>('/equipment');
   }
   const invoke = await getTauriInvoke();
-  return invoke('set_smart_tags_enabled', { enabled }) as Promise<boolean>;
+  return invoke('get_equipment') as Promise<Equipment[]>;
 }
 
-export async function regenerateSmartTags(): Promise<string> {
+/** Add an item to the inventory, or update it when `id` is given */
+export async function saveEquipment(
+  id: number | null,
+  name: string,
+  category: EquipmentCategory,
+  serial: string | null,
+  notes: string | null,
+): Promise<Equipment> {
   if (isWeb) {
-    return fetchJson<string>('/regenerate_smart_tags', {
+    return fetchJson<Equipment>('/equipment', {
       method: 'POST',
+      body: JSON.stringify({ id, name, category, serial, notes }),
     });
   }
   const invoke = await getTauriInvoke();
-  return invoke('regenerate_all_smart_tags') as Promise<string>;
+  return invoke('save_equipment', { id, name, category, serial, notes }) as Promise<Equipment>;
 }
 
-export async function removeAllAutoTags(): Promise<number> {
+export async function deleteEquipment(equipmentId: number): Promise<boolean> {
   if (isWeb) {
-    return fetchJson<number>('/tags/remove_auto', {
-      method: 'POST',
+    return fetchJson<boolean>(`/equipment?equipment_id=${equipmentId}`, { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_equipment', { equipmentId }) as Promise<boolean>;
+}
+
+export async function getFlightEquipment(flightId: number): Promise<number[]> {
+  if (isWeb) {
+    return fetchJson<number[]>(`/flights/equipment?flight_id=${flightId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_equipment', { flightId }) as Promise<number[]>;
+}
+
+export async function setFlightEquipment(flightId: number, equipmentIds: number[]): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/flights/equipment', {
+      method: 'PUT',
+      body: JSON.stringify({ flight_id: flightId, equipment_ids: equipmentIds }),
     });
   }
   const invoke = await getTauriInvoke();
-  return invoke('remove_all_auto_tags') as Promise<number>;
+  return invoke('set_flight_equipment', { flightId, equipmentIds }) as Promise<boolean>;
 }
 
-export async function regenerateFlightSmartTags(flightId: number, enabledTagTypes?: string[]): Promise<string> {
+/** IDs of the flights that used a piece of equipment, newest first */
+export async function getEquipmentFlightIds(equipmentId: number): Promise<number[]> {
   if (isWeb) {
-    return fetchJson<string>(`/regenerate_flight_smart_tags/${flightId}`, {
+    return fetchJson<number[]>(`/equipment/flights?equipment_id=${equipmentId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_equipment_flight_ids', { equipmentId }) as Promise<number[]>;
+}
+
+export async function addMaintenanceRecord(
+  targetType: 'drone' | 'battery',
+  serial: string,
+  kind: MaintenanceKind,
+  performedAt: string | null,
+  description: string | null,
+): Promise<MaintenanceRecord> {
+  if (isWeb) {
+    return fetchJson<MaintenanceRecord>('/maintenance', {
       method: 'POST',
-      body: JSON.stringify({ enabledTagTypes }),
+      body: JSON.stringify({ target_type: targetType, serial, kind, performed_at: performedAt, description }),
     });
   }
   const invoke = await getTauriInvoke();
-  return invoke('regenerate_flight_smart_tags', { flightId, enabledTagTypes }) as Promise<string>;
+  return invoke('add_maintenance_record', { targetType, serial, kind, performedAt, description }) as Promise<MaintenanceRecord>;
 }
 
-// ============================================================================
+export async function getMaintenanceRecords(
+  targetType?: 'drone' | 'battery',
+  serial?: string,
+): Promise<MaintenanceRecord[]> {
+  if (isWeb) {
+    const params = new URLSearchParams();
+    if (targetType) params.set('target_type', targetType);
+    if (serial) params.set('serial', serial);
+    return fetchJson<MaintenanceRecord[]>(`/maintenance?${params.toString()}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_maintenance_records', { targetType: targetType ?? null, serial: serial ?? null }) as Promise<MaintenanceRecord[]>;
+}
+
+export async function deleteMaintenanceRecord(recordId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>(`/maintenance?record_id=${recordId}`, { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_maintenance_record', { recordId }) as Promise<boolean>;
+}
+
+export async function getMaintenanceStatus(): Promise<MaintenanceStatus[]> {
+  if (isWeb) {
+    return fetchJson<MaintenanceStatus[]>('/maintenance/status');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_maintenance_status') as Promise<MaintenanceStatus[]>;
+}
+
+export async function getBatteries(): Promise<Battery[]> {
+  if (isWeb) {
+    return fetchJson<Battery[]>('/batteries');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_batteries') as Promise<Battery[]>;
+}
+
+/** Capacity fade, voltage sag trend and health score of every battery */
+export async function getBatteryHealth(): Promise<BatteryHealth[]> {
+  if (isWeb) {
+    return fetchJson<BatteryHealth[]>('/batteries/health');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_battery_health') as Promise<BatteryHealth[]>;
+}
+
+export async function getDischargeCurves(serial: string): Promise<DischargeCurve[]> {
+  if (isWeb) {
+    return fetchJson<DischargeCurve[]>(`/batteries/discharge?serial=${encodeURIComponent(serial)}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_discharge_curves', { serial }) as Promise<DischargeCurve[]>;
+}
+
+export async function getDroneProfiles(): Promise<DroneProfile[]> {
+  if (isWeb) {
+    return fetchJson<DroneProfile[]>('/drones');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_drone_profiles') as Promise<DroneProfile[]>;
+}
+
+/** Firmware versions a drone has flown with, oldest first */
+export async function getFirmwareHistory(serial: string): Promise<FirmwareHistoryEntry[]> {
+  if (isWeb) {
+    return fetchJson<FirmwareHistoryEntry[]>(`/drones/firmware?serial=${encodeURIComponent(serial)}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_firmware_history', { serial }) as Promise<FirmwareHistoryEntry[]>;
+}
+
+export async function updateDroneProfile(
+  serial: string,
+  nickname: string | null,
+  purchaseDate: string | null,
+  notes: string | null,
+  registration: string | null,
+): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/drones', {
+      method: 'PUT',
+      body: JSON.stringify({ serial, nickname, purchase_date: purchaseDate, notes, registration }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('update_drone_profile', { serial, nickname, purchaseDate, notes, registration }) as Promise<boolean>;
+}
+
+export async function addFlightAnnotation(
+  flightId: number,
+  offsetSecs: number,
+  text: string,
+): Promise<FlightAnnotation> {
+  if (isWeb) {
+    return fetchJson<FlightAnnotation>('/flights/annotations/add', {
+      method: 'POST',
+      body: JSON.stringify({ flight_id: flightId, offset_secs: offsetSecs, text }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('add_flight_annotation', { flightId, offsetSecs, text }) as Promise<FlightAnnotation>;
+}
+
+export async function updateFlightAnnotation(
+  annotationId: number,
+  text: string,
+): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/flights/annotations', {
+      method: 'PUT',
+      body: JSON.stringify({ annotation_id: annotationId, text }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('update_flight_annotation', { annotationId, text }) as Promise<boolean>;
+}
+
+export async function deleteFlightAnnotation(annotationId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/flights/annotations/remove', {
+      method: 'POST',
+      body: JSON.stringify({ annotation_id: annotationId }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_flight_annotation', { annotationId }) as Promise<boolean>;
+}
+
+export async function hasApiKey(): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/has_api_key');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('has_api_key') as Promise<boolean>;
+}
+
+export async function getApiKeyType(): Promise<string> {
+  if (isWeb) {
+    return fetchJson<string>('/api_key_type');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_api_key_type') as Promise<string>;
+}
+
+export async function setApiKey(apiKey: string): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/set_api_key', {
+      method: 'POST',
+      body: JSON.stringify({ api_key: apiKey }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_api_key', { apiKey }) as Promise<boolean>;
+}
+
+export async function removeApiKey(): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/remove_api_key', {
+      method: 'DELETE',
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('remove_api_key') as Promise<boolean>;
+}
+
+export async function getAppDataDir(): Promise<string> {
+  if (isWeb) {
+    return fetchJson<string>('/app_data_dir');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_app_data_dir') as Promise<string>;
+}
+
+export async function getAppLogDir(): Promise<string> {
+  if (isWeb) {
+    return fetchJson<string>('/app_log_dir');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_app_log_dir') as Promise<string>;
+}
+
+// ============================================================================
+// Tag Management
+// ============================================================================
+
+export async function addFlightTag(flightId: number, tag: string): Promise<FlightTag[]> {
+  if (isWeb) {
+    return fetchJson<FlightTag[]>('/flights/tags/add', {
+      method: 'POST',
+      body: JSON.stringify({ flight_id: flightId, tag }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('add_flight_tag', { flightId, tag }) as Promise<FlightTag[]>;
+}
+
+export async function removeFlightTag(flightId: number, tag: string): Promise<FlightTag[]> {
+  if (isWeb) {
+    return fetchJson<FlightTag[]>('/flights/tags/remove', {
+      method: 'POST',
+      body: JSON.stringify({ flight_id: flightId, tag }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('remove_flight_tag', { flightId, tag }) as Promise<FlightTag[]>;
+}
+
+export async function getAllTags(): Promise<string[]> {
+  if (isWeb) {
+    return fetchJson<string[]>('/tags');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_all_tags') as Promise<string[]>;
+}
+
+export type GeocodeProvider = 'offline' | 'nominatim';
+
+export async function getGeocodeProvider(): Promise<GeocodeProvider> {
+  if (isWeb) {
+    return fetchJson<GeocodeProvider>('/settings/geocode_provider');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_geocode_provider') as Promise<GeocodeProvider>;
+}
+
+export async function setGeocodeProvider(provider: GeocodeProvider): Promise<GeocodeProvider> {
+  if (isWeb) {
+    return fetchJson<GeocodeProvider>('/settings/geocode_provider', {
+      method: 'POST',
+      body: JSON.stringify({ provider }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_geocode_provider', { provider }) as Promise<GeocodeProvider>;
+}
+
+export async function getPilotDetails(): Promise<PilotDetails> {
+  if (isWeb) {
+    return fetchJson<PilotDetails>('/settings/pilot');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_pilot_details') as Promise<PilotDetails>;
+}
+
+export async function setPilotDetails(pilot: PilotDetails): Promise<PilotDetails> {
+  if (isWeb) {
+    return fetchJson<PilotDetails>('/settings/pilot', {
+      method: 'POST',
+      body: JSON.stringify(pilot),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_pilot_details', { pilot }) as Promise<PilotDetails>;
+}
+
+export async function getAutoExportSettings(): Promise<AutoExportSettings> {
+  if (isWeb) {
+    return fetchJson<AutoExportSettings>('/settings/auto_export');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_auto_export_settings') as Promise<AutoExportSettings>;
+}
+
+/** Save where and in which formats newly imported flights are copied */
+export async function setAutoExportSettings(settings: AutoExportSettings): Promise<AutoExportSettings> {
+  if (isWeb) {
+    return fetchJson<AutoExportSettings>('/settings/auto_export', {
+      method: 'POST',
+      body: JSON.stringify(settings),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_auto_export_settings', { settings }) as Promise<AutoExportSettings>;
+}
+
+export async function getCurrencyRequirements(): Promise<CurrencyRequirement[]> {
+  if (isWeb) {
+    return fetchJson<CurrencyRequirement[]>('/settings/currency');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_currency_requirements') as Promise<CurrencyRequirement[]>;
+}
+
+/** Save the recency requirements checked by the currency report */
+export async function setCurrencyRequirements(requirements: CurrencyRequirement[]): Promise<CurrencyRequirement[]> {
+  if (isWeb) {
+    return fetchJson<CurrencyRequirement[]>('/settings/currency', {
+      method: 'POST',
+      body: JSON.stringify(requirements),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_currency_requirements', { requirements }) as Promise<CurrencyRequirement[]>;
+}
+
+export async function getComplianceLimits(): Promise<ComplianceLimits> {
+  if (isWeb) {
+    return fetchJson<ComplianceLimits>('/settings/compliance');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_compliance_limits') as Promise<ComplianceLimits>;
+}
+
+/** Save the height and distance limits checked by the compliance report */
+export async function setComplianceLimits(limits: ComplianceLimits): Promise<ComplianceLimits> {
+  if (isWeb) {
+    return fetchJson<ComplianceLimits>('/settings/compliance', {
+      method: 'POST',
+      body: JSON.stringify(limits),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_compliance_limits', { limits }) as Promise<ComplianceLimits>;
+}
+
+export async function getTerrainSettings(): Promise<TerrainSettings> {
+  if (isWeb) {
+    return fetchJson<TerrainSettings>('/settings/terrain');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_terrain_settings') as Promise<TerrainSettings>;
+}
+
+/** Save where ground elevations for the height above terrain come from */
+export async function setTerrainSettings(settings: TerrainSettings): Promise<TerrainSettings> {
+  if (isWeb) {
+    return fetchJson<TerrainSettings>('/settings/terrain', {
+      method: 'POST',
+      body: JSON.stringify(settings),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_terrain_settings', { settings }) as Promise<TerrainSettings>;
+}
+
+/** Ground elevation and height above ground along a flight; null while no terrain provider is set */
+export async function getFlightTerrain(flightId: number, maxPoints?: number): Promise<TerrainProfile | null> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId) });
+    if (maxPoints != null) params.set('max_points', String(maxPoints));
+    return fetchJson<TerrainProfile | null>(`/flights/terrain?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_terrain', {
+    flightId,
+    maxPoints: maxPoints ?? null,
+  }) as Promise<TerrainProfile | null>;
+}
+
+export async function getTrackSmoothing(): Promise<TrackSmoothing> {
+  if (isWeb) {
+    return fetchJson<TrackSmoothing>('/settings/track_smoothing');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_track_smoothing') as Promise<TrackSmoothing>;
+}
+
+/** Save the smoothing applied to the map track of opened flights */
+export async function setTrackSmoothing(method: TrackSmoothing): Promise<TrackSmoothing> {
+  if (isWeb) {
+    return fetchJson<TrackSmoothing>('/settings/track_smoothing', {
+      method: 'POST',
+      body: JSON.stringify({ method }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_track_smoothing', { method }) as Promise<TrackSmoothing>;
+}
+
+export type TelemetryStorage = 'rows' | 'packed';
+
+export async function getTelemetryStorage(): Promise<TelemetryStorage> {
+  if (isWeb) {
+    return fetchJson<TelemetryStorage>('/settings/telemetry_storage');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_telemetry_storage') as Promise<TelemetryStorage>;
+}
+
+/** Switch the telemetry storage mode and convert every flight. Returns how many were converted. */
+export async function setTelemetryStorage(mode: TelemetryStorage): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/settings/telemetry_storage', {
+      method: 'POST',
+      body: JSON.stringify({ mode }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_telemetry_storage', { mode }) as Promise<number>;
+}
+
+/** Number of flights whose telemetry is archived to sidecar files */
+export async function getArchivedFlightCount(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/telemetry_archive');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_archived_flight_count') as Promise<number>;
+}
+
+/** Archive the telemetry of flights older than `years` years. Returns how many were archived. */
+export async function archiveOldFlights(years: number): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/telemetry_archive', {
+      method: 'POST',
+      body: JSON.stringify({ years }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('archive_old_flights', { years }) as Promise<number>;
+}
+
+/** Move all archived telemetry back into the database. Returns how many flights were restored. */
+export async function restoreArchivedFlights(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/telemetry_archive/restore', { method: 'POST' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('restore_archived_flights') as Promise<number>;
+}
+
+/** Look up the location of flights without one (all flights with `force`). Returns how many were located. */
+export async function backfillFlightLocations(force = false): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/locations/backfill', {
+      method: 'POST',
+      body: JSON.stringify({ force }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('backfill_flight_locations', { force }) as Promise<number>;
+}
+
+/** Compute the farthest distance from home of flights without one (all flights with `force`). Returns how many were updated. */
+export async function backfillMaxDistanceFromHome(force = false): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/flights/home_distance', {
+      method: 'POST',
+      body: JSON.stringify({ force }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('backfill_max_distance_from_home', { force }) as Promise<number>;
+}
+
+/** Recompute the cached per-flight statistics of every flight. Returns how many were computed. */
+export async function rebuildStatsCache(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/overview/cache/rebuild', { method: 'POST' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('rebuild_stats_cache') as Promise<number>;
+}
+
+export async function getSmartTagsEnabled(): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/settings/smart_tags');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_smart_tags_enabled') as Promise<boolean>;
+}
+
+export async function setSmartTagsEnabled(enabled: boolean): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/settings/smart_tags', {
+      method: 'POST',
+      body: JSON.stringify({ enabled }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_smart_tags_enabled', { enabled }) as Promise<boolean>;
+}
+
+export async function regenerateSmartTags(): Promise<string> {
+  if (isWeb) {
+    return fetchJson<string>('/regenerate_smart_tags', {
+      method: 'POST',
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('regenerate_all_smart_tags') as Promise<string>;
+}
+
+export async function removeAllAutoTags(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/tags/remove_auto', {
+      method: 'POST',
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('remove_all_auto_tags') as Promise<number>;
+}
+
+export async function regenerateFlightSmartTags(flightId: number, enabledTagTypes?: string[]): Promise<string> {
+  if (isWeb) {
+    return fetchJson<string>(`/regenerate_flight_smart_tags/${flightId}`, {
+      method: 'POST',
+      body: JSON.stringify({ enabledTagTypes }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('regenerate_flight_smart_tags', { flightId, enabledTagTypes }) as Promise<string>;
+}
+
+// ============================================================================
 // Smart Tag Types
 // ============================================================================
 
-/** All available smart tag types that can be enabled/disabled */
-export const SMART_TAG_TYPES = [
-  { id: 'night_flight', label: 'Night Flight', description: 'Flights starting after 7 PM or before 6 AM' },
-  { id: 'high_speed', label: 'High Speed', description: 'Max speed exceeds 15 m/s' },
-  { id: 'cold_battery', label: 'Cold Battery', description: 'Battery temp below 15°C at start' },
-  { id: 'heavy_load', label: 'Heavy Load', description: 'Battery consumption >75% in <20 min' },
-  { id: 'low_battery', label: 'Low Battery', description: 'Battery dropped below 15% at landing' },
-  { id: 'high_altitude', label: 'High Altitude', description: 'Max height above 120 meters' },
-  { id: 'long_distance', label: 'Long Distance', description: 'Max distance from home >1 km' },
-  { id: 'long_flight', label: 'Long Flight', description: 'Duration over 25 minutes' },
-  { id: 'short_flight', label: 'Short Flight', description: 'Duration under 2 minutes' },
-  { id: 'aggressive_flying', label: 'Aggressive Flying', description: 'Average speed over 8 m/s' },
-  { id: 'no_gps', label: 'No GPS', description: 'No GPS data available' },
-  { id: 'country', label: 'Country', description: 'Country based on takeoff location' },
-  { id: 'continent', label: 'Continent', description: 'Continent based on takeoff location' },
-] as const;
+/** All available smart tag types that can be enabled/disabled */
+export const SMART_TAG_TYPES = [
+  { id: 'night_flight', label: 'Night Flight', description: 'Flights starting after 7 PM or before 6 AM' },
+  { id: 'high_speed', label: 'High Speed', description: 'Max speed exceeds 15 m/s' },
+  { id: 'cold_battery', label: 'Cold Battery', description: 'Battery temp below 15°C at start' },
+  { id: 'heavy_load', label: 'Heavy Load', description: 'Battery consumption >75% in <20 min' },
+  { id: 'low_battery', label: 'Low Battery', description: 'Battery dropped below 15% at landing' },
+  { id: 'high_altitude', label: 'High Altitude', description: 'Max height above 120 meters' },
+  { id: 'long_distance', label: 'Long Distance', description: 'Max distance from home >1 km' },
+  { id: 'long_flight', label: 'Long Flight', description: 'Duration over 25 minutes' },
+  { id: 'short_flight', label: 'Short Flight', description: 'Duration under 2 minutes' },
+  { id: 'aggressive_flying', label: 'Aggressive Flying', description: 'Average speed over 8 m/s' },
+  { id: 'no_gps', label: 'No GPS', description: 'No GPS data available' },
+  { id: 'country', label: 'Country', description: 'Country based on takeoff location' },
+  { id: 'continent', label: 'Continent', description: 'Continent based on takeoff location' },
+] as const;
+
+export type SmartTagTypeId = typeof SMART_TAG_TYPES[number]['id'];
+
+/** Get all enabled smart tag types from localStorage */
+export function getEnabledSmartTagTypes(): SmartTagTypeId[] {
+  if (typeof localStorage === 'undefined') return SMART_TAG_TYPES.map(t => t.id);
+  const stored = localStorage.getItem('enabledSmartTagTypes');
+  if (!stored) return SMART_TAG_TYPES.map(t => t.id); // Default: all enabled
+  try {
+    const parsed = JSON.parse(stored);
+    // Validate that all stored values are valid tag type IDs
+    const validIds = SMART_TAG_TYPES.map(t => t.id);
+    return parsed.filter((id: string) => validIds.includes(id as SmartTagTypeId));
+  } catch {
+    return SMART_TAG_TYPES.map(t => t.id);
+  }
+}
+
+/** Set enabled smart tag types in localStorage and sync to backend */
+export async function setEnabledSmartTagTypes(types: SmartTagTypeId[]): Promise<void> {
+  if (typeof localStorage !== 'undefined') {
+    localStorage.setItem('enabledSmartTagTypes', JSON.stringify(types));
+  }
+  // Also sync to backend config for import filtering
+  try {
+    if (isWeb) {
+      await fetchJson('/settings/enabled_tag_types', {
+        method: 'POST',
+        body: JSON.stringify({ types }),
+      });
+    } else {
+      const invoke = await getTauriInvoke();
+      await invoke('set_enabled_tag_types', { types });
+    }
+  } catch (e) {
+    console.warn('Failed to sync enabled tag types to backend:', e);
+  }
+}
+
+/**
+ * Folders monitored for new logs by the desktop auto-import watcher.
+ * Tauri-only: the web build uses SYNC_LOGS_PATH instead.
+ */
+export async function getWatchFolders(): Promise<string[]> {
+  if (isWeb) return [];
+  const invoke = await getTauriInvoke();
+  return invoke('get_watch_folders') as Promise<string[]>;
+}
+
+export async function setWatchFolders(folders: string[]): Promise<string[]> {
+  if (isWeb) {
+    throw new Error('Watched folders are not supported in web mode.');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_watch_folders', { folders }) as Promise<string[]>;
+}
+
+/** Load enabled smart tag types from backend (called on init) */
+export async function loadEnabledSmartTagTypes(): Promise<SmartTagTypeId[]> {
+  try {
+    let types: string[];
+    if (isWeb) {
+      types = await fetchJson<string[]>('/settings/enabled_tag_types');
+    } else {
+      const invoke = await getTauriInvoke();
+      types = await invoke('get_enabled_tag_types') as string[];
+    }
+    // Validate and store in localStorage
+    const validIds = SMART_TAG_TYPES.map(t => t.id);
+    const validTypes = types.filter((id) => validIds.includes(id as SmartTagTypeId)) as SmartTagTypeId[];
+    if (typeof localStorage !== 'undefined') {
+      localStorage.setItem('enabledSmartTagTypes', JSON.stringify(validTypes));
+    }
+    return validTypes;
+  } catch {
+    // If backend doesn't have the setting, use localStorage or default
+    return getEnabledSmartTagTypes();
+  }
+}
+
+// ============================================================================
+// File helpers for web mode (replacing Tauri dialog/fs plugins)
+// ============================================================================
+
+/** Trigger a browser file-open dialog and return selected Files */
+export function pickFiles(accept?: string, multiple = true): Promise<File[]> {
+  return new Promise((resolve) => {
+    const input = document.createElement('input');
+    input.type = 'file';
+    input.multiple = multiple;
+    if (accept) input.accept = accept;
+    input.onchange = () => {
+      const files = Array.from(input.files || []);
+      resolve(files);
+    };
+    // If user cancels, resolve with empty
+    input.addEventListener('cancel', () => resolve([]));
+    input.click();
+  });
+}
+
+/** Trigger a browser download for the given content */
+export function downloadFile(filename: string, content: string, mimeType = 'text/plain') {
+  const blob = new Blob([content], { type: mimeType });
+  const url = URL.createObjectURL(blob);
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = filename;
+  a.click();
+  URL.revokeObjectURL(url);
+}
+
+/** Trigger a browser download for a Blob */
+export function downloadBlob(filename: string, blob: Blob) {
+  const url = URL.createObjectURL(blob);
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = filename;
+  a.click();
+  URL.revokeObjectURL(url);
+}
+
+/** Check if running in web mode */
+export function isWebMode(): boolean {
+  return isWeb;
+}
+
+// ============================================================================
+// Sync from folder (Web/Docker mode only)
+// ============================================================================
+
+export interface SyncConfig {
+  processed: number;
+  skipped: number;
+  errors: number;
+  message: string;
+  syncPath: string | null;
+  /** Whether automatic scheduled sync is enabled (SYNC_INTERVAL is set on server) */
+  autoSync: boolean;
+}
+
+export interface SyncFilesResponse {
+  files: string[];
+  syncPath: string | null;
+  message: string;
+}
+
+export interface SyncFileResponse {
+  success: boolean;
+  message: string;
+  fileHash: string | null;
+}
+
+/**
+ * Get the sync folder configuration (web mode only).
+ * Returns the configured SYNC_LOGS_PATH if set on the server.
+ */
+export async function getSyncConfig(): Promise<SyncConfig> {
+  if (!isWeb) {
+    return { processed: 0, skipped: 0, errors: 0, message: 'Not in web mode', syncPath: null, autoSync: false };
+  }
+  return fetchJson<SyncConfig>('/sync/config');
+}
+
+/**
+ * List files available for sync in the server's SYNC_LOGS_PATH folder.
+ * Returns only files that haven't been imported yet (checked by hash).
+ */
+export async function getSyncFiles(): Promise<SyncFilesResponse> {
+  if (!isWeb) {
+    return { files: [], syncPath: null, message: 'Not in web mode' };
+  }
+  return fetchJson<SyncFilesResponse>('/sync/files');
+}
+
+/**
+ * Import a single file from the server's SYNC_LOGS_PATH folder.
+ */
+export async function syncSingleFile(filename: string): Promise<SyncFileResponse> {
+  if (!isWeb) {
+    return { success: false, message: 'Not in web mode', fileHash: null };
+  }
+  return fetchJson<SyncFileResponse>('/sync/file', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ filename }),
+  });
+}
+
+/**
+ * Trigger sync from the server's SYNC_LOGS_PATH folder (web mode only).
+ * This imports any new log files from the mounted sync folder.
+ */
+export async function triggerSync(): Promise<SyncConfig> {
+  if (!isWeb) {
+    return { processed: 0, skipped: 0, errors: 0, message: 'Not in web mode', syncPath: null, autoSync: false };
+  }
+  return fetchJson<SyncConfig>('/sync', { method: 'POST' });
+}
+
+// ============================================================================
+// Libraries
+// ============================================================================
+
+/** The default library followed by the recently opened ones */
+export async function getLibraries(): Promise<Library[]> {
+  if (isWeb) {
+    return fetchJson<Library[]>('/libraries');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_libraries') as Promise<Library[]>;
+}
+
+/**
+ * Open another library, creating it if it doesn't exist. `library` is a name
+ * (stored in the app data directory) or, on the desktop, a database file path.
+ */
+export async function switchLibrary(library: string, name?: string): Promise<Library> {
+  if (isWeb) {
+    return fetchJson<Library>('/libraries/switch', {
+      method: 'POST',
+      body: JSON.stringify({ library, name: name ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('switch_library', { library, name: name ?? null }) as Promise<Library>;
+}
+
+/**
+ * Pick a database file to open or create as a library with the native save
+ * dialog (desktop only). Returns null if the user cancelled.
+ */
+export async function pickLibraryFile(): Promise<string | null> {
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const path = await save({
+    defaultPath: 'Logbook.db',
+    filters: [{ name: 'Drone Logbook Library', extensions: ['db'] }],
+  });
+  return path ?? null;
+}
+
+/** Remove a library from the recent list. Its database file is kept. */
+export async function forgetLibrary(path: string): Promise<void> {
+  if (isWeb) {
+    await fetchJson<boolean>(`/libraries?path=${encodeURIComponent(path)}`, { method: 'DELETE' });
+    return;
+  }
+  const invoke = await getTauriInvoke();
+  await invoke('forget_library', { path });
+}
+
+/** Conflict copies of the open library left by a folder sync tool */
+export async function getConflictCopies(): Promise<string[]> {
+  if (isWeb) {
+    return fetchJson<string[]>('/libraries/conflicts');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_conflict_copies') as Promise<string[]>;
+}
+
+/** Merge a diverged copy of the open library into it; returns a summary */
+export async function reconcileLibrary(path: string): Promise<string> {
+  if (isWeb) {
+    return fetchJson<string>('/libraries/reconcile', {
+      method: 'POST',
+      body: JSON.stringify({ path }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('reconcile_library', { path }) as Promise<string>;
+}
+
+/** Desktop only: pick an existing library file to reconcile with */
+export async function pickLibraryCopy(): Promise<string | null> {
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const path = await open({
+    multiple: false,
+    filters: [{ name: 'Drone Logbook Library', extensions: ['db'] }],
+  });
+  if (!path) return null;
+  return typeof path === 'string' ? path : (path as { path: string }).path;
+}
+
+// ============================================================================
+// Database backup & restore
+// ============================================================================
+
+/**
+ * Export the database as a compressed backup file.
+ * - Tauri: prompts user with a save dialog, backend writes the file directly.
+ * - Web: downloads the backup file via the browser.
+ */
+export async function backupDatabase(): Promise<void> {
+  if (isWeb) {
+    // Web mode: download via fetch
+    const response = await fetch(`${API_BASE}/backup`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    const blob = await response.blob();
+    downloadBlob('Drone_logbook.db.backup', blob);
+    return;
+  }
+
+  // Tauri mode: use native save dialog
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: 'Drone_logbook.db.backup',
+    filters: [{ name: 'Drone Logbook Backup', extensions: ['backup'] }],
+  });
+  if (!destPath) return; // user cancelled
+  const invoke = await getTauriInvoke();
+  await invoke('export_backup', { destPath });
+}
+
+/**
+ * Import a backup file to restore flight data.
+ * - Tauri: prompts user with an open dialog, backend reads the file directly.
+ * - Web: uploads the file via multipart/form-data.
+ * Returns a status message string.
+ */
+export async function restoreDatabase(file?: File): Promise<string> {
+  if (isWeb) {
+    if (!file) throw new Error('No file provided');
+    const formData = new FormData();
+    formData.append('file', file, file.name);
+    const response = await fetch(`${API_BASE}/backup/restore`, {
+      method: 'POST',
+      body: formData,
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+
+  // Tauri mode: use native open dialog
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({
+    multiple: false,
+    filters: [{ name: 'Drone Logbook Backup', extensions: ['backup'] }],
+  });
+  if (!srcPath) return ''; // user cancelled
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('import_backup', { srcPath: filePath }) as Promise<string>;
+}
+
+/**
+ * Export the whole library (database, settings, keychains) as one archive
+ * for moving it to another machine. Returns false if the user cancelled.
+ */
+export async function exportArchive(): Promise<boolean> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/archive`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob('Drone_logbook.logbook', await response.blob());
+    return true;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: 'Drone_logbook.logbook',
+    filters: [{ name: 'Drone Logbook Archive', extensions: ['logbook'] }],
+  });
+  if (!destPath) return false;
+  const invoke = await getTauriInvoke();
+  await invoke('export_archive', { destPath });
+  return true;
+}
+
+/**
+ * Merge an archive from exportArchive into this library. Flights already
+ * present (same file hash) are skipped. Returns a status message, or an
+ * empty string if the user cancelled.
+ */
+export async function importArchive(file?: File): Promise<string> {
+  if (isWeb) {
+    if (!file) throw new Error('No file provided');
+    const formData = new FormData();
+    formData.append('file', file, file.name);
+    const response = await fetch(`${API_BASE}/archive/import`, {
+      method: 'POST',
+      body: formData,
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({
+    multiple: false,
+    filters: [{ name: 'Drone Logbook Archive', extensions: ['logbook'] }],
+  });
+  if (!srcPath) return '';
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('import_archive', { srcPath: filePath }) as Promise<string>;
+}
+
+/**
+ * Save a full snapshot of the database file (desktop only).
+ * Returns false if the user cancelled.
+ */
+export async function snapshotDatabase(): Promise<boolean> {
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: 'Drone_logbook_snapshot.db',
+    filters: [{ name: 'Drone Logbook Snapshot', extensions: ['db'] }],
+  });
+  if (!destPath) return false;
+  const invoke = await getTauriInvoke();
+  return invoke('backup_database', { destPath }) as Promise<boolean>;
+}
+
+/**
+ * Replace the whole library with a snapshot made by snapshotDatabase (desktop only).
+ * Returns false if the user cancelled.
+ */
+export async function restoreSnapshot(): Promise<boolean> {
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({
+    multiple: false,
+    filters: [{ name: 'Drone Logbook Snapshot', extensions: ['db'] }],
+  });
+  if (!srcPath) return false;
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('restore_database', { srcPath: filePath }) as Promise<boolean>;
+}
+
+export async function getAutoBackupEnabled(): Promise<boolean> {
+  const invoke = await getTauriInvoke();
+  return invoke('get_auto_backup_enabled') as Promise<boolean>;
+}
+
+export async function setAutoBackupEnabled(enabled: boolean): Promise<boolean> {
+  const invoke = await getTauriInvoke();
+  return invoke('set_auto_backup_enabled', { enabled }) as Promise<boolean>;
+}
+
+/**
+ * Export cached V13+ decryption keychains to a bundle file.
+ * Returns the number of keychains written, or null if the user cancelled.
+ */
+export async function exportKeychains(): Promise<number | null> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/keychains/export`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    const blob = await response.blob();
+    const bundle = JSON.parse(await blob.text());
+    downloadBlob('Drone_logbook.keychains.json', blob);
+    return bundle.entries?.length ?? 0;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: 'Drone_logbook.keychains.json',
+    filters: [{ name: 'Keychain Bundle', extensions: ['json'] }],
+  });
+  if (!destPath) return null; // user cancelled
+  const invoke = await getTauriInvoke();
+  return invoke('export_keychains', { destPath }) as Promise<number>;
+}
+
+/**
+ * Import a keychain bundle so encrypted logs decrypt without network access.
+ * Returns the number of keychains imported, or null if the user cancelled.
+ */
+export async function importKeychains(file?: File): Promise<number | null> {
+  if (isWeb) {
+    if (!file) throw new Error('No file provided');
+    const formData = new FormData();
+    formData.append('file', file, file.name);
+    const response = await fetch(`${API_BASE}/keychains/import`, {
+      method: 'POST',
+      body: formData,
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({
+    multiple: false,
+    filters: [{ name: 'Keychain Bundle', extensions: ['json'] }],
+  });
+  if (!srcPath) return null; // user cancelled
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('import_keychains', { srcPath: filePath }) as Promise<number>;
+}
+
+/**
+ * Import a DJI Fly waypoint mission (.kmz). The backend links it to every
+ * flight whose track reached most of its waypoints.
+ * Desktop: opens a file picker and returns null if cancelled.
+ */
+export async function importMission(file?: File): Promise<Mission | null> {
+  if (isWeb) {
+    if (!file) throw new Error('No file provided');
+    const formData = new FormData();
+    formData.append('file', file, file.name);
+    const response = await fetch(`${API_BASE}/missions/import`, {
+      method: 'POST',
+      body: formData,
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({
+    multiple: false,
+    filters: [{ name: 'Waypoint Mission', extensions: ['kmz'] }],
+  });
+  if (!srcPath) return null; // user cancelled
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('import_mission', { filePath }) as Promise<Mission>;
+}
+
+export async function getMissions(): Promise<Mission[]> {
+  if (isWeb) {
+    return fetchJson<Mission[]>('/missions');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_missions') as Promise<Mission[]>;
+}
 
-export type SmartTagTypeId = typeof SMART_TAG_TYPES[number]['id'];
+export async function deleteMission(missionId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>(`/missions/delete?mission_id=${missionId}`, {
+      method: 'DELETE',
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_mission', { missionId }) as Promise<boolean>;
+}
 
-/** Get all enabled smart tag types from localStorage */
-export function getEnabledSmartTagTypes(): SmartTagTypeId[] {
-  if (typeof localStorage === 'undefined') return SMART_TAG_TYPES.map(t => t.id);
-  const stored = localStorage.getItem('enabledSmartTagTypes');
-  if (!stored) return SMART_TAG_TYPES.map(t => t.id); // Default: all enabled
-  try {
-    const parsed = JSON.parse(stored);
-    // Validate that all stored values are valid tag type IDs
-    const validIds = SMART_TAG_TYPES.map(t => t.id);
-    return parsed.filter((id: string) => validIds.includes(id as SmartTagTypeId));
-  } catch {
-    return SMART_TAG_TYPES.map(t => t.id);
+/** Re-match all missions against the flight library; returns the number of links */
+export async function relinkMissions(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/missions/relink', { method: 'POST' });
   }
+  const invoke = await getTauriInvoke();
+  return invoke('relink_missions') as Promise<number>;
 }
 
-/** Set enabled smart tag types in localStorage and sync to backend */
-export async function setEnabledSmartTagTypes(types: SmartTagTypeId[]): Promise<void> {
-  if (typeof localStorage !== 'undefined') {
-    localStorage.setItem('enabledSmartTagTypes', JSON.stringify(types));
+/** Scan a photo/video folder and associate the files with flights by capture time */
+export async function scanMediaFolder(folderPath: string): Promise<MediaScanResult> {
+  if (isWeb) {
+    return fetchJson<MediaScanResult>('/media/scan', {
+      method: 'POST',
+      body: JSON.stringify({ folder_path: folderPath }),
+    });
   }
-  // Also sync to backend config for import filtering
-  try {
-    if (isWeb) {
-      await fetchJson('/settings/enabled_tag_types', {
-        method: 'POST',
-        body: JSON.stringify({ types }),
-      });
-    } else {
-      const invoke = await getTauriInvoke();
-      await invoke('set_enabled_tag_types', { types });
+  const invoke = await getTauriInvoke();
+  return invoke('scan_media_folder', { folderPath }) as Promise<MediaScanResult>;
+}
+
+export async function getFlightMedia(flightId: number): Promise<FlightMedia[]> {
+  if (isWeb) {
+    return fetchJson<FlightMedia[]>(`/flights/media?flight_id=${flightId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_media', { flightId }) as Promise<FlightMedia[]>;
+}
+
+export async function getFlightDocuments(flightId: number): Promise<FlightDocument[]> {
+  if (isWeb) {
+    return fetchJson<FlightDocument[]>(`/flights/documents?flight_id=${flightId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_documents', { flightId }) as Promise<FlightDocument[]>;
+}
+
+/**
+ * Attach a file to a flight.
+ * - Tauri: prompts user with an open dialog, backend reads the file directly.
+ * - Web: uploads the file via multipart/form-data.
+ * Returns null if the user cancelled.
+ */
+export async function addFlightDocument(flightId: number, file?: File): Promise<FlightDocument | null> {
+  if (isWeb) {
+    if (!file) throw new Error('No file provided');
+    const formData = new FormData();
+    formData.append('file', file, file.name);
+    const response = await fetch(`${API_BASE}/flights/documents?flight_id=${flightId}`, {
+      method: 'POST',
+      body: formData,
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
     }
-  } catch (e) {
-    console.warn('Failed to sync enabled tag types to backend:', e);
+    return response.json();
   }
+
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({ multiple: false });
+  if (!srcPath) return null;
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('add_flight_document', { flightId, filePath }) as Promise<FlightDocument>;
 }
 
-/** Load enabled smart tag types from backend (called on init) */
-export async function loadEnabledSmartTagTypes(): Promise<SmartTagTypeId[]> {
-  try {
-    let types: string[];
-    if (isWeb) {
-      types = await fetchJson<string[]>('/settings/enabled_tag_types');
-    } else {
-      const invoke = await getTauriInvoke();
-      types = await invoke('get_enabled_tag_types') as string[];
+/** Save a copy of an attached document: a browser download or a save dialog */
+export async function downloadFlightDocument(document: FlightDocument): Promise<void> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/documents/file?document_id=${document.id}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
     }
-    // Validate and store in localStorage
-    const validIds = SMART_TAG_TYPES.map(t => t.id);
-    const validTypes = types.filter((id) => validIds.includes(id as SmartTagTypeId)) as SmartTagTypeId[];
-    if (typeof localStorage !== 'undefined') {
-      localStorage.setItem('enabledSmartTagTypes', JSON.stringify(validTypes));
+    downloadBlob(document.fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({ defaultPath: document.fileName });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_document', { documentId: document.id, destPath });
+}
+
+export async function deleteFlightDocument(documentId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>(`/flights/documents?document_id=${documentId}`, { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_flight_document', { documentId }) as Promise<boolean>;
+}
+
+/** Export a flight as a GPX track with speed and course, at full telemetry resolution */
+export async function exportFlightGpx(flightId: number, fileName: string): Promise<void> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/export/gpx?flight_id=${flightId}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
     }
-    return validTypes;
-  } catch {
-    // If backend doesn't have the setting, use localStorage or default
-    return getEnabledSmartTagTypes();
+    downloadBlob(fileName, await response.blob());
+    return;
   }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'GPX', extensions: ['gpx'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_gpx', { flightId, destPath });
 }
 
-// ============================================================================
-// File helpers for web mode (replacing Tauri dialog/fs plugins)
-// ============================================================================
+/**
+ * Export a flight as KML with an extruded 3D path, a time-animated track and
+ * event placemarks. `kmz` zips it; `tour` adds a flythrough along the path.
+ */
+export async function exportFlightKml(
+  flightId: number,
+  fileName: string,
+  options: { tour?: boolean; kmz?: boolean } = {}
+): Promise<void> {
+  const tour = options.tour ?? false;
+  const kmz = options.kmz ?? false;
+  if (isWeb) {
+    const response = await fetch(
+      `${API_BASE}/flights/export/kml?flight_id=${flightId}&tour=${tour}&kmz=${kmz}`
+    );
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
 
-/** Trigger a browser file-open dialog and return selected Files */
-export function pickFiles(accept?: string, multiple = true): Promise<File[]> {
-  return new Promise((resolve) => {
-    const input = document.createElement('input');
-    input.type = 'file';
-    input.multiple = multiple;
-    if (accept) input.accept = accept;
-    input.onchange = () => {
-      const files = Array.from(input.files || []);
-      resolve(files);
-    };
-    // If user cancels, resolve with empty
-    input.addEventListener('cancel', () => resolve([]));
-    input.click();
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: kmz ? 'KMZ' : 'KML', extensions: [kmz ? 'kmz' : 'kml'] }],
   });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_kml', { flightId, destPath, tour, kmz });
 }
 
-/** Trigger a browser download for the given content */
-export function downloadFile(filename: string, content: string, mimeType = 'text/plain') {
-  const blob = new Blob([content], { type: mimeType });
-  const url = URL.createObjectURL(blob);
-  const a = document.createElement('a');
-  a.href = url;
-  a.download = filename;
-  a.click();
-  URL.revokeObjectURL(url);
+/**
+ * Export the telemetry of a flight as CSV, converted to `units`; with
+ * `columns` only those telemetry columns, in that order
+ */
+export async function exportFlightCsv(
+  flightId: number,
+  fileName: string,
+  units: ExportUnits = {},
+  columns: string[] = []
+): Promise<void> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId) });
+    if (columns.length > 0) params.set('columns', columns.join(','));
+    if (units.length) params.set('length', units.length);
+    if (units.speed) params.set('speed', units.speed);
+    if (units.temperature) params.set('temperature', units.temperature);
+    const response = await fetch(`${API_BASE}/flights/export/csv?${params}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'CSV', extensions: ['csv'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_csv', { flightId, destPath, units, columns });
 }
 
-/** Trigger a browser download for a Blob */
-export function downloadBlob(filename: string, blob: Blob) {
-  const url = URL.createObjectURL(blob);
-  const a = document.createElement('a');
-  a.href = url;
-  a.download = filename;
-  a.click();
-  URL.revokeObjectURL(url);
+/** Export a flight's track and events as a GeoJSON FeatureCollection */
+export async function exportFlightGeojson(flightId: number, fileName: string): Promise<void> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/export/geojson?flight_id=${flightId}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'GeoJSON', extensions: ['geojson', 'json'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_geojson', { flightId, destPath });
 }
 
-/** Check if running in web mode */
-export function isWebMode(): boolean {
-  return isWeb;
+/** Export a flight's telemetry as a MAVLink telemetry log (.tlog) */
+export async function exportFlightTlog(flightId: number, fileName: string): Promise<void> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/export/tlog?flight_id=${flightId}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'MAVLink tlog', extensions: ['tlog'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_tlog', { flightId, destPath });
 }
 
-// ============================================================================
-// Sync from folder (Web/Docker mode only)
-// ============================================================================
+/**
+ * Export telemetry on a video's time axis as CSV for overlay tools. Rows are
+ * interpolated every `intervalSecs`, starting `offsetSecs` into the flight.
+ */
+export async function exportFlightOverlay(
+  flightId: number,
+  fileName: string,
+  options: OverlayOptions = {},
+  units: ExportUnits = {}
+): Promise<void> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId) });
+    if (options.offsetSecs !== undefined) params.set('offset_secs', String(options.offsetSecs));
+    if (options.intervalSecs !== undefined) params.set('interval_secs', String(options.intervalSecs));
+    if (units.length) params.set('length', units.length);
+    if (units.speed) params.set('speed', units.speed);
+    const response = await fetch(`${API_BASE}/flights/export/overlay?${params}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'CSV', extensions: ['csv'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_overlay', { flightId, destPath, options, units });
+}
+
+/**
+ * Generate DJI-style subtitles for a video recorded during a flight.
+ * `recording` picks the video, 0-based in recording order.
+ */
+export async function exportFlightSrt(flightId: number, fileName: string, recording = 0): Promise<void> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId), recording: String(recording) });
+    const response = await fetch(`${API_BASE}/flights/export/srt?${params}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
 
-export interface SyncConfig {
-  processed: number;
-  skipped: number;
-  errors: number;
-  message: string;
-  syncPath: string | null;
-  /** Whether automatic scheduled sync is enabled (SYNC_INTERVAL is set on server) */
-  autoSync: boolean;
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'SRT', extensions: ['srt'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_srt', { flightId, destPath, recording });
 }
 
-export interface SyncFilesResponse {
-  files: string[];
-  syncPath: string | null;
-  message: string;
+/** Export a PDF report of a flight, with values in `units` */
+export async function exportFlightReport(
+  flightId: number,
+  fileName: string,
+  units: ExportUnits = {}
+): Promise<void> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId) });
+    if (units.length) params.set('length', units.length);
+    if (units.speed) params.set('speed', units.speed);
+    if (units.temperature) params.set('temperature', units.temperature);
+    const response = await fetch(`${API_BASE}/flights/export/report?${params}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'PDF', extensions: ['pdf'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flight_report', { flightId, destPath, units });
 }
 
-export interface SyncFileResponse {
-  success: boolean;
-  message: string;
-  fileHash: string | null;
+/** Progress reported by the desktop batch export after each flight */
+export interface BatchExportProgress {
+  currentFlight: string;
+  processed: number;
+  total: number;
+  percent: number;
+  failures: number;
 }
 
 /**
- * Get the sync folder configuration (web mode only).
- * Returns the configured SYNC_LOGS_PATH if set on the server.
+ * Export every flight matching `filter`, one file per flight plus summary.csv.
+ * On desktop the files go to a folder picked here and progress is reported via
+ * `export-progress`; on web they are downloaded as one ZIP archive.
+ * Returns null when the folder picker is cancelled and in web mode.
  */
-export async function getSyncConfig(): Promise<SyncConfig> {
-  if (!isWeb) {
-    return { processed: 0, skipped: 0, errors: 0, message: 'Not in web mode', syncPath: null, autoSync: false };
+export async function exportFlightsBatch(
+  filter: FlightFilter,
+  format: 'gpx' | 'kml' | 'csv' | 'json',
+  options: {
+    units?: ExportUnits;
+    columns?: string[];
+    onProgress?: (progress: BatchExportProgress) => void;
+  } = {}
+): Promise<BatchExportResult | null> {
+  const units = options.units ?? {};
+  const columns = options.columns ?? [];
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/export/batch`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ filter, format, columns, units }),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    const timestamp = new Date().toISOString().slice(0, 10);
+    downloadBlob(`drone_flights_${timestamp}_${format}.zip`, await response.blob());
+    return null;
   }
-  return fetchJson<SyncConfig>('/sync/config');
-}
 
-/**
- * List files available for sync in the server's SYNC_LOGS_PATH folder.
- * Returns only files that haven't been imported yet (checked by hash).
- */
-export async function getSyncFiles(): Promise<SyncFilesResponse> {
-  if (!isWeb) {
-    return { files: [], syncPath: null, message: 'Not in web mode' };
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const destDir = (await open({ directory: true, multiple: false })) as string | null;
+  if (!destDir) return null;
+
+  const { onProgress } = options;
+  let unlisten: (() => void) | null = null;
+  if (onProgress) {
+    const { listen } = await import('@tauri-apps/api/event');
+    unlisten = await listen<BatchExportProgress>('export-progress', (event) => onProgress(event.payload));
+  }
+  try {
+    const invoke = await getTauriInvoke();
+    return (await invoke('export_flights_batch', { filter, format, destDir, columns, units })) as BatchExportResult;
+  } finally {
+    if (unlisten) unlisten();
   }
-  return fetchJson<SyncFilesResponse>('/sync/files');
 }
 
 /**
- * Import a single file from the server's SYNC_LOGS_PATH folder.
+ * Export every flight matching `filter` as an Excel workbook; with `telemetry`
+ * each flight also gets a sheet of its telemetry
  */
-export async function syncSingleFile(filename: string): Promise<SyncFileResponse> {
-  if (!isWeb) {
-    return { success: false, message: 'Not in web mode', fileHash: null };
+export async function exportFlightsXlsx(
+  filter: FlightFilter,
+  fileName: string,
+  options: { telemetry?: boolean; units?: ExportUnits } = {}
+): Promise<void> {
+  const telemetry = options.telemetry ?? false;
+  const units = options.units ?? {};
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/export/xlsx`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ filter, telemetry, units }),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
   }
-  return fetchJson<SyncFileResponse>('/sync/file', {
-    method: 'POST',
-    headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ filename }),
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'Excel', extensions: ['xlsx'] }],
   });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_flights_xlsx', { filter, destPath, telemetry, units });
 }
 
 /**
- * Trigger sync from the server's SYNC_LOGS_PATH folder (web mode only).
- * This imports any new log files from the mounted sync folder.
+ * Export telemetry as a Parquet file: of one flight with `flightId`, otherwise
+ * of every flight matching `filter`
  */
-export async function triggerSync(): Promise<SyncConfig> {
-  if (!isWeb) {
-    return { processed: 0, skipped: 0, errors: 0, message: 'Not in web mode', syncPath: null, autoSync: false };
+export async function exportTelemetryParquet(
+  target: { flightId: number } | { filter: FlightFilter },
+  fileName: string
+): Promise<void> {
+  const flightId = 'flightId' in target ? target.flightId : null;
+  const filter = 'filter' in target ? target.filter : null;
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/flights/export/parquet`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ flight_id: flightId, filter: filter ?? {} }),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
   }
-  return fetchJson<SyncConfig>('/sync', { method: 'POST' });
-}
 
-// ============================================================================
-// Database backup & restore
-// ============================================================================
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'Parquet', extensions: ['parquet'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_telemetry_parquet', { flightId, filter, destPath });
+}
 
-/**
- * Export the database as a compressed backup file.
- * - Tauri: prompts user with a save dialog, backend writes the file directly.
- * - Web: downloads the backup file via the browser.
- */
-export async function backupDatabase(): Promise<void> {
+/** Export the tracks of every flight matching `filter` as one KML or GeoJSON coverage layer */
+export async function exportCoverage(
+  filter: FlightFilter,
+  format: 'kml' | 'geojson',
+  fileName: string,
+  options: CoverageOptions = {}
+): Promise<void> {
   if (isWeb) {
-    // Web mode: download via fetch
-    const response = await fetch(`${API_BASE}/backup`);
+    const response = await fetch(`${API_BASE}/flights/export/coverage`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ filter, format, options }),
+    });
     if (!response.ok) {
       const body = await response.text();
       throw new Error(body);
     }
-    const blob = await response.blob();
-    downloadBlob('Drone_logbook.db.backup', blob);
+    downloadBlob(fileName, await response.blob());
     return;
   }
 
-  // Tauri mode: use native save dialog
   const { save } = await import('@tauri-apps/plugin-dialog');
   const destPath = await save({
-    defaultPath: 'Drone_logbook.db.backup',
-    filters: [{ name: 'Drone Logbook Backup', extensions: ['backup'] }],
+    defaultPath: fileName,
+    filters: [{ name: format === 'kml' ? 'KML' : 'GeoJSON', extensions: [format] }],
   });
-  if (!destPath) return; // user cancelled
+  if (!destPath) return;
   const invoke = await getTauriInvoke();
-  await invoke('export_backup', { destPath });
+  await invoke('export_coverage', { filter, format, options, destPath });
 }
 
-/**
- * Import a backup file to restore flight data.
- * - Tauri: prompts user with an open dialog, backend reads the file directly.
- * - Web: uploads the file via multipart/form-data.
- * Returns a status message string.
- */
-export async function restoreDatabase(file?: File): Promise<string> {
+/** Export the operator logbook of every flight matching `filter` as CSV or PDF */
+export async function exportLogbook(
+  filter: FlightFilter,
+  format: 'csv' | 'pdf',
+  fileName: string
+): Promise<void> {
   if (isWeb) {
-    if (!file) throw new Error('No file provided');
-    const formData = new FormData();
-    formData.append('file', file, file.name);
-    const response = await fetch(`${API_BASE}/backup/restore`, {
+    const response = await fetch(`${API_BASE}/flights/export/logbook`, {
       method: 'POST',
-      body: formData,
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ filter, format }),
     });
     if (!response.ok) {
       const body = await response.text();
       throw new Error(body);
     }
-    return response.json();
+    downloadBlob(fileName, await response.blob());
+    return;
   }
 
-  // Tauri mode: use native open dialog
-  const { open } = await import('@tauri-apps/plugin-dialog');
-  const srcPath = await open({
-    multiple: false,
-    filters: [{ name: 'Drone Logbook Backup', extensions: ['backup'] }],
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: format.toUpperCase(), extensions: [format] }],
   });
-  if (!srcPath) return ''; // user cancelled
-  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  if (!destPath) return;
   const invoke = await getTauriInvoke();
-  return invoke('import_backup', { srcPath: filePath }) as Promise<string>;
+  await invoke('export_logbook', { filter, format, destPath });
+}
+
+/** Save the library's aggregate statistics (totals, drones, months, records) as JSON */
+export async function exportLibraryStats(fileName: string): Promise<void> {
+  if (isWeb) {
+    const response = await fetch(`${API_BASE}/overview/export`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'JSON', extensions: ['json'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_library_stats', { destPath });
+}
+
+export async function getYearReview(year: number): Promise<YearReview> {
+  if (isWeb) {
+    return fetchJson<YearReview>(`/overview/year?year=${year}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_year_review', { year }) as Promise<YearReview>;
+}
+
+/** Save the one-page PDF of a year in review */
+export async function exportYearReviewPdf(year: number, fileName: string, units: ExportUnits = {}): Promise<void> {
+  if (isWeb) {
+    const params = new URLSearchParams({ year: String(year) });
+    if (units.length) params.set('length', units.length);
+    if (units.speed) params.set('speed', units.speed);
+    const response = await fetch(`${API_BASE}/overview/year/pdf?${params}`);
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    downloadBlob(fileName, await response.blob());
+    return;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: fileName,
+    filters: [{ name: 'PDF', extensions: ['pdf'] }],
+  });
+  if (!destPath) return;
+  const invoke = await getTauriInvoke();
+  await invoke('export_year_review', { year, destPath, units });
+}
+
+/** App tips and warnings counted across all flights, critical first */
+export async function searchFlights(query: string): Promise<FlightSearchHit[]> {
+  if (isWeb) {
+    return fetchJson<FlightSearchHit[]>(`/flights/search?q=${encodeURIComponent(query)}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('search_flights', { query }) as Promise<FlightSearchHit[]>;
+}
+
+/** Check the library for damage and inconsistencies; with `repair` also fix what can be fixed */
+export async function checkLibrary(repair = false): Promise<LibraryCheckReport> {
+  if (isWeb) {
+    return fetchJson<LibraryCheckReport>('/library/check', {
+      method: 'POST',
+      body: JSON.stringify({ repair }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('check_library', { repair }) as Promise<LibraryCheckReport>;
+}
+
+/** Audit log entries, newest first; with a flight ID only those of that flight */
+export async function getAuditLog(flightId?: number, limit?: number): Promise<AuditEntry[]> {
+  if (isWeb) {
+    const params = new URLSearchParams();
+    if (flightId !== undefined) params.set('flight_id', String(flightId));
+    if (limit !== undefined) params.set('limit', String(limit));
+    const query = params.toString();
+    return fetchJson<AuditEntry[]>(`/audit_log${query ? `?${query}` : ''}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_audit_log', { flightId: flightId ?? null, limit: limit ?? null }) as Promise<AuditEntry[]>;
+}
+
+export async function getSmartLists(): Promise<SmartList[]> {
+  if (isWeb) {
+    return fetchJson<SmartList[]>('/smart_lists');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_smart_lists') as Promise<SmartList[]>;
+}
+
+/** Create a smart list, or replace it when `id` is given */
+export async function saveSmartList(name: string, filter: FlightFilter, id?: number): Promise<SmartList> {
+  if (isWeb) {
+    return fetchJson<SmartList>('/smart_lists', {
+      method: 'POST',
+      body: JSON.stringify({ id: id ?? null, name, filter }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('save_smart_list', { id: id ?? null, name, filter }) as Promise<SmartList>;
+}
+
+export async function deleteSmartList(listId: number): Promise<void> {
+  if (isWeb) {
+    await fetchJson<boolean>(`/smart_lists?list_id=${listId}`, { method: 'DELETE' });
+    return;
+  }
+  const invoke = await getTauriInvoke();
+  await invoke('delete_smart_list', { listId });
+}
+
+/** IDs of the flights in a smart list, newest first */
+export async function evaluateSmartList(listId: number): Promise<number[]> {
+  if (isWeb) {
+    return fetchJson<number[]>(`/smart_lists/flights?list_id=${listId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('evaluate_smart_list', { listId }) as Promise<number[]>;
+}
+
+/** Telemetry columns an export template can select, in table order */
+export async function getTelemetryColumns(): Promise<string[]> {
+  if (isWeb) {
+    return fetchJson<string[]>('/export_templates/columns');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_telemetry_columns') as Promise<string[]>;
+}
+
+export async function getExportTemplates(): Promise<ExportTemplate[]> {
+  if (isWeb) {
+    return fetchJson<ExportTemplate[]>('/export_templates');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_export_templates') as Promise<ExportTemplate[]>;
+}
+
+/** Create an export template, or replace it when `id` is given */
+export async function saveExportTemplate(
+  template: Pick<ExportTemplate, 'name' | 'format' | 'columns' | 'units'>,
+  id?: number
+): Promise<ExportTemplate> {
+  const { name, format, columns, units } = template;
+  if (isWeb) {
+    return fetchJson<ExportTemplate>('/export_templates', {
+      method: 'POST',
+      body: JSON.stringify({ id: id ?? null, name, format, columns, units }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('save_export_template', { id: id ?? null, name, format, columns, units }) as Promise<ExportTemplate>;
+}
+
+export async function deleteExportTemplate(templateId: number): Promise<void> {
+  if (isWeb) {
+    await fetchJson<boolean>(`/export_templates?template_id=${templateId}`, { method: 'DELETE' });
+    return;
+  }
+  const invoke = await getTauriInvoke();
+  await invoke('delete_export_template', { templateId });
+}
+
+/** IDs of the flights matching a filter, newest first */
+export async function evaluateFlightFilter(filter: FlightFilter): Promise<number[]> {
+  if (isWeb) {
+    return fetchJson<number[]>('/flights/filter', {
+      method: 'POST',
+      body: JSON.stringify(filter),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('evaluate_flight_filter', { filter }) as Promise<number[]>;
+}
+
+export async function getWarningSummary(): Promise<WarningSummary[]> {
+  if (isWeb) {
+    return fetchJson<WarningSummary[]>('/warnings/summary');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_warning_summary') as Promise<WarningSummary[]>;
 }
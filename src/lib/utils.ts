@@ -143,3 +143,30 @@ export function normalizeSerial(serial: string | null | undefined): string {
   if (!serial) return '';
   return serial.trim().toUpperCase();
 }
+
+/** Chart color of a flight mode category */
+export function flightModeColor(category: string): string {
+  const colors: Record<string, string> = {
+    GPS: '#10b981',
+    Sport: '#ef4444',
+    Cine: '#8b5cf6',
+    ATTI: '#f59e0b',
+    RTH: '#0ea5e9',
+    'Takeoff/Landing': '#64748b',
+    Intelligent: '#ec4899',
+    Manual: '#f97316',
+  };
+  return colors[category] ?? '#6b7280';
+}
+
+/** Chart color of a flight phase */
+export function flightPhaseColor(phase: string): string {
+  const colors: Record<string, string> = {
+    ascent: '#00D4AA',
+    cruise: '#00A0DC',
+    hover: '#a855f7',
+    descent: '#f59e0b',
+    rth: '#f97316',
+  };
+  return colors[phase] ?? '#6b7280';
+}
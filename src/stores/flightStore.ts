@@ -5,7 +5,7 @@
 
 import { create } from 'zustand';
 import * as api from '@/lib/api';
-import type { Flight, FlightDataResponse, ImportResult, OverviewStats } from '@/types';
+import type { DroneProfile, Flight, MaintenanceKind, FlightDataResponse, ImportResult, OverviewStats } from '@/types';
 import { normalizeSerial } from '@/lib/utils';
 
 interface FlightState {
@@ -50,6 +50,10 @@ interface FlightState {
   deleteFlight: (flightId: number) => Promise<void>;
   updateFlightName: (flightId: number, displayName: string) => Promise<void>;
   updateFlightNotes: (flightId: number, notes: string | null) => Promise<void>;
+  setFlightFavorite: (flightId: number, favorite: boolean) => Promise<void>;
+  setFlightExcludedFromStats: (flightId: number, excluded: boolean) => Promise<void>;
+  addAnnotation: (flightId: number, offsetSecs: number, text: string) => Promise<void>;
+  removeAnnotation: (flightId: number, annotationId: number) => Promise<void>;
   addTag: (flightId: number, tag: string) => Promise<void>;
   removeTag: (flightId: number, tag: string) => Promise<void>;
   loadAllTags: () => Promise<void>;
@@ -85,8 +89,17 @@ interface FlightState {
   renameBattery: (serial: string, displayName: string) => void;
   getBatteryDisplayName: (serial: string) => string;
 
-  // Drone name mapping (serial -> custom display name)
+  // Drone name mapping (serial -> custom display name), backed by the drone profiles
   droneNameMap: Record<string, string>;
+  droneProfiles: DroneProfile[];
+  loadDroneProfiles: () => Promise<void>;
+  updateDroneProfile: (
+    serial: string,
+    nickname: string | null,
+    purchaseDate: string | null,
+    notes: string | null,
+    registration: string | null
+  ) => Promise<void>;
   renameDrone: (serial: string, displayName: string) => void;
   getDroneDisplayName: (serial: string, fallbackName: string) => string;
 
@@ -123,7 +136,8 @@ interface FlightState {
     aircraft: Record<string, string>; // droneSerial -> ISO timestamp
   };
   setMaintenanceThreshold: (type: 'battery' | 'aircraft', field: 'flights' | 'airtime', value: number) => void;
-  performMaintenance: (type: 'battery' | 'aircraft', serial: string, date?: Date) => void;
+  loadMaintenance: () => Promise<void>;
+  performMaintenance: (type: 'battery' | 'aircraft', serial: string, date?: Date, kind?: MaintenanceKind, description?: string) => void;
   getMaintenanceLastReset: (type: 'battery' | 'aircraft', serial: string) => string | null;
 }
 
@@ -185,6 +199,7 @@ export const useFlightStore = create<FlightState>((set, get) => ({
       return {};
     }
   })(),
+  droneProfiles: [],
   hideSerialNumbers:
     typeof localStorage !== 'undefined'
       ? localStorage.getItem('hideSerialNumbers') === 'true'
@@ -235,16 +250,51 @@ export const useFlightStore = create<FlightState>((set, get) => ({
     }
     set({ maintenanceThresholds: thresholds });
   },
-  performMaintenance: (type, serial, date) => {
+  loadMaintenance: async () => {
+    try {
+      let status = await api.getMaintenanceStatus();
+
+      // Resets from before maintenance was logged in the database only exist in localStorage: move them over once
+      const legacy = typeof localStorage !== 'undefined' ? localStorage.getItem('maintenanceLastReset') : null;
+      if (legacy) {
+        const legacyReset: { battery: Record<string, string>; aircraft: Record<string, string> } = JSON.parse(legacy);
+        let migrated = 0;
+        for (const s of status) {
+          const at = legacyReset[s.targetType === 'drone' ? 'aircraft' : 'battery']?.[normalizeSerial(s.serial)];
+          if (at && !s.lastMaintenance) {
+            await api.addMaintenanceRecord(s.targetType, s.serial, 'service', at, null);
+            migrated++;
+          }
+        }
+        if (migrated > 0) {
+          status = await api.getMaintenanceStatus();
+        }
+        localStorage.removeItem('maintenanceLastReset');
+      }
+
+      const lastReset: { battery: Record<string, string>; aircraft: Record<string, string> } = { battery: {}, aircraft: {} };
+      for (const s of status) {
+        if (s.lastMaintenance) {
+          lastReset[s.targetType === 'drone' ? 'aircraft' : 'battery'][normalizeSerial(s.serial)] = s.lastMaintenance;
+        }
+      }
+      set({ maintenanceLastReset: lastReset });
+    } catch {
+      // Keep the resets already in memory
+    }
+  },
+  performMaintenance: (type, serial, date, kind = 'service', description) => {
     const normalizedSerial = normalizeSerial(serial);
     const lastReset = { ...get().maintenanceLastReset };
     // Use provided date or default to now
     const maintenanceDate = date ? date.toISOString() : new Date().toISOString();
     lastReset[type] = { ...lastReset[type], [normalizedSerial]: maintenanceDate };
-    if (typeof localStorage !== 'undefined') {
-      localStorage.setItem('maintenanceLastReset', JSON.stringify(lastReset));
-    }
     set({ maintenanceLastReset: lastReset });
+
+    api
+      .addMaintenanceRecord(type === 'aircraft' ? 'drone' : 'battery', serial, kind, maintenanceDate, description ?? null)
+      .then(() => get().loadMaintenance())
+      .catch((err) => set({ error: `Failed to log maintenance: ${err}` }));
   },
   getMaintenanceLastReset: (type, serial) => {
     const normalizedSerial = normalizeSerial(serial);
@@ -258,8 +308,10 @@ export const useFlightStore = create<FlightState>((set, get) => ({
       const flights = await api.getFlights();
       set({ flights, isLoading: false, isFlightsInitialized: true });
 
-      // Load all tags in background
+      // Load all tags, drone profiles and maintenance in background
       get().loadAllTags();
+      get().loadDroneProfiles();
+      get().loadMaintenance();
 
       // Auto-select last used flight if available (avoid heavy load on fresh startup)
       const selectedFlightId = get().selectedFlightId;
@@ -339,6 +391,21 @@ export const useFlightStore = create<FlightState>((set, get) => ({
       if (typeof localStorage !== 'undefined') {
         localStorage.setItem('lastSelectedFlightId', String(flightId));
       }
+
+      // The terrain lookup may go online, so it follows the flight data
+      api
+        .getFlightTerrain(flightId, 5000)
+        .then((terrain) => {
+          if (!terrain) return;
+          const withTerrain: FlightDataResponse = {
+            ...flightData,
+            telemetry: { ...flightData.telemetry, agl: terrain.agl, groundElevation: terrain.groundElevation },
+          };
+          const cache = new Map(get()._flightDataCache);
+          cache.set(flightId, withTerrain);
+          set(get().selectedFlightId === flightId ? { currentFlightData: withTerrain, _flightDataCache: cache } : { _flightDataCache: cache });
+        })
+        .catch((err) => console.warn('Terrain lookup failed:', err));
     } catch (err) {
       // Clear the persisted flight ID on error so we don't crash-loop on restart
       if (typeof localStorage !== 'undefined') {
@@ -400,7 +467,7 @@ export const useFlightStore = create<FlightState>((set, get) => ({
         if (result.success && result.flightId) {
           processed += 1;
           lastFlightId = result.flightId;
-        } else if (result.message.toLowerCase().includes('already been imported')) {
+        } else if (/already been imported|in the trash/.test(result.message.toLowerCase())) {
           skipped += 1;
         }
       } catch {
@@ -521,6 +588,101 @@ export const useFlightStore = create<FlightState>((set, get) => ({
     }
   },
 
+  // Star or unstar a flight
+  setFlightFavorite: async (flightId: number, favorite: boolean) => {
+    try {
+      await api.setFlightFavorite(flightId, favorite);
+
+      const flights = get().flights.map((flight) =>
+        flight.id === flightId ? { ...flight, favorite } : flight
+      );
+      set({ flights });
+
+      const current = get().currentFlightData;
+      if (current && current.flight.id === flightId) {
+        const updated = {
+          ...current,
+          flight: { ...current.flight, favorite },
+        };
+        const cache = new Map(get()._flightDataCache);
+        cache.set(flightId, updated);
+        set({
+          currentFlightData: updated,
+          _flightDataCache: cache,
+        });
+      }
+    } catch (err) {
+      set({ error: `Failed to update favorite: ${err}` });
+    }
+  },
+
+  // Leave a flight out of statistics or count it again
+  setFlightExcludedFromStats: async (flightId: number, excluded: boolean) => {
+    try {
+      await api.setFlightExcludedFromStats(flightId, excluded);
+
+      const flights = get().flights.map((flight) =>
+        flight.id === flightId ? { ...flight, excludedFromStats: excluded } : flight
+      );
+      set({ flights });
+
+      const current = get().currentFlightData;
+      if (current && current.flight.id === flightId) {
+        const updated = {
+          ...current,
+          flight: { ...current.flight, excludedFromStats: excluded },
+        };
+        const cache = new Map(get()._flightDataCache);
+        cache.set(flightId, updated);
+        set({
+          currentFlightData: updated,
+          _flightDataCache: cache,
+        });
+      }
+      get().loadOverview();
+    } catch (err) {
+      set({ error: `Failed to update statistics exclusion: ${err}` });
+    }
+  },
+
+  // Pin a note to a moment of a flight
+  addAnnotation: async (flightId: number, offsetSecs: number, text: string) => {
+    try {
+      const annotation = await api.addFlightAnnotation(flightId, offsetSecs, text);
+      const current = get().currentFlightData;
+      if (current && current.flight.id === flightId) {
+        const annotations = [...(current.annotations ?? []), annotation].sort(
+          (a, b) => a.offsetSecs - b.offsetSecs
+        );
+        const updated = { ...current, annotations };
+        const cache = new Map(get()._flightDataCache);
+        cache.set(flightId, updated);
+        set({ currentFlightData: updated, _flightDataCache: cache });
+      }
+    } catch (err) {
+      set({ error: `Failed to add annotation: ${err}` });
+    }
+  },
+
+  // Delete a flight annotation
+  removeAnnotation: async (flightId: number, annotationId: number) => {
+    try {
+      await api.deleteFlightAnnotation(annotationId);
+      const current = get().currentFlightData;
+      if (current && current.flight.id === flightId) {
+        const updated = {
+          ...current,
+          annotations: (current.annotations ?? []).filter((a) => a.id !== annotationId),
+        };
+        const cache = new Map(get()._flightDataCache);
+        cache.set(flightId, updated);
+        set({ currentFlightData: updated, _flightDataCache: cache });
+      }
+    } catch (err) {
+      set({ error: `Failed to delete annotation: ${err}` });
+    }
+  },
+
   // Add a tag to a flight
   addTag: async (flightId: number, tag: string) => {
     try {
@@ -715,6 +877,55 @@ export const useFlightStore = create<FlightState>((set, get) => ({
     return get().hideSerialNumbers ? '*****' : normalizedSerial;
   },
 
+  loadDroneProfiles: async () => {
+    try {
+      let profiles = await api.getDroneProfiles();
+
+      // Names from before profiles lived in the database only exist in localStorage: move them over once
+      const legacy = typeof localStorage !== 'undefined' ? localStorage.getItem('droneNameMap') : null;
+      if (legacy) {
+        const legacyMap: Record<string, string> = JSON.parse(legacy);
+        const pending = profiles.filter((p) => !p.nickname && legacyMap[normalizeSerial(p.serial)]);
+        for (const p of pending) {
+          await api.updateDroneProfile(
+            p.serial,
+            legacyMap[normalizeSerial(p.serial)],
+            p.purchaseDate,
+            p.notes,
+            p.registration
+          );
+        }
+        if (pending.length > 0) {
+          profiles = await api.getDroneProfiles();
+        }
+        localStorage.removeItem('droneNameMap');
+      }
+
+      const map: Record<string, string> = {};
+      for (const p of profiles) {
+        if (p.nickname) map[normalizeSerial(p.serial)] = p.nickname;
+      }
+      set({ droneProfiles: profiles, droneNameMap: map });
+    } catch {
+      // Keep the names already in memory
+    }
+  },
+
+  updateDroneProfile: async (serial, nickname, purchaseDate, notes, registration) => {
+    try {
+      await api.updateDroneProfile(serial, nickname, purchaseDate, notes, registration);
+      const nick = nickname?.trim() || null;
+      set((state) => ({
+        flights: state.flights.map((f) =>
+          f.droneSerial === serial ? { ...f, droneNickname: nick } : f
+        ),
+      }));
+      await get().loadDroneProfiles();
+    } catch (err) {
+      set({ error: `Failed to update drone profile: ${err}` });
+    }
+  },
+
   renameDrone: (serial: string, displayName: string) => {
     const normalizedSerial = normalizeSerial(serial);
     const map = { ...get().droneNameMap };
@@ -724,10 +935,16 @@ export const useFlightStore = create<FlightState>((set, get) => ({
     } else {
       map[normalizedSerial] = displayName.trim();
     }
-    if (typeof localStorage !== 'undefined') {
-      localStorage.setItem('droneNameMap', JSON.stringify(map));
-    }
     set({ droneNameMap: map });
+
+    const profile = get().droneProfiles.find((p) => normalizeSerial(p.serial) === normalizedSerial);
+    get().updateDroneProfile(
+      profile?.serial ?? serial,
+      displayName.trim() || null,
+      profile?.purchaseDate ?? null,
+      profile?.notes ?? null,
+      profile?.registration ?? null
+    );
   },
 
   getDroneDisplayName: (serial: string, fallbackName: string) => {
@@ -29,6 +29,28 @@ export interface Flight {
   pointCount: number | null;
   tags?: FlightTag[];
   notes?: string | null;
+  missionName?: string | null;
+  partial?: boolean;
+  /** Set while the flight is in the trash */
+  deletedAt?: string | null;
+  /** User-set name from the drone profile */
+  droneNickname?: string | null;
+  /** Reverse-geocoded from the home point */
+  locationCity?: string | null;
+  locationRegion?: string | null;
+  locationCountry?: string | null;
+  /** Starred by the user */
+  favorite?: boolean;
+  /** Left out of statistics, e.g. a bench test; still counts toward equipment usage */
+  excludedFromStats?: boolean;
+  /** Aircraft firmware version from the log */
+  firmwareVersion?: string | null;
+  /** Farthest 3D distance from the home point in meters */
+  maxDistanceFromHome?: number | null;
+  /** Darkest light flown in at the home point */
+  daylight?: 'day' | 'civil_twilight' | 'night' | null;
+  /** Flight time after the end of civil twilight, in seconds */
+  nightSecs?: number | null;
 }
 
 /** Telemetry data formatted for ECharts */
@@ -44,6 +66,8 @@ export interface TelemetryData {
   velocityX?: (number | null)[];
   velocityY?: (number | null)[];
   velocityZ?: (number | null)[];
+  /** Climb rate in m/s, positive up, derived from height by the backend */
+  verticalSpeed?: (number | null)[];
   battery: (number | null)[];
   batteryVoltage: (number | null)[];
   batteryTemp: (number | null)[];
@@ -64,6 +88,32 @@ export interface TelemetryData {
   isVideo?: (boolean | null)[];
   /** Flight mode (e.g., "GPS", "ATTI", "Sport") */
   flightMode?: (string | null)[];
+  /** Path distance flown so far in meters, ending on the flight's total distance */
+  cumulativeDistance?: number[];
+  /** Horizontal distance from the home point in meters, following mid-flight home updates */
+  distanceFromHome?: (number | null)[];
+  /** Distance from the home point including the height above takeoff, meters */
+  distanceFromHome3d?: (number | null)[];
+  /** Battery cell voltages, one series per cell (absent when not logged) */
+  cellVoltages?: (number | null)[][];
+  /** Compass error flag raised by the flight controller */
+  compassError?: (boolean | null)[];
+  /** Magnetic field deviation from the flight baseline (%) */
+  compassInterference?: (number | null)[];
+  /** IMU problem or calibration state (e.g. "Preheating"), null while healthy */
+  imuStatus?: (string | null)[];
+  /** Wind speed in m/s (only for logs that record it) */
+  windSpeed?: (number | null)[];
+  /** Direction the wind blows from, degrees (0 = north) */
+  windDirection?: (number | null)[];
+  /** Wind speed in m/s estimated from the attitude, for logs without windSpeed */
+  estimatedWindSpeed?: (number | null)[];
+  /** Direction the estimated wind blows from, degrees (0 = north) */
+  estimatedWindDirection?: (number | null)[];
+  /** Height above the ground in meters, added once the terrain lookup finishes */
+  agl?: (number | null)[];
+  /** Ground elevation above sea level in meters, added with agl */
+  groundElevation?: (number | null)[];
 }
 
 /** Complete flight data response from backend */
@@ -72,6 +122,492 @@ export interface FlightDataResponse {
   telemetry: TelemetryData;
   /** GPS track: [lng, lat, height][] */
   track: [number, number, number][];
+  /** Extra source files linked to this flight (e.g. aircraft DAT logs) */
+  attachments?: FlightAttachment[];
+  /** Home point history: takeoff point plus mid-flight updates */
+  homePoints?: HomePoint[];
+  /** Photo and video capture events derived from the camera state */
+  events?: FlightEvent[];
+  /** Takeoffs, landings, mode changes, captures and warnings in time order */
+  timeline?: TimelineEvent[];
+  /** Ascent, cruise, hover, descent and RTH ranges covering the flight */
+  phases?: FlightPhase[];
+  /** Per-motor ESC series aligned to telemetry.time (DAT logs only) */
+  motors?: MotorSeries[];
+  /** User notes pinned to moments of the flight */
+  annotations?: FlightAnnotation[];
+}
+
+/** User note pinned to a moment of a flight */
+export interface FlightAnnotation {
+  id: number;
+  /** Seconds since flight start */
+  offsetSecs: number;
+  text: string;
+  createdAt: string | null;
+}
+
+export type IncidentKind = 'crash' | 'flyaway' | 'near_miss' | 'equipment_failure' | 'other';
+
+export type IncidentSeverity = 'minor' | 'moderate' | 'serious' | 'critical';
+
+/** Safety incident reported against a moment of a flight */
+export interface Incident {
+  id: number;
+  flightId: number;
+  flightName: string | null;
+  flightStartTime: string | null;
+  /** Seconds since flight start */
+  offsetSecs: number;
+  kind: IncidentKind;
+  severity: IncidentSeverity;
+  description: string | null;
+  createdAt: string | null;
+}
+
+/** RPM, ESC current (A) and ESC temperature (°C) of one motor */
+export interface MotorSeries {
+  motorIndex: number;
+  rpm: (number | null)[];
+  current: (number | null)[];
+  temperature: (number | null)[];
+}
+
+/** Flight event: a photo, one continuous video recording, a camera/gimbal settings change, or an app message */
+export interface FlightEvent {
+  timestampMs: number;
+  eventType: 'photo' | 'video' | 'camera_settings' | 'gimbal_mode' | 'tip' | 'warning';
+  lat: number | null;
+  lon: number | null;
+  altitude: number | null;
+  /** Recording length, only set for video events */
+  durationMs: number | null;
+  /** New value of a settings change, e.g. "ISO 100, 1/1000, f/2.8" or "YawFollow", or the message text */
+  detail?: string | null;
+  /** Only set for tips and warnings */
+  severity?: 'info' | 'warning' | 'critical' | null;
+}
+
+/** Entry of a flight's event timeline */
+export interface TimelineEvent {
+  /** Seconds since flight start, on the telemetry time axis */
+  offsetSecs: number;
+  kind: 'takeoff' | 'landing' | 'mode' | 'rth' | 'photo' | 'video' | 'warning';
+  /** Readable description, e.g. "Mode changed to Sport" */
+  label: string;
+  /** New mode for mode changes, message text for warnings */
+  detail: string | null;
+  /** Recording length, only set for videos */
+  durationSecs: number | null;
+  /** Only set for warnings */
+  severity: 'info' | 'warning' | 'critical' | null;
+}
+
+/** Range of a flight spent in one phase */
+export interface FlightPhase {
+  phase: 'ground' | 'ascent' | 'cruise' | 'hover' | 'descent' | 'rth';
+  /** Seconds since flight start, on the telemetry time axis */
+  startSecs: number;
+  endSecs: number;
+}
+
+/** One app tip/warning message counted across the whole logbook */
+export interface WarningSummary {
+  message: string;
+  severity: 'info' | 'warning' | 'critical';
+  occurrences: number;
+  flightCount: number;
+  lastSeen: string | null;
+}
+
+/** Flight matching a search query, best matches scored highest */
+export interface FlightSearchHit {
+  flightId: number;
+  score: number;
+}
+
+/** Home point position from a given moment of the flight */
+export interface HomePoint {
+  timestampMs: number;
+  lat: number;
+  lon: number;
+  altitude: number | null;
+}
+
+/** Photo or video on disk associated with a flight */
+export interface FlightMedia {
+  filePath: string;
+  fileName: string;
+  mediaType: 'photo' | 'video';
+  capturedAt: string | null;
+  lat: number | null;
+  lon: number | null;
+  /** Seconds since flight start (negative when captured before takeoff) */
+  offsetSecs: number | null;
+}
+
+/** File attached to a flight, such as an airspace authorization or a client brief */
+export interface FlightDocument {
+  id: number;
+  flightId: number;
+  fileName: string;
+  /** SHA-256 of the content, hex encoded */
+  sha256: string;
+  sizeBytes: number;
+  addedAt: string | null;
+}
+
+/** Units exported telemetry is converted to; omitted fields stay SI (m, m/s, °C) */
+export interface ExportUnits {
+  length?: 'm' | 'ft';
+  speed?: 'ms' | 'kmh' | 'mph' | 'kn';
+  temperature?: 'c' | 'f';
+}
+
+/** Time alignment of the video overlay export */
+export interface OverlayOptions {
+  /** Seconds into the flight at which the video starts; defaults to the first recording in the log */
+  offsetSecs?: number;
+  /** Seconds between rows (default 0.1) */
+  intervalSecs?: number;
+}
+
+/** Simplification and heat grid of the coverage export */
+export interface CoverageOptions {
+  /** Largest distance in meters a simplified track may stray from the logged one (default 5, 0 keeps every point) */
+  simplifyM?: number;
+  /** Edge of a heat grid cell in meters (default 100); null for no grid */
+  heatGridM?: number | null;
+}
+
+/** Outcome of a batch export */
+export interface BatchExportResult {
+  /** Flights matching the filter */
+  total: number;
+  exported: number;
+  /** Flights that could not be exported; summary.csv says why */
+  failed: number;
+  /** Folder the files were written to (null for a downloaded archive) */
+  outputPath: string | null;
+}
+
+/** Pilot shown on flight reports */
+export interface PilotDetails {
+  name: string | null;
+  /** Remote pilot license or operator registration number */
+  license: string | null;
+}
+
+/** A recency requirement, e.g. an insurer's 3 flights in the last 90 days */
+export interface CurrencyRequirement {
+  windowDays: number;
+  minFlights: number;
+  minHours: number;
+}
+
+/** Source of ground elevations for the height above terrain */
+export type TerrainProvider = 'off' | 'open-meteo' | 'local';
+
+export interface TerrainSettings {
+  provider: TerrainProvider;
+  /** Folder of SRTM .hgt tiles for the local provider */
+  demDirectory: string | null;
+}
+
+/** Terrain under a flight, aligned to its telemetry points */
+export interface TerrainProfile {
+  /** Ground elevation above sea level under each point, meters */
+  groundElevation: (number | null)[];
+  /** Height above the ground under each point, meters */
+  agl: (number | null)[];
+}
+
+/** Smoothing of the map track: off, exponential moving average or Kalman smoother */
+export type TrackSmoothing = 'off' | 'ema' | 'kalman';
+
+/** Height and distance limits flights are checked against for compliance */
+export interface ComplianceLimits {
+  /** Height above takeoff in meters; null turns the check off */
+  maxHeightM: number | null;
+  /** Horizontal distance from home in meters; null turns the check off */
+  maxDistanceM: number | null;
+}
+
+/** Stretch of a flight over a compliance limit */
+export interface ComplianceSegment {
+  kind: 'height' | 'distance';
+  startMs: number;
+  endMs: number;
+  /** Highest height or farthest distance within the stretch, in meters */
+  peakM: number;
+  lat: number | null;
+  lon: number | null;
+}
+
+export interface ComplianceFlight {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  droneModel: string | null;
+  droneSerial: string | null;
+  /** In time order */
+  segments: ComplianceSegment[];
+}
+
+/** Flights counted in statistics checked against the compliance limits */
+export interface ComplianceReport {
+  limits: ComplianceLimits;
+  flightCount: number;
+  heightViolations: number;
+  distanceViolations: number;
+  /** Flights over a limit, newest first */
+  flights: ComplianceFlight[];
+}
+
+/** Flights and flight time within the last `days` days */
+export interface CurrencyWindow {
+  days: number;
+  flightCount: number;
+  durationSecs: number;
+}
+
+/** A requirement and whether the recent flying meets it */
+export interface CurrencyCheck {
+  requirement: CurrencyRequirement;
+  flightCount: number;
+  durationSecs: number;
+  met: boolean;
+}
+
+/** Recent flying of the pilot or of one aircraft */
+export interface CurrencyEntry {
+  /** Pilot name, or the aircraft's nickname or name */
+  name: string | null;
+  droneModel: string | null;
+  droneSerial: string | null;
+  lastFlight: string | null;
+  /** Shortest window first */
+  windows: CurrencyWindow[];
+  checks: CurrencyCheck[];
+  /** Every requirement is met */
+  current: boolean;
+}
+
+/** Currency of the pilot and of each aircraft */
+export interface CurrencyReport {
+  pilot: CurrencyEntry;
+  drones: CurrencyEntry[];
+}
+
+/** Time spent in one flight mode as logged */
+export interface FlightModeTime {
+  /** Mode name from the log, e.g. "GPSAtti" or "P-GPS" */
+  mode: string;
+  /** GPS, Sport, Cine, ATTI, RTH, Takeoff/Landing, Intelligent, Manual or Other */
+  category: string;
+  durationSecs: number;
+  /** Flights that were in the mode at some point */
+  flightCount: number;
+}
+
+/** Time spent in one group of flight modes */
+export interface FlightModeCategory {
+  category: string;
+  durationSecs: number;
+  flightCount: number;
+}
+
+/** Time per flight mode across the flights counted in statistics */
+export interface FlightModeStats {
+  /** Time with a logged flight mode */
+  totalSecs: number;
+  /** Longest first */
+  categories: FlightModeCategory[];
+  /** Longest first */
+  modes: FlightModeTime[];
+}
+
+/** RTH triggers, failsafe activations and RC signal losses of some flights */
+export interface RthEventCounts {
+  flightCount: number;
+  rthCount: number;
+  failsafeCount: number;
+  signalLossCount: number;
+  /** Flights with at least one of the events */
+  affectedFlights: number;
+}
+
+export interface MonthRthEvents {
+  /** YYYY-MM */
+  month: string;
+  counts: RthEventCounts;
+}
+
+/** RTH event counts of one aircraft, keyed by serial or, without one, by model */
+export interface DroneRthEvents {
+  droneModel: string;
+  droneSerial: string | null;
+  counts: RthEventCounts;
+  /** Oldest first */
+  months: MonthRthEvents[];
+}
+
+export interface FlightRthEvents {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  droneModel: string | null;
+  droneSerial: string | null;
+  rthCount: number;
+  failsafeCount: number;
+  signalLossCount: number;
+}
+
+/** RTH, failsafe and signal-loss statistics of the flights counted in statistics */
+export interface RthStats {
+  totals: RthEventCounts;
+  /** Most RTH triggers per flight first */
+  drones: DroneRthEvents[];
+  /** Oldest first */
+  months: MonthRthEvents[];
+  /** Flights with events, newest first */
+  flights: FlightRthEvents[];
+}
+
+/** Aggregates of one aircraft (by serial) or of every aircraft of a model */
+export interface DroneComparisonStats {
+  /** Serial or model the side was asked for */
+  drone: string;
+  /** Model of the newest flight */
+  droneModel: string | null;
+  /** Set when the flights are from a single aircraft */
+  droneSerial: string | null;
+  nickname: string | null;
+  flightCount: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+  avgDurationSecs: number;
+  avgDistanceM: number;
+  maxAltitudeM: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  whPerKm: number | null;
+  whPerMin: number | null;
+  /** Mean logged wind speed (m/s), weighted by flight time */
+  avgWindSpeed: number | null;
+  /** Flights that logged wind */
+  windFlightCount: number;
+  /** Incidents reported against the flights */
+  incidentCount: number;
+  safetyEvents: RthEventCounts;
+}
+
+/** Two aircraft or models side by side */
+export interface DroneComparison {
+  first: DroneComparisonStats;
+  second: DroneComparisonStats;
+}
+
+/** A place flights are flown from, found by clustering their home points */
+export interface Site {
+  id: number;
+  name: string;
+  /** Named by the user rather than after the geocoded location */
+  customName: boolean;
+  centerLat: number;
+  centerLon: number;
+  /** Farthest home point of the site from its center, in meters */
+  radiusM: number;
+  /** Counted flights only */
+  flightCount: number;
+  /** Days with at least one counted flight */
+  visitCount: number;
+  durationSecs: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+}
+
+/** Flying at a site in one month of the year, over every year */
+export interface SiteMonthStats {
+  /** 1 = January */
+  month: number;
+  flightCount: number;
+  durationSecs: number;
+  /** Mean logged wind speed (m/s), weighted by flight time */
+  avgWindSpeed: number | null;
+}
+
+/** History of one site: flight time, wind, seasons and aircraft */
+export interface SiteStats {
+  site: Site;
+  distanceM: number;
+  /** Median of the flights' mean wind speeds (m/s) */
+  typicalWindSpeed: number | null;
+  /** Strongest mean wind of a flight (m/s) */
+  maxWindSpeed: number | null;
+  /** Flights that logged wind */
+  windFlightCount: number;
+  /** Month of the year with the most flight time */
+  busiestMonth: number | null;
+  /** Month of the year with the lowest mean wind */
+  calmestMonth: number | null;
+  /** Months with flights, January first */
+  months: SiteMonthStats[];
+  /** Most flown first */
+  drones: DroneUsage[];
+}
+
+/** Battery energy used on one flight, integrated from voltage × current */
+export interface FlightEnergy {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  droneModel: string | null;
+  droneSerial: string | null;
+  energyWh: number;
+  durationSecs: number;
+  distanceM: number;
+  /** Null for flights that barely moved */
+  whPerKm: number | null;
+  whPerMin: number | null;
+}
+
+/** Energy use and efficiency of one aircraft */
+export interface DroneEnergy {
+  droneModel: string;
+  droneSerial: string | null;
+  flightCount: number;
+  energyWh: number;
+  durationSecs: number;
+  distanceM: number;
+  whPerKm: number | null;
+  whPerMin: number | null;
+  /** Oldest first */
+  flights: FlightEnergy[];
+}
+
+/** Copying every newly imported flight to a folder */
+export interface AutoExportSettings {
+  enabled: boolean;
+  /** Folder the copies are written to; on the server in web mode */
+  directory: string | null;
+  formats: ('gpx' | 'kml' | 'csv' | 'json')[];
+}
+
+/** Summary of a media folder scan */
+export interface MediaScanResult {
+  scanned: number;
+  timestamped: number;
+  matched: number;
+}
+
+/** Source file linked to an existing flight instead of imported separately */
+export interface FlightAttachment {
+  fileHash: string;
+  fileName: string;
+  sourceFormat: string;
+  pointCount: number;
+  attachedAt: string | null;
 }
 
 export interface BatteryUsage {
@@ -85,6 +621,111 @@ export interface DroneUsage {
   droneSerial: string | null;
   aircraftName: string | null;
   flightCount: number;
+  nickname?: string | null;
+}
+
+/** Flight battery registered from the logs, with its usage totals */
+export interface Battery {
+  serial: string;
+  /** Aircraft the battery was first flown with */
+  droneModel: string | null;
+  firstSeen: string | null;
+  lastUsed: string | null;
+  flightCount: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+}
+
+/** Reusable pre-flight checklist */
+export interface ChecklistTemplate {
+  id: number;
+  name: string;
+  items: string[];
+  updatedAt: string | null;
+}
+
+/** One line of a completed checklist */
+export interface ChecklistItem {
+  text: string;
+  checked: boolean;
+}
+
+/** A checklist as it was completed for a flight */
+export interface FlightChecklist {
+  id: number;
+  flightId: number;
+  /** Template the checklist was made from, if it still exists */
+  templateId: number | null;
+  name: string;
+  items: ChecklistItem[];
+  /** True when every item was checked off */
+  allChecked: boolean;
+  completedBy: string | null;
+  notes: string | null;
+  completedAt: string | null;
+}
+
+export type EquipmentCategory = 'filter' | 'lens' | 'payload' | 'propellers' | 'controller' | 'other';
+
+/** Payload or accessory in the equipment inventory with its usage totals */
+export interface Equipment {
+  id: number;
+  name: string;
+  category: EquipmentCategory;
+  serial: string | null;
+  notes: string | null;
+  flightCount: number;
+  totalDurationSecs: number;
+  lastUsed: string | null;
+}
+
+export type MaintenanceKind = 'prop_swap' | 'firmware_update' | 'repair' | 'calibration' | 'service' | 'other';
+
+/** Maintenance work logged against a drone or battery */
+export interface MaintenanceRecord {
+  id: number;
+  targetType: 'drone' | 'battery';
+  serial: string;
+  kind: MaintenanceKind;
+  performedAt: string;
+  description: string | null;
+}
+
+/** Usage of a drone or battery since its last maintenance */
+export interface MaintenanceStatus {
+  targetType: 'drone' | 'battery';
+  serial: string;
+  /** Null if no maintenance was ever logged */
+  lastMaintenance: string | null;
+  lastKind: MaintenanceKind | null;
+  flightsSince: number;
+  flightHoursSince: number;
+}
+
+/** User profile of one aircraft, keyed by serial, with its usage totals */
+export interface DroneProfile {
+  serial: string;
+  droneModel: string | null;
+  aircraftName: string | null;
+  nickname: string | null;
+  /** YYYY-MM-DD */
+  purchaseDate: string | null;
+  notes: string | null;
+  flightCount: number;
+  totalDurationSecs: number;
+  lastFlight: string | null;
+  /** Firmware version of the most recent flight that logged one */
+  firmwareVersion: string | null;
+  /** Registration number or operator marking, printed on the logbook */
+  registration: string | null;
+}
+
+/** A firmware version an aircraft flew with, and when */
+export interface FirmwareHistoryEntry {
+  firmwareVersion: string;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  flightCount: number;
 }
 
 export interface FlightDateCount {
@@ -115,6 +756,53 @@ export interface BatteryHealthPoint {
   ratePerMin: number;
 }
 
+/** Battery figures of one flight, the points of a battery's health trend */
+export interface BatteryFlightSample {
+  flightId: number;
+  batterySerial: string;
+  startTime: string | null;
+  durationSecs: number;
+  startPercent: number | null;
+  endPercent: number | null;
+  /** Mean current drawn in amperes */
+  avgCurrentA: number | null;
+  /** Charge a full battery holds, from the charge drawn and the percentage used */
+  capacityMah: number | null;
+  /** Resting voltage minus the lowest voltage over the first 10% of charge used */
+  voltageSagV: number | null;
+  /** Largest voltage difference between cells at one moment */
+  cellImbalanceV: number | null;
+}
+
+/** Health trend of one battery across its flights */
+export interface BatteryHealth {
+  serial: string;
+  flightCount: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  /** Capacity lost between the first and the latest flights, in percent */
+  capacityFadePercent: number | null;
+  /** Change in voltage sag between the first and the latest flights */
+  voltageSagChangeV: number | null;
+  /** Worst cell imbalance of the latest flights */
+  recentCellImbalanceV: number | null;
+  /** 100 for a battery behaving as when new, down to 0 */
+  healthScore: number;
+  /** Oldest first */
+  flights: BatteryFlightSample[];
+}
+
+/** Battery discharge during one flight, for comparing a battery's flights */
+export interface DischargeCurve {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  /** [minutes since the log started, charge in percent] */
+  percentByMinute: [number, number][];
+  /** [charge in percent, mean voltage at that charge], full battery first */
+  voltageByPercent: [number, number][];
+}
+
 export interface OverviewStats {
   totalFlights: number;
   totalDistanceM: number;
@@ -130,6 +818,129 @@ export interface OverviewStats {
   batteryHealthPoints: BatteryHealthPoint[];
 }
 
+/** Flight totals for one calendar month */
+export interface MonthlyStats {
+  /** YYYY-MM */
+  month: string;
+  flightCount: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+}
+
+/** Period length of a trend; weeks run Monday to Sunday */
+export type TrendBucket = 'day' | 'week' | 'month' | 'year';
+
+/** Flight totals for one period of a trend */
+export interface TrendPoint {
+  /** First day of the period, YYYY-MM-DD */
+  period: string;
+  flightCount: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+}
+
+/** Flight totals for one aircraft, keyed by serial or, without one, by model */
+export interface DroneStats {
+  droneModel: string;
+  droneSerial: string | null;
+  aircraftName: string | null;
+  nickname: string | null;
+  flightCount: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+}
+
+/** Aggregate statistics of the library or a subset of its flights */
+export interface LibraryStats {
+  totalFlights: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+  totalPoints: number;
+  maxAltitudeM: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  /** Days with at least one flight */
+  daysFlown: number;
+  /** Flight time after the end of civil twilight */
+  nightDurationSecs: number;
+  /** Flights with some time flown at night */
+  nightFlightCount: number;
+  /** Flights in civil twilight at the darkest, none at night */
+  twilightFlightCount: number;
+  /** Most flown first */
+  drones: DroneStats[];
+  /** Most flown first */
+  batteries: BatteryUsage[];
+  months: MonthlyStats[];
+}
+
+/** A round number of flights, hours or kilometers and when it was reached */
+export interface Milestone {
+  kind: 'flights' | 'hours' | 'distance';
+  /** Flights, hours or kilometers */
+  threshold: number;
+  /** Flight that reached it; null while not reached */
+  flightId: number | null;
+  reachedAt: string | null;
+  /** Share of the threshold flown so far, 0 to 1 */
+  progress: number;
+}
+
+/** Run of consecutive days or weeks with at least one flight */
+export interface Streak {
+  length: number;
+  /** YYYY-MM-DD */
+  startDate: string;
+  /** YYYY-MM-DD */
+  endDate: string;
+}
+
+export interface MilestoneReport {
+  /** Reached milestones oldest first, then the next one of each kind */
+  milestones: Milestone[];
+  longestDailyStreak: Streak | null;
+  /** Includes today or yesterday */
+  currentDailyStreak: Streak | null;
+  longestWeeklyStreak: Streak | null;
+  /** Includes this week or last week */
+  currentWeeklyStreak: Streak | null;
+}
+
+/** A year of flying summarized, for sharing as a "year in review" */
+export interface YearReview {
+  year: number;
+  /** Totals, aircraft, batteries and months of the year's counted flights */
+  stats: LibraryStats;
+  /** All twelve months, January first, including those without flights */
+  months: MonthlyStats[];
+  /** Best flights of the year */
+  yearRecords: PersonalRecords;
+  /** Keys of `PersonalRecords` whose all-time record was set during the year */
+  recordsSet: (keyof PersonalRecords)[];
+  /** Sites first flown from during the year */
+  newSites: Site[];
+}
+
+/** The flight holding a record and the record value */
+export interface PersonalRecord {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  /** Seconds, meters or m/s depending on the record */
+  value: number;
+}
+
+/** Record flights across the library; null when no flight qualifies */
+export interface PersonalRecords {
+  longestDuration: PersonalRecord | null;
+  longestDistance: PersonalRecord | null;
+  highestAltitude: PersonalRecord | null;
+  topSpeed: PersonalRecord | null;
+  farthestFromHome: PersonalRecord | null;
+}
+
 /** Result from import_log command */
 export interface ImportResult {
   success: boolean;
@@ -139,6 +950,38 @@ export interface ImportResult {
   fileHash: string | null;
 }
 
+/** Consecutive flights that look like one flight split across log files */
+export interface StitchCandidate {
+  firstId: number;
+  firstName: string;
+  secondId: number;
+  secondName: string;
+  gapSecs: number;
+  endBatteryPercent: number | null;
+  startBatteryPercent: number | null;
+}
+
+/** Waypoint of a planned mission */
+export interface Waypoint {
+  index: number;
+  lat: number;
+  lon: number;
+  height: number | null;
+  speed: number | null;
+}
+
+/** Imported waypoint mission (DJI Fly .kmz) and the flights that flew it */
+export interface Mission {
+  id: number;
+  name: string;
+  fileName: string;
+  droneModel: string | null;
+  createdAt: string | null;
+  importedAt: string | null;
+  waypoints: Waypoint[];
+  flightIds: number[];
+}
+
 /** Flight statistics */
 export interface FlightStats {
   durationSecs: number;
@@ -167,3 +1010,92 @@ export interface MapViewport {
   pitch: number;
   bearing: number;
 }
+
+/** Outcome of a library integrity check */
+export interface LibraryCheckReport {
+  /** True when the issues were only reported, not repaired */
+  dryRun: boolean;
+  tablesChecked: number;
+  issues: LibraryIssue[];
+}
+
+/** One kind of problem found by a library check */
+export interface LibraryIssue {
+  kind: 'unreadable_table' | 'orphaned_rows' | 'dangling_reference' | 'missing_file' | 'unused_file' | 'empty_flights';
+  table: string | null;
+  description: string;
+  /** Rows, files or flights affected */
+  count: number;
+  repairable: boolean;
+}
+
+/** A logbook library: one database file, e.g. a personal or a work logbook */
+export interface Library {
+  name: string;
+  path: string;
+  isDefault: boolean;
+  isActive: boolean;
+  /** False when the file was moved or sits on a drive that isn't attached */
+  exists: boolean;
+  lastOpened: string | null;
+}
+
+/** Filter definition of a smart list, mirroring the flight list filters */
+export interface FlightFilter {
+  /** First and last day, inclusive (YYYY-MM-DD) */
+  dateFrom?: string | null;
+  dateTo?: string | null;
+  /** Drone serials, or `model:<name>` for drones that report none */
+  drones?: string[];
+  batteries?: string[];
+  /** Site ids */
+  sites?: number[];
+  /** Flights must carry all of these tags */
+  tags?: string[];
+  minDurationMins?: number | null;
+  maxDurationMins?: number | null;
+  /** Meters */
+  minAltitude?: number | null;
+  maxAltitude?: number | null;
+  /** Meters */
+  minDistance?: number | null;
+  maxDistance?: number | null;
+  search?: string | null;
+  /** Only starred flights; not affected by `inverted` */
+  favoritesOnly?: boolean;
+  inverted?: boolean;
+}
+
+/** One entry of the audit log: an import, merge, deletion or edit */
+export interface AuditEntry {
+  id: number;
+  loggedAt: string;
+  /** `import`, `merge`, `delete`, `restore`, `purge`, `rename`, ... */
+  action: string;
+  /** Null for actions on the whole library */
+  flightId: number | null;
+  /** Log file and hash of the flight at the time of the action */
+  sourceFile: string | null;
+  fileHash: string | null;
+  appVersion: string | null;
+  details: string | null;
+}
+
+/** A saved, named flight filter */
+export interface SmartList {
+  id: number;
+  name: string;
+  filter: FlightFilter;
+  updatedAt: string | null;
+}
+
+/** A saved export setup: format, telemetry columns and units */
+export interface ExportTemplate {
+  id: number;
+  name: string;
+  format: 'gpx' | 'kml' | 'csv' | 'json';
+  /** Telemetry columns of CSV files, in order; empty for all */
+  columns: string[];
+  units: ExportUnits;
+  updatedAt: string | null;
+}